@@ -0,0 +1,410 @@
+use parking_lot::Mutex;
+use serde_json::{Value, json};
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+
+pub const DEFAULT_PORT: u16 = 47823;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Opening a session waits for nothing, but the app may be busy drawing.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+// JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const APP_ERROR: i64 = -32000;
+
+/// Why a call failed, sent back as a JSON-RPC error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn method_not_found(method: &str) -> Self {
+        Self {
+            code: METHOD_NOT_FOUND,
+            message: format!("Unknown method {}", method),
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+
+    pub fn failed(message: impl Into<String>) -> Self {
+        Self {
+            code: APP_ERROR,
+            message: message.into(),
+        }
+    }
+}
+
+/// A method call for the app to carry out. The caller waits until it is
+/// answered, and gets an error if it is dropped unanswered.
+pub struct ControlRequest {
+    pub method: String,
+    pub params: Value,
+    reply: oneshot::Sender<Result<Value, RpcError>>,
+}
+
+impl ControlRequest {
+    pub fn respond(self, result: Result<Value, RpcError>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Hands a request to the app through a message, which has to be
+/// cloneable; the first to take it answers it.
+#[derive(Clone)]
+pub struct ControlCall(Arc<Mutex<Option<ControlRequest>>>);
+
+impl ControlCall {
+    pub fn take(&self) -> Option<ControlRequest> {
+        self.0.lock().take()
+    }
+}
+
+impl fmt::Debug for ControlCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = self.0.lock();
+        let method = guard.as_ref().map(|request| request.method.as_str());
+        f.debug_tuple("ControlCall").field(&method).finish()
+    }
+}
+
+/// JSON-RPC 2.0 over HTTP `POST /rpc` on the loopback interface, for
+/// scripts and launchers. Every request carries `Authorization: Bearer
+/// <token>`. Dropping it stops listening.
+pub struct ControlServer {
+    addr: SocketAddr,
+    listener: tokio::task::JoinHandle<()>,
+}
+
+impl fmt::Debug for ControlServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ControlServer")
+            .field("addr", &self.addr)
+            .finish()
+    }
+}
+
+impl ControlServer {
+    pub async fn start(
+        port: u16,
+        token: String,
+        calls: mpsc::UnboundedSender<ControlCall>,
+    ) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .await
+            .map_err(|e| format!("Failed to start control API on port {}: {}", port, e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to start control API: {}", e))?;
+        let listener = tokio::spawn(accept_loop(listener, Arc::new(token), calls));
+        Ok(Self { addr, listener })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+/// Where the token lives; scripts read it from here.
+pub fn token_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".rivett")
+        .join("control-token")
+}
+
+/// Reads the token, creating a random one readable only by the user the
+/// first time.
+pub fn load_or_create_token() -> Result<String, String> {
+    let path = token_path();
+    if let Ok(token) = std::fs::read_to_string(&path)
+        && !token.trim().is_empty()
+    {
+        return Ok(token.trim().to_string());
+    }
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to create control token: {}", e))?;
+    std::io::Write::write_all(&mut file, token.as_bytes())
+        .map_err(|e| format!("Failed to write control token: {}", e))?;
+    Ok(token)
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    token: Arc<String>,
+    calls: mpsc::UnboundedSender<ControlCall>,
+) {
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                tracing::warn!("control API accept failed: {}", e);
+                continue;
+            }
+        };
+        let token = token.clone();
+        let calls = calls.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve(stream, &token, &calls).await {
+                tracing::warn!("control API request failed: {}", e);
+            }
+        });
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+// One request per connection; the response closes it.
+async fn serve(
+    mut stream: TcpStream,
+    token: &str,
+    calls: &mpsc::UnboundedSender<ControlCall>,
+) -> Result<(), String> {
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .map_err(|_| "timed out reading request".to_string())??;
+
+    let (status, body) = if request.path != "/rpc" {
+        ("404 Not Found", json!({ "error": "Not found" }))
+    } else if request.method != "POST" {
+        ("405 Method Not Allowed", json!({ "error": "Use POST" }))
+    } else if !authorized(request.authorization.as_deref(), token) {
+        (
+            "401 Unauthorized",
+            json!({ "error": "Missing or wrong token" }),
+        )
+    } else {
+        ("200 OK", call(&request.body, calls).await)
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, String> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    loop {
+        let read = (&mut reader)
+            .take((MAX_HEADER_BYTES - head.len()) as u64)
+            .read_line(&mut head)
+            .await
+            .map_err(|e| e.to_string())?;
+        if read == 0 || head.len() >= MAX_HEADER_BYTES {
+            return Err("incomplete request".to_string());
+        }
+        if head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            break;
+        }
+    }
+
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let mut content_length = 0;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .parse()
+                .map_err(|_| "bad Content-Length".to_string())?;
+        } else if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err("request body too large".to_string());
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(HttpRequest {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+// Compares every byte so the time taken doesn't reveal how much matched.
+fn authorized(header: Option<&str>, token: &str) -> bool {
+    let Some(given) = header.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let given = given.trim().as_bytes();
+    let token = token.as_bytes();
+    given.len() == token.len()
+        && given
+            .iter()
+            .zip(token)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn call(body: &[u8], calls: &mpsc::UnboundedSender<ControlCall>) -> Value {
+    let request: Value = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => return error_response(Value::Null, PARSE_ERROR, &e.to_string()),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return error_response(id, INVALID_REQUEST, "Missing method");
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let (reply, answer) = oneshot::channel();
+    let request = ControlRequest {
+        method: method.to_string(),
+        params,
+        reply,
+    };
+    if calls
+        .send(ControlCall(Arc::new(Mutex::new(Some(request)))))
+        .is_err()
+    {
+        return error_response(id, APP_ERROR, "The app is shutting down");
+    }
+    match tokio::time::timeout(REPLY_TIMEOUT, answer).await {
+        Ok(Ok(Ok(result))) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Ok(Ok(Err(error))) => error_response(id, error.code, &error.message),
+        Ok(Err(_)) => error_response(id, APP_ERROR, "The request was dropped"),
+        Err(_) => error_response(id, APP_ERROR, "The app did not answer in time"),
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_bearer_token() {
+        assert!(authorized(Some("Bearer abc123"), "abc123"));
+        assert!(!authorized(Some("Bearer abc124"), "abc123"));
+        assert!(!authorized(Some("Bearer abc"), "abc123"));
+        assert!(!authorized(Some("abc123"), "abc123"));
+        assert!(!authorized(None, "abc123"));
+    }
+
+    #[tokio::test]
+    async fn answers_json_rpc_over_http() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let server = ControlServer::start(0, "secret".to_string(), tx)
+            .await
+            .unwrap();
+        tokio::spawn(async move {
+            while let Some(call) = rx.recv().await {
+                let request = call.take().unwrap();
+                let result = match request.method.as_str() {
+                    "echo" => Ok(request.params.clone()),
+                    method => Err(RpcError::method_not_found(method)),
+                };
+                request.respond(result);
+            }
+        });
+
+        let post = |token: &str, body: &str| {
+            format!(
+                "POST /rpc HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\nContent-Length: {}\r\n\r\n{}",
+                token,
+                body.len(),
+                body
+            )
+        };
+        let addr = server.addr();
+        let send = |request: String| async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        let body = |response: &str| -> Value {
+            serde_json::from_str(response.split_once("\r\n\r\n").unwrap().1).unwrap()
+        };
+
+        let response = send(post(
+            "secret",
+            r#"{"jsonrpc":"2.0","id":7,"method":"echo","params":{"a":1}}"#,
+        ))
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert_eq!(body(&response)["id"], 7);
+        assert_eq!(body(&response)["result"], json!({ "a": 1 }));
+
+        let response = send(post(
+            "secret",
+            r#"{"jsonrpc":"2.0","id":8,"method":"nope"}"#,
+        ))
+        .await;
+        assert_eq!(body(&response)["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = send(post("wrong", r#"{"method":"echo"}"#)).await;
+        assert!(response.starts_with("HTTP/1.1 401"));
+    }
+}
@@ -94,4 +94,16 @@ impl SessionBackend {
             }
         }
     }
+
+    /// Process group in the foreground of a local PTY: the shell itself
+    /// when it is waiting at a prompt, or the program it started.
+    pub fn foreground_process_group(&self) -> Option<i32> {
+        match self {
+            SessionBackend::Ssh { .. } => None,
+            #[cfg(unix)]
+            SessionBackend::Local { master } => master.lock().ok()?.process_group_leader(),
+            #[cfg(not(unix))]
+            SessionBackend::Local { .. } => None,
+        }
+    }
 }
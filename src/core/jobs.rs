@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+// Ended jobs kept for the jobs panel; older ones are forgotten.
+const MAX_FINISHED: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JobId(u64);
+
+/// Checked by work that stops itself, such as a transfer between chunks.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobState {
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+pub struct Job<T> {
+    pub id: JobId,
+    /// The feature the job belongs to, for cleaning up after it.
+    pub owner: T,
+    pub label: String,
+    /// Fraction done, when the work knows how much there is.
+    pub progress: Option<f32>,
+    pub state: JobState,
+    pub started: Instant,
+    pub ended: Option<Instant>,
+    cancel: CancelToken,
+    // Stops work that can't check the token, like an aborted task.
+    abort: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Job<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Job")
+            .field("id", &self.id)
+            .field("owner", &self.owner)
+            .field("label", &self.label)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl<T> Job<T> {
+    pub fn is_running(&self) -> bool {
+        self.state == JobState::Running
+    }
+}
+
+/// Background work started from the UI, tracked in one place so it can be
+/// listed, followed and cancelled the same way whatever it is.
+#[derive(Debug)]
+pub struct JobQueue<T> {
+    next_id: u64,
+    jobs: VecDeque<Job<T>>,
+}
+
+impl<T> Default for JobQueue<T> {
+    fn default() -> Self {
+        Self {
+            next_id: 1,
+            jobs: VecDeque::new(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> JobQueue<T> {
+    /// Adds a running job. The token is cancelled along with the job.
+    pub fn start(&mut self, owner: T, label: impl Into<String>) -> (JobId, CancelToken) {
+        let id = JobId(self.next_id);
+        self.next_id += 1;
+        let cancel = CancelToken::default();
+        self.jobs.push_back(Job {
+            id,
+            owner,
+            label: label.into(),
+            progress: None,
+            state: JobState::Running,
+            started: Instant::now(),
+            ended: None,
+            cancel: cancel.clone(),
+            abort: None,
+        });
+        (id, cancel)
+    }
+
+    /// Runs `abort` if the job is cancelled while it still runs.
+    pub fn on_cancel(&mut self, id: JobId, abort: impl FnOnce() + Send + 'static) {
+        if let Some(job) = self.running_mut(id) {
+            job.abort = Some(Box::new(abort));
+        }
+    }
+
+    pub fn set_progress(&mut self, id: JobId, progress: f32) {
+        if let Some(job) = self.running_mut(id) {
+            job.progress = Some(progress.clamp(0.0, 1.0));
+        }
+    }
+
+    /// Ends a running job; jobs already cancelled stay that way.
+    pub fn finish(&mut self, id: JobId, result: Result<(), String>) {
+        let Some(job) = self.running_mut(id) else {
+            return;
+        };
+        job.state = match result {
+            Ok(()) => JobState::Done,
+            Err(e) => JobState::Failed(e),
+        };
+        job.ended = Some(Instant::now());
+        job.abort = None;
+        self.trim();
+    }
+
+    /// Forgets a job without keeping it in the history, for work with
+    /// nothing worth reporting once it is over.
+    pub fn dismiss(&mut self, id: JobId) {
+        self.jobs.retain(|job| job.id != id);
+    }
+
+    /// Cancels a running job and returns its owner, so the caller can undo
+    /// whatever the job left half done.
+    pub fn cancel(&mut self, id: JobId) -> Option<T> {
+        let job = self.running_mut(id)?;
+        job.cancel.cancel();
+        if let Some(abort) = job.abort.take() {
+            abort();
+        }
+        job.state = JobState::Cancelled;
+        job.ended = Some(Instant::now());
+        let owner = job.owner.clone();
+        self.trim();
+        Some(owner)
+    }
+
+    /// The running job for `owner`, if there is one.
+    pub fn running(&self, owner: &T) -> Option<JobId> {
+        self.jobs
+            .iter()
+            .find(|job| job.is_running() && job.owner == *owner)
+            .map(|job| job.id)
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.jobs.iter().filter(|job| job.is_running()).count()
+    }
+
+    /// Every job, oldest first.
+    pub fn jobs(&self) -> impl DoubleEndedIterator<Item = &Job<T>> {
+        self.jobs.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(Job::is_running);
+    }
+
+    fn running_mut(&mut self, id: JobId) -> Option<&mut Job<T>> {
+        self.jobs
+            .iter_mut()
+            .find(|job| job.id == id && job.is_running())
+    }
+
+    fn trim(&mut self) {
+        let mut finished = self.jobs.iter().filter(|job| !job.is_running()).count();
+        self.jobs.retain(|job| {
+            if finished > MAX_FINISHED && !job.is_running() {
+                finished -= 1;
+                return false;
+            }
+            true
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn cancelling_stops_the_work_once() {
+        let mut queue = JobQueue::default();
+        let (id, token) = queue.start("listing", "List /var/log");
+        let aborted = Arc::new(AtomicUsize::new(0));
+        let counter = aborted.clone();
+        queue.on_cancel(id, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(queue.running(&"listing"), Some(id));
+
+        assert_eq!(queue.cancel(id), Some("listing"));
+        assert!(token.is_cancelled());
+        assert_eq!(queue.cancel(id), None);
+        assert_eq!(aborted.load(Ordering::SeqCst), 1);
+
+        // A result arriving after the cancel doesn't change the outcome.
+        queue.finish(id, Ok(()));
+        let job = queue.jobs().next().unwrap();
+        assert_eq!(job.state, JobState::Cancelled);
+        assert_eq!(queue.running_count(), 0);
+    }
+
+    #[test]
+    fn keeps_a_bounded_history() {
+        let mut queue = JobQueue::default();
+        let (running, _) = queue.start(0, "still going");
+        for owner in 1..=MAX_FINISHED + 5 {
+            let (id, _) = queue.start(owner, "done");
+            queue.finish(id, Err("failed".to_string()));
+        }
+        assert_eq!(queue.jobs().count(), MAX_FINISHED + 1);
+        assert_eq!(queue.running(&0), Some(running));
+        assert_eq!(
+            queue.jobs().last().unwrap().state,
+            JobState::Failed("failed".to_string())
+        );
+
+        queue.clear_finished();
+        assert_eq!(queue.jobs().count(), 1);
+    }
+}
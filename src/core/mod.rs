@@ -1,19 +1,55 @@
 pub mod backend;
+pub mod jobs;
 pub mod session;
 
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::ssh::SshSession;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[allow(dead_code)]
 pub struct SessionId(u64);
 
+/// What makes two sessions the same connection: the host, user and port
+/// they resolve to, and every other setting that changes how that host is
+/// reached or logged into.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConnectionKey {
+    pub host: String,
+    pub username: String,
+    pub port: u16,
+    // The other settings, serialized, so keys match only when all of them do.
+    options: String,
+}
+
+impl ConnectionKey {
+    pub fn new(host: &str, username: &str, port: u16, options: &impl serde::Serialize) -> Self {
+        Self {
+            host: host.trim().to_ascii_lowercase(),
+            username: username.trim().to_string(),
+            port,
+            options: serde_json::to_string(options).unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct SessionManager {
     #[allow(dead_code)]
     next_id: u64,
+    // Open connections other tabs can add channels to. Held weakly so a
+    // connection still goes away with the last tab using it.
+    pool: HashMap<ConnectionKey, Weak<AsyncMutex<SshSession>>>,
 }
 
 impl SessionManager {
     pub fn new() -> Self {
-        Self { next_id: 1 }
+        Self {
+            next_id: 1,
+            pool: HashMap::new(),
+        }
     }
 
     #[allow(dead_code)]
@@ -22,4 +58,49 @@ impl SessionManager {
         self.next_id = self.next_id.saturating_add(1);
         id
     }
+
+    /// Offers `session` to later connections with the same key.
+    pub fn register(&mut self, key: ConnectionKey, session: &Arc<AsyncMutex<SshSession>>) {
+        self.pool.retain(|_, pooled| pooled.strong_count() > 0);
+        self.pool.insert(key, Arc::downgrade(session));
+    }
+
+    /// A live connection for `key`, if one is open.
+    pub fn shared(&mut self, key: &ConnectionKey) -> Option<Arc<AsyncMutex<SshSession>>> {
+        let session = self.pool.get(key).and_then(Weak::upgrade);
+        // A connection busy with a handshake or transfer is taken to be up.
+        let live = session
+            .as_ref()
+            .is_some_and(|session| session.try_lock().map_or(true, |s| s.is_connected()));
+        if !live {
+            self.pool.remove(key);
+            return None;
+        }
+        session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_ignore_host_case_and_padding() {
+        assert_eq!(
+            ConnectionKey::new(" Example.COM", "deploy", 22, &()),
+            ConnectionKey::new("example.com", "deploy ", 22, &())
+        );
+        assert_ne!(
+            ConnectionKey::new("example.com", "deploy", 22, &()),
+            ConnectionKey::new("example.com", "deploy", 2222, &())
+        );
+    }
+
+    #[test]
+    fn keys_differ_in_any_other_setting() {
+        assert_ne!(
+            ConnectionKey::new("example.com", "deploy", 22, &Some("bastion-a")),
+            ConnectionKey::new("example.com", "deploy", 22, &Some("bastion-b"))
+        );
+    }
 }
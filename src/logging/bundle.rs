@@ -0,0 +1,222 @@
+use chrono::{Datelike, Local, Timelike};
+use flate2::Compression;
+use flate2::write::DeflateEncoder;
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use super::rotate::rotated_name;
+use crate::session::SessionConfig;
+use crate::settings::AppSettings;
+
+// Fields whose values could identify hosts, people or secrets. Their
+// strings are replaced wherever they appear in the config.
+const REDACTED_FIELDS: &[&str] = &[
+    "name",
+    "host",
+    "hostname",
+    "username",
+    "password",
+    "key_passphrase",
+    "jump_host",
+    "local_host",
+    "remote_host",
+    "host_key_fingerprint",
+    "fingerprint",
+    "path",
+    "pattern",
+    "patterns",
+    "domain",
+    "tags",
+    "badge",
+    "tmux_session",
+    "remote_command",
+    "redact_patterns",
+    "startup_session",
+    "sftp_trash_dir",
+    "directory",
+    "environment",
+];
+
+/// Writes a zip with the recent log files, the settings and the saved
+/// sessions with anything identifying removed, for attaching to a bug
+/// report.
+pub fn write_bundle(
+    dest: &Path,
+    settings: &AppSettings,
+    sessions: &[SessionConfig],
+) -> Result<(), String> {
+    let mut zip = ZipWriter::default();
+    zip.add(
+        "about.txt",
+        format!(
+            "rivett {}\n{} {}\ncreated {}\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            Local::now().to_rfc3339()
+        )
+        .as_bytes(),
+    )?;
+    zip.add("settings.json", &redacted_json(settings)?)?;
+    zip.add("sessions.json", &redacted_json(&sessions)?)?;
+    let dir = settings.logging.log_dir();
+    for index in 0..=settings.logging.max_files {
+        let name = rotated_name(index);
+        if let Ok(contents) = fs::read(dir.join(&name)) {
+            zip.add(&format!("logs/{}", name), &contents)?;
+        }
+    }
+    fs::write(dest, zip.finish()).map_err(|e| format!("Failed to write diagnostic bundle: {}", e))
+}
+
+fn redacted_json(value: &impl serde::Serialize) -> Result<Vec<u8>, String> {
+    let mut value =
+        serde_json::to_value(value).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    redact(&mut value, false);
+    serde_json::to_vec_pretty(&value).map_err(|e| format!("Failed to serialize config: {}", e))
+}
+
+fn redact(value: &mut Value, sensitive: bool) {
+    match value {
+        Value::String(text) if sensitive && !text.is_empty() => {
+            *text = "[redacted]".to_string();
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact(item, sensitive);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                redact(field, sensitive || REDACTED_FIELDS.contains(&key.as_str()));
+            }
+        }
+        _ => {}
+    }
+}
+
+// Just enough of the zip format for a handful of small files: deflated
+// entries, no zip64.
+#[derive(Default)]
+struct ZipWriter {
+    data: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipWriter {
+    fn add(&mut self, name: &str, contents: &[u8]) -> Result<(), String> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(contents)
+            .map_err(|e| format!("Failed to compress {}: {}", name, e))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|e| format!("Failed to compress {}: {}", name, e))?;
+        let crc = crc32fast::hash(contents);
+        let (time, date) = dos_timestamp();
+        let offset = self.data.len() as u32;
+
+        // Shared by the local header and the central directory entry:
+        // version needed, UTF-8 names, deflate, time, date, crc, sizes and
+        // name length.
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes());
+        common.extend_from_slice(&0x0800u16.to_le_bytes());
+        common.extend_from_slice(&8u16.to_le_bytes());
+        common.extend_from_slice(&time.to_le_bytes());
+        common.extend_from_slice(&date.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+
+        self.data.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        self.data.extend_from_slice(&common);
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(&compressed);
+
+        self.central.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes());
+        self.central.extend_from_slice(&common);
+        // Extra field, comment, disk, internal and external attributes.
+        self.central.extend_from_slice(&[0; 12]);
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.data.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.data.append(&mut self.central);
+        self.data.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        self.data.extend_from_slice(&[0; 4]);
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&central_size.to_le_bytes());
+        self.data.extend_from_slice(&central_offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data
+    }
+}
+
+fn dos_timestamp() -> (u16, u16) {
+    let now = Local::now();
+    let time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+    let date = ((((now.year() - 1980).max(0) as u32) << 9) | (now.month() << 5) | now.day()) as u16;
+    (time, date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_identifying_fields() {
+        let mut value = serde_json::json!({
+            "host": "db.internal",
+            "port": 22,
+            "tags": ["prod"],
+            "jump_host": { "host": "bastion", "port": 2222 },
+            "theme": "dark",
+            "password": null,
+            "environment": [{ "name": "API_TOKEN", "value": "s3cret" }],
+            "remote_command": "mysql -psecret"
+        });
+        redact(&mut value, false);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "host": "[redacted]",
+                "port": 22,
+                "tags": ["[redacted]"],
+                "jump_host": { "host": "[redacted]", "port": 2222 },
+                "theme": "dark",
+                "password": null,
+                "environment": [{ "name": "[redacted]", "value": "[redacted]" }],
+                "remote_command": "[redacted]"
+            })
+        );
+    }
+
+    #[test]
+    fn writes_a_well_formed_archive() {
+        let mut zip = ZipWriter::default();
+        zip.add("about.txt", b"rivett").unwrap();
+        let data = zip.finish();
+        assert_eq!(&data[..4], &0x04034b50u32.to_le_bytes());
+        let end = &data[data.len() - 22..];
+        assert_eq!(&end[..4], &0x06054b50u32.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([end[10], end[11]]), 1);
+        let central_offset = u32::from_le_bytes([end[16], end[17], end[18], end[19]]) as usize;
+        assert_eq!(
+            &data[central_offset..central_offset + 4],
+            &0x02014b50u32.to_le_bytes()
+        );
+    }
+}
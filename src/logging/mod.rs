@@ -0,0 +1,267 @@
+mod bundle;
+mod rotate;
+
+pub use bundle::write_bundle;
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+use rotate::RotatingFile;
+
+/// Log file name; rotated copies are numbered `rivett.1.log` and up.
+pub const FILE_NAME: &str = "rivett.log";
+
+/// How verbose each part of the app logs, and where the log file goes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoggingOptions {
+    #[serde(default)]
+    pub ssh: LogLevel,
+    #[serde(default)]
+    pub sftp: LogLevel,
+    #[serde(default)]
+    pub terminal: LogLevel,
+    #[serde(default)]
+    pub ui: LogLevel,
+    /// Folder for log files; empty uses `~/.rivett/logs`.
+    #[serde(default)]
+    pub directory: String,
+    /// The file is rotated once it grows past this size.
+    #[serde(default = "default_max_file_mb")]
+    pub max_file_mb: u32,
+    /// Rotated files kept next to the current one.
+    #[serde(default = "default_max_files")]
+    pub max_files: u32,
+}
+
+fn default_max_file_mb() -> u32 {
+    10
+}
+
+fn default_max_files() -> u32 {
+    5
+}
+
+impl Default for LoggingOptions {
+    fn default() -> Self {
+        Self {
+            ssh: LogLevel::default(),
+            sftp: LogLevel::default(),
+            terminal: LogLevel::default(),
+            ui: LogLevel::default(),
+            directory: String::new(),
+            max_file_mb: default_max_file_mb(),
+            max_files: default_max_files(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 6] = [
+        LogLevel::Off,
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Off => "Off",
+            LogLevel::Error => "Error",
+            LogLevel::Warn => "Warn",
+            LogLevel::Info => "Info",
+            LogLevel::Debug => "Debug",
+            LogLevel::Trace => "Trace",
+        }
+    }
+
+    fn directive(self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// A part of the app with its own log level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Ssh,
+    Sftp,
+    Terminal,
+    Ui,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 4] = [
+        Subsystem::Ssh,
+        Subsystem::Sftp,
+        Subsystem::Terminal,
+        Subsystem::Ui,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Subsystem::Ssh => "SSH",
+            Subsystem::Sftp => "SFTP",
+            Subsystem::Terminal => "Terminal",
+            Subsystem::Ui => "Interface",
+        }
+    }
+
+    // Tracing targets, including the libraries doing the work. A longer
+    // target wins, so SFTP is carved out of the ssh module.
+    fn targets(self) -> &'static [&'static str] {
+        match self {
+            Subsystem::Ssh => &["rivett::ssh", "russh"],
+            Subsystem::Sftp => &["rivett::ssh::transfer", "russh_sftp"],
+            Subsystem::Terminal => &["rivett::terminal", "alacritty_terminal"],
+            Subsystem::Ui => &["rivett::ui", "iced", "wgpu"],
+        }
+    }
+}
+
+impl LoggingOptions {
+    pub fn level(&self, subsystem: Subsystem) -> LogLevel {
+        match subsystem {
+            Subsystem::Ssh => self.ssh,
+            Subsystem::Sftp => self.sftp,
+            Subsystem::Terminal => self.terminal,
+            Subsystem::Ui => self.ui,
+        }
+    }
+
+    pub fn set_level(&mut self, subsystem: Subsystem, level: LogLevel) {
+        match subsystem {
+            Subsystem::Ssh => self.ssh = level,
+            Subsystem::Sftp => self.sftp = level,
+            Subsystem::Terminal => self.terminal = level,
+            Subsystem::Ui => self.ui = level,
+        }
+    }
+
+    pub fn log_dir(&self) -> PathBuf {
+        let directory = self.directory.trim();
+        if directory.is_empty() {
+            return dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".rivett")
+                .join("logs");
+        }
+        match directory.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .map(|home| home.join(rest))
+                .unwrap_or_else(|| PathBuf::from(directory)),
+            None => PathBuf::from(directory),
+        }
+    }
+
+    /// The filter for these levels; everything else logs at info.
+    fn directives(&self) -> String {
+        Subsystem::ALL
+            .into_iter()
+            .flat_map(|subsystem| {
+                let level = self.level(subsystem).directive();
+                subsystem
+                    .targets()
+                    .iter()
+                    .map(move |target| format!("{}={}", target, level))
+            })
+            .fold("info".to_string(), |filter, directive| {
+                filter + "," + &directive
+            })
+    }
+}
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Logs to stderr and, with `to_file`, to the rotating log file. `RUST_LOG`
+/// replaces the configured levels when set.
+pub fn init(options: &LoggingOptions, to_file: bool) {
+    let from_env = EnvFilter::try_from_default_env().ok();
+    let overridden = from_env.is_some();
+    let filter = from_env.unwrap_or_else(|| EnvFilter::new(options.directives()));
+    let (filter, handle) = reload::Layer::new(filter);
+    let file = to_file
+        .then(|| {
+            RotatingFile::open(
+                options.log_dir(),
+                u64::from(options.max_file_mb.max(1)) * 1024 * 1024,
+                options.max_files,
+            )
+            .map_err(|e| eprintln!("Failed to open log file: {}", e))
+            .ok()
+        })
+        .flatten()
+        .map(|file| {
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+        });
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_level(true),
+        )
+        .with(file)
+        .init();
+    if !overridden {
+        let _ = FILTER.set(handle);
+    }
+}
+
+/// Applies changed levels to the running app. The file location and
+/// rotation are read once at startup.
+pub fn apply(options: &LoggingOptions) {
+    if let Some(handle) = FILTER.get()
+        && let Err(e) = handle.reload(EnvFilter::new(options.directives()))
+    {
+        eprintln!("Failed to apply log levels: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_filter_from_levels() {
+        let mut options = LoggingOptions::default();
+        options.set_level(Subsystem::Sftp, LogLevel::Trace);
+        options.set_level(Subsystem::Ui, LogLevel::Off);
+        let directives = options.directives();
+        assert!(directives.starts_with("info,rivett::ssh=info,russh=info,"));
+        assert!(directives.contains("rivett::ssh::transfer=trace,russh_sftp=trace"));
+        assert!(directives.contains("rivett::ui=off"));
+        assert!(EnvFilter::try_new(&directives).is_ok());
+    }
+}
@@ -0,0 +1,106 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::FILE_NAME;
+
+/// The log file, moved aside as `rivett.1.log` (and older ones shifted
+/// up) once it reaches `max_bytes`. Only `max_files` old files are kept.
+#[derive(Debug)]
+pub(super) struct RotatingFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_files: u32,
+    // Closed while rotating; Windows can't rename an open file.
+    file: Option<File>,
+    written: u64,
+}
+
+impl RotatingFile {
+    pub(super) fn open(dir: PathBuf, max_bytes: u64, max_files: u32) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let file = open_append(&dir)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            dir,
+            max_bytes,
+            max_files,
+            file: Some(file),
+            written,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        if self.max_files == 0 {
+            let _ = fs::remove_file(self.dir.join(FILE_NAME));
+        }
+        for index in (1..=self.max_files).rev() {
+            let from = self.dir.join(rotated_name(index - 1));
+            if from.exists() {
+                fs::rename(&from, self.dir.join(rotated_name(index)))?;
+            }
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let file = match self.file.take() {
+            Some(file) => file,
+            None => open_append(&self.dir)?,
+        };
+        let written = self.file.insert(file).write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn open_append(dir: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(FILE_NAME))
+}
+
+/// `rivett.log` for the current file, `rivett.N.log` for older ones.
+pub(super) fn rotated_name(index: u32) -> String {
+    match index {
+        0 => FILE_NAME.to_string(),
+        index => format!("rivett.{}.log", index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_and_keeps_a_limited_number_of_files() {
+        let dir = std::env::temp_dir().join(format!("rivett-log-{}", uuid::Uuid::new_v4()));
+        let mut file = RotatingFile::open(dir.clone(), 10, 2).unwrap();
+        for line in ["first....\n", "second...\n", "third....\n", "fourth...\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        let read = |index| fs::read_to_string(dir.join(rotated_name(index))).ok();
+        assert_eq!(read(0).as_deref(), Some("fourth...\n"));
+        assert_eq!(read(1).as_deref(), Some("third....\n"));
+        assert_eq!(read(2).as_deref(), Some("second...\n"));
+        assert_eq!(read(3), None);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,4 +1,6 @@
+mod control;
 mod core;
+mod logging;
 mod platform;
 mod session;
 mod settings;
@@ -7,20 +9,15 @@ mod ssh;
 mod terminal;
 mod ui;
 
-fn init_tracing() {
-    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_level(true)
-        .with_env_filter(filter)
-        .init();
-}
-
 fn main() -> iced::Result {
-    init_tracing();
-    tracing::info!("iced renderer: {}", std::any::type_name::<iced::Renderer>());
     let is_settings = std::env::args().any(|arg| arg == "--settings");
+    // Only the main window writes the log file, so the two processes never
+    // rotate it under each other.
+    let settings = settings::SettingsStorage::new()
+        .load_settings()
+        .unwrap_or_default();
+    logging::init(&settings.logging, !is_settings);
+    tracing::info!("iced renderer: {}", std::any::type_name::<iced::Renderer>());
     if is_settings {
         return settings_app::run();
     }
@@ -0,0 +1,12 @@
+use objc2_app_kit::NSApplication;
+use objc2_foundation::{MainThreadMarker, NSString};
+
+pub fn set_badge(count: usize) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return;
+    };
+    let label = (count > 0).then(|| NSString::from_str(&count.to_string()));
+    NSApplication::sharedApplication(mtm)
+        .dockTile()
+        .setBadgeLabel(label.as_deref());
+}
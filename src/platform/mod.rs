@@ -1,4 +1,6 @@
 #[cfg(target_os = "macos")]
+mod macos_dock;
+#[cfg(target_os = "macos")]
 mod macos_menu;
 
 #[derive(Debug, Default)]
@@ -20,6 +22,15 @@ pub fn maybe_setup_macos_menu() {
     macos_menu::maybe_install();
 }
 
+/// Shows `count` on the dock icon; zero clears the badge. Other platforms
+/// rely on the window title and attention requests instead.
+pub fn set_badge_count(count: usize) {
+    #[cfg(target_os = "macos")]
+    macos_dock::set_badge(count);
+    #[cfg(not(target_os = "macos"))]
+    let _ = count;
+}
+
 pub fn take_settings_request() -> bool {
     #[cfg(target_os = "macos")]
     {
@@ -31,6 +42,58 @@ pub fn take_settings_request() -> bool {
     }
 }
 
+/// Short name of a running process, such as `vim`.
+pub fn process_name(pid: i32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+        Some(name.trim().to_string()).filter(|name| !name.is_empty())
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut buffer = [0u8; 256];
+        let len = unsafe { libc::proc_name(pid, buffer.as_mut_ptr().cast(), buffer.len() as u32) };
+        (len > 0).then(|| String::from_utf8_lossy(&buffer[..len as usize]).into_owned())
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Current directory of a running process, such as a local tab's shell.
+pub fn process_cwd(pid: i32) -> Option<std::path::PathBuf> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_link(format!("/proc/{}/cwd", pid)).ok()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut info: libc::proc_vnodepathinfo = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<libc::proc_vnodepathinfo>() as i32;
+        let len = unsafe {
+            libc::proc_pidinfo(
+                pid,
+                libc::PROC_PIDVNODEPATHINFO,
+                0,
+                (&mut info as *mut libc::proc_vnodepathinfo).cast(),
+                size,
+            )
+        };
+        if len != size {
+            return None;
+        }
+        let path = unsafe { std::ffi::CStr::from_ptr(info.pvi_cdir.vip_path.as_ptr().cast()) };
+        Some(std::path::PathBuf::from(path.to_str().ok()?)).filter(|path| path.is_absolute())
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
 pub fn default_terminal_font_family() -> &'static str {
     #[cfg(target_os = "macos")]
     {
@@ -162,3 +225,166 @@ pub fn open_url(url: &str) -> Result<(), String> {
         }
     })
 }
+
+/// Shows `path` selected in Finder, Explorer or the desktop's file manager.
+pub fn reveal_in_file_manager(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let status = std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .status();
+
+    // Explorer exits with 1 even when it worked, so only a failed start
+    // counts.
+    #[cfg(target_os = "windows")]
+    return std::process::Command::new("explorer")
+        .arg(format!("/select,{}", path.display()))
+        .status()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open Explorer: {}", e));
+
+    // File managers that implement the freedesktop interface select the
+    // file; the rest are just opened on its folder.
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let status = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", file_uri(path)))
+        .arg("string:")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .ok()
+        .filter(|status| status.success())
+        .map_or_else(
+            || {
+                std::process::Command::new("xdg-open")
+                    .arg(path.parent().unwrap_or(path))
+                    .status()
+            },
+            Ok,
+        );
+
+    #[cfg(not(target_os = "windows"))]
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("Failed to reveal {}: {}", path.display(), status)),
+        Err(e) => Err(format!("Failed to reveal {}: {}", path.display(), e)),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn file_uri(path: &std::path::Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    let mut uri = "file://".to_string();
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            byte => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Shows a desktop notification. Spawns the platform helper and returns
+/// without waiting for it.
+pub fn notify(title: &str, body: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let script = format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        );
+        let mut cmd = std::process::Command::new("osascript");
+        cmd.args(["-e", &script]);
+        cmd
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let script = format!(
+            "[reflection.assembly]::loadwithpartialname('System.Windows.Forms') | Out-Null; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; \
+             $n.Icon = [System.Drawing.SystemIcons]::Information; \
+             $n.Visible = $true; \
+             $n.ShowBalloonTip(5000, '{}', '{}', 'Warning'); \
+             Start-Sleep -Seconds 6; $n.Dispose()",
+            title.replace('\'', "''"),
+            body.replace('\'', "''")
+        );
+        let mut cmd = std::process::Command::new("powershell");
+        cmd.args(["-NoProfile", "-WindowStyle", "Hidden", "-Command", &script]);
+        cmd
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut cmd = {
+        let mut cmd = std::process::Command::new("notify-send");
+        cmd.args(["--app-name=Rivett", title, body]);
+        cmd
+    };
+
+    let mut child = cmd
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// Plays the system alert sound without waiting for it to finish.
+pub fn beep() -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut cmd = {
+        let mut cmd = std::process::Command::new("osascript");
+        cmd.args(["-e", "beep"]);
+        cmd
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = std::process::Command::new("powershell");
+        cmd.args([
+            "-NoProfile",
+            "-WindowStyle",
+            "Hidden",
+            "-Command",
+            "[System.Media.SystemSounds]::Beep.Play()",
+        ]);
+        cmd
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut cmd = {
+        let mut cmd = std::process::Command::new("canberra-gtk-play");
+        cmd.args(["--id=bell", "--description=Rivett"]);
+        cmd
+    };
+
+    let mut child = cmd
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to play bell sound: {}", e))?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use super::SessionConfig;
+
+/// Domain appended to bare host names of sessions tagged `tag`, so `web01`
+/// in the "prod" group connects to `web01.prod.example.com`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DomainRule {
+    pub tag: String,
+    pub domain: String,
+}
+
+/// The session as it is connected to: `${USER}` and `${ENV:NAME}` replaced
+/// in the host, username and jump host, and the group's domain appended to
+/// a host given without one. The saved session keeps the short forms.
+pub fn resolve(session: &SessionConfig, domains: &[DomainRule]) -> Result<SessionConfig, String> {
+    resolve_with(session, domains, |name| std::env::var(name).ok())
+}
+
+fn resolve_with(
+    session: &SessionConfig,
+    domains: &[DomainRule],
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<SessionConfig, String> {
+    let mut session = session.clone();
+    session.host = qualify(&expand(&session.host, &env)?, &session.tags, domains);
+    session.username = expand(&session.username, &env)?;
+    // The bastion usually lives outside the group's domain, so only its
+    // variables are expanded.
+    if let Some(jump_host) = &mut session.jump_host {
+        jump_host.host = expand(&jump_host.host, &env)?;
+        if let Some(username) = &mut jump_host.username {
+            *username = expand(username, &env)?;
+        }
+    }
+    Ok(session)
+}
+
+/// Replaces `${USER}` with the local user name and `${ENV:NAME}` with the
+/// environment variable `NAME`.
+pub fn expand(value: &str, env: &impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(format!("Unclosed \"${{\" in \"{}\"", value));
+        };
+        let name = &rest[start + 2..start + end];
+        let replacement = match name.strip_prefix("ENV:") {
+            Some(var) => {
+                env(var).ok_or_else(|| format!("Environment variable {} is not set", var))?
+            }
+            None if name == "USER" => env("USER")
+                .or_else(|| env("USERNAME"))
+                .ok_or_else(|| "Cannot tell the local user name".to_string())?,
+            None => return Err(format!("Unknown variable ${{{}}}", name)),
+        };
+        expanded.push_str(&replacement);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Appends the domain of the first rule whose tag the session carries.
+/// Hosts that already have a domain, IP addresses and `localhost` are left
+/// alone.
+fn qualify(host: &str, tags: &[String], domains: &[DomainRule]) -> String {
+    let bare = !host.contains(['.', ':']) && !host.eq_ignore_ascii_case("localhost");
+    let domain = domains
+        .iter()
+        .filter(|rule| !rule.domain.trim().is_empty())
+        .find(|rule| {
+            tags.iter()
+                .any(|tag| tag.eq_ignore_ascii_case(rule.tag.trim()))
+        });
+    match domain {
+        Some(rule) if bare && !host.is_empty() => {
+            format!("{}.{}", host, rule.domain.trim().trim_start_matches('.'))
+        }
+        _ => host.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::config::JumpHost;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "USER" => Some("alice".to_string()),
+            "DEPLOY_HOST" => Some("deploy.example.com".to_string()),
+            _ => None,
+        }
+    }
+
+    fn rule(tag: &str, domain: &str) -> DomainRule {
+        DomainRule {
+            tag: tag.to_string(),
+            domain: domain.to_string(),
+        }
+    }
+
+    #[test]
+    fn expands_variables() {
+        assert_eq!(expand("${USER}-admin", &env).unwrap(), "alice-admin");
+        assert_eq!(
+            expand("${ENV:DEPLOY_HOST}", &env).unwrap(),
+            "deploy.example.com"
+        );
+        assert_eq!(expand("plain", &env).unwrap(), "plain");
+        assert!(expand("${ENV:MISSING}", &env).is_err());
+        assert!(expand("${HOME}", &env).is_err());
+        assert!(expand("web-${USER", &env).is_err());
+    }
+
+    #[test]
+    fn appends_group_domain_to_bare_hosts() {
+        let domains = vec![rule("staging", ""), rule("prod", ".prod.example.com")];
+        let mut session = SessionConfig::new(
+            "web".to_string(),
+            "web01".to_string(),
+            22,
+            "${USER}".to_string(),
+        );
+        session.tags = vec!["Prod".to_string()];
+        session.jump_host = Some(JumpHost {
+            username: Some("${USER}".to_string()),
+            host: "bastion".to_string(),
+            port: 22,
+        });
+
+        let resolved = resolve_with(&session, &domains, env).unwrap();
+        assert_eq!(resolved.host, "web01.prod.example.com");
+        assert_eq!(resolved.username, "alice");
+        let jump_host = resolved.jump_host.unwrap();
+        assert_eq!(jump_host.host, "bastion");
+        assert_eq!(jump_host.username.as_deref(), Some("alice"));
+
+        for host in ["web01.other.net", "10.0.0.1", "::1", "localhost"] {
+            session.host = host.to_string();
+            assert_eq!(resolve_with(&session, &domains, env).unwrap().host, host);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, TimeZone, Utc};
+
+const FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// How long before access ends the user is warned.
+pub fn warn_before() -> TimeDelta {
+    TimeDelta::minutes(10)
+}
+
+/// Reads an expiry typed as local `2024-03-09 17:30`, or pasted as RFC 3339
+/// from an access system. Empty means no expiry.
+pub fn parse(value: &str) -> Result<Option<DateTime<Utc>>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(at.with_timezone(&Utc)));
+    }
+    NaiveDateTime::parse_from_str(value, FORMAT)
+        .ok()
+        .and_then(|at| Local.from_local_datetime(&at).earliest())
+        .map(|at| Some(at.with_timezone(&Utc)))
+        .ok_or_else(|| "Access expiry must look like 2024-03-09 17:30".to_string())
+}
+
+pub fn format(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local).format(FORMAT).to_string()
+}
+
+/// Time left as `2d 4h`, `1h 05m`, `12m` or `45s`; None once expired.
+pub fn countdown(at: DateTime<Utc>, now: DateTime<Utc>) -> Option<String> {
+    let left = (at - now).num_seconds();
+    if left <= 0 {
+        return None;
+    }
+    let (days, hours, minutes) = (left / 86_400, left / 3_600 % 24, left / 60 % 60);
+    Some(if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", left)
+    })
+}
+
+/// Within the warning period or already over.
+pub fn is_ending(at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    at - now <= warn_before()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_expiry_formats() {
+        assert_eq!(parse("  "), Ok(None));
+        assert_eq!(
+            parse("2024-03-09T17:30:00+01:00"),
+            Ok(Some(Utc.with_ymd_and_hms(2024, 3, 9, 16, 30, 0).unwrap()))
+        );
+        let local = parse("2024-03-09 17:30").unwrap().unwrap();
+        assert_eq!(format(local), "2024-03-09 17:30");
+        assert!(parse("tomorrow").is_err());
+    }
+
+    #[test]
+    fn counts_down() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 9, 12, 0, 0).unwrap();
+        let at = |seconds| now + TimeDelta::seconds(seconds);
+        assert_eq!(countdown(at(2 * 86_400 + 4 * 3_600), now).unwrap(), "2d 4h");
+        assert_eq!(countdown(at(3_600 + 5 * 60 + 30), now).unwrap(), "1h 05m");
+        assert_eq!(countdown(at(12 * 60), now).unwrap(), "12m");
+        assert_eq!(countdown(at(45), now).unwrap(), "45s");
+        assert_eq!(countdown(at(0), now), None);
+        assert!(is_ending(at(600), now));
+        assert!(!is_ending(at(601), now));
+    }
+}
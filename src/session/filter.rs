@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use super::profile::glob_match;
+
+/// A named list of ignore patterns, such as `.git/ node_modules/ *.pyc`,
+/// that can be applied to a file pane. Patterns follow .gitignore: a
+/// trailing `/` only matches directories, and a pattern with a `/` inside
+/// is matched against the whole relative path instead of each name in it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FilterPreset {
+    pub name: String,
+    /// Separated by whitespace or commas, as typed in settings.
+    pub patterns: String,
+}
+
+impl FilterPreset {
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.patterns
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|pattern| !pattern.is_empty())
+    }
+
+    /// Whether `path`, relative to the directory being listed or
+    /// transferred and separated by `/`, is hidden by this preset. Anything
+    /// under an ignored directory is ignored too.
+    pub fn ignores(&self, path: &str, is_dir: bool) -> bool {
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+        self.patterns().any(|pattern| {
+            let (pattern, dir_only) = match pattern.strip_suffix('/') {
+                Some(pattern) => (pattern, true),
+                None => (pattern, false),
+            };
+            let pattern = pattern.trim_start_matches('/');
+            if pattern.is_empty() {
+                return false;
+            }
+            // Every component but the last is a directory.
+            let last = components.len().saturating_sub(1);
+            if pattern.contains('/') {
+                let depth = pattern.split('/').count();
+                depth <= components.len()
+                    && (is_dir || !dir_only || depth - 1 < last)
+                    && glob_match(pattern, &components[..depth].join("/"))
+            } else {
+                components.iter().enumerate().any(|(index, component)| {
+                    (!dir_only || index < last || is_dir) && glob_match(pattern, component)
+                })
+            }
+        })
+    }
+}
+
+pub fn default_presets() -> Vec<FilterPreset> {
+    vec![FilterPreset {
+        name: "Development".to_string(),
+        patterns: ".git/ node_modules/ __pycache__/ *.pyc .DS_Store".to_string(),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_like_gitignore() {
+        let preset = FilterPreset {
+            name: "Test".to_string(),
+            patterns: "node_modules/, *.pyc  build/out .env".to_string(),
+        };
+        assert!(preset.ignores("node_modules", true));
+        assert!(!preset.ignores("node_modules", false));
+        assert!(preset.ignores("web/node_modules/react/index.js", false));
+        assert!(preset.ignores("app/__init__.pyc", false));
+        assert!(preset.ignores("build/out", true));
+        assert!(preset.ignores("build/out/main.o", false));
+        assert!(!preset.ignores("src/build/out", true));
+        assert!(preset.ignores(".env", false));
+        assert!(!preset.ignores(".envrc", false));
+        assert!(!preset.ignores("src/main.py", false));
+    }
+}
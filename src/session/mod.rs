@@ -1,5 +1,15 @@
 pub mod config;
+pub mod expand;
+pub mod expiry;
+pub mod filter;
+pub mod placeholder;
+pub mod profile;
+pub mod schedule;
+pub mod search;
 mod storage;
+pub mod target;
+pub mod template;
+pub mod usage;
 
 pub use config::SessionConfig;
 pub use storage::SessionStorage;
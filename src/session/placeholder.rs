@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A value asked for when a snippet or remote command runs, written
+/// `{{service}}`, `{{service=nginx}}` for a default or
+/// `{{env:staging|production}}` for a choice, the first being the default.
+/// Braces around anything else, like a Go template's `{{.Name}}`, are left
+/// as they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub default: String,
+    pub choices: Vec<String>,
+}
+
+/// The placeholders in `text`, once each, in the order they first appear.
+/// A repeated name keeps the default and choices it was first given.
+pub fn parse(text: &str) -> Vec<Placeholder> {
+    let mut placeholders: Vec<Placeholder> = Vec::new();
+    for (_, placeholder) in scan(text) {
+        if !placeholders
+            .iter()
+            .any(|seen| seen.name == placeholder.name)
+        {
+            placeholders.push(placeholder);
+        }
+    }
+    placeholders
+}
+
+/// `text` with each placeholder replaced by its value, or by its default
+/// when `values` has none for it.
+pub fn fill(text: &str, values: &HashMap<String, String>) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut copied = 0;
+    for (range, placeholder) in scan(text) {
+        filled.push_str(&text[copied..range.start]);
+        filled.push_str(
+            values
+                .get(&placeholder.name)
+                .unwrap_or(&placeholder.default),
+        );
+        copied = range.end;
+    }
+    filled.push_str(&text[copied..]);
+    filled
+}
+
+/// `text` with every placeholder at its default, for when nobody is there
+/// to ask.
+pub fn fill_defaults(text: &str) -> String {
+    fill(text, &HashMap::new())
+}
+
+fn scan(text: &str) -> Vec<(Range<usize>, Placeholder)> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(open) = text[from..].find("{{").map(|at| from + at) {
+        let Some(close) = text[open + 2..].find("}}").map(|at| open + 2 + at) else {
+            break;
+        };
+        match placeholder(&text[open + 2..close]) {
+            Some(placeholder) => {
+                found.push((open..close + 2, placeholder));
+                from = close + 2;
+            }
+            None => from = open + 1,
+        }
+    }
+    found
+}
+
+fn placeholder(inner: &str) -> Option<Placeholder> {
+    let (name, rest) = match inner.find(['=', ':']) {
+        Some(at) => (&inner[..at], Some((&inner[at..at + 1], &inner[at + 1..]))),
+        None => (inner, None),
+    };
+    let name = name.trim();
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if name.is_empty() || !name.chars().all(valid) {
+        return None;
+    }
+    let (default, choices) = match rest {
+        None => (String::new(), Vec::new()),
+        Some(("=", default)) => (default.to_string(), Vec::new()),
+        Some((_, choices)) => {
+            let choices: Vec<String> = choices
+                .split('|')
+                .map(|choice| choice.trim().to_string())
+                .filter(|choice| !choice.is_empty())
+                .collect();
+            (choices.first().cloned().unwrap_or_default(), choices)
+        }
+    };
+    Some(Placeholder {
+        name: name.to_string(),
+        default,
+        choices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_placeholders_and_leaves_other_braces() {
+        let text = "kubectl -n {{ns:staging|prod}} rollout restart {{deploy}} \
+                    --timeout={{timeout=60s}} -o '{{.metadata.name}}' # {{deploy}}";
+        let placeholders = parse(text);
+        assert_eq!(
+            placeholders
+                .iter()
+                .map(|placeholder| placeholder.name.as_str())
+                .collect::<Vec<_>>(),
+            ["ns", "deploy", "timeout"]
+        );
+        assert_eq!(placeholders[0].choices, ["staging", "prod"]);
+        assert_eq!(placeholders[0].default, "staging");
+        assert_eq!(placeholders[2].default, "60s");
+
+        let values = HashMap::from([
+            ("ns".to_string(), "prod".to_string()),
+            ("deploy".to_string(), "api".to_string()),
+        ]);
+        assert_eq!(
+            fill(text, &values),
+            "kubectl -n prod rollout restart api --timeout=60s -o '{{.metadata.name}}' # api"
+        );
+        assert_eq!(fill_defaults("{{a}}{{b=1}}{{"), "1{{");
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+/// Appearance applied to tabs whose host matches `pattern`, such as a red
+/// tint and a "PRODUCTION" watermark for `*prod*`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HostProfile {
+    pub pattern: String,
+    /// Hex color like `#d03030` used to tint the terminal and tab.
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub badge: Option<String>,
+}
+
+impl HostProfile {
+    pub fn matches(&self, host: &str) -> bool {
+        let pattern = self.pattern.trim();
+        !pattern.is_empty() && glob_match(&pattern.to_lowercase(), &host.to_lowercase())
+    }
+
+    pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+        parse_hex_color(self.color.as_deref()?)
+    }
+
+    pub fn badge(&self) -> Option<&str> {
+        self.badge
+            .as_deref()
+            .map(str::trim)
+            .filter(|badge| !badge.is_empty())
+    }
+}
+
+/// The first rule matching `host` wins, so specific patterns go first.
+pub fn find<'a>(profiles: &'a [HostProfile], host: &str) -> Option<&'a HostProfile> {
+    profiles.iter().find(|profile| profile.matches(host))
+}
+
+/// Matches `*` (any run of characters) and `?` (one character).
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn parse_hex_color(value: &str) -> Option<(u8, u8, u8)> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |range| u8::from_str_radix(&hex[range], 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(pattern: &str) -> HostProfile {
+        HostProfile {
+            pattern: pattern.to_string(),
+            color: Some("#D03030".to_string()),
+            badge: Some("PRODUCTION".to_string()),
+        }
+    }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(profile("*prod*").matches("db-PROD-1.example.com"));
+        assert!(profile("web-?").matches("web-3"));
+        assert!(!profile("web-?").matches("web-10"));
+        assert!(profile("*.internal").matches("cache.internal"));
+        assert!(!profile("*.internal").matches("cache.internal.example"));
+        assert!(!profile("  ").matches("anything"));
+    }
+
+    #[test]
+    fn first_matching_profile_wins() {
+        let profiles = vec![profile("staging-*"), profile("*")];
+        assert_eq!(
+            find(&profiles, "staging-db").map(|p| p.pattern.as_str()),
+            Some("staging-*")
+        );
+        assert_eq!(
+            find(&profiles, "other").map(|p| p.pattern.as_str()),
+            Some("*")
+        );
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(profile("*").rgb(), Some((0xd0, 0x30, 0x30)));
+        assert_eq!(parse_hex_color("12345"), None);
+        assert_eq!(parse_hex_color("zzzzzz"), None);
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobDirection {
+    Download,
+    Upload,
+}
+
+/// A file copied between a saved session and this machine on a schedule,
+/// such as a nightly database dump. Jobs only run while the app is open;
+/// one that came due while it was closed runs shortly after start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferJob {
+    pub id: String,
+    pub session_id: String,
+    pub direction: JobDirection,
+    pub remote_path: String,
+    pub local_path: String,
+    /// As typed, e.g. `every 30m` or `daily 02:30`; see [`Schedule::parse`].
+    pub schedule: String,
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+impl TransferJob {
+    pub fn new(
+        session_id: String,
+        direction: JobDirection,
+        remote_path: String,
+        local_path: String,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id,
+            direction,
+            remote_path,
+            local_path,
+            schedule: "every 1h".to_string(),
+            last_run: None,
+        }
+    }
+
+    /// The name of the file being copied.
+    pub fn name(&self) -> &str {
+        let source = match self.direction {
+            JobDirection::Download => &self.remote_path,
+            JobDirection::Upload => &self.local_path,
+        };
+        source
+            .trim_end_matches(['/', '\\'])
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(source)
+    }
+
+    /// When the job should next run; `since` stands in for the last run of
+    /// a job that hasn't run yet. `None` while the schedule doesn't parse.
+    pub fn next_run(&self, since: DateTime<Local>) -> Option<DateTime<Local>> {
+        let last = self
+            .last_run
+            .map(|last| last.with_timezone(&Local))
+            .unwrap_or(since);
+        Some(Schedule::parse(&self.schedule)?.next_after(last))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    Every(chrono::Duration),
+    DailyAt(NaiveTime),
+}
+
+impl Schedule {
+    /// Accepts an interval like `every 30m`, `2h` or `1d`, or a time of day
+    /// like `daily 02:30` or `at 14:00`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        let value = value.strip_prefix("every").unwrap_or(&value).trim();
+        for prefix in ["daily", "at"] {
+            if let Some(time) = value.strip_prefix(prefix) {
+                return Self::parse_time(time.trim());
+            }
+        }
+        if value.contains(':') {
+            return Self::parse_time(value);
+        }
+        let split = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let amount: i64 = value[..split].parse().ok().filter(|amount| *amount > 0)?;
+        let interval = match value[split..].trim() {
+            "m" | "min" | "mins" | "minutes" => chrono::Duration::minutes(amount),
+            "h" | "hr" | "hours" => chrono::Duration::hours(amount),
+            "d" | "days" => chrono::Duration::days(amount),
+            _ => return None,
+        };
+        Some(Schedule::Every(interval))
+    }
+
+    fn parse_time(value: &str) -> Option<Self> {
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .ok()
+            .map(Schedule::DailyAt)
+    }
+
+    pub fn next_after(&self, last: DateTime<Local>) -> DateTime<Local> {
+        match *self {
+            Schedule::Every(interval) => last + interval,
+            Schedule::DailyAt(time) => {
+                let mut day = last.date_naive();
+                loop {
+                    // A time skipped by a DST change falls on the next day.
+                    if let Some(candidate) = Local
+                        .from_local_datetime(&day.and_time(time))
+                        .earliest()
+                        .filter(|candidate| *candidate > last)
+                    {
+                        return candidate;
+                    }
+                    day = day + Days::new(1);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_intervals_and_times_of_day() {
+        assert_eq!(
+            Schedule::parse("every 30m"),
+            Some(Schedule::Every(chrono::Duration::minutes(30)))
+        );
+        assert_eq!(
+            Schedule::parse("2 hours"),
+            Some(Schedule::Every(chrono::Duration::hours(2)))
+        );
+        assert_eq!(
+            Schedule::parse("Daily 02:30"),
+            Some(Schedule::DailyAt(
+                NaiveTime::from_hms_opt(2, 30, 0).unwrap()
+            ))
+        );
+        assert_eq!(Schedule::parse("every 0m"), None);
+        assert_eq!(Schedule::parse("sometimes"), None);
+        assert_eq!(Schedule::parse("at 25:00"), None);
+    }
+
+    #[test]
+    fn daily_runs_at_the_next_occurrence() {
+        let schedule = Schedule::parse("at 02:30").unwrap();
+        let before = Local.with_ymd_and_hms(2026, 3, 10, 1, 0, 0).unwrap();
+        let after = Local.with_ymd_and_hms(2026, 3, 10, 2, 30, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(before).time(),
+            NaiveTime::from_hms_opt(2, 30, 0).unwrap()
+        );
+        assert_eq!(
+            schedule.next_after(before).date_naive(),
+            before.date_naive()
+        );
+        assert!(schedule.next_after(after).date_naive() > after.date_naive());
+    }
+}
@@ -0,0 +1,227 @@
+use super::SessionConfig;
+
+// Scoring weights for fuzzy matches. A match on the session name outranks
+// the same match on host, username or tags.
+const SCORE_MATCH: i32 = 16;
+const BONUS_CONSECUTIVE: i32 = 24;
+const BONUS_WORD_START: i32 = 20;
+const BONUS_FIRST_CHAR: i32 = 12;
+const PENALTY_GAP: i32 = 1;
+const NAME_WEIGHT: i32 = 4;
+const FIELD_WEIGHT: i32 = 3;
+
+/// Matched character positions for each searchable field of a session.
+#[derive(Debug, Clone)]
+pub struct SessionMatch<'a> {
+    pub session: &'a SessionConfig,
+    pub score: i32,
+    pub name: Vec<usize>,
+    pub host: Vec<usize>,
+    pub username: Vec<usize>,
+    pub tags: Vec<(usize, Vec<usize>)>,
+}
+
+/// Scores `query` as a case-insensitive subsequence of `candidate`.
+/// Returns the score and the char indices of the matched characters.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<(i32, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+
+    // Try every start position and keep the best-scoring one, so "prod" in
+    // "pr-production" matches the contiguous word rather than "pr" + "od".
+    let mut best: Option<(i32, Vec<usize>)> = None;
+    for start in 0..chars.len() {
+        if !eq_ignore_case(chars[start], query[0]) {
+            continue;
+        }
+        let mut indices = vec![start];
+        let mut next = start + 1;
+        for &q in &query[1..] {
+            match (next..chars.len()).find(|&i| eq_ignore_case(chars[i], q)) {
+                Some(i) => {
+                    indices.push(i);
+                    next = i + 1;
+                }
+                None => break,
+            }
+        }
+        if indices.len() != query.len() {
+            break;
+        }
+        let score = score_indices(&chars, &indices);
+        if best
+            .as_ref()
+            .is_none_or(|(best_score, _)| score > *best_score)
+        {
+            best = Some((score, indices));
+        }
+    }
+    best
+}
+
+/// Ranks sessions by their best fuzzy match across name, host, username and
+/// tags. An empty query keeps every session in its saved order.
+pub fn rank_sessions<'a>(query: &str, sessions: &'a [SessionConfig]) -> Vec<SessionMatch<'a>> {
+    if query.trim().is_empty() {
+        return sessions
+            .iter()
+            .map(|session| SessionMatch {
+                session,
+                score: 0,
+                name: Vec::new(),
+                host: Vec::new(),
+                username: Vec::new(),
+                tags: Vec::new(),
+            })
+            .collect();
+    }
+
+    let mut matches: Vec<SessionMatch<'a>> = sessions
+        .iter()
+        .filter_map(|session| {
+            let name = fuzzy_match(query, &session.name);
+            let host = fuzzy_match(query, &session.host);
+            let username = fuzzy_match(query, &session.username);
+            let tags: Vec<(usize, (i32, Vec<usize>))> = session
+                .tags
+                .iter()
+                .enumerate()
+                .filter_map(|(index, tag)| fuzzy_match(query, tag).map(|m| (index, m)))
+                .collect();
+
+            let score = [
+                name.as_ref().map(|(score, _)| score * NAME_WEIGHT),
+                host.as_ref().map(|(score, _)| score * FIELD_WEIGHT),
+                username.as_ref().map(|(score, _)| score * FIELD_WEIGHT),
+                tags.iter()
+                    .map(|(_, (score, _))| score * FIELD_WEIGHT)
+                    .max(),
+            ]
+            .into_iter()
+            .flatten()
+            .max()?;
+
+            Some(SessionMatch {
+                session,
+                score,
+                name: name.map(|(_, indices)| indices).unwrap_or_default(),
+                host: host.map(|(_, indices)| indices).unwrap_or_default(),
+                username: username.map(|(_, indices)| indices).unwrap_or_default(),
+                tags: tags
+                    .into_iter()
+                    .map(|(index, (_, indices))| (index, indices))
+                    .collect(),
+            })
+        })
+        .collect();
+    // Equal scores put the most recently used session first; the stable
+    // sort keeps saved order among the rest.
+    matches.sort_by_key(|m| {
+        (
+            std::cmp::Reverse(m.score),
+            std::cmp::Reverse(m.session.last_connected),
+        )
+    });
+    matches
+}
+
+fn score_indices(chars: &[char], indices: &[usize]) -> i32 {
+    let mut score = 0;
+    let mut prev: Option<usize> = None;
+    for &index in indices {
+        score += SCORE_MATCH;
+        if index == 0 {
+            score += BONUS_FIRST_CHAR;
+        }
+        let word_start = index == 0
+            || !chars[index - 1].is_alphanumeric()
+            || (chars[index].is_uppercase() && chars[index - 1].is_lowercase());
+        if word_start {
+            score += BONUS_WORD_START;
+        }
+        if let Some(prev) = prev {
+            if index == prev + 1 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP * (index - prev - 1) as i32;
+            }
+        }
+        prev = Some(index);
+    }
+    score
+}
+
+fn eq_ignore_case(c: char, lower: char) -> bool {
+    c == lower || c.to_lowercase().eq(std::iter::once(lower))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(name: &str, host: &str, username: &str, tags: &[&str]) -> SessionConfig {
+        let mut session =
+            SessionConfig::new(name.to_string(), host.to_string(), 22, username.to_string());
+        session.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        session
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(
+            fuzzy_match("wb1", "web-1").map(|m| m.1),
+            Some(vec![0, 2, 4])
+        );
+        assert_eq!(
+            fuzzy_match("PROD", "pr-production").map(|m| m.1),
+            Some(vec![3, 4, 5, 6])
+        );
+        assert!(fuzzy_match("xyz", "web-1").is_none());
+        assert!(fuzzy_match("web", "we").is_none());
+    }
+
+    #[test]
+    fn test_rank_sessions() {
+        let sessions = vec![
+            session("database", "10.0.0.5", "postgres", &["prod"]),
+            session("web", "web.example.com", "deploy", &["staging"]),
+            session("build box", "ci.internal", "runner", &[]),
+        ];
+
+        let names = |query: &str| -> Vec<String> {
+            rank_sessions(query, &sessions)
+                .into_iter()
+                .map(|m| m.session.name.clone())
+                .collect()
+        };
+        assert_eq!(names(""), vec!["database", "web", "build box"]);
+        assert_eq!(names("prod"), vec!["database"]);
+        assert_eq!(names("deploy"), vec!["web"]);
+        assert_eq!(names("bb"), vec!["build box"]);
+
+        let matches = rank_sessions("stag", &sessions);
+        assert_eq!(matches[0].tags, vec![(0, vec![0, 1, 2, 3])]);
+    }
+
+    #[test]
+    fn test_rank_sessions_prefers_recent_on_ties() {
+        let mut recent = session("web-2", "10.0.0.2", "deploy", &[]);
+        recent.last_connected = Some(chrono::Utc::now());
+        let sessions = vec![session("web-1", "10.0.0.1", "deploy", &[]), recent];
+
+        let names: Vec<_> = rank_sessions("web", &sessions)
+            .into_iter()
+            .map(|m| m.session.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["web-2", "web-1"]);
+        // Without a query the saved order stays.
+        assert_eq!(rank_sessions("", &sessions)[0].session.name, "web-1");
+    }
+}
@@ -1,5 +1,9 @@
-use crate::session::config::SessionConfig;
+use crate::session::config::{AgentSignRule, ProxyOverride, SessionConfig, Snippet};
+use crate::session::schedule::TransferJob;
+use crate::session::template::SessionTemplate;
+use crate::session::usage::SessionUsage;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -11,9 +15,44 @@ struct SessionsFile {
     sessions: Vec<SessionConfig>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct TemplatesFile {
+    version: String,
+    templates: Vec<SessionTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JobsFile {
+    version: String,
+    jobs: Vec<TransferJob>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AgentRulesFile {
+    version: String,
+    rules: Vec<AgentSignRule>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnippetsFile {
+    version: String,
+    snippets: Vec<Snippet>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UsageFile {
+    version: String,
+    usage: HashMap<String, SessionUsage>,
+}
+
 #[derive(Debug)]
 pub struct SessionStorage {
     file_path: PathBuf,
+    templates_path: PathBuf,
+    jobs_path: PathBuf,
+    agent_rules_path: PathBuf,
+    snippets_path: PathBuf,
+    usage_path: PathBuf,
 }
 
 impl SessionStorage {
@@ -28,6 +67,11 @@ impl SessionStorage {
 
         Self {
             file_path: config_dir.join("sessions.json"),
+            templates_path: config_dir.join("templates.json"),
+            jobs_path: config_dir.join("jobs.json"),
+            agent_rules_path: config_dir.join("agent_signing.json"),
+            snippets_path: config_dir.join("snippets.json"),
+            usage_path: config_dir.join("usage.json"),
         }
     }
 
@@ -46,6 +90,9 @@ impl SessionStorage {
         for session in &mut sessions {
             session.password = load_secret(&session.id, SecretKind::Password);
             session.key_passphrase = load_secret(&session.id, SecretKind::KeyPassphrase);
+            if let Some(ProxyOverride::Via(proxy)) = &mut session.proxy {
+                proxy.password = load_secret(&session.id, SecretKind::ProxyPassword);
+            }
         }
 
         Ok(sessions)
@@ -68,6 +115,18 @@ impl SessionStorage {
             } else if let Err(err) = delete_secret(&session.id, SecretKind::KeyPassphrase) {
                 tracing::warn!("Failed to delete key passphrase from keyring: {}", err);
             }
+
+            let proxy_password = match &session.proxy {
+                Some(ProxyOverride::Via(proxy)) => proxy.password.as_deref(),
+                _ => None,
+            };
+            if let Some(password) = proxy_password {
+                if let Err(err) = store_secret(&session.id, SecretKind::ProxyPassword, password) {
+                    tracing::warn!("Failed to store proxy password in keyring: {}", err);
+                }
+            } else if let Err(err) = delete_secret(&session.id, SecretKind::ProxyPassword) {
+                tracing::warn!("Failed to delete proxy password from keyring: {}", err);
+            }
         }
 
         let sanitized: Vec<_> = sessions
@@ -117,20 +176,170 @@ impl SessionStorage {
         if let Err(err) = delete_secret(id, SecretKind::KeyPassphrase) {
             tracing::warn!("Failed to delete key passphrase from keyring: {}", err);
         }
+        if let Err(err) = delete_secret(id, SecretKind::ProxyPassword) {
+            tracing::warn!("Failed to delete proxy password from keyring: {}", err);
+        }
         self.save_sessions(existing)
     }
+
+    pub fn load_templates(&self) -> Result<Vec<SessionTemplate>, String> {
+        if !self.templates_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(&self.templates_path)
+            .map_err(|e| format!("Failed to read templates file: {}", e))?;
+
+        let file: TemplatesFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse templates file: {}", e))?;
+
+        Ok(file.templates)
+    }
+
+    pub fn save_templates(&self, templates: &[SessionTemplate]) -> Result<(), String> {
+        let file = TemplatesFile {
+            version: "1.0".to_string(),
+            templates: templates.to_vec(),
+        };
+
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize templates: {}", e))?;
+
+        fs::write(&self.templates_path, contents)
+            .map_err(|e| format!("Failed to write templates file: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn load_jobs(&self) -> Result<Vec<TransferJob>, String> {
+        if !self.jobs_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(&self.jobs_path)
+            .map_err(|e| format!("Failed to read jobs file: {}", e))?;
+
+        let file: JobsFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse jobs file: {}", e))?;
+
+        Ok(file.jobs)
+    }
+
+    pub fn save_jobs(&self, jobs: &[TransferJob]) -> Result<(), String> {
+        let file = JobsFile {
+            version: "1.0".to_string(),
+            jobs: jobs.to_vec(),
+        };
+
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize jobs: {}", e))?;
+
+        fs::write(&self.jobs_path, contents)
+            .map_err(|e| format!("Failed to write jobs file: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn load_agent_rules(&self) -> Result<Vec<AgentSignRule>, String> {
+        if !self.agent_rules_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(&self.agent_rules_path)
+            .map_err(|e| format!("Failed to read agent signing file: {}", e))?;
+
+        let file: AgentRulesFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse agent signing file: {}", e))?;
+
+        Ok(file.rules)
+    }
+
+    pub fn save_agent_rules(&self, rules: &[AgentSignRule]) -> Result<(), String> {
+        let file = AgentRulesFile {
+            version: "1.0".to_string(),
+            rules: rules.to_vec(),
+        };
+
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize agent signing rules: {}", e))?;
+
+        fs::write(&self.agent_rules_path, contents)
+            .map_err(|e| format!("Failed to write agent signing file: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn load_usage(&self) -> Result<HashMap<String, SessionUsage>, String> {
+        if !self.usage_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let contents = fs::read_to_string(&self.usage_path)
+            .map_err(|e| format!("Failed to read usage file: {}", e))?;
+
+        let file: UsageFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse usage file: {}", e))?;
+
+        Ok(file.usage)
+    }
+
+    pub fn save_usage(&self, usage: &HashMap<String, SessionUsage>) -> Result<(), String> {
+        let file = UsageFile {
+            version: "1.0".to_string(),
+            usage: usage.clone(),
+        };
+
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize session usage: {}", e))?;
+
+        fs::write(&self.usage_path, contents)
+            .map_err(|e| format!("Failed to write usage file: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn load_snippets(&self) -> Result<Vec<Snippet>, String> {
+        if !self.snippets_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(&self.snippets_path)
+            .map_err(|e| format!("Failed to read snippets file: {}", e))?;
+
+        let file: SnippetsFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse snippets file: {}", e))?;
+
+        Ok(file.snippets)
+    }
+
+    pub fn save_snippets(&self, snippets: &[Snippet]) -> Result<(), String> {
+        let file = SnippetsFile {
+            version: "1.0".to_string(),
+            snippets: snippets.to_vec(),
+        };
+
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize snippets: {}", e))?;
+
+        fs::write(&self.snippets_path, contents)
+            .map_err(|e| format!("Failed to write snippets file: {}", e))?;
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Copy)]
 enum SecretKind {
     Password,
     KeyPassphrase,
+    ProxyPassword,
 }
 
 fn secret_key(session_id: &str, kind: SecretKind) -> String {
     match kind {
         SecretKind::Password => format!("session:{}:password", session_id),
         SecretKind::KeyPassphrase => format!("session:{}:key_passphrase", session_id),
+        SecretKind::ProxyPassword => format!("session:{}:proxy_password", session_id),
     }
 }
 
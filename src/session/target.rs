@@ -0,0 +1,200 @@
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// Only the start of the clipboard is searched; a pasted log file isn't an
+// address someone meant to connect to.
+const MAX_LINES: usize = 20;
+
+// ssh options that take a value, as listed in ssh(1).
+const OPTIONS_WITH_VALUE: &str = "BbcDEeFIiJLlmOoPpQRSWw";
+
+/// A host found in free text such as a wiki page or an alert, with whatever
+/// user and port came along with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectTarget {
+    pub username: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl ConnectTarget {
+    /// Looks for an `ssh ...` command first, then `user@host`, then a bare
+    /// IP address.
+    pub fn find(text: &str) -> Option<Self> {
+        let lines = || text.lines().take(MAX_LINES);
+        lines()
+            .find_map(from_ssh_command)
+            .or_else(|| lines().flat_map(tokens).find_map(from_user_at_host))
+            .or_else(|| lines().flat_map(tokens).find_map(from_ip_address))
+    }
+}
+
+impl fmt::Display for ConnectTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(username) = &self.username {
+            write!(f, "{}@", username)?;
+        }
+        match (self.port, self.host.contains(':')) {
+            (Some(port), true) => write!(f, "[{}]:{}", self.host, port),
+            (Some(port), false) => write!(f, "{}:{}", self.host, port),
+            (None, _) => write!(f, "{}", self.host),
+        }
+    }
+}
+
+// Whitespace-separated words with quotes and sentence punctuation removed.
+fn tokens(line: &str) -> impl Iterator<Item = &str> {
+    line.split_whitespace()
+        .map(|token| {
+            token
+                .trim_matches(|c| matches!(c, '"' | '\'' | '`' | ',' | ';' | '(' | ')' | '<' | '>'))
+                .trim_end_matches(['.', ':'])
+        })
+        .filter(|token| !token.is_empty())
+}
+
+// Only a line that starts with `ssh`, possibly after a shell prompt, is a
+// command; "can't ssh into web-1" is prose.
+fn from_ssh_command(line: &str) -> Option<ConnectTarget> {
+    let words: Vec<&str> = tokens(line).collect();
+    let start = words.iter().position(|word| *word == "ssh")?;
+    if start > 0 && !words[start - 1].ends_with(['$', '#', '%']) {
+        return None;
+    }
+    let mut words = words.into_iter().skip(start + 1);
+    let mut username = None;
+    let mut port = None;
+    while let Some(word) = words.next() {
+        let Some(option) = word.strip_prefix('-') else {
+            let mut target = parse_address(word.strip_prefix("ssh://").unwrap_or(word))?;
+            target.username = target.username.or(username);
+            target.port = target.port.or(port);
+            return Some(target);
+        };
+        let Some(flag) = option.chars().next() else {
+            continue;
+        };
+        if !OPTIONS_WITH_VALUE.contains(flag) {
+            continue;
+        }
+        // The value is either attached (`-p2222`) or the next word.
+        let value = match &option[flag.len_utf8()..] {
+            "" => words.next()?,
+            attached => attached,
+        };
+        match flag {
+            'p' => port = value.parse().ok(),
+            'l' => username = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn from_user_at_host(token: &str) -> Option<ConnectTarget> {
+    let token = token.strip_prefix("ssh://").unwrap_or(token);
+    if !token.contains('@') {
+        return None;
+    }
+    parse_address(token)
+}
+
+fn from_ip_address(token: &str) -> Option<ConnectTarget> {
+    let target = parse_address(token)?;
+    let is_ip = target.host.parse::<Ipv4Addr>().is_ok() || target.host.parse::<Ipv6Addr>().is_ok();
+    is_ip.then_some(target)
+}
+
+/// `[user@]host[:port]`, with IPv6 hosts bracketed when a port follows.
+fn parse_address(value: &str) -> Option<ConnectTarget> {
+    let (username, rest) = match value.rsplit_once('@') {
+        Some((user, rest)) if is_username(user) => (Some(user.to_string()), rest),
+        Some(_) => return None,
+        None => (None, value),
+    };
+    let (host, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+        let (host, after) = bracketed.split_once(']')?;
+        let port = match after {
+            "" => None,
+            after => Some(after.strip_prefix(':')?.parse().ok()?),
+        };
+        (host, port)
+    } else if rest.parse::<Ipv6Addr>().is_ok() {
+        (rest, None)
+    } else {
+        match rest.split_once(':') {
+            Some((host, port)) => (host, Some(port.parse().ok()?)),
+            None => (rest, None),
+        }
+    };
+    let valid = host.parse::<Ipv6Addr>().is_ok() || is_hostname(host);
+    valid.then(|| ConnectTarget {
+        username,
+        host: host.to_string(),
+        port,
+    })
+}
+
+fn is_username(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn is_hostname(value: &str) -> bool {
+    !value.is_empty()
+        && !value.starts_with(['-', '.'])
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(username: Option<&str>, host: &str, port: Option<u16>) -> Option<ConnectTarget> {
+        Some(ConnectTarget {
+            username: username.map(str::to_string),
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    #[test]
+    fn finds_hosts_in_copied_text() {
+        assert_eq!(
+            ConnectTarget::find("$ ssh -i ~/.ssh/ops -p 2222 deploy@web-1.example.com"),
+            target(Some("deploy"), "web-1.example.com", Some(2222))
+        );
+        assert_eq!(
+            ConnectTarget::find("ssh -l admin -o StrictHostKeyChecking=no db01"),
+            target(Some("admin"), "db01", None)
+        );
+        assert_eq!(
+            ConnectTarget::find("Alert: disk full on root@10.0.3.7, please check."),
+            target(Some("root"), "10.0.3.7", None)
+        );
+        assert_eq!(
+            ConnectTarget::find("Host down: 192.168.1.20:2200 (ping timeout)"),
+            target(None, "192.168.1.20", Some(2200))
+        );
+        assert_eq!(
+            ConnectTarget::find("ssh://ops@[2001:db8::1]:22"),
+            target(Some("ops"), "2001:db8::1", Some(22))
+        );
+        assert_eq!(
+            ConnectTarget::find("can't ssh into it, see ops@bastion"),
+            target(Some("ops"), "bastion", None)
+        );
+        assert_eq!(ConnectTarget::find("nothing to see here"), None);
+        assert_eq!(ConnectTarget::find("version 1.2.3 released"), None);
+    }
+
+    #[test]
+    fn displays_as_an_address() {
+        let found = ConnectTarget::find("ssh -p 2222 ops@2001:db8::1").unwrap();
+        assert_eq!(found.to_string(), "ops@[2001:db8::1]:2222");
+    }
+}
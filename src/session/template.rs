@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use crate::session::config::{
+    AuthMethod, BellMode, EnvVar, JumpHost, Keepalive, KnockStep, PortForwardRule, Proxy,
+    ProxyOverride, SessionConfig, TransferDefaults, TransferLimits,
+};
+
+/// Everything about a session except where it points, so a fleet behind the
+/// same bastion can be added by hostname alone. Secrets are never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTemplate {
+    pub id: String,
+    pub name: String,
+    pub port: u16,
+    pub username: String,
+    pub auth_method: AuthMethod,
+    #[serde(default)]
+    pub jump_host: Option<JumpHost>,
+    #[serde(default)]
+    pub proxy: Option<ProxyOverride>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub port_forwards: Vec<PortForwardRule>,
+    #[serde(default)]
+    pub idle_timeout_minutes: Option<u32>,
+    #[serde(default)]
+    pub bell: BellMode,
+    #[serde(default)]
+    pub badge: Option<String>,
+    #[serde(default)]
+    pub tmux_session: Option<String>,
+    #[serde(default)]
+    pub remote_command: Option<String>,
+    #[serde(default)]
+    pub environment: Vec<EnvVar>,
+    #[serde(default)]
+    pub send_locale: bool,
+    #[serde(default)]
+    pub port_knock: Vec<KnockStep>,
+    #[serde(default)]
+    pub transfer_limits: Option<TransferLimits>,
+    #[serde(default)]
+    pub keepalive: Option<Keepalive>,
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u32>,
+    #[serde(default)]
+    pub forward_agent: bool,
+    #[serde(default)]
+    pub compression: bool,
+    #[serde(default)]
+    pub transfer_defaults: TransferDefaults,
+}
+
+impl SessionTemplate {
+    pub fn from_session(name: String, session: &SessionConfig) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            port: session.port,
+            username: session.username.clone(),
+            auth_method: session.auth_method.clone(),
+            jump_host: session.jump_host.clone(),
+            proxy: session.proxy.clone().map(|proxy| match proxy {
+                ProxyOverride::Via(proxy) => ProxyOverride::Via(Proxy {
+                    password: None,
+                    ..proxy
+                }),
+                direct => direct,
+            }),
+            tags: session.tags.clone(),
+            port_forwards: session.port_forwards.clone(),
+            idle_timeout_minutes: session.idle_timeout_minutes,
+            bell: session.bell,
+            badge: session.badge.clone(),
+            tmux_session: session.tmux_session.clone(),
+            remote_command: session.remote_command.clone(),
+            environment: session.environment.clone(),
+            send_locale: session.send_locale,
+            port_knock: session.port_knock.clone(),
+            transfer_limits: session.transfer_limits,
+            keepalive: session.keepalive,
+            connect_timeout_secs: session.connect_timeout_secs,
+            forward_agent: session.forward_agent,
+            compression: session.compression,
+            transfer_defaults: session.transfer_defaults,
+        }
+    }
+
+    /// A new session for `host`, named after it.
+    pub fn instantiate(&self, host: &str) -> SessionConfig {
+        let mut session = SessionConfig::new(
+            host.to_string(),
+            host.to_string(),
+            self.port,
+            self.username.clone(),
+        );
+        session.auth_method = self.auth_method.clone();
+        session.jump_host = self.jump_host.clone();
+        session.proxy = self.proxy.clone();
+        session.tags = self.tags.clone();
+        session.port_forwards = self
+            .port_forwards
+            .iter()
+            .cloned()
+            .map(|mut rule| {
+                rule.id = uuid::Uuid::new_v4().to_string();
+                rule
+            })
+            .collect();
+        session.idle_timeout_minutes = self.idle_timeout_minutes;
+        session.bell = self.bell;
+        session.badge = self.badge.clone();
+        session.tmux_session = self.tmux_session.clone();
+        session.remote_command = self.remote_command.clone();
+        session.environment = self.environment.clone();
+        session.send_locale = self.send_locale;
+        session.port_knock = self.port_knock.clone();
+        session.transfer_limits = self.transfer_limits;
+        session.keepalive = self.keepalive;
+        session.connect_timeout_secs = self.connect_timeout_secs;
+        session.forward_agent = self.forward_agent;
+        session.compression = self.compression;
+        session.transfer_defaults = self.transfer_defaults;
+        session
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instantiates_without_secrets_or_shared_ids() {
+        let mut session = SessionConfig::new(
+            "web-1".to_string(),
+            "10.0.1.12".to_string(),
+            2222,
+            "deploy".to_string(),
+        );
+        session.password = Some("secret".to_string());
+        session.jump_host = Some(JumpHost::parse("ops@bastion.example.com").unwrap());
+        session.tags = vec!["prod".to_string()];
+        session.port_forwards.push(PortForwardRule {
+            id: "rule".to_string(),
+            direction: Default::default(),
+            local_host: "127.0.0.1".to_string(),
+            local_port: 5432,
+            remote_host: "db.internal".to_string(),
+            remote_port: 5432,
+            enabled: true,
+        });
+
+        let template = SessionTemplate::from_session("Prod VPC".to_string(), &session);
+        let created = template.instantiate("10.0.1.13");
+        assert_eq!(created.name, "10.0.1.13");
+        assert_eq!(created.host, "10.0.1.13");
+        assert_eq!(created.port, 2222);
+        assert_eq!(created.username, "deploy");
+        assert_eq!(created.jump_host, session.jump_host);
+        assert_eq!(created.tags, session.tags);
+        assert_eq!(created.password, None);
+        assert_ne!(created.id, session.id);
+        assert_eq!(created.port_forwards.len(), 1);
+        assert_ne!(created.port_forwards[0].id, "rule");
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Days covered by the connect history on a session card.
+pub const USAGE_DAYS: usize = 30;
+
+/// How much a saved session is used, kept apart from its settings so
+/// counting a connect doesn't rewrite the sessions file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionUsage {
+    #[serde(default)]
+    pub connects: u64,
+    #[serde(default)]
+    pub connected_ms: u64,
+    #[serde(default)]
+    pub bytes_received: u64,
+    #[serde(default)]
+    pub bytes_sent: u64,
+    /// Connects per local day, for the last `USAGE_DAYS` days.
+    #[serde(default)]
+    pub daily: BTreeMap<NaiveDate, u32>,
+}
+
+impl SessionUsage {
+    pub fn record_connect(&mut self, today: NaiveDate) {
+        self.connects += 1;
+        *self.daily.entry(today).or_default() += 1;
+        if let Some(first) = first_day(today) {
+            self.daily.retain(|day, _| *day >= first);
+        }
+    }
+
+    pub fn add_connected(&mut self, elapsed: Duration) {
+        self.connected_ms = self.connected_ms.saturating_add(elapsed.as_millis() as u64);
+    }
+
+    pub fn add_traffic(&mut self, received: u64, sent: u64) {
+        self.bytes_received = self.bytes_received.saturating_add(received);
+        self.bytes_sent = self.bytes_sent.saturating_add(sent);
+    }
+
+    pub fn connected_time(&self) -> Duration {
+        Duration::from_millis(self.connected_ms)
+    }
+
+    /// Connects on each of the last `USAGE_DAYS` days, oldest first and
+    /// ending with `today`.
+    pub fn daily_connects(&self, today: NaiveDate) -> [u32; USAGE_DAYS] {
+        let mut days = [0; USAGE_DAYS];
+        for (index, count) in days.iter_mut().rev().enumerate() {
+            if let Some(day) = today.checked_sub_days(Days::new(index as u64)) {
+                *count = self.daily.get(&day).copied().unwrap_or(0);
+            }
+        }
+        days
+    }
+}
+
+fn first_day(today: NaiveDate) -> Option<NaiveDate> {
+    today.checked_sub_days(Days::new(USAGE_DAYS as u64 - 1))
+}
+
+/// Connected time the way the card shows it, e.g. `3h 12m`.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) if duration.as_secs() > 0 => "<1m".to_string(),
+        (0, minutes) => format!("{}m", minutes),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_a_month_of_connects() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let mut usage = SessionUsage::default();
+        usage.record_connect(day(1));
+        usage.record_connect(day(20));
+        usage.record_connect(day(20));
+        usage.record_connect(day(31));
+        assert_eq!(usage.connects, 4);
+        // The 1st is more than 30 days before the 31st.
+        assert_eq!(usage.daily.len(), 2);
+
+        let days = usage.daily_connects(day(31));
+        assert_eq!(days[USAGE_DAYS - 1], 1);
+        assert_eq!(days[USAGE_DAYS - 12], 2);
+        assert_eq!(days.iter().sum::<u32>(), 3);
+
+        usage.add_connected(Duration::from_secs(3 * 3600 + 12 * 60 + 5));
+        assert_eq!(format_duration(usage.connected_time()), "3h 12m");
+        assert_eq!(format_duration(Duration::from_secs(20)), "<1m");
+        assert_eq!(format_duration(Duration::ZERO), "0m");
+    }
+}
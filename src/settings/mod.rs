@@ -1,8 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::logging::LoggingOptions;
+use crate::session::config::{Keepalive, Proxy, TransferLimits};
+use crate::session::expand::DomainRule;
+use crate::session::filter::{self, FilterPreset};
+use crate::session::profile::HostProfile;
+use crate::terminal::editor::EditorOptions;
+use crate::terminal::keymap::{self, KeyBinding};
+use crate::terminal::paste::PasteOptions;
+use crate::terminal::scroll::ScrollOptions;
+use crate::terminal::special_keys::CustomKey;
+
 const KEYRING_SERVICE: &str = "rivett";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -26,8 +38,191 @@ pub struct AppSettings {
     pub use_gpu_renderer: bool,
     #[serde(default)]
     pub theme: ThemeMode,
+    /// Light or dark terminal, which may differ from the app's theme.
+    #[serde(default)]
+    pub terminal_theme: TerminalTheme,
     #[serde(default)]
     pub ssh_keys: Vec<SshKeyEntry>,
+    /// Offer every saved key once a session's own keys are refused, instead
+    /// of only the keys the session names (OpenSSH's IdentitiesOnly).
+    #[serde(default)]
+    pub offer_all_keys: bool,
+    #[serde(default = "keymap::default_bindings")]
+    pub key_bindings: Vec<KeyBinding>,
+    #[serde(default)]
+    pub paste: PasteOptions,
+    #[serde(default)]
+    pub custom_keys: Vec<CustomKey>,
+    /// Keep the viewport still while reading history instead of following
+    /// new output.
+    #[serde(default = "default_pause_scroll_in_history")]
+    pub pause_scroll_in_history: bool,
+    #[serde(default)]
+    pub scroll: ScrollOptions,
+    #[serde(default)]
+    pub bold_is_bright: bool,
+    #[serde(default = "default_render_italics")]
+    pub render_italics: bool,
+    #[serde(default)]
+    pub bold_font: Option<String>,
+    #[serde(default)]
+    pub italic_font: Option<String>,
+    #[serde(default = "default_background_opacity")]
+    pub background_opacity: f32,
+    /// Blur behind a translucent window; macOS only, applied at startup.
+    #[serde(default)]
+    pub background_blur: bool,
+    /// Space in pixels between the terminal grid and its edges.
+    #[serde(default)]
+    pub terminal_padding: f32,
+    /// Multiplier applied to the base cell height.
+    #[serde(default = "default_line_height")]
+    pub line_height: f32,
+    /// Per-host appearance rules, matched against the session hostname.
+    #[serde(default)]
+    pub host_profiles: Vec<HostProfile>,
+    /// Domains appended to short host names, chosen by session tag.
+    #[serde(default)]
+    pub host_domains: Vec<DomainRule>,
+    /// Extra regular expressions masked in terminal output while redaction
+    /// is switched on, on top of addresses, hostnames and common secrets.
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+    /// Opens `path:line:col` references Cmd+clicked in local tabs.
+    #[serde(default)]
+    pub editor: EditorOptions,
+    /// Start the SSH handshake for a saved session as soon as it is hovered
+    /// or selected in quick connect, so opening it feels instant.
+    #[serde(default)]
+    pub preconnect_sessions: bool,
+    /// Retry saved sessions whose connection drops, waiting longer after
+    /// each failed attempt.
+    #[serde(default = "default_auto_reconnect")]
+    pub auto_reconnect: bool,
+    /// Open new tabs to a host that is already connected as channels on
+    /// that connection, instead of logging in again.
+    #[serde(default = "default_share_connections")]
+    pub share_connections: bool,
+    /// Check in the background whether saved hosts answer, for the dots
+    /// on the session cards.
+    #[serde(default = "default_probe_sessions")]
+    pub probe_sessions: bool,
+    /// Keepalive for sessions that don't set their own.
+    #[serde(default)]
+    pub keepalive: Keepalive,
+    /// Seconds each step of connecting may take, for sessions that don't
+    /// set their own.
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u32,
+    /// Proxy for sessions that don't set their own.
+    #[serde(default)]
+    pub proxy: Option<Proxy>,
+    /// Default SFTP concurrency; sessions may override it.
+    #[serde(default)]
+    pub transfer_limits: TransferLimits,
+    /// SFTP read or write requests kept in flight per transfer.
+    #[serde(default = "default_sftp_pipeline_depth")]
+    pub sftp_pipeline_depth: usize,
+    /// Resolve symlinks in remote file lists in the background.
+    #[serde(default)]
+    pub sftp_prefetch_details: bool,
+    /// Move remote files to `sftp_trash_dir` instead of deleting them.
+    #[serde(default)]
+    pub sftp_remote_trash: bool,
+    /// Remote folder deleted files are moved to; `~/` is the remote home.
+    #[serde(default = "default_sftp_trash_dir")]
+    pub sftp_trash_dir: String,
+    /// Ignore lists that can be applied to either SFTP pane.
+    #[serde(default = "filter::default_presets")]
+    pub sftp_filter_presets: Vec<FilterPreset>,
+    /// Let OSC 52 from remote programs (and the installed pbcopy helper)
+    /// write the local clipboard.
+    #[serde(default)]
+    pub remote_clipboard: bool,
+    /// Also answer OSC 52 queries (and so pbpaste), which lets any remote
+    /// program read the local clipboard without asking.
+    #[serde(default)]
+    pub remote_clipboard_read: bool,
+    /// Offer mirroring a terminal tab read-only to other rivett instances.
+    #[serde(default)]
+    pub allow_terminal_sharing: bool,
+    /// Start new local tabs in the previous local tab's directory, with the
+    /// variables its shell integration reported.
+    #[serde(default)]
+    pub local_tabs_inherit: bool,
+    /// What a local tab does once its shell exits.
+    #[serde(default)]
+    pub local_shell_exit: ShellExitAction,
+    /// Close an SSH tab when its shell exits with status 0 instead of
+    /// showing the Connection closed overlay.
+    #[serde(default)]
+    pub close_on_clean_exit: bool,
+    /// Hides the terminal while nobody is using it.
+    #[serde(default)]
+    pub screen_guard: ScreenGuard,
+    /// What is open when the app starts.
+    #[serde(default)]
+    pub startup_view: StartupView,
+    /// Saved session connected at launch with `StartupView::ConnectSession`.
+    #[serde(default)]
+    pub startup_session: Option<String>,
+    /// Log levels per subsystem and the rotating log file.
+    #[serde(default)]
+    pub logging: LoggingOptions,
+    /// Serve the token-protected control API on localhost for scripts.
+    #[serde(default)]
+    pub control_api: bool,
+    #[serde(default = "default_control_api_port")]
+    pub control_api_port: u16,
+    /// Read-only mode for demo machines and wall displays: terminal output
+    /// stays redacted, sessions can't be created, edited or deleted, and
+    /// SFTP can't delete files. `--viewer` turns it on for one run.
+    #[serde(default)]
+    pub viewer_mode: bool,
+}
+
+fn default_sftp_trash_dir() -> String {
+    "~/.rivett-trash".to_string()
+}
+
+fn default_control_api_port() -> u16 {
+    crate::control::DEFAULT_PORT
+}
+
+fn default_sftp_pipeline_depth() -> usize {
+    16
+}
+
+fn default_line_height() -> f32 {
+    1.0
+}
+
+fn default_background_opacity() -> f32 {
+    1.0
+}
+
+fn default_render_italics() -> bool {
+    true
+}
+
+fn default_auto_reconnect() -> bool {
+    true
+}
+
+fn default_share_connections() -> bool {
+    true
+}
+
+fn default_connect_timeout_secs() -> u32 {
+    10
+}
+
+fn default_probe_sessions() -> bool {
+    true
+}
+
+fn default_pause_scroll_in_history() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -43,13 +238,196 @@ impl Default for ThemeMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalTheme {
+    /// Whichever the app theme is.
+    #[default]
+    Match,
+    Light,
+    Dark,
+}
+
+impl TerminalTheme {
+    pub const ALL: [TerminalTheme; 3] = [
+        TerminalTheme::Match,
+        TerminalTheme::Light,
+        TerminalTheme::Dark,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TerminalTheme::Match => "Match app",
+            TerminalTheme::Light => "Light",
+            TerminalTheme::Dark => "Dark",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellExitAction {
+    Close,
+    /// Keep the output and wait for Enter to close or R to restart.
+    #[default]
+    Hold,
+    Restart,
+}
+
+impl ShellExitAction {
+    pub const ALL: [ShellExitAction; 3] = [
+        ShellExitAction::Close,
+        ShellExitAction::Hold,
+        ShellExitAction::Restart,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShellExitAction::Close => "Close",
+            ShellExitAction::Hold => "Hold",
+            ShellExitAction::Restart => "Restart",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupView {
+    #[default]
+    SessionManager,
+    LocalShell,
+    RestoreTabs,
+    ConnectSession,
+}
+
+impl StartupView {
+    pub const ALL: [StartupView; 4] = [
+        StartupView::SessionManager,
+        StartupView::LocalShell,
+        StartupView::RestoreTabs,
+        StartupView::ConnectSession,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StartupView::SessionManager => "Sessions",
+            StartupView::LocalShell => "Local Shell",
+            StartupView::RestoreTabs => "Last Tabs",
+            StartupView::ConnectSession => "Connect",
+        }
+    }
+}
+
+/// Dims or blanks the terminal after a while without keyboard or mouse
+/// input, so an unattended screen doesn't show it. The connection stays up
+/// and a click or key brings it back.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScreenGuard {
+    #[serde(default)]
+    pub mode: ScreenGuardMode,
+    /// Minutes without input before the terminal is hidden.
+    #[serde(default = "default_screen_guard_minutes")]
+    pub minutes: u32,
+}
+
+impl Default for ScreenGuard {
+    fn default() -> Self {
+        Self {
+            mode: ScreenGuardMode::default(),
+            minutes: default_screen_guard_minutes(),
+        }
+    }
+}
+
+impl ScreenGuard {
+    /// How long without input before the terminal is hidden, if ever.
+    pub fn delay(&self) -> Option<std::time::Duration> {
+        (self.mode != ScreenGuardMode::Off)
+            .then(|| std::time::Duration::from_secs(u64::from(self.minutes.max(1)) * 60))
+    }
+}
+
+fn default_screen_guard_minutes() -> u32 {
+    10
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenGuardMode {
+    #[default]
+    Off,
+    Dim,
+    Blank,
+}
+
+impl ScreenGuardMode {
+    pub const ALL: [ScreenGuardMode; 3] = [
+        ScreenGuardMode::Off,
+        ScreenGuardMode::Dim,
+        ScreenGuardMode::Blank,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScreenGuardMode::Off => "Off",
+            ScreenGuardMode::Dim => "Dim",
+            ScreenGuardMode::Blank => "Blank",
+        }
+    }
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             terminal_font_size: 12.0,
             use_gpu_renderer: true,
             theme: ThemeMode::Light,
+            terminal_theme: TerminalTheme::default(),
             ssh_keys: Vec::new(),
+            offer_all_keys: false,
+            key_bindings: keymap::default_bindings(),
+            paste: PasteOptions::default(),
+            custom_keys: Vec::new(),
+            pause_scroll_in_history: true,
+            scroll: ScrollOptions::default(),
+            bold_is_bright: false,
+            render_italics: true,
+            bold_font: None,
+            italic_font: None,
+            background_opacity: 1.0,
+            background_blur: false,
+            terminal_padding: 0.0,
+            line_height: 1.0,
+            host_profiles: Vec::new(),
+            host_domains: Vec::new(),
+            redact_patterns: Vec::new(),
+            editor: EditorOptions::default(),
+            preconnect_sessions: false,
+            auto_reconnect: default_auto_reconnect(),
+            share_connections: default_share_connections(),
+            probe_sessions: default_probe_sessions(),
+            keepalive: Keepalive::default(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            proxy: None,
+            transfer_limits: TransferLimits::default(),
+            sftp_pipeline_depth: default_sftp_pipeline_depth(),
+            sftp_prefetch_details: false,
+            sftp_remote_trash: false,
+            sftp_trash_dir: default_sftp_trash_dir(),
+            sftp_filter_presets: filter::default_presets(),
+            remote_clipboard: false,
+            remote_clipboard_read: false,
+            allow_terminal_sharing: false,
+            local_tabs_inherit: false,
+            local_shell_exit: ShellExitAction::default(),
+            close_on_clean_exit: false,
+            screen_guard: ScreenGuard::default(),
+            startup_view: StartupView::default(),
+            startup_session: None,
+            logging: LoggingOptions::default(),
+            control_api: false,
+            control_api_port: default_control_api_port(),
+            viewer_mode: false,
         }
     }
 }
@@ -60,9 +438,44 @@ struct SettingsFile {
     settings: AppSettings,
 }
 
+/// Main window layout from the last run. Kept out of the settings file,
+/// which the settings window rewrites whole.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    #[serde(default)]
+    pub size: Option<(f32, f32)>,
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+    #[serde(default)]
+    pub maximized: bool,
+    /// SFTP panel width by saved session id.
+    #[serde(default)]
+    pub sftp_panel_widths: HashMap<String, f32>,
+    /// Tabs that were open, left to right, for `StartupView::RestoreTabs`.
+    #[serde(default)]
+    pub open_tabs: Vec<OpenTab>,
+    /// Show saved sessions as a table instead of cards.
+    #[serde(default)]
+    pub session_table: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpenTab {
+    Local,
+    Session { id: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WindowFile {
+    version: String,
+    window: WindowState,
+}
+
 #[derive(Debug)]
 pub struct SettingsStorage {
     file_path: PathBuf,
+    window_path: PathBuf,
 }
 
 impl SettingsStorage {
@@ -76,6 +489,7 @@ impl SettingsStorage {
 
         Self {
             file_path: config_dir.join("settings.json"),
+            window_path: config_dir.join("window.json"),
         }
     }
 
@@ -99,6 +513,10 @@ impl SettingsStorage {
             }
         }
 
+        if let Some(proxy) = &mut settings.proxy {
+            proxy.password = load_proxy_secret();
+        }
+
         if needs_save {
             let _ = self.save_settings(&settings);
         }
@@ -107,6 +525,10 @@ impl SettingsStorage {
     }
 
     pub fn save_settings(&self, settings: &AppSettings) -> Result<(), String> {
+        let password = settings.proxy.as_ref().and_then(|p| p.password.as_deref());
+        if let Err(err) = store_proxy_secret(password) {
+            tracing::warn!("Failed to update proxy password in keyring: {}", err);
+        }
         let file = SettingsFile {
             version: "1.0".to_string(),
             settings: settings.clone(),
@@ -120,6 +542,35 @@ impl SettingsStorage {
 
         Ok(())
     }
+
+    pub fn load_window_state(&self) -> Result<WindowState, String> {
+        if !self.window_path.exists() {
+            return Ok(WindowState::default());
+        }
+
+        let contents = fs::read_to_string(&self.window_path)
+            .map_err(|e| format!("Failed to read window state file: {}", e))?;
+
+        let file: WindowFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse window state file: {}", e))?;
+
+        Ok(file.window)
+    }
+
+    pub fn save_window_state(&self, window: &WindowState) -> Result<(), String> {
+        let file = WindowFile {
+            version: "1.0".to_string(),
+            window: window.clone(),
+        };
+
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+
+        fs::write(&self.window_path, contents)
+            .map_err(|e| format!("Failed to write window state file: {}", e))?;
+
+        Ok(())
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -151,6 +602,25 @@ pub fn load_key_secret(key_id: &str) -> Option<String> {
     entry.get_password().ok()
 }
 
+const PROXY_SECRET: &str = "proxy:default:password";
+
+fn load_proxy_secret() -> Option<String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, PROXY_SECRET).ok()?;
+    entry.get_password().ok()
+}
+
+/// Stores the default proxy's password, or removes it when there is none.
+fn store_proxy_secret(password: Option<&str>) -> Result<(), String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, PROXY_SECRET).map_err(|e| e.to_string())?;
+    match password {
+        Some(password) => entry.set_password(password).map_err(|e| e.to_string()),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(err.to_string()),
+        },
+    }
+}
+
 pub fn delete_key_secret(key_id: &str) -> Result<(), String> {
     let entry = keyring::Entry::new(
         KEYRING_SERVICE,
@@ -1,6 +1,22 @@
-use crate::settings::{AppSettings, SettingsStorage, ThemeMode};
+use crate::logging::{LogLevel, Subsystem};
+use crate::session::SessionStorage;
+use crate::session::config::{Proxy, TransferLimit};
+use crate::session::expand::DomainRule;
+use crate::session::filter::FilterPreset;
+use crate::session::profile::HostProfile;
+use crate::settings::{
+    AppSettings, ScreenGuardMode, SettingsStorage, ShellExitAction, StartupView, TerminalTheme,
+    ThemeMode,
+};
+use crate::ssh::AgentIdentity;
+use crate::terminal::editor::{EditorOptions, EditorRule};
+use crate::terminal::scroll::AltScreenScroll;
+use crate::terminal::special_keys::CustomKey;
 use crate::ui::style as ui_style;
-use iced::widget::{button, column, container, row, scrollable, text, text_editor, text_input};
+use iced::widget::{
+    Column, button, column, container, pick_list, row, scrollable, slider, text, text_editor,
+    text_input,
+};
 use iced::{Alignment, Element, Length, Settings, Subscription, Theme};
 use std::fs;
 use std::path::Path;
@@ -27,6 +43,46 @@ enum SettingsTab {
     Keys,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PasteOption {
+    StripTrailingNewline,
+    ConvertCrlf,
+    CollapseLeadingWhitespace,
+    Slow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CustomKeyField {
+    Label,
+    Sequence,
+    Shortcut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterPresetField {
+    Name,
+    Patterns,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HostProfileField {
+    Pattern,
+    Color,
+    Badge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditorRuleField {
+    Extensions,
+    Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HostDomainField {
+    Tag,
+    Domain,
+}
+
 #[derive(Debug)]
 struct SettingsApp {
     activation_set: bool,
@@ -35,6 +91,9 @@ struct SettingsApp {
     tab: SettingsTab,
     parent_pid: Option<u32>,
     font_size_input: String,
+    control_port_input: String,
+    proxy_input: String,
+    proxy_error: Option<String>,
     editing_key: Option<usize>,
     key_status: Option<String>,
     adding_key: bool,
@@ -42,6 +101,22 @@ struct SettingsApp {
     adding_key_path: String,
     adding_key_type: String,
     adding_key_paste: text_editor::Content,
+    sessions: Vec<SessionChoice>,
+    agent_keys: Result<Vec<AgentIdentity>, String>,
+    bundle_status: Option<String>,
+}
+
+// A saved session offered for connecting at launch.
+#[derive(Debug, Clone, PartialEq)]
+struct SessionChoice {
+    id: String,
+    name: String,
+}
+
+impl std::fmt::Display for SessionChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +129,74 @@ enum Message {
     FontSizeInputSubmit,
     SetGpuRenderer(bool),
     SetTheme(ThemeMode),
+    SetTerminalTheme(TerminalTheme),
+    SetPasteOption(PasteOption, bool),
+    SetPauseScroll(bool),
+    SetScrollLinesPerNotch(f32),
+    SetScrollSensitivity(f32),
+    SetScrollInvert(bool),
+    SetAltScreenScroll(AltScreenScroll),
+    SetScrollArrowsPerNotch(f32),
+    SetBoldIsBright(bool),
+    SetPreconnectSessions(bool),
+    SetAutoReconnect(bool),
+    SetShareConnections(bool),
+    SetProbeSessions(bool),
+    SetLogLevel(Subsystem, LogLevel),
+    LogDirectoryChanged(String),
+    SetLogFileSize(f32),
+    SetLogFileCount(f32),
+    CopyDiagnosticBundle,
+    SetKeepaliveInterval(f32),
+    SetKeepaliveMissed(f32),
+    SetConnectTimeout(f32),
+    SetBackgroundOpacity(f32),
+    SetTerminalPadding(f32),
+    SetLineHeight(f32),
+    SetTransferLimit(TransferLimit, f32),
+    SetPipelineDepth(f32),
+    SetPrefetchDetails(bool),
+    SetRemoteClipboard(bool),
+    SetRemoteClipboardRead(bool),
+    SetAllowTerminalSharing(bool),
+    SetRemoteTrash(bool),
+    RemoteTrashDirChanged(String),
+    SetControlApi(bool),
+    ControlApiPortChanged(String),
+    SetViewerMode(bool),
+    ProxyChanged(String),
+    ProxyPasswordChanged(String),
+    SetLocalTabsInherit(bool),
+    SetCloseOnCleanExit(bool),
+    SetOfferAllKeys(bool),
+    SetScreenGuardMode(ScreenGuardMode),
+    SetScreenGuardMinutes(f32),
+    SetLocalShellExit(ShellExitAction),
+    SetStartupView(StartupView),
+    SetStartupSession(SessionChoice),
+    SetBackgroundBlur(bool),
+    SetRenderItalics(bool),
+    BoldFontChanged(String),
+    ItalicFontChanged(String),
+    AddCustomKey,
+    CustomKeyChanged(usize, CustomKeyField, String),
+    RemoveCustomKey(usize),
+    AddHostProfile,
+    HostProfileChanged(usize, HostProfileField, String),
+    RemoveHostProfile(usize),
+    AddHostDomain,
+    HostDomainChanged(usize, HostDomainField, String),
+    RemoveHostDomain(usize),
+    EditorCommandChanged(String),
+    AddEditorRule,
+    EditorRuleChanged(usize, EditorRuleField, String),
+    RemoveEditorRule(usize),
+    AddRedactPattern,
+    RedactPatternChanged(usize, String),
+    RemoveRedactPattern(usize),
+    AddFilterPreset,
+    FilterPresetChanged(usize, FilterPresetField, String),
+    RemoveFilterPreset(usize),
     AddExistingKey,
     AddKeyNameChanged(String),
     AddKeyPathChanged(String),
@@ -63,6 +206,7 @@ enum Message {
     AddKeySave,
     AddKeyCancel,
     RefreshKeys,
+    AgentKeysLoaded(Result<Vec<AgentIdentity>, String>),
     EditKeyStart(usize),
     DeleteKey(usize),
     SetDefaultKey(usize),
@@ -75,7 +219,22 @@ impl SettingsApp {
         let settings = storage.load_settings().unwrap_or_default();
         ui_style::set_dark_mode(matches!(settings.theme, ThemeMode::Dark));
         let font_size_input = format!("{}", settings.terminal_font_size.round() as i32);
+        let control_port_input = settings.control_api_port.to_string();
+        let proxy_input = settings
+            .proxy
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
         let parent_pid = read_parent_pid();
+        let sessions = SessionStorage::new()
+            .load_sessions()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|session| SessionChoice {
+                id: session.id,
+                name: session.name,
+            })
+            .collect();
         let app = Self {
             activation_set: false,
             storage,
@@ -83,6 +242,9 @@ impl SettingsApp {
             tab: SettingsTab::Terminal,
             parent_pid,
             font_size_input,
+            control_port_input,
+            proxy_input,
+            proxy_error: None,
             editing_key: None,
             key_status: None,
             adding_key: false,
@@ -90,6 +252,9 @@ impl SettingsApp {
             adding_key_path: String::new(),
             adding_key_type: String::new(),
             adding_key_paste: text_editor::Content::new(),
+            sessions,
+            agent_keys: Ok(Vec::new()),
+            bundle_status: None,
         };
         (app, iced::Task::done(Message::Init))
     }
@@ -103,6 +268,9 @@ impl SettingsApp {
         match message {
             Message::SelectTab(tab) => {
                 self.tab = tab;
+                if matches!(tab, SettingsTab::Keys) {
+                    return load_agent_keys();
+                }
             }
             Message::FontSizeDecrease => {
                 let next = (self.settings.terminal_font_size - 1.0).max(8.0);
@@ -136,6 +304,465 @@ impl SettingsApp {
                     let _ = self.storage.save_settings(&self.settings);
                 }
             }
+            Message::SetTerminalTheme(theme) => {
+                if self.settings.terminal_theme != theme {
+                    self.settings.terminal_theme = theme;
+                    self.persist_settings();
+                }
+            }
+            Message::SetPasteOption(option, enabled) => {
+                let paste = &mut self.settings.paste;
+                let field = match option {
+                    PasteOption::StripTrailingNewline => &mut paste.strip_trailing_newline,
+                    PasteOption::ConvertCrlf => &mut paste.convert_crlf,
+                    PasteOption::CollapseLeadingWhitespace => {
+                        &mut paste.collapse_leading_whitespace
+                    }
+                    PasteOption::Slow => &mut paste.slow,
+                };
+                if *field != enabled {
+                    *field = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetPauseScroll(enabled) => {
+                if self.settings.pause_scroll_in_history != enabled {
+                    self.settings.pause_scroll_in_history = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetScrollLinesPerNotch(lines) => {
+                self.settings.scroll.lines_per_notch = lines.round();
+                self.persist_settings();
+            }
+            Message::SetScrollSensitivity(sensitivity) => {
+                self.settings.scroll.sensitivity = sensitivity;
+                self.persist_settings();
+            }
+            Message::SetScrollInvert(enabled) => {
+                if self.settings.scroll.invert != enabled {
+                    self.settings.scroll.invert = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetAltScreenScroll(behavior) => {
+                if self.settings.scroll.alternate_screen != behavior {
+                    self.settings.scroll.alternate_screen = behavior;
+                    self.persist_settings();
+                }
+            }
+            Message::SetScrollArrowsPerNotch(arrows) => {
+                self.settings.scroll.arrows_per_notch = arrows.round();
+                self.persist_settings();
+            }
+            Message::SetBoldIsBright(enabled) => {
+                if self.settings.bold_is_bright != enabled {
+                    self.settings.bold_is_bright = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetKeepaliveInterval(seconds) => {
+                let seconds = seconds.round() as u32;
+                if self.settings.keepalive.interval_secs != seconds {
+                    self.settings.keepalive.interval_secs = seconds;
+                    self.persist_settings();
+                }
+            }
+            Message::SetKeepaliveMissed(count) => {
+                let count = count.round() as u32;
+                if self.settings.keepalive.max_missed != count {
+                    self.settings.keepalive.max_missed = count;
+                    self.persist_settings();
+                }
+            }
+            Message::SetConnectTimeout(seconds) => {
+                let seconds = seconds.round() as u32;
+                if self.settings.connect_timeout_secs != seconds {
+                    self.settings.connect_timeout_secs = seconds;
+                    self.persist_settings();
+                }
+            }
+            Message::SetPreconnectSessions(enabled) => {
+                if self.settings.preconnect_sessions != enabled {
+                    self.settings.preconnect_sessions = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetAutoReconnect(enabled) => {
+                if self.settings.auto_reconnect != enabled {
+                    self.settings.auto_reconnect = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetProbeSessions(enabled) => {
+                if self.settings.probe_sessions != enabled {
+                    self.settings.probe_sessions = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetShareConnections(enabled) => {
+                if self.settings.share_connections != enabled {
+                    self.settings.share_connections = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetLogLevel(subsystem, level) => {
+                self.settings.logging.set_level(subsystem, level);
+                self.persist_settings();
+            }
+            Message::LogDirectoryChanged(directory) => {
+                self.settings.logging.directory = directory;
+                self.persist_settings();
+            }
+            Message::SetLogFileSize(megabytes) => {
+                self.settings.logging.max_file_mb = megabytes.round() as u32;
+                self.persist_settings();
+            }
+            Message::SetLogFileCount(count) => {
+                self.settings.logging.max_files = count.round() as u32;
+                self.persist_settings();
+            }
+            Message::CopyDiagnosticBundle => {
+                let name = format!(
+                    "rivett-diagnostics-{}.zip",
+                    chrono::Local::now().format("%Y%m%d-%H%M%S")
+                );
+                let path = dirs::download_dir()
+                    .or_else(dirs::home_dir)
+                    .unwrap_or_default()
+                    .join(name);
+                let sessions = SessionStorage::new().load_sessions().unwrap_or_default();
+                match crate::logging::write_bundle(&path, &self.settings, &sessions) {
+                    Ok(()) => {
+                        let path = path.display().to_string();
+                        self.bundle_status = Some(format!("Saved {}; path copied", path));
+                        return iced::clipboard::write(path);
+                    }
+                    Err(e) => self.bundle_status = Some(e),
+                }
+            }
+            Message::SetBackgroundOpacity(opacity) => {
+                self.settings.background_opacity = opacity;
+                self.persist_settings();
+            }
+            Message::SetTerminalPadding(padding) => {
+                self.settings.terminal_padding = padding.round();
+                self.persist_settings();
+            }
+            Message::SetLineHeight(multiplier) => {
+                self.settings.line_height = multiplier;
+                self.persist_settings();
+            }
+            Message::SetTransferLimit(limit, value) => {
+                let value = value.round() as usize;
+                if self.settings.transfer_limits.get(limit) != value {
+                    self.settings.transfer_limits.set(limit, value);
+                    self.persist_settings();
+                }
+            }
+            Message::SetPipelineDepth(value) => {
+                let value = value.round() as usize;
+                if self.settings.sftp_pipeline_depth != value {
+                    self.settings.sftp_pipeline_depth = value;
+                    self.persist_settings();
+                }
+            }
+            Message::SetPrefetchDetails(enabled) => {
+                if self.settings.sftp_prefetch_details != enabled {
+                    self.settings.sftp_prefetch_details = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetRemoteClipboard(enabled) => {
+                if self.settings.remote_clipboard != enabled {
+                    self.settings.remote_clipboard = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetRemoteClipboardRead(enabled) => {
+                if self.settings.remote_clipboard_read != enabled {
+                    self.settings.remote_clipboard_read = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetAllowTerminalSharing(enabled) => {
+                if self.settings.allow_terminal_sharing != enabled {
+                    self.settings.allow_terminal_sharing = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetRemoteTrash(enabled) => {
+                if self.settings.sftp_remote_trash != enabled {
+                    self.settings.sftp_remote_trash = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::RemoteTrashDirChanged(value) => {
+                self.settings.sftp_trash_dir = value;
+                self.persist_settings();
+            }
+            Message::SetControlApi(enabled) => {
+                if self.settings.control_api != enabled {
+                    self.settings.control_api = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetViewerMode(enabled) => {
+                // Only turned off by editing the settings file, so anyone at
+                // the machine can't undo it from here.
+                if enabled && !self.settings.viewer_mode {
+                    self.settings.viewer_mode = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::ProxyChanged(value) => {
+                let proxy = match value.trim() {
+                    "" => Ok(None),
+                    url => Proxy::parse(url).map(Some),
+                };
+                match proxy {
+                    Ok(mut proxy) => {
+                        // The password is typed separately; keep it.
+                        if let Some(proxy) = &mut proxy
+                            && proxy.password.is_none()
+                        {
+                            proxy.password = self
+                                .settings
+                                .proxy
+                                .as_ref()
+                                .and_then(|old| old.password.clone());
+                        }
+                        if self.settings.proxy != proxy {
+                            self.settings.proxy = proxy;
+                            self.persist_settings();
+                        }
+                        self.proxy_error = None;
+                    }
+                    Err(e) => self.proxy_error = Some(e),
+                }
+                self.proxy_input = value;
+            }
+            Message::ProxyPasswordChanged(value) => {
+                if let Some(proxy) = &mut self.settings.proxy {
+                    proxy.password = Some(value).filter(|password| !password.is_empty());
+                    self.persist_settings();
+                }
+            }
+            Message::ControlApiPortChanged(value) => {
+                if let Ok(port) = value.trim().parse::<u16>()
+                    && port != 0
+                    && port != self.settings.control_api_port
+                {
+                    self.settings.control_api_port = port;
+                    self.persist_settings();
+                }
+                self.control_port_input = value;
+            }
+            Message::SetLocalTabsInherit(enabled) => {
+                if self.settings.local_tabs_inherit != enabled {
+                    self.settings.local_tabs_inherit = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetCloseOnCleanExit(enabled) => {
+                if self.settings.close_on_clean_exit != enabled {
+                    self.settings.close_on_clean_exit = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetOfferAllKeys(enabled) => {
+                if self.settings.offer_all_keys != enabled {
+                    self.settings.offer_all_keys = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetScreenGuardMode(mode) => {
+                if self.settings.screen_guard.mode != mode {
+                    self.settings.screen_guard.mode = mode;
+                    self.persist_settings();
+                }
+            }
+            Message::SetScreenGuardMinutes(minutes) => {
+                let minutes = minutes.round() as u32;
+                if self.settings.screen_guard.minutes != minutes {
+                    self.settings.screen_guard.minutes = minutes;
+                    self.persist_settings();
+                }
+            }
+            Message::SetStartupView(view) => {
+                if self.settings.startup_view != view {
+                    self.settings.startup_view = view;
+                    self.persist_settings();
+                }
+            }
+            Message::SetStartupSession(choice) => {
+                self.settings.startup_session = Some(choice.id);
+                self.persist_settings();
+            }
+            Message::SetLocalShellExit(action) => {
+                if self.settings.local_shell_exit != action {
+                    self.settings.local_shell_exit = action;
+                    self.persist_settings();
+                }
+            }
+            Message::SetBackgroundBlur(enabled) => {
+                if self.settings.background_blur != enabled {
+                    self.settings.background_blur = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::SetRenderItalics(enabled) => {
+                if self.settings.render_italics != enabled {
+                    self.settings.render_italics = enabled;
+                    self.persist_settings();
+                }
+            }
+            Message::BoldFontChanged(value) => {
+                self.settings.bold_font = Some(value).filter(|value| !value.trim().is_empty());
+                self.persist_settings();
+            }
+            Message::ItalicFontChanged(value) => {
+                self.settings.italic_font = Some(value).filter(|value| !value.trim().is_empty());
+                self.persist_settings();
+            }
+            Message::AddCustomKey => {
+                self.settings.custom_keys.push(CustomKey {
+                    label: "New key".to_string(),
+                    sequence: String::new(),
+                    shortcut: None,
+                });
+                self.persist_settings();
+            }
+            Message::CustomKeyChanged(index, field, value) => {
+                if let Some(key) = self.settings.custom_keys.get_mut(index) {
+                    match field {
+                        CustomKeyField::Label => key.label = value,
+                        CustomKeyField::Sequence => key.sequence = value,
+                        CustomKeyField::Shortcut => {
+                            key.shortcut = Some(value).filter(|value| !value.trim().is_empty());
+                        }
+                    }
+                    self.persist_settings();
+                }
+            }
+            Message::RemoveCustomKey(index) => {
+                if index < self.settings.custom_keys.len() {
+                    self.settings.custom_keys.remove(index);
+                    self.persist_settings();
+                }
+            }
+            Message::AddHostProfile => {
+                self.settings.host_profiles.push(HostProfile {
+                    pattern: "*prod*".to_string(),
+                    color: Some("#d03030".to_string()),
+                    badge: Some("PRODUCTION".to_string()),
+                });
+                self.persist_settings();
+            }
+            Message::HostProfileChanged(index, field, value) => {
+                if let Some(profile) = self.settings.host_profiles.get_mut(index) {
+                    let optional = Some(value.clone()).filter(|value| !value.trim().is_empty());
+                    match field {
+                        HostProfileField::Pattern => profile.pattern = value,
+                        HostProfileField::Color => profile.color = optional,
+                        HostProfileField::Badge => profile.badge = optional,
+                    }
+                    self.persist_settings();
+                }
+            }
+            Message::RemoveHostProfile(index) => {
+                if index < self.settings.host_profiles.len() {
+                    self.settings.host_profiles.remove(index);
+                    self.persist_settings();
+                }
+            }
+            Message::AddHostDomain => {
+                self.settings.host_domains.push(DomainRule {
+                    tag: String::new(),
+                    domain: String::new(),
+                });
+                self.persist_settings();
+            }
+            Message::HostDomainChanged(index, field, value) => {
+                if let Some(rule) = self.settings.host_domains.get_mut(index) {
+                    match field {
+                        HostDomainField::Tag => rule.tag = value,
+                        HostDomainField::Domain => rule.domain = value,
+                    }
+                    self.persist_settings();
+                }
+            }
+            Message::RemoveHostDomain(index) => {
+                if index < self.settings.host_domains.len() {
+                    self.settings.host_domains.remove(index);
+                    self.persist_settings();
+                }
+            }
+            Message::EditorCommandChanged(command) => {
+                self.settings.editor.command = command;
+                self.persist_settings();
+            }
+            Message::AddEditorRule => {
+                self.settings.editor.rules.push(EditorRule {
+                    extensions: String::new(),
+                    command: String::new(),
+                });
+                self.persist_settings();
+            }
+            Message::EditorRuleChanged(index, field, value) => {
+                if let Some(rule) = self.settings.editor.rules.get_mut(index) {
+                    match field {
+                        EditorRuleField::Extensions => rule.extensions = value,
+                        EditorRuleField::Command => rule.command = value,
+                    }
+                    self.persist_settings();
+                }
+            }
+            Message::RemoveEditorRule(index) => {
+                if index < self.settings.editor.rules.len() {
+                    self.settings.editor.rules.remove(index);
+                    self.persist_settings();
+                }
+            }
+            Message::AddRedactPattern => {
+                self.settings.redact_patterns.push(String::new());
+                self.persist_settings();
+            }
+            Message::RedactPatternChanged(index, value) => {
+                if let Some(pattern) = self.settings.redact_patterns.get_mut(index) {
+                    *pattern = value;
+                    self.persist_settings();
+                }
+            }
+            Message::RemoveRedactPattern(index) => {
+                if index < self.settings.redact_patterns.len() {
+                    self.settings.redact_patterns.remove(index);
+                    self.persist_settings();
+                }
+            }
+            Message::AddFilterPreset => {
+                let number = self.settings.sftp_filter_presets.len() + 1;
+                self.settings.sftp_filter_presets.push(FilterPreset {
+                    name: format!("Filter {}", number),
+                    patterns: String::new(),
+                });
+                self.persist_settings();
+            }
+            Message::FilterPresetChanged(index, field, value) => {
+                if let Some(preset) = self.settings.sftp_filter_presets.get_mut(index) {
+                    match field {
+                        FilterPresetField::Name => preset.name = value,
+                        FilterPresetField::Patterns => preset.patterns = value,
+                    }
+                    self.persist_settings();
+                }
+            }
+            Message::RemoveFilterPreset(index) => {
+                if index < self.settings.sftp_filter_presets.len() {
+                    self.settings.sftp_filter_presets.remove(index);
+                    self.persist_settings();
+                }
+            }
             Message::FontSizeInputSubmit => {
                 if let Ok(parsed) = self.font_size_input.trim().parse::<f32>() {
                     let clamped = parsed.clamp(8.0, 24.0).round();
@@ -314,7 +941,10 @@ impl SettingsApp {
                 self.adding_key_type.clear();
                 self.adding_key_paste = text_editor::Content::new();
             }
-            Message::RefreshKeys => {}
+            Message::RefreshKeys => return load_agent_keys(),
+            Message::AgentKeysLoaded(result) => {
+                self.agent_keys = result;
+            }
             Message::EditKeyStart(index) => {
                 if let Some(entry) = self.settings.ssh_keys.get(index) {
                     self.editing_key = Some(index);
@@ -386,7 +1016,7 @@ impl SettingsApp {
             SettingsTab::General => {
                 let header = column![
                     text("General").size(14),
-                    text("Customize the app appearance.")
+                    text("Customize the app appearance and startup.")
                         .size(13)
                         .style(ui_style::muted_text),
                 ]
@@ -413,10 +1043,157 @@ impl SettingsApp {
                 .align_y(Alignment::Center)
                 .spacing(8);
 
-                let panel = container(column![container(theme_row).padding([8, 10])])
-                    .style(ui_style::panel);
+                let panel = container(
+                    column![
+                        container(theme_row).padding([8, 10]),
+                        startup_row(self.settings.startup_view),
+                        (self.settings.startup_view == StartupView::ConnectSession).then(|| {
+                            let selected = self.sessions.iter().find(|choice| {
+                                self.settings.startup_session.as_ref() == Some(&choice.id)
+                            });
+                            container(
+                                row![
+                                    text("Session").size(13),
+                                    container("").width(Length::Fill),
+                                    pick_list(
+                                        self.sessions.as_slice(),
+                                        selected.cloned(),
+                                        Message::SetStartupSession,
+                                    )
+                                    .placeholder("Choose a session")
+                                    .text_size(12),
+                                ]
+                                .align_y(Alignment::Center)
+                                .spacing(8),
+                            )
+                            .padding([8, 10])
+                        }),
+                        toggle_row(
+                            "Pre-connect highlighted sessions",
+                            self.settings.preconnect_sessions,
+                            Message::SetPreconnectSessions,
+                        ),
+                        toggle_row(
+                            "Reconnect dropped sessions automatically",
+                            self.settings.auto_reconnect,
+                            Message::SetAutoReconnect,
+                        ),
+                        toggle_row(
+                            "Share one connection per host between tabs",
+                            self.settings.share_connections,
+                            Message::SetShareConnections,
+                        ),
+                        toggle_row(
+                            "Show which saved hosts are reachable",
+                            self.settings.probe_sessions,
+                            Message::SetProbeSessions,
+                        ),
+                        slider_row(
+                            "Connect timeout",
+                            5.0..=120.0,
+                            5.0,
+                            self.settings.connect_timeout_secs as f32,
+                            format!("{}s", self.settings.connect_timeout_secs),
+                            Message::SetConnectTimeout,
+                        ),
+                        slider_row(
+                            "Keepalive interval",
+                            0.0..=300.0,
+                            5.0,
+                            self.settings.keepalive.interval_secs as f32,
+                            match self.settings.keepalive.interval_secs {
+                                0 => "Off".to_string(),
+                                seconds => format!("{}s", seconds),
+                            },
+                            Message::SetKeepaliveInterval,
+                        ),
+                        slider_row(
+                            "Missed keepalives before disconnect",
+                            1.0..=10.0,
+                            1.0,
+                            self.settings.keepalive.max_missed as f32,
+                            self.settings.keepalive.max_missed.to_string(),
+                            Message::SetKeepaliveMissed,
+                        ),
+                    ]
+                    .spacing(6),
+                )
+                .style(ui_style::panel);
 
-                column![header, panel].spacing(16)
+                let limits = self.settings.transfer_limits;
+                let transfers_panel = container(
+                    TransferLimit::ALL
+                        .into_iter()
+                        .fold(Column::new().spacing(6), |rows, limit| {
+                            rows.push(slider_row(
+                                limit.label(),
+                                1.0..=8.0,
+                                1.0,
+                                limits.get(limit) as f32,
+                                limits.get(limit).to_string(),
+                                move |value| Message::SetTransferLimit(limit, value),
+                            ))
+                        })
+                        .push(slider_row(
+                            "Requests in flight",
+                            1.0..=64.0,
+                            1.0,
+                            self.settings.sftp_pipeline_depth as f32,
+                            self.settings.sftp_pipeline_depth.to_string(),
+                            Message::SetPipelineDepth,
+                        ))
+                        .push(toggle_row(
+                            "Resolve symlinks in the background",
+                            self.settings.sftp_prefetch_details,
+                            Message::SetPrefetchDetails,
+                        ))
+                        .push(toggle_row(
+                            "Move remote deletes to a trash folder",
+                            self.settings.sftp_remote_trash,
+                            Message::SetRemoteTrash,
+                        ))
+                        .push(
+                            row![
+                                text("Trash folder").size(13),
+                                container("").width(Length::Fill),
+                                text_input("~/.rivett-trash", &self.settings.sftp_trash_dir)
+                                    .on_input(Message::RemoteTrashDirChanged)
+                                    .padding([4, 6])
+                                    .size(13)
+                                    .style(ui_style::dialog_input)
+                                    .width(Length::Fixed(200.0)),
+                            ]
+                            .spacing(8)
+                            .padding([0, 10])
+                            .align_y(Alignment::Center),
+                        ),
+                )
+                .style(ui_style::panel);
+
+                column![
+                    header,
+                    scrollable(
+                        column![
+                            panel,
+                            text("Proxy").size(13).style(ui_style::muted_text),
+                            proxy_panel(
+                                &self.proxy_input,
+                                self.settings.proxy.as_ref(),
+                                self.proxy_error.as_deref(),
+                            ),
+                            text("File transfers").size(13).style(ui_style::muted_text),
+                            transfers_panel,
+                            text("File filters").size(13).style(ui_style::muted_text),
+                            filter_presets_panel(&self.settings.sftp_filter_presets),
+                            text("Logging").size(13).style(ui_style::muted_text),
+                            logging_panel(&self.settings.logging, self.bundle_status.as_deref()),
+                        ]
+                        .spacing(10),
+                    )
+                    .style(ui_style::scrollable_style)
+                    .height(Length::Fill),
+                ]
+                .spacing(16)
             }
             SettingsTab::Terminal => {
                 let header = column![
@@ -454,6 +1231,7 @@ impl SettingsApp {
                 let panel = container(
                     column![
                         container(font_row).padding([8, 10]),
+                        terminal_theme_row(self.settings.terminal_theme),
                         container(
                             row![
                                 text("GPU Renderer").size(13),
@@ -471,12 +1249,195 @@ impl SettingsApp {
                             .spacing(8),
                         )
                         .padding([8, 10]),
+                        toggle_row(
+                            "Pause scroll in history",
+                            self.settings.pause_scroll_in_history,
+                            Message::SetPauseScroll,
+                        ),
+                        slider_row(
+                            "Lines per wheel notch",
+                            1.0..=10.0,
+                            1.0,
+                            self.settings.scroll.lines_per_notch,
+                            format!("{}", self.settings.scroll.lines_per_notch as i32),
+                            Message::SetScrollLinesPerNotch,
+                        ),
+                        slider_row(
+                            "Trackpad sensitivity",
+                            0.25..=4.0,
+                            0.25,
+                            self.settings.scroll.sensitivity,
+                            format!("{:.2}x", self.settings.scroll.sensitivity),
+                            Message::SetScrollSensitivity,
+                        ),
+                        toggle_row(
+                            "Natural scrolling",
+                            self.settings.scroll.invert,
+                            Message::SetScrollInvert,
+                        ),
+                        alt_screen_scroll_row(self.settings.scroll.alternate_screen),
+                        (self.settings.scroll.alternate_screen == AltScreenScroll::Arrows).then(
+                            || {
+                                slider_row(
+                                    "Arrow keys per notch",
+                                    1.0..=10.0,
+                                    1.0,
+                                    self.settings.scroll.arrows_per_notch,
+                                    format!("{}", self.settings.scroll.arrows_per_notch as i32),
+                                    Message::SetScrollArrowsPerNotch,
+                                )
+                            },
+                        ),
+                        slider_row(
+                            "Background Opacity",
+                            0.3..=1.0,
+                            0.05,
+                            self.settings.background_opacity,
+                            format!(
+                                "{}%",
+                                (self.settings.background_opacity * 100.0).round() as i32
+                            ),
+                            Message::SetBackgroundOpacity,
+                        ),
+                        cfg!(target_os = "macos").then(|| {
+                            toggle_row(
+                                "Background Blur",
+                                self.settings.background_blur,
+                                Message::SetBackgroundBlur,
+                            )
+                        }),
+                        slider_row(
+                            "Padding",
+                            0.0..=32.0,
+                            1.0,
+                            self.settings.terminal_padding,
+                            format!("{} px", self.settings.terminal_padding.round() as i32),
+                            Message::SetTerminalPadding,
+                        ),
+                        slider_row(
+                            "Line Height",
+                            1.0..=2.0,
+                            0.05,
+                            self.settings.line_height,
+                            format!("{:.2}x", self.settings.line_height),
+                            Message::SetLineHeight,
+                        ),
+                        toggle_row(
+                            "Bold as bright",
+                            self.settings.bold_is_bright,
+                            Message::SetBoldIsBright,
+                        ),
+                        toggle_row(
+                            "Render italics",
+                            self.settings.render_italics,
+                            Message::SetRenderItalics,
+                        ),
+                        font_face_row(
+                            "Bold font",
+                            self.settings.bold_font.as_deref(),
+                            Message::BoldFontChanged,
+                        ),
+                        font_face_row(
+                            "Italic font",
+                            self.settings.italic_font.as_deref(),
+                            Message::ItalicFontChanged,
+                        ),
                     ]
                     .spacing(6),
                 )
                 .style(ui_style::panel);
 
-                column![header, panel].spacing(16)
+                let paste = &self.settings.paste;
+                let paste_panel = container(
+                    column![
+                        toggle_row(
+                            "Strip trailing newline",
+                            paste.strip_trailing_newline,
+                            |on| Message::SetPasteOption(PasteOption::StripTrailingNewline, on)
+                        ),
+                        toggle_row("Convert CRLF to LF", paste.convert_crlf, |on| {
+                            Message::SetPasteOption(PasteOption::ConvertCrlf, on)
+                        }),
+                        toggle_row(
+                            "Collapse leading whitespace",
+                            paste.collapse_leading_whitespace,
+                            |on| Message::SetPasteOption(
+                                PasteOption::CollapseLeadingWhitespace,
+                                on
+                            )
+                        ),
+                        toggle_row("Paste slowly", paste.slow, |on| Message::SetPasteOption(
+                            PasteOption::Slow,
+                            on
+                        )),
+                        toggle_row(
+                            "Remote clipboard (OSC 52, pbcopy/pbpaste)",
+                            self.settings.remote_clipboard,
+                            Message::SetRemoteClipboard,
+                        ),
+                        toggle_row(
+                            "Let remote programs read the clipboard (pbpaste)",
+                            self.settings.remote_clipboard_read,
+                            Message::SetRemoteClipboardRead,
+                        ),
+                        toggle_row(
+                            "Allow sharing tabs read-only on the network (unencrypted)",
+                            self.settings.allow_terminal_sharing,
+                            Message::SetAllowTerminalSharing,
+                        ),
+                        toggle_row(
+                            "New local tabs inherit directory and variables (OSC 7, SetUserVar)",
+                            self.settings.local_tabs_inherit,
+                            Message::SetLocalTabsInherit,
+                        ),
+                        shell_exit_row(self.settings.local_shell_exit),
+                        toggle_row(
+                            "Close SSH tabs when the remote shell exits cleanly",
+                            self.settings.close_on_clean_exit,
+                            Message::SetCloseOnCleanExit,
+                        ),
+                        screen_guard_row(self.settings.screen_guard.mode),
+                        slider_row(
+                            "Hide after",
+                            1.0..=60.0,
+                            1.0,
+                            self.settings.screen_guard.minutes as f32,
+                            format!("{}m", self.settings.screen_guard.minutes),
+                            Message::SetScreenGuardMinutes,
+                        ),
+                    ]
+                    .spacing(6),
+                )
+                .style(ui_style::panel);
+
+                column![
+                    header,
+                    scrollable(
+                        column![
+                            panel,
+                            text("Paste").size(13).style(ui_style::muted_text),
+                            paste_panel,
+                            text("Custom keys").size(13).style(ui_style::muted_text),
+                            custom_keys_panel(&self.settings.custom_keys),
+                            text("Editor").size(13).style(ui_style::muted_text),
+                            editor_panel(&self.settings.editor),
+                            text("Host profiles").size(13).style(ui_style::muted_text),
+                            host_profiles_panel(&self.settings.host_profiles),
+                            text("Host domains").size(13).style(ui_style::muted_text),
+                            host_domains_panel(&self.settings.host_domains),
+                            text("Redaction").size(13).style(ui_style::muted_text),
+                            redact_patterns_panel(&self.settings.redact_patterns),
+                            text("Control API").size(13).style(ui_style::muted_text),
+                            control_api_panel(self.settings.control_api, &self.control_port_input),
+                            text("Viewer mode").size(13).style(ui_style::muted_text),
+                            viewer_mode_panel(self.settings.viewer_mode),
+                        ]
+                        .spacing(10),
+                    )
+                    .style(ui_style::scrollable_style)
+                    .height(Length::Fill),
+                ]
+                .spacing(16)
             }
             SettingsTab::Keys => {
                 let header = column![
@@ -670,6 +1631,7 @@ impl SettingsApp {
                     }
                     rows.spacing(4)
                 };
+                let list_rows = list_rows.push(agent_key_rows(&self.agent_keys));
 
                 let list = container(
                     column![
@@ -707,10 +1669,18 @@ impl SettingsApp {
                 .spacing(10)
                 .align_y(Alignment::Center);
 
+                // Servers drop the connection after a few refused keys, so
+                // by default only the session's own are offered.
+                let offer_all = toggle_row(
+                    "Offer every saved key when a session's own keys are refused",
+                    self.settings.offer_all_keys,
+                    Message::SetOfferAllKeys,
+                );
+
                 let mut content = if self.adding_key {
                     column![header, add_form].spacing(8)
                 } else {
-                    column![header, list, actions].spacing(16)
+                    column![header, list, actions, offer_all].spacing(16)
                 };
                 if let Some(line) = status_line {
                     content = content.push(line);
@@ -758,6 +1728,63 @@ impl SettingsApp {
     }
 }
 
+/// Keys offered by the running ssh-agent, read-only; sessions use them with
+/// the "SSH agent" sign-in method.
+fn agent_key_rows(keys: &Result<Vec<AgentIdentity>, String>) -> Element<'_, Message> {
+    let mut rows = column![text("In ssh-agent").size(12).style(ui_style::muted_text)]
+        .spacing(4)
+        .padding(iced::Padding {
+            top: 10.0,
+            ..iced::Padding::ZERO
+        });
+    match keys {
+        Err(e) => rows = rows.push(text(e).size(12).style(ui_style::muted_text)),
+        Ok(keys) if keys.is_empty() => {
+            rows = rows.push(
+                text("The agent holds no keys.")
+                    .size(12)
+                    .style(ui_style::muted_text),
+            )
+        }
+        Ok(keys) => {
+            for key in keys {
+                let name = if key.comment.is_empty() {
+                    "(no comment)"
+                } else {
+                    key.comment.as_str()
+                };
+                rows = rows.push(
+                    row![
+                        text(name).size(13).width(Length::FillPortion(4)),
+                        text(&key.algorithm)
+                            .size(12)
+                            .style(ui_style::muted_text)
+                            .width(Length::FillPortion(2)),
+                        text(short_fingerprint(&key.fingerprint))
+                            .size(12)
+                            .style(ui_style::muted_text)
+                            .width(Length::FillPortion(3)),
+                        text("Agent")
+                            .size(12)
+                            .style(ui_style::muted_text)
+                            .width(Length::Fixed(70.0)),
+                        container("").width(Length::Fixed(120.0)),
+                    ]
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                );
+            }
+        }
+    }
+    rows.into()
+}
+
+fn load_agent_keys() -> iced::Task<Message> {
+    iced::Task::perform(crate::ssh::agent_identities(), |result| {
+        Message::AgentKeysLoaded(result.map_err(|e| e.to_string()))
+    })
+}
+
 fn short_fingerprint(value: &str) -> String {
     let trimmed = value.trim();
     if trimmed.len() <= 14 {
@@ -836,6 +1863,695 @@ fn tab_button(label: &str, active: bool, tab: SettingsTab) -> iced::Element<'_,
         .into()
 }
 
+fn toggle_row(
+    label: &str,
+    enabled: bool,
+    on_change: impl Fn(bool) -> Message,
+) -> Element<'_, Message> {
+    container(
+        row![
+            text(label).size(13),
+            container("").width(Length::Fill),
+            button(text("On").size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(enabled))
+                .on_press(on_change(true)),
+            button(text("Off").size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(!enabled))
+                .on_press(on_change(false)),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn startup_row<'a>(selected: StartupView) -> Element<'a, Message> {
+    let buttons = StartupView::ALL.into_iter().map(|view| {
+        button(text(view.label()).size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(view == selected))
+            .on_press(Message::SetStartupView(view))
+            .into()
+    });
+    container(
+        row![
+            text("On launch").size(13),
+            container("").width(Length::Fill),
+        ]
+        .extend(buttons)
+        .align_y(Alignment::Center)
+        .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn screen_guard_row<'a>(selected: ScreenGuardMode) -> Element<'a, Message> {
+    let buttons = ScreenGuardMode::ALL.into_iter().map(|mode| {
+        button(text(mode.label()).size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(mode == selected))
+            .on_press(Message::SetScreenGuardMode(mode))
+            .into()
+    });
+    container(
+        row![
+            text("When the terminal is idle").size(13),
+            container("").width(Length::Fill),
+        ]
+        .extend(buttons)
+        .align_y(Alignment::Center)
+        .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn shell_exit_row<'a>(selected: ShellExitAction) -> Element<'a, Message> {
+    let buttons = ShellExitAction::ALL.into_iter().map(|action| {
+        button(text(action.label()).size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(action == selected))
+            .on_press(Message::SetLocalShellExit(action))
+            .into()
+    });
+    container(
+        row![
+            text("When a local shell exits").size(13),
+            container("").width(Length::Fill),
+        ]
+        .extend(buttons)
+        .align_y(Alignment::Center)
+        .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn terminal_theme_row<'a>(selected: TerminalTheme) -> Element<'a, Message> {
+    let buttons = TerminalTheme::ALL.into_iter().map(|theme| {
+        button(text(theme.label()).size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(theme == selected))
+            .on_press(Message::SetTerminalTheme(theme))
+            .into()
+    });
+    container(
+        row![text("Colors").size(13), container("").width(Length::Fill),]
+            .extend(buttons)
+            .align_y(Alignment::Center)
+            .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn alt_screen_scroll_row<'a>(selected: AltScreenScroll) -> Element<'a, Message> {
+    let buttons = AltScreenScroll::ALL.into_iter().map(|behavior| {
+        button(text(behavior.label()).size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(behavior == selected))
+            .on_press(Message::SetAltScreenScroll(behavior))
+            .into()
+    });
+    container(
+        row![
+            text("Wheel in full-screen apps").size(13),
+            container("").width(Length::Fill),
+        ]
+        .extend(buttons)
+        .align_y(Alignment::Center)
+        .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn slider_row<'a>(
+    label: &'a str,
+    range: std::ops::RangeInclusive<f32>,
+    step: f32,
+    value: f32,
+    value_label: String,
+    on_change: impl Fn(f32) -> Message + 'a,
+) -> Element<'a, Message> {
+    container(
+        row![
+            text(label).size(13),
+            container("").width(Length::Fill),
+            slider(range, value, on_change)
+                .step(step)
+                .width(Length::Fixed(160.0)),
+            text(value_label).size(12).width(Length::Fixed(48.0)),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn font_face_row<'a>(
+    label: &'a str,
+    value: Option<&'a str>,
+    on_change: impl Fn(String) -> Message + 'a,
+) -> Element<'a, Message> {
+    container(
+        row![
+            text(label).size(13),
+            container("").width(Length::Fill),
+            text_input("Same as regular", value.unwrap_or(""))
+                .on_input(on_change)
+                .padding([4, 6])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fixed(200.0)),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(8),
+    )
+    .padding([8, 10])
+    .into()
+}
+
+fn custom_keys_panel(keys: &[CustomKey]) -> Element<'_, Message> {
+    let mut rows = column![
+        text(
+            "Sequences accept \\e, \\r, \\n, \\t, \\xNN and ^C. Shortcuts look like ctrl+shift+f1."
+        )
+        .size(12)
+        .style(ui_style::muted_text)
+    ]
+    .spacing(6);
+
+    for (index, key) in keys.iter().enumerate() {
+        rows = rows.push(
+            row![
+                text_input("Label", &key.label)
+                    .on_input(move |value| {
+                        Message::CustomKeyChanged(index, CustomKeyField::Label, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(2)),
+                text_input("Sequence", &key.sequence)
+                    .on_input(move |value| {
+                        Message::CustomKeyChanged(index, CustomKeyField::Sequence, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(3)),
+                text_input("Shortcut", key.shortcut.as_deref().unwrap_or(""))
+                    .on_input(move |value| {
+                        Message::CustomKeyChanged(index, CustomKeyField::Shortcut, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(2)),
+                button(text("✕").size(12))
+                    .padding([4, 8])
+                    .style(ui_style::icon_button)
+                    .on_press(Message::RemoveCustomKey(index)),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    rows = rows.push(
+        button(text("Add Key").size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(false))
+            .on_press(Message::AddCustomKey),
+    );
+
+    container(rows)
+        .padding([8, 10])
+        .style(ui_style::panel)
+        .into()
+}
+
+fn host_profiles_panel(profiles: &[HostProfile]) -> Element<'_, Message> {
+    let mut rows = column![
+        text("Tint and label tabs by hostname. Patterns use * and ?; the first match wins.")
+            .size(12)
+            .style(ui_style::muted_text)
+    ]
+    .spacing(6);
+
+    for (index, profile) in profiles.iter().enumerate() {
+        rows = rows.push(
+            row![
+                text_input("Host pattern", &profile.pattern)
+                    .on_input(move |value| {
+                        Message::HostProfileChanged(index, HostProfileField::Pattern, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(3)),
+                text_input("#rrggbb", profile.color.as_deref().unwrap_or(""))
+                    .on_input(move |value| {
+                        Message::HostProfileChanged(index, HostProfileField::Color, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(2)),
+                text_input("Badge", profile.badge.as_deref().unwrap_or(""))
+                    .on_input(move |value| {
+                        Message::HostProfileChanged(index, HostProfileField::Badge, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(3)),
+                button(text("✕").size(12))
+                    .padding([4, 8])
+                    .style(ui_style::icon_button)
+                    .on_press(Message::RemoveHostProfile(index)),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    rows = rows.push(
+        button(text("Add Profile").size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(false))
+            .on_press(Message::AddHostProfile),
+    );
+
+    container(rows)
+        .padding([8, 10])
+        .style(ui_style::panel)
+        .into()
+}
+
+fn host_domains_panel(rules: &[DomainRule]) -> Element<'_, Message> {
+    let mut rows = column![
+        text("Sessions with the tag connect to short host names within the domain, so web01 tagged prod reaches web01.prod.example.com. Hosts and usernames can also use ${USER} and ${ENV:NAME}.")
+            .size(12)
+            .style(ui_style::muted_text)
+    ]
+    .spacing(6);
+
+    for (index, rule) in rules.iter().enumerate() {
+        rows = rows.push(
+            row![
+                text_input("Tag", &rule.tag)
+                    .on_input(move |value| {
+                        Message::HostDomainChanged(index, HostDomainField::Tag, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(2)),
+                text_input("prod.example.com", &rule.domain)
+                    .on_input(move |value| {
+                        Message::HostDomainChanged(index, HostDomainField::Domain, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(4)),
+                button(text("✕").size(12))
+                    .padding([4, 8])
+                    .style(ui_style::icon_button)
+                    .on_press(Message::RemoveHostDomain(index)),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    rows = rows.push(
+        button(text("Add Domain").size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(false))
+            .on_press(Message::AddHostDomain),
+    );
+
+    container(rows)
+        .padding([8, 10])
+        .style(ui_style::panel)
+        .into()
+}
+
+fn editor_panel(options: &EditorOptions) -> Element<'_, Message> {
+    let mut rows = column![
+        text("Cmd+click a path:line:col in a local tab to open it. {file}, {line} and {column} are filled in; without {file} the path goes last. With no command the system's default app opens the file.")
+            .size(12)
+            .style(ui_style::muted_text),
+        text_input("code -g {file}:{line}:{column}", &options.command)
+            .on_input(Message::EditorCommandChanged)
+            .padding([4, 6])
+            .size(13)
+            .style(ui_style::dialog_input)
+            .width(Length::Fill),
+    ]
+    .spacing(6);
+
+    for (index, rule) in options.rules.iter().enumerate() {
+        rows = rows.push(
+            row![
+                text_input("py, txt", &rule.extensions)
+                    .on_input(move |value| {
+                        Message::EditorRuleChanged(index, EditorRuleField::Extensions, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(2)),
+                text_input("subl {file}:{line}:{column}", &rule.command)
+                    .on_input(move |value| {
+                        Message::EditorRuleChanged(index, EditorRuleField::Command, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(4)),
+                button(text("✕").size(12))
+                    .padding([4, 8])
+                    .style(ui_style::icon_button)
+                    .on_press(Message::RemoveEditorRule(index)),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    rows = rows.push(
+        button(text("Add Extension Rule").size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(false))
+            .on_press(Message::AddEditorRule),
+    );
+
+    container(rows)
+        .padding([8, 10])
+        .style(ui_style::panel)
+        .into()
+}
+
+fn proxy_panel<'a>(
+    input: &'a str,
+    proxy: Option<&'a Proxy>,
+    error: Option<&'a str>,
+) -> Element<'a, Message> {
+    let password = proxy.filter(|proxy| proxy.username.is_some()).map(|proxy| {
+        row![
+            text("Password").size(13),
+            container("").width(Length::Fill),
+            text_input("", proxy.password.as_deref().unwrap_or(""))
+                .on_input(Message::ProxyPasswordChanged)
+                .secure(true)
+                .padding([4, 6])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fixed(240.0)),
+        ]
+        .spacing(8)
+        .padding([0, 10])
+        .align_y(Alignment::Center)
+    });
+    container(
+        column![
+            text(
+                "Used by sessions that don't set their own proxy: socks5://host:1080 or \
+                 http://user@host:3128. Leave empty to connect directly."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+            row![
+                text("Proxy").size(13),
+                container("").width(Length::Fill),
+                text_input("None", input)
+                    .on_input(Message::ProxyChanged)
+                    .padding([4, 6])
+                    .size(13)
+                    .font(iced::Font::MONOSPACE)
+                    .style(ui_style::dialog_input)
+                    .width(Length::Fixed(240.0)),
+            ]
+            .spacing(8)
+            .padding([0, 10])
+            .align_y(Alignment::Center),
+            password,
+            error.map(|error| {
+                text(error)
+                    .size(12)
+                    .color(iced::Color::from_rgb(0.9, 0.3, 0.3))
+            }),
+        ]
+        .spacing(6),
+    )
+    .padding([8, 10])
+    .style(ui_style::panel)
+    .into()
+}
+
+fn viewer_mode_panel<'a>(enabled: bool) -> Element<'a, Message> {
+    let note = if enabled {
+        "On: terminal output stays redacted, sessions can't be created, edited or deleted, and SFTP can't delete files. It can't be turned off here; set \"viewer_mode\": false in ~/.rivett/settings.json instead."
+    } else {
+        "For shared machines: terminal output stays redacted, sessions can't be created, edited or deleted, and SFTP can't delete files. Once on, it can only be turned off by editing ~/.rivett/settings.json."
+    };
+    container(
+        column![
+            text(note).size(12).style(ui_style::muted_text),
+            toggle_row("Read-only viewer", enabled, Message::SetViewerMode),
+        ]
+        .spacing(6),
+    )
+    .padding([8, 10])
+    .style(ui_style::panel)
+    .into()
+}
+
+fn control_api_panel(enabled: bool, port: &str) -> Element<'_, Message> {
+    let note = format!(
+        "JSON-RPC over POST http://127.0.0.1:<port>/rpc for scripts and launchers: sessions.list, sessions.open, tabs.list, local.open, terminal.run, sftp.upload and sftp.download. Requests send \"Authorization: Bearer\" with the token in {}.",
+        crate::control::token_path().display()
+    );
+    container(
+        column![
+            text(note).size(12).style(ui_style::muted_text),
+            toggle_row("Listen on localhost", enabled, Message::SetControlApi),
+            row![
+                text("Port").size(13),
+                container("").width(Length::Fill),
+                text_input("47823", port)
+                    .on_input(Message::ControlApiPortChanged)
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::Fixed(70.0)),
+            ]
+            .spacing(8)
+            .padding([0, 10])
+            .align_y(Alignment::Center),
+        ]
+        .spacing(6),
+    )
+    .padding([8, 10])
+    .style(ui_style::panel)
+    .into()
+}
+
+fn redact_patterns_panel(patterns: &[String]) -> Element<'_, Message> {
+    let mut rows = column![
+        text("Masked while Redact is on in the status bar, along with IP addresses, saved hostnames and common secrets. Patterns are regular expressions; with a capture group only the group is masked.")
+            .size(12)
+            .style(ui_style::muted_text)
+    ]
+    .spacing(6);
+
+    for (index, pattern) in patterns.iter().enumerate() {
+        let error = (!pattern.trim().is_empty())
+            .then(|| crate::terminal::redact::validate(pattern).err())
+            .flatten()
+            .map(|_| {
+                text("Invalid")
+                    .size(12)
+                    .color(iced::Color::from_rgb(0.9, 0.3, 0.3))
+            });
+        rows = rows.push(
+            row![
+                text_input("e.g. acme-[0-9]+", pattern)
+                    .on_input(move |value| Message::RedactPatternChanged(index, value))
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::Fill),
+                error,
+                button(text("✕").size(12))
+                    .padding([4, 8])
+                    .style(ui_style::icon_button)
+                    .on_press(Message::RemoveRedactPattern(index)),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    rows = rows.push(
+        button(text("Add Pattern").size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(false))
+            .on_press(Message::AddRedactPattern),
+    );
+
+    container(rows)
+        .padding([8, 10])
+        .style(ui_style::panel)
+        .into()
+}
+
+fn logging_panel<'a>(
+    logging: &'a crate::logging::LoggingOptions,
+    bundle_status: Option<&'a str>,
+) -> Element<'a, Message> {
+    let levels = Subsystem::ALL.into_iter().map(|subsystem| {
+        container(
+            row![
+                text(subsystem.label()).size(13),
+                container("").width(Length::Fill),
+                pick_list(
+                    LogLevel::ALL,
+                    Some(logging.level(subsystem)),
+                    move |level| Message::SetLogLevel(subsystem, level),
+                )
+                .text_size(12),
+            ]
+            .align_y(Alignment::Center)
+            .spacing(8),
+        )
+        .padding([8, 10])
+        .into()
+    });
+    container(
+        Column::with_children(levels)
+            .push(
+                row![
+                    text("Log folder").size(13),
+                    container("").width(Length::Fill),
+                    text_input("~/.rivett/logs", &logging.directory)
+                        .on_input(Message::LogDirectoryChanged)
+                        .padding([4, 6])
+                        .size(13)
+                        .style(ui_style::dialog_input)
+                        .width(Length::Fixed(200.0)),
+                ]
+                .spacing(8)
+                .padding([0, 10])
+                .align_y(Alignment::Center),
+            )
+            .push(slider_row(
+                "Rotate log file at",
+                1.0..=100.0,
+                1.0,
+                logging.max_file_mb as f32,
+                format!("{} MB", logging.max_file_mb),
+                Message::SetLogFileSize,
+            ))
+            .push(slider_row(
+                "Old log files kept",
+                0.0..=20.0,
+                1.0,
+                logging.max_files as f32,
+                logging.max_files.to_string(),
+                Message::SetLogFileCount,
+            ))
+            .push(
+                container(
+                    text("Levels apply right away; the folder and rotation after a restart.")
+                        .size(12)
+                        .style(ui_style::muted_text),
+                )
+                .padding([0, 10]),
+            )
+            .push(
+                row![
+                    text(bundle_status.unwrap_or(
+                        "Zip recent logs with hosts, names and secrets removed from the config."
+                    ))
+                    .size(12)
+                    .style(ui_style::muted_text)
+                    .width(Length::Fill),
+                    button(text("Copy Diagnostic Bundle").size(12))
+                        .padding([4, 10])
+                        .style(ui_style::secondary_button_style)
+                        .on_press(Message::CopyDiagnosticBundle),
+                ]
+                .spacing(8)
+                .padding([0, 10])
+                .align_y(Alignment::Center),
+            )
+            .spacing(6)
+            .padding(iced::Padding::ZERO.bottom(8.0)),
+    )
+    .style(ui_style::panel)
+    .into()
+}
+
+fn filter_presets_panel(presets: &[FilterPreset]) -> Element<'_, Message> {
+    let mut rows = column![
+        text("Hide matching entries in the SFTP panes, e.g. .git/ node_modules/ *.pyc. A trailing / matches folders only.")
+            .size(12)
+            .style(ui_style::muted_text)
+    ]
+    .spacing(6);
+
+    for (index, preset) in presets.iter().enumerate() {
+        rows = rows.push(
+            row![
+                text_input("Name", &preset.name)
+                    .on_input(move |value| {
+                        Message::FilterPresetChanged(index, FilterPresetField::Name, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(2)),
+                text_input("Patterns", &preset.patterns)
+                    .on_input(move |value| {
+                        Message::FilterPresetChanged(index, FilterPresetField::Patterns, value)
+                    })
+                    .padding([4, 6])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::FillPortion(6)),
+                button(text("✕").size(12))
+                    .padding([4, 8])
+                    .style(ui_style::icon_button)
+                    .on_press(Message::RemoveFilterPreset(index)),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    rows = rows.push(
+        button(text("Add Filter").size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(false))
+            .on_press(Message::AddFilterPreset),
+    );
+
+    container(rows)
+        .padding([8, 10])
+        .style(ui_style::panel)
+        .into()
+}
+
 fn read_parent_pid() -> Option<u32> {
     let mut args = std::env::args();
     while let Some(arg) = args.next() {
@@ -0,0 +1,170 @@
+use anyhow::{Context, Result};
+use russh::client;
+use russh::keys::agent::client::AgentClient;
+use russh::keys::{Algorithm, PublicKey};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use super::connection::SshClient;
+
+// Long enough to find the key and touch it.
+const TOUCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A key held by the local ssh-agent, as shown in settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentIdentity {
+    pub algorithm: String,
+    pub fingerprint: String,
+    pub comment: String,
+}
+
+/// Lists the keys the local agent offers.
+pub async fn identities() -> Result<Vec<AgentIdentity>> {
+    #[cfg(unix)]
+    let keys = list_keys(&mut connect_env().await?).await?;
+    #[cfg(windows)]
+    let keys = match AgentClient::connect_named_pipe(OPENSSH_PIPE).await {
+        Ok(mut agent) => list_keys(&mut agent).await?,
+        Err(_) => list_keys(&mut connect_pageant().await?).await?,
+    };
+    Ok(keys
+        .iter()
+        .map(|key| AgentIdentity {
+            algorithm: key.algorithm().to_string(),
+            fingerprint: key.fingerprint(Default::default()).to_string(),
+            comment: key.comment().to_string(),
+        })
+        .collect())
+}
+
+/// FIDO2 keys (`sk-...`) are signed on the hardware token; the agent does
+/// that, so they are only usable through it.
+pub(super) fn is_security_key(algorithm: &Algorithm) -> bool {
+    matches!(
+        algorithm,
+        Algorithm::SkEd25519 | Algorithm::SkEcdsaSha2NistP256
+    )
+}
+
+/// Offers agent keys in turn until the server accepts one; just `only` when
+/// given. `touch` is told while a security key waits to be touched.
+pub(super) async fn authenticate(
+    session: &mut client::Handle<SshClient>,
+    username: &str,
+    only: Option<&PublicKey>,
+    timeout: Duration,
+    touch: &impl Fn(bool),
+    debug: &impl Fn(String),
+) -> Result<()> {
+    #[cfg(unix)]
+    return try_keys(
+        session,
+        username,
+        connect_env().await?,
+        only,
+        timeout,
+        touch,
+        debug,
+    )
+    .await;
+    #[cfg(windows)]
+    match AgentClient::connect_named_pipe(OPENSSH_PIPE).await {
+        Ok(agent) => try_keys(session, username, agent, only, timeout, touch, debug).await,
+        Err(_) => {
+            let agent = connect_pageant().await?;
+            try_keys(session, username, agent, only, timeout, touch, debug).await
+        }
+    }
+}
+
+async fn list_keys<S>(agent: &mut AgentClient<S>) -> Result<Vec<PublicKey>>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    agent
+        .request_identities()
+        .await
+        .context("Failed to list ssh-agent keys")
+}
+
+async fn try_keys<S>(
+    session: &mut client::Handle<SshClient>,
+    username: &str,
+    mut agent: AgentClient<S>,
+    only: Option<&PublicKey>,
+    timeout: Duration,
+    touch: &impl Fn(bool),
+    debug: &impl Fn(String),
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut keys = list_keys(&mut agent).await?;
+    if let Some(wanted) = only {
+        keys.retain(|key| key.key_data() == wanted.key_data());
+        if keys.is_empty() {
+            return Err(anyhow::anyhow!(
+                "The security key {} is not loaded in ssh-agent; add it with ssh-add",
+                wanted.fingerprint(Default::default())
+            ));
+        }
+    }
+    if keys.is_empty() {
+        return Err(anyhow::anyhow!("The ssh-agent holds no keys"));
+    }
+    for key in keys {
+        let hash_alg = if key.algorithm().is_rsa() {
+            super::session::within_timeout(timeout, session.best_supported_rsa_hash())
+                .await??
+                .flatten()
+        } else {
+            None
+        };
+        debug(format!(
+            "auth: trying agent key {} {} for {}",
+            key.algorithm(),
+            key.comment(),
+            username
+        ));
+        let security_key = is_security_key(&key.algorithm());
+        let sign = session.authenticate_publickey_with(username, key, hash_alg, &mut agent);
+        let auth_res = if security_key {
+            touch(true);
+            let signed = tokio::time::timeout(TOUCH_TIMEOUT, sign).await;
+            touch(false);
+            signed.map_err(|_| anyhow::anyhow!("The security key was not touched in time"))?
+        } else {
+            super::session::within_timeout(timeout, sign).await?
+        }
+        .context("ssh-agent failed to sign")?;
+        debug(format!(
+            "auth: agent key -> {}",
+            super::session::describe_auth(&auth_res)
+        ));
+        if auth_res.success() {
+            tracing::info!("ssh auth success (agent)");
+            return Ok(());
+        }
+    }
+    Err(anyhow::anyhow!("Authentication failed"))
+}
+
+#[cfg(unix)]
+async fn connect_env() -> Result<AgentClient<tokio::net::UnixStream>> {
+    AgentClient::connect_env()
+        .await
+        .map_err(|e| anyhow::anyhow!("No ssh-agent is running (SSH_AUTH_SOCK): {}", e))
+}
+
+// The Windows OpenSSH agent listens on a named pipe; Pageant is the
+// fallback for PuTTY users.
+#[cfg(windows)]
+pub(super) const OPENSSH_PIPE: &str = r"\\.\pipe\openssh-ssh-agent";
+
+#[cfg(windows)]
+async fn connect_pageant()
+-> Result<AgentClient<impl AsyncRead + AsyncWrite + Unpin + Send + 'static>> {
+    AgentClient::connect_pageant()
+        .await
+        .map_err(|e| anyhow::anyhow!("No ssh-agent or Pageant is running: {}", e))
+}
@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use russh::keys::PublicKey;
+use russh::{Channel, client};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::prompt::{AgentSignRequest, CredentialPrompter};
+
+const REQUEST_IDENTITIES: u8 = 11;
+const SIGN_REQUEST: u8 = 13;
+const FAILURE: u8 = 5;
+// As ssh-agent(1); anything larger is not a real request.
+const MAX_MESSAGE: usize = 256 * 1024;
+// An unanswered confirmation refuses, so the server isn't left waiting.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The server agent requests come from, and who confirms its signatures.
+#[derive(Clone)]
+pub(super) struct AgentForward {
+    /// `user@host`, as shown to the user.
+    pub target: String,
+    pub host: String,
+    pub prompter: Option<Arc<dyn CredentialPrompter>>,
+}
+
+/// Relays one agent channel opened by the server to the local agent. Only
+/// listing keys and signing are passed on, and every signature needs the
+/// user's confirmation; without a prompter none is given.
+pub(super) async fn serve(channel: Channel<client::Msg>, forward: AgentForward) -> Result<()> {
+    let mut remote = channel.into_stream();
+    let mut agent = connect().await?;
+    while let Some(request) = read_message(&mut remote).await? {
+        let allowed = match request.first() {
+            Some(&REQUEST_IDENTITIES) => true,
+            Some(&SIGN_REQUEST) => confirm(&forward, &request).await,
+            _ => false,
+        };
+        let reply = if allowed {
+            write_message(&mut agent, &request).await?;
+            read_message(&mut agent)
+                .await?
+                .context("ssh-agent closed the connection")?
+        } else {
+            vec![FAILURE]
+        };
+        write_message(&mut remote, &reply).await?;
+    }
+    Ok(())
+}
+
+async fn confirm(forward: &AgentForward, request: &[u8]) -> bool {
+    let Some(prompter) = &forward.prompter else {
+        return false;
+    };
+    let Some(key) = sign_request_key(request).and_then(|blob| PublicKey::from_bytes(blob).ok())
+    else {
+        return false;
+    };
+    let fingerprint = key.fingerprint(Default::default()).to_string();
+    let comment = super::agent::identities()
+        .await
+        .ok()
+        .and_then(|keys| keys.into_iter().find(|k| k.fingerprint == fingerprint))
+        .map(|k| k.comment)
+        .unwrap_or_default();
+    let decision = prompter.confirm_agent_sign(AgentSignRequest {
+        target: forward.target.clone(),
+        host: forward.host.clone(),
+        fingerprint,
+        comment,
+    });
+    matches!(
+        tokio::time::timeout(CONFIRM_TIMEOUT, decision).await,
+        Ok(Ok(true))
+    )
+}
+
+/// The key blob a sign request names.
+fn sign_request_key(request: &[u8]) -> Option<&[u8]> {
+    let len = u32::from_be_bytes(request.get(1..5)?.try_into().ok()?) as usize;
+    request.get(5..5usize.checked_add(len)?)
+}
+
+async fn read_message(stream: &mut (impl AsyncRead + Unpin)) -> Result<Option<Vec<u8>>> {
+    let mut len = [0; 4];
+    match stream.read_exact(&mut len).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len == 0 || len > MAX_MESSAGE {
+        anyhow::bail!("Agent message of {} bytes refused", len);
+    }
+    let mut body = vec![0; len];
+    stream.read_exact(&mut body).await?;
+    Ok(Some(body))
+}
+
+async fn write_message(stream: &mut (impl AsyncWrite + Unpin), body: &[u8]) -> Result<()> {
+    stream.write_all(&(body.len() as u32).to_be_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn connect() -> Result<tokio::net::UnixStream> {
+    let path = std::env::var("SSH_AUTH_SOCK").context("No ssh-agent is running (SSH_AUTH_SOCK)")?;
+    tokio::net::UnixStream::connect(path)
+        .await
+        .context("Failed to reach ssh-agent")
+}
+
+// Only the OpenSSH agent is forwarded; Pageant has no stream to relay.
+#[cfg(windows)]
+async fn connect() -> Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    tokio::net::windows::named_pipe::ClientOptions::new()
+        .open(super::agent::OPENSSH_PIPE)
+        .context("Failed to reach the OpenSSH agent")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_key_a_sign_request_names() {
+        let request = [&[SIGN_REQUEST, 0, 0, 0, 3][..], b"key", b"\0\0\0\x04data"].concat();
+        assert_eq!(sign_request_key(&request), Some(&b"key"[..]));
+        assert_eq!(sign_request_key(&[SIGN_REQUEST, 0, 0, 0, 9, 1]), None);
+        assert_eq!(sign_request_key(&[SIGN_REQUEST]), None);
+    }
+
+    #[tokio::test]
+    async fn frames_agent_messages() {
+        let mut framed = Vec::new();
+        write_message(&mut framed, &[REQUEST_IDENTITIES])
+            .await
+            .unwrap();
+        let mut reader = &framed[..];
+        assert_eq!(
+            read_message(&mut reader).await.unwrap(),
+            Some(vec![REQUEST_IDENTITIES])
+        );
+        assert_eq!(read_message(&mut reader).await.unwrap(), None);
+        assert!(read_message(&mut &[0u8, 0, 0, 0][..]).await.is_err());
+    }
+}
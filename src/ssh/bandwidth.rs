@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Seconds of history kept for the graphs.
+pub const HISTORY_SECONDS: usize = 60;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Bytes read from and written to a session's socket. Everything shares the
+/// one connection (shell, SFTP, forwards), so counting at the socket covers
+/// all of it, SSH framing included.
+#[derive(Debug, Clone, Default)]
+pub struct Bandwidth {
+    bytes_in: Arc<AtomicU64>,
+    bytes_out: Arc<AtomicU64>,
+}
+
+impl Bandwidth {
+    pub fn totals(&self) -> (u64, u64) {
+        (
+            self.bytes_in.load(Ordering::Relaxed),
+            self.bytes_out.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Whether both count the same connection.
+    pub fn same_as(&self, other: &Bandwidth) -> bool {
+        Arc::ptr_eq(&self.bytes_in, &other.bytes_in)
+    }
+}
+
+/// Wraps the TCP stream handed to russh and counts what passes through.
+pub(super) struct CountingStream<S> {
+    inner: S,
+    bandwidth: Bandwidth,
+}
+
+impl<S> CountingStream<S> {
+    pub(super) fn new(inner: S, bandwidth: Bandwidth) -> Self {
+        Self { inner, bandwidth }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for CountingStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let before = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        let read = buf.filled().len() - before;
+        self.bandwidth
+            .bytes_in
+            .fetch_add(read as u64, Ordering::Relaxed);
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CountingStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            self.bandwidth
+                .bytes_out
+                .fetch_add(written as u64, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Per-second rates derived from the running totals, newest last.
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthHistory {
+    samples: VecDeque<(u64, u64)>,
+    last: Option<(Instant, u64, u64)>,
+}
+
+impl BandwidthHistory {
+    /// Records a sample once at least a second has passed since the last.
+    pub fn sample(&mut self, totals: (u64, u64), now: Instant) {
+        let (bytes_in, bytes_out) = totals;
+        let Some((at, last_in, last_out)) = self.last else {
+            self.last = Some((now, bytes_in, bytes_out));
+            return;
+        };
+        let elapsed = now.duration_since(at);
+        if elapsed < SAMPLE_INTERVAL {
+            return;
+        }
+        let secs = elapsed.as_secs_f64();
+        let rate = |now: u64, before: u64| (now.saturating_sub(before) as f64 / secs) as u64;
+        if self.samples.len() == HISTORY_SECONDS {
+            self.samples.pop_front();
+        }
+        self.samples
+            .push_back((rate(bytes_in, last_in), rate(bytes_out, last_out)));
+        self.last = Some((now, bytes_in, bytes_out));
+    }
+
+    pub fn samples(&self) -> impl ExactSizeIterator<Item = (u64, u64)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Latest (in, out) rate in bytes per second.
+    pub fn current(&self) -> (u64, u64) {
+        self.samples.back().copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_are_per_second() {
+        let start = Instant::now();
+        let mut history = BandwidthHistory::default();
+        history.sample((0, 0), start);
+        history.sample((500, 10), start + Duration::from_millis(500));
+        assert_eq!(history.samples().len(), 0);
+        history.sample((4000, 200), start + Duration::from_secs(2));
+        assert_eq!(history.current(), (2000, 100));
+    }
+
+    #[test]
+    fn keeps_a_minute_of_samples() {
+        let start = Instant::now();
+        let mut history = BandwidthHistory::default();
+        for second in 0..=(HISTORY_SECONDS as u64 + 10) {
+            history.sample((second * 100, 0), start + Duration::from_secs(second));
+        }
+        assert_eq!(history.samples().len(), HISTORY_SECONDS);
+        assert_eq!(history.current(), (100, 0));
+    }
+}
@@ -1,17 +1,45 @@
-use russh::keys::PublicKey;
-use russh::{ChannelId, client};
+use russh::keys::{HashAlg, PublicKey};
+use russh::{ChannelId, Names, client};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::net::TcpStream;
-use tokio::sync::mpsc;
+
+use super::DebugLog;
+use super::agent_forward::AgentForward;
+use crate::terminal::OutputSender;
 
 #[derive(Clone)]
 pub struct SshClient {
-    tx: mpsc::UnboundedSender<Vec<u8>>,
+    tx: OutputSender,
     shell_channel: Arc<Mutex<Option<ChannelId>>>,
     remote_forwards: RemoteForwardMap,
+    debug_log: Option<DebugLog>,
+    host_key: HostKeySlot,
+    pinned_host_key: Option<String>,
+    agent_forward: Option<AgentForward>,
+    status: ConnectionStatus,
+}
+
+/// Whether a connection is still up, readable without locking its session.
+#[derive(Debug, Clone)]
+pub struct ConnectionStatus(Arc<AtomicBool>);
+
+impl Default for ConnectionStatus {
+    fn default() -> Self {
+        Self(Arc::new(AtomicBool::new(true)))
+    }
+}
+
+impl ConnectionStatus {
+    pub fn is_up(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
 }
 
+/// SHA256 fingerprint of the key the server presented during key exchange.
+pub(super) type HostKeySlot = Arc<Mutex<Option<String>>>;
+
 #[derive(Clone)]
 pub(super) struct RemoteForwardTarget {
     pub local_host: String,
@@ -26,31 +54,130 @@ pub(super) fn remote_forward_key(address: &str, port: u32) -> String {
 
 impl SshClient {
     pub fn new(
-        tx: mpsc::UnboundedSender<Vec<u8>>,
+        tx: OutputSender,
         shell_channel: Arc<Mutex<Option<ChannelId>>>,
         remote_forwards: RemoteForwardMap,
+        debug_log: Option<DebugLog>,
+        host_key: HostKeySlot,
+        pinned_host_key: Option<String>,
+        agent_forward: Option<AgentForward>,
     ) -> Self {
         Self {
             tx,
             shell_channel,
             remote_forwards,
+            debug_log,
+            host_key,
+            pinned_host_key,
+            agent_forward,
+            status: ConnectionStatus::default(),
+        }
+    }
+
+    /// Set to down once the connection ends, for whatever reason.
+    pub(super) fn status(&self) -> ConnectionStatus {
+        self.status.clone()
+    }
+
+    fn debug(&self, line: impl Into<String>) {
+        if let Some(log) = &self.debug_log {
+            log.push(line);
         }
     }
 }
 
+/// Compares fingerprints ignoring surrounding whitespace and the optional
+/// `SHA256:` prefix, so a pin can be pasted from either `ssh-keygen -l` or
+/// our own logs.
+fn fingerprint_matches(pinned: &str, presented: &str) -> bool {
+    let normalize = |value: &str| {
+        let value = value.trim();
+        value.strip_prefix("SHA256:").unwrap_or(value).to_string()
+    };
+    normalize(pinned) == normalize(presented)
+}
+
+/// The pre-auth banner as terminal output. Control characters are dropped,
+/// as RFC 4252 asks, so a banner can't send escape sequences.
+fn banner_output(banner: &str) -> Vec<u8> {
+    let mut text = String::new();
+    for line in banner.lines() {
+        text.extend(line.chars().filter(|c| *c == '\t' || !c.is_control()));
+        text.push_str("\r\n");
+    }
+    text.into_bytes()
+}
+
 impl client::Handler for SshClient {
     type Error = anyhow::Error;
 
     fn check_server_key(
         &mut self,
-        _server_public_key: &PublicKey,
+        server_public_key: &PublicKey,
     ) -> impl std::future::Future<Output = Result<bool, Self::Error>> + Send {
-        async {
-            // For now, accept all keys. In a real app, we should verify against known_hosts.
-            Ok(true)
+        let fingerprint = server_public_key.fingerprint(HashAlg::Sha256).to_string();
+        self.debug(format!(
+            "server host key: {} {}",
+            server_public_key.algorithm(),
+            fingerprint
+        ));
+        let mismatch = self
+            .pinned_host_key
+            .as_deref()
+            .filter(|pinned| !fingerprint_matches(pinned, &fingerprint))
+            .map(|pinned| {
+                self.debug(format!("host key does not match pinned {}", pinned));
+                anyhow::anyhow!(
+                    "Host key mismatch: pinned {}, server presented {}",
+                    pinned.trim(),
+                    fingerprint
+                )
+            });
+        if let Ok(mut slot) = self.host_key.lock() {
+            *slot = Some(fingerprint);
+        }
+        async move {
+            match mismatch {
+                Some(err) => Err(err),
+                // Without a pin, accept all keys. In a real app, we should verify against known_hosts.
+                None => Ok(true),
+            }
         }
     }
 
+    fn auth_banner(
+        &mut self,
+        banner: &str,
+        _session: &mut client::Session,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.debug(format!("server banner: {} bytes", banner.len()));
+        // Queued ahead of the shell's output, so it opens the terminal.
+        if let Err(e) = self.tx.send(banner_output(banner)) {
+            eprintln!("Failed to send SSH banner to UI: {}", e);
+        }
+        async { Ok(()) }
+    }
+
+    fn kex_done(
+        &mut self,
+        _shared_secret: Option<&[u8]>,
+        names: &Names,
+        _session: &mut client::Session,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.debug(format!(
+            "negotiated kex={} host-key={} cipher={} mac={}/{} compression={:?}/{:?} strict-kex={}",
+            names.kex.as_ref(),
+            names.key,
+            names.cipher.as_ref(),
+            names.client_mac.as_ref(),
+            names.server_mac.as_ref(),
+            names.client_compression,
+            names.server_compression,
+            names.strict_kex()
+        ));
+        async { Ok(()) }
+    }
+
     fn channel_open_confirmation(
         &mut self,
         id: ChannelId,
@@ -58,6 +185,10 @@ impl client::Handler for SshClient {
         window_size: u32,
         _session: &mut client::Session,
     ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.debug(format!(
+            "channel {} open (window={}, max_packet={})",
+            id, window_size, max_packet_size
+        ));
         async move {
             tracing::info!(
                 "ssh channel {:?} open (window={}, max_packet={})",
@@ -119,6 +250,7 @@ impl client::Handler for SshClient {
         channel: ChannelId,
         _session: &mut client::Session,
     ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.debug(format!("channel {} closed by server", channel));
         async move {
             tracing::info!("ssh channel {:?} closed by server", channel);
             Ok(())
@@ -187,10 +319,35 @@ impl client::Handler for SshClient {
         }
     }
 
+    fn server_channel_open_agent_forward(
+        &mut self,
+        channel: russh::Channel<client::Msg>,
+        _session: &mut client::Session,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.debug(format!("agent channel {} opened by server", channel.id()));
+        let forward = self.agent_forward.clone();
+        async move {
+            // Only servers we asked to forward to may reach the agent.
+            let Some(forward) = forward else {
+                tracing::warn!("unrequested agent channel refused");
+                let _ = channel.close().await;
+                return Ok(());
+            };
+            tokio::spawn(async move {
+                if let Err(err) = super::agent_forward::serve(channel, forward).await {
+                    tracing::warn!("agent forwarding ended: {:#}", err);
+                }
+            });
+            Ok(())
+        }
+    }
+
     fn disconnected(
         &mut self,
         reason: client::DisconnectReason<Self::Error>,
     ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send {
+        self.debug(format!("disconnected: {:?}", reason));
+        self.status.0.store(false, Ordering::Relaxed);
         async move {
             tracing::info!("ssh disconnected: {:?}", reason);
             match reason {
@@ -200,3 +357,27 @@ impl client::Handler for SshClient {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{banner_output, fingerprint_matches};
+
+    #[test]
+    fn fingerprint_prefix_is_optional() {
+        let presented = "SHA256:47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU";
+        assert!(fingerprint_matches(presented, presented));
+        assert!(fingerprint_matches(
+            " 47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU\n",
+            presented
+        ));
+        assert!(!fingerprint_matches("SHA256:other", presented));
+    }
+
+    #[test]
+    fn banner_is_stripped_of_escapes() {
+        assert_eq!(
+            banner_output("Authorized use only.\n\x1b[2JBye\r\n"),
+            b"Authorized use only.\r\n[2JBye\r\n"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+// Keeps a long-running session from growing the log without bound.
+const MAX_DEBUG_LINES: usize = 2000;
+
+/// Per-connection negotiation log, shared between the SSH handler and the UI.
+#[derive(Clone, Default)]
+pub struct DebugLog {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl fmt::Debug for DebugLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DebugLog({} lines)", self.len())
+    }
+}
+
+impl DebugLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, line: impl Into<String>) {
+        let line = format!(
+            "{} {}",
+            chrono::Local::now().format("%H:%M:%S%.3f"),
+            line.into()
+        );
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() >= MAX_DEBUG_LINES {
+                lines.remove(0);
+            }
+            lines.push(line);
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.lock().map(|lines| lines.len()).unwrap_or(0)
+    }
+}
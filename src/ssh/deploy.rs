@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use russh::keys::{PrivateKey, decode_secret_key};
+
+use super::session::shell_quote;
+use super::{ConnectOptions, SshSession};
+use crate::session::config::AuthMethod;
+
+// Printed by the remote command, so its outcome can be told apart from
+// whatever the login shell prints.
+const ADDED: &str = "rivett-key-added";
+const PRESENT: &str = "rivett-key-present";
+
+/// What deploying a public key did on the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyDeployment {
+    Added,
+    AlreadyPresent,
+}
+
+/// The public half of a saved key as an `authorized_keys` line, read from
+/// the keyring or, for keys that live on disk, from `path`.
+pub fn saved_public_key(key_id: &str, path: &str) -> Result<String> {
+    let secret = match crate::settings::load_key_secret(key_id) {
+        Some(secret) => secret,
+        None if !path.trim().is_empty() => {
+            let expanded = SshSession::expand_tilde(path);
+            std::fs::read_to_string(&expanded)
+                .with_context(|| format!("Failed to read key {}", expanded))?
+        }
+        None => return Err(anyhow::anyhow!("Private key content is missing")),
+    };
+    public_key_line(&secret)
+}
+
+// Encrypted OpenSSH keys keep the public half in the clear, so no
+// passphrase is needed.
+fn public_key_line(secret: &str) -> Result<String> {
+    let key = match PrivateKey::from_openssh(secret) {
+        Ok(key) => key,
+        Err(_) => decode_secret_key(secret, None).context("Failed to read the private key")?,
+    };
+    key.public_key()
+        .to_openssh()
+        .context("Failed to encode the public key")
+}
+
+/// Logs in with a password and appends `public_key` to
+/// `~/.ssh/authorized_keys` unless it is there already, as `ssh-copy-id`
+/// does. The prompter in `options` is asked when `password` is `None`.
+pub async fn deploy_public_key(
+    host: &str,
+    port: u16,
+    username: &str,
+    password: Option<String>,
+    public_key: &str,
+    options: ConnectOptions,
+) -> Result<KeyDeployment> {
+    let (session, _rx) = SshSession::connect(
+        host,
+        port,
+        username,
+        AuthMethod::Password,
+        password,
+        None,
+        options,
+    )
+    .await?;
+    let result = async {
+        let mut output = session.exec_stream(&install_command(public_key)).await?;
+        let mut printed = Vec::new();
+        while let Some(chunk) = output.recv().await {
+            printed.extend(chunk);
+        }
+        let printed = String::from_utf8_lossy(&printed);
+        if printed.contains(ADDED) {
+            Ok(KeyDeployment::Added)
+        } else if printed.contains(PRESENT) {
+            Ok(KeyDeployment::AlreadyPresent)
+        } else {
+            Err(anyhow::anyhow!(
+                "Failed to update ~/.ssh/authorized_keys: {}",
+                printed.trim()
+            ))
+        }
+    }
+    .await;
+    if let Err(e) = session.disconnect("public key deployed").await {
+        tracing::debug!("disconnect after deploying key failed: {}", e);
+    }
+    result
+}
+
+// Creates `~/.ssh` and the file with the modes sshd insists on, and looks
+// for the key by its type and body so a different comment isn't a new key.
+fn install_command(public_key: &str) -> String {
+    let body: Vec<&str> = public_key.split_whitespace().take(2).collect();
+    format!(
+        "umask 077; mkdir -p ~/.ssh && touch ~/.ssh/authorized_keys || exit 1; \
+         if grep -qF {body} ~/.ssh/authorized_keys; then echo {PRESENT}; \
+         else {{ [ -z \"$(tail -c1 ~/.ssh/authorized_keys)\" ] || echo; echo {line}; }} \
+         >> ~/.ssh/authorized_keys && echo {ADDED}; fi",
+        body = shell_quote(&body.join(" ")),
+        line = shell_quote(public_key.trim()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use russh::keys::Algorithm;
+    use russh::keys::ssh_key::LineEnding;
+    use russh::keys::ssh_key::rand_core::OsRng;
+
+    #[test]
+    fn appends_a_key_once() {
+        let key = PrivateKey::random(&mut OsRng, Algorithm::Ed25519).unwrap();
+        let secret = key.to_openssh(LineEnding::LF).unwrap();
+        let line = public_key_line(&secret).unwrap();
+        assert!(line.starts_with("ssh-ed25519 "));
+
+        let home = std::env::temp_dir().join(format!("rivett-deploy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(home.join(".ssh")).unwrap();
+        // An existing file without a final newline.
+        std::fs::write(home.join(".ssh/authorized_keys"), "ssh-rsa AAAA other").unwrap();
+        let run = || {
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(install_command(&line))
+                .env("HOME", &home)
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        assert_eq!(run(), ADDED);
+        assert_eq!(run(), PRESENT);
+        assert_eq!(
+            std::fs::read_to_string(home.join(".ssh/authorized_keys")).unwrap(),
+            format!("ssh-rsa AAAA other\n{}\n", line)
+        );
+        let _ = std::fs::remove_dir_all(home);
+    }
+}
@@ -0,0 +1,103 @@
+/// Broad cause of a failed connection attempt, derived from the error text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    Dns,
+    Refused,
+    Timeout,
+    Auth,
+    HostKey,
+    Other,
+}
+
+impl FailureKind {
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| error.contains(needle));
+
+        if has(&[
+            "failed to lookup address",
+            "name or service not known",
+            "nodename nor servname",
+            "no such host is known",
+            "temporary failure in name resolution",
+        ]) {
+            FailureKind::Dns
+        } else if has(&[
+            "connection refused",
+            "os error 111",
+            "os error 61",
+            "os error 10061",
+        ]) {
+            FailureKind::Refused
+        } else if has(&["timeout", "timed out"]) {
+            FailureKind::Timeout
+        } else if has(&["host key", "unknown key", "key changed", "key mismatch"]) {
+            FailureKind::HostKey
+        } else if has(&[
+            "authentication failed",
+            "password required",
+            "private key",
+            "passphrase",
+            "could not decrypt",
+        ]) {
+            FailureKind::Auth
+        } else {
+            FailureKind::Other
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            FailureKind::Dns => "Host not found",
+            FailureKind::Refused => "Connection refused",
+            FailureKind::Timeout => "Connection timed out",
+            FailureKind::Auth => "Authentication failed",
+            FailureKind::HostKey => "Host key rejected",
+            FailureKind::Other => "Connection failed",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            FailureKind::Dns => "The host name could not be resolved. Check it for typos.",
+            FailureKind::Refused => "The host is reachable but nothing is listening on that port.",
+            FailureKind::Timeout => {
+                "The host did not answer. It may be down, firewalled, or only reachable through a tunnel."
+            }
+            FailureKind::Auth => "The server rejected the credentials for this user.",
+            FailureKind::HostKey => {
+                "The server presented a host key that does not match the one on record."
+            }
+            FailureKind::Other => "See the log below for details.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FailureKind;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            FailureKind::classify(
+                "failed to lookup address information: Name or service not known"
+            ),
+            FailureKind::Dns
+        );
+        assert_eq!(
+            FailureKind::classify("IO error: Connection refused (os error 111)"),
+            FailureKind::Refused
+        );
+        assert_eq!(
+            FailureKind::classify("Connection timeout (10s)"),
+            FailureKind::Timeout
+        );
+        assert_eq!(
+            FailureKind::classify("Authentication failed"),
+            FailureKind::Auth
+        );
+        assert_eq!(FailureKind::classify("Unknown key"), FailureKind::HostKey);
+        assert_eq!(FailureKind::classify("Disconnected"), FailureKind::Other);
+    }
+}
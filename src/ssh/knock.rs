@@ -0,0 +1,49 @@
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::session::config::{KnockProtocol, KnockStep};
+
+// A knock only has to reach the host; nobody answers on a knock port, so
+// the TCP attempt is abandoned quickly instead of waiting for a timeout.
+const TCP_KNOCK_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Sends a port-knock sequence to `host` so a knock daemon opens the SSH
+/// port. Knocks that go unanswered are expected and not errors; only a
+/// failed lookup or an unusable local socket is.
+pub async fn knock(host: &str, steps: &[KnockStep]) -> Result<()> {
+    let address = tokio::net::lookup_host((host, 0))
+        .await
+        .with_context(|| format!("DNS lookup for {} failed", host))?
+        .next()
+        .with_context(|| format!("DNS lookup for {} returned no addresses", host))?;
+
+    for step in steps {
+        let target = SocketAddr::new(address.ip(), step.port);
+        match step.protocol {
+            KnockProtocol::Tcp => {
+                let _ =
+                    tokio::time::timeout(TCP_KNOCK_TIMEOUT, tokio::net::TcpStream::connect(target))
+                        .await;
+            }
+            KnockProtocol::Udp => {
+                let local: SocketAddr = if target.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0u16; 8], 0).into()
+                };
+                let socket = tokio::net::UdpSocket::bind(local)
+                    .await
+                    .context("Failed to open a UDP socket for port knocking")?;
+                socket
+                    .send_to(&[0], target)
+                    .await
+                    .with_context(|| format!("Failed to knock on {}", target))?;
+            }
+        }
+        if step.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(step.delay_ms.into())).await;
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,32 @@
+mod agent;
+mod agent_forward;
+mod bandwidth;
 mod connection;
+mod debug_log;
+mod deploy;
+mod diagnostics;
+mod knock;
+mod preflight;
+mod probe;
+mod prompt;
+mod proxy;
 mod session;
+#[cfg(test)]
+mod test_server;
+mod transfer;
 
 // pub use connection::SshClient;
-pub use session::SshSession;
+pub use agent::{AgentIdentity, identities as agent_identities};
+pub use bandwidth::{Bandwidth, BandwidthHistory, HISTORY_SECONDS};
+pub use connection::ConnectionStatus;
+pub use debug_log::DebugLog;
+pub use deploy::{KeyDeployment, deploy_public_key, saved_public_key};
+pub use diagnostics::FailureKind;
+pub use preflight::{PreflightReport, preflight};
+pub use probe::probe;
+pub use prompt::{
+    AgentSignRequest, CredentialAnswer, CredentialKind, CredentialPrompter, CredentialRequest,
+    TypedPassphrase,
+};
+pub use session::{ConnectOptions, DEFAULT_CONNECT_TIMEOUT, SshSession, shell_quote};
+pub use transfer::{DirListing, TransferChannel};
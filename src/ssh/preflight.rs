@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use super::{ConnectOptions, SshSession};
+use crate::session::config::AuthMethod;
+
+const DNS_TIMEOUT_SECS: u64 = 5;
+
+/// What a background check of a saved session found out.
+#[derive(Debug, Clone)]
+pub struct PreflightReport {
+    /// Empty when a proxy resolves the name instead.
+    pub addresses: Vec<SocketAddr>,
+    pub host_key_fingerprint: String,
+}
+
+/// Resolves the host, completes a full handshake and authenticates, then
+/// hangs up. A pinned host key in `options` that no longer matches fails
+/// the check. DNS is checked on its own first so a typo in the hostname is
+/// reported as such rather than as a generic connect failure.
+pub async fn preflight(
+    host: &str,
+    port: u16,
+    username: &str,
+    auth_method: AuthMethod,
+    password: Option<String>,
+    key_passphrase: Option<String>,
+    options: ConnectOptions,
+) -> Result<PreflightReport> {
+    // Behind a proxy the name may only resolve on the proxy's side.
+    let addresses = match options.proxy {
+        Some(_) => Vec::new(),
+        None => resolve(host, port).await?,
+    };
+
+    let (session, _rx) = SshSession::connect(
+        host,
+        port,
+        username,
+        auth_method,
+        password,
+        key_passphrase,
+        options,
+    )
+    .await?;
+    let fingerprint = session.host_key_fingerprint();
+    if let Err(e) = session.disconnect("pre-flight check").await {
+        tracing::debug!("pre-flight disconnect failed: {}", e);
+    }
+
+    Ok(PreflightReport {
+        addresses,
+        host_key_fingerprint: fingerprint
+            .ok_or_else(|| anyhow::anyhow!("Server did not present a host key"))?,
+    })
+}
+
+async fn resolve(host: &str, port: u16) -> Result<Vec<SocketAddr>> {
+    let lookup = tokio::net::lookup_host((host, port));
+    let addresses: Vec<SocketAddr> =
+        tokio::time::timeout(Duration::from_secs(DNS_TIMEOUT_SECS), lookup)
+            .await
+            .map_err(|_| anyhow::anyhow!("DNS lookup for {} timed out", host))?
+            .with_context(|| format!("DNS lookup for {} failed", host))?
+            .collect();
+    if addresses.is_empty() {
+        anyhow::bail!("DNS lookup for {} returned no addresses", host);
+    }
+    Ok(addresses)
+}
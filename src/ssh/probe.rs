@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+
+use crate::session::config::Proxy;
+
+const PROBE_TIMEOUT_SECS: u64 = 3;
+// The identification line is at most 255 bytes (RFC 4253); servers may
+// send other lines first, so read a little more.
+const MAX_GREETING_BYTES: usize = 1024;
+
+/// Checks that an SSH server answers on `host:port` without logging in:
+/// connects, waits for its `SSH-` identification line and hangs up.
+/// Returns how long the TCP connection took, through `proxy` if given.
+pub async fn probe(host: &str, port: u16, proxy: Option<&Proxy>) -> Result<Duration> {
+    let timeout = Duration::from_secs(PROBE_TIMEOUT_SECS);
+    let started = Instant::now();
+    let mut stream = tokio::time::timeout(timeout, super::proxy::connect(proxy, host, port))
+        .await
+        .map_err(|_| anyhow::anyhow!("Connection to {}:{} timed out", host, port))?
+        .with_context(|| format!("Failed to reach {}:{}", host, port))?;
+    let elapsed = started.elapsed();
+
+    let greeting = async {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 256];
+        while buf.len() < MAX_GREETING_BYTES {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+            if has_identification(&buf) {
+                return Ok(true);
+            }
+        }
+        Ok::<_, std::io::Error>(false)
+    };
+    match tokio::time::timeout(timeout, greeting).await {
+        Ok(Ok(true)) => Ok(elapsed),
+        Ok(Ok(false)) => anyhow::bail!("{}:{} is not an SSH server", host, port),
+        Ok(Err(e)) => Err(e).with_context(|| format!("Failed to read from {}:{}", host, port)),
+        Err(_) => anyhow::bail!("{}:{} did not greet in time", host, port),
+    }
+}
+
+fn has_identification(buf: &[u8]) -> bool {
+    buf.split_inclusive(|&byte| byte == b'\n')
+        .any(|line| line.starts_with(b"SSH-") && line.ends_with(b"\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::has_identification;
+
+    #[test]
+    fn waits_for_a_complete_identification_line() {
+        assert!(has_identification(b"SSH-2.0-OpenSSH_9.6\r\n"));
+        assert!(has_identification(b"Welcome\r\nSSH-2.0-dropbear\r\n"));
+        assert!(!has_identification(b"Welcome\r\nSSH-2.0-Open"));
+        assert!(!has_identification(b"HTTP/1.1 400 Bad Request\r\n"));
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use std::fmt;
+use tokio::sync::oneshot;
+
+/// What a credential request is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialKind {
+    Password,
+    KeyPassphrase,
+    /// A question from the server, such as a one-time code.
+    KeyboardInteractive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialRequest {
+    pub kind: CredentialKind,
+    /// Who is asking, as `user@host`.
+    pub target: String,
+    /// The label for the answer; the server's own text for
+    /// keyboard-interactive questions.
+    pub prompt: String,
+    /// Whether the answer may be shown while typed.
+    pub echo: bool,
+    /// The previous answer was rejected.
+    pub retry: bool,
+    /// A passphrase for the session's own key, which it can save; keys
+    /// tried after it have nowhere to keep one.
+    pub can_remember: bool,
+}
+
+/// What the user typed in answer to a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialAnswer {
+    pub value: String,
+    /// "Remember" was ticked, where the request offered it.
+    pub remember: bool,
+}
+
+impl CredentialRequest {
+    pub fn new(kind: CredentialKind, target: &str) -> Self {
+        let prompt = match kind {
+            CredentialKind::Password => "Password",
+            CredentialKind::KeyPassphrase => "Key passphrase",
+            CredentialKind::KeyboardInteractive => "Response",
+        };
+        Self {
+            kind,
+            target: target.to_string(),
+            prompt: prompt.to_string(),
+            echo: false,
+            retry: false,
+            can_remember: false,
+        }
+    }
+}
+
+/// A key passphrase the user typed while connecting, and the key it
+/// unlocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedPassphrase {
+    /// The `user@host` it was asked for.
+    pub target: String,
+    /// The saved key it unlocked, if it was one.
+    pub key_id: Option<String>,
+    /// The key file it unlocked, when not known by id.
+    pub path: String,
+    pub passphrase: String,
+    /// The user asked for it to be saved with the session.
+    pub remember: bool,
+}
+
+/// A server asking the forwarded agent to sign with one of the user's keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentSignRequest {
+    /// The server the request came from, as `user@host`.
+    pub target: String,
+    pub host: String,
+    pub fingerprint: String,
+    /// The key's comment in the agent, usually where it came from.
+    pub comment: String,
+}
+
+/// Asks the user for a credential the connection is missing. Implemented by
+/// the UI; the ssh code only describes what it needs.
+pub trait CredentialPrompter: Send + Sync + fmt::Debug {
+    /// Queues `request`. The receiver resolves to the answer, or None when
+    /// the user cancels.
+    fn ask(&self, request: CredentialRequest) -> oneshot::Receiver<Option<CredentialAnswer>>;
+
+    /// A security key for `target` started (true) or stopped (false)
+    /// waiting for the user to touch it.
+    fn security_key_touch(&self, target: &str, waiting: bool);
+
+    /// Asks whether the forwarded agent may sign `request`. The receiver
+    /// resolves to the decision; dropping the sender refuses.
+    fn confirm_agent_sign(&self, request: AgentSignRequest) -> oneshot::Receiver<bool>;
+}
+
+pub(super) async fn ask(
+    prompter: &dyn CredentialPrompter,
+    request: CredentialRequest,
+) -> Result<CredentialAnswer> {
+    prompter
+        .ask(request)
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| anyhow::anyhow!("Authentication cancelled"))
+}
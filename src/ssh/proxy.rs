@@ -0,0 +1,228 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use std::net::IpAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::session::config::{Proxy, ProxyKind};
+
+// Longest HTTP response head read from a proxy before giving up on it.
+const MAX_HTTP_HEAD: usize = 8 * 1024;
+
+/// Opens a TCP connection to `host:port`, through `proxy` when there is
+/// one. The caller bounds how long this may take.
+pub async fn connect(proxy: Option<&Proxy>, host: &str, port: u16) -> Result<TcpStream> {
+    let Some(proxy) = proxy else {
+        return Ok(TcpStream::connect((host, port)).await?);
+    };
+    let mut stream = TcpStream::connect((proxy.host.as_str(), proxy.port))
+        .await
+        .with_context(|| format!("Failed to reach proxy {}:{}", proxy.host, proxy.port))?;
+    match proxy.kind {
+        ProxyKind::Socks5 => socks5(&mut stream, proxy, host, port).await?,
+        ProxyKind::Http => http_connect(&mut stream, proxy, host, port).await?,
+    }
+    Ok(stream)
+}
+
+// RFC 1928, with username/password authentication from RFC 1929.
+async fn socks5(stream: &mut TcpStream, proxy: &Proxy, host: &str, port: u16) -> Result<()> {
+    let credentials = proxy
+        .username
+        .as_deref()
+        .map(|user| (user, proxy.password.as_deref().unwrap_or("")));
+    let greeting: &[u8] = match credentials {
+        Some(_) => &[5, 2, 0, 2],
+        None => &[5, 1, 0],
+    };
+    stream.write_all(greeting).await?;
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await?;
+    if choice[0] != 5 {
+        anyhow::bail!("{}:{} is not a SOCKS5 proxy", proxy.host, proxy.port);
+    }
+    match (choice[1], credentials) {
+        (0, _) => {}
+        (2, Some((user, password))) => {
+            stream
+                .write_all(&socks5_auth_request(user, password)?)
+                .await?;
+            let mut status = [0u8; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0 {
+                anyhow::bail!("The proxy rejected the username and password");
+            }
+        }
+        _ => anyhow::bail!("The proxy requires a username and password"),
+    }
+
+    stream
+        .write_all(&socks5_connect_request(host, port)?)
+        .await?;
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        anyhow::bail!(
+            "The proxy could not connect to {}:{}: {}",
+            host,
+            port,
+            socks5_error(reply[1])
+        );
+    }
+    // Skip the address the proxy bound, which nothing here needs.
+    let address_len = match reply[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        _ => anyhow::bail!("Malformed reply from the proxy"),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+fn socks5_auth_request(user: &str, password: &str) -> Result<Vec<u8>> {
+    let user_len = u8::try_from(user.len()).context("Proxy username is too long")?;
+    let password_len = u8::try_from(password.len()).context("Proxy password is too long")?;
+    let mut request = vec![1, user_len];
+    request.extend_from_slice(user.as_bytes());
+    request.push(password_len);
+    request.extend_from_slice(password.as_bytes());
+    Ok(request)
+}
+
+fn socks5_connect_request(host: &str, port: u16) -> Result<Vec<u8>> {
+    let mut request = vec![5, 1, 0];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(1);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            let len = u8::try_from(host.len()).context("Hostname is too long for SOCKS5")?;
+            request.extend_from_slice(&[3, len]);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    Ok(request)
+}
+
+fn socks5_error(code: u8) -> &'static str {
+    match code {
+        1 => "general failure",
+        2 => "not allowed by the proxy's rules",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+async fn http_connect(stream: &mut TcpStream, proxy: &Proxy, host: &str, port: u16) -> Result<()> {
+    stream
+        .write_all(http_connect_request(proxy, host, port).as_bytes())
+        .await?;
+    // Read byte by byte so nothing after the head, the start of the SSH
+    // greeting, is taken from the stream.
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() == MAX_HTTP_HEAD {
+            anyhow::bail!("The proxy sent an oversized response");
+        }
+        if stream.read(&mut byte).await? == 0 {
+            anyhow::bail!("The proxy closed the connection");
+        }
+        head.push(byte[0]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    match http_status(&head) {
+        Some((200..=299, _)) => Ok(()),
+        Some((407, _)) if proxy.username.is_some() => {
+            anyhow::bail!("The proxy rejected the username and password")
+        }
+        Some((407, _)) => anyhow::bail!("The proxy requires a username and password"),
+        Some((code, reason)) => anyhow::bail!(
+            "The proxy could not connect to {}:{}: {} {}",
+            host,
+            port,
+            code,
+            reason
+        ),
+        None => anyhow::bail!("{}:{} is not an HTTP proxy", proxy.host, proxy.port),
+    }
+}
+
+fn http_connect_request(proxy: &Proxy, host: &str, port: u16) -> String {
+    let target = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target);
+    if let Some(user) = &proxy.username {
+        let credentials = format!("{}:{}", user, proxy.password.as_deref().unwrap_or(""));
+        request.push_str(&format!(
+            "Proxy-Authorization: Basic {}\r\n",
+            base64::engine::general_purpose::STANDARD.encode(credentials)
+        ));
+    }
+    request.push_str("\r\n");
+    request
+}
+
+/// Status code and reason from the first line of an HTTP response.
+fn http_status(head: &str) -> Option<(u16, &str)> {
+    let line = head.lines().next()?;
+    let mut parts = line.splitn(3, ' ');
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    let code = parts.next()?.parse().ok()?;
+    Some((code, parts.next().unwrap_or("").trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_handshake_requests() {
+        assert_eq!(
+            socks5_connect_request("10.0.0.5", 22).unwrap(),
+            [5, 1, 0, 1, 10, 0, 0, 5, 0, 22]
+        );
+        assert_eq!(
+            socks5_connect_request("db", 2222).unwrap(),
+            [5, 1, 0, 3, 2, b'd', b'b', 0x08, 0xae]
+        );
+        assert_eq!(
+            socks5_auth_request("ops", "pw").unwrap(),
+            [1, 3, b'o', b'p', b's', 2, b'p', b'w']
+        );
+
+        let proxy = Proxy::parse("http://ops:pw@proxy:3128").unwrap();
+        assert_eq!(
+            http_connect_request(&proxy, "fd00::1", 22),
+            "CONNECT [fd00::1]:22 HTTP/1.1\r\nHost: [fd00::1]:22\r\n\
+             Proxy-Authorization: Basic b3BzOnB3\r\n\r\n"
+        );
+        assert_eq!(
+            http_status("HTTP/1.1 200 Connection established\r\n\r\n"),
+            Some((200, "Connection established"))
+        );
+        assert_eq!(http_status("SSH-2.0-OpenSSH\r\n"), None);
+    }
+}
@@ -1,11 +1,13 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dirs::home_dir;
-use russh::keys::{PrivateKey, PrivateKeyWithHashAlg, decode_secret_key, load_secret_key};
-use russh::{ChannelId, client};
-use russh_sftp::client::SftpSession;
+use russh::keys::{PrivateKey, PrivateKeyWithHashAlg, decode_secret_key};
+use russh::{ChannelId, MethodKind, client, compression};
+use russh_sftp::client::{RawSftpSession, SftpSession};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
 use tokio::sync::Mutex as AsyncMutex;
@@ -13,8 +15,20 @@ use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
-use super::connection::{RemoteForwardMap, RemoteForwardTarget, SshClient, remote_forward_key};
-use crate::session::config::{AuthMethod, PortForwardDirection, PortForwardRule};
+use super::DebugLog;
+use super::agent_forward::AgentForward;
+use super::bandwidth::{Bandwidth, CountingStream};
+use super::connection::{
+    ConnectionStatus, HostKeySlot, RemoteForwardMap, RemoteForwardTarget, SshClient,
+    remote_forward_key,
+};
+use super::prompt::{
+    self, CredentialAnswer, CredentialKind, CredentialPrompter, CredentialRequest, TypedPassphrase,
+};
+use super::transfer::TransferChannel;
+use crate::session::config::{
+    AuthMethod, JumpHost, Keepalive, KnockStep, PortForwardDirection, PortForwardRule, Proxy,
+};
 
 use std::fmt;
 
@@ -23,13 +37,73 @@ pub struct SshSession {
     session: Arc<AsyncMutex<client::Handle<SshClient>>>,
     active_channel: Option<russh::ChannelWriteHalf<client::Msg>>,
     shell_channel: Arc<StdMutex<Option<ChannelId>>>,
+    // Shells opened for other tabs on this same connection.
+    extra_shells: ExtraShells,
     port_forwards: HashMap<String, PortForwardHandle>,
     remote_forwards: RemoteForwardMap,
+    host_key: HostKeySlot,
+    bandwidth: Bandwidth,
+    status: ConnectionStatus,
+    forward_agent: bool,
+    environment: Vec<(String, String)>,
+    // Exit status of the main shell's remote command, sent once it ends.
+    shell_exit: Option<oneshot::Receiver<Option<u32>>>,
+    typed_passphrases: Vec<TypedPassphrase>,
+    // Keeps the bastion connection open for as long as this one.
+    _jump_session: Option<client::Handle<SshClient>>,
 }
 
-const CONNECT_TIMEOUT_SECS: u64 = 10;
-const KEEPALIVE_INTERVAL_SECS: u64 = 30;
-const KEEPALIVE_MAX: usize = 3;
+type ExtraShells = Arc<StdMutex<HashMap<ChannelId, Arc<russh::ChannelWriteHalf<client::Msg>>>>>;
+
+/// Per-connection settings beyond the address and credentials.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    pub debug_log: Option<DebugLog>,
+    /// Refuse the server unless its host key has this fingerprint.
+    pub pinned_host_key: Option<String>,
+    /// Knocked, in order, before the TCP connection is opened.
+    pub port_knock: Vec<KnockStep>,
+    /// Tunnel through this host, authenticating with the same credentials.
+    pub jump_host: Option<JumpHost>,
+    /// Asked for credentials that are missing or rejected. Without one,
+    /// those fail the connection.
+    pub prompter: Option<Arc<dyn CredentialPrompter>>,
+    pub keepalive: Keepalive,
+    /// Let the server use the local ssh-agent, confirming each signature
+    /// with the prompter.
+    pub forward_agent: bool,
+    /// Set on every shell before it starts, where the server accepts them.
+    pub environment: Vec<(String, String)>,
+    /// Offer zlib so the transport is compressed when the server agrees.
+    pub compression: bool,
+    /// Reach the host, or the jump host, through this proxy.
+    pub proxy: Option<Proxy>,
+    /// Longest one network step of connecting may take; `None` for
+    /// `DEFAULT_CONNECT_TIMEOUT`.
+    pub connect_timeout: Option<Duration>,
+    /// Saved keys offered after the session's own are refused. Left empty
+    /// only the session's keys are tried, like OpenSSH's IdentitiesOnly.
+    pub offer_keys: Vec<String>,
+}
+
+// What `authenticate` may use: the saved secrets first, then the prompter.
+struct Credentials {
+    password: Option<String>,
+    key_passphrase: Option<String>,
+    prompter: Option<Arc<dyn CredentialPrompter>>,
+    offer_keys: Vec<String>,
+    // Bounds each step of the exchange.
+    timeout: Duration,
+    // Passphrases typed for keys so far, each tried on the next key before
+    // asking again.
+    typed_passphrases: StdMutex<Vec<TypedPassphrase>>,
+}
+
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// Answers tried per credential before giving up, as ssh(1) does.
+const MAX_AUTH_ATTEMPTS: usize = 3;
+// Keyboard-interactive question rounds before the server is assumed stuck.
+const MAX_INTERACTIVE_ROUNDS: usize = 10;
 
 impl fmt::Debug for SshSession {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -64,96 +138,231 @@ impl SshSession {
         auth_method: AuthMethod,
         password: Option<String>,
         key_passphrase: Option<String>,
-    ) -> Result<(Self, mpsc::UnboundedReceiver<Vec<u8>>)> {
+        options: ConnectOptions,
+    ) -> Result<(Self, crate::terminal::OutputReceiver)> {
         tracing::info!("ssh connect start {}@{}:{}", username, host, port);
-        let config = client::Config {
+        let ConnectOptions {
+            debug_log,
+            pinned_host_key,
+            port_knock,
+            jump_host,
+            prompter,
+            keepalive,
+            forward_agent,
+            environment,
+            compression,
+            proxy,
+            connect_timeout,
+            offer_keys,
+        } = options;
+        let timeout = connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+        let debug = |line: String| {
+            if let Some(log) = &debug_log {
+                log.push(line);
+            }
+        };
+        let mut config = client::Config {
             inactivity_timeout: None,
-            keepalive_interval: Some(std::time::Duration::from_secs(KEEPALIVE_INTERVAL_SECS)),
-            keepalive_max: KEEPALIVE_MAX,
+            keepalive_interval: keepalive.interval(),
+            keepalive_max: keepalive.max_missed as usize,
             ..Default::default()
         };
+        // russh offers no compression first; the client's order decides.
+        config.preferred.compression = if compression {
+            Cow::Borrowed(&[
+                compression::ZLIB_LEGACY,
+                compression::ZLIB,
+                compression::NONE,
+            ])
+        } else {
+            Cow::Borrowed(&[compression::NONE])
+        };
+        debug(format!("connecting to {}:{} as {}", host, port, username));
+        if let Some(proxy) = &proxy {
+            debug(format!("connecting through proxy {}", proxy));
+        }
+        debug(match keepalive.interval() {
+            Some(_) => format!(
+                "keepalive every {}s, up to {} missed",
+                keepalive.interval_secs, keepalive.max_missed
+            ),
+            None => "keepalive off".to_string(),
+        });
+        debug(format!(
+            "client offers kex: {}",
+            join_names(config.preferred.kex.iter().map(|n| n.as_ref()))
+        ));
+        debug(format!(
+            "client offers host keys: {}",
+            join_names(config.preferred.key.iter().map(|a| a.as_str()))
+        ));
+        debug(format!(
+            "client offers ciphers: {}",
+            join_names(config.preferred.cipher.iter().map(|n| n.as_ref()))
+        ));
+        debug(format!(
+            "client offers macs: {}",
+            join_names(config.preferred.mac.iter().map(|n| n.as_ref()))
+        ));
+        debug(format!(
+            "client offers compression: {}",
+            join_names(config.preferred.compression.iter().map(|n| n.as_ref()))
+        ));
         let config = Arc::new(config);
 
         // Create the channel for received data
-        let (tx, rx) = mpsc::unbounded_channel();
+        let (tx, rx) = crate::terminal::output_channel();
 
         // Create the handler
         let shell_channel = Arc::new(StdMutex::new(None));
         let remote_forwards: RemoteForwardMap = Arc::new(StdMutex::new(HashMap::new()));
-        let sh = SshClient::new(tx, shell_channel.clone(), remote_forwards.clone());
+        let host_key: HostKeySlot = Arc::new(StdMutex::new(None));
+        let agent_forward = forward_agent.then(|| AgentForward {
+            target: format!("{}@{}", username, host),
+            host: host.to_string(),
+            prompter: prompter.clone(),
+        });
+        let sh = SshClient::new(
+            tx,
+            shell_channel.clone(),
+            remote_forwards.clone(),
+            debug_log.clone(),
+            host_key.clone(),
+            pinned_host_key,
+            agent_forward,
+        );
+        let status = sh.status();
+
+        if !port_knock.is_empty() {
+            debug(format!(
+                "knocking on {}",
+                KnockStep::format_sequence(&port_knock)
+            ));
+            super::knock::knock(host, &port_knock).await?;
+        }
 
         let addr = format!("{}:{}", host, port);
-        let timeout = std::time::Duration::from_secs(CONNECT_TIMEOUT_SECS);
-        let connect_result = tokio::time::timeout(timeout, async move {
-            let mut session = client::connect(config, addr, sh).await?;
-
-            match auth_method {
-                AuthMethod::Password => {
-                    let password = password.unwrap_or_default();
-                    if password.trim().is_empty() {
-                        return Err(anyhow::anyhow!("Password required for authentication"));
-                    }
-                    let auth_res = session.authenticate_password(username, password).await?;
-                    if !auth_res.success() {
-                        return Err(anyhow::anyhow!("Authentication failed"));
+        let bandwidth = Bandwidth::default();
+        let jump_debug_log = debug_log.clone();
+        let credentials = Credentials {
+            password,
+            key_passphrase,
+            prompter,
+            offer_keys,
+            timeout,
+            typed_passphrases: StdMutex::new(Vec::new()),
+        };
+        let connect_result: Result<(Self, crate::terminal::OutputReceiver)> = async move {
+            let (mut session, jump_session) = match jump_host {
+                None => {
+                    let socket =
+                        within_timeout(timeout, super::proxy::connect(proxy.as_ref(), host, port))
+                            .await??;
+                    if config.nodelay
+                        && let Err(e) = socket.set_nodelay(true)
+                    {
+                        tracing::warn!("set_nodelay failed: {}", e);
                     }
-                    tracing::info!("ssh auth success (password)");
+                    let stream = CountingStream::new(socket, bandwidth.clone());
+                    (
+                        within_timeout(timeout, client::connect_stream(config, stream, sh))
+                            .await??,
+                        None,
+                    )
                 }
-                AuthMethod::PrivateKey { path, key_id } => {
-                    let mut key_source: Option<String> = None;
-                    if let Some(id) = key_id.as_deref() {
-                        key_source = crate::settings::load_key_secret(id);
-                    }
-
-                    let key: PrivateKey = if let Some(secret) = key_source.as_deref() {
-                        decode_secret_key(secret, key_passphrase.as_deref())?
-                    } else if !path.trim().is_empty() {
-                        let expanded = Self::expand_tilde(&path);
-                        load_secret_key(&expanded, key_passphrase.as_deref())?
-                    } else {
-                        return Err(anyhow::anyhow!("Private key content is missing"));
-                    };
-                    let hash_alg = if key.algorithm().is_rsa() {
-                        session.best_supported_rsa_hash().await?.flatten()
-                    } else {
-                        None
-                    };
-                    let key_with_alg = PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg);
-                    let auth_res = session
-                        .authenticate_publickey(username, key_with_alg)
-                        .await?;
-                    if !auth_res.success() {
-                        return Err(anyhow::anyhow!("Authentication failed"));
-                    }
-                    tracing::info!("ssh auth success (public key)");
+                Some(jump) => {
+                    debug(format!("connecting through {}:{}", jump.host, jump.port));
+                    let socket = within_timeout(
+                        timeout,
+                        super::proxy::connect(proxy.as_ref(), &jump.host, jump.port),
+                    )
+                    .await?
+                    .with_context(|| format!("Failed to reach jump host {}", jump.host))?;
+                    // The bastion only carries the tunnel: no shell, no
+                    // forwards and no pinned key of its own.
+                    let jump_handler = SshClient::new(
+                        crate::terminal::output_channel().0,
+                        Arc::new(StdMutex::new(None)),
+                        Arc::new(StdMutex::new(HashMap::new())),
+                        jump_debug_log,
+                        Arc::new(StdMutex::new(None)),
+                        None,
+                        None,
+                    );
+                    let mut jump_session = within_timeout(
+                        timeout,
+                        client::connect_stream(config.clone(), socket, jump_handler),
+                    )
+                    .await??;
+                    authenticate(
+                        &mut jump_session,
+                        jump.username.as_deref().unwrap_or(username),
+                        &jump.host,
+                        &auth_method,
+                        &credentials,
+                        &debug,
+                    )
+                    .await
+                    .with_context(|| format!("Jump host {}", jump.host))?;
+                    let channel = within_timeout(
+                        timeout,
+                        jump_session.channel_open_direct_tcpip(host, port as u32, "127.0.0.1", 0),
+                    )
+                    .await?
+                    .with_context(|| format!("Jump host {} could not reach {}", jump.host, addr))?;
+                    let stream = CountingStream::new(channel.into_stream(), bandwidth.clone());
+                    (
+                        within_timeout(timeout, client::connect_stream(config, stream, sh))
+                            .await??,
+                        Some(jump_session),
+                    )
                 }
-            }
+            };
+
+            authenticate(
+                &mut session,
+                username,
+                host,
+                &auth_method,
+                &credentials,
+                &debug,
+            )
+            .await?;
 
             Ok((
                 Self {
                     session: Arc::new(AsyncMutex::new(session)),
                     active_channel: None,
                     shell_channel,
+                    extra_shells: Arc::new(StdMutex::new(HashMap::new())),
                     port_forwards: HashMap::new(),
                     remote_forwards,
+                    host_key,
+                    bandwidth,
+                    status,
+                    forward_agent,
+                    environment,
+                    shell_exit: None,
+                    typed_passphrases: credentials
+                        .typed_passphrases
+                        .lock()
+                        .map(|typed| typed.clone())
+                        .unwrap_or_default(),
+                    _jump_session: jump_session,
                 },
                 rx,
             ))
-        })
+        }
         .await;
 
-        match connect_result {
-            Ok(result) => {
-                if result.is_ok() {
-                    tracing::info!("ssh connect ok {}@{}:{}", username, host, port);
-                }
-                result
+        match &connect_result {
+            Ok(_) => {
+                tracing::info!("ssh connect ok {}@{}:{}", username, host, port);
+                debug("connected".to_string());
             }
-            Err(_) => Err(anyhow::anyhow!(
-                "Connection timeout ({}s)",
-                CONNECT_TIMEOUT_SECS
-            )),
+            Err(e) => debug(format!("connect failed: {:#}", e)),
         }
+        connect_result
     }
 
     fn expand_home(path: &str) -> Option<String> {
@@ -165,10 +374,32 @@ impl SshSession {
         Some(home.join(rest).to_string_lossy().to_string())
     }
 
-    fn expand_tilde(path: &str) -> String {
+    pub(super) fn expand_tilde(path: &str) -> String {
         Self::expand_home(path).unwrap_or_else(|| path.to_string())
     }
 
+    /// Running byte counts for everything sent over this connection.
+    pub fn bandwidth(&self) -> Bandwidth {
+        self.bandwidth.clone()
+    }
+
+    /// Whether the connection is still up, for checks that can't wait for
+    /// this session's lock.
+    pub fn status(&self) -> ConnectionStatus {
+        self.status.clone()
+    }
+
+    /// The key passphrases typed while connecting, in the order the keys
+    /// were unlocked.
+    pub fn take_typed_passphrases(&mut self) -> Vec<TypedPassphrase> {
+        std::mem::take(&mut self.typed_passphrases)
+    }
+
+    /// SHA256 fingerprint of the server's host key, e.g. `SHA256:abc...`.
+    pub fn host_key_fingerprint(&self) -> Option<String> {
+        self.host_key.lock().ok().and_then(|slot| slot.clone())
+    }
+
     #[allow(dead_code)]
     pub async fn call_password_auth(&mut self, username: &str, password: &str) -> Result<bool> {
         let mut session = self.session.lock().await;
@@ -176,16 +407,70 @@ impl SshSession {
         Ok(result.success())
     }
 
-    pub async fn open_shell(&mut self) -> Result<ChannelId> {
+    /// Opens the main shell. With `tmux_session` it attaches to that tmux
+    /// session, creating it if needed, or gets a plain shell where tmux
+    /// isn't installed. With `remote_command` it runs that instead of a
+    /// shell. See [`Self::take_shell_exit`] for when it ends.
+    pub async fn open_shell(
+        &mut self,
+        tmux_session: Option<&str>,
+        remote_command: Option<&str>,
+    ) -> Result<ChannelId> {
         let session = self.session.lock().await;
         let channel = session.channel_open_session().await?;
         channel
             .request_pty(true, "xterm-256color", 80, 24, 0, 0, &[])
             .await?;
-        channel.request_shell(true).await?;
+        if self.forward_agent {
+            channel.agent_forward(false).await?;
+        }
+        // Without a reply asked for, variables the server refuses are
+        // dropped instead of failing the shell.
+        for (name, value) in &self.environment {
+            channel.set_env(false, name.clone(), value.clone()).await?;
+        }
+        match (remote_command, tmux_session) {
+            (Some(command), _) => channel.exec(true, command).await?,
+            (None, Some(name)) => {
+                let command = format!(
+                    "if command -v tmux >/dev/null 2>&1; then exec tmux new -A -s {}; fi; \
+                     exec \"${{SHELL:-/bin/sh}}\" -l",
+                    shell_quote(name)
+                );
+                channel.exec(true, command).await?;
+            }
+            (None, None) => channel.request_shell(true).await?,
+        }
         let id = channel.id();
         let (mut read_half, write_half) = channel.split();
-        tokio::spawn(async move { while let Some(_msg) = read_half.wait().await {} });
+        let (exit_tx, exit_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let mut exit_status = None;
+            let mut ended = false;
+            while let Some(msg) = read_half.wait().await {
+                match msg {
+                    russh::ChannelMsg::ExitStatus { exit_status: code } => {
+                        exit_status = Some(code);
+                        ended = true;
+                    }
+                    russh::ChannelMsg::ExitSignal { .. } | russh::ChannelMsg::Eof => {
+                        ended = true;
+                    }
+                    russh::ChannelMsg::Close => {
+                        ended = true;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            // The client drops a channel the server closes without passing
+            // `Close` on, so the command counts as ended once the server said
+            // so; a channel that stops with the connection reports nothing.
+            if ended {
+                let _ = exit_tx.send(exit_status);
+            }
+        });
+        self.shell_exit = Some(exit_rx);
         self.active_channel = Some(write_half);
         if let Ok(mut guard) = self.shell_channel.lock() {
             *guard = Some(id);
@@ -193,6 +478,100 @@ impl SshSession {
         Ok(id)
     }
 
+    /// Resolves once the main shell or its remote command has ended, with
+    /// its exit status when the server sent one. Taken once per shell.
+    pub fn take_shell_exit(&mut self) -> Option<oneshot::Receiver<Option<u32>>> {
+        self.shell_exit.take()
+    }
+
+    /// Opens another shell on this connection, starting in `directory` when
+    /// given. Its output goes to the returned receiver, which ends when the
+    /// shell exits.
+    pub async fn open_extra_shell(
+        &mut self,
+        directory: Option<&str>,
+        cols: u32,
+        rows: u32,
+    ) -> Result<(ChannelId, crate::terminal::OutputReceiver)> {
+        let session = self.session.lock().await;
+        let channel = session.channel_open_session().await?;
+        channel
+            .request_pty(true, "xterm-256color", cols, rows, 0, 0, &[])
+            .await?;
+        if self.forward_agent {
+            channel.agent_forward(false).await?;
+        }
+        for (name, value) in &self.environment {
+            channel.set_env(false, name.clone(), value.clone()).await?;
+        }
+        match directory {
+            // A missing directory still gets a shell, just in the home one.
+            Some(directory) => {
+                let command = format!(
+                    "cd {} 2>/dev/null; exec \"${{SHELL:-/bin/sh}}\" -l",
+                    shell_quote(directory)
+                );
+                channel.exec(true, command).await?;
+            }
+            None => channel.request_shell(true).await?,
+        }
+        let id = channel.id();
+        let (mut read_half, write_half) = channel.split();
+        if let Ok(mut shells) = self.extra_shells.lock() {
+            shells.insert(id, Arc::new(write_half));
+        }
+        let (tx, rx) = crate::terminal::output_channel();
+        let extra_shells = self.extra_shells.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = read_half.wait().await {
+                match msg {
+                    russh::ChannelMsg::Data { data }
+                    | russh::ChannelMsg::ExtendedData { data, .. }
+                        if tx.send(data.to_vec()).is_err() =>
+                    {
+                        break;
+                    }
+                    russh::ChannelMsg::Close => break,
+                    _ => {}
+                }
+            }
+            if let Ok(mut shells) = extra_shells.lock() {
+                shells.remove(&id);
+            }
+        });
+        Ok((id, rx))
+    }
+
+    /// Closes the shell on `channel_id`, leaving the connection and any
+    /// other shells on it open.
+    pub async fn close_shell(&mut self, channel_id: ChannelId) {
+        let extra = self
+            .extra_shells
+            .lock()
+            .ok()
+            .and_then(|mut shells| shells.remove(&channel_id));
+        let result = match extra {
+            Some(channel) => channel.close().await,
+            None if self.shell_channel.lock().ok().and_then(|id| *id) == Some(channel_id) => {
+                match self.active_channel.take() {
+                    Some(channel) => channel.close().await,
+                    None => Ok(()),
+                }
+            }
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("closing shell channel {:?} failed: {}", channel_id, e);
+        }
+    }
+
+    /// Whether the connection is still up. Assumed so while it is busy.
+    pub fn is_connected(&self) -> bool {
+        self.session
+            .try_lock()
+            .map_or(true, |session| !session.is_closed())
+    }
+
     pub async fn open_sftp(&mut self) -> Result<SftpSession> {
         let session = self.session.lock().await;
         let channel = session.channel_open_session().await?;
@@ -201,6 +580,60 @@ impl SshSession {
         Ok(sftp)
     }
 
+    /// Opens an SFTP channel of its own for a single file transfer. It closes
+    /// once the channel and every file opened through it are dropped.
+    pub async fn open_transfer_channel(&self) -> Result<TransferChannel> {
+        let session = self.session.lock().await;
+        let channel = session.channel_open_session().await?;
+        channel.request_subsystem(true, "sftp").await?;
+        let sftp = RawSftpSession::new(channel.into_stream());
+        Ok(TransferChannel::new(sftp).await?)
+    }
+
+    /// Runs `command` on a separate exec channel and streams stdout/stderr.
+    /// Dropping the returned receiver closes the channel.
+    pub async fn exec_stream(&self, command: &str) -> Result<mpsc::UnboundedReceiver<Vec<u8>>> {
+        let session = self.session.lock().await;
+        let mut channel = session.channel_open_session().await?;
+        channel.exec(true, command).await?;
+        drop(session);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    msg = channel.wait() => match msg {
+                        Some(russh::ChannelMsg::Data { data }) => {
+                            if tx.send(data.to_vec()).is_err() {
+                                break;
+                            }
+                        }
+                        Some(russh::ChannelMsg::ExtendedData { data, .. }) => {
+                            if tx.send(data.to_vec()).is_err() {
+                                break;
+                            }
+                        }
+                        Some(russh::ChannelMsg::Eof) | Some(russh::ChannelMsg::Close) | None => {
+                            break;
+                        }
+                        Some(_) => {}
+                    },
+                    _ = tx.closed() => break,
+                }
+            }
+            let _ = channel.close().await;
+        });
+        Ok(rx)
+    }
+
+    pub async fn disconnect(&self, reason: &str) -> Result<()> {
+        let session = self.session.lock().await;
+        session
+            .disconnect(russh::Disconnect::ByApplication, reason, "en")
+            .await?;
+        Ok(())
+    }
+
     pub async fn write_data(&mut self, channel_id: ChannelId, data: &[u8]) -> Result<()> {
         let data = russh::CryptoVec::from_slice(data);
         tracing::debug!("write {} bytes on channel {:?}", data.len(), channel_id);
@@ -213,8 +646,16 @@ impl SshSession {
         }
     }
 
-    pub async fn resize(&mut self, _channel_id: ChannelId, cols: u32, rows: u32) -> Result<()> {
-        if let Some(channel) = self.active_channel.as_mut() {
+    pub async fn resize(&mut self, channel_id: ChannelId, cols: u32, rows: u32) -> Result<()> {
+        let extra = self
+            .extra_shells
+            .lock()
+            .ok()
+            .and_then(|shells| shells.get(&channel_id).cloned());
+        if let Some(channel) = extra {
+            channel.window_change(cols, rows, 0, 0).await?;
+            Ok(())
+        } else if let Some(channel) = self.active_channel.as_mut() {
             channel.window_change(cols, rows, 0, 0).await?;
             Ok(())
         } else {
@@ -640,3 +1081,508 @@ async fn handle_socks5(
     let _ = tokio::io::copy_bidirectional(&mut channel_stream, stream).await;
     Ok(())
 }
+
+/// Authenticates `session` as `username` with the saved credentials, asking
+/// the prompter for any that are missing or rejected.
+async fn authenticate(
+    session: &mut client::Handle<SshClient>,
+    username: &str,
+    host: &str,
+    auth_method: &AuthMethod,
+    credentials: &Credentials,
+    debug: &impl Fn(String),
+) -> Result<()> {
+    let target = format!("{}@{}", username, host);
+    let prompter = credentials.prompter.as_deref();
+    let touch = |waiting| {
+        if let Some(prompter) = prompter {
+            prompter.security_key_touch(&target, waiting);
+        }
+    };
+    match auth_method {
+        AuthMethod::Password => {
+            let mut saved = credentials
+                .password
+                .clone()
+                .filter(|password| !password.trim().is_empty());
+            let mut request = CredentialRequest::new(CredentialKind::Password, &target);
+            for _ in 0..MAX_AUTH_ATTEMPTS {
+                let password = match (saved.take(), prompter) {
+                    (Some(password), _) => password,
+                    (None, Some(prompter)) => prompt::ask(prompter, request.clone()).await?.value,
+                    (None, None) => {
+                        return Err(anyhow::anyhow!("Password required for authentication"));
+                    }
+                };
+                debug(format!("auth: trying password for {}", username));
+                let auth_res = within_timeout(
+                    credentials.timeout,
+                    session.authenticate_password(username, password),
+                )
+                .await??;
+                debug(format!("auth: password -> {}", describe_auth(&auth_res)));
+                match auth_res {
+                    client::AuthResult::Success => {
+                        tracing::info!("ssh auth success (password)");
+                        return Ok(());
+                    }
+                    // Servers that check passwords through PAM often accept
+                    // them only as keyboard-interactive answers.
+                    client::AuthResult::Failure {
+                        remaining_methods, ..
+                    } if !remaining_methods.contains(&MethodKind::Password)
+                        && remaining_methods.contains(&MethodKind::KeyboardInteractive) =>
+                    {
+                        let Some(prompter) = prompter else {
+                            return Err(anyhow::anyhow!("Authentication failed"));
+                        };
+                        return keyboard_interactive(
+                            session,
+                            username,
+                            &target,
+                            prompter,
+                            credentials.timeout,
+                            debug,
+                        )
+                        .await;
+                    }
+                    client::AuthResult::Failure { .. } if prompter.is_some() => {
+                        request.retry = true;
+                    }
+                    client::AuthResult::Failure { .. } => {
+                        return Err(anyhow::anyhow!("Authentication failed"));
+                    }
+                }
+            }
+            Err(anyhow::anyhow!("Authentication failed"))
+        }
+        AuthMethod::PrivateKey {
+            path,
+            key_id,
+            fallback_key_ids,
+        } => {
+            let identities = identities(key_id, path, fallback_key_ids, &credentials.offer_keys);
+            // A key that can't be read or unlocked fails the connection
+            // only when no key could be offered at all.
+            let mut unusable = None;
+            let mut offered = false;
+            for (index, (key_id, path)) in identities.iter().enumerate() {
+                let own_key = index == 0;
+                let key =
+                    match load_key(key_id.as_deref(), path, own_key, &target, credentials).await {
+                        Ok(key) => key,
+                        Err(e) if identities.len() > 1 => {
+                            debug(format!("auth: skipping key: {:#}", e));
+                            unusable.get_or_insert(e);
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    };
+                if super::agent::is_security_key(&key.algorithm()) {
+                    debug(format!(
+                        "auth: {} is a security key, signing through ssh-agent",
+                        key.algorithm()
+                    ));
+                    let public = Some(key.public_key());
+                    offered = true;
+                    match super::agent::authenticate(
+                        session,
+                        username,
+                        public,
+                        credentials.timeout,
+                        &touch,
+                        debug,
+                    )
+                    .await
+                    {
+                        Ok(()) => return Ok(()),
+                        Err(e) if identities.len() > 1 => {
+                            debug(format!("auth: security key -> {:#}", e));
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                let hash_alg = if key.algorithm().is_rsa() {
+                    within_timeout(credentials.timeout, session.best_supported_rsa_hash())
+                        .await??
+                        .flatten()
+                } else {
+                    None
+                };
+                debug(format!(
+                    "auth: trying public key {} (hash {:?}) for {}",
+                    key.algorithm(),
+                    hash_alg,
+                    username
+                ));
+                let key_with_alg = PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg);
+                offered = true;
+                let auth_res = within_timeout(
+                    credentials.timeout,
+                    session.authenticate_publickey(username, key_with_alg),
+                )
+                .await??;
+                debug(format!("auth: public key -> {}", describe_auth(&auth_res)));
+                if auth_res.success() {
+                    tracing::info!("ssh auth success (public key)");
+                    return Ok(());
+                }
+            }
+            match unusable {
+                Some(e) if !offered => Err(e),
+                _ => Err(anyhow::anyhow!("Authentication failed")),
+            }
+        }
+        AuthMethod::Agent => {
+            super::agent::authenticate(session, username, None, credentials.timeout, &touch, debug)
+                .await
+        }
+    }
+}
+
+// The keys to try in order: the session's own, its fallbacks, then the
+// other saved keys on offer, each once. Keys other than the first are only
+// known by their saved id.
+fn identities(
+    key_id: &Option<String>,
+    path: &str,
+    fallback_key_ids: &[String],
+    offer_keys: &[String],
+) -> Vec<(Option<String>, String)> {
+    let mut identities = vec![(key_id.clone(), path.to_string())];
+    for id in fallback_key_ids.iter().chain(offer_keys) {
+        if !identities
+            .iter()
+            .any(|(seen, _)| seen.as_deref() == Some(id.as_str()))
+        {
+            identities.push((Some(id.clone()), String::new()));
+        }
+    }
+    identities
+}
+
+async fn load_key(
+    key_id: Option<&str>,
+    path: &str,
+    own_key: bool,
+    target: &str,
+    credentials: &Credentials,
+) -> Result<PrivateKey> {
+    let secret = match key_id.and_then(crate::settings::load_key_secret) {
+        Some(secret) => secret,
+        None if !path.trim().is_empty() => {
+            let expanded = SshSession::expand_tilde(path);
+            std::fs::read_to_string(&expanded)
+                .with_context(|| format!("Failed to read key {}", expanded))?
+        }
+        None => return Err(anyhow::anyhow!("Private key content is missing")),
+    };
+    let mut request = CredentialRequest::new(CredentialKind::KeyPassphrase, target);
+    request.can_remember = own_key;
+    let (key, typed) = decrypt_key(&secret, request, credentials).await?;
+    if let (Some(answer), Ok(mut typed_passphrases)) = (typed, credentials.typed_passphrases.lock())
+    {
+        typed_passphrases.push(TypedPassphrase {
+            target: target.to_string(),
+            key_id: key_id.map(str::to_string),
+            path: path.to_string(),
+            passphrase: answer.value,
+            remember: own_key && answer.remember,
+        });
+    }
+    Ok(key)
+}
+
+// Tries the saved passphrase and the ones typed for earlier keys, then asks
+// for one while the key stays locked. Returns what was typed, if anything.
+async fn decrypt_key(
+    secret: &str,
+    mut request: CredentialRequest,
+    credentials: &Credentials,
+) -> Result<(PrivateKey, Option<CredentialAnswer>)> {
+    let mut known: Vec<String> = credentials
+        .key_passphrase
+        .iter()
+        .filter(|passphrase| !passphrase.is_empty())
+        .cloned()
+        .collect();
+    if let Ok(typed) = credentials.typed_passphrases.lock() {
+        known.extend(typed.iter().map(|typed| typed.passphrase.clone()));
+    }
+    let mut error = None;
+    let tries: Vec<Option<&str>> = if known.is_empty() {
+        vec![None]
+    } else {
+        known
+            .iter()
+            .map(|passphrase| Some(passphrase.as_str()))
+            .collect()
+    };
+    for passphrase in tries {
+        match decode_secret_key(secret, passphrase) {
+            Ok(key) => return Ok((key, None)),
+            Err(e) => error = Some(e),
+        }
+    }
+    let encrypted = matches!(
+        decode_secret_key(secret, None),
+        Err(russh::keys::Error::KeyIsEncrypted)
+    );
+    if let Some(prompter) = credentials.prompter.as_deref().filter(|_| encrypted) {
+        request.retry = !known.is_empty();
+        for _ in 0..MAX_AUTH_ATTEMPTS {
+            let answer = prompt::ask(prompter, request.clone()).await?;
+            match decode_secret_key(secret, Some(&answer.value)) {
+                Ok(key) => return Ok((key, Some(answer))),
+                Err(e) => error = Some(e),
+            }
+            request.retry = true;
+        }
+    }
+    Err(error.map_or_else(|| anyhow::anyhow!("Failed to load key"), Into::into))
+}
+
+// Puts each of the server's questions to the user until it accepts or
+// rejects the answers.
+async fn keyboard_interactive(
+    session: &mut client::Handle<SshClient>,
+    username: &str,
+    target: &str,
+    prompter: &dyn CredentialPrompter,
+    timeout: Duration,
+    debug: &impl Fn(String),
+) -> Result<()> {
+    debug(format!(
+        "auth: trying keyboard-interactive for {}",
+        username
+    ));
+    let mut response = within_timeout(
+        timeout,
+        session.authenticate_keyboard_interactive_start(username, None::<String>),
+    )
+    .await??;
+    for _ in 0..MAX_INTERACTIVE_ROUNDS {
+        let prompts = match response {
+            client::KeyboardInteractiveAuthResponse::Success => {
+                debug("auth: keyboard-interactive -> accepted".to_string());
+                tracing::info!("ssh auth success (keyboard-interactive)");
+                return Ok(());
+            }
+            client::KeyboardInteractiveAuthResponse::Failure { .. } => {
+                debug("auth: keyboard-interactive -> rejected".to_string());
+                return Err(anyhow::anyhow!("Authentication failed"));
+            }
+            client::KeyboardInteractiveAuthResponse::InfoRequest { prompts, .. } => prompts,
+        };
+        let mut answers = Vec::with_capacity(prompts.len());
+        for question in prompts {
+            let request = CredentialRequest {
+                prompt: question.prompt.trim().to_string(),
+                echo: question.echo,
+                ..CredentialRequest::new(CredentialKind::KeyboardInteractive, target)
+            };
+            answers.push(prompt::ask(prompter, request).await?.value);
+        }
+        response = within_timeout(
+            timeout,
+            session.authenticate_keyboard_interactive_respond(answers),
+        )
+        .await??;
+    }
+    Err(anyhow::anyhow!("Authentication failed"))
+}
+
+// Bounds one network step of connecting. Time spent waiting on the user for
+// a credential is not counted.
+pub(super) async fn within_timeout<F: std::future::Future>(
+    timeout: Duration,
+    future: F,
+) -> Result<F::Output> {
+    tokio::time::timeout(timeout, future)
+        .await
+        .map_err(|_| anyhow::anyhow!("Connection timeout ({}s)", timeout.as_secs()))
+}
+
+// Single-quoted for the remote shell.
+pub fn shell_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', "'\\''"))
+}
+
+fn join_names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    names.collect::<Vec<_>>().join(",")
+}
+
+pub(super) fn describe_auth(result: &client::AuthResult) -> String {
+    match result {
+        client::AuthResult::Success => "accepted".to_string(),
+        client::AuthResult::Failure {
+            remaining_methods,
+            partial_success,
+        } => format!(
+            "rejected (remaining: {:?}, partial success: {})",
+            remaining_methods, partial_success
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::test_server::{PASSWORD, ServerEvent, TestServer, read_until};
+
+    #[tokio::test]
+    async fn authenticates_with_a_password() {
+        let server = TestServer::start().await;
+        let (session, _output) = server.connect().await.unwrap();
+        assert!(session.is_connected());
+        assert_eq!(
+            session.host_key_fingerprint(),
+            Some(server.host_key_fingerprint.clone())
+        );
+
+        let err = server
+            .connect_with("wrong", ConnectOptions::default())
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Authentication failed"));
+    }
+
+    #[tokio::test]
+    async fn status_follows_the_connection_without_its_lock() {
+        let server = TestServer::start().await;
+        let (session, _output) = server.connect().await.unwrap();
+        let status = session.status();
+        let session = AsyncMutex::new(session);
+        let guard = session.lock().await;
+        assert!(status.is_up());
+
+        guard.disconnect("bye").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while status.is_up() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn refuses_a_host_key_other_than_the_pinned_one() {
+        let server = TestServer::start().await;
+        let options = ConnectOptions {
+            pinned_host_key: Some("SHA256:someone-else".to_string()),
+            ..Default::default()
+        };
+        let err = server.connect_with(PASSWORD, options).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Host key mismatch"));
+    }
+
+    #[tokio::test]
+    async fn shell_carries_input_output_and_resizes() {
+        let mut server = TestServer::start().await;
+        let (mut session, mut output) = server.connect().await.unwrap();
+        let channel = session.open_shell(None, None).await.unwrap();
+        assert_eq!(
+            server.next_event().await,
+            ServerEvent::Pty { cols: 80, rows: 24 }
+        );
+
+        session.write_data(channel, b"ls -la\r").await.unwrap();
+        read_until(&mut output, b"ls -la\r").await;
+
+        session.resize(channel, 132, 43).await.unwrap();
+        assert_eq!(
+            server.next_event().await,
+            ServerEvent::Resized {
+                cols: 132,
+                rows: 43
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn sets_the_environment_before_the_shell() {
+        let mut server = TestServer::start().await;
+        let options = ConnectOptions {
+            environment: vec![
+                ("LANG".to_string(), "C.UTF-8".to_string()),
+                ("EDITOR".to_string(), "vim".to_string()),
+            ],
+            ..Default::default()
+        };
+        let (mut session, _output) = server.connect_with(PASSWORD, options).await.unwrap();
+        session.open_shell(None, Some("env")).await.unwrap();
+        assert!(matches!(server.next_event().await, ServerEvent::Pty { .. }));
+        assert_eq!(
+            server.next_event().await,
+            ServerEvent::Env("LANG".to_string(), "C.UTF-8".to_string())
+        );
+        assert_eq!(
+            server.next_event().await,
+            ServerEvent::Env("EDITOR".to_string(), "vim".to_string())
+        );
+        assert_eq!(
+            server.next_event().await,
+            ServerEvent::Exec("env".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_the_exit_status_of_a_remote_command() {
+        let mut server = TestServer::start().await;
+        let (mut session, _output) = server.connect().await.unwrap();
+        session
+            .open_shell(Some("main"), Some("exit 3"))
+            .await
+            .unwrap();
+        assert!(matches!(server.next_event().await, ServerEvent::Pty { .. }));
+        // The remote command wins over the tmux session.
+        assert_eq!(
+            server.next_event().await,
+            ServerEvent::Exec("exit 3".to_string())
+        );
+
+        let exit = session.take_shell_exit().unwrap();
+        let status = tokio::time::timeout(std::time::Duration::from_secs(5), exit)
+            .await
+            .unwrap();
+        assert_eq!(status, Ok(Some(3)));
+    }
+
+    #[test]
+    fn tries_each_key_once_in_order() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let tried = identities(
+            &Some("work".to_string()),
+            "~/.ssh/work",
+            &ids(&["old", "work"]),
+            &ids(&["home", "old", "work"]),
+        );
+        assert_eq!(
+            tried,
+            [
+                (Some("work".to_string()), "~/.ssh/work".to_string()),
+                (Some("old".to_string()), String::new()),
+                (Some("home".to_string()), String::new()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_the_exit_status_of_an_interactive_shell() {
+        let mut server = TestServer::start().await;
+        let (mut session, _output) = server.connect().await.unwrap();
+        let channel = session.open_shell(None, None).await.unwrap();
+        assert!(matches!(server.next_event().await, ServerEvent::Pty { .. }));
+
+        let exit = session.take_shell_exit().unwrap();
+        session.write_data(channel, b"exit 0\r").await.unwrap();
+        let status = tokio::time::timeout(std::time::Duration::from_secs(5), exit)
+            .await
+            .unwrap();
+        assert_eq!(status, Ok(Some(0)));
+        // Only the shell ended; the connection is still up.
+        assert!(session.is_connected());
+    }
+}
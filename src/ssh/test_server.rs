@@ -0,0 +1,539 @@
+//! An SSH and SFTP server running inside the test process, so connecting,
+//! authentication, shell I/O and transfers can be tested without a host.
+
+use anyhow::Result;
+use russh::keys::ssh_key::rand_core::OsRng;
+use russh::keys::{Algorithm, HashAlg, PrivateKey};
+use russh::server::{self, Auth, Msg, Session};
+use russh::{Channel, ChannelId, CryptoVec};
+use russh_sftp::protocol::{
+    Attrs, Data, File, FileAttributes, Handle, Name, OpenFlags, Status, StatusCode, Version,
+};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::{ConnectOptions, SshSession};
+use crate::session::config::AuthMethod;
+use crate::terminal::OutputReceiver;
+
+pub const USER: &str = "tester";
+pub const PASSWORD: &str = "secret";
+
+// Longest a test waits for something the server should do.
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Requests the server received, in order, for tests to check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerEvent {
+    Pty { cols: u32, rows: u32 },
+    Resized { cols: u32, rows: u32 },
+    Env(String, String),
+    Exec(String),
+}
+
+/// Accepts any number of connections on a local port until dropped.
+///
+/// A shell echoes what it is sent, and ends with status N when sent
+/// `exit N` and Enter. An exec of `exit N` ends at once with status N; any
+/// other command stays open and echoes like a shell. SFTP
+/// serves a temporary directory as `/`.
+pub struct TestServer {
+    pub port: u16,
+    pub root: PathBuf,
+    pub host_key_fingerprint: String,
+    events: mpsc::UnboundedReceiver<ServerEvent>,
+    accept: JoinHandle<()>,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        let key = PrivateKey::random(&mut OsRng, Algorithm::Ed25519).expect("host key");
+        let host_key_fingerprint = key.public_key().fingerprint(HashAlg::Sha256).to_string();
+        let config = Arc::new(server::Config {
+            keys: vec![key],
+            auth_rejection_time: Duration::from_millis(10),
+            auth_rejection_time_initial: Some(Duration::ZERO),
+            ..Default::default()
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let port = listener.local_addr().expect("local address").port();
+        let root = std::env::temp_dir().join(format!("rivett-sshd-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).expect("server root");
+
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let files_root = root.clone();
+        let accept = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = Connection {
+                    root: files_root.clone(),
+                    events: events_tx.clone(),
+                    channels: HashMap::new(),
+                    shells: HashSet::new(),
+                };
+                let config = config.clone();
+                tokio::spawn(async move {
+                    if let Ok(session) = server::run_stream(config, stream, handler).await {
+                        let _ = session.await;
+                    }
+                });
+            }
+        });
+
+        Self {
+            port,
+            root,
+            host_key_fingerprint,
+            events,
+            accept,
+        }
+    }
+
+    /// Connects as the test user with the right password.
+    pub async fn connect(&self) -> Result<(SshSession, OutputReceiver)> {
+        self.connect_with(PASSWORD, ConnectOptions::default()).await
+    }
+
+    pub async fn connect_with(
+        &self,
+        password: &str,
+        options: ConnectOptions,
+    ) -> Result<(SshSession, OutputReceiver)> {
+        SshSession::connect(
+            "127.0.0.1",
+            self.port,
+            USER,
+            AuthMethod::Password,
+            Some(password.to_string()),
+            None,
+            options,
+        )
+        .await
+    }
+
+    /// The next request the server saw, waiting briefly for it.
+    pub async fn next_event(&mut self) -> ServerEvent {
+        tokio::time::timeout(EVENT_TIMEOUT, self.events.recv())
+            .await
+            .expect("timed out waiting for the server")
+            .expect("server stopped")
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Reads terminal output until `expected` has arrived, failing the test if
+/// it doesn't come in time.
+pub async fn read_until(output: &mut OutputReceiver, expected: &[u8]) -> Vec<u8> {
+    let mut received = Vec::new();
+    while !received.windows(expected.len()).any(|w| w == expected) {
+        let chunk = tokio::time::timeout(EVENT_TIMEOUT, output.recv())
+            .await
+            .unwrap_or_else(|_| {
+                panic!(
+                    "timed out waiting for {:?}, got {:?}",
+                    String::from_utf8_lossy(expected),
+                    String::from_utf8_lossy(&received)
+                )
+            })
+            .expect("output closed");
+        received.extend(chunk);
+    }
+    received
+}
+
+struct Connection {
+    root: PathBuf,
+    events: mpsc::UnboundedSender<ServerEvent>,
+    // Session channels, kept until a shell, command or subsystem claims one.
+    channels: HashMap<ChannelId, Channel<Msg>>,
+    // Channels running a shell or command, which echo their input.
+    shells: HashSet<ChannelId>,
+}
+
+impl Connection {
+    fn event(&self, event: ServerEvent) {
+        let _ = self.events.send(event);
+    }
+
+    // Data is answered from `data`, so the channel's own queue is dropped
+    // rather than left to fill up.
+    fn start_shell(&mut self, channel: ChannelId) {
+        self.channels.remove(&channel);
+        self.shells.insert(channel);
+    }
+}
+
+impl server::Handler for Connection {
+    type Error = anyhow::Error;
+
+    async fn auth_password(&mut self, user: &str, password: &str) -> Result<Auth> {
+        Ok(if user == USER && password == PASSWORD {
+            Auth::Accept
+        } else {
+            Auth::reject()
+        })
+    }
+
+    async fn channel_open_session(
+        &mut self,
+        channel: Channel<Msg>,
+        _session: &mut Session,
+    ) -> Result<bool> {
+        self.channels.insert(channel.id(), channel);
+        Ok(true)
+    }
+
+    async fn pty_request(
+        &mut self,
+        channel: ChannelId,
+        _term: &str,
+        cols: u32,
+        rows: u32,
+        _pix_width: u32,
+        _pix_height: u32,
+        _modes: &[(russh::Pty, u32)],
+        session: &mut Session,
+    ) -> Result<()> {
+        self.event(ServerEvent::Pty { cols, rows });
+        session.channel_success(channel)?;
+        Ok(())
+    }
+
+    async fn env_request(
+        &mut self,
+        _channel: ChannelId,
+        name: &str,
+        value: &str,
+        _session: &mut Session,
+    ) -> Result<()> {
+        self.event(ServerEvent::Env(name.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    async fn shell_request(&mut self, channel: ChannelId, session: &mut Session) -> Result<()> {
+        self.start_shell(channel);
+        session.channel_success(channel)?;
+        Ok(())
+    }
+
+    async fn exec_request(
+        &mut self,
+        channel: ChannelId,
+        data: &[u8],
+        session: &mut Session,
+    ) -> Result<()> {
+        let command = String::from_utf8_lossy(data).into_owned();
+        let exit_code = exit_code(&command);
+        self.event(ServerEvent::Exec(command));
+        self.start_shell(channel);
+        session.channel_success(channel)?;
+        if let Some(code) = exit_code {
+            exit_shell(channel, code, session)?;
+        }
+        Ok(())
+    }
+
+    async fn data(&mut self, channel: ChannelId, data: &[u8], session: &mut Session) -> Result<()> {
+        if self.shells.contains(&channel) {
+            session.data(channel, CryptoVec::from_slice(data))?;
+            let line = String::from_utf8_lossy(data);
+            if let Some(code) = line.strip_suffix('\r').and_then(exit_code) {
+                exit_shell(channel, code, session)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn window_change_request(
+        &mut self,
+        _channel: ChannelId,
+        cols: u32,
+        rows: u32,
+        _pix_width: u32,
+        _pix_height: u32,
+        _session: &mut Session,
+    ) -> Result<()> {
+        self.event(ServerEvent::Resized { cols, rows });
+        Ok(())
+    }
+
+    async fn subsystem_request(
+        &mut self,
+        channel: ChannelId,
+        name: &str,
+        session: &mut Session,
+    ) -> Result<()> {
+        match self.channels.remove(&channel) {
+            Some(stream) if name == "sftp" => {
+                session.channel_success(channel)?;
+                russh_sftp::server::run(stream.into_stream(), Files::new(self.root.clone())).await;
+            }
+            _ => session.channel_failure(channel)?,
+        }
+        Ok(())
+    }
+}
+
+enum OpenHandle {
+    File(std::fs::File),
+    // Entries not yet sent; a listing goes out in one batch.
+    Dir(Option<Vec<File>>),
+}
+
+/// SFTP on a local directory, enough for the client's listing and transfer
+/// code. Paths are taken relative to the root whatever their form.
+struct Files {
+    root: PathBuf,
+    handles: HashMap<String, OpenHandle>,
+    next_handle: u64,
+}
+
+impl Files {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            handles: HashMap::new(),
+            next_handle: 0,
+        }
+    }
+
+    fn local(&self, path: &str) -> PathBuf {
+        self.root.join(normalize(path).trim_start_matches('/'))
+    }
+
+    fn insert(&mut self, id: u32, open: OpenHandle) -> Handle {
+        self.next_handle += 1;
+        let handle = self.next_handle.to_string();
+        self.handles.insert(handle.clone(), open);
+        Handle { id, handle }
+    }
+
+    fn file(&mut self, handle: &str) -> Result<&mut std::fs::File, StatusCode> {
+        match self.handles.get_mut(handle) {
+            Some(OpenHandle::File(file)) => Ok(file),
+            _ => Err(StatusCode::Failure),
+        }
+    }
+}
+
+/// N in `exit N`.
+fn exit_code(command: &str) -> Option<u32> {
+    command.strip_prefix("exit ")?.trim().parse().ok()
+}
+
+fn exit_shell(channel: ChannelId, code: u32, session: &mut Session) -> Result<()> {
+    session.exit_status_request(channel, code)?;
+    session.eof(channel)?;
+    session.close(channel)?;
+    Ok(())
+}
+
+/// `path` as an absolute path with `.` and `..` resolved.
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+fn status_code(error: std::io::Error) -> StatusCode {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => StatusCode::NoSuchFile,
+        std::io::ErrorKind::PermissionDenied => StatusCode::PermissionDenied,
+        _ => StatusCode::Failure,
+    }
+}
+
+fn ok(id: u32) -> Status {
+    Status {
+        id,
+        status_code: StatusCode::Ok,
+        error_message: "Ok".to_string(),
+        language_tag: "en-US".to_string(),
+    }
+}
+
+fn attrs(id: u32, path: &Path) -> Result<Attrs, StatusCode> {
+    let metadata = std::fs::metadata(path).map_err(status_code)?;
+    Ok(Attrs {
+        id,
+        attrs: FileAttributes::from(&metadata),
+    })
+}
+
+impl russh_sftp::server::Handler for Files {
+    type Error = StatusCode;
+
+    fn unimplemented(&self) -> Self::Error {
+        StatusCode::OpUnsupported
+    }
+
+    async fn init(
+        &mut self,
+        _version: u32,
+        _extensions: HashMap<String, String>,
+    ) -> Result<Version, Self::Error> {
+        Ok(Version::new())
+    }
+
+    async fn realpath(&mut self, id: u32, path: String) -> Result<Name, Self::Error> {
+        Ok(Name {
+            id,
+            files: vec![File::dummy(normalize(&path))],
+        })
+    }
+
+    async fn open(
+        &mut self,
+        id: u32,
+        filename: String,
+        pflags: OpenFlags,
+        _attrs: FileAttributes,
+    ) -> Result<Handle, Self::Error> {
+        let file = std::fs::OpenOptions::from(pflags)
+            .open(self.local(&filename))
+            .map_err(status_code)?;
+        Ok(self.insert(id, OpenHandle::File(file)))
+    }
+
+    async fn close(&mut self, id: u32, handle: String) -> Result<Status, Self::Error> {
+        self.handles.remove(&handle);
+        Ok(ok(id))
+    }
+
+    async fn read(
+        &mut self,
+        id: u32,
+        handle: String,
+        offset: u64,
+        len: u32,
+    ) -> Result<Data, Self::Error> {
+        let file = self.file(&handle)?;
+        file.seek(SeekFrom::Start(offset)).map_err(status_code)?;
+        let mut data = vec![0; len as usize];
+        let read = file.read(&mut data).map_err(status_code)?;
+        if read == 0 {
+            return Err(StatusCode::Eof);
+        }
+        data.truncate(read);
+        Ok(Data { id, data })
+    }
+
+    async fn write(
+        &mut self,
+        id: u32,
+        handle: String,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> Result<Status, Self::Error> {
+        let file = self.file(&handle)?;
+        file.seek(SeekFrom::Start(offset)).map_err(status_code)?;
+        file.write_all(&data).map_err(status_code)?;
+        Ok(ok(id))
+    }
+
+    async fn stat(&mut self, id: u32, path: String) -> Result<Attrs, Self::Error> {
+        attrs(id, &self.local(&path))
+    }
+
+    async fn lstat(&mut self, id: u32, path: String) -> Result<Attrs, Self::Error> {
+        attrs(id, &self.local(&path))
+    }
+
+    async fn fstat(&mut self, id: u32, handle: String) -> Result<Attrs, Self::Error> {
+        let metadata = self.file(&handle)?.metadata().map_err(status_code)?;
+        Ok(Attrs {
+            id,
+            attrs: FileAttributes::from(&metadata),
+        })
+    }
+
+    async fn setstat(
+        &mut self,
+        id: u32,
+        _path: String,
+        _attrs: FileAttributes,
+    ) -> Result<Status, Self::Error> {
+        Ok(ok(id))
+    }
+
+    async fn fsetstat(
+        &mut self,
+        id: u32,
+        _handle: String,
+        _attrs: FileAttributes,
+    ) -> Result<Status, Self::Error> {
+        Ok(ok(id))
+    }
+
+    async fn opendir(&mut self, id: u32, path: String) -> Result<Handle, Self::Error> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(self.local(&path)).map_err(status_code)? {
+            let entry = entry.map_err(status_code)?;
+            let metadata = entry.metadata().map_err(status_code)?;
+            entries.push(File::new(
+                entry.file_name().to_string_lossy(),
+                FileAttributes::from(&metadata),
+            ));
+        }
+        Ok(self.insert(id, OpenHandle::Dir(Some(entries))))
+    }
+
+    async fn readdir(&mut self, id: u32, handle: String) -> Result<Name, Self::Error> {
+        match self.handles.get_mut(&handle) {
+            Some(OpenHandle::Dir(entries)) => match entries.take() {
+                Some(files) => Ok(Name { id, files }),
+                None => Err(StatusCode::Eof),
+            },
+            _ => Err(StatusCode::Failure),
+        }
+    }
+
+    async fn rename(
+        &mut self,
+        id: u32,
+        oldpath: String,
+        newpath: String,
+    ) -> Result<Status, Self::Error> {
+        std::fs::rename(self.local(&oldpath), self.local(&newpath)).map_err(status_code)?;
+        Ok(ok(id))
+    }
+
+    async fn remove(&mut self, id: u32, filename: String) -> Result<Status, Self::Error> {
+        std::fs::remove_file(self.local(&filename)).map_err(status_code)?;
+        Ok(ok(id))
+    }
+
+    async fn mkdir(
+        &mut self,
+        id: u32,
+        path: String,
+        _attrs: FileAttributes,
+    ) -> Result<Status, Self::Error> {
+        std::fs::create_dir(self.local(&path)).map_err(status_code)?;
+        Ok(ok(id))
+    }
+
+    async fn rmdir(&mut self, id: u32, path: String) -> Result<Status, Self::Error> {
+        std::fs::remove_dir(self.local(&path)).map_err(status_code)?;
+        Ok(ok(id))
+    }
+}
@@ -0,0 +1,397 @@
+use russh_sftp::client::RawSftpSession;
+use russh_sftp::client::error::Error as SftpError;
+use russh_sftp::protocol::{File, FileAttributes, OpenFlags, StatusCode};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+type SftpResult<T> = Result<T, SftpError>;
+
+/// Bytes per read or write request, lowered if the server advertises a
+/// smaller limit.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A dedicated SFTP channel for one transfer. Requests are issued with the
+/// raw protocol so several can be in flight at once; the high-level file API
+/// waits for each reply before sending the next request, which caps
+/// throughput at one chunk per round trip. The same reasoning applies to
+/// directory listings, which the high-level API only returns once complete.
+pub struct TransferChannel {
+    sftp: Arc<RawSftpSession>,
+    chunk_size: usize,
+}
+
+impl TransferChannel {
+    pub(super) async fn new(sftp: RawSftpSession) -> SftpResult<Self> {
+        let version = sftp.init().await?;
+        let mut chunk_size = CHUNK_SIZE;
+        if version
+            .extensions
+            .get(russh_sftp::extensions::LIMITS)
+            .is_some_and(|value| value == "1")
+        {
+            let limits = sftp.limits().await?;
+            for limit in [limits.max_read_len, limits.max_write_len] {
+                if limit > 0 {
+                    chunk_size = chunk_size.min(limit as usize);
+                }
+            }
+        }
+        Ok(Self {
+            sftp: Arc::new(sftp),
+            chunk_size,
+        })
+    }
+
+    pub async fn open(&self, path: &str) -> SftpResult<RemoteFile> {
+        self.open_with(path, OpenFlags::READ).await
+    }
+
+    pub async fn create(&self, path: &str) -> SftpResult<RemoteFile> {
+        self.open_with(
+            path,
+            OpenFlags::CREATE | OpenFlags::TRUNCATE | OpenFlags::WRITE,
+        )
+        .await
+    }
+
+    /// Opens an existing file for writing without truncating it, so an
+    /// interrupted upload can be continued.
+    pub async fn reopen(&self, path: &str) -> SftpResult<RemoteFile> {
+        self.open_with(path, OpenFlags::WRITE).await
+    }
+
+    /// Opens `path` for listing; entries then arrive in the batches the
+    /// server sends them.
+    pub async fn read_dir(&self, path: &str) -> SftpResult<DirListing> {
+        let handle = self.sftp.opendir(path).await?.handle;
+        Ok(DirListing {
+            sftp: self.sftp.clone(),
+            handle,
+            done: false,
+        })
+    }
+
+    pub async fn canonicalize(&self, path: &str) -> SftpResult<String> {
+        let name = self.sftp.realpath(path).await?;
+        name.files
+            .into_iter()
+            .next()
+            .map(|file| file.filename)
+            .ok_or_else(|| SftpError::UnexpectedBehavior("no file".to_string()))
+    }
+
+    /// Attributes of what `path` points to, following symlinks.
+    pub async fn stat(&self, path: &str) -> SftpResult<FileAttributes> {
+        Ok(self.sftp.stat(path).await?.attrs)
+    }
+
+    pub async fn rename(&self, from: &str, to: &str) -> SftpResult<()> {
+        self.sftp.rename(from, to).await?;
+        Ok(())
+    }
+
+    pub async fn read_link(&self, path: &str) -> SftpResult<String> {
+        let name = self.sftp.readlink(path).await?;
+        name.files
+            .into_iter()
+            .next()
+            .map(|file| file.filename)
+            .ok_or_else(|| SftpError::UnexpectedBehavior("no file".to_string()))
+    }
+
+    async fn open_with(&self, path: &str, flags: OpenFlags) -> SftpResult<RemoteFile> {
+        let handle = self
+            .sftp
+            .open(path, flags, FileAttributes::empty())
+            .await?
+            .handle;
+        Ok(RemoteFile {
+            sftp: self.sftp.clone(),
+            handle,
+            chunk_size: self.chunk_size,
+        })
+    }
+}
+
+pub struct RemoteFile {
+    sftp: Arc<RawSftpSession>,
+    handle: String,
+    chunk_size: usize,
+}
+
+impl RemoteFile {
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    pub async fn metadata(&self) -> SftpResult<FileAttributes> {
+        Ok(self.sftp.fstat(self.handle.as_str()).await?.attrs)
+    }
+
+    pub async fn set_metadata(&self, attrs: FileAttributes) -> SftpResult<()> {
+        self.sftp.fsetstat(self.handle.as_str(), attrs).await?;
+        Ok(())
+    }
+
+    pub async fn close(self) -> SftpResult<()> {
+        self.sftp.close(self.handle).await?;
+        Ok(())
+    }
+
+    /// Reads the file from `offset` to the end with up to `window` requests
+    /// in flight.
+    pub fn reader(&self, window: usize, offset: u64) -> PipelinedReader {
+        PipelinedReader {
+            sftp: self.sftp.clone(),
+            handle: self.handle.clone(),
+            chunk_size: self.chunk_size,
+            window: window.max(1),
+            next_offset: offset,
+            eof: false,
+            in_flight: VecDeque::new(),
+        }
+    }
+
+    /// Writes sequential chunks starting at `offset` with up to `window`
+    /// requests in flight.
+    pub fn writer(&self, window: usize, offset: u64) -> PipelinedWriter {
+        PipelinedWriter {
+            sftp: self.sftp.clone(),
+            handle: self.handle.clone(),
+            window: window.max(1),
+            next_offset: offset,
+            acknowledged: 0,
+            in_flight: VecDeque::new(),
+        }
+    }
+}
+
+pub struct PipelinedReader {
+    sftp: Arc<RawSftpSession>,
+    handle: String,
+    chunk_size: usize,
+    window: usize,
+    next_offset: u64,
+    eof: bool,
+    in_flight: VecDeque<(u64, JoinHandle<SftpResult<Vec<u8>>>)>,
+}
+
+impl PipelinedReader {
+    /// Returns the next chunk in file order, or `None` at end of file.
+    pub async fn next_chunk(&mut self) -> SftpResult<Option<Vec<u8>>> {
+        while !self.eof && self.in_flight.len() < self.window {
+            let offset = self.next_offset;
+            let task = tokio::spawn(read_at(
+                self.sftp.clone(),
+                self.handle.clone(),
+                offset,
+                self.chunk_size,
+            ));
+            self.in_flight.push_back((offset, task));
+            self.next_offset += self.chunk_size as u64;
+        }
+        let Some((offset, task)) = self.in_flight.pop_front() else {
+            return Ok(None);
+        };
+        let mut data = match join(task).await {
+            Ok(data) if !data.is_empty() => data,
+            Ok(_) => return Ok(self.stop()),
+            Err(err) if is_eof(&err) => return Ok(self.stop()),
+            Err(err) => return Err(err),
+        };
+        // Servers may return less than asked for before the end of the file.
+        // Fill the gap before handing out later chunks so the output stays
+        // contiguous.
+        while data.len() < self.chunk_size {
+            let missing = self.chunk_size - data.len();
+            let gap = read_at(
+                self.sftp.clone(),
+                self.handle.clone(),
+                offset + data.len() as u64,
+                missing,
+            )
+            .await;
+            match gap {
+                Ok(more) if !more.is_empty() => data.extend_from_slice(&more),
+                Ok(_) => {
+                    self.stop();
+                    break;
+                }
+                Err(err) if is_eof(&err) => {
+                    self.stop();
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Some(data))
+    }
+
+    // Requests past the end finish on their own; their replies are ignored.
+    fn stop(&mut self) -> Option<Vec<u8>> {
+        self.eof = true;
+        self.in_flight.clear();
+        None
+    }
+}
+
+pub struct PipelinedWriter {
+    sftp: Arc<RawSftpSession>,
+    handle: String,
+    window: usize,
+    next_offset: u64,
+    acknowledged: u64,
+    in_flight: VecDeque<(usize, JoinHandle<SftpResult<()>>)>,
+}
+
+impl PipelinedWriter {
+    /// Queues `data` after the previous chunk, waiting for the oldest write
+    /// when the window is full.
+    pub async fn write(&mut self, data: Vec<u8>) -> SftpResult<()> {
+        if self.in_flight.len() >= self.window {
+            self.settle_oldest().await?;
+        }
+        let len = data.len();
+        let offset = self.next_offset;
+        let sftp = self.sftp.clone();
+        let handle = self.handle.clone();
+        let task = tokio::spawn(async move {
+            sftp.write(handle, offset, data).await?;
+            Ok(())
+        });
+        self.in_flight.push_back((len, task));
+        self.next_offset += len as u64;
+        Ok(())
+    }
+
+    /// Bytes the server has confirmed writing.
+    pub fn acknowledged(&self) -> u64 {
+        self.acknowledged
+    }
+
+    pub async fn flush(&mut self) -> SftpResult<()> {
+        while !self.in_flight.is_empty() {
+            self.settle_oldest().await?;
+        }
+        Ok(())
+    }
+
+    async fn settle_oldest(&mut self) -> SftpResult<()> {
+        if let Some((len, task)) = self.in_flight.pop_front() {
+            join(task).await?;
+            self.acknowledged += len as u64;
+        }
+        Ok(())
+    }
+}
+
+pub struct DirListing {
+    sftp: Arc<RawSftpSession>,
+    handle: String,
+    done: bool,
+}
+
+impl DirListing {
+    /// Returns the next batch of entries, or `None` once the directory has
+    /// been read to the end.
+    pub async fn next_batch(&mut self) -> SftpResult<Option<Vec<File>>> {
+        if self.done {
+            return Ok(None);
+        }
+        match self.sftp.readdir(self.handle.as_str()).await {
+            Ok(name) => Ok(Some(name.files)),
+            Err(err) if is_eof(&err) => {
+                self.done = true;
+                if let Err(e) = self.sftp.close(self.handle.as_str()).await {
+                    tracing::debug!("closing directory handle failed: {}", e);
+                }
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl Drop for DirListing {
+    // A listing abandoned halfway (the user navigated on) still holds a
+    // handle on the server.
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let sftp = self.sftp.clone();
+            let handle = std::mem::take(&mut self.handle);
+            runtime.spawn(async move {
+                let _ = sftp.close(handle).await;
+            });
+        }
+    }
+}
+
+async fn read_at(
+    sftp: Arc<RawSftpSession>,
+    handle: String,
+    offset: u64,
+    len: usize,
+) -> SftpResult<Vec<u8>> {
+    Ok(sftp.read(handle, offset, len as u32).await?.data)
+}
+
+async fn join<T>(task: JoinHandle<SftpResult<T>>) -> SftpResult<T> {
+    task.await
+        .map_err(|e| SftpError::UnexpectedBehavior(format!("transfer task failed: {}", e)))?
+}
+
+fn is_eof(err: &SftpError) -> bool {
+    matches!(err, SftpError::Status(status) if status.status_code == StatusCode::Eof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CHUNK_SIZE;
+    use crate::ssh::test_server::TestServer;
+
+    #[tokio::test]
+    async fn uploads_lists_and_downloads_over_sftp() {
+        let server = TestServer::start().await;
+        let (session, _output) = server.connect().await.unwrap();
+        let channel = session.open_transfer_channel().await.unwrap();
+        // Several chunks and a partial one, so requests overlap.
+        let content: Vec<u8> = (0..CHUNK_SIZE * 3 + 100).map(|i| (i % 251) as u8).collect();
+
+        let file = channel.create("/upload.bin").await.unwrap();
+        let mut writer = file.writer(4, 0);
+        for chunk in content.chunks(file.chunk_size()) {
+            writer.write(chunk.to_vec()).await.unwrap();
+        }
+        writer.flush().await.unwrap();
+        assert_eq!(writer.acknowledged(), content.len() as u64);
+        file.close().await.unwrap();
+        assert_eq!(
+            std::fs::read(server.root.join("upload.bin")).unwrap(),
+            content
+        );
+
+        channel.rename("/upload.bin", "/moved.bin").await.unwrap();
+        let mut listing = channel.read_dir("/").await.unwrap();
+        let mut names = Vec::new();
+        while let Some(batch) = listing.next_batch().await.unwrap() {
+            names.extend(batch.into_iter().map(|file| file.filename));
+        }
+        assert_eq!(names, ["moved.bin"]);
+
+        let file = channel.open("/moved.bin").await.unwrap();
+        assert_eq!(
+            file.metadata().await.unwrap().size,
+            Some(content.len() as u64)
+        );
+        let mut reader = file.reader(4, 0);
+        let mut downloaded = Vec::new();
+        while let Some(chunk) = reader.next_chunk().await.unwrap() {
+            downloaded.extend(chunk);
+        }
+        assert_eq!(downloaded, content);
+    }
+}
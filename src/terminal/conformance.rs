@@ -0,0 +1,231 @@
+//! Escape-sequence conformance cases in the spirit of vttest and esctest,
+//! checked against the screen they leave, plus property tests that feed the
+//! emulator arbitrary byte streams.
+
+use proptest::prelude::*;
+
+use super::TerminalEmulator;
+
+fn terminal(cols: usize, rows: usize, input: &[u8]) -> TerminalEmulator {
+    let mut emulator = TerminalEmulator::new();
+    emulator.resize(cols, rows);
+    emulator.process_input(input);
+    emulator
+}
+
+fn replies(emulator: &TerminalEmulator) -> Vec<u8> {
+    let mut output = emulator.take_output_receiver().unwrap();
+    let mut replies = Vec::new();
+    while let Ok(reply) = output.try_recv() {
+        replies.extend(reply);
+    }
+    replies
+}
+
+#[test]
+fn cursor_movement_stays_on_screen() {
+    let emulator = terminal(
+        10,
+        4,
+        b"\x1b[2;3Ha\x1b[99;99Hb\x1b[H\x1b[5Cc\x1b[3;1H\x1b[20Dd",
+    );
+    assert_eq!(emulator.screen_text(), ["     c", "  a", "d", "         b"]);
+    assert_eq!(emulator.cursor_position(), (1, 2));
+
+    let emulator = terminal(10, 4, b"\x1b[3;3H\x1b[9A\x1b[9Bx\x1b[G\x1b[9dy");
+    assert_eq!(emulator.screen_text(), ["", "", "", "y x"]);
+}
+
+#[test]
+fn erases_parts_of_lines_and_the_display() {
+    let mut emulator = terminal(10, 4, &b"0123456789".repeat(4));
+    emulator.process_input(b"\x1b[2;5H\x1b[K\x1b[3;5H\x1b[1K\x1b[4;5H\x1b[2K\x1b[1;3H\x1b[1J");
+    assert_eq!(
+        emulator.screen_text(),
+        ["   3456789", "0123", "     56789", ""]
+    );
+
+    emulator.process_input(b"\x1b[2;1H\x1b[J");
+    assert_eq!(emulator.screen_text(), ["   3456789", "", "", ""]);
+    emulator.process_input(b"\x1b[2J");
+    assert_eq!(emulator.screen_text(), ["", "", "", ""]);
+    assert_eq!(emulator.cursor_position(), (0, 1));
+}
+
+#[test]
+fn inserts_deletes_and_erases_characters() {
+    let mut emulator = terminal(10, 2, b"abcdefghij\x1b[1;3H\x1b[2@");
+    assert_eq!(emulator.screen_text()[0], "ab  cdefgh");
+    emulator.process_input(b"\x1b[3P");
+    assert_eq!(emulator.screen_text()[0], "abdefgh");
+    emulator.process_input(b"\x1b[1;1H\x1b[2X");
+    assert_eq!(emulator.screen_text()[0], "  defgh");
+    assert_eq!(emulator.cursor_position(), (0, 0));
+}
+
+#[test]
+fn inserts_and_deletes_lines() {
+    let mut emulator = terminal(10, 4, b"1\r\n2\r\n3\r\n4\x1b[2;1H\x1b[L");
+    assert_eq!(emulator.screen_text(), ["1", "", "2", "3"]);
+    emulator.process_input(b"\x1b[2M");
+    assert_eq!(emulator.screen_text(), ["1", "3", "", ""]);
+}
+
+#[test]
+fn scroll_region_confines_scrolling() {
+    let mut emulator = terminal(10, 4, b"1\r\n2\r\n3\r\n4\x1b[2;3r");
+    // Setting the region homes the cursor.
+    assert_eq!(emulator.cursor_position(), (0, 0));
+
+    emulator.process_input(b"\x1b[3;1H\nX");
+    assert_eq!(emulator.screen_text(), ["1", "3", "X", "4"]);
+    emulator.process_input(b"\x1b[2;1H\x1bMY");
+    assert_eq!(emulator.screen_text(), ["1", "Y", "3", "4"]);
+
+    // Lines inserted inside the region push lines out of it, not below it.
+    emulator.process_input(b"\x1b[2;1H\x1b[L");
+    assert_eq!(emulator.screen_text(), ["1", "", "Y", "4"]);
+}
+
+#[test]
+fn wraps_at_the_margin_unless_told_not_to() {
+    let mut emulator = terminal(5, 3, b"abcdefg");
+    assert_eq!(emulator.screen_text(), ["abcde", "fg", ""]);
+
+    emulator.process_input(b"\x1b[?7l\x1b[3;1Hvwxyz12");
+    assert_eq!(emulator.screen_text()[2], "vwxy2");
+    assert_eq!(emulator.cursor_position(), (4, 2));
+}
+
+#[test]
+fn wide_characters_wrap_whole() {
+    let emulator = terminal(5, 2, "abcd界x".as_bytes());
+    assert_eq!(emulator.screen_text(), ["abcd", "界x"]);
+    assert_eq!(emulator.cursor_position(), (3, 1));
+}
+
+#[test]
+fn follows_tab_stops() {
+    // The cell a tab starts from keeps the tab, so copying gives it back.
+    let screen = |emulator: &TerminalEmulator| -> Vec<String> {
+        let screen = emulator.screen_text();
+        screen.iter().map(|line| line.replace('\t', " ")).collect()
+    };
+    let mut emulator = terminal(20, 2, b"a\tb");
+    assert_eq!(screen(&emulator)[0], "a       b");
+
+    emulator.process_input(b"\r\n\x1b[3g\x1b[5G\x1bH\r\tc\td");
+    // With no stop left after it, a tab goes to the last column.
+    assert_eq!(screen(&emulator)[1], "    c              d");
+}
+
+#[test]
+fn restores_the_cursor_and_main_screen() {
+    let mut emulator = terminal(10, 3, b"abc\x1b7\x1b[3;3H\x1b8x");
+    assert_eq!(emulator.screen_text(), ["abcx", "", ""]);
+
+    emulator.process_input(b"\x1b[?1049h\x1b[Halt");
+    assert_eq!(emulator.screen_text(), ["alt", "", ""]);
+    emulator.process_input(b"\x1b[?1049l");
+    assert_eq!(emulator.screen_text(), ["abcx", "", ""]);
+    assert_eq!(emulator.cursor_position(), (4, 0));
+}
+
+#[test]
+fn answers_cursor_position_reports() {
+    let emulator = terminal(10, 4, b"\x1b[2;5H\x1b[6n\x1b[?6n");
+    let replies = replies(&emulator);
+    assert!(replies.starts_with(b"\x1b[2;5R"), "{:?}", replies);
+}
+
+#[test]
+fn characters_split_between_reads_keep_what_follows() {
+    let mut emulator = terminal(20, 2, b"\xce");
+    emulator.process_input(b"\xa3 \xea\xb0");
+    emulator.process_input(b"\x80!");
+    assert_eq!(emulator.screen_text()[0], "Σ 가!");
+}
+
+// Bytes shaped like what hosts send: text, control characters and escape
+// sequences with plausible and implausible parameters.
+fn token() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        3 => "[ -~]{1,16}".prop_map(String::into_bytes),
+        1 => any::<String>().prop_map(String::into_bytes),
+        1 => prop::collection::vec(0u8..0x20, 1..4),
+        3 => (
+            prop::option::of(prop::sample::select(vec!["?", ">", "!"])),
+            prop::collection::vec(0u16..300, 0..4),
+            0x40u8..0x7f,
+        )
+            .prop_map(|(private, params, last)| {
+                let params: Vec<String> = params.iter().map(u16::to_string).collect();
+                let mut sequence = format!("\x1b[{}{}", private.unwrap_or(""), params.join(";"));
+                sequence.push(last as char);
+                sequence.into_bytes()
+            }),
+        2 => (
+            prop::sample::select(vec!["0", "2", "7", "8", "52", "133", "1337"]),
+            "[ -~]{0,24}",
+            prop::bool::ANY,
+        )
+            .prop_map(|(code, payload, bel)| {
+                let end = if bel { "\x07" } else { "\x1b\\" };
+                format!("\x1b]{};{}{}", code, payload, end).into_bytes()
+            }),
+        1 => (0x20u8..0x7f).prop_map(|last| vec![0x1b, last]),
+        1 => prop::collection::vec(any::<u8>(), 1..16),
+    ]
+}
+
+fn stream() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(token(), 0..48).prop_map(|tokens| tokens.concat())
+}
+
+fn assert_fits(emulator: &TerminalEmulator, cols: usize, rows: usize) {
+    let (col, line) = emulator.cursor_position();
+    assert!(col < cols && line < rows, "cursor at {},{}", col, line);
+    let screen = emulator.screen_text();
+    assert_eq!(screen.len(), rows);
+    assert!(screen.iter().all(|line| line.chars().count() <= cols));
+}
+
+proptest! {
+    #[test]
+    fn any_input_leaves_a_consistent_screen(
+        input in stream(),
+        (cols, rows) in (2usize..40, 1usize..20),
+        (new_cols, new_rows) in (2usize..40, 1usize..20),
+    ) {
+        let mut emulator = terminal(cols, rows, &input);
+        assert_fits(&emulator, cols, rows);
+        emulator.resize(new_cols, new_rows);
+        assert_fits(&emulator, new_cols, new_rows);
+        emulator.process_input(b"after");
+        emulator.buffer_text();
+    }
+
+    #[test]
+    fn split_reads_parse_like_one(
+        input in stream(),
+        splits in prop::collection::vec(any::<prop::sample::Index>(), 0..8),
+    ) {
+        let whole = terminal(20, 6, &input);
+
+        let mut cuts: Vec<usize> = splits.iter().map(|i| i.index(input.len() + 1)).collect();
+        cuts.sort_unstable();
+        let mut pieces = terminal(20, 6, b"");
+        let mut start = 0;
+        for cut in cuts.into_iter().chain([input.len()]) {
+            pieces.process_input(&input[start..cut]);
+            start = cut;
+        }
+
+        prop_assert_eq!(pieces.screen_text(), whole.screen_text());
+        prop_assert_eq!(pieces.cursor_position(), whole.cursor_position());
+        prop_assert_eq!(pieces.working_directory(), whole.working_directory());
+        prop_assert_eq!(pieces.user_vars(), whole.user_vars());
+        prop_assert_eq!(pieces.command_history().len(), whole.command_history().len());
+        prop_assert_eq!(replies(&pieces), replies(&whole));
+    }
+}
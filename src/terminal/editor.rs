@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+/// A file named in terminal output, as compilers and grep print it:
+/// `src/main.rs:42:7`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLocation {
+    pub path: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl FileLocation {
+    /// Reads a token such as `path:line:col`, trimming the punctuation a
+    /// message puts around it. Relative paths need a directory or a line
+    /// number so plain words aren't taken for files.
+    pub fn parse(token: &str) -> Option<Self> {
+        let token = token
+            .split_once('=')
+            .map(|(_, value)| value)
+            .unwrap_or(token);
+        if token.contains("://") {
+            return None;
+        }
+        let mut path = token.trim_end_matches(['.', ',', ';', ':']);
+        let mut numbers = Vec::new();
+        while let Some((head, tail)) = path.rsplit_once(':') {
+            match tail.parse::<u32>() {
+                Ok(number) if numbers.len() < 2 => {
+                    numbers.push(number);
+                    path = head;
+                }
+                _ => break,
+            }
+        }
+        numbers.reverse();
+
+        let absolute = path.len() > 1 && path.starts_with('/') && !path.starts_with("//");
+        let relative = !path.starts_with('/')
+            && path.chars().any(char::is_alphanumeric)
+            && (path.contains('/') || (!numbers.is_empty() && path.contains('.')));
+        if !absolute && !relative {
+            return None;
+        }
+        Some(Self {
+            path: path.to_string(),
+            line: numbers.first().copied(),
+            column: numbers.get(1).copied(),
+        })
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.path.starts_with('/')
+    }
+}
+
+/// The editor that opens files clicked in local tabs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct EditorOptions {
+    /// Command for files no rule covers; empty opens them with the
+    /// system's default app.
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub rules: Vec<EditorRule>,
+}
+
+/// A command for files with one of `extensions`, listed like `rs, toml`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EditorRule {
+    pub extensions: String,
+    pub command: String,
+}
+
+impl EditorRule {
+    fn matches(&self, extension: &str) -> bool {
+        self.extensions
+            .split([',', ' '])
+            .map(|ext| ext.trim().trim_start_matches('.'))
+            .any(|ext| !ext.is_empty() && ext.eq_ignore_ascii_case(extension))
+    }
+}
+
+impl EditorOptions {
+    /// The command for `path`, or None to use the system default.
+    pub fn command_for(&self, path: &str) -> Option<&str> {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        self.rules
+            .iter()
+            .find(|rule| !rule.command.trim().is_empty() && rule.matches(extension))
+            .map(|rule| rule.command.as_str())
+            .or(Some(self.command.as_str()))
+            .filter(|command| !command.trim().is_empty())
+    }
+}
+
+/// The program and its arguments for `command`, split on spaces, with
+/// `{file}`, `{line}` and `{column}` filled in. The file goes last when
+/// the command doesn't place it.
+pub fn command_line(command: &str, path: &str, location: &FileLocation) -> Vec<String> {
+    let line = location.line.unwrap_or(1).to_string();
+    let column = location.column.unwrap_or(1).to_string();
+    let mut args: Vec<String> = command
+        .split_whitespace()
+        .map(|arg| {
+            arg.replace("{file}", path)
+                .replace("{line}", &line)
+                .replace("{column}", &column)
+        })
+        .collect();
+    if !command.contains("{file}") {
+        args.push(path.to_string());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(path: &str, line: Option<u32>, column: Option<u32>) -> Option<FileLocation> {
+        Some(FileLocation {
+            path: path.to_string(),
+            line,
+            column,
+        })
+    }
+
+    #[test]
+    fn reads_compiler_and_grep_locations() {
+        assert_eq!(
+            FileLocation::parse("src/main.rs:42:7:"),
+            location("src/main.rs", Some(42), Some(7))
+        );
+        assert_eq!(
+            FileLocation::parse("main.go:12"),
+            location("main.go", Some(12), None)
+        );
+        assert_eq!(
+            FileLocation::parse("file=/srv/app.py,"),
+            location("/srv/app.py", None, None)
+        );
+        assert_eq!(FileLocation::parse("Cargo.toml"), None);
+        assert_eq!(FileLocation::parse("error:"), None);
+        assert_eq!(FileLocation::parse("https://example.com/a"), None);
+    }
+
+    #[test]
+    fn picks_the_command_by_extension() {
+        let options = EditorOptions {
+            command: "code -g {file}:{line}:{column}".to_string(),
+            rules: vec![EditorRule {
+                extensions: "py, .txt".to_string(),
+                command: "subl".to_string(),
+            }],
+        };
+        assert_eq!(options.command_for("/a/b.TXT"), Some("subl"));
+        assert_eq!(
+            options.command_for("main.rs"),
+            Some("code -g {file}:{line}:{column}")
+        );
+        assert_eq!(EditorOptions::default().command_for("main.rs"), None);
+
+        let at = location("main.rs", Some(3), None).unwrap();
+        assert_eq!(
+            command_line("code -g {file}:{line}:{column}", "/src/main.rs", &at),
+            ["code", "-g", "/src/main.rs:3:1"]
+        );
+        assert_eq!(
+            command_line("subl", "/src/main.rs", &at),
+            ["subl", "/src/main.rs"]
+        );
+    }
+}
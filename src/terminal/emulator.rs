@@ -1,21 +1,38 @@
 use alacritty_terminal::event::{Event, EventListener};
 use alacritty_terminal::grid::Dimensions;
-use alacritty_terminal::term::{Config, Term, TermDamage};
+use alacritty_terminal::term::{Config, Osc52, Term, TermDamage, TermMode};
 use alacritty_terminal::vte::ansi;
 use alacritty_terminal::vte::ansi::{CursorShape, NamedColor, Rgb};
 use parking_lot::Mutex;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::mpsc;
 
+use super::editor::FileLocation;
+use super::shell_integration::{CommandHistory, CommandRecord, Mark, MarkScanner, PromptNote};
+
 // Initial terminal size
 const DEFAULT_COLS: usize = 80;
 const DEFAULT_ROWS: usize = 24;
+// Grid width while long lines don't wrap; anything wider is still cut.
+const NO_WRAP_COLUMNS: usize = 512;
+
+/// OSC 52 clipboard access from the remote side. The UI decides whether to
+/// honor it, so requests are only collected here.
+pub enum ClipboardRequest {
+    Store(String),
+    /// Formats the clipboard contents as the reply to write back.
+    Load(Arc<dyn Fn(&str) -> String + Send + Sync>),
+}
 
 /// EventListener that forwards terminal output (like cursor position reports) to a channel
 #[derive(Clone)]
 struct EventWriter {
-    tx: mpsc::UnboundedSender<Vec<u8>>,
+    tx: Arc<Mutex<mpsc::UnboundedSender<Vec<u8>>>>,
+    bell: Arc<AtomicBool>,
+    clipboard: Arc<Mutex<Vec<ClipboardRequest>>>,
 }
 
 impl EventListener for EventWriter {
@@ -23,7 +40,16 @@ impl EventListener for EventWriter {
         match event {
             Event::PtyWrite(ref s) => {
                 // Terminal wants to write something back to PTY (e.g., cursor position report)
-                let _ = self.tx.send(s.as_bytes().to_vec());
+                let _ = self.tx.lock().send(s.as_bytes().to_vec());
+            }
+            Event::Bell => {
+                self.bell.store(true, Ordering::Relaxed);
+            }
+            Event::ClipboardStore(_, text) => {
+                self.clipboard.lock().push(ClipboardRequest::Store(text));
+            }
+            Event::ClipboardLoad(_, format) => {
+                self.clipboard.lock().push(ClipboardRequest::Load(format));
             }
             _ => {
                 // Ignore other events for now
@@ -35,11 +61,159 @@ impl EventListener for EventWriter {
 #[derive(Clone)]
 pub struct TerminalEmulator {
     term: Arc<Mutex<Term<EventWriter>>>,
-    parser: Arc<Mutex<ansi::Processor>>,
+    parser: Arc<Mutex<Parser>>,
     scroll_accumulator: Arc<Mutex<f32>>,
     selection_start: Option<alacritty_terminal::index::Point>,
     /// Receiver for terminal output responses (like CPR)
     output_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<Vec<u8>>>>>,
+    output_tx: Arc<Mutex<mpsc::UnboundedSender<Vec<u8>>>>,
+    bell: Arc<AtomicBool>,
+    clipboard: Arc<Mutex<Vec<ClipboardRequest>>>,
+    shell: Arc<Mutex<ShellIntegration>>,
+    columns: Arc<Mutex<ColumnView>>,
+}
+
+/// The part of each line on screen. While long lines don't wrap the grid
+/// is wider than the screen and the view slides across it.
+#[derive(Debug, Clone, Copy)]
+struct ColumnView {
+    wrap: bool,
+    /// Columns that fit on screen, which is what the shell is told.
+    width: usize,
+    /// First grid column shown.
+    offset: usize,
+    /// Sideways scrolling that hasn't added up to a whole column yet.
+    pending: f32,
+}
+
+impl ColumnView {
+    fn grid_columns(&self) -> usize {
+        if self.wrap {
+            self.width
+        } else {
+            self.width.max(NO_WRAP_COLUMNS)
+        }
+    }
+
+    /// Screen column of grid column `col`, if it is on screen.
+    fn screen_column(&self, col: usize) -> Option<usize> {
+        col.checked_sub(self.offset).filter(|col| *col < self.width)
+    }
+}
+
+/// The escape-sequence parser, handed whole characters only. vte drops
+/// the text following a character it finishes from an earlier read, so
+/// one split between reads is held back until the rest of it arrives.
+#[derive(Default)]
+struct Parser {
+    processor: ansi::Processor,
+    partial: Vec<u8>,
+}
+
+impl Parser {
+    fn advance(&mut self, term: &mut Term<EventWriter>, data: &[u8]) {
+        if self.partial.is_empty() {
+            self.feed(term, data);
+        } else {
+            let mut joined = std::mem::take(&mut self.partial);
+            joined.extend_from_slice(data);
+            self.feed(term, &joined);
+        }
+    }
+
+    fn feed(&mut self, term: &mut Term<EventWriter>, data: &[u8]) {
+        let end = data.len() - partial_char_len(data);
+        self.processor.advance(term, &data[..end]);
+        self.partial.extend_from_slice(&data[end..]);
+    }
+}
+
+/// Length of the incomplete UTF-8 character ending `data`, if there is one.
+fn partial_char_len(data: &[u8]) -> usize {
+    for back in 1..=data.len().min(3) {
+        let byte = data[data.len() - back];
+        if byte & 0xc0 == 0x80 {
+            continue;
+        }
+        let len = match byte {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => return 0,
+        };
+        return if back < len { back } else { 0 };
+    }
+    0
+}
+
+/// Command tracking driven by OSC 133 marks.
+#[derive(Default)]
+struct ShellIntegration {
+    scanner: MarkScanner,
+    history: CommandHistory,
+    // Last directory reported with OSC 7.
+    cwd: Option<String>,
+    user_vars: BTreeMap<String, String>,
+    // Where the typed command begins, as (line + history size, column) so
+    // it survives output scrolling the screen.
+    command_start: Option<(i64, usize)>,
+}
+
+impl ShellIntegration {
+    fn apply_mark(&mut self, mark: Mark, term: &Term<EventWriter>) {
+        let grid = term.grid();
+        let cursor = grid.cursor.point;
+        let history_size = grid.history_size() as i64;
+        let cursor_line = cursor.line.0 as i64 + history_size;
+        match mark {
+            Mark::PromptStart => {
+                self.history.finish_running(None, cursor_line);
+                self.command_start = None;
+            }
+            Mark::CommandStart => {
+                self.command_start = Some((cursor_line, cursor.column.0));
+            }
+            Mark::CommandExecuted => {
+                let Some((line, column)) = self.command_start.take() else {
+                    return;
+                };
+                let start_line = (line - history_size) as i32;
+                let command = command_text(term, start_line, column);
+                self.history.command_executed(command, line, cursor_line);
+            }
+            Mark::CommandFinished(exit_code) => {
+                self.history.finish_running(exit_code, cursor_line);
+            }
+            Mark::WorkingDirectory(path) => self.cwd = Some(path),
+            Mark::UserVar(name, value) => {
+                self.user_vars.insert(name, value);
+            }
+        }
+    }
+}
+
+/// Text typed between the end of the prompt and the cursor, which sits on
+/// the line after the command once it was submitted.
+fn command_text(term: &Term<EventWriter>, start_line: i32, start_column: usize) -> String {
+    use alacritty_terminal::index::{Column, Line, Point};
+
+    let grid = term.grid();
+    let cursor = grid.cursor.point;
+    let end = if cursor.column.0 > 0 {
+        Point::new(cursor.line, cursor.column - 1)
+    } else {
+        Point::new(cursor.line - 1, grid.last_column())
+    };
+    let start = Point::new(Line(start_line), Column(start_column));
+    if start_line < grid.topmost_line().0 || start > end {
+        return String::new();
+    }
+    term.bounds_to_string(start, end)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 #[derive(Debug, Clone)]
@@ -82,6 +256,7 @@ impl TerminalEmulator {
     pub fn new() -> Self {
         let mut config = Config::default();
         config.scrolling_history = 10000; // Set explicit history size
+        config.osc52 = Osc52::CopyPaste;
 
         let size = TermDimensions {
             cols: DEFAULT_COLS,
@@ -89,51 +264,306 @@ impl TerminalEmulator {
         };
 
         let (tx, rx) = mpsc::unbounded_channel();
-        let listener = EventWriter { tx };
+        let output_tx = Arc::new(Mutex::new(tx));
+        let bell = Arc::new(AtomicBool::new(false));
+        let clipboard = Arc::new(Mutex::new(Vec::new()));
+        let listener = EventWriter {
+            tx: output_tx.clone(),
+            bell: bell.clone(),
+            clipboard: clipboard.clone(),
+        };
         let term = Term::new(config, &size, listener);
 
         Self {
             term: Arc::new(Mutex::new(term)),
-            parser: Arc::new(Mutex::new(ansi::Processor::new())),
+            parser: Arc::new(Mutex::new(Parser::default())),
             scroll_accumulator: Arc::new(Mutex::new(0.0)),
             selection_start: None,
             output_rx: Arc::new(Mutex::new(Some(rx))),
+            output_tx,
+            bell,
+            clipboard,
+            shell: Arc::new(Mutex::new(ShellIntegration::default())),
+            columns: Arc::new(Mutex::new(ColumnView {
+                wrap: true,
+                width: DEFAULT_COLS,
+                offset: 0,
+                pending: 0.0,
+            })),
         }
     }
 
+    /// Returns whether BEL was received since the last call.
+    pub fn take_bell(&self) -> bool {
+        self.bell.swap(false, Ordering::Relaxed)
+    }
+
+    /// OSC 52 requests received since the last call, oldest first.
+    pub fn take_clipboard_requests(&self) -> Vec<ClipboardRequest> {
+        std::mem::take(&mut *self.clipboard.lock())
+    }
+
     /// Take the output receiver (should be called once during session setup)
     pub fn take_output_receiver(&self) -> Option<mpsc::UnboundedReceiver<Vec<u8>>> {
         self.output_rx.lock().take()
     }
 
+    /// Replace the output channel after a reconnect. The previous receiver
+    /// sees its channel close once the old sender is dropped.
+    pub fn reattach_output_receiver(&self) -> mpsc::UnboundedReceiver<Vec<u8>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        *self.output_tx.lock() = tx;
+        self.output_rx.lock().take();
+        rx
+    }
+
     /// Process input bytes (from SSH stream)
     pub fn process_input(&mut self, data: &[u8]) {
         let mut term = self.term.lock();
         let mut parser = self.parser.lock();
+        let mut shell = self.shell.lock();
 
         // Feed the data to the parser, which updates the terminal state
-        // Term implements Handler, so we pass it as the handler.
-        parser.advance(&mut *term, data);
+        // Term implements Handler, so we pass it as the handler. Shell
+        // integration marks are handled once the output before them is in.
+        let mut start = 0;
+        for (end, mark) in shell.scanner.scan(data) {
+            parser.advance(&mut term, &data[start..end]);
+            start = end;
+            shell.apply_mark(mark, &term);
+        }
+        parser.advance(&mut term, &data[start..]);
+    }
+
+    /// Commands seen through shell integration, oldest first.
+    pub fn command_history(&self) -> Vec<CommandRecord> {
+        self.shell.lock().history.records().to_vec()
+    }
+
+    /// The shell's working directory, if it reports one with OSC 7.
+    pub fn working_directory(&self) -> Option<String> {
+        self.shell.lock().cwd.clone()
+    }
+
+    /// Variables the shell published with `SetUserVar`, by name.
+    pub fn user_vars(&self) -> BTreeMap<String, String> {
+        self.shell.lock().user_vars.clone()
+    }
+
+    /// Finished commands whose prompt line is on screen. A full-screen
+    /// program on the alternate screen hides them.
+    pub fn prompt_notes(&self) -> Vec<PromptNote> {
+        use alacritty_terminal::index::Line;
+        use alacritty_terminal::term::cell::LineLength;
+
+        let shell = self.shell.lock();
+        let records = shell.history.records();
+        if records.is_empty() {
+            return Vec::new();
+        }
+        let term = self.term.lock();
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return Vec::new();
+        }
+        let grid = term.grid();
+        let history_size = grid.history_size() as i64;
+        let top = history_size - grid.display_offset() as i64;
+        let column_offset = self.column_offset();
+        records
+            .iter()
+            .filter(|record| !record.is_running())
+            .filter_map(|record| {
+                let line = record.prompt_line - top;
+                if line < 0 || line >= grid.screen_lines() as i64 {
+                    return None;
+                }
+                let row = &grid[Line((record.prompt_line - history_size) as i32)];
+                Some(PromptNote {
+                    line: line as usize,
+                    used_columns: row.line_length().0.saturating_sub(column_offset),
+                    duration: record.elapsed(),
+                    exit_code: record.exit_code,
+                })
+            })
+            .collect()
+    }
+
+    /// Index of the command block shown on `viewport_line`, if any.
+    pub fn command_block_at(&self, viewport_line: usize) -> Option<usize> {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let history_size = grid.history_size() as i64;
+        let line = viewport_line as i64 - grid.display_offset() as i64 + history_size;
+        let cursor_line = grid.cursor.point.line.0 as i64 + history_size;
+        self.shell.lock().history.block_at(line, cursor_line)
+    }
+
+    pub fn command_at(&self, index: usize) -> Option<String> {
+        let shell = self.shell.lock();
+        shell
+            .history
+            .get(index)
+            .map(|record| record.command.clone())
     }
 
+    /// Output of a command block; running commands include everything up to
+    /// the cursor. Lines already dropped from the scrollback are skipped.
+    pub fn command_output(&self, index: usize) -> Option<String> {
+        use alacritty_terminal::index::{Column, Line, Point};
+
+        let shell = self.shell.lock();
+        let record = shell.history.get(index)?;
+        let term = self.term.lock();
+        let grid = term.grid();
+        let history_size = grid.history_size() as i64;
+        let cursor = grid.cursor.point;
+        let (end_line, end_column) = match record.end_line {
+            Some(end) => (end - history_size - 1, grid.last_column()),
+            None => (cursor.line.0 as i64, grid.last_column()),
+        };
+        let start_line = (record.output_line - history_size).max(grid.topmost_line().0 as i64);
+        if end_line < start_line {
+            return Some(String::new());
+        }
+        let start = Point::new(Line(start_line as i32), Column(0));
+        let end = Point::new(Line(end_line as i32), end_column);
+        Some(term.bounds_to_string(start, end).trim_end().to_string())
+    }
+
+    /// Fits the terminal to `cols` by `rows` cells on screen.
     pub fn resize(&mut self, cols: usize, rows: usize) {
-        let size = TermDimensions { cols, rows };
+        let grid_cols = {
+            let mut view = self.columns.lock();
+            view.width = cols;
+            let grid_cols = view.grid_columns();
+            view.offset = view.offset.min(grid_cols.saturating_sub(cols));
+            grid_cols
+        };
+        let size = TermDimensions {
+            cols: grid_cols,
+            rows,
+        };
         let mut term = self.term.lock();
         term.resize(size);
     }
 
-    pub fn scroll(&self, delta: f32) {
-        let mut accumulator = self.scroll_accumulator.lock();
-        *accumulator += delta;
+    pub fn wraps_lines(&self) -> bool {
+        self.columns.lock().wrap
+    }
 
-        let steps = (*accumulator) as i32;
+    /// Switches between wrapping long lines and letting them run off to
+    /// the right. Lines already on screen are rewrapped to match.
+    pub fn set_wrap_lines(&mut self, wrap: bool) {
+        let width = {
+            let mut view = self.columns.lock();
+            view.wrap = wrap;
+            view.offset = 0;
+            view.pending = 0.0;
+            view.width
+        };
+        let rows = self.term.lock().screen_lines();
+        self.resize(width, rows);
+    }
+
+    /// Scrolls unwrapped lines sideways by `delta` columns, no further than
+    /// the widest line on screen allows. Returns whether the view moved.
+    pub fn scroll_columns(&self, delta: f32) -> bool {
+        use alacritty_terminal::index::Line;
+        use alacritty_terminal::term::cell::LineLength;
+
+        let widest = {
+            let term = self.term.lock();
+            let grid = term.grid();
+            let display_offset = grid.display_offset();
+            (0..grid.screen_lines())
+                .map(|line| grid[Line::from(line) - display_offset].line_length().0)
+                .max()
+                .unwrap_or(0)
+        };
+        let mut view = self.columns.lock();
+        if view.wrap {
+            return false;
+        }
+        view.pending += delta;
+        let steps = view.pending as i64;
+        view.pending -= steps as f32;
+        let max_offset = widest.saturating_sub(view.width) as i64;
+        let offset = (view.offset as i64 + steps).clamp(0, max_offset.max(0)) as usize;
+        let moved = offset != view.offset;
+        view.offset = offset;
+        moved
+    }
+
+    /// First column shown of lines that don't wrap.
+    pub fn column_offset(&self) -> usize {
+        self.columns.lock().offset
+    }
+
+    /// Slides the view sideways so the cursor is on screen. Returns whether
+    /// it moved.
+    pub fn reveal_cursor(&self) -> bool {
+        let cursor = self.term.lock().grid().cursor.point.column.0;
+        let mut view = self.columns.lock();
+        let offset = if cursor < view.offset {
+            cursor
+        } else if cursor >= view.offset + view.width {
+            cursor + 1 - view.width
+        } else {
+            return false;
+        };
+        view.offset = offset;
+        view.pending = 0.0;
+        true
+    }
+
+    pub fn scroll(&self, delta: f32) {
+        let steps = self.scroll_steps(delta);
         if steps != 0 {
-            *accumulator -= steps as f32;
             let mut term = self.term.lock();
             term.scroll_display(alacritty_terminal::grid::Scroll::Delta(steps));
         }
     }
 
+    /// Adds `delta` to the pending scroll and takes the whole lines from it.
+    pub fn scroll_steps(&self, delta: f32) -> i32 {
+        let mut accumulator = self.scroll_accumulator.lock();
+        *accumulator += delta;
+        let steps = (*accumulator) as i32;
+        *accumulator -= steps as f32;
+        steps
+    }
+
+    /// Whether the wheel should become arrow keys: a full-screen program is
+    /// on the alternate screen, has not turned alternate scroll off, and
+    /// does not want mouse reports.
+    pub fn wheel_sends_arrows(&self) -> bool {
+        let term = self.term.lock();
+        let mode = term.mode();
+        mode.contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
+            && !mode.intersects(TermMode::MOUSE_MODE)
+    }
+
+    /// Whether cursor keys are in application mode (`ESC O A`).
+    pub fn app_cursor_keys(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::APP_CURSOR)
+    }
+
+    /// Moves the viewport so it sits `offset` lines above the bottom.
+    pub fn scroll_to_offset(&self, offset: usize) {
+        *self.scroll_accumulator.lock() = 0.0;
+        let mut term = self.term.lock();
+        let current = term.grid().display_offset() as i32;
+        term.scroll_display(alacritty_terminal::grid::Scroll::Delta(
+            offset as i32 - current,
+        ));
+    }
+
+    pub fn scroll_to_bottom(&self) {
+        *self.scroll_accumulator.lock() = 0.0;
+        let mut term = self.term.lock();
+        term.scroll_display(alacritty_terminal::grid::Scroll::Bottom);
+    }
+
     pub fn render_line<F>(&self, line: usize, mut func: F)
     where
         // line_idx, col_idx, cell, is_selected
@@ -141,6 +571,7 @@ impl TerminalEmulator {
     {
         use alacritty_terminal::index::{Column, Line, Point};
 
+        let view = *self.columns.lock();
         let term = self.term.lock();
         let grid = term.grid();
         let cols = grid.columns();
@@ -154,14 +585,23 @@ impl TerminalEmulator {
 
         let grid_line = Line::from(line) - display_offset;
         let row = &grid[grid_line];
+        let masked = super::redact::current()
+            .map(|redactor| masked_columns(&redactor, (0..cols).map(|col| &row[Column(col)])));
 
-        for col in 0..cols {
+        for col in view.offset..cols.min(view.offset + view.width) {
             let cell = &row[Column(col)];
             let point = Point::new(grid_line, Column(col));
             let is_selected = selection
                 .map(|range| range.contains(point))
                 .unwrap_or(false);
-            func(col, line, cell, is_selected);
+            let screen_col = col - view.offset;
+            if masked.as_ref().is_some_and(|masked| masked[col]) {
+                let mut cell = cell.clone();
+                cell.c = REDACTED_CHAR;
+                func(screen_col, line, &cell, is_selected);
+            } else {
+                func(screen_col, line, cell, is_selected);
+            }
         }
     }
 
@@ -197,22 +637,25 @@ impl TerminalEmulator {
         (cursor.point.column.0 as usize, cursor.point.line.0 as usize)
     }
 
+    /// Where the cursor is drawn on screen. It is hidden while scrolled
+    /// out of view sideways.
     pub fn cursor_render_info(&self) -> (usize, usize, CursorShape, Option<Rgb>) {
+        let view = *self.columns.lock();
         let term = self.term.lock();
         let content = term.renderable_content();
         let cursor = content.cursor;
         let color = content.colors[NamedColor::Cursor];
-        (
-            cursor.point.column.0 as usize,
-            cursor.point.line.0 as usize,
-            cursor.shape,
-            color,
-        )
+        let (column, shape) = match view.screen_column(cursor.point.column.0) {
+            Some(column) => (column, cursor.shape),
+            None => (0, CursorShape::Hidden),
+        };
+        (column, cursor.point.line.0 as usize, shape, color)
     }
 
     pub fn hyperlink_at(&self, col: usize, line: usize) -> Option<String> {
         use alacritty_terminal::index::{Column, Line};
 
+        let col = col + self.column_offset();
         let term = self.term.lock();
         let grid = term.grid();
         let cols = grid.columns();
@@ -237,6 +680,70 @@ impl TerminalEmulator {
         cell.hyperlink().map(|link| link.uri().to_string())
     }
 
+    /// The `path:line:col` reference under the given viewport cell, if any.
+    pub fn location_at(&self, col: usize, line: usize) -> Option<FileLocation> {
+        let (chars, index) = self.line_chars(col, line)?;
+        location_token(&chars, index)
+    }
+
+    // The characters of a viewport line and the index of the one at `col`.
+    fn line_chars(&self, col: usize, line: usize) -> Option<(Vec<char>, usize)> {
+        use alacritty_terminal::index::{Column, Line};
+        use alacritty_terminal::term::cell::Flags;
+
+        let col = col + self.column_offset();
+        let term = self.term.lock();
+        let grid = term.grid();
+        let cols = grid.columns();
+        let rows = grid.screen_lines();
+        if col >= cols || line >= rows {
+            return None;
+        }
+        let display_offset = grid.display_offset();
+        let grid_line = Line::from(line) - display_offset;
+        let row = &grid[grid_line];
+
+        let mut chars = Vec::with_capacity(cols);
+        let mut index = None;
+        for c in 0..cols {
+            let cell = &row[Column(c)];
+            if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                if c == col {
+                    index = chars.len().checked_sub(1);
+                }
+                continue;
+            }
+            if c == col {
+                index = Some(chars.len());
+            }
+            chars.push(cell.c);
+        }
+        Some((chars, index?))
+    }
+
+    /// Returns the text of the visible screen, one string per row with
+    /// trailing blanks removed.
+    pub fn screen_text(&self) -> Vec<String> {
+        use alacritty_terminal::index::{Column, Line};
+        use alacritty_terminal::term::cell::Flags;
+
+        let term = self.term.lock();
+        let grid = term.grid();
+        let cols = grid.columns();
+        let display_offset = grid.display_offset();
+        (0..grid.screen_lines())
+            .map(|line| {
+                let row = &grid[Line::from(line) - display_offset];
+                let text: String = (0..cols)
+                    .map(|c| &row[Column(c)])
+                    .filter(|cell| !cell.flags.contains(Flags::WIDE_CHAR_SPACER))
+                    .map(|cell| cell.c)
+                    .collect();
+                text.trim_end().to_string()
+            })
+            .collect()
+    }
+
     /// Returns (total_lines, view_offset, screen_lines)
     /// view_offset is the number of lines from the bottom of history to the bottom of the viewport.
     /// 0 means we are at the bottom.
@@ -260,6 +767,32 @@ impl TerminalEmulator {
         term.selection_to_string()
     }
 
+    /// Returns scrollback and screen as one string, without trailing blank
+    /// lines.
+    pub fn buffer_text(&self) -> String {
+        use alacritty_terminal::index::{Column, Point};
+
+        let term = self.term.lock();
+        let grid = term.grid();
+        let start = Point::new(grid.topmost_line(), Column(0));
+        let end = Point::new(grid.bottommost_line(), grid.last_column());
+        term.bounds_to_string(start, end).trim_end().to_string()
+    }
+
+    pub fn select_all(&mut self) {
+        use alacritty_terminal::index::{Column, Point, Side};
+        use alacritty_terminal::selection::{Selection, SelectionType};
+
+        let mut term = self.term.lock();
+        let grid = term.grid();
+        let start = Point::new(grid.topmost_line(), Column(0));
+        let end = Point::new(grid.bottommost_line(), grid.last_column());
+        let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
+        selection.update(end, Side::Right);
+        term.selection = Some(selection);
+        self.selection_start = None;
+    }
+
     pub fn on_mouse_double_click(&mut self, col: usize, line: usize) {
         use alacritty_terminal::index::Side;
         use alacritty_terminal::selection::{Selection, SelectionType};
@@ -313,6 +846,7 @@ impl TerminalEmulator {
         let display_offset = grid.display_offset();
 
         let grid_line = (line as i32) - (display_offset as i32);
+        let col = (col + self.column_offset()).min(grid.last_column().0);
 
         alacritty_terminal::index::Point::new(
             alacritty_terminal::index::Line(grid_line),
@@ -321,6 +855,60 @@ impl TerminalEmulator {
     }
 }
 
+/// Extracts the file reference around `index`, trimming surrounding
+/// punctuation, with the `:line:col` printed by compilers and grep.
+fn location_token(chars: &[char], index: usize) -> Option<FileLocation> {
+    let is_delimiter = |c: char| {
+        c.is_whitespace() || matches!(c, '"' | '\'' | '`' | '(' | ')' | '<' | '>' | '[' | ']')
+    };
+    if index >= chars.len() || is_delimiter(chars[index]) {
+        return None;
+    }
+    let mut start = index;
+    while start > 0 && !is_delimiter(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < chars.len() && !is_delimiter(chars[end]) {
+        end += 1;
+    }
+
+    let token: String = chars[start..end].iter().collect();
+    FileLocation::parse(&token)
+}
+
+const REDACTED_CHAR: char = '*';
+
+// Which columns of a line the redactor masks. The spacer after a wide
+// character goes with it.
+fn masked_columns<'a>(
+    redactor: &super::redact::Redactor,
+    cells: impl Iterator<Item = &'a alacritty_terminal::term::cell::Cell>,
+) -> Vec<bool> {
+    use alacritty_terminal::term::cell::Flags;
+
+    let mut text = String::new();
+    let mut cols = 0;
+    let mut starts = Vec::new();
+    for (col, cell) in cells.enumerate() {
+        cols += 1;
+        if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+            starts.push((text.len(), col));
+            text.push(cell.c);
+        }
+    }
+    let mut masked = vec![false; cols];
+    let ranges = redactor.ranges(&text);
+    for (index, &(offset, col)) in starts.iter().enumerate() {
+        if !ranges.iter().any(|range| range.contains(&offset)) {
+            continue;
+        }
+        let next = starts.get(index + 1).map_or(cols, |&(_, col)| col);
+        masked[col..next].fill(true);
+    }
+    masked
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,4 +974,65 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn long_lines_scroll_sideways_without_wrapping() {
+        let mut emulator = TerminalEmulator::new();
+        emulator.resize(10, 3);
+        emulator.set_wrap_lines(false);
+        emulator.process_input(b"0123456789abcdef");
+        assert_eq!(emulator.screen_text()[0], "0123456789abcdef");
+
+        // No further than the end of the widest line.
+        assert!(emulator.scroll_columns(20.0));
+        assert_eq!(emulator.column_offset(), 6);
+        let mut shown = String::new();
+        emulator.render_line(0, |_, _, cell, _| shown.push(cell.c));
+        assert_eq!(shown, "6789abcdef");
+
+        emulator.set_wrap_lines(true);
+        assert_eq!(emulator.column_offset(), 0);
+        // Wrapped again, joined back up when copied.
+        assert_eq!(emulator.buffer_text(), "0123456789abcdef");
+        assert!(emulator.screen_text().iter().all(|line| line.len() <= 10));
+    }
+
+    #[test]
+    fn notes_finished_commands_on_their_prompt_line() {
+        let mut emulator = TerminalEmulator::new();
+        emulator.resize(40, 5);
+        emulator.process_input(b"\x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07");
+        // Still running.
+        assert!(emulator.prompt_notes().is_empty());
+        emulator.process_input(b"\x1b]133;D;1\x07\x1b]133;A\x07$ ");
+        let notes = emulator.prompt_notes();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].line, 0);
+        assert_eq!(notes[0].used_columns, "$ false".len());
+        assert_eq!(notes[0].exit_code, Some(1));
+
+        // Off screen once it scrolls away.
+        emulator.process_input(b"\r\n\r\n\r\n\r\n\r\n");
+        assert!(emulator.prompt_notes().is_empty());
+    }
+
+    #[test]
+    fn test_path_token() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        let path_token =
+            |line: &[char], index| location_token(line, index).map(|location| location.path);
+
+        let line = chars("tail -f /var/log/nginx/error.log");
+        assert_eq!(
+            path_token(&line, 12).as_deref(),
+            Some("/var/log/nginx/error.log")
+        );
+        assert_eq!(path_token(&line, 2), None);
+
+        let line = chars("src=/srv/app/main.rs:42:7: error");
+        assert_eq!(path_token(&line, 6).as_deref(), Some("/srv/app/main.rs"));
+
+        let line = chars("see https://example.com/docs");
+        assert_eq!(path_token(&line, 10), None);
+    }
 }
@@ -0,0 +1,138 @@
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+/// Terminal actions that can be bound to a key chord.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    Copy,
+    Paste,
+    SelectAll,
+}
+
+/// A single binding such as `{"keys": "ctrl+shift+v", "action": "paste"}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyBinding {
+    pub keys: String,
+    pub action: KeyAction,
+}
+
+impl KeyBinding {
+    fn new(keys: &str, action: KeyAction) -> Self {
+        Self {
+            keys: keys.to_string(),
+            action,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn default_bindings() -> Vec<KeyBinding> {
+    vec![
+        KeyBinding::new("cmd+c", KeyAction::Copy),
+        KeyBinding::new("cmd+v", KeyAction::Paste),
+        KeyBinding::new("cmd+a", KeyAction::SelectAll),
+    ]
+}
+
+/// Plain Ctrl+C/V/A must reach the remote shell, so the clipboard lives on the
+/// usual terminal chords instead.
+#[cfg(not(target_os = "macos"))]
+pub fn default_bindings() -> Vec<KeyBinding> {
+    vec![
+        KeyBinding::new("ctrl+shift+c", KeyAction::Copy),
+        KeyBinding::new("ctrl+shift+v", KeyAction::Paste),
+        KeyBinding::new("ctrl+insert", KeyAction::Copy),
+        KeyBinding::new("shift+insert", KeyAction::Paste),
+        KeyBinding::new("ctrl+shift+a", KeyAction::SelectAll),
+    ]
+}
+
+pub fn lookup(bindings: &[KeyBinding], key: &Key, modifiers: Modifiers) -> Option<KeyAction> {
+    bindings
+        .iter()
+        .find(|binding| matches(&binding.keys, key, modifiers))
+        .map(|binding| binding.action)
+}
+
+/// Whether a chord such as `ctrl+shift+f1` describes this key press.
+pub fn matches(chord: &str, key: &Key, modifiers: Modifiers) -> bool {
+    let Some(name) = key_name(key) else {
+        return false;
+    };
+    parse_chord(chord)
+        .is_some_and(|(chord_mods, chord_key)| chord_mods == modifiers && chord_key == name)
+}
+
+fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Character(c) => Some(c.to_lowercase()),
+        Key::Named(named) => Some(format!("{:?}", named).to_lowercase()),
+        Key::Unidentified => None,
+    }
+}
+
+fn parse_chord(chord: &str) -> Option<(Modifiers, String)> {
+    let mut modifiers = Modifiers::empty();
+    let mut key = None;
+    for part in chord.split('+').map(|part| part.trim().to_lowercase()) {
+        match part.as_str() {
+            "ctrl" | "control" => modifiers |= Modifiers::CTRL,
+            "shift" => modifiers |= Modifiers::SHIFT,
+            "alt" | "option" => modifiers |= Modifiers::ALT,
+            "cmd" | "super" | "logo" => modifiers |= Modifiers::LOGO,
+            "" => return None,
+            _ if key.is_none() => key = Some(part),
+            _ => return None,
+        }
+    }
+    key.map(|key| (modifiers, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::keyboard::key::Named;
+
+    fn bindings() -> Vec<KeyBinding> {
+        vec![
+            KeyBinding::new("ctrl+shift+v", KeyAction::Paste),
+            KeyBinding::new("Shift+Insert", KeyAction::Paste),
+            KeyBinding::new("ctrl+insert", KeyAction::Copy),
+        ]
+    }
+
+    #[test]
+    fn matches_character_and_named_keys() {
+        let bindings = bindings();
+        assert_eq!(
+            lookup(
+                &bindings,
+                &Key::Character("V".into()),
+                Modifiers::CTRL | Modifiers::SHIFT
+            ),
+            Some(KeyAction::Paste)
+        );
+        assert_eq!(
+            lookup(&bindings, &Key::Named(Named::Insert), Modifiers::SHIFT),
+            Some(KeyAction::Paste)
+        );
+        assert_eq!(
+            lookup(&bindings, &Key::Named(Named::Insert), Modifiers::CTRL),
+            Some(KeyAction::Copy)
+        );
+    }
+
+    #[test]
+    fn requires_exact_modifiers() {
+        let bindings = bindings();
+        assert_eq!(
+            lookup(&bindings, &Key::Character("v".into()), Modifiers::CTRL),
+            None
+        );
+        assert_eq!(
+            lookup(&bindings, &Key::Named(Named::Insert), Modifiers::empty()),
+            None
+        );
+    }
+}
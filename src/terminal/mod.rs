@@ -1,5 +1,18 @@
+#[cfg(test)]
+mod conformance;
+pub mod editor;
 pub mod emulator;
 pub mod input;
+pub mod keymap;
+pub mod output;
+pub mod paste;
+pub mod redact;
+pub mod scroll;
+pub mod share;
+pub mod shell_integration;
+pub mod special_keys;
 
+pub use emulator::ClipboardRequest;
 pub use emulator::TerminalDamage;
 pub use emulator::TerminalEmulator;
+pub use output::{OutputReceiver, OutputSender, output_channel};
@@ -0,0 +1,230 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Output waiting for the UI beyond this is dropped instead of queued, so a
+/// runaway `yes` can't grow memory without limit.
+const MAX_QUEUED_BYTES: usize = 8 * 1024 * 1024;
+// Small reads are appended to the previous chunk up to this size.
+const COALESCE_BYTES: usize = 64 * 1024;
+// Upper bound on what one `recv` hands to the parser at once.
+const MAX_BATCH_BYTES: usize = 1024 * 1024;
+
+/// Start of the line written into the terminal where output was dropped.
+pub const THROTTLE_NOTICE: &[u8] = b"\r\n\x1b[0m\x1b[7m[output throttled:";
+
+#[derive(Debug)]
+enum Chunk {
+    Data(Vec<u8>),
+    Dropped(usize),
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    chunks: VecDeque<Chunk>,
+    queued_bytes: usize,
+    senders_gone: bool,
+    receiver_gone: bool,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    queue: Mutex<Queue>,
+    notify: Notify,
+    senders: AtomicUsize,
+}
+
+/// Creates the channel that carries a terminal's output from the SSH or PTY
+/// reader to the UI. It is bounded by bytes rather than messages: bursts are
+/// merged into larger chunks, and once the UI falls too far behind new output
+/// is dropped and a notice is shown in its place.
+pub fn output_channel() -> (OutputSender, OutputReceiver) {
+    let shared = Arc::new(Shared {
+        senders: AtomicUsize::new(1),
+        ..Default::default()
+    });
+    (
+        OutputSender {
+            shared: shared.clone(),
+        },
+        OutputReceiver { shared },
+    )
+}
+
+#[derive(Debug)]
+pub struct ReceiverClosed;
+
+impl std::fmt::Display for ReceiverClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("terminal output receiver closed")
+    }
+}
+
+#[derive(Debug)]
+pub struct OutputSender {
+    shared: Arc<Shared>,
+}
+
+impl OutputSender {
+    /// Never blocks; when the queue is full the data is counted as dropped.
+    pub fn send(&self, data: Vec<u8>) -> Result<(), ReceiverClosed> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let mut queue = self.shared.queue.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.receiver_gone {
+            return Err(ReceiverClosed);
+        }
+        if queue.queued_bytes + data.len() > MAX_QUEUED_BYTES {
+            match queue.chunks.back_mut() {
+                Some(Chunk::Dropped(bytes)) => *bytes += data.len(),
+                _ => queue.chunks.push_back(Chunk::Dropped(data.len())),
+            }
+        } else {
+            queue.queued_bytes += data.len();
+            match queue.chunks.back_mut() {
+                Some(Chunk::Data(last)) if last.len() + data.len() <= COALESCE_BYTES => {
+                    last.extend_from_slice(&data)
+                }
+                _ => queue.chunks.push_back(Chunk::Data(data)),
+            }
+        }
+        drop(queue);
+        self.shared.notify.notify_one();
+        Ok(())
+    }
+}
+
+impl Clone for OutputSender {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::Relaxed);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for OutputSender {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let mut queue = self.shared.queue.lock().unwrap_or_else(|e| e.into_inner());
+            queue.senders_gone = true;
+            drop(queue);
+            self.shared.notify.notify_one();
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OutputReceiver {
+    shared: Arc<Shared>,
+}
+
+impl OutputReceiver {
+    /// Waits for output and returns everything queued, up to a batch limit.
+    /// Where output was dropped a notice is returned on its own, in order.
+    /// Returns `None` once every sender is gone and the queue is empty.
+    pub async fn recv(&mut self) -> Option<Vec<u8>> {
+        loop {
+            if let Some(data) = self.take() {
+                return Some(data);
+            }
+            if self
+                .shared
+                .queue
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .senders_gone
+            {
+                // A send may have raced with the last sender going away.
+                return self.take();
+            }
+            self.shared.notify.notified().await;
+        }
+    }
+
+    fn take(&mut self) -> Option<Vec<u8>> {
+        let mut queue = self.shared.queue.lock().unwrap_or_else(|e| e.into_inner());
+        let mut batch = match queue.chunks.pop_front()? {
+            Chunk::Dropped(bytes) => return Some(throttle_notice(bytes)),
+            Chunk::Data(data) => data,
+        };
+        while batch.len() < MAX_BATCH_BYTES {
+            match queue.chunks.front_mut() {
+                Some(Chunk::Data(next)) => batch.append(next),
+                _ => break,
+            }
+            queue.chunks.pop_front();
+        }
+        queue.queued_bytes = queue.queued_bytes.saturating_sub(batch.len());
+        Some(batch)
+    }
+}
+
+impl Drop for OutputReceiver {
+    fn drop(&mut self) {
+        let mut queue = self.shared.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.receiver_gone = true;
+        queue.chunks.clear();
+    }
+}
+
+fn throttle_notice(bytes: usize) -> Vec<u8> {
+    let mut notice = THROTTLE_NOTICE.to_vec();
+    notice.extend_from_slice(
+        format!(
+            " {:.1} MB skipped]\x1b[0m\r\n",
+            bytes as f64 / (1024.0 * 1024.0)
+        )
+        .as_bytes(),
+    );
+    notice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn coalesces_small_writes() {
+        let (tx, mut rx) = output_channel();
+        for _ in 0..100 {
+            tx.send(b"y\n".to_vec()).unwrap();
+        }
+        assert_eq!(rx.recv().await.unwrap().len(), 200);
+    }
+
+    #[tokio::test]
+    async fn drops_and_marks_when_full() {
+        let (tx, mut rx) = output_channel();
+        let block = vec![b'x'; COALESCE_BYTES];
+        for _ in 0..(MAX_QUEUED_BYTES / COALESCE_BYTES + 3) {
+            tx.send(block.clone()).unwrap();
+        }
+        tx.send(b"tail".to_vec()).unwrap();
+        drop(tx);
+
+        let mut data = 0;
+        while let Some(chunk) = rx.recv().await {
+            if chunk.starts_with(THROTTLE_NOTICE) {
+                assert_eq!(data, MAX_QUEUED_BYTES);
+                break;
+            }
+            data += chunk.len();
+        }
+        // Everything after the queue filled up was dropped, `tail` included.
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn ends_when_senders_are_gone() {
+        let (tx, mut rx) = output_channel();
+        let other = tx.clone();
+        drop(tx);
+        other.send(b"last".to_vec()).unwrap();
+        drop(other);
+        assert_eq!(rx.recv().await.as_deref(), Some(&b"last"[..]));
+        assert_eq!(rx.recv().await, None);
+    }
+}
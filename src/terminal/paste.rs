@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+/// Optional transforms applied to clipboard text before it is sent to the
+/// terminal.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PasteOptions {
+    #[serde(default)]
+    pub strip_trailing_newline: bool,
+    #[serde(default)]
+    pub convert_crlf: bool,
+    #[serde(default)]
+    pub collapse_leading_whitespace: bool,
+    #[serde(default)]
+    pub slow: bool,
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+    #[serde(default = "default_chunk_delay_ms")]
+    pub chunk_delay_ms: u64,
+}
+
+fn default_chunk_size() -> usize {
+    16
+}
+
+fn default_chunk_delay_ms() -> u64 {
+    20
+}
+
+impl Default for PasteOptions {
+    fn default() -> Self {
+        Self {
+            strip_trailing_newline: false,
+            convert_crlf: false,
+            collapse_leading_whitespace: false,
+            slow: false,
+            chunk_size: default_chunk_size(),
+            chunk_delay_ms: default_chunk_delay_ms(),
+        }
+    }
+}
+
+impl PasteOptions {
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = if self.convert_crlf {
+            text.replace("\r\n", "\n")
+        } else {
+            text.to_string()
+        };
+
+        if self.collapse_leading_whitespace {
+            result = result
+                .split('\n')
+                .map(|line| line.trim_start_matches([' ', '\t']))
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+
+        if self.strip_trailing_newline {
+            let trimmed = result.trim_end_matches(['\r', '\n']).len();
+            result.truncate(trimmed);
+        }
+
+        result
+    }
+
+    /// Splits paste data into the chunks sent when slow paste is enabled.
+    pub fn chunks(&self, data: Vec<u8>) -> Vec<Vec<u8>> {
+        if !self.slow {
+            return vec![data];
+        }
+        data.chunks(self.chunk_size.max(1))
+            .map(|chunk| chunk.to_vec())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_leaves_text_untouched() {
+        let options = PasteOptions::default();
+        assert_eq!(options.apply("  ls -la\r\n"), "  ls -la\r\n");
+    }
+
+    #[test]
+    fn applies_enabled_transforms() {
+        let options = PasteOptions {
+            strip_trailing_newline: true,
+            convert_crlf: true,
+            collapse_leading_whitespace: true,
+            ..PasteOptions::default()
+        };
+        assert_eq!(
+            options.apply("if true; then\r\n    echo hi\r\n\tfi\r\n\r\n"),
+            "if true; then\necho hi\nfi"
+        );
+    }
+
+    #[test]
+    fn slow_paste_splits_into_chunks() {
+        let options = PasteOptions {
+            slow: true,
+            chunk_size: 4,
+            ..PasteOptions::default()
+        };
+        assert_eq!(
+            options.chunks(b"abcdefghij".to_vec()),
+            vec![b"abcd".to_vec(), b"efgh".to_vec(), b"ij".to_vec()]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use parking_lot::RwLock;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Always masked while redaction is on, in front of the user's patterns.
+/// A pattern with a capture group masks only the first group, so the label
+/// in `password: hunter2` stays readable.
+const BUILTIN_PATTERNS: &[&str] = &[
+    // IPv4 addresses.
+    r"\b(?:\d{1,3}\.){3}\d{1,3}\b",
+    // IPv6 addresses, full or with `::`; times like 12:30:45 don't match.
+    r"\b(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}\b",
+    r"\b[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*::[0-9a-fA-F]{1,4}(?::[0-9a-fA-F]{1,4})*\b",
+    // Credentials printed as `key=value` or `key: value`.
+    r"(?i)(?:password|passwd|secret|token|api[_-]?key|access[_-]?key)\b\s*[:=]\s*(\S+)",
+    r"(?i)\bbearer\s+([A-Za-z0-9._~+/-]+=*)",
+    // AWS access key IDs.
+    r"\bAKIA[0-9A-Z]{16}\b",
+];
+
+static REDACTOR: RwLock<Option<Arc<Redactor>>> = RwLock::new(None);
+
+/// Patterns whose matches are masked in rendered terminal output while
+/// screen sharing.
+#[derive(Debug)]
+pub struct Redactor {
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    /// Invalid user patterns are skipped; settings flags them as they are
+    /// typed. Hostnames are matched literally.
+    pub fn new<'a>(patterns: &[String], hosts: impl IntoIterator<Item = &'a str>) -> Self {
+        let builtin = BUILTIN_PATTERNS.iter().map(|pattern| pattern.to_string());
+        let hosts = hosts
+            .into_iter()
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .map(|host| format!(r"(?i)\b{}\b", regex::escape(host)));
+        let patterns = builtin
+            .chain(hosts)
+            .chain(patterns.iter().map(|pattern| pattern.trim().to_string()))
+            .filter(|pattern| !pattern.is_empty())
+            .filter_map(|pattern| match Regex::new(&pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!("skipping redaction pattern {:?}: {}", pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    /// Byte ranges of `text` to mask, possibly overlapping.
+    pub fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.patterns
+            .iter()
+            .flat_map(|pattern| {
+                pattern.captures_iter(text).filter_map(|captures| {
+                    captures
+                        .get(1)
+                        .or_else(|| captures.get(0))
+                        .map(|found| found.range())
+                })
+            })
+            .filter(|range| !range.is_empty())
+            .collect()
+    }
+}
+
+pub fn validate(pattern: &str) -> Result<(), String> {
+    Regex::new(pattern.trim())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Turns redaction on with `redactor`, or off with `None`. Both terminal
+/// renderers read it when they draw a line.
+pub fn set(redactor: Option<Redactor>) {
+    *REDACTOR.write() = redactor.map(Arc::new);
+}
+
+pub fn current() -> Option<Arc<Redactor>> {
+    REDACTOR.read().clone()
+}
+
+pub fn is_enabled() -> bool {
+    REDACTOR.read().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masked(redactor: &Redactor, text: &str) -> String {
+        let ranges = redactor.ranges(text);
+        text.char_indices()
+            .map(|(index, c)| {
+                if ranges.iter().any(|range| range.contains(&index)) {
+                    '*'
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn masks_addresses_secrets_and_hosts() {
+        let redactor = Redactor::new(&["acme-\\d+".to_string()], ["db.internal"]);
+        assert_eq!(
+            masked(&redactor, "inet 10.0.3.7/24 fe80::1 up 12:30:45"),
+            "inet ********/24 ******* up 12:30:45"
+        );
+        assert_eq!(
+            masked(&redactor, "DB_PASSWORD=hunter2 token: abc"),
+            "DB_PASSWORD=******* token: ***"
+        );
+        assert_eq!(
+            masked(&redactor, "ssh DB.internal for acme-42"),
+            "ssh *********** for *******"
+        );
+        assert_eq!(masked(&redactor, "cargo build"), "cargo build");
+    }
+}
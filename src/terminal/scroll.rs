@@ -0,0 +1,169 @@
+use iced::mouse::ScrollDelta;
+use serde::{Deserialize, Serialize};
+
+// Trackpads report pixels; this many make one line at sensitivity 1.
+const PIXELS_PER_LINE: f32 = 20.0;
+// Columns moved sideways by one wheel notch on lines that don't wrap.
+const COLUMNS_PER_NOTCH: f32 = 4.0;
+
+/// How mouse wheel and trackpad movement scrolls the terminal.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ScrollOptions {
+    /// Lines moved by one wheel notch.
+    #[serde(default = "default_lines_per_notch")]
+    pub lines_per_notch: f32,
+    /// Multiplier for pixel-precise trackpad scrolling.
+    #[serde(default = "default_sensitivity")]
+    pub sensitivity: f32,
+    /// Natural scrolling: content follows the fingers.
+    #[serde(default)]
+    pub invert: bool,
+    /// What the wheel does in full-screen programs such as less or vim.
+    #[serde(default)]
+    pub alternate_screen: AltScreenScroll,
+    /// Arrow key presses sent per wheel notch with `AltScreenScroll::Arrows`.
+    #[serde(default = "default_arrows_per_notch")]
+    pub arrows_per_notch: f32,
+}
+
+fn default_arrows_per_notch() -> f32 {
+    3.0
+}
+
+fn default_lines_per_notch() -> f32 {
+    1.0
+}
+
+fn default_sensitivity() -> f32 {
+    1.0
+}
+
+impl Default for ScrollOptions {
+    fn default() -> Self {
+        Self {
+            lines_per_notch: default_lines_per_notch(),
+            sensitivity: default_sensitivity(),
+            invert: false,
+            alternate_screen: AltScreenScroll::default(),
+            arrows_per_notch: default_arrows_per_notch(),
+        }
+    }
+}
+
+impl ScrollOptions {
+    /// Lines to scroll for a wheel event; positive moves back into history.
+    pub fn lines(&self, delta: ScrollDelta) -> f32 {
+        let lines = match delta {
+            ScrollDelta::Lines { y, .. } => y * self.lines_per_notch,
+            ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE * self.sensitivity,
+        };
+        if self.invert { -lines } else { lines }
+    }
+
+    /// Arrow key presses for a wheel event in a full-screen program;
+    /// positive is Up. A trackpad moving one line's worth counts as a notch.
+    pub fn arrows(&self, delta: ScrollDelta) -> f32 {
+        let notches = match delta {
+            ScrollDelta::Lines { y, .. } => y,
+            ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE * self.sensitivity,
+        };
+        let arrows = notches * self.arrows_per_notch;
+        if self.invert { -arrows } else { arrows }
+    }
+
+    /// Columns to scroll sideways for a wheel event; positive moves right.
+    /// With `shift` held a vertical wheel scrolls sideways too.
+    pub fn columns(&self, delta: ScrollDelta, shift: bool) -> f32 {
+        let (x, y) = match delta {
+            ScrollDelta::Lines { x, y } => (x, y),
+            ScrollDelta::Pixels { x, y } => (
+                x / PIXELS_PER_LINE * self.sensitivity,
+                y / PIXELS_PER_LINE * self.sensitivity,
+            ),
+        };
+        let notches = if x != 0.0 {
+            x
+        } else if shift {
+            y
+        } else {
+            0.0
+        };
+        let columns = -notches * COLUMNS_PER_NOTCH;
+        if self.invert { -columns } else { columns }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AltScreenScroll {
+    /// Send Up and Down keys, so the program scrolls itself.
+    #[default]
+    Arrows,
+    /// Scroll the terminal's own history, which full-screen programs
+    /// usually leave empty.
+    History,
+}
+
+impl AltScreenScroll {
+    pub const ALL: [AltScreenScroll; 2] = [AltScreenScroll::Arrows, AltScreenScroll::History];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AltScreenScroll::Arrows => "Arrow keys",
+            AltScreenScroll::History => "History",
+        }
+    }
+}
+
+/// The key presses sent in place of scrolling `steps` lines.
+pub fn arrow_keys(steps: i32, app_cursor: bool) -> Vec<u8> {
+    let key: &[u8] = match (steps > 0, app_cursor) {
+        (true, true) => b"\x1bOA",
+        (true, false) => b"\x1b[A",
+        (false, true) => b"\x1bOB",
+        (false, false) => b"\x1b[B",
+    };
+    key.repeat(steps.unsigned_abs() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_and_inverts_wheel_deltas() {
+        let options = ScrollOptions {
+            lines_per_notch: 3.0,
+            sensitivity: 2.0,
+            ..ScrollOptions::default()
+        };
+        assert_eq!(options.lines(ScrollDelta::Lines { x: 0.0, y: 1.0 }), 3.0);
+        assert_eq!(options.lines(ScrollDelta::Pixels { x: 0.0, y: 10.0 }), 1.0);
+
+        let natural = ScrollOptions {
+            invert: true,
+            ..options
+        };
+        assert_eq!(natural.lines(ScrollDelta::Lines { x: 0.0, y: 1.0 }), -3.0);
+        assert_eq!(natural.arrows(ScrollDelta::Lines { x: 0.0, y: -1.0 }), 3.0);
+    }
+
+    #[test]
+    fn shift_turns_the_wheel_sideways() {
+        let options = ScrollOptions::default();
+        let down = ScrollDelta::Lines { x: 0.0, y: -1.0 };
+        assert_eq!(options.columns(down, false), 0.0);
+        assert_eq!(options.columns(down, true), 4.0);
+        assert_eq!(
+            options.columns(ScrollDelta::Pixels { x: 40.0, y: 0.0 }, false),
+            -8.0
+        );
+    }
+
+    #[test]
+    fn arrow_keys_follow_cursor_mode() {
+        assert_eq!(arrow_keys(2, false), b"\x1b[A\x1b[A".to_vec());
+        assert_eq!(arrow_keys(-1, true), b"\x1bOB".to_vec());
+        assert!(arrow_keys(0, false).is_empty());
+    }
+}
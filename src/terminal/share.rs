@@ -0,0 +1,340 @@
+use parking_lot::Mutex;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+
+use super::{OutputReceiver, output_channel};
+
+const SCHEME: &str = "rivett://";
+// Recent output sent to a viewer as it joins so it starts with a full
+// screen instead of a blank one.
+const REPLAY_BYTES: usize = 256 * 1024;
+// Chunks a slow viewer may fall behind by before it is dropped.
+const BROADCAST_CAPACITY: usize = 1024;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const CODE_LENGTH: usize = 6;
+// No 0/O or 1/I, so a code read out loud can't be mistyped.
+const CODE_ALPHABET: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+// Wrong codes tried before no one else is let in, so the code can't be
+// guessed by trying them all.
+const MAX_WRONG_CODES: usize = 5;
+
+/// Where to watch a shared terminal, written as `rivett://host:port/CODE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareLink {
+    pub addr: SocketAddr,
+    pub code: String,
+}
+
+impl ShareLink {
+    /// Finds a link anywhere in `text`, such as a chat message.
+    pub fn find(text: &str) -> Option<Self> {
+        let start = text.find(SCHEME)?;
+        let rest = &text[start + SCHEME.len()..];
+        let link = rest.split_whitespace().next()?;
+        let (addr, code) = link.split_once('/')?;
+        let code = code.trim_end_matches(['.', ',', ')', '>']).to_uppercase();
+        if code.len() != CODE_LENGTH || !code.bytes().all(|b| CODE_ALPHABET.contains(&b)) {
+            return None;
+        }
+        Some(Self {
+            addr: addr.parse().ok()?,
+            code,
+        })
+    }
+}
+
+impl fmt::Display for ShareLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}/{}", SCHEME, self.addr, self.code)
+    }
+}
+
+struct HostShared {
+    title: String,
+    // Each code lets one viewer in; the next viewer needs the new one.
+    code: Mutex<String>,
+    wrong_codes: AtomicUsize,
+    replay: Mutex<Vec<u8>>,
+    tx: broadcast::Sender<Arc<Vec<u8>>>,
+}
+
+/// A terminal tab mirrored read-only to viewers on the network. Viewers
+/// only ever receive output; nothing they send reaches the session.
+/// Dropping it stops sharing and disconnects everyone.
+pub struct ShareHost {
+    addr: SocketAddr,
+    shared: Arc<HostShared>,
+    viewers: Arc<AtomicUsize>,
+    listener: tokio::task::JoinHandle<()>,
+}
+
+impl fmt::Debug for ShareHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShareHost")
+            .field("addr", &self.addr)
+            .field("viewers", &self.viewers())
+            .finish()
+    }
+}
+
+impl ShareHost {
+    /// Listens on all interfaces. `screen` seeds what the first viewer
+    /// sees, since the output that drew it is long gone.
+    pub async fn start(title: String, screen: Vec<u8>) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .await
+            .map_err(|e| format!("Failed to listen for viewers: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to listen for viewers: {}", e))?
+            .port();
+        let (tx, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let shared = Arc::new(HostShared {
+            title,
+            code: Mutex::new(new_code()),
+            wrong_codes: AtomicUsize::new(0),
+            replay: Mutex::new(screen),
+            tx,
+        });
+        let viewers = Arc::new(AtomicUsize::new(0));
+        let listener = tokio::spawn(accept_loop(listener, shared.clone(), viewers.clone()));
+        Ok(Self {
+            addr: SocketAddr::new(lan_address(), port),
+            shared,
+            viewers,
+            listener,
+        })
+    }
+
+    /// The link for the next viewer.
+    pub fn link(&self) -> ShareLink {
+        ShareLink {
+            addr: self.addr,
+            code: self.shared.code.lock().clone(),
+        }
+    }
+
+    pub fn viewers(&self) -> usize {
+        self.viewers.load(Ordering::Relaxed)
+    }
+
+    /// Too many wrong codes were tried, so no more viewers are let in.
+    /// Viewers already watching stay.
+    pub fn locked_out(&self) -> bool {
+        self.shared.locked_out()
+    }
+
+    pub fn send(&self, data: &[u8]) {
+        // Held while broadcasting so a viewer joining now gets each byte
+        // exactly once, from either the replay or the stream.
+        let mut replay = self.shared.replay.lock();
+        replay.extend_from_slice(data);
+        if replay.len() > REPLAY_BYTES {
+            let excess = replay.len() - REPLAY_BYTES / 2;
+            replay.drain(..excess);
+        }
+        let _ = self.shared.tx.send(Arc::new(data.to_vec()));
+    }
+}
+
+impl HostShared {
+    fn locked_out(&self) -> bool {
+        self.wrong_codes.load(Ordering::Relaxed) >= MAX_WRONG_CODES
+    }
+}
+
+impl Drop for ShareHost {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+async fn accept_loop(listener: TcpListener, shared: Arc<HostShared>, viewers: Arc<AtomicUsize>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                tracing::warn!("share accept failed: {}", e);
+                continue;
+            }
+        };
+        if shared.locked_out() {
+            tracing::warn!("share stopped listening after too many wrong codes");
+            return;
+        }
+        let shared = shared.clone();
+        let viewers = viewers.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, admit(stream, &shared)).await {
+                Ok(Ok((stream, rx))) => {
+                    tracing::info!("share viewer joined from {}", peer);
+                    drop(shared);
+                    viewers.fetch_add(1, Ordering::Relaxed);
+                    stream_to_viewer(stream, rx).await;
+                    viewers.fetch_sub(1, Ordering::Relaxed);
+                }
+                Ok(Err(e)) => tracing::warn!("share viewer {} refused: {}", peer, e),
+                Err(_) => tracing::warn!("share viewer {} timed out", peer),
+            }
+        });
+    }
+}
+
+// Checks the code, then sends the title and recent output.
+async fn admit(
+    mut stream: TcpStream,
+    shared: &HostShared,
+) -> Result<(TcpStream, broadcast::Receiver<Arc<Vec<u8>>>), String> {
+    let mut line = String::new();
+    BufReader::new(&mut stream)
+        .take(64)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+    let accepted = {
+        let mut code = shared.code.lock();
+        let accepted = !shared.locked_out() && line.trim().eq_ignore_ascii_case(&code);
+        if accepted {
+            *code = new_code();
+        } else {
+            shared.wrong_codes.fetch_add(1, Ordering::Relaxed);
+        }
+        accepted
+    };
+    if !accepted {
+        let _ = stream.write_all(b"NO\n").await;
+        return Err("wrong code".to_string());
+    }
+    let (replay, rx) = {
+        let replay = shared.replay.lock();
+        (replay.clone(), shared.tx.subscribe())
+    };
+    let title = shared.title.replace('\n', " ");
+    stream
+        .write_all(format!("OK {}\n", title).as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    stream.write_all(&replay).await.map_err(|e| e.to_string())?;
+    Ok((stream, rx))
+}
+
+async fn stream_to_viewer(mut stream: TcpStream, mut rx: broadcast::Receiver<Arc<Vec<u8>>>) {
+    loop {
+        match rx.recv().await {
+            Ok(data) => {
+                if stream.write_all(&data).await.is_err() {
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => {
+                let _ = stream
+                    .write_all(b"\r\n[disconnected: this viewer fell behind]\r\n")
+                    .await;
+                return;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+/// Joins a shared terminal. Returns its title and a receiver carrying its
+/// output, which ends when the host stops sharing.
+pub async fn watch(link: &ShareLink) -> Result<(String, OutputReceiver), String> {
+    let connect = async {
+        let mut stream = TcpStream::connect(link.addr)
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", link.addr, e))?;
+        stream
+            .write_all(format!("{}\n", link.code).as_bytes())
+            .await
+            .map_err(|e| format!("Failed to send code: {}", e))?;
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| format!("Failed to join: {}", e))?;
+        match line.trim_end().strip_prefix("OK") {
+            Some(title) => Ok((title.trim().to_string(), reader)),
+            None => Err("The code was not accepted; ask for a new link".to_string()),
+        }
+    };
+    let (title, mut reader) = tokio::time::timeout(HANDSHAKE_TIMEOUT, connect)
+        .await
+        .map_err(|_| format!("Timed out joining {}", link.addr))??;
+
+    let (tx, rx) = output_channel();
+    tokio::spawn(async move {
+        let mut buffer = vec![0; 32 * 1024];
+        loop {
+            match reader.read(&mut buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(read) => {
+                    if tx.send(buffer[..read].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    Ok((title, rx))
+}
+
+fn new_code() -> String {
+    let bytes = uuid::Uuid::new_v4().into_bytes();
+    bytes[..CODE_LENGTH]
+        .iter()
+        .map(|b| CODE_ALPHABET[*b as usize % CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+// The address other machines on the LAN reach this one at. Connecting a UDP
+// socket sends nothing; it only picks the outgoing interface.
+fn lan_address() -> IpAddr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_links_in_messages() {
+        let link = ShareLink::find("watch rivett://192.168.1.20:40123/k7pq2m.").unwrap();
+        assert_eq!(link.addr, "192.168.1.20:40123".parse().unwrap());
+        assert_eq!(link.code, "K7PQ2M");
+        assert_eq!(link.to_string(), "rivett://192.168.1.20:40123/K7PQ2M");
+        assert_eq!(ShareLink::find("rivett://192.168.1.20:40123/K0"), None);
+        assert_eq!(ShareLink::find("ssh deploy@web-1"), None);
+    }
+
+    #[tokio::test]
+    async fn stops_admitting_after_too_many_wrong_codes() {
+        let host = ShareHost::start("web-1".to_string(), Vec::new())
+            .await
+            .unwrap();
+        let mut link = host.link();
+        link.addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), link.addr.port());
+        let wrong = ShareLink {
+            code: "222222".to_string(),
+            ..link.clone()
+        };
+        for _ in 0..MAX_WRONG_CODES {
+            assert!(watch(&wrong).await.is_err());
+        }
+        assert!(host.locked_out());
+        assert!(watch(&link).await.is_err());
+    }
+}
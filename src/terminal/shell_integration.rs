@@ -0,0 +1,321 @@
+use std::time::{Duration, Instant};
+
+/// Keep the sidebar bounded for long-lived tabs.
+const MAX_RECORDS: usize = 1000;
+// Long enough for a deep working directory; longer payloads (big titles,
+// hyperlinks) are left entirely to the terminal parser.
+const MAX_OSC_LEN: usize = 1024;
+
+/// Semantic prompt marks (OSC 133) emitted by shell integration scripts,
+/// plus the working directory a shell reports with OSC 7 and variables it
+/// publishes with iTerm2's `SetUserVar`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mark {
+    /// `A`: the prompt is about to be drawn.
+    PromptStart,
+    /// `B`: the prompt ended; what follows is the typed command.
+    CommandStart,
+    /// `C`: the command was submitted and its output starts.
+    CommandExecuted,
+    /// `D[;exit]`: the command finished.
+    CommandFinished(Option<i32>),
+    /// `7;file://host/path`: the shell changed directory.
+    WorkingDirectory(String),
+    /// `1337;SetUserVar=NAME=<base64 value>`.
+    UserVar(String, String),
+}
+
+impl Mark {
+    fn parse(payload: &[u8]) -> Option<Self> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        if let Some(url) = payload.strip_prefix("7;") {
+            // The host part is dropped; the path starts at its first slash.
+            let rest = url.strip_prefix("file://")?;
+            let path = &rest[rest.find('/')?..];
+            return Some(Mark::WorkingDirectory(percent_decode(path)?));
+        }
+        if let Some(var) = payload.strip_prefix("1337;SetUserVar=") {
+            use base64::Engine;
+            let (name, value) = var.split_once('=')?;
+            let valid_name = !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_name {
+                return None;
+            }
+            let value = base64::engine::general_purpose::STANDARD
+                .decode(value)
+                .ok()?;
+            return Some(Mark::UserVar(
+                name.to_string(),
+                String::from_utf8(value).ok()?,
+            ));
+        }
+        let mut parts = payload.strip_prefix("133;")?.split(';');
+        match parts.next()? {
+            "A" => Some(Mark::PromptStart),
+            "B" => Some(Mark::CommandStart),
+            "C" => Some(Mark::CommandExecuted),
+            "D" => Some(Mark::CommandFinished(
+                parts.next().and_then(|code| code.trim().parse().ok()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Finds OSC 133, OSC 7 and `SetUserVar` marks in the output stream. Sequences may be split across
+/// reads, so the scanner keeps its state between calls.
+#[derive(Debug, Default)]
+pub struct MarkScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+}
+
+impl MarkScanner {
+    /// Returns each mark with the byte offset just past its terminator, so
+    /// the caller can feed `data[..offset]` to the terminal before acting.
+    pub fn scan(&mut self, data: &[u8]) -> Vec<(usize, Mark)> {
+        let mut marks = Vec::new();
+        for (index, &byte) in data.iter().enumerate() {
+            self.state = match (self.state, byte) {
+                (ScanState::Ground, 0x1b) => ScanState::Escape,
+                (ScanState::Ground, _) => ScanState::Ground,
+                (ScanState::Escape, b']') => {
+                    self.payload.clear();
+                    ScanState::Osc
+                }
+                (ScanState::Escape, 0x1b) => ScanState::Escape,
+                (ScanState::Escape, _) => ScanState::Ground,
+                (ScanState::Osc, 0x07) | (ScanState::OscEscape, b'\\') => {
+                    if let Some(mark) = Mark::parse(&self.payload) {
+                        marks.push((index + 1, mark));
+                    }
+                    ScanState::Ground
+                }
+                (ScanState::Osc, 0x1b) => ScanState::OscEscape,
+                (ScanState::Osc, _) if self.payload.len() < MAX_OSC_LEN => {
+                    self.payload.push(byte);
+                    ScanState::Osc
+                }
+                (ScanState::Osc, _) => ScanState::Ground,
+                (ScanState::OscEscape, b']') => {
+                    self.payload.clear();
+                    ScanState::Osc
+                }
+                (ScanState::OscEscape, _) => ScanState::Ground,
+            };
+        }
+        marks
+    }
+}
+
+/// Lines are counted from the top of the scrollback (screen line plus
+/// history size) so they stay put while output scrolls.
+#[derive(Debug, Clone)]
+pub struct CommandRecord {
+    pub command: String,
+    pub started_at: chrono::DateTime<chrono::Local>,
+    started: Instant,
+    pub duration: Option<Duration>,
+    pub exit_code: Option<i32>,
+    /// Line holding the prompt and typed command.
+    pub prompt_line: i64,
+    /// First line of output.
+    pub output_line: i64,
+    /// Line the cursor was on when the command finished; output ends on the
+    /// line before unless the cursor had moved along it.
+    pub end_line: Option<i64>,
+}
+
+impl CommandRecord {
+    pub fn is_running(&self) -> bool {
+        self.duration.is_none()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.duration.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    /// Whether `line` falls inside this command's prompt or output.
+    pub fn contains_line(&self, line: i64, current_line: i64) -> bool {
+        line >= self.prompt_line && line <= self.end_line.unwrap_or(current_line)
+    }
+}
+
+/// How a finished command went, noted at the end of its prompt line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptNote {
+    /// Viewport line holding the prompt.
+    pub line: usize,
+    /// Columns the prompt and typed command take up on that line.
+    pub used_columns: usize,
+    pub duration: Duration,
+    pub exit_code: Option<i32>,
+}
+
+/// Commands run in one tab, oldest first.
+#[derive(Debug, Default)]
+pub struct CommandHistory {
+    records: Vec<CommandRecord>,
+}
+
+impl CommandHistory {
+    pub fn records(&self) -> &[CommandRecord] {
+        &self.records
+    }
+
+    pub fn command_executed(&mut self, command: String, prompt_line: i64, output_line: i64) {
+        self.finish_running(None, output_line);
+        let command = command.trim().to_string();
+        if command.is_empty() {
+            return;
+        }
+        if self.records.len() >= MAX_RECORDS {
+            self.records.remove(0);
+        }
+        self.records.push(CommandRecord {
+            command,
+            started_at: chrono::Local::now(),
+            started: Instant::now(),
+            duration: None,
+            exit_code: None,
+            prompt_line,
+            output_line,
+            end_line: None,
+        });
+    }
+
+    /// Closes the running command. A new prompt without `D` (for example
+    /// after Ctrl+C in some shells) finishes it with no exit code.
+    pub fn finish_running(&mut self, exit_code: Option<i32>, end_line: i64) {
+        if let Some(record) = self.records.last_mut().filter(|r| r.is_running()) {
+            record.duration = Some(record.started.elapsed());
+            record.exit_code = exit_code;
+            record.end_line = Some(end_line.max(record.output_line));
+        }
+    }
+
+    /// Index of the most recent command whose block covers `line`.
+    pub fn block_at(&self, line: i64, current_line: i64) -> Option<usize> {
+        self.records
+            .iter()
+            .rposition(|record| record.contains_line(line, current_line))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&CommandRecord> {
+        self.records.get(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_marks_with_both_terminators() {
+        let mut scanner = MarkScanner::default();
+        let data = b"\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\r\n\x1b]133;C\x07out\x1b]133;D;2\x07";
+        let marks: Vec<Mark> = scanner.scan(data).into_iter().map(|(_, m)| m).collect();
+        assert_eq!(
+            marks,
+            vec![
+                Mark::PromptStart,
+                Mark::CommandStart,
+                Mark::CommandExecuted,
+                Mark::CommandFinished(Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_split_across_reads() {
+        let mut scanner = MarkScanner::default();
+        assert!(scanner.scan(b"abc\x1b]13").is_empty());
+        assert_eq!(
+            scanner.scan(b"3;C\x07rest"),
+            vec![(4, Mark::CommandExecuted)]
+        );
+    }
+
+    #[test]
+    fn reads_working_directory_reports() {
+        let mut scanner = MarkScanner::default();
+        let marks = scanner.scan(b"\x1b]7;file://web-1/srv/my%20app\x07\x1b]7;file:///tmp\x1b\\");
+        assert_eq!(
+            marks.into_iter().map(|(_, m)| m).collect::<Vec<_>>(),
+            vec![
+                Mark::WorkingDirectory("/srv/my app".to_string()),
+                Mark::WorkingDirectory("/tmp".to_string()),
+            ]
+        );
+        let marks = scanner.scan(b"\x1b]1337;SetUserVar=VIRTUAL_ENV=L3Nydi8udmVudg==\x07");
+        assert_eq!(
+            marks.into_iter().map(|(_, m)| m).collect::<Vec<_>>(),
+            vec![Mark::UserVar(
+                "VIRTUAL_ENV".to_string(),
+                "/srv/.venv".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn ignores_other_osc_sequences() {
+        let mut scanner = MarkScanner::default();
+        assert!(scanner.scan(b"\x1b]0;title\x07\x1b]1330;A\x07").is_empty());
+        let long_title = format!("\x1b]2;{}\x07", "x".repeat(2000));
+        assert!(scanner.scan(long_title.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn records_durations_and_exit_codes() {
+        let mut history = CommandHistory::default();
+        history.command_executed("  make test ".to_string(), 10, 11);
+        assert!(history.records()[0].is_running());
+        history.finish_running(Some(1), 40);
+        history.command_executed(String::new(), 41, 42);
+        let record = &history.records()[0];
+        assert_eq!(history.records().len(), 1);
+        assert_eq!(record.command, "make test");
+        assert_eq!(record.exit_code, Some(1));
+        assert!(!record.is_running());
+    }
+
+    #[test]
+    fn finds_block_by_line() {
+        let mut history = CommandHistory::default();
+        history.command_executed("ls".to_string(), 0, 1);
+        history.finish_running(Some(0), 5);
+        history.command_executed("top".to_string(), 6, 7);
+        assert_eq!(history.block_at(3, 20), Some(0));
+        assert_eq!(history.block_at(5, 20), Some(0));
+        assert_eq!(history.block_at(15, 20), Some(1));
+        assert_eq!(history.block_at(25, 20), None);
+    }
+}
@@ -0,0 +1,128 @@
+use iced::keyboard::key::Named;
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::terminal::input::map_key_to_input;
+
+/// A user-defined button that sends a fixed byte sequence.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomKey {
+    pub label: String,
+    /// Text with escapes: `\e`, `\r`, `\n`, `\t`, `\\`, `\xNN` and `^X` for
+    /// control characters.
+    pub sequence: String,
+    #[serde(default)]
+    pub shortcut: Option<String>,
+}
+
+impl CustomKey {
+    pub fn bytes(&self) -> Vec<u8> {
+        parse_sequence(&self.sequence)
+    }
+}
+
+/// Keys offered by the "Send key" menu, in display order.
+pub fn special_keys() -> Vec<(String, Vec<u8>)> {
+    let mut keys = vec![
+        ("Ctrl+C".to_string(), vec![0x03]),
+        ("Ctrl+D".to_string(), vec![0x04]),
+        ("Ctrl+Z".to_string(), vec![0x1a]),
+        ("Ctrl+\\".to_string(), vec![0x1c]),
+        ("Esc".to_string(), vec![0x1b]),
+        // The SSH client has no break request, so Break is approximated by a
+        // NUL byte.
+        ("Break".to_string(), vec![0x00]),
+    ];
+    let function_keys = [
+        Named::F1,
+        Named::F2,
+        Named::F3,
+        Named::F4,
+        Named::F5,
+        Named::F6,
+        Named::F7,
+        Named::F8,
+        Named::F9,
+        Named::F10,
+        Named::F11,
+        Named::F12,
+    ];
+    for (index, named) in function_keys.into_iter().enumerate() {
+        if let Some(bytes) = map_key_to_input(Key::Named(named), Modifiers::empty()) {
+            keys.push((format!("F{}", index + 1), bytes));
+        }
+    }
+    keys
+}
+
+pub fn parse_sequence(sequence: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(sequence.len());
+    let mut chars = sequence.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('e') => bytes.push(0x1b),
+                Some('r') => bytes.push(b'\r'),
+                Some('n') => bytes.push(b'\n'),
+                Some('t') => bytes.push(b'\t'),
+                Some('0') => bytes.push(0x00),
+                Some('x') => {
+                    let hex: String = (0..2)
+                        .filter_map(|_| chars.next_if(char::is_ascii_hexdigit))
+                        .collect();
+                    match u8::from_str_radix(&hex, 16) {
+                        Ok(value) => bytes.push(value),
+                        Err(_) => bytes.extend_from_slice(b"\\x"),
+                    }
+                }
+                Some(other) => push_char(&mut bytes, other),
+                None => bytes.push(b'\\'),
+            },
+            '^' => match chars.peek().copied() {
+                Some(next @ ('@'..='_' | 'a'..='z' | '?')) => {
+                    chars.next();
+                    let value = if next == '?' {
+                        0x7f
+                    } else {
+                        (next.to_ascii_uppercase() as u8) & 0x1f
+                    };
+                    bytes.push(value);
+                }
+                _ => bytes.push(b'^'),
+            },
+            other => push_char(&mut bytes, other),
+        }
+    }
+    bytes
+}
+
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    let mut buf = [0u8; 4];
+    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_escapes_and_caret_notation() {
+        assert_eq!(parse_sequence("show run\\r"), b"show run\r");
+        assert_eq!(
+            parse_sequence("\\e[A^c^?"),
+            vec![0x1b, b'[', b'A', 0x03, 0x7f]
+        );
+        assert_eq!(parse_sequence("\\x1B\\x7\\\\"), vec![0x1b, 0x07, b'\\']);
+        assert_eq!(parse_sequence("a^ b^"), b"a^ b^");
+    }
+
+    #[test]
+    fn palette_includes_function_keys() {
+        let keys = special_keys();
+        assert!(
+            keys.iter()
+                .any(|(label, bytes)| label == "F1" && bytes == b"\x1bOP")
+        );
+        assert!(keys.iter().any(|(label, _)| label == "F12"));
+    }
+}
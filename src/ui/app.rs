@@ -2,16 +2,38 @@ use iced::{Settings, Task, Theme};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use super::message::{ActiveView, Message, SessionDialogTab};
-use super::state::{ConnectionTestStatus, SessionTab, SftpPane, SftpState, SftpTransferUpdate};
+use super::credentials::CredentialQueue;
+use super::edge_scroll::EdgeScroll;
+use super::message::{ActiveView, Message, SessionDialogTab, SessionMessage};
+use super::state::{
+    AuthChoice, CommandMatch, ConnectionTestStatus, DeployKey, DisconnectToast, HostProbe,
+    IdleScreen, JobFailureToast, JobOwner, JobRun, LogTailState, PlaceholderPrompt,
+    SelectionActions, SessionSort, SessionTab, SftpPane, SftpState, SftpTransferUpdate,
+    TerminalMenu, TransferPanelMode, TrashToast, UsageTracker, WarmConnection,
+};
+use crate::control::{ControlCall, ControlServer};
 use crate::core::SessionManager;
+use crate::core::jobs::JobQueue;
 use crate::platform::PlatformServices;
-use crate::session::config::PortForwardDirection;
+use crate::session::config::{
+    AgentSignRule, Keepalive, PortForwardDirection, Snippet, TransferDefaults, TransferLimits,
+};
+use crate::session::profile::HostProfile;
+use crate::session::schedule::TransferJob;
+use crate::session::target::ConnectTarget;
+use crate::session::template::SessionTemplate;
+use crate::session::usage::SessionUsage;
 use crate::session::{SessionConfig, SessionStorage};
 use crate::settings::ThemeMode;
-use crate::settings::{AppSettings, SettingsStorage};
+use crate::settings::{AppSettings, OpenTab, SettingsStorage, StartupView, WindowState};
+use crate::ssh::{AgentSignRequest, CredentialRequest};
+use crate::terminal::share::ShareLink;
 use crate::ui::style as ui_style;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+// Quick connect is for jumping somewhere, not browsing history; the sidebar
+// has the full list.
+const MAX_COMMAND_MATCHES: usize = 8;
 
 #[derive(Debug)]
 pub struct App {
@@ -26,6 +48,9 @@ pub struct App {
     // Session management
     pub(in crate::ui) active_view: ActiveView,
     pub(in crate::ui) saved_sessions: Vec<SessionConfig>,
+    pub(in crate::ui) session_templates: Vec<SessionTemplate>,
+    /// Hostname typed next to the templates in the session manager.
+    pub(in crate::ui) template_host: String,
     pub(in crate::ui) session_storage: SessionStorage,
     pub(in crate::ui) settings_storage: SettingsStorage,
     pub(in crate::ui) app_settings: AppSettings,
@@ -39,8 +64,31 @@ pub struct App {
     pub(in crate::ui) form_username: String,
     pub(in crate::ui) form_password: String,
     pub(in crate::ui) form_key_id: String,
+    pub(in crate::ui) form_fallback_keys: Vec<String>,
     pub(in crate::ui) form_key_passphrase: String,
-    pub(in crate::ui) auth_method_password: bool,
+    pub(in crate::ui) form_idle_timeout: String,
+    pub(in crate::ui) form_connect_timeout: String,
+    pub(in crate::ui) form_access_expires: String,
+    pub(in crate::ui) form_disconnect_on_expiry: bool,
+    pub(in crate::ui) form_debug_log: bool,
+    pub(in crate::ui) form_preflight: bool,
+    pub(in crate::ui) form_host_key: String,
+    pub(in crate::ui) form_port_knock: String,
+    pub(in crate::ui) form_jump_host: String,
+    pub(in crate::ui) form_keepalive: Option<Keepalive>,
+    pub(in crate::ui) form_forward_agent: bool,
+    pub(in crate::ui) form_compression: bool,
+    pub(in crate::ui) form_transfer_limits: Option<TransferLimits>,
+    pub(in crate::ui) form_bell: crate::session::config::BellMode,
+    pub(in crate::ui) form_tags: String,
+    pub(in crate::ui) form_badge: String,
+    pub(in crate::ui) form_tmux_session: String,
+    pub(in crate::ui) form_remote_command: String,
+    pub(in crate::ui) form_environment: String,
+    pub(in crate::ui) form_send_locale: bool,
+    pub(in crate::ui) form_proxy: String,
+    pub(in crate::ui) form_proxy_password: String,
+    pub(in crate::ui) form_auth: AuthChoice,
     pub(in crate::ui) validation_error: Option<String>,
     pub(in crate::ui) session_search_query: String,
     pub(in crate::ui) show_password: bool,
@@ -56,24 +104,78 @@ pub struct App {
     pub(in crate::ui) port_forward_error: Option<String>,
     pub(in crate::ui) port_forward_statuses:
         HashMap<String, HashMap<String, crate::ui::state::PortForwardStatus>>,
+    // Keyed by port forward rule id.
+    pub(in crate::ui) port_forward_checks: HashMap<String, crate::ui::state::PortForwardCheck>,
     pub(in crate::ui) window_width: u32,
     pub(in crate::ui) window_height: u32,
     pub(in crate::ui) last_error: Option<(String, std::time::Instant)>, // (error message, timestamp)
+    /// Passwords and other secrets asked for by connections in flight.
+    pub(in crate::ui) credential_queue: CredentialQueue,
+    /// The request on screen, taken from the queue on each tick.
+    pub(in crate::ui) credential_shown: Option<CredentialRequest>,
+    pub(in crate::ui) credential_input: String,
+    /// "Remember in keyring" on the key passphrase prompt.
+    pub(in crate::ui) credential_remember: bool,
+    /// The forwarded agent signature waiting for the user, unless a rule
+    /// already allows it.
+    pub(in crate::ui) agent_sign_shown: Option<AgentSignRequest>,
+    /// Host and key pairs allowed to sign without asking.
+    pub(in crate::ui) agent_sign_rules: Vec<AgentSignRule>,
+    pub(in crate::ui) credential_input_id: iced::widget::Id,
     // Quick Connect
     pub(in crate::ui) show_quick_connect: bool,
+    pub(in crate::ui) show_tab_overview: bool,
+    pub(in crate::ui) show_send_key_menu: bool,
+    pub(in crate::ui) show_bandwidth_popover: bool,
+    pub(in crate::ui) terminal_menu: Option<TerminalMenu>,
+    pub(in crate::ui) selection_actions: Option<SelectionActions>,
+    /// Terminal text saved from the selection toolbar, newest first.
+    pub(in crate::ui) snippets: Vec<Snippet>,
+    pub(in crate::ui) placeholder_prompt: Option<PlaceholderPrompt>,
+    pub(in crate::ui) deploy_key: Option<DeployKey>,
+    pub(in crate::ui) command_history_open: bool,
+    pub(in crate::ui) command_history_query: String,
+    pub(in crate::ui) pending_rerun: Option<String>,
+    // Tab waiting on "close anyway?" and the program still running in it.
+    pub(in crate::ui) pending_close: Option<(usize, String)>,
     pub(in crate::ui) quick_connect_query: String,
+    pub(in crate::ui) quick_connect_selected: usize,
+    /// Host found on the clipboard when quick connect opened.
+    pub(in crate::ui) quick_connect_clipboard: Option<ConnectTarget>,
+    /// Shared terminal link found on the clipboard when quick connect opened.
+    pub(in crate::ui) quick_connect_share: Option<ShareLink>,
     pub(in crate::ui) session_menu_open: Option<String>,
+    pub(in crate::ui) session_sort: SessionSort,
+    pub(in crate::ui) selected_sessions: HashSet<String>,
+    /// The bulk delete button was pressed once and waits for a second press.
+    pub(in crate::ui) bulk_delete_armed: bool,
+    pub(in crate::ui) warm_connection: Option<WarmConnection>,
+    /// Reachability shown on session cards, by session id.
+    pub(in crate::ui) host_probes: HashMap<String, HostProbe>,
+    /// Connect counts, time and traffic by session id.
+    pub(in crate::ui) session_usage: HashMap<String, SessionUsage>,
+    pub(in crate::ui) usage_tracker: UsageTracker,
+    /// The session card showing its usage.
+    pub(in crate::ui) usage_open: Option<String>,
+    pub(in crate::ui) idle_screen: IdleScreen,
     pub(in crate::ui) ime_buffer: String,
     pub(in crate::ui) ime_input_id: iced::widget::Id,
     pub(in crate::ui) ime_focused: bool,
     pub(in crate::ui) last_ime_focus_check: std::time::Instant,
     pub(in crate::ui) ime_preedit: String,
+    /// Keyboard modifiers held right now, for mouse wheel handling.
+    pub(in crate::ui) modifiers: iced::keyboard::Modifiers,
     pub(in crate::ui) ime_ignore_next_input: bool,
     pub(in crate::ui) pending_resize: Option<(usize, usize, std::time::Instant)>,
     pub(in crate::ui) last_terminal_tab: usize,
     pub(in crate::ui) sftp_panel_open: bool,
     pub(in crate::ui) sftp_panel_width: f32,
     pub(in crate::ui) sftp_panel_initialized: bool,
+    // Geometry restored on the next launch, saved a moment after it last
+    // changed rather than on every resize event.
+    pub(in crate::ui) window_state: WindowState,
+    pub(in crate::ui) window_state_changed: Option<std::time::Instant>,
+    pub(in crate::ui) window_position: Option<iced::Point>,
     pub(in crate::ui) port_forward_panel_open: bool,
     pub(in crate::ui) port_forward_panel_width: f32,
     pub(in crate::ui) port_forward_panel_initialized: bool,
@@ -81,13 +183,80 @@ pub struct App {
     pub(in crate::ui) sftp_dragging: bool, // Window resizing
     pub(in crate::ui) sftp_file_dragging: Option<(SftpPane, String)>,
     pub(in crate::ui) sftp_drag_position: Option<iced::Point>,
+    pub(in crate::ui) sftp_edge_scroll: EdgeScroll,
     pub(in crate::ui) sftp_hovered_file: Option<(SftpPane, String)>,
     pub(in crate::ui) sftp_transfer_tx: tokio::sync::mpsc::UnboundedSender<SftpTransferUpdate>,
     pub(in crate::ui) sftp_transfer_rx:
         Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<SftpTransferUpdate>>>,
-    pub(in crate::ui) sftp_max_concurrent: usize,
     pub(in crate::ui) sftp_rename_input_id: iced::widget::Id,
+    pub(in crate::ui) control_server: Option<Arc<ControlServer>>,
+    /// Port the control API was last started on; `None` while it is off.
+    pub(in crate::ui) control_port: Option<u16>,
+    pub(in crate::ui) control_tx: tokio::sync::mpsc::UnboundedSender<ControlCall>,
+    pub(in crate::ui) control_rx: Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<ControlCall>>>,
     pub(in crate::ui) sftp_states: HashMap<String, SftpState>,
+    pub(in crate::ui) log_tail: Option<LogTailState>,
+    pub(in crate::ui) window_focused: bool,
+    pub(in crate::ui) badge_count: usize,
+    pub(in crate::ui) disconnect_toast: Option<DisconnectToast>,
+    pub(in crate::ui) transfer_jobs: Vec<TransferJob>,
+    pub(in crate::ui) job_runs: HashMap<String, Vec<JobRun>>,
+    /// Transfers, listings, connection tests and scheduled jobs in flight,
+    /// with the last few that ended.
+    pub(in crate::ui) background_jobs: JobQueue<JobOwner>,
+    pub(in crate::ui) show_jobs_panel: bool,
+    /// Jobs that have never run count their first interval from here.
+    pub(in crate::ui) jobs_since: chrono::DateTime<chrono::Local>,
+    pub(in crate::ui) job_toast: Option<JobFailureToast>,
+    pub(in crate::ui) trash_toast: Option<TrashToast>,
+    /// The SFTP queue shows scheduled jobs instead of transfers.
+    pub(in crate::ui) sftp_show_jobs: bool,
+    pub(in crate::ui) transfer_panel_mode: TransferPanelMode,
+    /// The window holding the transfer queue while it is popped out.
+    pub(in crate::ui) transfer_window: Option<iced::window::Id>,
+    pub(in crate::ui) debug_log_tab: Option<usize>,
+    pub(in crate::ui) debug_log_status: Option<String>,
+    /// Started with `--viewer`, which forces viewer mode whatever the
+    /// settings say.
+    pub(in crate::ui) viewer_launch: bool,
+}
+
+/// Tabs opened at launch, as chosen in settings. Sessions deleted since are
+/// skipped.
+fn startup_tasks(
+    settings: &AppSettings,
+    window: &WindowState,
+    sessions: &[SessionConfig],
+) -> Task<Message> {
+    let connect = |id: &String| {
+        sessions
+            .iter()
+            .any(|session| &session.id == id)
+            .then(|| Message::Sessions(SessionMessage::Connect(id.clone())))
+    };
+    let messages: Vec<Message> = match settings.startup_view {
+        StartupView::SessionManager => Vec::new(),
+        StartupView::LocalShell => vec![Message::CreateLocalTab],
+        StartupView::RestoreTabs => window
+            .open_tabs
+            .iter()
+            .filter_map(|tab| match tab {
+                OpenTab::Local => Some(Message::CreateLocalTab),
+                OpenTab::Session { id } => connect(id),
+            })
+            .collect(),
+        StartupView::ConnectSession => settings
+            .startup_session
+            .as_ref()
+            .and_then(connect)
+            .into_iter()
+            .collect(),
+    };
+    // Chained so restored tabs come back in their old order.
+    messages
+        .into_iter()
+        .map(Task::done)
+        .fold(Task::none(), Task::chain)
 }
 
 impl App {
@@ -97,15 +266,71 @@ impl App {
             eprintln!("Failed to load sessions: {}", e);
             Vec::new()
         });
+        let session_templates = storage.load_templates().unwrap_or_else(|e| {
+            eprintln!("Failed to load session templates: {}", e);
+            Vec::new()
+        });
+        let transfer_jobs = storage.load_jobs().unwrap_or_else(|e| {
+            eprintln!("Failed to load transfer jobs: {}", e);
+            Vec::new()
+        });
+        let agent_sign_rules = storage.load_agent_rules().unwrap_or_else(|e| {
+            eprintln!("Failed to load agent signing rules: {}", e);
+            Vec::new()
+        });
+        let session_usage = storage.load_usage().unwrap_or_else(|e| {
+            eprintln!("Failed to load session usage: {}", e);
+            HashMap::new()
+        });
+        let snippets = storage.load_snippets().unwrap_or_else(|e| {
+            eprintln!("Failed to load snippets: {}", e);
+            Vec::new()
+        });
         let settings_storage = SettingsStorage::new();
         let app_settings = settings_storage.load_settings().unwrap_or_default();
         ui_style::set_dark_mode(matches!(app_settings.theme, ThemeMode::Dark));
+        ui_style::set_terminal_theme(app_settings.terminal_theme);
+        crate::ui::terminal_text_style::set_text_style(&app_settings);
+        ui_style::set_terminal_opacity(app_settings.background_opacity);
+        crate::ui::terminal_widget::set_line_height(app_settings.line_height);
         let use_gpu_renderer = app_settings.use_gpu_renderer;
         let mut sessions_tab = SessionTab::new("Sessions");
         sessions_tab.sftp_key = Some("session-manager".to_string());
 
-        let (main_window, open_task) = iced::window::open(iced::window::Settings::default());
+        // Transparency is fixed when the window is created, so the opacity
+        // can only be lowered live if the window started out translucent.
+        let translucent = app_settings.background_opacity < 1.0;
+        let window_state = settings_storage.load_window_state().unwrap_or_else(|e| {
+            eprintln!("Failed to load window state: {}", e);
+            WindowState::default()
+        });
+        let defaults = iced::window::Settings::default();
+        let (main_window, open_task) = iced::window::open(iced::window::Settings {
+            size: window_state.size.map_or(defaults.size, |(width, height)| {
+                iced::Size::new(width, height)
+            }),
+            position: window_state.position.map_or(defaults.position, |(x, y)| {
+                iced::window::Position::Specific(iced::Point::new(x, y))
+            }),
+            maximized: window_state.maximized,
+            transparent: translucent,
+            blur: translucent && cfg!(target_os = "macos") && app_settings.background_blur,
+            ..defaults
+        });
+
+        let startup = startup_tasks(&app_settings, &window_state, &saved_sessions);
+
+        // Viewer mode starts with terminal output redacted, like the
+        // status bar's Redact button, and it stays that way.
+        let viewer_launch = std::env::args().any(|arg| arg == "--viewer");
+        if viewer_launch || app_settings.viewer_mode {
+            crate::terminal::redact::set(Some(crate::terminal::redact::Redactor::new(
+                &app_settings.redact_patterns,
+                saved_sessions.iter().map(|session| session.host.as_str()),
+            )));
+        }
 
+        let (control_tx, control_rx) = tokio::sync::mpsc::unbounded_channel::<ControlCall>();
         let (sftp_transfer_tx, sftp_transfer_rx) =
             tokio::sync::mpsc::unbounded_channel::<SftpTransferUpdate>();
 
@@ -122,6 +347,8 @@ impl App {
                 settings_process: None,
                 active_view: ActiveView::SessionManager,
                 saved_sessions,
+                session_templates,
+                template_host: String::new(),
                 session_storage: storage,
                 settings_storage,
                 terminal_font_size: app_settings.terminal_font_size,
@@ -135,8 +362,31 @@ impl App {
                 form_username: String::new(),
                 form_password: String::new(),
                 form_key_id: String::new(),
+                form_fallback_keys: Vec::new(),
                 form_key_passphrase: String::new(),
-                auth_method_password: true,
+                form_idle_timeout: String::new(),
+                form_connect_timeout: String::new(),
+                form_access_expires: String::new(),
+                form_disconnect_on_expiry: false,
+                form_debug_log: false,
+                form_preflight: false,
+                form_host_key: String::new(),
+                form_port_knock: String::new(),
+                form_jump_host: String::new(),
+                form_transfer_limits: None,
+                form_keepalive: None,
+                form_forward_agent: false,
+                form_compression: false,
+                form_bell: Default::default(),
+                form_tags: String::new(),
+                form_badge: String::new(),
+                form_tmux_session: String::new(),
+                form_remote_command: String::new(),
+                form_environment: String::new(),
+                form_send_locale: false,
+                form_proxy: String::new(),
+                form_proxy_password: String::new(),
+                form_auth: AuthChoice::Password,
                 validation_error: None,
                 session_search_query: String::new(),
                 show_password: false,
@@ -151,23 +401,59 @@ impl App {
                 port_forward_direction: PortForwardDirection::Local,
                 port_forward_error: None,
                 port_forward_statuses: HashMap::new(),
+                port_forward_checks: HashMap::new(),
                 window_width: 1024, // Default assumption
                 window_height: 768,
                 last_error: None,
+                credential_queue: CredentialQueue::default(),
+                credential_shown: None,
+                agent_sign_shown: None,
+                agent_sign_rules,
+                credential_input: String::new(),
+                credential_remember: false,
+                credential_input_id: iced::widget::Id::new("credential-input"),
                 show_quick_connect: false,
+                show_tab_overview: false,
+                show_send_key_menu: false,
+                show_bandwidth_popover: false,
+                terminal_menu: None,
+                selection_actions: None,
+                snippets,
+                placeholder_prompt: None,
+                deploy_key: None,
+                command_history_open: false,
+                command_history_query: String::new(),
+                pending_rerun: None,
+                pending_close: None,
                 quick_connect_query: String::new(),
+                quick_connect_selected: 0,
+                quick_connect_clipboard: None,
+                quick_connect_share: None,
                 session_menu_open: None,
+                session_sort: SessionSort::default(),
+                selected_sessions: HashSet::new(),
+                bulk_delete_armed: false,
+                warm_connection: None,
+                host_probes: HashMap::new(),
+                session_usage,
+                usage_tracker: UsageTracker::default(),
+                usage_open: None,
+                idle_screen: IdleScreen::default(),
                 ime_buffer: String::new(),
                 ime_input_id: iced::widget::Id::new("terminal-ime-input"),
                 ime_focused: false,
                 last_ime_focus_check: std::time::Instant::now(),
                 ime_preedit: String::new(),
+                modifiers: iced::keyboard::Modifiers::default(),
                 ime_ignore_next_input: false,
                 pending_resize: None,
                 last_terminal_tab: 0,
                 sftp_panel_open: false,
                 sftp_panel_width: 520.0,
                 sftp_panel_initialized: false,
+                window_state,
+                window_state_changed: None,
+                window_position: None,
                 port_forward_panel_open: false,
                 port_forward_panel_width: 420.0,
                 port_forward_panel_initialized: false,
@@ -175,25 +461,72 @@ impl App {
                 sftp_dragging: false,
                 sftp_file_dragging: None,
                 sftp_drag_position: None,
+                sftp_edge_scroll: EdgeScroll::default(),
                 sftp_hovered_file: None,
                 sftp_transfer_tx,
                 sftp_transfer_rx: Arc::new(Mutex::new(sftp_transfer_rx)),
-                sftp_max_concurrent: 2,
+                control_server: None,
+                control_port: None,
+                control_tx,
+                control_rx: Arc::new(Mutex::new(control_rx)),
                 sftp_rename_input_id: iced::widget::Id::new("sftp-rename-input"),
                 sftp_states,
+                log_tail: None,
+                window_focused: true,
+                badge_count: 0,
+                disconnect_toast: None,
+                transfer_jobs,
+                job_runs: HashMap::new(),
+                background_jobs: JobQueue::default(),
+                show_jobs_panel: false,
+                jobs_since: chrono::Local::now(),
+                job_toast: None,
+                trash_toast: None,
+                sftp_show_jobs: false,
+                transfer_panel_mode: TransferPanelMode::Docked,
+                transfer_window: None,
+                debug_log_tab: None,
+                debug_log_status: None,
+                viewer_launch,
             },
-            open_task.map(Message::WindowOpened), // Open the main window
+            Task::batch([
+                open_task.map(Message::WindowOpened), // Open the main window
+                startup,
+            ]),
         )
     }
 
-    pub fn title(&self, _window: iced::window::Id) -> String {
-        if self.active_tab == 0 {
+    pub fn title(&self, window: iced::window::Id) -> String {
+        if Some(window) == self.transfer_window {
+            return "Rivett - Transfers".to_string();
+        }
+        let title = if self.active_tab == 0 {
             "Rivett - Sessions".to_string()
         } else {
             format!("Rivett - {}", self.tabs[self.active_tab].title)
+        };
+        match self.unread_tab_count() {
+            0 => title,
+            unread => format!("({}) {}", unread, title),
         }
     }
 
+    /// Saved session behind this tab; local shells have none.
+    pub(in crate::ui) fn session_for_tab(&self, tab_index: usize) -> Option<&SessionConfig> {
+        let id = self.sftp_key_for_tab(tab_index)?;
+        self.saved_sessions.iter().find(|s| s.id == id)
+    }
+
+    /// Host profile matching the saved session behind this tab, if any.
+    pub(in crate::ui) fn host_profile_for_tab(&self, tab_index: usize) -> Option<&HostProfile> {
+        let session = self.session_for_tab(tab_index)?;
+        crate::session::profile::find(&self.app_settings.host_profiles, &session.host)
+    }
+
+    pub(in crate::ui) fn unread_tab_count(&self) -> usize {
+        self.tabs.iter().filter(|tab| tab.has_activity).count()
+    }
+
     pub fn run(settings: Settings) -> iced::Result {
         iced::daemon(App::new, App::update, App::view)
             .title(App::title)
@@ -201,6 +534,15 @@ impl App {
                 ThemeMode::Dark => Theme::Dark,
                 ThemeMode::Light => Theme::Light,
             })
+            .style(|_app: &App, theme| {
+                let base = iced::theme::Base::base(theme);
+                iced::theme::Style {
+                    background_color: base
+                        .background_color
+                        .scale_alpha(ui_style::terminal_opacity()),
+                    ..base
+                }
+            })
             .subscription(App::subscription)
             .settings(settings)
             .run()
@@ -210,12 +552,115 @@ impl App {
 
     // Add separate timer subscription method if needed, or combine:
 
+    pub(in crate::ui) fn quick_connect_matches(
+        &self,
+    ) -> Vec<crate::session::search::SessionMatch<'_>> {
+        crate::session::search::rank_sessions(&self.quick_connect_query, &self.saved_sessions)
+    }
+
+    /// Commands from every tab's history matching the quick connect query,
+    /// best match first and the most recent run among equals. Repeated
+    /// commands are listed once.
+    pub(in crate::ui) fn quick_connect_commands(&self) -> Vec<CommandMatch> {
+        let query = self.quick_connect_query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut records: Vec<_> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .flat_map(|(tab_index, tab)| {
+                tab.emulator
+                    .command_history()
+                    .into_iter()
+                    .map(move |record| (tab_index, record))
+            })
+            .collect();
+        records.sort_by_key(|(_, record)| std::cmp::Reverse(record.started_at));
+
+        let mut seen = HashSet::new();
+        let mut matches: Vec<(i32, CommandMatch)> = records
+            .into_iter()
+            .filter(|(_, record)| seen.insert(record.command.clone()))
+            .filter_map(|(tab_index, record)| {
+                let (score, hits) = crate::session::search::fuzzy_match(query, &record.command)?;
+                Some((
+                    score,
+                    CommandMatch {
+                        record,
+                        tab_index,
+                        tab_title: self.tabs[tab_index].title.clone(),
+                        hits,
+                    },
+                ))
+            })
+            .collect();
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        matches
+            .into_iter()
+            .take(MAX_COMMAND_MATCHES)
+            .map(|(_, m)| m)
+            .collect()
+    }
+
+    /// Asks the user for credentials a connection started from the UI needs.
+    pub(in crate::ui) fn credential_prompter(
+        &self,
+    ) -> Option<Arc<dyn crate::ssh::CredentialPrompter>> {
+        Some(Arc::new(
+            self.credential_queue
+                .clone()
+                .in_viewer_mode(self.viewer_mode()),
+        ))
+    }
+
+    /// Like `credential_prompter`, for the connection behind tab `tab`.
+    pub(in crate::ui) fn tab_credential_prompter(
+        &self,
+        tab: uuid::Uuid,
+    ) -> Option<Arc<dyn crate::ssh::CredentialPrompter>> {
+        Some(Arc::new(
+            self.credential_queue
+                .for_tab(tab)
+                .in_viewer_mode(self.viewer_mode()),
+        ))
+    }
+
+    /// A shared terminal link typed into quick connect, or else one copied.
+    pub(in crate::ui) fn quick_connect_share_link(&self) -> Option<ShareLink> {
+        ShareLink::find(&self.quick_connect_query).or_else(|| self.quick_connect_share.clone())
+    }
+
+    pub(in crate::ui) fn warm_up_quick_connect_selection(&self) -> Task<Message> {
+        match self
+            .quick_connect_matches()
+            .get(self.quick_connect_selected)
+        {
+            Some(selected) => Task::done(Message::WarmUpSession(selected.session.id.clone())),
+            None => Task::none(),
+        }
+    }
+
     pub(in crate::ui) fn sftp_key_for_tab(&self, tab_index: usize) -> Option<&str> {
         self.tabs
             .get(tab_index)
             .and_then(|tab| tab.sftp_key.as_deref())
     }
 
+    /// The session's own limits when it has them, otherwise the global ones.
+    pub(in crate::ui) fn transfer_limits_for_tab(&self, tab_index: usize) -> TransferLimits {
+        self.session_for_tab(tab_index)
+            .and_then(|session| session.transfer_limits)
+            .unwrap_or(self.app_settings.transfer_limits)
+    }
+
+    pub(in crate::ui) fn transfer_defaults_for_tab(&self, tab_index: usize) -> TransferDefaults {
+        self.session_for_tab(tab_index)
+            .map(|session| session.transfer_defaults)
+            .unwrap_or_default()
+    }
+
     pub(in crate::ui) fn sftp_state_for_tab(&self, tab_index: usize) -> Option<&SftpState> {
         let key = self.sftp_key_for_tab(tab_index)?;
         self.sftp_states.get(key)
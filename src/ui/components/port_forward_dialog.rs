@@ -1,7 +1,7 @@
 use crate::session::config::{PortForwardDirection, PortForwardRule, SessionConfig};
-use crate::ui::Message;
-use crate::ui::state::PortForwardStatus;
+use crate::ui::state::{PortForwardCheck, PortForwardStatus};
 use crate::ui::style as ui_style;
+use crate::ui::{Message, SessionMessage};
 use iced::widget::{button, column, container, row, text, text_input};
 use iced::{Alignment, Background, Border, Color, Element, Length};
 use std::collections::HashMap;
@@ -29,8 +29,9 @@ pub fn render_manage_inline<'a>(
 pub fn render_list<'a>(
     session: &'a SessionConfig,
     statuses: Option<&'a HashMap<String, PortForwardStatus>>,
+    checks: &'a HashMap<String, PortForwardCheck>,
 ) -> Element<'a, Message> {
-    list_view(session, statuses)
+    list_view(session, statuses, checks)
 }
 
 fn render_manage_body<'a>(
@@ -88,7 +89,9 @@ fn render_manage_body<'a>(
             .on_press(if direction == PortForwardDirection::Local {
                 Message::Ignore
             } else {
-                Message::PortForwardDirectionChanged(PortForwardDirection::Local)
+                Message::Sessions(SessionMessage::PortForwardDirectionChanged(
+                    PortForwardDirection::Local,
+                ))
             }),
         button(text("Remote").size(12))
             .padding([6, 12])
@@ -98,7 +101,9 @@ fn render_manage_body<'a>(
             .on_press(if direction == PortForwardDirection::Remote {
                 Message::Ignore
             } else {
-                Message::PortForwardDirectionChanged(PortForwardDirection::Remote)
+                Message::Sessions(SessionMessage::PortForwardDirectionChanged(
+                    PortForwardDirection::Remote,
+                ))
             }),
         button(text("Dynamic").size(12))
             .padding([6, 12])
@@ -108,7 +113,9 @@ fn render_manage_body<'a>(
             .on_press(if direction == PortForwardDirection::Dynamic {
                 Message::Ignore
             } else {
-                Message::PortForwardDirectionChanged(PortForwardDirection::Dynamic)
+                Message::Sessions(SessionMessage::PortForwardDirectionChanged(
+                    PortForwardDirection::Dynamic,
+                ))
             }),
     ]
     .spacing(6);
@@ -123,7 +130,9 @@ fn render_manage_body<'a>(
         column![
             text(local_host_label).size(11).style(ui_style::muted_text),
             text_input("127.0.0.1", local_host)
-                .on_input(Message::PortForwardLocalHostChanged)
+                .on_input(
+                    |value| Message::Sessions(SessionMessage::PortForwardLocalHostChanged(value))
+                )
                 .padding([7, 10])
                 .size(13)
                 .style(ui_style::dialog_input)
@@ -135,7 +144,9 @@ fn render_manage_body<'a>(
         column![
             text(local_port_label).size(11).style(ui_style::muted_text),
             text_input("8080", local_port)
-                .on_input(Message::PortForwardLocalPortChanged)
+                .on_input(
+                    |value| Message::Sessions(SessionMessage::PortForwardLocalPortChanged(value))
+                )
                 .padding([7, 10])
                 .size(13)
                 .style(ui_style::dialog_input)
@@ -147,7 +158,9 @@ fn render_manage_body<'a>(
         column![
             text(remote_host_label).size(11).style(ui_style::muted_text),
             text_input("127.0.0.1", remote_host)
-                .on_input(Message::PortForwardRemoteHostChanged)
+                .on_input(
+                    |value| Message::Sessions(SessionMessage::PortForwardRemoteHostChanged(value))
+                )
                 .padding([7, 10])
                 .size(13)
                 .style(ui_style::dialog_input)
@@ -159,7 +172,9 @@ fn render_manage_body<'a>(
         column![
             text(remote_port_label).size(11).style(ui_style::muted_text),
             text_input("3306", remote_port)
-                .on_input(Message::PortForwardRemotePortChanged)
+                .on_input(
+                    |value| Message::Sessions(SessionMessage::PortForwardRemotePortChanged(value))
+                )
                 .padding([7, 10])
                 .size(13)
                 .style(ui_style::dialog_input)
@@ -185,7 +200,7 @@ fn render_manage_body<'a>(
             button(text("Add").size(12))
                 .padding([7, 14])
                 .style(ui_style::primary_button_style)
-                .on_press(Message::AddPortForward),
+                .on_press(Message::Sessions(SessionMessage::AddPortForward)),
         ]
         .spacing(10)
         .align_y(Alignment::Center),
@@ -204,6 +219,7 @@ fn render_manage_body<'a>(
 fn list_view<'a>(
     session: &'a SessionConfig,
     statuses: Option<&'a HashMap<String, PortForwardStatus>>,
+    checks: &'a HashMap<String, PortForwardCheck>,
 ) -> Element<'a, Message> {
     if session.port_forwards.is_empty() {
         return column![
@@ -243,6 +259,7 @@ fn list_view<'a>(
             .size(13)
             .style(ui_style::muted_text)
             .width(Length::FillPortion(1)),
+        container("").width(Length::Fixed(70.0)),
     ]
     .spacing(12)
     .align_y(Alignment::Center);
@@ -253,10 +270,13 @@ fn list_view<'a>(
 
     let mut rows = column![header].spacing(0);
     for (index, rule) in session.port_forwards.iter().enumerate() {
-        let row = container(render_rule_row(
-            rule,
-            statuses.and_then(|map| map.get(&rule.id)),
-        ))
+        let row = container(
+            column![
+                render_rule_row(rule, statuses.and_then(|map| map.get(&rule.id))),
+                checks.get(&rule.id).map(render_check),
+            ]
+            .spacing(6),
+        )
         .padding([8, 12]);
         rows = rows.push(row);
         if index + 1 < session.port_forwards.len() {
@@ -366,12 +386,38 @@ fn render_rule_row<'a>(
             .width(Length::FillPortion(1))
             .align_x(Alignment::Center)
             .align_y(Alignment::Center),
+        button(text("Check").size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_item_button)
+            .on_press(Message::CheckPortForward(rule.id.clone()))
+            .width(Length::Fixed(70.0)),
     ]
     .spacing(12)
     .align_y(Alignment::Center)
     .into()
 }
 
+fn render_check<'a>(check: &'a PortForwardCheck) -> Element<'a, Message> {
+    match check {
+        PortForwardCheck::Running => text("Checking…")
+            .size(12)
+            .style(ui_style::muted_text)
+            .into(),
+        PortForwardCheck::Finished(results) => results
+            .iter()
+            .fold(column![].spacing(2), |lines, result| {
+                let line = match &result.error {
+                    None => text(format!("✓ {}: reachable", result.label))
+                        .color(Color::from_rgb8(52, 199, 89)),
+                    Some(error) => text(format!("✗ {}: {}", result.label, error))
+                        .color(Color::from_rgb(0.9, 0.3, 0.3)),
+                };
+                lines.push(line.size(12))
+            })
+            .into(),
+    }
+}
+
 fn render_manage_row<'a>(rule: &'a PortForwardRule) -> Element<'a, Message> {
     let (direction_label, bind_host, bind_port, target_host, target_port) =
         rule_display_values(rule);
@@ -389,7 +435,9 @@ fn render_manage_row<'a>(rule: &'a PortForwardRule) -> Element<'a, Message> {
         button(text("Delete").size(12))
             .padding([4, 10])
             .style(ui_style::menu_item_destructive)
-            .on_press(Message::DeletePortForward(rule.id.clone()))
+            .on_press(Message::Sessions(SessionMessage::DeletePortForward(
+                rule.id.clone()
+            )))
             .width(Length::Fixed(70.0)),
     ]
     .spacing(12)
@@ -1,14 +1,26 @@
 use crate::session::SessionConfig;
-use crate::ui::Message;
+use crate::session::expiry;
+use crate::session::usage::{self, SessionUsage, USAGE_DAYS};
+use crate::ui::state::HostStatus;
 use crate::ui::style as ui_style;
-use iced::widget::{button, column, container, row, stack, text};
-use iced::{Element, Length, Renderer, Theme};
+use crate::ui::views::sftp::format_size;
+use crate::ui::{Message, SessionMessage};
+use iced::widget::canvas::{self, Canvas, Frame, Geometry};
+use iced::widget::{button, column, container, row, stack, text, tooltip};
+use iced::{Alignment, Color, Element, Length, Point, Rectangle, Renderer, Size, Theme, mouse};
 
-pub fn render<'a>(session: &'a SessionConfig, menu_open: bool) -> Element<'a, Message> {
+pub fn render<'a>(
+    session: &'a SessionConfig,
+    menu_open: bool,
+    status: Option<&'a HostStatus>,
+    usage: Option<SessionUsage>,
+    read_only: bool,
+) -> Element<'a, Message> {
     let connection_info = format!("{}@{}:{}", session.username, session.host, session.port);
 
     let mut card_content: iced::widget::Column<'a, Message, Theme, Renderer> = column![
         row![
+            status.map(status_dot),
             text(session.name.clone())
                 .size(14)
                 .style(ui_style::header_text),
@@ -16,8 +28,12 @@ pub fn render<'a>(session: &'a SessionConfig, menu_open: bool) -> Element<'a, Me
             button(text("⋮").size(16))
                 .padding([2, 6])
                 .style(ui_style::icon_button)
-                .on_press(Message::ToggleSessionMenu(session.id.clone())),
-        ],
+                .on_press(Message::Sessions(SessionMessage::ToggleMenu(
+                    session.id.clone()
+                ))),
+        ]
+        .align_y(Alignment::Center)
+        .spacing(6),
         text(connection_info).size(12).style(ui_style::muted_text),
     ]
     .spacing(6);
@@ -31,12 +47,24 @@ pub fn render<'a>(session: &'a SessionConfig, menu_open: bool) -> Element<'a, Me
         );
     }
 
+    if let Some(expires_at) = session.access_expires_at {
+        card_content = card_content.push(access_expiry(expires_at));
+    }
+
+    if let Some(usage) = usage {
+        card_content = card_content
+            .push(container("").height(4.0))
+            .push(usage_details(&usage));
+    }
+
     card_content = card_content.push(container("").height(10.0)).push(
         row![
             button(text("Connect").size(12))
                 .padding([6, 16])
                 .style(ui_style::primary_button_style)
-                .on_press(Message::ConnectToSession(session.id.clone())),
+                .on_press(Message::Sessions(SessionMessage::Connect(
+                    session.id.clone()
+                ))),
             container("").width(Length::Fill),
         ]
         .spacing(8),
@@ -45,29 +73,52 @@ pub fn render<'a>(session: &'a SessionConfig, menu_open: bool) -> Element<'a, Me
     let base_card = container(card_content.padding(16)).width(Length::Fill);
 
     let content: Element<'a, Message> = if menu_open {
+        // Viewer mode leaves only what doesn't change the session.
         let menu = iced::widget::mouse_area(
             container(
                 column![
-                    button(text("Edit").size(12))
+                    (!read_only).then(|| {
+                        button(text("Edit").size(12))
+                            .padding([6, 10])
+                            .style(ui_style::menu_item_button)
+                            .width(Length::Fill)
+                            .on_press(Message::Sessions(SessionMessage::Edit(session.id.clone())))
+                    }),
+                    button(text("Usage").size(12))
                         .padding([6, 10])
                         .style(ui_style::menu_item_button)
                         .width(Length::Fill)
-                        .on_press(Message::EditSession(session.id.clone())),
+                        .on_press(Message::Sessions(SessionMessage::ToggleUsage(
+                            session.id.clone()
+                        ))),
                     button(text("Port Forwarding").size(12))
                         .padding([6, 10])
                         .style(ui_style::menu_item_button)
                         .width(Length::Fill)
-                        .on_press(Message::OpenPortForwarding(session.id.clone())),
-                    button(text("Delete").size(12))
-                        .padding([6, 10])
-                        .style(ui_style::menu_item_destructive)
-                        .width(Length::Fill)
-                        .on_press(Message::DeleteSession(session.id.clone())),
+                        .on_press(Message::Sessions(SessionMessage::OpenPortForwarding(
+                            session.id.clone()
+                        ))),
+                    (!read_only).then(|| {
+                        button(text("Deploy Public Key").size(12))
+                            .padding([6, 10])
+                            .style(ui_style::menu_item_button)
+                            .width(Length::Fill)
+                            .on_press(Message::OpenDeployKey(session.id.clone()))
+                    }),
+                    (!read_only).then(|| {
+                        button(text("Delete").size(12))
+                            .padding([6, 10])
+                            .style(ui_style::menu_item_destructive)
+                            .width(Length::Fill)
+                            .on_press(Message::Sessions(SessionMessage::Delete(
+                                session.id.clone(),
+                            )))
+                    }),
                 ]
                 .spacing(4),
             )
             .padding(8)
-            .width(Length::Fixed(120.0))
+            .width(Length::Fixed(150.0))
             .style(ui_style::popover_menu),
         )
         .on_press(Message::Ignore);
@@ -81,8 +132,118 @@ pub fn render<'a>(session: &'a SessionConfig, menu_open: bool) -> Element<'a, Me
         base_card.into()
     };
 
-    container(content)
-        .width(Length::Fixed(320.0))
-        .style(ui_style::panel)
+    iced::widget::mouse_area(
+        container(content)
+            .width(Length::Fixed(320.0))
+            .style(ui_style::panel),
+    )
+    .on_enter(Message::WarmUpSession(session.id.clone()))
+    .into()
+}
+
+/// Green when the host answered the last probe, red when it didn't.
+fn status_dot<'a>(status: &'a HostStatus) -> Element<'a, Message> {
+    let (color, label) = match status {
+        HostStatus::Checking => (Color::from_rgb(0.55, 0.55, 0.6), "Checking…".to_string()),
+        HostStatus::Reachable(latency) => (
+            Color::from_rgb(0.3, 0.75, 0.45),
+            format!("Reachable ({} ms)", latency.as_millis()),
+        ),
+        HostStatus::Unreachable(e) => (Color::from_rgb(0.9, 0.3, 0.3), e.clone()),
+    };
+    let tip = container(text(label).size(11).style(ui_style::tooltip_text)).padding([4, 8]);
+    tooltip(text("●").size(10).color(color), tip, tooltip::Position::Top)
+        .style(ui_style::tooltip_style)
+        .gap(6)
         .into()
 }
+
+/// Totals and a bar per day of connects, to tell busy hosts from ones
+/// nobody uses any more.
+fn usage_details<'a>(usage: &SessionUsage) -> Element<'a, Message> {
+    let days = usage.daily_connects(chrono::Local::now().date_naive());
+    let recent: u32 = days.iter().sum();
+    column![
+        text(format!(
+            "{} connects · {} connected",
+            usage.connects,
+            usage::format_duration(usage.connected_time())
+        ))
+        .size(12)
+        .style(ui_style::muted_text),
+        text(format!(
+            "Received {} · Sent {}",
+            format_size(usage.bytes_received),
+            format_size(usage.bytes_sent)
+        ))
+        .size(12)
+        .style(ui_style::muted_text),
+        Canvas::new(Sparkline { days })
+            .width(Length::Fill)
+            .height(Length::Fixed(28.0)),
+        text(format!("{} in the last {} days", recent, USAGE_DAYS))
+            .size(11)
+            .style(ui_style::muted_text),
+    ]
+    .spacing(4)
+    .into()
+}
+
+struct Sparkline {
+    days: [u32; USAGE_DAYS],
+}
+
+impl canvas::Program<Message> for Sparkline {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let peak = self.days.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let slot = bounds.width / USAGE_DAYS as f32;
+        for (index, &count) in self.days.iter().enumerate() {
+            // Days without connects keep a sliver so the span stays visible.
+            let (height, color) = if count == 0 {
+                (1.0, Color::from_rgba(0.5, 0.5, 0.5, 0.3))
+            } else {
+                (
+                    (count as f32 / peak * bounds.height).max(2.0),
+                    Color::from_rgb(0.3, 0.6, 0.95),
+                )
+            };
+            frame.fill_rectangle(
+                Point::new(index as f32 * slot, bounds.height - height),
+                Size::new((slot - 1.0).max(1.0), height),
+                color,
+            );
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Time left on just-in-time access, highlighted once it is nearly over.
+fn access_expiry<'a>(expires_at: chrono::DateTime<chrono::Utc>) -> Element<'a, Message> {
+    let now = chrono::Utc::now();
+    match expiry::countdown(expires_at, now) {
+        Some(left) if expiry::is_ending(expires_at, now) => {
+            text(format!("Access ends in {}", left))
+                .size(12)
+                .color(Color::from_rgb(0.9, 0.6, 0.2))
+                .into()
+        }
+        Some(left) => text(format!("Access ends in {}", left))
+            .size(12)
+            .style(ui_style::muted_text)
+            .into(),
+        None => text("Access expired")
+            .size(12)
+            .color(Color::from_rgb(0.9, 0.3, 0.3))
+            .into(),
+    }
+}
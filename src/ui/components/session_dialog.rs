@@ -1,36 +1,117 @@
 use crate::session::SessionConfig;
+use crate::session::config::{BellMode, Keepalive, TransferLimit, TransferLimits};
 use crate::settings::SshKeyEntry;
-use crate::ui::Message;
 use crate::ui::message::SessionDialogTab;
-use crate::ui::state::ConnectionTestStatus;
+use crate::ui::state::{AuthChoice, ConnectionTestStatus};
 use crate::ui::style as ui_style;
-use iced::widget::{Space, button, column, container, mouse_area, row, stack, text, text_input};
+use crate::ui::{Message, SessionMessage};
+use iced::widget::{
+    Column, Row, Space, button, checkbox, column, container, mouse_area, row, stack, text,
+    text_input,
+};
 use iced::{Alignment, Element, Length};
 
-pub fn render<'a>(
-    editing_session: Option<&'a SessionConfig>,
-    saved_sessions: &'a [SessionConfig],
-    saved_keys: &'a [SshKeyEntry],
-    form_name: &'a str,
-    form_host: &'a str,
-    form_port: &'a str,
-    form_username: &'a str,
-    form_password: &'a str,
-    form_key_id: &'a str,
-    _form_key_passphrase: &'a str,
-    auth_method_password: bool,
-    show_password: bool,
-    connection_test_status: &'a ConnectionTestStatus,
-    saved_key_menu_open: bool,
-    validation_error: Option<&'a String>,
-    session_dialog_tab: SessionDialogTab,
-    port_forward_local_host: &'a str,
-    port_forward_local_port: &'a str,
-    port_forward_remote_host: &'a str,
-    port_forward_remote_port: &'a str,
-    port_forward_direction: crate::session::config::PortForwardDirection,
-    port_forward_error: Option<&'a String>,
-) -> Element<'a, Message> {
+const MAX_KEEPALIVE_INTERVAL: u32 = 300;
+const MAX_KEEPALIVE_MISSED: u32 = 10;
+
+/// Everything the session dialog shows: the form being edited and the
+/// state of its connection test and port-forward editor.
+pub struct SessionDialog<'a> {
+    pub editing_session: Option<&'a SessionConfig>,
+    pub saved_sessions: &'a [SessionConfig],
+    pub saved_keys: &'a [SshKeyEntry],
+    pub form_name: &'a str,
+    pub form_host: &'a str,
+    pub form_port: &'a str,
+    pub form_username: &'a str,
+    pub form_password: &'a str,
+    pub form_key_id: &'a str,
+    pub form_fallback_keys: &'a [String],
+    pub form_idle_timeout: &'a str,
+    pub form_connect_timeout: &'a str,
+    pub default_connect_timeout: u32,
+    pub form_access_expires: &'a str,
+    pub form_disconnect_on_expiry: bool,
+    pub form_debug_log: bool,
+    pub form_preflight: bool,
+    pub form_host_key: &'a str,
+    pub form_port_knock: &'a str,
+    pub form_jump_host: &'a str,
+    pub form_proxy: &'a str,
+    pub form_proxy_password: &'a str,
+    pub form_transfer_limits: Option<TransferLimits>,
+    pub form_keepalive: Option<Keepalive>,
+    pub form_forward_agent: bool,
+    pub form_compression: bool,
+    pub form_bell: BellMode,
+    pub form_tags: &'a str,
+    pub form_badge: &'a str,
+    pub form_tmux_session: &'a str,
+    pub form_remote_command: &'a str,
+    pub form_environment: &'a str,
+    pub form_send_locale: bool,
+    pub form_auth: AuthChoice,
+    pub show_password: bool,
+    pub connection_test_status: &'a ConnectionTestStatus,
+    pub saved_key_menu_open: bool,
+    pub validation_error: Option<&'a String>,
+    pub session_dialog_tab: SessionDialogTab,
+    pub port_forward_local_host: &'a str,
+    pub port_forward_local_port: &'a str,
+    pub port_forward_remote_host: &'a str,
+    pub port_forward_remote_port: &'a str,
+    pub port_forward_direction: crate::session::config::PortForwardDirection,
+    pub port_forward_error: Option<&'a String>,
+}
+
+pub fn render<'a>(dialog: SessionDialog<'a>) -> Element<'a, Message> {
+    let SessionDialog {
+        editing_session,
+        saved_sessions,
+        saved_keys,
+        form_name,
+        form_host,
+        form_port,
+        form_username,
+        form_password,
+        form_key_id,
+        form_fallback_keys,
+        form_idle_timeout,
+        form_connect_timeout,
+        default_connect_timeout,
+        form_access_expires,
+        form_disconnect_on_expiry,
+        form_debug_log,
+        form_preflight,
+        form_host_key,
+        form_port_knock,
+        form_jump_host,
+        form_proxy,
+        form_proxy_password,
+        form_transfer_limits,
+        form_keepalive,
+        form_forward_agent,
+        form_compression,
+        form_bell,
+        form_tags,
+        form_badge,
+        form_tmux_session,
+        form_remote_command,
+        form_environment,
+        form_send_locale,
+        form_auth,
+        show_password,
+        connection_test_status,
+        saved_key_menu_open,
+        validation_error,
+        session_dialog_tab,
+        port_forward_local_host,
+        port_forward_local_port,
+        port_forward_remote_host,
+        port_forward_remote_port,
+        port_forward_direction,
+        port_forward_error,
+    } = dialog;
     let is_new = editing_session
         .map(|s| !saved_sessions.iter().any(|saved| saved.id == s.id))
         .unwrap_or(true);
@@ -57,7 +138,7 @@ pub fn render<'a>(
         button(text("✕").size(13))
             .padding(8)
             .style(ui_style::tab_close_button)
-            .on_press(Message::CancelSessionEdit),
+            .on_press(Message::Sessions(SessionMessage::CancelEdit)),
     ]
     .align_y(Alignment::Center)
     .spacing(12);
@@ -68,15 +149,25 @@ pub fn render<'a>(
             .style(ui_style::dialog_tab(
                 session_dialog_tab == SessionDialogTab::General
             ))
-            .on_press(Message::SessionDialogTabSelected(SessionDialogTab::General)),
+            .on_press(Message::Sessions(SessionMessage::DialogTabSelected(
+                SessionDialogTab::General
+            ))),
         button(text("Port Forwarding").size(13))
             .padding([6, 12])
             .style(ui_style::dialog_tab(
                 session_dialog_tab == SessionDialogTab::PortForwarding,
             ))
-            .on_press(Message::SessionDialogTabSelected(
+            .on_press(Message::Sessions(SessionMessage::DialogTabSelected(
                 SessionDialogTab::PortForwarding,
-            )),
+            ))),
+        button(text("Advanced").size(13))
+            .padding([6, 12])
+            .style(ui_style::dialog_tab(
+                session_dialog_tab == SessionDialogTab::Advanced
+            ))
+            .on_press(Message::Sessions(SessionMessage::DialogTabSelected(
+                SessionDialogTab::Advanced
+            ))),
     ]
     .spacing(6);
 
@@ -95,27 +186,26 @@ pub fn render<'a>(
     );
 
     // Form fields
-    let auth_selector = row![
-        button(text("Password").size(12))
+    let auth_selector = Row::with_children(AuthChoice::ALL.into_iter().map(|choice| {
+        button(text(choice.label()).size(12))
             .padding([6, 12])
-            .style(ui_style::compact_tab(auth_method_password))
-            .on_press(if auth_method_password {
+            .style(ui_style::compact_tab(form_auth == choice))
+            .on_press(if form_auth == choice {
                 Message::Ignore
             } else {
-                Message::ToggleAuthMethod
-            }),
-        button(text("Private key").size(12))
-            .padding([6, 12])
-            .style(ui_style::compact_tab(!auth_method_password))
-            .on_press(if auth_method_password {
-                Message::ToggleAuthMethod
-            } else {
-                Message::Ignore
-            }),
-    ]
+                Message::Sessions(SessionMessage::SetAuthMethod(choice))
+            })
+            .into()
+    }))
     .spacing(6);
 
-    let auth_fields = if auth_method_password {
+    let auth_fields = if form_auth == AuthChoice::Agent {
+        column![
+            text("Each key held by the local ssh-agent is offered in turn (SSH_AUTH_SOCK, or the OpenSSH agent or Pageant on Windows).")
+                .size(12)
+                .style(ui_style::muted_text)
+        ]
+    } else if form_auth == AuthChoice::Password {
         let eye_icon = if show_password {
             iced::widget::svg(iced::widget::svg::Handle::from_memory(
                 include_bytes!(concat!(
@@ -139,7 +229,7 @@ pub fn render<'a>(
             text("Password").size(12).style(ui_style::muted_text),
             row![
                 text_input("", form_password)
-                    .on_input(Message::SessionPasswordChanged)
+                    .on_input(|value| Message::Sessions(SessionMessage::PasswordChanged(value)))
                     .padding([8, 10])
                     .size(13)
                     .style(ui_style::dialog_input)
@@ -148,7 +238,7 @@ pub fn render<'a>(
                 button(eye_icon)
                     .padding([8, 8])
                     .style(ui_style::icon_button)
-                    .on_press(Message::TogglePasswordVisibility),
+                    .on_press(Message::Sessions(SessionMessage::TogglePasswordVisibility)),
             ]
             .spacing(8)
             .align_y(Alignment::Center),
@@ -185,20 +275,48 @@ pub fn render<'a>(
                 options,
                 saved_key_menu_open,
                 false,
-                Message::ToggleSavedKeyMenu,
-                Message::SessionKeyIdChanged,
+                Message::Sessions(SessionMessage::ToggleSavedKeyMenu),
+                |value| Message::Sessions(SessionMessage::KeyIdChanged(value)),
                 None,
             )
         };
 
-        column![saved_key_section].spacing(6)
+        // Numbered in the order they are tried after the first.
+        let fallback_section = (saved_keys.len() > 1).then(|| {
+            let chips = saved_keys
+                .iter()
+                .filter(|key| key.id != form_key_id)
+                .map(|key| {
+                    let position = form_fallback_keys.iter().position(|id| *id == key.id);
+                    let label = match position {
+                        Some(index) => format!("{}. {}", index + 2, key.name),
+                        None => key.name.clone(),
+                    };
+                    button(text(label).size(12))
+                        .padding([4, 10])
+                        .style(ui_style::menu_button(position.is_some()))
+                        .on_press(Message::Sessions(SessionMessage::ToggleFallbackKey(
+                            key.id.clone(),
+                        )))
+                        .into()
+                });
+            column![
+                text("If refused, also try")
+                    .size(12)
+                    .style(ui_style::muted_text),
+                Row::with_children(chips).spacing(6).wrap(),
+            ]
+            .spacing(4)
+        });
+
+        column![saved_key_section, fallback_section].spacing(6)
     };
 
     let general_content = column![
         column![
             text("Display name").size(12).style(ui_style::muted_text),
             text_input("Production Server 01", form_name)
-                .on_input(Message::SessionNameChanged)
+                .on_input(|value| Message::Sessions(SessionMessage::NameChanged(value)))
                 .padding([8, 10])
                 .size(13)
                 .style(ui_style::dialog_input),
@@ -209,7 +327,7 @@ pub fn render<'a>(
             column![
                 text("Host address").size(12).style(ui_style::muted_text),
                 text_input("192.168.1.1 or example.com", form_host)
-                    .on_input(Message::SessionHostChanged)
+                    .on_input(|value| Message::Sessions(SessionMessage::HostChanged(value)))
                     .padding([8, 10])
                     .size(13)
                     .style(ui_style::dialog_input),
@@ -220,7 +338,7 @@ pub fn render<'a>(
             column![
                 text("Port").size(12).style(ui_style::muted_text),
                 text_input("22", form_port)
-                    .on_input(Message::SessionPortChanged)
+                    .on_input(|value| Message::Sessions(SessionMessage::PortChanged(value)))
                     .padding([8, 10])
                     .size(13)
                     .style(ui_style::dialog_input)
@@ -233,7 +351,7 @@ pub fn render<'a>(
         column![
             text("Username").size(12).style(ui_style::muted_text),
             text_input("root", form_username)
-                .on_input(Message::SessionUsernameChanged)
+                .on_input(|value| Message::Sessions(SessionMessage::UsernameChanged(value)))
                 .padding([8, 10])
                 .size(13)
                 .style(ui_style::dialog_input),
@@ -272,11 +390,328 @@ pub fn render<'a>(
         },
     );
 
+    let advanced_content = column![
+        column![
+            text("Tags").size(12).style(ui_style::muted_text),
+            text_input("e.g. prod, database", form_tags)
+                .on_input(|value| Message::Sessions(SessionMessage::TagsChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            text("Comma-separated. Quick connect searches tags as well as name and host.")
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Badge").size(12).style(ui_style::muted_text),
+            text_input("e.g. PROD or {user}@{host}", form_badge)
+                .on_input(|value| Message::Sessions(SessionMessage::BadgeChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            text("Shown in the corner of the terminal. {host}, {user} and {name} are filled in.")
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("tmux session").size(12).style(ui_style::muted_text),
+            text_input("None", form_tmux_session)
+                .on_input(|value| Message::Sessions(SessionMessage::TmuxChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            text(
+                "Attach to this tmux session on connect, creating it if needed, so work \
+                 survives a dropped connection. Hosts without tmux get a plain shell."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Remote command").size(12).style(ui_style::muted_text),
+            text_input("None", form_remote_command)
+                .on_input(|value| Message::Sessions(SessionMessage::RemoteCommandChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            text(
+                "Run this instead of a shell, e.g. journalctl -f. The tab closes when it \
+                 succeeds and shows the exit status otherwise. {{unit}}, {{unit=nginx}} \
+                 or {{env:staging|prod}} are asked for on connecting."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Environment").size(12).style(ui_style::muted_text),
+            text_input("e.g. EDITOR=vim, TZ=Europe/Berlin", form_environment)
+                .on_input(|value| Message::Sessions(SessionMessage::EnvironmentChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .font(iced::Font::MONOSPACE)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            checkbox(form_send_locale)
+                .label("Send local language settings (LANG, LC_*)")
+                .on_toggle(|value| Message::Sessions(SessionMessage::SendLocaleToggled(value)))
+                .size(16)
+                .text_size(13),
+            text(
+                "Set before the shell starts, like SetEnv in OpenSSH. The server ignores \
+                 names its AcceptEnv doesn't allow."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Connect timeout (seconds)")
+                .size(12)
+                .style(ui_style::muted_text),
+            row![
+                text_input(
+                    &format!("Default ({})", default_connect_timeout),
+                    form_connect_timeout
+                )
+                .on_input(|value| Message::Sessions(SessionMessage::ConnectTimeoutChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fixed(120.0)),
+                text("Give up on a step of connecting that takes longer.")
+                    .size(12)
+                    .style(ui_style::muted_text),
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(6),
+        column![
+            text("Idle timeout (minutes)")
+                .size(12)
+                .style(ui_style::muted_text),
+            row![
+                text_input("Never", form_idle_timeout)
+                    .on_input(|value| Message::Sessions(SessionMessage::IdleTimeoutChanged(value)))
+                    .padding([8, 10])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::Fixed(120.0)),
+                text("Disconnect after this long without input.")
+                    .size(12)
+                    .style(ui_style::muted_text),
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(6),
+        column![
+            text("Access expires").size(12).style(ui_style::muted_text),
+            row![
+                text_input("Never", form_access_expires)
+                    .on_input(|value| Message::Sessions(SessionMessage::AccessExpiresChanged(value)))
+                    .padding([8, 10])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::Fixed(160.0)),
+                checkbox(form_disconnect_on_expiry)
+                    .label("Disconnect when it ends")
+                    .on_toggle(|value| Message::Sessions(SessionMessage::DisconnectOnExpiryToggled(value)))
+                    .size(16)
+                    .text_size(13),
+            ]
+            .spacing(12)
+            .align_y(Alignment::Center),
+            text(
+                "When access from a just-in-time grant runs out, in local time \
+                 (2024-03-09 17:30). Open tabs are warned 10 minutes before."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Bell").size(12).style(ui_style::muted_text),
+            BellMode::ALL
+                .into_iter()
+                .fold(Row::new().spacing(4), |row, mode| {
+                    row.push(
+                        button(text(mode.label()).size(12))
+                            .padding([6, 12])
+                            .style(ui_style::compact_tab(mode == form_bell))
+                            .on_press(Message::Sessions(SessionMessage::BellChanged(mode))),
+                    )
+                }),
+            text("What happens when a program in this session rings the bell.")
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            checkbox(form_debug_log)
+                .label("Debug this connection")
+                .on_toggle(|value| Message::Sessions(SessionMessage::DebugLogToggled(value)))
+                .size(16)
+                .text_size(13),
+            text("Record algorithm negotiation, auth attempts and channel opens.")
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            checkbox(form_preflight)
+                .label("Check connection on save")
+                .on_toggle(|value| Message::Sessions(SessionMessage::PreflightToggled(value)))
+                .size(16)
+                .text_size(13),
+            text(
+                "Resolve the host, pin its host key and make sure the credentials \
+                 are accepted, in the background."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            checkbox(form_transfer_limits.is_some())
+                .label("Custom transfer limits")
+                .on_toggle(|value| Message::Sessions(SessionMessage::TransferLimitsToggled(value)))
+                .size(16)
+                .text_size(13),
+            text("Override the global SFTP concurrency from Settings for this session.")
+                .size(12)
+                .style(ui_style::muted_text),
+            form_transfer_limits.map(transfer_limit_rows),
+        ]
+        .spacing(6),
+        column![
+            checkbox(form_keepalive.is_some())
+                .label("Custom keepalive")
+                .on_toggle(|value| Message::Sessions(SessionMessage::KeepaliveToggled(value)))
+                .size(16)
+                .text_size(13),
+            text(
+                "Override the global keepalive from Settings, for hosts behind NAT or \
+                 firewalls that drop quiet connections."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+            form_keepalive.map(keepalive_rows),
+        ]
+        .spacing(6),
+        column![
+            checkbox(form_forward_agent)
+                .label("Forward ssh-agent")
+                .on_toggle(|value| Message::Sessions(SessionMessage::ForwardAgentToggled(value)))
+                .size(16)
+                .text_size(13),
+            text(
+                "Let this server use your local keys, for git or hopping on to other \
+                 hosts. You confirm every signature it asks for."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            checkbox(form_compression)
+                .label("Enable compression")
+                .on_toggle(|value| Message::Sessions(SessionMessage::CompressionToggled(value)))
+                .size(16)
+                .text_size(13),
+            text(
+                "Compress traffic with zlib. Helps on slow links when printing large \
+                 logs or transferring files; costs CPU on fast ones."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Pinned host key").size(12).style(ui_style::muted_text),
+            text_input("SHA256:...", form_host_key)
+                .on_input(|value| Message::Sessions(SessionMessage::HostKeyChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .font(iced::Font::MONOSPACE)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            text("Refuse to connect unless the server presents this key. Leave empty to accept any key.")
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Port knock sequence").size(12).style(ui_style::muted_text),
+            text_input("e.g. 7000, 8000/udp, 9000:250", form_port_knock)
+                .on_input(|value| Message::Sessions(SessionMessage::PortKnockChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .font(iced::Font::MONOSPACE)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            text("Knocked in order before connecting. Add /udp for UDP and :ms to pause after a knock.")
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Jump host").size(12).style(ui_style::muted_text),
+            text_input("e.g. bastion.example.com or ops@10.0.0.5:2222", form_jump_host)
+                .on_input(|value| Message::Sessions(SessionMessage::JumpHostChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .font(iced::Font::MONOSPACE)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            text("Connect through this host first, signing in with the same credentials.")
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+        column![
+            text("Proxy").size(12).style(ui_style::muted_text),
+            text_input("Default from Settings", form_proxy)
+                .on_input(|value| Message::Sessions(SessionMessage::ProxyChanged(value)))
+                .padding([8, 10])
+                .size(13)
+                .font(iced::Font::MONOSPACE)
+                .style(ui_style::dialog_input)
+                .width(Length::Fill),
+            form_proxy.contains('@').then(|| {
+                text_input("Proxy password", form_proxy_password)
+                    .on_input(|value| Message::Sessions(SessionMessage::ProxyPasswordChanged(value)))
+                    .secure(true)
+                    .padding([8, 10])
+                    .size(13)
+                    .style(ui_style::dialog_input)
+                    .width(Length::Fill)
+            }),
+            text(
+                "socks5://host:1080 or http://user@host:3128, for reaching hosts from \
+                 behind a corporate proxy. Enter direct to skip the default proxy."
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(6),
+    ]
+    .spacing(12);
+
     let form_content: Element<'a, Message> = match session_dialog_tab {
         SessionDialogTab::General => {
             column![general_content, container("").height(14.0), auth_content].into()
         }
         SessionDialogTab::PortForwarding => port_forward_content,
+        SessionDialogTab::Advanced => advanced_content.into(),
     };
 
     // Footer with buttons
@@ -300,20 +735,30 @@ pub fn render<'a>(
         _ => button(text("Test Connection").size(12))
             .padding([8, 16])
             .style(ui_style::secondary_button_style)
-            .on_press(Message::TestConnection),
+            .on_press(Message::Sessions(SessionMessage::TestConnection)),
     };
 
     let mut footer = row![test_button];
     if let Some(status) = status_text {
         footer = footer.push(status);
     }
+    footer = footer.push(container("").width(Length::Fill));
+    // Templates are made from a fresh form; an existing session would lose
+    // unsaved edits when the dialog closes.
+    if is_new {
+        footer = footer.push(
+            button(text("Save as Template").size(12))
+                .padding([8, 16])
+                .style(ui_style::secondary_button_style)
+                .on_press(Message::Sessions(SessionMessage::SaveTemplate)),
+        );
+    }
     footer = footer
-        .push(container("").width(Length::Fill))
         .push(
             button(text("Cancel").size(12))
                 .padding([8, 16])
                 .style(ui_style::secondary_button_style)
-                .on_press(Message::CancelSessionEdit),
+                .on_press(Message::Sessions(SessionMessage::CancelEdit)),
         )
         .push({
             let action_label = if is_new {
@@ -324,7 +769,7 @@ pub fn render<'a>(
             button(text(action_label).size(12))
                 .padding([8, 16])
                 .style(ui_style::primary_button_style)
-                .on_press(Message::SaveSession)
+                .on_press(Message::Sessions(SessionMessage::Save))
         })
         .spacing(12)
         .align_y(Alignment::Center);
@@ -354,7 +799,7 @@ pub fn render<'a>(
                 .width(Length::Fill)
                 .height(Length::Fill),
         )
-        .on_press(Message::CloseSavedKeyMenu);
+        .on_press(Message::Sessions(SessionMessage::CloseSavedKeyMenu));
 
         stack![dialog_body, dismiss_layer].into()
     } else {
@@ -365,3 +810,86 @@ pub fn render<'a>(
         .style(ui_style::dialog_container)
         .into()
 }
+
+fn keepalive_rows<'a>(keepalive: Keepalive) -> Column<'a, Message> {
+    let stepper = |label, value: String, less: Keepalive, more: Keepalive| {
+        row![
+            text(label).size(13),
+            container("").width(Length::Fill),
+            button(text("−").size(12))
+                .padding([2, 8])
+                .style(ui_style::icon_button)
+                .on_press(Message::Sessions(SessionMessage::KeepaliveChanged(less))),
+            text(value).size(13),
+            button(text("+").size(12))
+                .padding([2, 8])
+                .style(ui_style::icon_button)
+                .on_press(Message::Sessions(SessionMessage::KeepaliveChanged(more))),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center)
+    };
+    let interval = if keepalive.interval_secs == 0 {
+        "Off".to_string()
+    } else {
+        format!("{}s", keepalive.interval_secs)
+    };
+    column![
+        stepper(
+            "Interval",
+            interval,
+            Keepalive {
+                interval_secs: keepalive.interval_secs.saturating_sub(5),
+                ..keepalive
+            },
+            Keepalive {
+                interval_secs: (keepalive.interval_secs + 5).min(MAX_KEEPALIVE_INTERVAL),
+                ..keepalive
+            },
+        ),
+        stepper(
+            "Missed before disconnect",
+            keepalive.max_missed.to_string(),
+            Keepalive {
+                max_missed: keepalive.max_missed.saturating_sub(1).max(1),
+                ..keepalive
+            },
+            Keepalive {
+                max_missed: (keepalive.max_missed + 1).min(MAX_KEEPALIVE_MISSED),
+                ..keepalive
+            },
+        ),
+    ]
+    .spacing(4)
+}
+
+fn transfer_limit_rows<'a>(limits: TransferLimits) -> Column<'a, Message> {
+    TransferLimit::ALL
+        .into_iter()
+        .fold(Column::new().spacing(4), |rows, limit| {
+            let value = limits.get(limit);
+            rows.push(
+                row![
+                    text(limit.label()).size(13),
+                    container("").width(Length::Fill),
+                    button(text("−").size(12))
+                        .padding([2, 8])
+                        .style(ui_style::icon_button)
+                        .on_press(Message::Sessions(SessionMessage::TransferLimitChanged(
+                            limit,
+                            value.saturating_sub(1)
+                        ))),
+                    text(value.to_string()).size(13),
+                    button(text("+").size(12))
+                        .padding([2, 8])
+                        .style(ui_style::icon_button)
+                        .on_press(Message::Sessions(SessionMessage::TransferLimitChanged(
+                            limit,
+                            value + 1
+                        ))),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            )
+        })
+}
@@ -1,6 +1,7 @@
 use crate::session::SessionConfig;
-use crate::ui::Message;
+use crate::ui::state::AuthChoice;
 use crate::ui::style as ui_style;
+use crate::ui::{Message, SessionMessage};
 use iced::widget::{button, column, container, row, text, text_input};
 use iced::{Alignment, Element, Length};
 
@@ -33,11 +34,11 @@ pub fn render<'a>(
         button(text("Save").size(12))
             .padding([5, 10])
             .style(ui_style::new_tab_button)
-            .on_press(Message::SaveSession),
+            .on_press(Message::Sessions(SessionMessage::Save)),
         button(text("Cancel").size(12))
             .padding([5, 10])
             .style(ui_style::tab_close_button)
-            .on_press(Message::CancelSessionEdit),
+            .on_press(Message::Sessions(SessionMessage::CancelEdit)),
     ]
     .spacing(6)
     .align_y(Alignment::Center)
@@ -62,13 +63,13 @@ pub fn render<'a>(
         container("").height(8.0),
         text("Name").size(11).style(ui_style::muted_text),
         text_input("Production Server", form_name)
-            .on_input(Message::SessionNameChanged)
+            .on_input(|value| Message::Sessions(SessionMessage::NameChanged(value)))
             .padding(8)
             .size(12),
         container("").height(8.0),
         text("Host").size(11).style(ui_style::muted_text),
         text_input("example.com", form_host)
-            .on_input(Message::SessionHostChanged)
+            .on_input(|value| Message::Sessions(SessionMessage::HostChanged(value)))
             .padding(8)
             .size(12),
         container("").height(8.0),
@@ -76,7 +77,7 @@ pub fn render<'a>(
             column![
                 text("Port").size(11).style(ui_style::muted_text),
                 text_input("22", form_port)
-                    .on_input(Message::SessionPortChanged)
+                    .on_input(|value| Message::Sessions(SessionMessage::PortChanged(value)))
                     .padding(8)
                     .size(12)
                     .width(Length::Fixed(80.0)),
@@ -86,7 +87,7 @@ pub fn render<'a>(
             column![
                 text("Username").size(11).style(ui_style::muted_text),
                 text_input("user", form_username)
-                    .on_input(Message::SessionUsernameChanged)
+                    .on_input(|value| Message::Sessions(SessionMessage::UsernameChanged(value)))
                     .padding(8)
                     .size(12)
                     .width(Length::Fill),
@@ -106,11 +107,9 @@ pub fn render<'a>(
                         (ui_style::menu_button(false))(theme, status)
                     }
                 })
-                .on_press(if auth_method_password {
-                    Message::ToggleAuthMethod
-                } else {
-                    Message::ToggleAuthMethod // dummy, won't toggle if already selected
-                }),
+                .on_press(Message::Sessions(SessionMessage::SetAuthMethod(
+                    AuthChoice::PrivateKey
+                ))),
             button(text("🔒 Password").size(11))
                 .padding([6, 12])
                 .style(move |theme, status| {
@@ -120,11 +119,9 @@ pub fn render<'a>(
                         (ui_style::menu_button(false))(theme, status)
                     }
                 })
-                .on_press(if !auth_method_password {
-                    Message::ToggleAuthMethod
-                } else {
-                    Message::ToggleAuthMethod // dummy
-                }),
+                .on_press(Message::Sessions(SessionMessage::SetAuthMethod(
+                    AuthChoice::Password
+                ))),
         ]
         .spacing(6),
         container("").height(8.0),
@@ -142,7 +139,7 @@ pub fn render<'a>(
             column![
                 text("Password").size(11).style(ui_style::muted_text),
                 text_input("", form_password)
-                    .on_input(Message::SessionPasswordChanged)
+                    .on_input(|value| Message::Sessions(SessionMessage::PasswordChanged(value)))
                     .padding(8)
                     .size(12)
                     .secure(true),
@@ -0,0 +1,198 @@
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use crate::ssh::{AgentSignRequest, CredentialAnswer, CredentialPrompter, CredentialRequest};
+
+#[derive(Debug)]
+struct Pending {
+    request: CredentialRequest,
+    reply: oneshot::Sender<Option<CredentialAnswer>>,
+}
+
+#[derive(Debug)]
+struct Touch {
+    tab: Option<Uuid>,
+    target: String,
+}
+
+#[derive(Debug)]
+struct PendingSign {
+    request: AgentSignRequest,
+    reply: oneshot::Sender<bool>,
+}
+
+/// Credential requests from connections in flight, shown to the user one at
+/// a time in the order they were asked.
+#[derive(Debug, Clone, Default)]
+pub struct CredentialQueue {
+    pending: Arc<Mutex<VecDeque<Pending>>>,
+    touching: Arc<Mutex<Vec<Touch>>>,
+    signing: Arc<Mutex<VecDeque<PendingSign>>>,
+    /// The tab whose connection asks through this handle.
+    tab: Option<Uuid>,
+    /// Nothing typed through this handle may be saved.
+    viewer_mode: bool,
+}
+
+impl CredentialPrompter for CredentialQueue {
+    fn ask(&self, mut request: CredentialRequest) -> oneshot::Receiver<Option<CredentialAnswer>> {
+        request.can_remember &= !self.viewer_mode;
+        let (reply, answer) = oneshot::channel();
+        self.pending.lock().push_back(Pending { request, reply });
+        answer
+    }
+
+    fn security_key_touch(&self, target: &str, waiting: bool) {
+        let mut touching = self.touching.lock();
+        if waiting {
+            touching.push(Touch {
+                tab: self.tab,
+                target: target.to_string(),
+            });
+        } else if let Some(index) = touching
+            .iter()
+            .position(|t| t.tab == self.tab && t.target == target)
+        {
+            touching.remove(index);
+        }
+    }
+
+    fn confirm_agent_sign(&self, request: AgentSignRequest) -> oneshot::Receiver<bool> {
+        let (reply, decision) = oneshot::channel();
+        self.signing
+            .lock()
+            .push_back(PendingSign { request, reply });
+        decision
+    }
+}
+
+impl CredentialQueue {
+    /// The request to show. Ones whose connection has since given up are
+    /// skipped.
+    pub fn current(&self) -> Option<CredentialRequest> {
+        let mut pending = self.pending.lock();
+        skip_abandoned(&mut pending);
+        pending.front().map(|p| p.request.clone())
+    }
+
+    /// The same queue, for a connection made for `tab`.
+    pub fn for_tab(&self, tab: Uuid) -> Self {
+        Self {
+            tab: Some(tab),
+            ..self.clone()
+        }
+    }
+
+    /// The same queue, never offering to remember what is typed while
+    /// `viewer_mode` is on.
+    pub fn in_viewer_mode(self, viewer_mode: bool) -> Self {
+        Self {
+            viewer_mode,
+            ..self
+        }
+    }
+
+    /// The `user@host` that `tab`'s connection is waiting on a security key
+    /// touch for.
+    pub fn touch_target(&self, tab: Uuid) -> Option<String> {
+        self.touching
+            .lock()
+            .iter()
+            .find(|t| t.tab == Some(tab))
+            .map(|t| t.target.clone())
+    }
+
+    /// Answers the current request; None cancels it.
+    pub fn answer(&self, value: Option<CredentialAnswer>) {
+        let mut pending = self.pending.lock();
+        skip_abandoned(&mut pending);
+        if let Some(current) = pending.pop_front() {
+            let _ = current.reply.send(value);
+        }
+    }
+
+    /// The forwarded agent signature waiting for a decision. Ones the
+    /// server has since given up on are skipped.
+    pub fn sign_request(&self) -> Option<AgentSignRequest> {
+        let mut signing = self.signing.lock();
+        while signing.front().is_some_and(|p| p.reply.is_closed()) {
+            signing.pop_front();
+        }
+        signing.front().map(|p| p.request.clone())
+    }
+
+    /// Allows or refuses the current signature request.
+    pub fn answer_sign(&self, allow: bool) {
+        if self.sign_request().is_some()
+            && let Some(current) = self.signing.lock().pop_front()
+        {
+            let _ = current.reply.send(allow);
+        }
+    }
+}
+
+fn skip_abandoned(pending: &mut VecDeque<Pending>) {
+    while pending.front().is_some_and(|p| p.reply.is_closed()) {
+        pending.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssh::CredentialKind;
+
+    #[test]
+    fn answers_in_order_and_skips_abandoned_requests() {
+        let queue = CredentialQueue::default();
+        let first = queue.ask(CredentialRequest::new(CredentialKind::Password, "a@web-1"));
+        let abandoned = queue.ask(CredentialRequest::new(CredentialKind::Password, "b@web-2"));
+        let mut last = queue.ask(CredentialRequest::new(
+            CredentialKind::KeyPassphrase,
+            "c@web-3",
+        ));
+
+        assert_eq!(queue.current().unwrap().target, "a@web-1");
+        let answer = CredentialAnswer {
+            value: "secret".to_string(),
+            remember: false,
+        };
+        queue.answer(Some(answer.clone()));
+        assert_eq!(first.blocking_recv().unwrap(), Some(answer));
+
+        drop(abandoned);
+        assert_eq!(queue.current().unwrap().target, "c@web-3");
+        queue.answer(None);
+        assert_eq!(last.try_recv().unwrap(), None);
+        assert_eq!(queue.current(), None);
+    }
+
+    #[test]
+    fn viewer_mode_never_offers_to_remember() {
+        let mut request = CredentialRequest::new(CredentialKind::KeyPassphrase, "a@web-1");
+        request.can_remember = true;
+        let queue = CredentialQueue::default();
+        let _reply = queue.clone().in_viewer_mode(true).ask(request.clone());
+        assert!(!queue.current().unwrap().can_remember);
+
+        queue.answer(None);
+        let _reply = queue.ask(request);
+        assert!(queue.current().unwrap().can_remember);
+    }
+
+    #[test]
+    fn touch_prompts_belong_to_their_tab() {
+        let queue = CredentialQueue::default();
+        let (waiting, other) = (Uuid::new_v4(), Uuid::new_v4());
+        queue.for_tab(waiting).security_key_touch("a@web-1", true);
+
+        assert_eq!(queue.touch_target(waiting).as_deref(), Some("a@web-1"));
+        assert_eq!(queue.touch_target(other), None);
+
+        queue.for_tab(waiting).security_key_touch("a@web-1", false);
+        assert_eq!(queue.touch_target(waiting), None);
+    }
+}
@@ -1,6 +1,8 @@
 use std::process::Command;
+use std::time::Duration;
 
 use crate::ui::App;
+use crate::ui::state::ConnectDefaults;
 
 impl App {
     pub(in crate::ui) fn reload_settings(&mut self) {
@@ -13,13 +15,78 @@ impl App {
                 self.app_settings.theme,
                 crate::settings::ThemeMode::Dark
             ));
+            crate::ui::style::set_terminal_theme(self.app_settings.terminal_theme);
+            crate::ui::terminal_text_style::set_text_style(&self.app_settings);
+            crate::ui::style::set_terminal_opacity(self.app_settings.background_opacity);
+            crate::ui::terminal_widget::set_line_height(self.app_settings.line_height);
+            crate::logging::apply(&self.app_settings.logging);
+            if crate::terminal::redact::is_enabled() || self.viewer_mode() {
+                self.set_redaction(true);
+            }
+            let sharing_allowed = self.app_settings.allow_terminal_sharing;
             for tab in &mut self.tabs {
+                if !sharing_allowed {
+                    tab.share = None;
+                }
                 tab.mark_full_damage();
             }
         }
     }
 
+    /// Switches masking of sensitive text in terminal output. Saved session
+    /// hostnames are masked along with the configured patterns.
+    pub(in crate::ui) fn set_redaction(&mut self, enabled: bool) {
+        let redactor = enabled.then(|| {
+            crate::terminal::redact::Redactor::new(
+                &self.app_settings.redact_patterns,
+                self.saved_sessions
+                    .iter()
+                    .map(|session| session.host.as_str()),
+            )
+        });
+        crate::terminal::redact::set(redactor);
+        for tab in &mut self.tabs {
+            tab.mark_full_damage();
+        }
+    }
+
+    /// Read-only mode, from the settings or the command line.
+    pub(in crate::ui) fn viewer_mode(&self) -> bool {
+        self.viewer_launch || self.app_settings.viewer_mode
+    }
+
+    /// Saved keys offered to sessions that authenticate with a key, after
+    /// their own.
+    pub(in crate::ui) fn offered_keys(&self) -> Vec<String> {
+        if !self.app_settings.offer_all_keys {
+            return Vec::new();
+        }
+        self.app_settings
+            .ssh_keys
+            .iter()
+            .map(|key| key.id.clone())
+            .collect()
+    }
+
+    /// What saved sessions fall back to for the settings they leave unset.
+    pub(in crate::ui) fn connect_defaults(&self) -> ConnectDefaults {
+        ConnectDefaults {
+            keepalive: self.app_settings.keepalive,
+            proxy: self.app_settings.proxy.clone(),
+            connect_timeout: self.default_connect_timeout(),
+            offer_keys: self.offered_keys(),
+        }
+    }
+
+    pub(in crate::ui) fn default_connect_timeout(&self) -> Duration {
+        Duration::from_secs(u64::from(self.app_settings.connect_timeout_secs.max(1)))
+    }
+
     pub(in crate::ui) fn open_settings_window(&mut self) {
+        // Settings are where viewer mode is turned off, and where keys live.
+        if self.viewer_mode() {
+            return;
+        }
         if let Some(child) = &mut self.settings_process {
             if let Ok(None) = child.try_wait() {
                 return;
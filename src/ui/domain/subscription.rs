@@ -2,7 +2,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::ui::App;
-use crate::ui::message::Message;
+use crate::ui::message::{ActiveView, Message, SftpMessage, TerminalMessage};
 use crate::ui::state::SessionState;
 
 impl App {
@@ -38,11 +38,16 @@ impl App {
             subs.push(iced::time::every(std::time::Duration::from_millis(50)).map(Message::Tick));
         }
 
+        // Reachability dots on the session cards are refreshed while shown.
+        if self.active_view == ActiveView::SessionManager {
+            subs.push(
+                iced::time::every(std::time::Duration::from_secs(2))
+                    .map(|_| Message::ProbeSessions),
+            );
+        }
+
         // Hashable wrapper for Rx
-        struct HashableRx(
-            Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>>>,
-            usize,
-        );
+        struct HashableRx(Arc<Mutex<crate::terminal::OutputReceiver>>, usize);
 
         impl std::hash::Hash for HashableRx {
             fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
@@ -73,33 +78,15 @@ impl App {
                         let rx = rx.clone();
                         let idx = *idx;
                         iced::futures::stream::unfold(rx, move |rx| async move {
-                            let result = {
-                                let mut guard: tokio::sync::MutexGuard<
-                                    '_,
-                                    tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>,
-                                > = rx.lock().await;
-
-                                if let Some(first_chunk) = guard.recv().await {
-                                    let mut batch = first_chunk;
-                                    let mut count = 0;
-                                    // Drain up to 100 pending chunks to batch them
-                                    while count < 100 {
-                                        match guard.try_recv() {
-                                            Ok(chunk) => {
-                                                batch.extend(chunk);
-                                                count += 1;
-                                            }
-                                            Err(_) => break,
-                                        }
-                                    }
-                                    Some(batch)
-                                } else {
-                                    None
-                                }
-                            };
+                            // The receiver already hands out everything
+                            // queued as one batch.
+                            let result = rx.lock().await.recv().await;
 
                             match result {
-                                Some(data) => Some((Message::TerminalDataReceived(idx, data), rx)),
+                                Some(data) => Some((
+                                    Message::Terminal(TerminalMessage::DataReceived(idx, data)),
+                                    rx,
+                                )),
                                 None => {
                                     std::future::pending::<()>().await;
                                     None
@@ -155,7 +142,10 @@ impl App {
                             };
 
                             match result {
-                                Some(damage) => Some((Message::TerminalDamaged(idx, damage), rx)),
+                                Some(damage) => Some((
+                                    Message::Terminal(TerminalMessage::Damaged(idx, damage)),
+                                    rx,
+                                )),
                                 None => {
                                     std::future::pending::<()>().await;
                                     None
@@ -200,7 +190,9 @@ impl App {
                         guard.recv().await
                     };
                     match result {
-                        Some(update) => Some((Message::SftpTransferUpdate(update), rx)),
+                        Some(update) => {
+                            Some((Message::Sftp(SftpMessage::TransferUpdate(update)), rx))
+                        }
                         None => {
                             std::future::pending::<()>().await;
                             None
@@ -210,6 +202,102 @@ impl App {
             },
         ));
 
+        // Control API calls
+        struct HashableControlRx(
+            Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<crate::control::ControlCall>>>,
+        );
+
+        impl std::hash::Hash for HashableControlRx {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                (Arc::as_ptr(&self.0) as usize).hash(state);
+            }
+        }
+        impl PartialEq for HashableControlRx {
+            fn eq(&self, other: &Self) -> bool {
+                Arc::ptr_eq(&self.0, &other.0)
+            }
+        }
+        impl Eq for HashableControlRx {}
+        impl Clone for HashableControlRx {
+            fn clone(&self) -> Self {
+                Self(self.0.clone())
+            }
+        }
+
+        if self.control_server.is_some() {
+            subs.push(iced::Subscription::run_with(
+                HashableControlRx(self.control_rx.clone()),
+                |HashableControlRx(rx)| {
+                    let rx = rx.clone();
+                    iced::futures::stream::unfold(rx, move |rx| async move {
+                        let call = rx.lock().await.recv().await;
+                        match call {
+                            Some(call) => Some((Message::ControlCall(call), rx)),
+                            None => {
+                                std::future::pending::<()>().await;
+                                None
+                            }
+                        }
+                    })
+                },
+            ));
+        }
+
+        // Log tail viewer stream
+        struct HashableLogRx(
+            Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>>>,
+            uuid::Uuid,
+        );
+
+        impl std::hash::Hash for HashableLogRx {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                (Arc::as_ptr(&self.0) as usize).hash(state);
+                self.1.hash(state);
+            }
+        }
+        impl PartialEq for HashableLogRx {
+            fn eq(&self, other: &Self) -> bool {
+                Arc::ptr_eq(&self.0, &other.0) && self.1 == other.1
+            }
+        }
+        impl Eq for HashableLogRx {}
+        impl Clone for HashableLogRx {
+            fn clone(&self) -> Self {
+                Self(self.0.clone(), self.1)
+            }
+        }
+
+        if let Some(log_tail) = &self.log_tail
+            && let Some(rx) = &log_tail.rx
+        {
+            subs.push(iced::Subscription::run_with(
+                HashableLogRx(rx.clone(), log_tail.id),
+                |HashableLogRx(rx, id)| {
+                    let id = *id;
+                    iced::futures::stream::unfold(Some(rx.clone()), move |rx| async move {
+                        let rx = rx?;
+                        let result = {
+                            let mut guard = rx.lock().await;
+                            match guard.recv().await {
+                                Some(mut batch) => {
+                                    while let Ok(chunk) = guard.try_recv() {
+                                        batch.extend(chunk);
+                                    }
+                                    Some(batch)
+                                }
+                                None => None,
+                            }
+                        };
+                        match result {
+                            Some(data) => Some((Message::LogTailData(id, data), Some(rx))),
+                            // Report the end of the stream once, then finish.
+                            None => Some((Message::LogTailData(id, Vec::new()), None)),
+                        }
+                    })
+                },
+            ));
+        }
+
         iced::Subscription::batch(subs)
     }
 }
@@ -1,7 +1,8 @@
 use iced::Task;
 
 use crate::ui::terminal_widget;
-use crate::ui::{App, Message};
+use crate::ui::views;
+use crate::ui::{App, Message, TerminalMessage};
 
 impl App {
     pub(in crate::ui) fn focus_terminal_ime(&self) -> Task<Message> {
@@ -16,24 +17,34 @@ impl App {
         terminal_widget::cell_height(self.terminal_font_size)
     }
 
-    pub(in crate::ui) fn recalc_terminal_size(&self) -> Task<Message> {
-        let width = self.window_width;
-        let height = self.window_height;
-        if width == 0 || height == 0 {
-            return Task::none();
-        }
-
-        let reserved_width = 0.0;
-        let h_padding = 24.0;
-        let v_padding = 80.0;
+    /// Columns and rows that fit in a window of the given size.
+    pub(in crate::ui) fn terminal_grid_size(&self, width: u32, height: u32) -> (usize, usize) {
+        let reserved_width = if self.command_history_open {
+            views::command_history::SIDEBAR_WIDTH
+        } else {
+            0.0
+        };
+        let inner_padding = self.app_settings.terminal_padding.max(0.0) * 2.0;
+        let h_padding = 24.0 + inner_padding;
+        let v_padding = 80.0 + inner_padding;
 
         let term_w = (width as f32 - reserved_width - h_padding).max(0.0);
         let term_h = (height as f32 - v_padding).max(0.0);
 
         let cols = (term_w / self.cell_width()) as usize;
         let rows = (term_h / self.cell_height()) as usize;
+        (cols, rows)
+    }
 
-        Task::done(Message::TerminalResize(cols, rows))
+    pub(in crate::ui) fn recalc_terminal_size(&self) -> Task<Message> {
+        let width = self.window_width;
+        let height = self.window_height;
+        if width == 0 || height == 0 {
+            return Task::none();
+        }
+
+        let (cols, rows) = self.terminal_grid_size(width, height);
+        Task::done(Message::Terminal(TerminalMessage::Resize(cols, rows)))
     }
 
     pub(in crate::ui) fn bracketed_paste_bytes(&self, text: &str) -> Vec<u8> {
@@ -44,6 +55,32 @@ impl App {
         data
     }
 
+    /// Writes paste data in small chunks for consoles that drop fast input.
+    pub(in crate::ui) fn slow_paste(&mut self, data: Vec<u8>) -> Task<Message> {
+        let chunks = self.app_settings.paste.chunks(data);
+        let delay = std::time::Duration::from_millis(self.app_settings.paste.chunk_delay_ms);
+        let Some(tab) = self.tabs.get_mut(self.active_tab) else {
+            return Task::none();
+        };
+        let Some(session) = tab.session.clone() else {
+            return Task::none();
+        };
+        tab.last_input = std::time::Instant::now();
+
+        Task::perform(
+            async move {
+                for chunk in chunks {
+                    if let Err(e) = session.write(&chunk).await {
+                        tracing::warn!("slow paste write error: {}", e);
+                        break;
+                    }
+                    tokio::time::sleep(delay).await;
+                }
+            },
+            |_| Message::Terminal(TerminalMessage::Input(vec![])),
+        )
+    }
+
     pub(in crate::ui) fn maybe_wrap_bracketed_paste(&self, data: &[u8]) -> Vec<u8> {
         if data.contains(&b'\n') && !data.windows(6).any(|w| w == b"\x1b[200~") {
             let mut wrapped = Vec::with_capacity(data.len() + 12);
@@ -0,0 +1,195 @@
+use iced::Task;
+
+use crate::ssh::shell_quote;
+use crate::terminal::ClipboardRequest;
+use crate::ui::App;
+use crate::ui::message::Message;
+
+const INSTALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+// Marker line used to recognize helpers installed earlier, so reinstalling
+// replaces them but never a pbcopy the user put there.
+const MARKER: &str = "rivett clipboard helper";
+
+const PBCOPY: &str = r#"#!/bin/sh
+# rivett clipboard helper: copies stdin to the local clipboard over OSC 52.
+data=$(base64 | tr -d '\r\n')
+if [ -n "$TMUX" ]; then
+  printf '\033Ptmux;\033\033]52;c;%s\a\033\\' "$data" > /dev/tty
+else
+  printf '\033]52;c;%s\a' "$data" > /dev/tty
+fi
+"#;
+
+const PBPASTE: &str = r#"#!/bin/sh
+# rivett clipboard helper: prints the local clipboard, read over OSC 52.
+saved=$(stty -g < /dev/tty)
+trap 'stty "$saved" < /dev/tty' EXIT
+trap 'exit 1' INT TERM
+stty raw -echo min 0 time 10 < /dev/tty
+printf '\033]52;c;?\a' > /dev/tty
+bel=$(printf '\a')
+reply=
+while chunk=$(dd bs=4096 count=1 < /dev/tty 2>/dev/null) && [ -n "$chunk" ]; do
+  reply="$reply$chunk"
+  case $chunk in *"$bel"*) break ;; esac
+done
+data=${reply#*52;c;}
+data=${data%%"$bel"*}
+if printf '' | base64 -d > /dev/null 2>&1; then
+  printf '%s' "$data" | base64 -d
+else
+  printf '%s' "$data" | base64 -D
+fi
+"#;
+
+/// Answers OSC 52 requests collected by the terminals since the last tick.
+/// While the setting is off they are dropped, and a pbpaste just times out.
+/// Reads need their own setting on top, since any remote program can ask.
+pub(in crate::ui) fn sync(app: &mut App) -> Option<Task<Message>> {
+    let enabled = app.app_settings.remote_clipboard;
+    let read_enabled = enabled && app.app_settings.remote_clipboard_read;
+    let mut tasks = Vec::new();
+    for tab in &app.tabs {
+        let tab_id = tab.id;
+        for request in tab.emulator.take_clipboard_requests() {
+            match request {
+                ClipboardRequest::Store(text) if enabled => {
+                    tasks.push(iced::clipboard::write(text))
+                }
+                ClipboardRequest::Load(format) if read_enabled => {
+                    tasks.push(iced::clipboard::read().map(move |text| {
+                        Message::RemoteClipboardReply(tab_id, format(&text.unwrap_or_default()))
+                    }))
+                }
+                _ => {}
+            }
+        }
+    }
+    (!tasks.is_empty()).then(|| Task::batch(tasks))
+}
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::RemoteClipboardReply(tab_id, reply) => {
+            // Looked up by id, since tabs may have closed or moved while the
+            // clipboard was read.
+            let Some(session) = app
+                .tabs
+                .iter()
+                .find(|tab| tab.id == tab_id)
+                .and_then(|tab| tab.session.clone())
+            else {
+                return Task::none();
+            };
+            Task::perform(
+                async move {
+                    if let Err(e) = session.write(reply.as_bytes()).await {
+                        tracing::warn!("Failed to answer clipboard request: {}", e);
+                    }
+                },
+                |_| Message::Ignore,
+            )
+        }
+        Message::InstallClipboardHelpers => {
+            app.terminal_menu = None;
+            let Some((tab_id, ssh_handle)) = app
+                .tabs
+                .get(app.active_tab)
+                .and_then(|tab| Some((tab.id, tab.ssh_handle.clone()?)))
+            else {
+                return Task::none();
+            };
+            let command = format!("sh -c {}", shell_quote(&install_script()));
+            Task::perform(
+                async move {
+                    let mut rx = ssh_handle
+                        .lock()
+                        .await
+                        .exec_stream(&command)
+                        .await
+                        .map_err(|e| format!("Failed to install clipboard helpers: {}", e))?;
+                    let mut output = Vec::new();
+                    tokio::time::timeout(INSTALL_TIMEOUT, async {
+                        while let Some(data) = rx.recv().await {
+                            output.extend_from_slice(&data);
+                        }
+                    })
+                    .await
+                    .map_err(|_| "Failed to install clipboard helpers: timed out".to_string())?;
+                    Ok(String::from_utf8_lossy(&output).trim().to_string())
+                },
+                move |result| Message::ClipboardHelpersInstalled(tab_id, result),
+            )
+        }
+        Message::ClipboardHelpersInstalled(tab_id, result) => {
+            let report = result.unwrap_or_else(|e| e);
+            // Looked up by id, since tabs may have closed or moved during
+            // the install.
+            if let Some(tab) = app.tabs.iter_mut().find(|tab| tab.id == tab_id) {
+                // Shown in the terminal, dimmed, since that's where the
+                // helpers get used.
+                tab.emulator
+                    .process_input(install_notice(&report).as_bytes());
+                tab.mark_full_damage();
+            }
+            app.focus_terminal_ime()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// The install report as dimmed terminal output. It comes from the remote
+/// shell, so control characters are dropped and it can't send escape
+/// sequences of its own.
+fn install_notice(report: &str) -> String {
+    let mut notice = String::from("\r\n\x1b[2m");
+    for (index, line) in report.lines().enumerate() {
+        if index > 0 {
+            notice.push_str("\r\n");
+        }
+        notice.extend(line.chars().filter(|c| *c == '\t' || !c.is_control()));
+    }
+    notice.push_str("\x1b[0m\r\n");
+    notice
+}
+
+/// Writes the helpers to `~/.local/bin` and reports what it did.
+fn install_script() -> String {
+    let mut script = format!(
+        r#"dir="$HOME/.local/bin"
+mkdir -p "$dir" || exit 1
+install_helper() {{
+  if [ -e "$dir/$1" ] && ! grep -q '{MARKER}' "$dir/$1"; then
+    echo "Skipped $dir/$1: another program is installed there"
+    return
+  fi
+  printf '%s' "$2" > "$dir/$1" && chmod 755 "$dir/$1" && echo "Installed $dir/$1"
+}}
+"#
+    );
+    for (name, body) in [("pbcopy", PBCOPY), ("pbpaste", PBPASTE)] {
+        script.push_str(&format!("install_helper {} {}\n", name, shell_quote(body)));
+    }
+    script.push_str(
+        r#"case ":$PATH:" in
+  *":$dir:"*) ;;
+  *) echo "Add $dir to PATH to use them" ;;
+esac
+"#,
+    );
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_notice_drops_remote_escape_sequences() {
+        assert_eq!(
+            install_notice("Installed a\n\x1b]52;c;cHduZWQ=\x07Installed b"),
+            "\r\n\x1b[2mInstalled a\r\n]52;c;cHduZWQ=Installed b\x1b[0m\r\n"
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use iced::Task;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::control::{ControlCall, ControlServer, RpcError};
+use crate::ui::App;
+use crate::ui::message::{Message, SessionMessage};
+use crate::ui::state::SessionState;
+
+type Reply = Result<Value, RpcError>;
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::ControlServerStarted(port, result) => {
+            // The setting changed while the server was starting.
+            if app.control_port != Some(port) {
+                return Task::none();
+            }
+            match result {
+                Ok(server) => {
+                    println!("Control API listening on {}", server.addr());
+                    app.control_server = Some(server);
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    app.last_error = Some((e, std::time::Instant::now()));
+                }
+            }
+            Task::none()
+        }
+        Message::ControlCall(call) => answer(app, call),
+        _ => Task::none(),
+    }
+}
+
+/// Starts or stops the server to match the settings. A port that fails to
+/// bind is not retried until the setting changes again.
+pub(super) fn sync_server(app: &mut App) -> Task<Message> {
+    let wanted = app
+        .app_settings
+        .control_api
+        .then_some(app.app_settings.control_api_port);
+    if wanted == app.control_port {
+        return Task::none();
+    }
+    app.control_port = wanted;
+    app.control_server = None;
+    let Some(port) = wanted else {
+        return Task::none();
+    };
+    let calls = app.control_tx.clone();
+    Task::perform(
+        async move {
+            let token = crate::control::load_or_create_token()?;
+            ControlServer::start(port, token, calls).await.map(Arc::new)
+        },
+        move |result| Message::ControlServerStarted(port, result),
+    )
+}
+
+fn answer(app: &mut App, call: ControlCall) -> Task<Message> {
+    let Some(request) = call.take() else {
+        return Task::none();
+    };
+    let (reply, task) = match request.method.as_str() {
+        "sessions.list" => (Ok(list_sessions(app)), Task::none()),
+        "tabs.list" => (Ok(list_tabs(app)), Task::none()),
+        "sessions.open" => open_session(app, &request.params),
+        "local.open" => {
+            let task = app.update(Message::CreateLocalTab);
+            (Ok(json!({ "tab": app.tabs.len() - 1 })), task)
+        }
+        "terminal.run" => run_command(app, &request.params),
+        "sftp.upload" => upload(app, &request.params),
+        "sftp.download" => download(app, &request.params),
+        method => (Err(RpcError::method_not_found(method)), Task::none()),
+    };
+    request.respond(reply);
+    task
+}
+
+fn list_sessions(app: &App) -> Value {
+    app.saved_sessions
+        .iter()
+        .map(|session| {
+            json!({
+                "id": session.id,
+                "name": session.name,
+                "host": session.host,
+                "port": session.port,
+                "username": session.username,
+                "tags": session.tags,
+            })
+        })
+        .collect()
+}
+
+fn list_tabs(app: &App) -> Value {
+    app.tabs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(index, tab)| {
+            let state = match tab.state {
+                SessionState::Connecting(_) => "connecting",
+                SessionState::Connected => "connected",
+                SessionState::Disconnected => "disconnected",
+                SessionState::Failed(_) => "failed",
+            };
+            json!({
+                "tab": index,
+                "title": tab.title,
+                "state": state,
+                "session_id": tab.sftp_key,
+                "active": index == app.active_tab,
+            })
+        })
+        .collect()
+}
+
+/// `{"id": ...}` or `{"name": ...}`; names match case-insensitively.
+fn open_session(app: &mut App, params: &Value) -> (Reply, Task<Message>) {
+    let id = string_param(params, "id");
+    let name = string_param(params, "name");
+    let session = app.saved_sessions.iter().find(|session| match (id, name) {
+        (Some(id), _) => session.id == id,
+        (None, Some(name)) => session.name.eq_ignore_ascii_case(name),
+        (None, None) => false,
+    });
+    let Some(id) = session.map(|session| session.id.clone()) else {
+        let error = if id.is_none() && name.is_none() {
+            RpcError::invalid_params("Expected \"id\" or \"name\"")
+        } else {
+            RpcError::failed("No such session")
+        };
+        return (Err(error), Task::none());
+    };
+    let task = app.update(Message::Sessions(SessionMessage::Connect(id)));
+    (Ok(json!({ "tab": app.tabs.len() - 1 })), task)
+}
+
+/// Types `command` into a tab's shell and presses Enter. The output is not
+/// returned; it shows up in the terminal like anything typed.
+fn run_command(app: &mut App, params: &Value) -> (Reply, Task<Message>) {
+    let tab_index = match tab_param(app, params) {
+        Ok(tab_index) => tab_index,
+        Err(e) => return (Err(e), Task::none()),
+    };
+    let Some(command) = string_param(params, "command") else {
+        return (
+            Err(RpcError::invalid_params("Expected \"command\"")),
+            Task::none(),
+        );
+    };
+    if app.tabs[tab_index].session.is_none() {
+        return (
+            Err(RpcError::failed("The tab is not connected")),
+            Task::none(),
+        );
+    }
+    let data = format!("{}\r", command).into_bytes();
+    let task = super::terminal::write_to_tab(app, tab_index, data);
+    (Ok(json!({ "tab": tab_index })), task)
+}
+
+/// `{"paths": [...], "remote_dir"?: ...}`, into the SFTP panel's remote
+/// directory by default.
+fn upload(app: &mut App, params: &Value) -> (Reply, Task<Message>) {
+    let tab_index = match sftp_tab_param(app, params) {
+        Ok(tab_index) => tab_index,
+        Err(e) => return (Err(e), Task::none()),
+    };
+    let paths: Vec<PathBuf> = params
+        .get("paths")
+        .and_then(Value::as_array)
+        .map(|paths| {
+            paths
+                .iter()
+                .filter_map(Value::as_str)
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    if paths.is_empty() {
+        return (
+            Err(RpcError::invalid_params("Expected \"paths\"")),
+            Task::none(),
+        );
+    }
+    if let Some(missing) = paths.iter().find(|path| !path.is_file()) {
+        return (
+            Err(RpcError::failed(format!(
+                "{} is not a file",
+                missing.display()
+            ))),
+            Task::none(),
+        );
+    }
+    let queued = paths.len();
+    let remote_dir = string_param(params, "remote_dir").map(str::to_string);
+    let task = super::start_upload_files(app, tab_index, paths, remote_dir);
+    (
+        Ok(json!({ "tab": tab_index, "queued": queued })),
+        task.unwrap_or_else(Task::none),
+    )
+}
+
+/// `{"remote_path": ..., "local_dir"?: ...}`, into the SFTP panel's local
+/// directory by default.
+fn download(app: &mut App, params: &Value) -> (Reply, Task<Message>) {
+    let tab_index = match sftp_tab_param(app, params) {
+        Ok(tab_index) => tab_index,
+        Err(e) => return (Err(e), Task::none()),
+    };
+    let Some(remote_path) = string_param(params, "remote_path") else {
+        return (
+            Err(RpcError::invalid_params("Expected \"remote_path\"")),
+            Task::none(),
+        );
+    };
+    let local_dir = string_param(params, "local_dir").map(str::to_string);
+    let before = queued_count(app, tab_index);
+    let task = super::start_download_path(app, tab_index, remote_path.to_string(), local_dir);
+    if queued_count(app, tab_index) == before {
+        return (
+            Err(RpcError::invalid_params(format!(
+                "Cannot download {}",
+                remote_path
+            ))),
+            Task::none(),
+        );
+    }
+    (
+        Ok(json!({ "tab": tab_index, "queued": 1 })),
+        task.unwrap_or_else(Task::none),
+    )
+}
+
+fn queued_count(app: &mut App, tab_index: usize) -> usize {
+    app.sftp_state_for_tab_mut(tab_index)
+        .map_or(0, |state| state.transfers.len())
+}
+
+fn string_param<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+}
+
+/// The tab named by `"tab"`, or the active one.
+fn tab_param(app: &App, params: &Value) -> Result<usize, RpcError> {
+    let tab_index = match params.get("tab") {
+        None | Some(Value::Null) => app.active_tab,
+        Some(tab) => tab
+            .as_u64()
+            .map(|tab| tab as usize)
+            .ok_or_else(|| RpcError::invalid_params("\"tab\" must be a number"))?,
+    };
+    if tab_index == 0 || tab_index >= app.tabs.len() {
+        return Err(RpcError::failed(format!("No tab {}", tab_index)));
+    }
+    Ok(tab_index)
+}
+
+fn sftp_tab_param(app: &App, params: &Value) -> Result<usize, RpcError> {
+    let tab_index = tab_param(app, params)?;
+    if app.tabs[tab_index].ssh_handle.is_none() {
+        return Err(RpcError::failed("The tab has no SSH connection"));
+    }
+    Ok(tab_index)
+}
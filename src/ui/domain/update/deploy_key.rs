@@ -0,0 +1,137 @@
+use iced::Task;
+
+use crate::session::config::AuthMethod;
+use crate::ui::App;
+use crate::ui::message::Message;
+use crate::ui::state::{DeployKey, DeployKeyStatus};
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::OpenDeployKey(session_id) => {
+            app.session_menu_open = None;
+            let Some(session) = app
+                .saved_sessions
+                .iter()
+                .find(|session| session.id == session_id)
+            else {
+                return Task::none();
+            };
+            // The session's own key when it has one, otherwise the default.
+            let own_key = match &session.auth_method {
+                AuthMethod::PrivateKey { key_id, .. } => key_id.clone(),
+                _ => None,
+            };
+            let keys = &app.app_settings.ssh_keys;
+            let key_id = own_key
+                .filter(|id| keys.iter().any(|key| &key.id == id))
+                .or_else(|| {
+                    keys.iter()
+                        .find(|key| key.is_default)
+                        .map(|key| key.id.clone())
+                })
+                .or_else(|| keys.first().map(|key| key.id.clone()))
+                .unwrap_or_default();
+            app.deploy_key = Some(DeployKey {
+                target: format!("{}@{}", session.username, session.host),
+                session_id,
+                key_id,
+                status: DeployKeyStatus::Idle,
+            });
+            Task::none()
+        }
+        Message::DeployKeySelected(key_id) => {
+            if let Some(deploy) = app.deploy_key.as_mut()
+                && deploy.status != DeployKeyStatus::Deploying
+            {
+                deploy.key_id = key_id;
+                deploy.status = DeployKeyStatus::Idle;
+            }
+            Task::none()
+        }
+        Message::DeployKey => start(app),
+        Message::DeployKeyFinished(result) => {
+            if let Some(deploy) = app.deploy_key.as_mut() {
+                deploy.status = match result {
+                    Ok(done) => DeployKeyStatus::Done(done),
+                    Err(e) => DeployKeyStatus::Failed(e),
+                };
+            }
+            Task::none()
+        }
+        Message::CloseDeployKey => {
+            // A deploy in flight finishes on its own; its answer is dropped.
+            app.deploy_key = None;
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+fn start(app: &mut App) -> Task<Message> {
+    let Some(deploy) = app.deploy_key.as_mut() else {
+        return Task::none();
+    };
+    if deploy.status == DeployKeyStatus::Deploying {
+        return Task::none();
+    }
+    let Some(key) = app
+        .app_settings
+        .ssh_keys
+        .iter()
+        .find(|key| key.id == deploy.key_id)
+        .cloned()
+    else {
+        deploy.status = DeployKeyStatus::Failed("Choose a key to deploy".to_string());
+        return Task::none();
+    };
+    let session = app
+        .saved_sessions
+        .iter()
+        .find(|session| session.id == deploy.session_id)
+        .ok_or_else(|| "The session no longer exists".to_string())
+        .and_then(|session| {
+            crate::session::expand::resolve(session, &app.app_settings.host_domains)
+        });
+    let session = match session {
+        Ok(session) => session,
+        Err(e) => {
+            deploy.status = DeployKeyStatus::Failed(e);
+            return Task::none();
+        }
+    };
+    deploy.status = DeployKeyStatus::Deploying;
+
+    let defaults = app.connect_defaults();
+    let options = crate::ssh::ConnectOptions {
+        pinned_host_key: session.host_key_fingerprint.clone(),
+        port_knock: session.port_knock.clone(),
+        jump_host: session.jump_host.clone(),
+        proxy: session.proxy_or(defaults.proxy.as_ref()),
+        prompter: app.credential_prompter(),
+        connect_timeout: Some(defaults.connect_timeout_for(&session)),
+        ..Default::default()
+    };
+    // Sessions that sign in with a key usually have no password saved; the
+    // credential prompt asks for it.
+    let password = session
+        .password
+        .clone()
+        .filter(|password| !password.is_empty());
+    Task::perform(
+        async move {
+            let public_key =
+                crate::ssh::saved_public_key(&key.id, &key.path).map_err(|e| format!("{:#}", e))?;
+            crate::ssh::deploy_public_key(
+                &session.host,
+                session.port,
+                &session.username,
+                password,
+                &public_key,
+                options,
+            )
+            .await
+            .map_err(|e| format!("{:#}", e))
+        },
+        Message::DeployKeyFinished,
+    )
+}
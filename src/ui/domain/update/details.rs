@@ -0,0 +1,125 @@
+use iced::Task;
+
+use super::{join_remote_path, list_channel};
+use crate::ui::App;
+use crate::ui::message::{Message, SftpMessage};
+use crate::ui::state::SftpEntryDetails;
+
+// Browsing a directory full of links shouldn't turn into thousands of
+// requests; whatever is past the cap stays unresolved.
+const MAX_QUEUED: usize = 256;
+const MAX_IN_FLIGHT: usize = 2;
+
+/// Queues the symlinks of the shown remote directory for resolving.
+pub(in crate::ui) fn start(app: &mut App, tab_index: usize) -> Task<Message> {
+    let enabled = app.app_settings.sftp_prefetch_details;
+    let Some(state) = app.sftp_state_for_tab_mut(tab_index) else {
+        return Task::none();
+    };
+    state.details_queue.clear();
+    if !enabled {
+        return Task::none();
+    }
+    state.details_queue.extend(
+        state
+            .remote_entries
+            .iter()
+            .filter(|entry| entry.is_symlink && entry.link_target.is_none())
+            .take(MAX_QUEUED)
+            .map(|entry| entry.name.clone()),
+    );
+    pump(app, tab_index)
+}
+
+/// Sends queued requests while there is room. Transfers the user started
+/// take precedence, so nothing is sent while any of them is running.
+pub(in crate::ui) fn pump(app: &mut App, tab_index: usize) -> Task<Message> {
+    let Some(tab) = app.tabs.get(tab_index) else {
+        return Task::none();
+    };
+    let (Some(session), channel) = (tab.session.clone(), tab.sftp_list_channel.clone()) else {
+        return Task::none();
+    };
+    let Some(state) = app.sftp_state_for_tab_mut(tab_index) else {
+        return Task::none();
+    };
+    if state.running_transfers() != (0, 0) {
+        return Task::none();
+    }
+
+    let mut tasks = Vec::new();
+    while state.details_in_flight < MAX_IN_FLIGHT {
+        let Some(name) = state.details_queue.pop_front() else {
+            break;
+        };
+        state.details_in_flight += 1;
+        let dir = state.remote_path.clone();
+        let path = join_remote_path(&dir, &name);
+        let session = session.clone();
+        let channel = channel.clone();
+        tasks.push(Task::perform(
+            async move {
+                let sftp = list_channel(&session, &channel).await?;
+                let attrs = sftp
+                    .stat(&path)
+                    .await
+                    .map_err(|e| format!("Failed to stat {}: {}", path, e))?;
+                let link_target = sftp
+                    .read_link(&path)
+                    .await
+                    .map_err(|e| format!("Failed to read link {}: {}", path, e))?;
+                Ok(SftpEntryDetails {
+                    link_target,
+                    is_dir: attrs.is_dir(),
+                    size: attrs.size,
+                })
+            },
+            move |result| {
+                Message::Sftp(SftpMessage::EntryDetails(
+                    tab_index,
+                    dir.clone(),
+                    name.clone(),
+                    result,
+                ))
+            },
+        ));
+    }
+    Task::batch(tasks)
+}
+
+pub(in crate::ui) fn handle(app: &mut App, message: SftpMessage) -> Task<Message> {
+    let SftpMessage::EntryDetails(tab_index, dir, name, result) = message else {
+        return Task::none();
+    };
+    let Some(state) = app.sftp_state_for_tab_mut(tab_index) else {
+        return Task::none();
+    };
+    state.details_in_flight = state.details_in_flight.saturating_sub(1);
+    match result {
+        Ok(details) => {
+            if state.remote_path == dir
+                && let Some(entry) = state.remote_entries.iter_mut().find(|e| e.name == name)
+            {
+                apply(entry, &details);
+            }
+            for cached in state
+                .remote_cache
+                .values_mut()
+                .filter(|cached| cached.remote_path == dir)
+            {
+                if let Some(entry) = cached.entries.iter_mut().find(|e| e.name == name) {
+                    apply(entry, &details);
+                }
+            }
+        }
+        // A dangling link is common and not worth an error banner.
+        Err(err) => tracing::debug!("{}", err),
+    }
+    pump(app, tab_index)
+}
+
+fn apply(entry: &mut crate::ui::state::SftpEntry, details: &SftpEntryDetails) {
+    entry.link_target = Some(details.link_target.clone());
+    entry.is_dir = details.is_dir;
+    entry.size = if details.is_dir { None } else { details.size };
+}
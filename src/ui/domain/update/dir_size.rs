@@ -0,0 +1,235 @@
+use iced::Task;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::{join_local_path, join_remote_path, open_transfer_channel};
+use crate::core::session::Session;
+use crate::ssh::shell_quote;
+use crate::ssh::{SshSession, TransferChannel};
+use crate::ui::App;
+use crate::ui::message::{Message, SftpMessage};
+use crate::ui::state::{DirSize, SftpContextAction, SftpPane};
+
+// How often a running count is shown.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+const DU_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub(in crate::ui) fn handle(app: &mut App, message: SftpMessage) -> Task<Message> {
+    match message {
+        SftpMessage::ContextAction(pane, name, SftpContextAction::CalculateSize) => {
+            start(app, pane, name)
+        }
+        SftpMessage::DirSize(tab_index, pane, dir, name, size) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+                let (current, entries) = match pane {
+                    SftpPane::Local => (&state.local_path, &mut state.local_entries),
+                    SftpPane::Remote => (&state.remote_path, &mut state.remote_entries),
+                };
+                // The pane moved on; the count is of no use there.
+                if *current == dir
+                    && let Some(entry) = entries.iter_mut().find(|entry| entry.name == name)
+                {
+                    entry.dir_size = Some(size);
+                }
+            }
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+fn start(app: &mut App, pane: SftpPane, name: String) -> Task<Message> {
+    let tab_index = app.active_tab;
+    let (walk, dir) = {
+        let Some(state) = app.sftp_state_for_tab(tab_index) else {
+            return Task::none();
+        };
+        match pane {
+            SftpPane::Local => {
+                let path = PathBuf::from(join_local_path(&state.local_path, &name));
+                (
+                    Walk::Local {
+                        root: path.clone(),
+                        stack: vec![path],
+                        total: 0,
+                    },
+                    state.local_path.clone(),
+                )
+            }
+            SftpPane::Remote => {
+                let Some(tab) = app.tabs.get(tab_index) else {
+                    return Task::none();
+                };
+                let (Some(session), Some(ssh)) = (tab.session.clone(), tab.ssh_handle.clone())
+                else {
+                    return Task::none();
+                };
+                let path = join_remote_path(&state.remote_path, &name);
+                (Walk::Du { session, ssh, path }, state.remote_path.clone())
+            }
+        }
+    };
+    if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+        let entries = match pane {
+            SftpPane::Local => &mut state.local_entries,
+            SftpPane::Remote => &mut state.remote_entries,
+        };
+        if let Some(entry) = entries.iter_mut().find(|entry| entry.name == name) {
+            entry.dir_size = Some(DirSize::Counting(0));
+        }
+    }
+    Task::run(iced::futures::stream::unfold(walk, step), move |size| {
+        Message::Sftp(SftpMessage::DirSize(
+            tab_index,
+            pane,
+            dir.clone(),
+            name.clone(),
+            size,
+        ))
+    })
+}
+
+enum Walk {
+    Local {
+        root: PathBuf,
+        stack: Vec<PathBuf>,
+        total: u64,
+    },
+    // `du` on the server is far faster than listing every folder over SFTP;
+    // servers without GNU du fall back to the walk.
+    Du {
+        session: Session,
+        ssh: Arc<Mutex<SshSession>>,
+        path: String,
+    },
+    Remote {
+        channel: TransferChannel,
+        root: String,
+        stack: Vec<String>,
+        total: u64,
+    },
+    Done,
+}
+
+/// Counts until the progress interval passes, then reports the running
+/// total. Symlinks are not followed, and unreadable subfolders are skipped.
+async fn step(walk: Walk) -> Option<(DirSize, Walk)> {
+    let started = Instant::now();
+    match walk {
+        Walk::Local {
+            root,
+            mut stack,
+            mut total,
+        } => {
+            while let Some(dir) = stack.pop() {
+                let mut entries = match tokio::fs::read_dir(&dir).await {
+                    Ok(entries) => entries,
+                    Err(_) if dir == root => return Some((DirSize::Failed, Walk::Done)),
+                    Err(_) => continue,
+                };
+                while let Ok(Some(entry)) = entries.next_entry().await {
+                    let Ok(meta) = entry.metadata().await else {
+                        continue;
+                    };
+                    if meta.is_dir() {
+                        stack.push(entry.path());
+                    } else {
+                        total += meta.len();
+                    }
+                }
+                if started.elapsed() >= PROGRESS_INTERVAL && !stack.is_empty() {
+                    let walk = Walk::Local { root, stack, total };
+                    return Some((DirSize::Counting(total), walk));
+                }
+            }
+            Some((DirSize::Total(total), Walk::Done))
+        }
+        Walk::Du { session, ssh, path } => {
+            if let Some(total) = du(&ssh, &path).await {
+                return Some((DirSize::Total(total), Walk::Done));
+            }
+            match open_transfer_channel(&session).await {
+                Ok(channel) => {
+                    let walk = Walk::Remote {
+                        channel,
+                        root: path.clone(),
+                        stack: vec![path],
+                        total: 0,
+                    };
+                    Some((DirSize::Counting(0), walk))
+                }
+                Err(_) => Some((DirSize::Failed, Walk::Done)),
+            }
+        }
+        Walk::Remote {
+            channel,
+            root,
+            mut stack,
+            mut total,
+        } => {
+            while let Some(dir) = stack.pop() {
+                let mut listing = match channel.read_dir(&dir).await {
+                    Ok(listing) => listing,
+                    Err(_) if dir == root => return Some((DirSize::Failed, Walk::Done)),
+                    Err(_) => continue,
+                };
+                while let Ok(Some(files)) = listing.next_batch().await {
+                    for file in files {
+                        if file.filename == "." || file.filename == ".." {
+                            continue;
+                        }
+                        if file.attrs.is_dir() {
+                            stack.push(join_remote_path(&dir, &file.filename));
+                        } else {
+                            total += file.attrs.size.unwrap_or(0);
+                        }
+                    }
+                }
+                if started.elapsed() >= PROGRESS_INTERVAL && !stack.is_empty() {
+                    let walk = Walk::Remote {
+                        channel,
+                        root,
+                        stack,
+                        total,
+                    };
+                    return Some((DirSize::Counting(total), walk));
+                }
+            }
+            Some((DirSize::Total(total), Walk::Done))
+        }
+        Walk::Done => None,
+    }
+}
+
+/// Bytes under `path` by GNU `du -sb`, or None where that isn't available.
+async fn du(ssh: &Mutex<SshSession>, path: &str) -> Option<u64> {
+    let command = format!("du -sb -- {} 2>/dev/null", shell_quote(path));
+    let run = async {
+        let mut rx = ssh.lock().await.exec_stream(&command).await.ok()?;
+        let mut output = Vec::new();
+        while let Some(data) = rx.recv().await {
+            output.extend_from_slice(&data);
+        }
+        Some(output)
+    };
+    let output = tokio::time::timeout(DU_TIMEOUT, run).await.ok()??;
+    parse_du(&String::from_utf8_lossy(&output))
+}
+
+fn parse_du(output: &str) -> Option<u64> {
+    output.split_whitespace().next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_du_output() {
+        assert_eq!(parse_du("73421\t/srv/app\n"), Some(73421));
+        assert_eq!(parse_du(""), None);
+        assert_eq!(parse_du("du: illegal option -- b\n"), None);
+    }
+}
@@ -0,0 +1,103 @@
+use iced::Task;
+use std::path::{Path, PathBuf};
+
+use crate::terminal::editor::{self, FileLocation};
+use crate::ui::App;
+use crate::ui::message::Message;
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::OpenTerminalLocation(location) => {
+            let Some(tab) = app.tabs.get(app.active_tab) else {
+                return Task::none();
+            };
+            let cwd = tab.emulator.working_directory();
+            if let Some(process) = &tab.local_process {
+                // Shells that don't report their directory are asked the OS.
+                let cwd = cwd.map(PathBuf::from).or_else(|| {
+                    process
+                        .pid
+                        .and_then(|pid| crate::platform::process_cwd(pid as i32))
+                });
+                let path = local_path(&location.path, cwd.as_deref());
+                if !path.exists() {
+                    return Task::done(Message::EditorOpened(Err(format!(
+                        "No such file: {}",
+                        path.display()
+                    ))));
+                }
+                let command = app
+                    .app_settings
+                    .editor
+                    .command_for(&location.path)
+                    .map(str::to_string);
+                return Task::perform(open(command, path, location), Message::EditorOpened);
+            }
+            // Remote files are shown in the SFTP panel instead.
+            if tab.ssh_handle.is_some() {
+                let path = if location.is_absolute() {
+                    Some(location.path)
+                } else {
+                    cwd.filter(|_| !location.path.starts_with('~')).map(|dir| {
+                        format!(
+                            "{}/{}",
+                            dir.trim_end_matches('/'),
+                            location.path.trim_start_matches("./")
+                        )
+                    })
+                };
+                if let Some(path) = path {
+                    return Task::done(Message::OpenRemotePath(path));
+                }
+            }
+            Task::none()
+        }
+        Message::EditorOpened(result) => {
+            if let Err(e) = result {
+                app.last_error = Some((e, std::time::Instant::now()));
+            }
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+fn local_path(path: &str, cwd: Option<&Path>) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    match cwd {
+        Some(cwd) => cwd.join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Opens `path` with the configured `command`, or the system's default
+/// app for it without one.
+async fn open(
+    command: Option<String>,
+    path: PathBuf,
+    location: FileLocation,
+) -> Result<(), String> {
+    let file = path.to_string_lossy();
+    let Some(command) = command else {
+        return crate::platform::open_url(&file)
+            .map_err(|e| format!("Failed to open {}: {}", file, e));
+    };
+    let args = editor::command_line(&command, &file, &location);
+    let Some((program, args)) = args.split_first() else {
+        return Ok(());
+    };
+    let status = tokio::process::Command::new(program)
+        .args(args)
+        .status()
+        .await
+        .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
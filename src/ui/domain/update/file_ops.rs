@@ -0,0 +1,280 @@
+use iced::Task;
+use std::path::Path;
+use std::time::Duration;
+
+use super::{
+    MAX_NUMBERED_COPIES, join_local_path, join_remote_path, open_transfer_channel,
+    start_remote_list,
+};
+use crate::session::config::ConflictAction;
+use crate::ssh::shell_quote;
+use crate::ui::App;
+use crate::ui::message::{Message, SftpMessage};
+use crate::ui::state::{SftpClipboard, SftpContextAction, SftpPane};
+
+// A recursive copy of a large tree can take a while, but not forever.
+const REMOTE_COPY_TIMEOUT: Duration = Duration::from_secs(600);
+
+pub(in crate::ui) fn handle(app: &mut App, message: SftpMessage) -> Task<Message> {
+    match message {
+        SftpMessage::ContextAction(pane, name, action) => {
+            let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) else {
+                return Task::none();
+            };
+            match action {
+                SftpContextAction::Cut | SftpContextAction::Copy => {
+                    let dir = match pane {
+                        SftpPane::Local => state.local_path.clone(),
+                        SftpPane::Remote => state.remote_path.clone(),
+                    };
+                    state.clipboard = Some(SftpClipboard {
+                        pane,
+                        dir,
+                        name,
+                        cut: action == SftpContextAction::Cut,
+                    });
+                    Task::none()
+                }
+                SftpContextAction::Paste => {
+                    let Some(clipboard) = state.clipboard.clone().filter(|c| c.pane == pane) else {
+                        return Task::none();
+                    };
+                    // Pasting on a folder puts the entry inside it.
+                    let (current, entries) = match pane {
+                        SftpPane::Local => (&state.local_path, &state.local_entries),
+                        SftpPane::Remote => (&state.remote_path, &state.remote_entries),
+                    };
+                    let into_folder = entries
+                        .iter()
+                        .any(|entry| entry.is_dir && entry.name == name);
+                    let dest_dir = if into_folder {
+                        join_path(pane, current, &name)
+                    } else {
+                        current.clone()
+                    };
+                    if clipboard.cut {
+                        state.clipboard = None;
+                    }
+                    start(
+                        app,
+                        pane,
+                        clipboard.dir,
+                        clipboard.name,
+                        dest_dir,
+                        !clipboard.cut,
+                    )
+                }
+                _ => Task::none(),
+            }
+        }
+        SftpMessage::FileOpFinished(tab_index, pane, result) => {
+            let Some(state) = app.sftp_state_for_tab_mut(tab_index) else {
+                return Task::none();
+            };
+            if let Err(error) = result {
+                state.remote_error = Some(error);
+            }
+            match pane {
+                SftpPane::Local => Task::done(Message::Sftp(SftpMessage::LocalPathChanged(
+                    state.local_path.clone(),
+                ))),
+                SftpPane::Remote => {
+                    state.invalidate_remote_cache();
+                    start_remote_list(app, tab_index).unwrap_or_else(Task::none)
+                }
+            }
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Moves `name` into the folder `folder`, both in the shown directory of
+/// `pane`. Used when a row is dropped on a folder row.
+pub(super) fn move_into(
+    app: &mut App,
+    pane: SftpPane,
+    name: String,
+    folder: String,
+) -> Task<Message> {
+    let Some(state) = app.sftp_state_for_tab(app.active_tab) else {
+        return Task::none();
+    };
+    let (current, entries) = match pane {
+        SftpPane::Local => (state.local_path.clone(), &state.local_entries),
+        SftpPane::Remote => (state.remote_path.clone(), &state.remote_entries),
+    };
+    if name == folder
+        || !entries
+            .iter()
+            .any(|entry| entry.is_dir && entry.name == folder)
+    {
+        return Task::none();
+    }
+    let dest_dir = join_path(pane, &current, &folder);
+    start(app, pane, current, name, dest_dir, false)
+}
+
+fn join_path(pane: SftpPane, base: &str, name: &str) -> String {
+    match pane {
+        SftpPane::Local => join_local_path(base, name),
+        SftpPane::Remote => join_remote_path(base, name),
+    }
+}
+
+/// Moves or copies `name` from `source_dir` to `dest_dir` within one pane.
+/// A copy into its own directory gets a numbered name; anything else that
+/// would replace an existing entry is refused.
+fn start(
+    app: &mut App,
+    pane: SftpPane,
+    source_dir: String,
+    name: String,
+    dest_dir: String,
+    copy: bool,
+) -> Task<Message> {
+    let tab_index = app.active_tab;
+    let source = join_path(pane, &source_dir, &name);
+    let dest = join_path(pane, &dest_dir, &name);
+    if source == dest && !copy {
+        return Task::none();
+    }
+    let inside_itself = match pane {
+        SftpPane::Local => source != dest && Path::new(&dest).starts_with(&source),
+        SftpPane::Remote => dest.starts_with(&format!("{}/", source.trim_end_matches('/'))),
+    };
+    if inside_itself {
+        if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+            state.remote_error = Some(format!("Can't put {} inside itself", name));
+        }
+        return Task::none();
+    }
+
+    let done = move |result| Message::Sftp(SftpMessage::FileOpFinished(tab_index, pane, result));
+    match pane {
+        SftpPane::Local => Task::perform(
+            async move {
+                let dest = if source == dest {
+                    free_numbered_local(&dest_dir, &name).await
+                } else if tokio::fs::try_exists(&dest).await.unwrap_or(false) {
+                    return Err(format!("{} already exists there", name));
+                } else {
+                    dest
+                };
+                if copy {
+                    tokio::task::spawn_blocking(move || {
+                        copy_local(Path::new(&source), Path::new(&dest))
+                    })
+                    .await
+                    .map_err(|e| format!("Copy failed: {}", e))?
+                    .map_err(|e| format!("Copy failed: {}", e))
+                } else {
+                    tokio::fs::rename(&source, &dest)
+                        .await
+                        .map_err(|e| format!("Move failed: {}", e))
+                }
+            },
+            done,
+        ),
+        SftpPane::Remote => {
+            let Some(tab) = app.tabs.get(tab_index) else {
+                return Task::none();
+            };
+            let (Some(session), Some(ssh_handle)) = (tab.session.clone(), tab.ssh_handle.clone())
+            else {
+                return Task::none();
+            };
+            Task::perform(
+                async move {
+                    let channel = open_transfer_channel(&session).await?;
+                    let dest = if source == dest {
+                        let mut copy_number = 2;
+                        loop {
+                            let candidate = join_remote_path(
+                                &dest_dir,
+                                &ConflictAction::numbered_name(&name, copy_number),
+                            );
+                            if copy_number >= MAX_NUMBERED_COPIES
+                                || channel.stat(&candidate).await.is_err()
+                            {
+                                break candidate;
+                            }
+                            copy_number += 1;
+                        }
+                    } else if channel.stat(&dest).await.is_ok() {
+                        return Err(format!("{} already exists there", name));
+                    } else {
+                        dest
+                    };
+                    if !copy {
+                        return channel
+                            .rename(&source, &dest)
+                            .await
+                            .map_err(|e| format!("Move failed: {}", e));
+                    }
+                    // SFTP has no copy request; the server copies it.
+                    let command = format!(
+                        "cp -Rp -- {} {} 2>&1; echo \"exit=$?\"",
+                        shell_quote(&source),
+                        shell_quote(&dest)
+                    );
+                    let run = async {
+                        let mut rx = ssh_handle
+                            .lock()
+                            .await
+                            .exec_stream(&command)
+                            .await
+                            .map_err(|e| format!("Copy failed: {}", e))?;
+                        let mut output = Vec::new();
+                        while let Some(data) = rx.recv().await {
+                            output.extend_from_slice(&data);
+                        }
+                        Ok::<_, String>(String::from_utf8_lossy(&output).into_owned())
+                    };
+                    let output = tokio::time::timeout(REMOTE_COPY_TIMEOUT, run)
+                        .await
+                        .map_err(|_| "Copy failed: timed out".to_string())??;
+                    let (message, status) = output
+                        .trim_end()
+                        .rsplit_once("exit=")
+                        .unwrap_or((output.as_str(), ""));
+                    match status {
+                        "0" => Ok(()),
+                        _ => Err(format!("Copy failed: {}", message.trim())),
+                    }
+                },
+                done,
+            )
+        }
+    }
+}
+
+async fn free_numbered_local(dir: &str, name: &str) -> String {
+    let mut copy = 2;
+    loop {
+        let candidate = join_local_path(dir, &ConflictAction::numbered_name(name, copy));
+        if copy >= MAX_NUMBERED_COPIES || !tokio::fs::try_exists(&candidate).await.unwrap_or(false)
+        {
+            return candidate;
+        }
+        copy += 1;
+    }
+}
+
+// Symlinks are recreated rather than followed, as `cp -R` does.
+fn copy_local(source: &Path, dest: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(source)?;
+    #[cfg(unix)]
+    if metadata.file_type().is_symlink() {
+        return std::os::unix::fs::symlink(std::fs::read_link(source)?, dest);
+    }
+    if metadata.is_dir() {
+        std::fs::create_dir(dest)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_local(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        std::fs::set_permissions(dest, metadata.permissions())
+    } else {
+        std::fs::copy(source, dest).map(|_| ())
+    }
+}
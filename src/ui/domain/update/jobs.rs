@@ -0,0 +1,64 @@
+use iced::Task;
+
+use super::cancel_transfer;
+use crate::ui::App;
+use crate::ui::message::Message;
+use crate::ui::state::{ConnectionTestStatus, JobOwner};
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::ToggleJobsPanel => {
+            app.show_jobs_panel = !app.show_jobs_panel;
+            Task::none()
+        }
+        Message::ClearFinishedJobs => {
+            app.background_jobs.clear_finished();
+            if app.background_jobs.is_empty() {
+                app.show_jobs_panel = false;
+            }
+            Task::none()
+        }
+        Message::CancelBackgroundJob(id) => {
+            let Some(owner) = app.background_jobs.cancel(id) else {
+                return Task::none();
+            };
+            // The work itself has stopped; what's left is the state each
+            // feature shows for it.
+            match owner {
+                JobOwner::Transfer(transfer_id) => {
+                    let tab_index = app.sftp_states.values().find_map(|state| {
+                        state
+                            .transfers
+                            .iter()
+                            .find(|transfer| transfer.id == transfer_id)
+                            .map(|transfer| transfer.tab_index)
+                    });
+                    if let Some(tab_index) = tab_index
+                        && let Some(task) = cancel_transfer(app, tab_index, transfer_id)
+                    {
+                        return task;
+                    }
+                }
+                JobOwner::RemoteListing(listing_id) => {
+                    if let Some(state) = app.sftp_states.values_mut().find(|state| {
+                        state
+                            .remote_listing
+                            .as_ref()
+                            .is_some_and(|listing| listing.id == listing_id)
+                    }) {
+                        state.remote_listing = None;
+                        state.remote_error = Some("Listing cancelled".to_string());
+                    }
+                }
+                JobOwner::ConnectionTest => {
+                    if app.connection_test_status == ConnectionTestStatus::Testing {
+                        app.connection_test_status = ConnectionTestStatus::Idle;
+                    }
+                }
+                JobOwner::Scheduled(_) => {}
+            }
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
@@ -1,18 +1,77 @@
 use iced::Task;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
 
 use portable_pty::{CommandBuilder, PtySize, native_pty_system};
 use uuid::Uuid;
 
+use super::forward_terminal_responses;
+use crate::core::session::Session;
+use crate::terminal::OutputReceiver;
 use crate::ui::App;
-use crate::ui::message::{ActiveView, Message};
-use crate::ui::state::{SessionState, SessionTab, SftpState};
+use crate::ui::message::{ActiveView, Message, TerminalMessage};
+use crate::ui::state::{LocalProcess, SessionState, SessionTab, SftpState};
 
 pub(in crate::ui) fn create_local_tab(app: &mut App) -> Task<Message> {
-    let mut commands = Vec::new();
-
     app.show_quick_connect = false;
+    let mut cmd = shell_command();
+    if app.app_settings.local_tabs_inherit {
+        inherit_from_previous_tab(app, &mut cmd);
+    }
+    let Some((session, rx, process)) = spawn_shell(cmd) else {
+        return Task::none();
+    };
+
+    let mut tab = SessionTab::new("Local Shell");
+    let sftp_key = format!("local:{}", Uuid::new_v4());
+    tab.sftp_key = Some(sftp_key.clone());
+    app.sftp_states
+        .entry(sftp_key)
+        .or_insert_with(SftpState::new);
+    app.tabs.push(tab);
+    let tab_index = app.tabs.len() - 1;
+    app.active_tab = tab_index;
+    app.active_view = ActiveView::Terminal;
+    app.last_terminal_tab = tab_index;
+    Task::batch([
+        attach(app, tab_index, session, rx, process),
+        app.focus_terminal_ime(),
+    ])
+}
+
+/// Starts a new shell in a local tab whose shell has exited, keeping the
+/// old output above it.
+pub(in crate::ui) fn restart(app: &mut App, tab_index: usize) -> Task<Message> {
+    let Some(tab) = app.tabs.get(tab_index) else {
+        return Task::none();
+    };
+    let mut cmd = shell_command();
+    if let Some(dir) = tab
+        .emulator
+        .working_directory()
+        .filter(|dir| std::path::Path::new(dir).is_dir())
+    {
+        cmd.cwd(dir);
+    }
+    let Some((session, rx, process)) = spawn_shell(cmd) else {
+        return Task::none();
+    };
+    tab.write_notice("— restarted —");
+    attach(app, tab_index, session, rx, process)
+}
+
+fn shell_command() -> CommandBuilder {
+    let mut cmd = CommandBuilder::new("zsh");
+    cmd.env("TERM", "xterm-256color");
+    cmd.env("LANG", "en_US.UTF-8");
+    cmd.env("LC_ALL", "en_US.UTF-8");
+    cmd
+}
+
+/// Runs `cmd` on a new PTY. The output receiver ends once the shell has
+/// exited and its exit code is recorded.
+fn spawn_shell(cmd: CommandBuilder) -> Option<(Session, OutputReceiver, Arc<LocalProcess>)> {
     let system = native_pty_system();
     let size = PtySize {
         rows: 24,
@@ -20,122 +79,126 @@ pub(in crate::ui) fn create_local_tab(app: &mut App) -> Task<Message> {
         pixel_width: 0,
         pixel_height: 0,
     };
+    let pair = match system.openpty(size) {
+        Ok(pair) => pair,
+        Err(e) => {
+            println!("Failed to open PTY: {}", e);
+            return None;
+        }
+    };
+    let mut child = match pair.slave.spawn_command(cmd) {
+        Ok(child) => child,
+        Err(e) => {
+            println!("Failed to spawn shell: {}", e);
+            return None;
+        }
+    };
+    println!("Local: process spawned");
+    let master = pair.master;
+    let mut reader = master.try_clone_reader().unwrap();
 
-    match system.openpty(size) {
-        Ok(pair) => {
-            let mut cmd = CommandBuilder::new("zsh");
-            cmd.env("TERM", "xterm-256color");
-            cmd.env("LANG", "en_US.UTF-8");
-            cmd.env("LC_ALL", "en_US.UTF-8");
-
-            match pair.slave.spawn_command(cmd) {
-                Ok(_) => {
-                    println!("Local: process spawned");
-                    let master = pair.master;
-                    let mut reader = master.try_clone_reader().unwrap();
-
-                    let backend = crate::core::backend::SessionBackend::Local {
-                        master: Arc::new(std::sync::Mutex::new(master)),
-                    };
-                    let session = crate::core::session::Session::new(backend);
-
-                    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-
-                    std::thread::spawn(move || {
-                        println!("Local: reader thread started");
-                        let mut buf = [0u8; 1024];
-                        loop {
-                            match reader.read(&mut buf) {
-                                Ok(n) if n > 0 => {
-                                    if let Err(e) = tx.send(buf[..n].to_vec()) {
-                                        println!("Local: failed to send to channel: {}", e);
-                                        break;
-                                    }
-                                }
-                                Ok(_) => break,
-                                Err(e) => {
-                                    println!("Local: read error: {}", e);
-                                    break;
-                                }
-                            }
-                        }
-                        println!("Local: reader thread ended");
-                    });
-
-                    let mut tab = SessionTab::new("Local Shell");
-                    let sftp_key = format!("local:{}", Uuid::new_v4());
-                    tab.sftp_key = Some(sftp_key.clone());
-                    app.sftp_states
-                        .entry(sftp_key)
-                        .or_insert_with(SftpState::new);
-                    tab.state = SessionState::Connected;
-                    tab.session = Some(session.clone());
-                    tab.rx = Some(Arc::new(Mutex::new(rx)));
-
-                    if let Some(mut output_rx) = tab.emulator.take_output_receiver() {
-                        let session_clone = session.clone();
-                        std::thread::spawn(move || {
-                            let rt = tokio::runtime::Runtime::new().unwrap();
-                            rt.block_on(async {
-                                while let Some(data) = output_rx.recv().await {
-                                    if let Err(e) = session_clone.write(&data).await {
-                                        println!("Failed to write terminal response to PTY: {}", e);
-                                        break;
-                                    }
-                                }
-                            });
-                        });
-                    }
-
-                    app.tabs.push(tab);
-                    let tab_index = app.tabs.len() - 1;
-                    app.active_tab = tab_index;
-                    app.active_view = ActiveView::Terminal;
-                    app.last_terminal_tab = tab_index;
-                    commands.push(app.focus_terminal_ime());
-
-                    if let Some(tab) = app.tabs.get_mut(tab_index) {
-                        if let Some(rx) = &tab.rx {
-                            let rx_clone = rx.clone();
-                            let read_task = Task::perform(
-                                async move {
-                                    let mut guard = rx_clone.lock().await;
-                                    match guard.recv().await {
-                                        Some(data) => (tab_index, data),
-                                        None => (tab_index, vec![]),
-                                    }
-                                },
-                                |(idx, data)| Message::TerminalDataReceived(idx, data),
-                            );
-                            commands.push(read_task);
-
-                            let width = app.window_width;
-                            let height = app.window_height;
-                            if width > 0 && height > 0 {
-                                let reserved_width = 0.0;
-                                let h_padding = 24.0;
-                                let v_padding = 80.0;
-
-                                let term_w = (width as f32 - reserved_width - h_padding).max(0.0);
-                                let term_h = (height as f32 - v_padding).max(0.0);
-
-                                let cols = (term_w / app.cell_width()) as usize;
-                                let rows = (term_h / app.cell_height()) as usize;
-
-                                commands.push(Task::done(Message::TerminalResize(cols, rows)));
-                            }
-                        }
+    let backend = crate::core::backend::SessionBackend::Local {
+        master: Arc::new(std::sync::Mutex::new(master)),
+    };
+    let session = Session::new(backend);
+    let process = Arc::new(LocalProcess {
+        pid: child.process_id(),
+        started: Instant::now(),
+        exit_code: std::sync::OnceLock::new(),
+    });
+
+    let (tx, rx) = crate::terminal::output_channel();
+    let exited = process.clone();
+    std::thread::spawn(move || {
+        println!("Local: reader thread started");
+        let mut buf = [0u8; 1024];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(n) if n > 0 => {
+                    if let Err(e) = tx.send(buf[..n].to_vec()) {
+                        println!("Local: failed to send to channel: {}", e);
+                        break;
                     }
                 }
+                Ok(_) => break,
                 Err(e) => {
-                    println!("Failed to spawn shell: {}", e);
+                    println!("Local: read error: {}", e);
+                    break;
                 }
             }
         }
-        Err(e) => {
-            println!("Failed to open PTY: {}", e);
+        // Recorded before `tx` drops, so the UI sees it when the output ends.
+        match child.wait() {
+            Ok(status) => {
+                let _ = exited.exit_code.set(status.exit_code());
+            }
+            Err(e) => println!("Local: wait failed: {}", e),
         }
-    }
+        println!("Local: reader thread ended");
+    });
+    Some((session, rx, process))
+}
 
+fn attach(
+    app: &mut App,
+    tab_index: usize,
+    session: Session,
+    rx: OutputReceiver,
+    process: Arc<LocalProcess>,
+) -> Task<Message> {
+    let Some(tab) = app.tabs.get_mut(tab_index) else {
+        return Task::none();
+    };
+    let rx = Arc::new(Mutex::new(rx));
+    tab.state = SessionState::Connected;
+    tab.session = Some(session);
+    tab.rx = Some(rx.clone());
+    tab.local_process = Some(process);
+    forward_terminal_responses(tab);
+
+    let read_task = Task::perform(
+        async move {
+            let mut guard = rx.lock().await;
+            match guard.recv().await {
+                Some(data) => (tab_index, data),
+                None => (tab_index, vec![]),
+            }
+        },
+        |(idx, data)| Message::Terminal(TerminalMessage::DataReceived(idx, data)),
+    );
+    let mut commands = vec![read_task];
+    let width = app.window_width;
+    let height = app.window_height;
+    if app.active_tab == tab_index && width > 0 && height > 0 {
+        let (cols, rows) = app.terminal_grid_size(width, height);
+        commands.push(Task::done(Message::Terminal(TerminalMessage::Resize(
+            cols, rows,
+        ))));
+    }
     Task::batch(commands)
 }
+
+/// Carries over what the last local tab's shell reported about itself. A
+/// shell without integration reports nothing, so the tab starts cold.
+fn inherit_from_previous_tab(app: &App, cmd: &mut CommandBuilder) {
+    let Some(previous) = app.tabs.get(app.last_terminal_tab).filter(|tab| {
+        tab.session.as_ref().is_some_and(|session| {
+            matches!(
+                *session.backend,
+                crate::core::backend::SessionBackend::Local { .. }
+            )
+        })
+    }) else {
+        return;
+    };
+    if let Some(dir) = previous
+        .emulator
+        .working_directory()
+        .filter(|dir| std::path::Path::new(dir).is_dir())
+    {
+        cmd.cwd(dir);
+    }
+    for (name, value) in previous.emulator.user_vars() {
+        cmd.env(name, value);
+    }
+}
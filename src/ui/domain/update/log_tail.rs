@@ -0,0 +1,106 @@
+use iced::Task;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::ssh::shell_quote;
+use crate::ui::App;
+use crate::ui::message::Message;
+use crate::ui::state::LogTailState;
+
+const TAIL_INITIAL_LINES: usize = 200;
+
+pub(in crate::ui) fn start(app: &mut App, remote_path: String) -> Task<Message> {
+    let tab_index = app.active_tab;
+    let Some(ssh_handle) = app
+        .tabs
+        .get(tab_index)
+        .filter(|_| tab_index != 0)
+        .and_then(|tab| tab.ssh_handle.clone())
+    else {
+        if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+            state.remote_error = Some("No active SSH session".to_string());
+        }
+        return Task::none();
+    };
+
+    let state = LogTailState::new(remote_path.clone());
+    let id = state.id;
+    app.log_tail = Some(state);
+
+    let command = format!(
+        "tail -n {} -F -- {}",
+        TAIL_INITIAL_LINES,
+        shell_quote(&remote_path)
+    );
+    Task::perform(
+        async move {
+            let guard = ssh_handle.lock().await;
+            guard
+                .exec_stream(&command)
+                .await
+                .map(|rx| Arc::new(Mutex::new(rx)))
+                .map_err(|e| format!("Failed to start tail: {}", e))
+        },
+        move |result| Message::LogTailStarted(id, result),
+    )
+}
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::LogTailStarted(id, result) => {
+            if let Some(state) = app.log_tail.as_mut().filter(|state| state.id == id) {
+                match result {
+                    Ok(rx) => {
+                        state.rx = Some(rx);
+                        state.streaming = true;
+                    }
+                    Err(err) => {
+                        state.error = Some(err);
+                    }
+                }
+            }
+        }
+        Message::LogTailData(id, data) => {
+            if let Some(state) = app.log_tail.as_mut().filter(|state| state.id == id) {
+                if data.is_empty() {
+                    // Channel closed: flush whatever is left of the last line.
+                    if !state.partial.is_empty() {
+                        state.push_data(b"\n");
+                    }
+                    state.streaming = false;
+                    state.rx = None;
+                } else {
+                    state.push_data(&data);
+                }
+            }
+        }
+        Message::LogTailTogglePause => {
+            if let Some(state) = app.log_tail.as_mut() {
+                let paused = !state.paused;
+                state.set_paused(paused);
+            }
+        }
+        Message::LogTailFilterChanged(value) => {
+            if let Some(state) = app.log_tail.as_mut() {
+                state.filter = value;
+            }
+        }
+        Message::LogTailHighlightChanged(value) => {
+            if let Some(state) = app.log_tail.as_mut() {
+                state.highlight = value;
+            }
+        }
+        Message::LogTailClear => {
+            if let Some(state) = app.log_tail.as_mut() {
+                state.lines.clear();
+                state.held.clear();
+            }
+        }
+        Message::LogTailClose => {
+            // Dropping the receiver closes the exec channel on the remote side.
+            app.log_tail = None;
+        }
+        _ => {}
+    }
+    Task::none()
+}
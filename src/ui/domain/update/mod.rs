@@ -1,6 +1,30 @@
+mod clipboard;
+mod control;
+mod deploy_key;
+mod details;
+mod dir_size;
+mod editor;
+mod file_ops;
+mod jobs;
 mod local;
+mod log_tail;
+mod placeholders;
+mod port_check;
+mod probe;
+mod reconnect;
+mod schedule;
+mod screen_guard;
+mod selection;
+mod session_table;
 mod sessions;
+mod sftp;
+mod share;
+mod subshell;
 mod terminal;
+mod transfer_panel;
+mod trash;
+mod usage;
+mod warmup;
 mod window;
 
 use iced::Task;
@@ -10,12 +34,15 @@ use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+use crate::core::jobs::CancelToken;
 use crate::core::session::Session;
+use crate::session::config::{ConflictAction, TransferDefaults};
+use crate::session::placeholder;
 use crate::ui::App;
-use crate::ui::message::{ActiveView, Message};
+use crate::ui::message::{ActiveView, Message, SessionMessage, SftpMessage, TerminalMessage};
 use crate::ui::state::{
-    SessionState, SftpContextAction, SftpContextMenu, SftpEntry, SftpPane, SftpTransfer,
-    SftpTransferDirection, SftpTransferStatus, SftpTransferUpdate,
+    JobOwner, RemoteListing, SessionState, SessionTab, SftpEntry, SftpListEvent, SftpPane,
+    SftpTransfer, SftpTransferDirection, SftpTransferStatus, SftpTransferUpdate,
 };
 
 impl App {
@@ -26,11 +53,17 @@ impl App {
             Message::CreateLocalTab => {
                 return local::create_local_tab(self);
             }
+            Message::RestartLocalShell(index) => {
+                return local::restart(self, index);
+            }
             // Message::CreateSession => { ... } // Removed
             Message::SelectTab(index) => {
                 println!("UI: Selecting tab {}", index);
+                self.show_tab_overview = false;
                 if index < self.tabs.len() {
                     self.active_tab = index;
+                    self.tabs[index].has_activity = false;
+                    window::restore_sftp_panel_width(self);
                     if index == 0 {
                         self.active_view = ActiveView::SessionManager;
                     } else {
@@ -48,33 +81,23 @@ impl App {
                 }
             }
             Message::CloseTab(index) => {
-                if index == 0 {
+                if let Some(program) = self.tabs.get(index).and_then(SessionTab::running_program)
+                    && index > 0
+                {
+                    self.pending_close = Some((index, program));
                     return Task::none();
                 }
-                if index < self.tabs.len() {
-                    self.tabs.remove(index);
-                    let mut active_keys = HashSet::new();
-                    for tab in &self.tabs {
-                        if let Some(key) = &tab.sftp_key {
-                            active_keys.insert(key.clone());
-                        }
-                    }
-                    self.sftp_states.retain(|key, _| active_keys.contains(key));
-                    if self.active_tab >= self.tabs.len() && self.active_tab > 0 {
-                        self.active_tab -= 1;
-                    }
-                    if self.last_terminal_tab == index {
-                        self.last_terminal_tab = self.active_tab;
-                    } else if self.last_terminal_tab > index {
-                        self.last_terminal_tab -= 1;
-                    }
-                    if self.active_tab == 0 {
-                        self.active_view = ActiveView::SessionManager;
-                    } else {
-                        self.active_view = ActiveView::Terminal;
-                    }
+                return close_tab(self, index);
+            }
+            Message::ConfirmCloseTab => {
+                if let Some((index, _)) = self.pending_close.take() {
+                    return close_tab(self, index);
                 }
             }
+            Message::CancelCloseTab => {
+                self.pending_close = None;
+                return self.focus_terminal_ime();
+            }
             Message::ShowSessionManager => {
                 self.show_quick_connect = false;
                 self.active_view = ActiveView::SessionManager;
@@ -109,6 +132,7 @@ impl App {
                             self.sftp_panel_width = self.sftp_panel_width.clamp(280.0, max_width);
                         }
                     }
+                    window::restore_sftp_panel_width(self);
                     if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
                         let result = load_local_entries(&state.local_path);
                         match result {
@@ -172,23 +196,18 @@ impl App {
                     Message::Ignore
                 });
             }
+            Message::LocalFileRevealed(result) => {
+                if let Err(e) = result {
+                    self.last_error = Some((e, std::time::Instant::now()));
+                }
+            }
+            Message::OpenRemotePath(path) => {
+                return reveal_remote_path(self, path);
+            }
             Message::PortForwardStatusUpdated(session_id, statuses) => {
                 self.port_forward_statuses
                     .insert(session_id, statuses.into_iter().collect());
             }
-            Message::SftpDragStart => {
-                self.sftp_dragging = true;
-            }
-            Message::SftpDragEnd => {
-                self.sftp_dragging = false;
-            }
-            Message::SftpDragMove(point) => {
-                if self.sftp_dragging && self.window_width > 0 {
-                    let max_width = (self.window_width as f32 - 240.0).max(320.0);
-                    let width = (self.window_width as f32 - point.x).clamp(280.0, max_width);
-                    self.sftp_panel_width = width;
-                }
-            }
             Message::PortForwardDragStart => {
                 self.port_forward_dragging = true;
             }
@@ -202,550 +221,72 @@ impl App {
                     self.port_forward_panel_width = width;
                 }
             }
-            Message::PortForwardDirectionChanged(_) => {
-                return sessions::handle(self, message);
-            }
-            Message::SftpLocalPathChanged(path) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.local_path = path;
-                    state.local_selected = None;
-                    state.local_last_click = None;
-                    state.context_menu = None;
-                    let result = load_local_entries(&state.local_path);
-                    match result {
-                        Ok(entries) => {
-                            state.local_entries = entries;
-                            state.local_error = None;
-                        }
-                        Err(err) => {
-                            state.local_entries.clear();
-                            state.local_error = Some(err);
-                        }
-                    }
-                }
+            Message::RunTransferJob(_)
+            | Message::TransferJobFinished(..)
+            | Message::TransferJobScheduleChanged(..)
+            | Message::DeleteTransferJob(_)
+            | Message::JobToastDismiss => {
+                return schedule::handle(self, message);
             }
-            Message::SftpRemotePathChanged(path) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.remote_path = path;
-                    state.remote_selected = None;
-                    state.remote_last_click = None;
-                    state.context_menu = None;
-                }
-                if let Some(task) = start_remote_list(self, self.active_tab) {
-                    return task;
-                }
-            }
-            Message::SftpRemoteLoaded(tab_index, result) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(tab_index) {
-                    state.remote_loading = false;
-                    match result {
-                        Ok((entries, resolved_path)) => {
-                            state.remote_entries = entries;
-                            state.remote_error = None;
-                            if let Some(path) = resolved_path {
-                                state.remote_path = path;
-                            }
-                        }
-                        Err(err) => {
-                            state.remote_entries.clear();
-                            state.remote_error = Some(err);
-                        }
-                    }
-                }
-            }
-            Message::SftpPanelCursorMoved(point) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.panel_cursor = Some(point);
-                }
-            }
-            Message::SftpLocalEntryPressed(name, is_dir) => {
-                return handle_local_click(self, name, is_dir);
-            }
-            Message::SftpRemoteEntryPressed(name, is_dir) => {
-                return handle_remote_click(self, name, is_dir);
-            }
-            Message::SftpFileDragStart(pane, name) => {
-                // Also select the item when dragging starts
-                let mut tasks = Vec::new();
-                match pane {
-                    SftpPane::Local => {
-                        let is_dir = self
-                            .sftp_state_for_tab(self.active_tab)
-                            .and_then(|s| s.local_entries.iter().find(|e| e.name == name))
-                            .map(|e| e.is_dir)
-                            .unwrap_or(false);
-                        tasks.push(handle_local_click(self, name.clone(), is_dir));
-                    }
-                    SftpPane::Remote => {
-                        let is_dir = self
-                            .sftp_state_for_tab(self.active_tab)
-                            .and_then(|s| s.remote_entries.iter().find(|e| e.name == name))
-                            .map(|e| e.is_dir)
-                            .unwrap_or(false);
-                        tasks.push(handle_remote_click(self, name.clone(), is_dir));
-                    }
-                }
-                self.sftp_file_dragging = Some((pane, name));
-                if !tasks.is_empty() {
-                    return Task::batch(tasks);
-                }
-            }
-            Message::SftpFileDragUpdate(position) => {
-                self.sftp_drag_position = Some(position);
-            }
-            Message::SftpFileDragEnd => {
-                if let Some((source_pane, name)) = self.sftp_file_dragging.take() {
-                    let cursor_x = self.sftp_drag_position.map(|p| p.x).unwrap_or(0.0);
-                    let window_width = self.window_width as f32;
-                    let panel_width = self.sftp_panel_width;
-
-                    // Logic to determine drop target
-                    // SFTP Panel Right = window_width
-                    // SFTP Panel Left = window_width - panel_width
-                    // Content Left = SFTP Panel Left + 10 (handle) + 12 (padding)
-                    // Content Width = panel_width - 10 - 24
-                    // Split X = Content Left + Content Width / 2.0
-
-                    let content_left = window_width - panel_width + 22.0;
-                    let content_width = panel_width - 34.0;
-                    let split_x = content_left + content_width / 2.0;
-
-                    let target_pane = if cursor_x < split_x {
-                        SftpPane::Local
-                    } else {
-                        SftpPane::Remote
-                    };
-
-                    if source_pane != target_pane {
-                        match (source_pane, target_pane) {
-                            (SftpPane::Local, SftpPane::Remote) => {
-                                if let Some(task) = start_upload(self, name) {
-                                    return task;
-                                }
-                            }
-                            (SftpPane::Remote, SftpPane::Local) => {
-                                if let Some(task) = start_download(self, name) {
-                                    return task;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                    self.sftp_drag_position = None;
-                }
+            Message::Sftp(message) => {
+                return sftp::handle(self, message);
             }
-            Message::SftpFileHover(hovered) => {
-                self.sftp_hovered_file = hovered;
+            Message::SetTransferPanelMode(_) => {
+                return transfer_panel::handle(self, message);
             }
-            Message::SftpOpenContextMenu(pane, name) => {
-                let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) else {
-                    return Task::none();
-                };
-                let position = state.panel_cursor.unwrap_or(iced::Point::new(16.0, 16.0));
-                if !name.is_empty() {
-                    match pane {
-                        SftpPane::Local => {
-                            state.local_selected = Some(name.clone());
-                        }
-                        SftpPane::Remote => {
-                            state.remote_selected = Some(name.clone());
-                        }
-                    }
-                }
-                state.context_menu = Some(SftpContextMenu {
-                    pane,
-                    name,
-                    position,
-                });
+            Message::ControlServerStarted(..) | Message::ControlCall(_) => {
+                return control::handle(self, message);
             }
-            Message::SftpCloseContextMenu => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.context_menu = None;
-                    if state.rename_target.is_some() {
-                        state.rename_target = None;
-                        state.rename_value.clear();
-                    }
-                }
+            Message::ShareTab(_)
+            | Message::ShareStarted(..)
+            | Message::StopSharing(_)
+            | Message::CopyShareLink(_)
+            | Message::WatchShare(_)
+            | Message::WatchStarted(_) => {
+                return share::handle(self, message);
             }
-            Message::SftpContextAction(pane, name, action) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.context_menu = None;
-                }
-
-                if action == SftpContextAction::Refresh {
-                    if let Some(state) = self.sftp_state_for_tab(self.active_tab) {
-                        let path = match pane {
-                            SftpPane::Local => state.local_path.clone(),
-                            SftpPane::Remote => state.remote_path.clone(),
-                        };
-                        let message = match pane {
-                            SftpPane::Local => Message::SftpLocalPathChanged(path),
-                            SftpPane::Remote => Message::SftpRemotePathChanged(path),
-                        };
-                        return Task::done(message);
-                    }
-                    return Task::none();
-                }
-
-                if pane == SftpPane::Local && action == SftpContextAction::Upload {
-                    if let Some(task) = start_upload(self, name.clone()) {
-                        return task;
-                    }
-                }
-                if pane == SftpPane::Remote && action == SftpContextAction::Download {
-                    if let Some(task) = start_download(self, name.clone()) {
-                        return task;
-                    }
-                }
-                if action == SftpContextAction::Rename {
-                    let is_dir = match pane {
-                        SftpPane::Local => self
-                            .sftp_state_for_tab(self.active_tab)
-                            .and_then(|state| {
-                                state
-                                    .local_entries
-                                    .iter()
-                                    .find(|entry| entry.name == name)
-                                    .map(|entry| entry.is_dir)
-                            })
-                            .unwrap_or(false),
-                        SftpPane::Remote => self
-                            .sftp_state_for_tab(self.active_tab)
-                            .and_then(|state| {
-                                state
-                                    .remote_entries
-                                    .iter()
-                                    .find(|entry| entry.name == name)
-                                    .map(|entry| entry.is_dir)
-                            })
-                            .unwrap_or(false),
-                    };
-                    if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                        state.rename_target = Some(crate::ui::state::SftpPendingAction {
-                            pane,
-                            name: name.clone(),
-                            is_dir,
-                        });
-                        state.rename_value = name.clone();
-                    }
-                    return iced::widget::operation::focus(self.sftp_rename_input_id.clone());
-                }
-                if action == SftpContextAction::Delete {
-                    let is_dir = match pane {
-                        SftpPane::Local => self
-                            .sftp_state_for_tab(self.active_tab)
-                            .and_then(|state| {
-                                state
-                                    .local_entries
-                                    .iter()
-                                    .find(|entry| entry.name == name)
-                                    .map(|entry| entry.is_dir)
-                            })
-                            .unwrap_or(false),
-                        SftpPane::Remote => self
-                            .sftp_state_for_tab(self.active_tab)
-                            .and_then(|state| {
-                                state
-                                    .remote_entries
-                                    .iter()
-                                    .find(|entry| entry.name == name)
-                                    .map(|entry| entry.is_dir)
-                            })
-                            .unwrap_or(false),
-                    };
-                    if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                        state.delete_target =
-                            Some(crate::ui::state::SftpPendingAction { pane, name, is_dir });
-                    }
-                }
+            Message::DuplicateTab(_)
+            | Message::NewShellOnHost(_)
+            | Message::SiblingShellOpened(..) => {
+                return subshell::handle(self, message);
             }
-            Message::SftpTransferCancel(id) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    if let Some(transfer) = state
-                        .transfers
-                        .iter_mut()
-                        .find(|transfer| transfer.id == id)
-                    {
-                        transfer.cancel_flag.store(true, Ordering::SeqCst);
-                        transfer.pause_flag.store(false, Ordering::SeqCst);
-                        transfer.pause_notify.notify_waiters();
-                        if matches!(
-                            transfer.status,
-                            SftpTransferStatus::Queued | SftpTransferStatus::Uploading
-                        ) {
-                            transfer.status = SftpTransferStatus::Canceled;
-                        }
-                    }
-                    if let Some(task) = schedule_transfer_tasks(self, self.active_tab) {
-                        return task;
-                    }
-                }
+            Message::CheckPortForward(_) | Message::PortForwardChecked(..) => {
+                return port_check::handle(self, message);
             }
-            Message::SftpTransferPause(id) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    if let Some(transfer) = state
-                        .transfers
-                        .iter_mut()
-                        .find(|transfer| transfer.id == id)
-                    {
-                        if transfer.status == SftpTransferStatus::Uploading {
-                            transfer.pause_flag.store(true, Ordering::SeqCst);
-                            transfer.status = SftpTransferStatus::Paused;
-                        }
-                    }
-                    if let Some(task) = schedule_transfer_tasks(self, self.active_tab) {
-                        return task;
-                    }
-                }
+            Message::RemoteClipboardReply(..)
+            | Message::InstallClipboardHelpers
+            | Message::ClipboardHelpersInstalled(..) => {
+                return clipboard::handle(self, message);
             }
-            Message::SftpTransferResume(id) => {
-                let max_concurrent = self.sftp_max_concurrent;
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    let active = state
-                        .transfers
-                        .iter()
-                        .filter(|transfer| transfer.status == SftpTransferStatus::Uploading)
-                        .count();
-                    if active < max_concurrent {
-                        if let Some(transfer) = state
-                            .transfers
-                            .iter_mut()
-                            .find(|transfer| transfer.id == id)
-                        {
-                            if transfer.status == SftpTransferStatus::Paused {
-                                transfer.pause_flag.store(false, Ordering::SeqCst);
-                                transfer.pause_notify.notify_waiters();
-                                transfer.status = SftpTransferStatus::Uploading;
-                            }
-                        }
-                    }
-                }
-            }
-            Message::SftpTransferRetry(id) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    if let Some(transfer) = state
-                        .transfers
-                        .iter_mut()
-                        .find(|transfer| transfer.id == id)
-                    {
-                        transfer.cancel_flag.store(false, Ordering::SeqCst);
-                        transfer.status = SftpTransferStatus::Queued;
-                        transfer.bytes_sent = 0;
-                        transfer.bytes_total = 0;
-                        transfer.started_at = None;
-                        transfer.last_update = None;
-                        transfer.last_bytes_sent = 0;
-                        transfer.last_rate_bps = None;
-                        transfer.cancel_flag.store(false, Ordering::SeqCst);
-                        transfer.pause_flag.store(false, Ordering::SeqCst);
-                    }
-                    if let Some(task) = schedule_transfer_tasks(self, self.active_tab) {
-                        return task;
-                    }
-                }
-            }
-            Message::SftpTransferClearDone => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.transfers.retain(|transfer| {
-                        !matches!(
-                            transfer.status,
-                            SftpTransferStatus::Completed
-                                | SftpTransferStatus::Failed(_)
-                                | SftpTransferStatus::Canceled
-                        )
-                    });
-                }
-            }
-            Message::SftpRenameStart(pane, name, is_dir) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.rename_target = Some(crate::ui::state::SftpPendingAction {
-                        pane,
-                        name: name.clone(),
-                        is_dir,
-                    });
-                    state.rename_value = name;
-                }
-                return iced::widget::operation::focus(self.sftp_rename_input_id.clone());
-            }
-            Message::SftpRenameInput(value) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.rename_value = value;
-                }
-            }
-            Message::SftpRenameCancel => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.rename_target = None;
-                    state.rename_value.clear();
-                }
-            }
-            Message::SftpRenameConfirm => {
-                if let Some(task) = start_rename(self) {
-                    return task;
-                }
-            }
-            Message::SftpRenameFinished(tab_index, result) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(tab_index) {
-                    let target = state.rename_target.clone();
-                    state.rename_target = None;
-                    state.rename_value.clear();
-                    match result {
-                        Ok(()) => {
-                            if let Some(target) = target {
-                                return match target.pane {
-                                    SftpPane::Local => Task::done(Message::SftpLocalPathChanged(
-                                        state.local_path.clone(),
-                                    )),
-                                    SftpPane::Remote => {
-                                        if let Some(task) = start_remote_list(self, tab_index) {
-                                            task
-                                        } else {
-                                            Task::none()
-                                        }
-                                    }
-                                };
-                            }
-                        }
-                        Err(err) => {
-                            state.remote_error = Some(err);
-                        }
-                    }
-                }
-            }
-            Message::SftpDeleteStart(pane, name, is_dir) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.delete_target =
-                        Some(crate::ui::state::SftpPendingAction { pane, name, is_dir });
-                }
-            }
-            Message::SftpDeleteCancel => {
-                if let Some(state) = self.sftp_state_for_tab_mut(self.active_tab) {
-                    state.delete_target = None;
-                }
-            }
-            Message::SftpDeleteConfirm => {
-                if let Some(task) = start_delete(self) {
-                    return task;
-                }
-            }
-            Message::SftpDeleteFinished(tab_index, result) => {
-                if let Some(state) = self.sftp_state_for_tab_mut(tab_index) {
-                    let target = state.delete_target.clone();
-                    state.delete_target = None;
-                    match result {
-                        Ok(()) => {
-                            if let Some(target) = target {
-                                return match target.pane {
-                                    SftpPane::Local => Task::done(Message::SftpLocalPathChanged(
-                                        state.local_path.clone(),
-                                    )),
-                                    SftpPane::Remote => {
-                                        if let Some(task) = start_remote_list(self, tab_index) {
-                                            task
-                                        } else {
-                                            Task::none()
-                                        }
-                                    }
-                                };
-                            }
-                        }
-                        Err(err) => {
-                            state.remote_error = Some(err);
-                        }
-                    }
-                }
-            }
-            Message::SftpTransferUpdate(update) => {
-                let status = update.status.clone();
-                let mut should_refresh = false;
-                let mut error_message: Option<String> = None;
-                if let Some(state) = self.sftp_state_for_tab_mut(update.tab_index) {
-                    if let Some(transfer) = state
-                        .transfers
-                        .iter_mut()
-                        .find(|transfer| transfer.id == update.id)
-                    {
-                        transfer.bytes_sent = update.bytes_sent;
-                        transfer.bytes_total = update.bytes_total;
-                        let now = std::time::Instant::now();
-                        if transfer.started_at.is_none() {
-                            transfer.started_at = Some(now);
-                        }
-                        if let Some(last_update) = transfer.last_update {
-                            let elapsed = now.duration_since(last_update);
-                            if elapsed.as_millis() >= 200 {
-                                let delta_bytes =
-                                    update.bytes_sent.saturating_sub(transfer.last_bytes_sent);
-                                let rate = (delta_bytes as f64 / elapsed.as_secs_f64()) as u64;
-                                transfer.last_rate_bps = Some(rate);
-                                transfer.last_update = Some(now);
-                                transfer.last_bytes_sent = update.bytes_sent;
-                            }
-                        } else {
-                            transfer.last_update = Some(now);
-                            transfer.last_bytes_sent = update.bytes_sent;
-                        }
-                        if let Some(status_value) = status.clone() {
-                            transfer.status = status_value;
-                        }
-                        if matches!(
-                            status,
-                            Some(
-                                SftpTransferStatus::Completed
-                                    | SftpTransferStatus::Canceled
-                                    | SftpTransferStatus::Paused
-                            )
-                        ) && transfer.direction == SftpTransferDirection::Upload
-                            && update.tab_index == self.active_tab
-                            && self.sftp_panel_open
-                        {
-                            should_refresh = true;
-                        }
-                        if let Some(SftpTransferStatus::Failed(error)) = status.clone() {
-                            error_message = Some(error);
-                        }
-                    }
-                }
-
-                if let Some(message) = error_message {
-                    if let Some(state) = self.sftp_state_for_tab_mut(update.tab_index) {
-                        state.remote_error = Some(message);
-                    }
-                }
-
-                let mut tasks = Vec::new();
-                if should_refresh {
-                    if let Some(task) = start_remote_list(self, self.active_tab) {
-                        tasks.push(task);
-                    }
-                }
-                if matches!(
-                    status,
-                    Some(
-                        SftpTransferStatus::Completed
-                            | SftpTransferStatus::Failed(_)
-                            | SftpTransferStatus::Canceled
-                            | SftpTransferStatus::Paused
-                    )
-                ) {
-                    if let Some(task) = schedule_transfer_tasks(self, update.tab_index) {
-                        tasks.push(task);
-                    }
-                }
-                if !tasks.is_empty() {
-                    return Task::batch(tasks);
-                }
+            Message::LogTailStarted(..)
+            | Message::LogTailData(..)
+            | Message::LogTailTogglePause
+            | Message::LogTailFilterChanged(_)
+            | Message::LogTailHighlightChanged(_)
+            | Message::LogTailClear
+            | Message::LogTailClose => {
+                return log_tail::handle(self, message);
             }
             Message::ShowSettings => {
                 self.show_quick_connect = false;
                 self.session_menu_open = None;
                 self.open_settings_window();
             }
-            Message::WindowResized(_, _) | Message::WindowOpened(_) | Message::WindowClosed(_) => {
+            Message::WindowResized(_, _)
+            | Message::WindowMoved(_)
+            | Message::WindowMaximized(_)
+            | Message::WindowOpened(_)
+            | Message::WindowClosed(_) => {
                 if let Some(task) = window::handle(self, message) {
                     return task;
                 }
             }
             Message::RuntimeEvent(event, window_id) => {
+                if Some(window_id) == self.main_window
+                    && let Some(task) = screen_guard::handle_runtime_event(self, &event)
+                {
+                    return task;
+                }
                 if let Some(task) = window::handle_runtime_event(self, &event, window_id) {
                     return task;
                 }
@@ -753,46 +294,59 @@ impl App {
                     return task;
                 }
             }
-            Message::CreateNewSession
-            | Message::EditSession(_)
-            | Message::DeleteSession(_)
-            | Message::ConnectToSession(_)
-            | Message::SaveSession
-            | Message::CancelSessionEdit
-            | Message::CloseSessionManager
-            | Message::ToggleAuthMethod
-            | Message::ClearValidationError
-            | Message::SessionNameChanged(_)
-            | Message::SessionHostChanged(_)
-            | Message::SessionPortChanged(_)
-            | Message::SessionUsernameChanged(_)
-            | Message::SessionPasswordChanged(_)
-            | Message::TogglePasswordVisibility
-            | Message::SessionKeyIdChanged(_)
-            | Message::SessionKeyPassphraseChanged(_)
-            | Message::SessionSearchChanged(_)
-            | Message::ToggleSavedKeyMenu
-            | Message::CloseSavedKeyMenu
-            | Message::SessionDialogTabSelected(_)
-            | Message::OpenPortForwarding(_)
-            | Message::ClosePortForwarding
-            | Message::PortForwardLocalPortChanged(_)
-            | Message::PortForwardLocalHostChanged(_)
-            | Message::PortForwardRemoteHostChanged(_)
-            | Message::PortForwardRemotePortChanged(_)
-            | Message::AddPortForward
-            | Message::TogglePortForward(_)
-            | Message::DeletePortForward(_)
-            | Message::TestConnection
-            | Message::TestConnectionResult(_)
-            | Message::ToggleSessionMenu(_)
-            | Message::CloseSessionMenu => {
+            Message::Sessions(message) => {
                 return sessions::handle(self, message);
             }
+            Message::SetSessionTable(_)
+            | Message::SortSessions(_)
+            | Message::ToggleSessionSelected(_)
+            | Message::SelectSessions(_, _)
+            | Message::ConnectSelectedSessions
+            | Message::DeleteSelectedSessions => {
+                return session_table::handle(self, message);
+            }
             Message::SessionConnected(result, tab_index) => match result {
-                Ok((session, rx)) => {
+                Ok((session, rx, typed_passphrases)) => {
+                    let saved = self.tabs.get(tab_index).and_then(|tab| {
+                        self.saved_sessions
+                            .iter()
+                            .find(|saved| tab.sftp_key.as_ref() == Some(&saved.id))
+                    });
+                    let tmux_session = saved.and_then(|saved| saved.tmux_session.clone());
+                    // Placeholders nobody was asked about, as when a
+                    // session opens at startup, get their defaults.
+                    let remote_command = self
+                        .tabs
+                        .get(tab_index)
+                        .and_then(|tab| tab.remote_command.clone())
+                        .or_else(|| {
+                            saved
+                                .and_then(|saved| saved.remote_command.as_deref())
+                                .map(placeholder::fill_defaults)
+                        });
+                    let mut connected_id = None;
+                    if let Some(saved) = saved {
+                        if let Some(key) = sessions::connection_key(
+                            saved,
+                            &self.app_settings.host_domains,
+                            &self.connect_defaults(),
+                        ) {
+                            self.sessions.register(key, &session);
+                        }
+                        if !self.tabs[tab_index].reconnecting {
+                            connected_id = Some(saved.id.clone());
+                        }
+                    }
+                    if let Some(id) = connected_id {
+                        usage::record_connect(self, &id);
+                    }
+                    sessions::remember_typed_passphrases(self, tab_index, &typed_passphrases);
                     if let Some(tab) = self.tabs.get_mut(tab_index) {
                         tab.ssh_handle = Some(session.clone()); // Store SSH handle
+                        if let Ok(ssh) = session.try_lock() {
+                            tab.bandwidth = Some(ssh.bandwidth());
+                            tab.connection_status = Some(ssh.status());
+                        }
                         tab.session = None; // Not fully ready (shell not opened)
                         tab.rx = Some(rx.clone());
                         tab.state = SessionState::Connected; // Transition to Connected
@@ -802,7 +356,10 @@ impl App {
                         let open_shell_task = Task::perform(
                             async move {
                                 let mut guard = session_clone.lock().await;
-                                match guard.open_shell().await {
+                                match guard
+                                    .open_shell(tmux_session.as_deref(), remote_command.as_deref())
+                                    .await
+                                {
                                     Ok(id) => Ok(id),
                                     Err(e) => Err(e.to_string()),
                                 }
@@ -830,7 +387,9 @@ impl App {
                                     }
                                 }
                             },
-                            |(idx, data)| Message::TerminalDataReceived(idx, data),
+                            |(idx, data)| {
+                                Message::Terminal(TerminalMessage::DataReceived(idx, data))
+                            },
                         );
                         return Task::batch(vec![open_shell_task, read_task]);
                     }
@@ -856,53 +415,60 @@ impl App {
                                 session: ssh_handle.clone(),
                                 channel_id: id,
                             };
+                            let ssh_handle = ssh_handle.clone();
+                            let channel_id = id;
                             tab.session = Some(Session::new(backend));
+                            forward_terminal_responses(tab);
+
+                            commands.push(Task::perform(
+                                async move {
+                                    let exit = ssh_handle.lock().await.take_shell_exit();
+                                    match exit {
+                                        Some(exit) => exit.await.ok(),
+                                        None => None,
+                                    }
+                                },
+                                move |status| match status {
+                                    Some(status) => Message::Terminal(
+                                        TerminalMessage::ShellExited(tab_index, channel_id, status),
+                                    ),
+                                    None => Message::Ignore,
+                                },
+                            ));
+                        }
 
-                            // Wire up terminal responses (CPR) for SSH
-                            if let Some(mut output_rx) = tab.emulator.take_output_receiver() {
-                                if let Some(session) = &tab.session {
-                                    let session_clone = session.clone();
-                                    std::thread::spawn(move || {
-                                        let rt = tokio::runtime::Runtime::new().unwrap();
-                                        rt.block_on(async {
-                                            while let Some(data) = output_rx.recv().await {
-                                                // println!("SSH: Sending terminal response: {} bytes", data.len());
-                                                // Add timeout to prevent hanging if connection is dead
-                                                let write_future = session_clone.write(&data);
-                                                match tokio::time::timeout(std::time::Duration::from_millis(1000), write_future).await {
-                                                    Ok(Ok(_)) => {},
-                                                    Ok(Err(e)) => {
-                                                        tracing::warn!("ssh write terminal response failed: {}", e);
-                                                        break;
-                                                    },
-                                                    Err(_) => {
-                                                        tracing::warn!("ssh write terminal response timeout - connection might be dead");
-                                                        // We don't break here immediately, hoping it's temporary?
-                                                        // Or we should? If TCP is stuck, it's stuck.
-                                                    }
-                                                }
-                                            }
-                                        });
-                                    });
-                                }
-                            }
+                        tab.last_input = std::time::Instant::now();
+                        let reconnected = tab.reconnecting;
+                        if reconnected {
+                            tab.reconnecting = false;
+                            tab.auto_reconnect = None;
+                            tab.write_reconnect_divider();
                         }
+                        let session_id = tab.sftp_key.clone();
 
                         // Trigger initial resize based on current window size
                         let width = self.window_width;
                         let height = self.window_height;
                         if width > 0 && height > 0 {
-                            let reserved_width = 0.0;
-                            let h_padding = 24.0;
-                            let v_padding = 80.0;
-
-                            let term_w = (width as f32 - reserved_width - h_padding).max(0.0);
-                            let term_h = (height as f32 - v_padding).max(0.0);
-
-                            let cols = (term_w / self.cell_width()) as usize;
-                            let rows = (term_h / self.cell_height()) as usize;
+                            let (cols, rows) = self.terminal_grid_size(width, height);
+                            commands.push(Task::done(Message::Terminal(TerminalMessage::Resize(
+                                cols, rows,
+                            ))));
+                        }
 
-                            return Task::done(Message::TerminalResize(cols, rows));
+                        // Bring back what the dropped connection was carrying.
+                        if reconnected {
+                            if let Some(session_id) = session_id
+                                && self.port_forward_statuses.contains_key(&session_id)
+                            {
+                                commands.push(sessions::apply_port_forwards(self, &session_id));
+                            }
+                            if self.sftp_panel_open
+                                && tab_index == self.active_tab
+                                && let Some(task) = start_remote_list(self, tab_index)
+                            {
+                                commands.push(task);
+                            }
                         }
                     }
                 }
@@ -913,27 +479,7 @@ impl App {
                     }
                 }
             },
-            Message::TerminalDataReceived(tab_index, data) => {
-                if let Some(task) =
-                    terminal::handle(self, Message::TerminalDataReceived(tab_index, data))
-                {
-                    return task;
-                }
-            }
-            Message::TerminalDamaged(_, _)
-            | Message::TerminalMousePress(_, _)
-            | Message::TerminalMouseDrag(_, _)
-            | Message::TerminalMouseRelease
-            | Message::TerminalMouseDoubleClick(_, _)
-            | Message::TerminalResize(_, _)
-            | Message::ScrollWheel(_)
-            | Message::TerminalInput(_)
-            | Message::Copy
-            | Message::Paste
-            | Message::ClipboardReceived(_)
-            | Message::ImeBufferChanged(_)
-            | Message::ImeFocusChanged(_)
-            | Message::ImePaste => {
+            Message::Terminal(message) => {
                 if let Some(task) = terminal::handle(self, message) {
                     return task;
                 }
@@ -942,19 +488,129 @@ impl App {
                 self.show_quick_connect = !self.show_quick_connect;
                 if self.show_quick_connect {
                     self.quick_connect_query = String::new(); // Reset query on open
+                    self.quick_connect_selected = 0;
+                    self.quick_connect_clipboard = None;
+                    self.quick_connect_share = None;
+                    commands.push(self.warm_up_quick_connect_selection());
+                    commands.push(iced::clipboard::read().map(Message::QuickConnectClipboardRead));
+                } else if self.active_view == ActiveView::Terminal {
+                    commands.push(self.focus_terminal_ime());
+                }
+            }
+            Message::ToggleTabOverview => {
+                self.show_tab_overview = !self.show_tab_overview;
+                if self.show_tab_overview {
+                    self.show_quick_connect = false;
                 } else if self.active_view == ActiveView::Terminal {
                     commands.push(self.focus_terminal_ime());
                 }
             }
             Message::QuickConnectQueryChanged(query) => {
                 self.quick_connect_query = query;
+                self.quick_connect_selected = 0;
+                return self.warm_up_quick_connect_selection();
+            }
+            Message::QuickConnectMoveSelection(delta) => {
+                // Commands follow the sessions, and the local terminal entry
+                // comes last.
+                let count =
+                    self.quick_connect_matches().len() + self.quick_connect_commands().len() + 1;
+                self.quick_connect_selected = (self.quick_connect_selected as i64 + delta as i64)
+                    .rem_euclid(count as i64)
+                    as usize;
+                return self.warm_up_quick_connect_selection();
+            }
+            Message::WarmUpSession(_) | Message::WarmUpFinished(_, _) => {
+                return warmup::handle(self, message);
+            }
+            Message::ProbeSessions | Message::SessionProbed(_, _) => {
+                return probe::handle(self, message);
+            }
+            Message::ToggleJobsPanel
+            | Message::CancelBackgroundJob(_)
+            | Message::ClearFinishedJobs => {
+                return jobs::handle(self, message);
+            }
+            Message::QuickConnectSubmit => {
+                if let Some(link) =
+                    crate::terminal::share::ShareLink::find(&self.quick_connect_query)
+                {
+                    return Task::done(Message::WatchShare(link));
+                }
+                let sessions = self.quick_connect_matches();
+                if let Some(m) = sessions.get(self.quick_connect_selected) {
+                    return Task::done(Message::SelectQuickConnectSession(m.session.id.clone()));
+                }
+                let commands = self.quick_connect_commands();
+                return match commands.get(self.quick_connect_selected - sessions.len()) {
+                    Some(m) => Task::done(Message::QuickConnectInsertCommand(
+                        m.tab_index,
+                        m.record.command.clone(),
+                    )),
+                    None => Task::done(Message::CreateLocalTab),
+                };
+            }
+            Message::QuickConnectClipboardRead(contents) => {
+                self.quick_connect_share = contents
+                    .as_deref()
+                    .and_then(crate::terminal::share::ShareLink::find);
+                // A share link also contains an IP address; don't offer both.
+                self.quick_connect_clipboard = contents
+                    .as_deref()
+                    .filter(|_| self.quick_connect_share.is_none())
+                    .and_then(crate::session::target::ConnectTarget::find);
             }
             Message::SelectQuickConnectSession(name) => {
                 self.show_quick_connect = false;
-                return Task::perform(async move { name }, Message::ConnectToSession);
+                return Task::perform(async move { name }, |value| {
+                    Message::Sessions(SessionMessage::Connect(value))
+                });
+            }
+            // The command is typed, not run. Without a shell in the active
+            // tab it goes to the tab it was run in.
+            Message::QuickConnectInsertCommand(tab_index, command) => {
+                self.show_quick_connect = false;
+                let has_shell = self.active_view == ActiveView::Terminal
+                    && self
+                        .tabs
+                        .get(self.active_tab)
+                        .is_some_and(|tab| tab.session.is_some());
+                let insert = Task::done(Message::Terminal(TerminalMessage::InsertCommand(command)));
+                return if has_shell {
+                    insert
+                } else {
+                    Task::done(Message::SelectTab(tab_index)).chain(insert)
+                };
             }
             Message::Tick(_now) => {
                 crate::platform::maybe_setup_macos_menu();
+                let unread = self.unread_tab_count();
+                if unread != self.badge_count {
+                    self.badge_count = unread;
+                    crate::platform::set_badge_count(unread);
+                }
+                let credential = self.credential_queue.current();
+                if credential != self.credential_shown {
+                    self.credential_input.clear();
+                    self.credential_remember = false;
+                    if credential.is_some() {
+                        commands.push(iced::widget::operation::focus(
+                            self.credential_input_id.clone(),
+                        ));
+                    }
+                    self.credential_shown = credential;
+                }
+                while let Some(request) = self.credential_queue.sign_request()
+                    && self
+                        .agent_sign_rules
+                        .iter()
+                        .any(|rule| rule.matches(&request.host, &request.fingerprint))
+                {
+                    self.credential_queue.answer_sign(true);
+                }
+                self.agent_sign_shown = self.credential_queue.sign_request();
+                window::save_settled_state(self);
+                commands.push(control::sync_server(self));
                 if crate::platform::take_settings_request() {
                     self.show_quick_connect = false;
                     self.session_menu_open = None;
@@ -968,24 +624,60 @@ impl App {
                     }
                 }
 
+                let now = std::time::Instant::now();
+                for tab in &mut self.tabs {
+                    if let Some(bandwidth) = &tab.bandwidth {
+                        tab.bandwidth_history.sample(bandwidth.totals(), now);
+                    }
+                }
+                usage::tick(self);
+                screen_guard::tick(self);
+
+                if let Some(task) = sessions::disconnect_idle_tabs(self) {
+                    commands.push(task);
+                }
+                if let Some(task) = sessions::check_access_expiry(self) {
+                    commands.push(task);
+                }
+                if let Some(task) = reconnect::tick(self) {
+                    commands.push(task);
+                }
+                if let Some(task) = terminal::ring_bells(self) {
+                    commands.push(task);
+                }
+                if let Some(task) = clipboard::sync(self) {
+                    commands.push(task);
+                }
+                if let Some(task) = warmup::expire(self) {
+                    commands.push(task);
+                }
+                if let Some(task) = schedule::run_due(self) {
+                    commands.push(task);
+                }
+                if let Some(task) = sftp::scroll_dragged_list(self) {
+                    commands.push(task);
+                }
+
                 if let Some((cols, rows, at)) = self.pending_resize {
                     if std::time::Instant::now().duration_since(at)
                         > std::time::Duration::from_millis(120)
                     {
                         self.pending_resize = None;
-                        return Task::done(Message::TerminalResize(cols, rows));
+                        return Task::done(Message::Terminal(TerminalMessage::Resize(cols, rows)));
                     }
                 }
 
                 if self.active_view == ActiveView::Terminal
                     && !self.show_quick_connect
+                    && self.placeholder_prompt.is_none()
                     && std::time::Instant::now().duration_since(self.last_ime_focus_check)
                         > std::time::Duration::from_millis(120)
                 {
                     self.last_ime_focus_check = std::time::Instant::now();
                     commands.push(
-                        iced::widget::operation::is_focused(self.ime_input_id.clone())
-                            .map(Message::ImeFocusChanged),
+                        iced::widget::operation::is_focused(self.ime_input_id.clone()).map(
+                            |value| Message::Terminal(TerminalMessage::ImeFocusChanged(value)),
+                        ),
                     );
                 }
 
@@ -1021,51 +713,89 @@ impl App {
                     }
                 }
             }
+            Message::RevealTerminal => {
+                return screen_guard::reveal(self);
+            }
             Message::RetryConnection(tab_index) => {
-                // Actually retry the SSH connection
+                let config = self
+                    .sftp_key_for_tab(tab_index)
+                    .and_then(|id| self.saved_sessions.iter().find(|s| s.id == id))
+                    .cloned();
+                let defaults = self.connect_defaults();
+                if self
+                    .disconnect_toast
+                    .as_ref()
+                    .is_some_and(|toast| toast.tab_index == tab_index)
+                {
+                    self.disconnect_toast = None;
+                }
                 if let Some(tab) = self.tabs.get_mut(tab_index) {
+                    let Some(saved_session) = config else {
+                        tab.state =
+                            SessionState::Failed("Session configuration not found".to_string());
+                        return Task::none();
+                    };
+                    // Keep the emulator (and its scrollback) when the tab had a live
+                    // shell before; the new shell is appended below a divider.
+                    tab.reconnecting = tab.reconnecting || tab.session.is_some();
+                    tab.expiry_warned = false;
+                    tab.shell_exit = None;
+                    if let Some(reconnect) = tab.auto_reconnect.as_mut() {
+                        reconnect.next = None;
+                    }
+                    // SFTP channels of the old connection are dead; the
+                    // panel opens new ones on the next listing.
+                    tab.sftp_session = Arc::new(Mutex::new(None));
+                    tab.sftp_list_channel = Arc::new(Mutex::new(None));
                     tab.state = SessionState::Connecting(std::time::Instant::now());
+                    tab.connect_timeout = defaults.connect_timeout_for(&saved_session);
 
-                    // For now, we need the session config to retry
-                    // TODO: Store session config with each tab for retry
-                    // As a workaround, try to find matching saved session
-                    if let Some(saved_session) = self.saved_sessions.first() {
-                        let host = saved_session.host.clone();
-                        let port = saved_session.port;
-                        let username = saved_session.username.clone();
-                        let password = saved_session.password.clone();
-                        let auth_method = saved_session.auth_method.clone();
-                        let key_passphrase = saved_session.key_passphrase.clone();
-
-                        return Task::perform(
-                            async move {
-                                match crate::ssh::SshSession::connect(
-                                    &host,
-                                    port,
-                                    &username,
-                                    auth_method,
-                                    password,
-                                    key_passphrase,
-                                )
-                                .await
-                                {
-                                    Ok((session, rx)) => Ok((
-                                        Arc::new(Mutex::new(session)),
-                                        Arc::new(Mutex::new(rx)),
-                                    )),
-                                    Err(e) => Err(e.to_string()),
-                                }
-                            },
-                            move |result| Message::SessionConnected(result, tab_index),
-                        );
+                    // Retries append to the existing log so earlier attempts stay visible.
+                    if saved_session.debug_log {
+                        let log = tab.debug_log.get_or_insert_with(crate::ssh::DebugLog::new);
+                        log.push("— retry —");
+                    } else {
+                        tab.debug_log = None;
                     }
+                    let debug_log = tab.debug_log.clone();
+                    let tab_id = tab.id;
+
+                    return Task::perform(
+                        sessions::connect_saved_session(
+                            &saved_session,
+                            &self.app_settings.host_domains,
+                            &defaults,
+                            debug_log,
+                            self.tab_credential_prompter(tab_id),
+                        ),
+                        move |result| Message::SessionConnected(result, tab_index),
+                    );
                 }
             }
-            Message::EditSessionConfig(tab_index) => {
-                // Switch to session manager and load the session for editing
-                if tab_index < self.tabs.len() {
-                    self.active_view = ActiveView::SessionManager;
-                    // TODO: Load the session config for editing
+            Message::ReconnectNow(_) | Message::CancelReconnect(_) => {
+                return reconnect::handle(self, message);
+            }
+            Message::OpenTerminalLocation(_) | Message::EditorOpened(_) => {
+                return editor::handle(self, message);
+            }
+            Message::SelectionAction(_) | Message::InsertSnippet(_) => {
+                return selection::handle(self, message);
+            }
+            Message::PlaceholderChanged(..)
+            | Message::SubmitPlaceholders
+            | Message::CancelPlaceholders => {
+                return placeholders::handle(self, message);
+            }
+            Message::OpenDeployKey(_)
+            | Message::DeployKeySelected(_)
+            | Message::DeployKey
+            | Message::DeployKeyFinished(_)
+            | Message::CloseDeployKey => {
+                return deploy_key::handle(self, message);
+            }
+            Message::ToggleFailureLog(tab_index) => {
+                if let Some(tab) = self.tabs.get_mut(tab_index) {
+                    tab.show_failure_log = !tab.show_failure_log;
                 }
             }
             Message::Ignore => {}
@@ -1107,16 +837,24 @@ fn load_local_entries(path: &str) -> Result<Vec<SftpEntry>, String> {
             size,
             modified,
             is_dir,
+            is_symlink: false,
+            link_target: None,
+            dir_size: None,
         });
     }
 
-    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+    entries.sort_by(compare_entries);
+
+    Ok(entries)
+}
+
+/// Directories first, then names case-insensitively.
+fn compare_entries(a: &SftpEntry, b: &SftpEntry) -> std::cmp::Ordering {
+    match (a.is_dir, b.is_dir) {
         (true, false) => std::cmp::Ordering::Less,
         (false, true) => std::cmp::Ordering::Greater,
         _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
-
-    Ok(entries)
+    }
 }
 
 fn expand_tilde(path: &str) -> String {
@@ -1157,106 +895,182 @@ fn join_remote_path(base: &str, name: &str) -> String {
     }
 }
 
+// Numbered names tried before giving up and overwriting the last one.
+const MAX_NUMBERED_COPIES: u32 = 100;
+
+fn numbered_remote_path(path: &str, copy: u32) -> String {
+    match path.rsplit_once('/') {
+        Some((dir, name)) => format!("{}/{}", dir, ConflictAction::numbered_name(name, copy)),
+        None => ConflictAction::numbered_name(path, copy),
+    }
+}
+
+fn numbered_local_path(path: &str, copy: u32) -> String {
+    let path = std::path::Path::new(path);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(ConflictAction::numbered_name(&name, copy))
+        .to_string_lossy()
+        .to_string()
+}
+
 fn start_remote_list(app: &mut App, tab_index: usize) -> Option<Task<Message>> {
-    if tab_index == 0 || tab_index >= app.tabs.len() {
+    // Entries of a directory the user already left are of no use.
+    let previous = app.sftp_state_for_tab_mut(tab_index)?.remote_listing.take();
+    if let Some(previous) = previous {
+        app.background_jobs.cancel(previous.job);
+        app.background_jobs.dismiss(previous.job);
+    }
+    let session = app
+        .tabs
+        .get(tab_index)
+        .filter(|_| tab_index != 0)
+        .and_then(|tab| tab.session.clone());
+    let Some(session) = session else {
         if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
             state.remote_entries.clear();
             state.remote_error = Some("No active SSH session".to_string());
-            state.remote_loading = false;
         }
         return None;
-    }
-
-    let tab = app.tabs.get(tab_index)?;
-    let session = match &tab.session {
-        Some(session) => session.clone(),
-        None => {
-            if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
-                state.remote_entries.clear();
-                state.remote_error = Some("No active SSH session".to_string());
-                state.remote_loading = false;
-            }
-            return None;
-        }
     };
 
-    let sftp_session = tab.sftp_session.clone();
-    let path = app
-        .sftp_state_for_tab(tab_index)
-        .map(|state| normalize_remote_path(&state.remote_path))
-        .unwrap_or_else(|| ".".to_string());
-    if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
-        state.remote_loading = true;
+    let channel = app.tabs[tab_index].sftp_list_channel.clone();
+    let state = app.sftp_state_for_tab_mut(tab_index)?;
+    let path = normalize_remote_path(&state.remote_path);
+    if let Some(cached) = state.cached_listing(&path).cloned() {
+        state.remote_entries = cached.entries;
+        state.remote_path = cached.remote_path;
         state.remote_error = None;
+        return Some(details::start(app, tab_index));
     }
-    Some(Task::perform(
-        async move { load_remote_entries(session, sftp_session, path).await },
-        move |result| Message::SftpRemoteLoaded(tab_index, result),
-    ))
+
+    let id = uuid::Uuid::new_v4();
+    let (task, handle) = Task::run(
+        remote_list_stream(session, channel, path.clone()),
+        move |event| Message::Sftp(SftpMessage::RemoteListing(tab_index, id, event)),
+    )
+    .abortable();
+    let (job, _) = app
+        .background_jobs
+        .start(JobOwner::RemoteListing(id), format!("List {}", path));
+    app.background_jobs.on_cancel(job, move || handle.abort());
+    let state = app.sftp_state_for_tab_mut(tab_index)?;
+    state.remote_listing = Some(RemoteListing { id, path, job });
+    state.remote_entries.clear();
+    state.remote_error = None;
+    Some(task)
 }
 
-async fn load_remote_entries(
-    session: crate::core::session::Session,
-    sftp_session: Arc<Mutex<Option<russh_sftp::client::SftpSession>>>,
+/// Lists a remote directory as the server returns it, so the first entries
+/// show up without waiting for the rest of a large directory.
+fn remote_list_stream(
+    session: Session,
+    channel: Arc<Mutex<Option<Arc<crate::ssh::TransferChannel>>>>,
     path: String,
-) -> Result<(Vec<SftpEntry>, Option<String>), String> {
-    use chrono::TimeZone;
+) -> impl iced::futures::Stream<Item = SftpListEvent> {
+    enum Step {
+        Open(
+            Session,
+            Arc<Mutex<Option<Arc<crate::ssh::TransferChannel>>>>,
+            String,
+        ),
+        Read(crate::ssh::DirListing),
+        Done,
+    }
 
-    let (dir_entries, resolved_path) = {
-        let mut guard = sftp_session.lock().await;
-        if guard.is_none() {
-            let ssh = match session.backend.as_ref() {
-                crate::core::backend::SessionBackend::Ssh { session, .. } => session.clone(),
-                _ => return Err("No SSH session".to_string()),
-            };
-            let mut ssh_guard = ssh.lock().await;
-            let created = ssh_guard
-                .open_sftp()
-                .await
-                .map_err(|e| format!("SFTP init failed: {}", e))?;
-            *guard = Some(created);
+    iced::futures::stream::unfold(Step::Open(session, channel, path), |step| async move {
+        match step {
+            Step::Open(session, channel, path) => {
+                match open_remote_dir(&session, &channel, &path).await {
+                    Ok((listing, resolved)) => {
+                        Some((SftpListEvent::Opened(resolved), Step::Read(listing)))
+                    }
+                    Err(err) => Some((SftpListEvent::Failed(err), Step::Done)),
+                }
+            }
+            Step::Read(mut listing) => match listing.next_batch().await {
+                Ok(Some(files)) => Some((
+                    SftpListEvent::Entries(remote_entries(files)),
+                    Step::Read(listing),
+                )),
+                Ok(None) => Some((SftpListEvent::Finished, Step::Done)),
+                Err(e) => Some((
+                    SftpListEvent::Failed(format!("Failed to read remote dir: {}", e)),
+                    Step::Done,
+                )),
+            },
+            Step::Done => None,
         }
-        let sftp = guard
-            .as_ref()
-            .ok_or_else(|| "SFTP not available".to_string())?;
-        let resolved = if path == "." || path.starts_with("./") {
-            sftp.canonicalize(".").await.ok()
-        } else {
-            None
-        };
-        let entries = sftp
-            .read_dir(path)
-            .await
-            .map_err(|e| format!("Failed to read remote dir: {}", e))?;
-        (entries, resolved)
-    };
+    })
+}
 
-    let mut entries = Vec::new();
-    for entry in dir_entries {
-        let meta = entry.metadata();
-        let is_dir = meta.is_dir();
-        if entry.file_name().starts_with('.') {
-            continue;
+/// The tab's listing channel, opened on first use.
+async fn list_channel(
+    session: &Session,
+    channel: &Mutex<Option<Arc<crate::ssh::TransferChannel>>>,
+) -> Result<Arc<crate::ssh::TransferChannel>, String> {
+    let mut guard = channel.lock().await;
+    match guard.as_ref() {
+        Some(sftp) => Ok(sftp.clone()),
+        None => {
+            let created = Arc::new(open_transfer_channel(session).await?);
+            *guard = Some(created.clone());
+            Ok(created)
         }
-        let size = if is_dir { None } else { meta.size };
-        let modified = meta
-            .mtime
-            .and_then(|t| chrono::Local.timestamp_opt(t as i64, 0).single());
-        entries.push(SftpEntry {
-            name: entry.file_name(),
-            size,
-            modified,
-            is_dir,
-        });
     }
+}
 
-    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
+async fn open_remote_dir(
+    session: &Session,
+    channel: &Mutex<Option<Arc<crate::ssh::TransferChannel>>>,
+    path: &str,
+) -> Result<(crate::ssh::DirListing, Option<String>), String> {
+    let sftp = list_channel(session, channel).await?;
+
+    let resolved = if path == "." || path.starts_with("./") {
+        sftp.canonicalize(".").await.ok()
+    } else {
+        None
+    };
+    let listing = match sftp.read_dir(path).await {
+        Ok(listing) => listing,
+        Err(e) => {
+            // A status reply (missing directory, permission denied) means the
+            // server answered; anything else means the channel is gone and
+            // the next listing should open a fresh one.
+            if !matches!(e, russh_sftp::client::error::Error::Status(_)) {
+                *channel.lock().await = None;
+            }
+            return Err(format!("Failed to read remote dir: {}", e));
+        }
+    };
+    Ok((listing, resolved))
+}
+
+fn remote_entries(files: Vec<russh_sftp::protocol::File>) -> Vec<SftpEntry> {
+    use chrono::TimeZone;
 
-    Ok((entries, resolved_path))
+    files
+        .into_iter()
+        .filter(|file| !file.filename.starts_with('.'))
+        .map(|file| {
+            let is_dir = file.attrs.is_dir();
+            SftpEntry {
+                size: if is_dir { None } else { file.attrs.size },
+                modified: file
+                    .attrs
+                    .mtime
+                    .and_then(|t| chrono::Local.timestamp_opt(t as i64, 0).single()),
+                name: file.filename,
+                is_dir,
+                is_symlink: file.attrs.is_symlink(),
+                link_target: None,
+                dir_size: None,
+            }
+        })
+        .collect()
 }
 
 fn normalize_remote_path(path: &str) -> String {
@@ -1294,26 +1108,13 @@ fn start_upload(app: &mut App, name: String) -> Option<Task<Message>> {
 
     let local_path = join_local_path(&state.local_path, &name);
     let remote_path = join_remote_path(&state.remote_path, &name);
-    let transfer_id = uuid::Uuid::new_v4();
-
-    state.transfers.push(SftpTransfer {
-        id: transfer_id,
+    state.transfers.push(queued_transfer(
         tab_index,
-        name: name.clone(),
-        direction: SftpTransferDirection::Upload,
-        status: SftpTransferStatus::Queued,
-        bytes_sent: 0,
-        bytes_total: 0,
-        local_path: local_path.clone(),
-        remote_path: remote_path.clone(),
-        started_at: None,
-        last_update: None,
-        last_bytes_sent: 0,
-        last_rate_bps: None,
-        cancel_flag: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
-        pause_flag: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
-        pause_notify: std::sync::Arc::new(tokio::sync::Notify::new()),
-    });
+        name,
+        SftpTransferDirection::Upload,
+        local_path,
+        remote_path,
+    ));
     state.remote_error = None;
 
     schedule_transfer_tasks(app, tab_index)
@@ -1321,19 +1122,20 @@ fn start_upload(app: &mut App, name: String) -> Option<Task<Message>> {
 
 async fn upload_local_file(
     session: crate::core::session::Session,
-    sftp_session: Arc<Mutex<Option<russh_sftp::client::SftpSession>>>,
+    window: usize,
+    defaults: TransferDefaults,
     local_path: String,
     remote_path: String,
     transfer_id: uuid::Uuid,
     tab_index: usize,
     tx: tokio::sync::mpsc::UnboundedSender<SftpTransferUpdate>,
-    cancel_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    cancel: CancelToken,
     pause_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
     pause_notify: std::sync::Arc<tokio::sync::Notify>,
 ) -> Result<(), String> {
     #[cfg(unix)]
     use std::os::unix::fs::PermissionsExt;
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
     let send_status = |status| {
         let _ = tx.send(SftpTransferUpdate {
@@ -1371,33 +1173,56 @@ async fn upload_local_file(
         status: Some(SftpTransferStatus::Uploading),
     });
 
-    let mut remote_file = {
-        let mut guard = sftp_session.lock().await;
-        if guard.is_none() {
-            let ssh = match session.backend.as_ref() {
-                crate::core::backend::SessionBackend::Ssh { session, .. } => session.clone(),
-                _ => return Err("No SSH session".to_string()),
+    let channel = open_transfer_channel(&session).await.inspect_err(|msg| {
+        send_status(SftpTransferStatus::Failed(msg.clone()));
+    })?;
+    let existing = channel
+        .stat(&remote_path)
+        .await
+        .ok()
+        .map(|attrs| attrs.size.unwrap_or(0));
+    let mut offset = 0;
+    let opened = match existing {
+        Some(size) if defaults.resume && size > 0 && size < total => {
+            offset = size;
+            channel.reopen(&remote_path).await
+        }
+        Some(_) if defaults.conflict == ConflictAction::Skip => {
+            send_status(SftpTransferStatus::Skipped);
+            return Ok(());
+        }
+        Some(_) if defaults.conflict == ConflictAction::Rename => {
+            let mut copy = 2;
+            let free_path = loop {
+                let candidate = numbered_remote_path(&remote_path, copy);
+                if copy >= MAX_NUMBERED_COPIES || channel.stat(&candidate).await.is_err() {
+                    break candidate;
+                }
+                copy += 1;
             };
-            let mut ssh_guard = ssh.lock().await;
-            let created = ssh_guard.open_sftp().await.map_err(|e| {
-                let msg = format!("SFTP init failed: {}", e);
+            channel.create(&free_path).await
+        }
+        _ => channel.create(&remote_path).await,
+    };
+    let remote_file = opened.map_err(|e| {
+        let msg = format!("Failed to open remote file: {}", e);
+        send_status(SftpTransferStatus::Failed(msg.clone()));
+        msg
+    })?;
+    if offset > 0 {
+        local_file
+            .seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(|e| {
+                let msg = format!("Failed to resume upload: {}", e);
                 send_status(SftpTransferStatus::Failed(msg.clone()));
                 msg
             })?;
-            *guard = Some(created);
-        }
-        let sftp = guard
-            .as_ref()
-            .ok_or_else(|| "SFTP not available".to_string())?;
-        sftp.create(remote_path).await.map_err(|e| {
-            let msg = format!("Failed to open remote file: {}", e);
-            send_status(SftpTransferStatus::Failed(msg.clone()));
-            msg
-        })?
-    };
+    }
 
-    let mut buffer = vec![0u8; 64 * 1024];
-    let mut sent: u64 = 0;
+    let mut writer = remote_file.writer(window, offset);
+    let mut buffer = vec![0u8; remote_file.chunk_size()];
+    let mut sent: u64 = offset;
     loop {
         while pause_flag.load(Ordering::SeqCst) {
             let _ = tx.send(SftpTransferUpdate {
@@ -1409,7 +1234,7 @@ async fn upload_local_file(
             });
             pause_notify.notified().await;
         }
-        if cancel_flag.load(Ordering::SeqCst) {
+        if cancel.is_cancelled() {
             let _ = tx.send(SftpTransferUpdate {
                 id: transfer_id,
                 tab_index,
@@ -1427,12 +1252,14 @@ async fn upload_local_file(
         if read == 0 {
             break;
         }
-        remote_file.write_all(&buffer[..read]).await.map_err(|e| {
+        writer.write(buffer[..read].to_vec()).await.map_err(|e| {
             let msg = format!("Upload failed: {}", e);
             send_status(SftpTransferStatus::Failed(msg.clone()));
             msg
         })?;
-        sent = sent.saturating_add(read as u64);
+        // Progress counts what the server has acknowledged, not what is
+        // still in flight.
+        sent = offset + writer.acknowledged();
         let _ = tx.send(SftpTransferUpdate {
             id: transfer_id,
             tab_index,
@@ -1441,11 +1268,15 @@ async fn upload_local_file(
             status: None,
         });
     }
-    let _ = remote_file.sync_all().await;
-    let _ = remote_file.shutdown().await;
+    writer.flush().await.map_err(|e| {
+        let msg = format!("Upload failed: {}", e);
+        send_status(SftpTransferStatus::Failed(msg.clone()));
+        msg
+    })?;
+    sent = offset + writer.acknowledged();
 
     #[cfg(unix)]
-    {
+    if defaults.preserve_permissions {
         let mode = metadata.permissions().mode();
         let attrs = russh_sftp::protocol::FileAttributes {
             size: None,
@@ -1461,6 +1292,9 @@ async fn upload_local_file(
             tracing::warn!("Failed to set remote permissions: {}", err);
         }
     }
+    if let Err(err) = remote_file.close().await {
+        tracing::warn!("Failed to close remote file: {}", err);
+    }
 
     let _ = tx.send(SftpTransferUpdate {
         id: transfer_id,
@@ -1473,8 +1307,14 @@ async fn upload_local_file(
     Ok(())
 }
 
-fn start_download(app: &mut App, name: String) -> Option<Task<Message>> {
-    let tab_index = app.active_tab;
+/// Queues a download of `name` from the remote pane, into `local_dir` when
+/// given and the pane's current local directory otherwise.
+fn start_download(
+    app: &mut App,
+    tab_index: usize,
+    name: String,
+    local_dir: Option<String>,
+) -> Option<Task<Message>> {
     if tab_index == 0 || tab_index >= app.tabs.len() {
         if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
             state.remote_error = Some("No active SSH session".to_string());
@@ -1495,31 +1335,113 @@ fn start_download(app: &mut App, name: String) -> Option<Task<Message>> {
         return None;
     }
 
-    let local_path = join_local_path(&state.local_path, &name);
+    let local_dir = local_dir.unwrap_or_else(|| state.local_path.clone());
+    let local_path = join_local_path(&local_dir, &name);
     let remote_path = join_remote_path(&state.remote_path, &name);
-    let transfer_id = uuid::Uuid::new_v4();
+    state.transfers.push(queued_transfer(
+        tab_index,
+        name,
+        SftpTransferDirection::Download,
+        local_path,
+        remote_path,
+    ));
+    state.remote_error = None;
 
-    state.transfers.push(SftpTransfer {
-        id: transfer_id,
+    schedule_transfer_tasks(app, tab_index)
+}
+
+/// Queues a download of a remote file named by its full path, for callers
+/// outside the SFTP panel.
+fn start_download_path(
+    app: &mut App,
+    tab_index: usize,
+    remote_path: String,
+    local_dir: Option<String>,
+) -> Option<Task<Message>> {
+    if tab_index == 0 || tab_index >= app.tabs.len() {
+        return None;
+    }
+    let name = remote_path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())?
+        .to_string();
+    let state = app.sftp_state_for_tab_mut(tab_index)?;
+    let local_dir = local_dir.unwrap_or_else(|| state.local_path.clone());
+    let local_path = join_local_path(&local_dir, &name);
+    state.transfers.push(queued_transfer(
+        tab_index,
+        name,
+        SftpTransferDirection::Download,
+        local_path,
+        remote_path,
+    ));
+    state.remote_error = None;
+
+    schedule_transfer_tasks(app, tab_index)
+}
+
+/// Queues uploads of files picked outside the local pane into `remote_dir`,
+/// or the current remote directory.
+fn start_upload_files(
+    app: &mut App,
+    tab_index: usize,
+    files: Vec<std::path::PathBuf>,
+    remote_dir: Option<String>,
+) -> Option<Task<Message>> {
+    if files.is_empty() || tab_index == 0 || tab_index >= app.tabs.len() {
+        return None;
+    }
+    let state = app.sftp_state_for_tab_mut(tab_index)?;
+    let remote_dir = remote_dir.unwrap_or_else(|| state.remote_path.clone());
+    for file in files {
+        let Some(name) = file
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        let remote_path = join_remote_path(&remote_dir, &name);
+        state.transfers.push(queued_transfer(
+            tab_index,
+            name,
+            SftpTransferDirection::Upload,
+            file.to_string_lossy().into_owned(),
+            remote_path,
+        ));
+    }
+    state.remote_error = None;
+
+    schedule_transfer_tasks(app, tab_index)
+}
+
+fn queued_transfer(
+    tab_index: usize,
+    name: String,
+    direction: SftpTransferDirection,
+    local_path: String,
+    remote_path: String,
+) -> SftpTransfer {
+    SftpTransfer {
+        id: uuid::Uuid::new_v4(),
         tab_index,
-        name: name.clone(),
-        direction: SftpTransferDirection::Download,
+        name,
+        direction,
         status: SftpTransferStatus::Queued,
         bytes_sent: 0,
         bytes_total: 0,
-        local_path: local_path.clone(),
-        remote_path: remote_path.clone(),
+        local_path,
+        remote_path,
         started_at: None,
         last_update: None,
         last_bytes_sent: 0,
         last_rate_bps: None,
-        cancel_flag: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        job: None,
+        cancel: CancelToken::default(),
         pause_flag: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
         pause_notify: std::sync::Arc::new(tokio::sync::Notify::new()),
-    });
-    state.remote_error = None;
-
-    schedule_transfer_tasks(app, tab_index)
+    }
 }
 
 fn start_rename(app: &mut App) -> Option<Task<Message>> {
@@ -1550,7 +1472,7 @@ fn start_rename(app: &mut App) -> Option<Task<Message>> {
                         .await
                         .map_err(|e| format!("Rename failed: {}", e))
                 },
-                move |result| Message::SftpRenameFinished(tab_index, result),
+                move |result| Message::Sftp(SftpMessage::RenameFinished(tab_index, result)),
             ))
         }
         SftpPane::Remote => {
@@ -1586,7 +1508,7 @@ fn start_rename(app: &mut App) -> Option<Task<Message>> {
                         .await
                         .map_err(|e| format!("Rename failed: {}", e))
                 },
-                move |result| Message::SftpRenameFinished(tab_index, result),
+                move |result| Message::Sftp(SftpMessage::RenameFinished(tab_index, result)),
             ))
         }
     }
@@ -1614,17 +1536,20 @@ fn start_delete(app: &mut App) -> Option<Task<Message>> {
                             .map_err(|e| format!("Delete failed: {}", e))
                     }
                 },
-                move |result| Message::SftpDeleteFinished(tab_index, result),
+                move |result| Message::Sftp(SftpMessage::DeleteFinished(tab_index, result)),
             ))
         }
         SftpPane::Remote => {
+            let path = join_remote_path(&remote_path, &target.name);
+            if app.app_settings.sftp_remote_trash {
+                return trash::move_to_trash(app, tab_index, path, target.name);
+            }
             let tab = app.tabs.get(tab_index)?;
             let session = match &tab.session {
                 Some(session) => session.clone(),
                 None => return None,
             };
             let sftp_session = tab.sftp_session.clone();
-            let path = join_remote_path(&remote_path, &target.name);
             Some(Task::perform(
                 async move {
                     let mut guard = sftp_session.lock().await;
@@ -1655,33 +1580,59 @@ fn start_delete(app: &mut App) -> Option<Task<Message>> {
                             .map_err(|e| format!("Delete failed: {}", e))
                     }
                 },
-                move |result| Message::SftpDeleteFinished(tab_index, result),
+                move |result| Message::Sftp(SftpMessage::DeleteFinished(tab_index, result)),
             ))
         }
     }
 }
 
+/// Stops a queued, running or paused transfer and starts whatever was
+/// waiting behind it.
+fn cancel_transfer(app: &mut App, tab_index: usize, id: uuid::Uuid) -> Option<Task<Message>> {
+    let state = app.sftp_state_for_tab_mut(tab_index)?;
+    let mut job = None;
+    if let Some(transfer) = state
+        .transfers
+        .iter_mut()
+        .find(|transfer| transfer.id == id)
+    {
+        transfer.cancel.cancel();
+        transfer.pause_flag.store(false, Ordering::SeqCst);
+        transfer.pause_notify.notify_waiters();
+        if matches!(
+            transfer.status,
+            SftpTransferStatus::Queued | SftpTransferStatus::Uploading
+        ) {
+            transfer.status = SftpTransferStatus::Canceled;
+        }
+        job = transfer.job;
+    }
+    if let Some(job) = job {
+        app.background_jobs.cancel(job);
+    }
+    schedule_transfer_tasks(app, tab_index)
+}
+
 fn schedule_transfer_tasks(app: &mut App, tab_index: usize) -> Option<Task<Message>> {
-    let max_concurrent = app.sftp_max_concurrent.max(1);
+    let limits = app.transfer_limits_for_tab(tab_index);
+    let defaults = app.transfer_defaults_for_tab(tab_index);
+    let window = app.app_settings.sftp_pipeline_depth;
     let tx = app.sftp_transfer_tx.clone();
     let mut tasks = Vec::new();
 
     loop {
-        let (transfer, transfer_index) = {
+        let (mut transfer, transfer_index) = {
             let state = app.sftp_state_for_tab_mut(tab_index)?;
-            let active = state
-                .transfers
-                .iter()
-                .filter(|transfer| transfer.status == SftpTransferStatus::Uploading)
-                .count();
-            if active >= max_concurrent {
-                break;
-            }
-            let Some(index) = state
-                .transfers
-                .iter()
-                .position(|transfer| transfer.status == SftpTransferStatus::Queued)
-            else {
+            let (uploads, downloads) = state.running_transfers();
+            // A full upload lane must not hold back queued downloads.
+            let Some(index) = state.transfers.iter().position(|transfer| {
+                transfer.status == SftpTransferStatus::Queued
+                    && limits.allows(
+                        transfer.direction == SftpTransferDirection::Upload,
+                        uploads,
+                        downloads,
+                    )
+            }) else {
                 break;
             };
             let transfer = state.transfers[index].clone();
@@ -1714,10 +1665,25 @@ fn schedule_transfer_tasks(app: &mut App, tab_index: usize) -> Option<Task<Messa
             }
         };
 
-        let sftp_session = tab.sftp_session.clone();
+        let label = match transfer.direction {
+            SftpTransferDirection::Upload => format!("Upload {}", transfer.name),
+            SftpTransferDirection::Download => format!("Download {}", transfer.name),
+        };
+        let (job, cancel) = app
+            .background_jobs
+            .start(JobOwner::Transfer(transfer.id), label);
+        transfer.cancel = cancel.clone();
+        if let Some(entry) = app
+            .sftp_state_for_tab_mut(tab_index)
+            .and_then(|state| state.transfers.get_mut(transfer_index))
+        {
+            entry.job = Some(job);
+            entry.cancel = cancel;
+        }
+
         let tx = tx.clone();
         tasks.push(Task::perform(
-            async move { run_transfer(session, sftp_session, transfer, tx).await },
+            async move { run_transfer(session, window, defaults, transfer, tx).await },
             |_| Message::Ignore,
         ));
     }
@@ -1731,17 +1697,20 @@ fn schedule_transfer_tasks(app: &mut App, tab_index: usize) -> Option<Task<Messa
 
 async fn download_remote_file(
     session: crate::core::session::Session,
-    sftp_session: Arc<Mutex<Option<russh_sftp::client::SftpSession>>>,
+    window: usize,
+    defaults: TransferDefaults,
     remote_path: String,
     local_path: String,
     transfer_id: uuid::Uuid,
     tab_index: usize,
     tx: tokio::sync::mpsc::UnboundedSender<SftpTransferUpdate>,
-    cancel_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    cancel: CancelToken,
     pause_flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
     pause_notify: std::sync::Arc<tokio::sync::Notify>,
 ) -> Result<(), String> {
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 
     let send_status = |status| {
         let _ = tx.send(SftpTransferUpdate {
@@ -1753,30 +1722,14 @@ async fn download_remote_file(
         });
     };
 
-    let mut remote_file = {
-        let mut guard = sftp_session.lock().await;
-        if guard.is_none() {
-            let ssh = match session.backend.as_ref() {
-                crate::core::backend::SessionBackend::Ssh { session, .. } => session.clone(),
-                _ => return Err("No SSH session".to_string()),
-            };
-            let mut ssh_guard = ssh.lock().await;
-            let created = ssh_guard.open_sftp().await.map_err(|e| {
-                let msg = format!("SFTP init failed: {}", e);
-                send_status(SftpTransferStatus::Failed(msg.clone()));
-                msg
-            })?;
-            *guard = Some(created);
-        }
-        let sftp = guard
-            .as_ref()
-            .ok_or_else(|| "SFTP not available".to_string())?;
-        sftp.open(&remote_path).await.map_err(|e| {
-            let msg = format!("Failed to open remote file: {}", e);
-            send_status(SftpTransferStatus::Failed(msg.clone()));
-            msg
-        })?
-    };
+    let channel = open_transfer_channel(&session).await.inspect_err(|msg| {
+        send_status(SftpTransferStatus::Failed(msg.clone()));
+    })?;
+    let remote_file = channel.open(&remote_path).await.map_err(|e| {
+        let msg = format!("Failed to open remote file: {}", e);
+        send_status(SftpTransferStatus::Failed(msg.clone()));
+        msg
+    })?;
 
     let metadata = remote_file.metadata().await.map_err(|e| {
         let msg = format!("Failed to stat remote file: {}", e);
@@ -1799,14 +1752,53 @@ async fn download_remote_file(
         status: Some(SftpTransferStatus::Uploading), // Reusing 'Uploading' state for running
     });
 
-    let mut local_file = tokio::fs::File::create(&local_path).await.map_err(|e| {
+    let existing = tokio::fs::metadata(&local_path)
+        .await
+        .ok()
+        .map(|metadata| metadata.len());
+    let mut local_path = local_path;
+    let mut offset = 0;
+    let opened = match existing {
+        Some(size) if defaults.resume && size > 0 && size < total => {
+            offset = size;
+            match tokio::fs::OpenOptions::new()
+                .write(true)
+                .open(&local_path)
+                .await
+            {
+                Ok(mut file) => file
+                    .seek(std::io::SeekFrom::Start(offset))
+                    .await
+                    .map(|_| file),
+                Err(e) => Err(e),
+            }
+        }
+        Some(_) if defaults.conflict == ConflictAction::Skip => {
+            send_status(SftpTransferStatus::Skipped);
+            return Ok(());
+        }
+        Some(_) if defaults.conflict == ConflictAction::Rename => {
+            let mut copy = 2;
+            local_path = loop {
+                let candidate = numbered_local_path(&local_path, copy);
+                let taken = tokio::fs::try_exists(&candidate).await.unwrap_or(false);
+                if copy >= MAX_NUMBERED_COPIES || !taken {
+                    break candidate;
+                }
+                copy += 1;
+            };
+            tokio::fs::File::create(&local_path).await
+        }
+        _ => tokio::fs::File::create(&local_path).await,
+    };
+    let mut local_file = opened.map_err(|e| {
         let msg = format!("Failed to create local file: {}", e);
         send_status(SftpTransferStatus::Failed(msg.clone()));
         msg
     })?;
 
-    let mut buffer = vec![0u8; 64 * 1024]; // 64KB buffer
-    let mut sent: u64 = 0;
+    let mut reader = remote_file.reader(window, offset);
+    let mut sent: u64 = offset;
 
     loop {
         while pause_flag.load(Ordering::SeqCst) {
@@ -1819,7 +1811,7 @@ async fn download_remote_file(
             });
             pause_notify.notified().await;
         }
-        if cancel_flag.load(Ordering::SeqCst) {
+        if cancel.is_cancelled() {
             let _ = tx.send(SftpTransferUpdate {
                 id: transfer_id,
                 tab_index,
@@ -1830,23 +1822,23 @@ async fn download_remote_file(
             return Ok(());
         }
 
-        let read = remote_file.read(&mut buffer).await.map_err(|e| {
+        let chunk = reader.next_chunk().await.map_err(|e| {
             let msg = format!("Download failed: {}", e);
             send_status(SftpTransferStatus::Failed(msg.clone()));
             msg
         })?;
 
-        if read == 0 {
+        let Some(chunk) = chunk else {
             break;
-        }
+        };
 
-        local_file.write_all(&buffer[..read]).await.map_err(|e| {
+        local_file.write_all(&chunk).await.map_err(|e| {
             let msg = format!("Download failed: {}", e);
             send_status(SftpTransferStatus::Failed(msg.clone()));
             msg
         })?;
 
-        sent = sent.saturating_add(read as u64);
+        sent = sent.saturating_add(chunk.len() as u64);
         let _ = tx.send(SftpTransferUpdate {
             id: transfer_id,
             tab_index,
@@ -1857,6 +1849,19 @@ async fn download_remote_file(
     }
 
     let _ = local_file.sync_all().await;
+    #[cfg(unix)]
+    if let Some(mode) = metadata
+        .permissions
+        .filter(|_| defaults.preserve_permissions)
+    {
+        let permissions = std::fs::Permissions::from_mode(mode & 0o7777);
+        if let Err(err) = tokio::fs::set_permissions(&local_path, permissions).await {
+            tracing::warn!("Failed to set local permissions: {}", err);
+        }
+    }
+    if let Err(err) = remote_file.close().await {
+        tracing::warn!("Failed to close remote file: {}", err);
+    }
 
     let _ = tx.send(SftpTransferUpdate {
         id: transfer_id,
@@ -1869,6 +1874,45 @@ async fn download_remote_file(
     Ok(())
 }
 
+fn reveal_remote_path(app: &mut App, path: String) -> Task<Message> {
+    let tab_index = app.active_tab;
+    let is_ssh = app
+        .tabs
+        .get(tab_index)
+        .map(|tab| tab_index != 0 && tab.ssh_handle.is_some())
+        .unwrap_or(false);
+    if !is_ssh {
+        return Task::none();
+    }
+
+    // A trailing slash means the path itself is a directory; otherwise open the
+    // parent directory and select the entry so it can be downloaded from there.
+    let (dir, name) = if path.len() > 1 && path.ends_with('/') {
+        (path.trim_end_matches('/').to_string(), None)
+    } else {
+        match path.rsplit_once('/') {
+            Some(("", name)) => ("/".to_string(), Some(name.to_string())),
+            Some((dir, name)) => (dir.to_string(), Some(name.to_string())),
+            None => return Task::none(),
+        }
+    };
+
+    if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+        state.remote_path = dir;
+        state.context_menu = None;
+    }
+    let task = if app.sftp_panel_open {
+        start_remote_list(app, tab_index).unwrap_or_else(Task::none)
+    } else {
+        app.update(Message::ToggleSftpPanel)
+    };
+    if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+        state.remote_selected = name;
+        state.remote_last_click = None;
+    }
+    task
+}
+
 fn handle_local_click(app: &mut App, name: String, is_dir: bool) -> Task<Message> {
     let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) else {
         return Task::none();
@@ -1942,9 +1986,24 @@ fn handle_remote_click(app: &mut App, name: String, is_dir: bool) -> Task<Messag
     Task::none()
 }
 
+async fn open_transfer_channel(
+    session: &crate::core::session::Session,
+) -> Result<crate::ssh::TransferChannel, String> {
+    let ssh = match session.backend.as_ref() {
+        crate::core::backend::SessionBackend::Ssh { session, .. } => session.clone(),
+        _ => return Err("No SSH session".to_string()),
+    };
+    let guard = ssh.lock().await;
+    guard
+        .open_transfer_channel()
+        .await
+        .map_err(|e| format!("SFTP init failed: {}", e))
+}
+
 async fn run_transfer(
     session: crate::core::session::Session,
-    sftp_session: Arc<Mutex<Option<russh_sftp::client::SftpSession>>>,
+    window: usize,
+    defaults: TransferDefaults,
     transfer: SftpTransfer,
     tx: tokio::sync::mpsc::UnboundedSender<SftpTransferUpdate>,
 ) -> Result<(), String> {
@@ -1952,13 +2011,14 @@ async fn run_transfer(
         SftpTransferDirection::Upload => {
             upload_local_file(
                 session,
-                sftp_session,
+                window,
+                defaults,
                 transfer.local_path,
                 transfer.remote_path,
                 transfer.id,
                 transfer.tab_index,
                 tx,
-                transfer.cancel_flag,
+                transfer.cancel,
                 transfer.pause_flag,
                 transfer.pause_notify,
             )
@@ -1967,13 +2027,14 @@ async fn run_transfer(
         SftpTransferDirection::Download => {
             download_remote_file(
                 session,
-                sftp_session,
+                window,
+                defaults,
                 transfer.remote_path,
                 transfer.local_path,
                 transfer.id,
                 transfer.tab_index,
                 tx,
-                transfer.cancel_flag,
+                transfer.cancel,
                 transfer.pause_flag,
                 transfer.pause_notify,
             )
@@ -1981,3 +2042,86 @@ async fn run_transfer(
         }
     }
 }
+
+/// Removes tab `index` and fixes up everything that refers to tabs by
+/// position.
+fn close_tab(app: &mut App, index: usize) -> Task<Message> {
+    if index == 0 || index >= app.tabs.len() {
+        return Task::none();
+    }
+    let close_channel = subshell::close_channel(&app.tabs[index]);
+    app.tabs.remove(index);
+    if app.debug_log_tab == Some(index) {
+        app.debug_log_tab = None;
+    } else if let Some(debug_tab) = app.debug_log_tab.as_mut()
+        && *debug_tab > index
+    {
+        *debug_tab -= 1;
+    }
+    if let Some(toast) = app.disconnect_toast.as_mut() {
+        if toast.tab_index == index {
+            app.disconnect_toast = None;
+        } else if toast.tab_index > index {
+            toast.tab_index -= 1;
+        }
+    }
+    let mut active_keys = HashSet::new();
+    for tab in &app.tabs {
+        if let Some(key) = &tab.sftp_key {
+            active_keys.insert(key.clone());
+        }
+    }
+    app.sftp_states.retain(|key, _| active_keys.contains(key));
+    if app.active_tab >= app.tabs.len() && app.active_tab > 0 {
+        app.active_tab -= 1;
+    }
+    if app.last_terminal_tab == index {
+        app.last_terminal_tab = app.active_tab;
+    } else if app.last_terminal_tab > index {
+        app.last_terminal_tab -= 1;
+    }
+    if app.active_tab == 0 {
+        app.active_view = ActiveView::SessionManager;
+    } else {
+        app.active_view = ActiveView::Terminal;
+    }
+    close_channel
+}
+
+/// Sends the emulator's replies to queries (like CPR) to the tab's shell.
+/// A reconnect needs a fresh channel since the previous writer thread owns
+/// the old one.
+pub(super) fn forward_terminal_responses(tab: &mut SessionTab) {
+    let mut output_rx = tab
+        .emulator
+        .take_output_receiver()
+        .unwrap_or_else(|| tab.emulator.reattach_output_receiver());
+    if let Some(session) = &tab.session {
+        let session_clone = session.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                while let Some(data) = output_rx.recv().await {
+                    // Add timeout to prevent hanging if connection is dead
+                    let write_future = session_clone.write(&data);
+                    match tokio::time::timeout(std::time::Duration::from_millis(1000), write_future)
+                        .await
+                    {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => {
+                            tracing::warn!("write terminal response failed: {}", e);
+                            break;
+                        }
+                        Err(_) => {
+                            tracing::warn!(
+                                "write terminal response timeout - connection might be dead"
+                            );
+                            // We don't break here immediately, hoping it's temporary?
+                            // Or we should? If TCP is stuck, it's stuck.
+                        }
+                    }
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,81 @@
+use iced::Task;
+use std::collections::HashMap;
+
+use crate::session::placeholder;
+use crate::ui::App;
+use crate::ui::message::{Message, TerminalMessage};
+use crate::ui::state::{PlaceholderPrompt, PlaceholderTarget};
+
+/// Asks for the placeholders in `text` before it is used, or returns
+/// `None` when it has none.
+pub(super) fn prompt(
+    app: &mut App,
+    title: &str,
+    text: &str,
+    target: PlaceholderTarget,
+) -> Option<Task<Message>> {
+    let placeholders = placeholder::parse(text);
+    if placeholders.is_empty() {
+        return None;
+    }
+    app.placeholder_prompt = Some(PlaceholderPrompt {
+        title: title.to_string(),
+        text: text.to_string(),
+        values: placeholders
+            .iter()
+            .map(|placeholder| placeholder.default.clone())
+            .collect(),
+        placeholders,
+        target,
+    });
+    Some(iced::widget::operation::focus(
+        crate::ui::views::placeholder_prompt::first_input(),
+    ))
+}
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::PlaceholderChanged(index, value) => {
+            if let Some(value_slot) = app
+                .placeholder_prompt
+                .as_mut()
+                .and_then(|prompt| prompt.values.get_mut(index))
+            {
+                *value_slot = value;
+            }
+            Task::none()
+        }
+        Message::SubmitPlaceholders => {
+            let Some(prompt) = app.placeholder_prompt.take() else {
+                return Task::none();
+            };
+            let values: HashMap<String, String> = prompt
+                .placeholders
+                .into_iter()
+                .map(|placeholder| placeholder.name)
+                .zip(prompt.values)
+                .collect();
+            let text = placeholder::fill(&prompt.text, &values);
+            match prompt.target {
+                PlaceholderTarget::Snippet => {
+                    let data = app.bracketed_paste_bytes(&text);
+                    Task::batch([
+                        Task::done(Message::Terminal(TerminalMessage::Input(data))),
+                        app.focus_terminal_ime(),
+                    ])
+                }
+                PlaceholderTarget::RemoteCommand(id) => {
+                    super::sessions::open_saved_session(app, &id, Some(text))
+                }
+            }
+        }
+        Message::CancelPlaceholders => {
+            let target = app.placeholder_prompt.take().map(|prompt| prompt.target);
+            if target == Some(PlaceholderTarget::Snippet) {
+                return app.focus_terminal_ime();
+            }
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
@@ -0,0 +1,163 @@
+use iced::Task;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use crate::session::config::{PortForwardDirection, PortForwardRule};
+use crate::ssh::shell_quote;
+use crate::ui::App;
+use crate::ui::message::Message;
+use crate::ui::state::{PortCheckResult, PortForwardCheck};
+
+type SshHandle = Arc<Mutex<crate::ssh::SshSession>>;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+// The remote probe gets its own 3 seconds on the server plus the round trip.
+const REMOTE_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::CheckPortForward(rule_id) => {
+            let Some(tab) = app.tabs.get(app.active_tab) else {
+                return Task::none();
+            };
+            let (Some(ssh_handle), Some(session_id)) = (tab.ssh_handle.clone(), &tab.sftp_key)
+            else {
+                return Task::none();
+            };
+            let Some(rule) = app
+                .saved_sessions
+                .iter()
+                .find(|session| &session.id == session_id)
+                .and_then(|session| session.port_forwards.iter().find(|r| r.id == rule_id))
+                .cloned()
+            else {
+                return Task::none();
+            };
+            app.port_forward_checks
+                .insert(rule_id.clone(), PortForwardCheck::Running);
+            Task::perform(
+                async move {
+                    let (local_host, remote_host) = hosts(&rule);
+                    match rule.direction {
+                        PortForwardDirection::Local => vec![
+                            listener(&local_host, rule.local_port, "Local listener").await,
+                            remote_target(&ssh_handle, &remote_host, rule.remote_port).await,
+                        ],
+                        PortForwardDirection::Remote => vec![
+                            remote_listener(&ssh_handle, &remote_host, rule.remote_port).await,
+                            local_target(&local_host, rule.local_port).await,
+                        ],
+                        PortForwardDirection::Dynamic => {
+                            vec![listener(&local_host, rule.local_port, "SOCKS listener").await]
+                        }
+                    }
+                },
+                move |results| Message::PortForwardChecked(rule_id.clone(), results),
+            )
+        }
+        Message::PortForwardChecked(rule_id, results) => {
+            app.port_forward_checks
+                .insert(rule_id, PortForwardCheck::Finished(results));
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+// Empty hosts mean loopback, as in the forwards list. A wildcard bind is
+// checked through loopback too.
+fn hosts(rule: &PortForwardRule) -> (String, String) {
+    let host = |value: &str| match value.trim() {
+        "" | "0.0.0.0" | "*" => "127.0.0.1".to_string(),
+        "::" => "::1".to_string(),
+        other => other.to_string(),
+    };
+    (host(&rule.local_host), host(&rule.remote_host))
+}
+
+async fn connect_locally(host: &str, port: u16) -> Option<String> {
+    match tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        tokio::net::TcpStream::connect((host, port)),
+    )
+    .await
+    {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some("timed out".to_string()),
+    }
+}
+
+async fn listener(host: &str, port: u16, what: &str) -> PortCheckResult {
+    PortCheckResult {
+        label: format!("{} on {}:{}", what, host, port),
+        error: connect_locally(host, port).await,
+    }
+}
+
+async fn local_target(host: &str, port: u16) -> PortCheckResult {
+    PortCheckResult {
+        label: format!("Target {}:{} from this machine", host, port),
+        error: connect_locally(host, port).await,
+    }
+}
+
+async fn remote_target(ssh_handle: &SshHandle, host: &str, port: u16) -> PortCheckResult {
+    PortCheckResult {
+        label: format!("Target {}:{} from the server", host, port),
+        error: connect_remotely(ssh_handle, host, port).await,
+    }
+}
+
+async fn remote_listener(ssh_handle: &SshHandle, host: &str, port: u16) -> PortCheckResult {
+    PortCheckResult {
+        label: format!("Remote listener on {}:{}", host, port),
+        error: connect_remotely(ssh_handle, host, port).await,
+    }
+}
+
+/// Opens a TCP connection on the server with `nc -z`, or bash's /dev/tcp
+/// where nc is missing, and reports the exit status.
+async fn connect_remotely(ssh_handle: &SshHandle, host: &str, port: u16) -> Option<String> {
+    let host = shell_quote(host);
+    let script = format!(
+        r#"if command -v nc >/dev/null 2>&1; then
+  nc -z -w 3 {host} {port} >/dev/null 2>&1; echo "exit=$?"
+elif command -v bash >/dev/null 2>&1; then
+  timeout 3 bash -c 'exec 3<>/dev/tcp/$0/$1' {host} {port} >/dev/null 2>&1; echo "exit=$?"
+else
+  echo "exit=127"
+fi
+"#
+    );
+    let command = format!("sh -c {}", shell_quote(&script));
+    let run = async {
+        let mut rx = ssh_handle
+            .lock()
+            .await
+            .exec_stream(&command)
+            .await
+            .map_err(|e| format!("Failed to run check on the server: {}", e))?;
+        let mut output = Vec::new();
+        while let Some(data) = rx.recv().await {
+            output.extend_from_slice(&data);
+        }
+        Ok::<_, String>(String::from_utf8_lossy(&output).into_owned())
+    };
+    let output = match tokio::time::timeout(REMOTE_CHECK_TIMEOUT, run).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Some(e),
+        Err(_) => return Some("timed out".to_string()),
+    };
+    match output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("exit="))
+    {
+        Some("0") => None,
+        Some("127") => Some("neither nc nor bash with timeout is available on the server".into()),
+        Some("124") => Some("timed out".to_string()),
+        Some(_) => Some("connection failed".to_string()),
+        None => Some("no result from the server".to_string()),
+    }
+}
@@ -0,0 +1,91 @@
+use iced::Task;
+use std::time::{Duration, Instant};
+
+use crate::session::SessionConfig;
+use crate::session::expand::DomainRule;
+use crate::ui::App;
+use crate::ui::message::{ActiveView, Message};
+use crate::ui::state::{HostProbe, HostStatus};
+
+/// A host's dot is trusted for this long before it is checked again.
+const PROBE_TTL: Duration = Duration::from_secs(120);
+// Started per round, so a long session list is probed a few at a time.
+const MAX_PROBES_PER_ROUND: usize = 8;
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::ProbeSessions => {
+            if !app.app_settings.probe_sessions {
+                app.host_probes.clear();
+                return Task::none();
+            }
+            if app.active_view != ActiveView::SessionManager {
+                return Task::none();
+            }
+            let mut tasks = Vec::new();
+            for session in &app.saved_sessions {
+                if tasks.len() == MAX_PROBES_PER_ROUND {
+                    break;
+                }
+                let Some((host, port)) = probe_target(session, &app.app_settings.host_domains)
+                else {
+                    app.host_probes.remove(&session.id);
+                    continue;
+                };
+                let target = format!("{}:{}", host, port);
+                let fresh = app.host_probes.get(&session.id).is_some_and(|probe| {
+                    probe.target == target
+                        && (probe.status == HostStatus::Checking
+                            || probe.checked.elapsed() < PROBE_TTL)
+                });
+                if fresh {
+                    continue;
+                }
+                app.host_probes.insert(
+                    session.id.clone(),
+                    HostProbe {
+                        target,
+                        status: HostStatus::Checking,
+                        checked: Instant::now(),
+                    },
+                );
+                let id = session.id.clone();
+                let proxy = session.proxy_or(app.app_settings.proxy.as_ref());
+                tasks.push(Task::perform(
+                    async move {
+                        crate::ssh::probe(&host, port, proxy.as_ref())
+                            .await
+                            .map_err(|e| format!("{:#}", e))
+                    },
+                    move |result| Message::SessionProbed(id.clone(), result),
+                ));
+            }
+            Task::batch(tasks)
+        }
+        Message::SessionProbed(id, result) => {
+            if let Some(probe) = app.host_probes.get_mut(&id) {
+                probe.status = match result {
+                    Ok(latency) => HostStatus::Reachable(latency),
+                    Err(e) => HostStatus::Unreachable(e),
+                };
+                probe.checked = Instant::now();
+            }
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Where a session's first hop answers: its jump host when it has one.
+/// Sessions behind port knocking are left alone, since probing them
+/// without knocking would only ever fail.
+fn probe_target(session: &SessionConfig, domains: &[DomainRule]) -> Option<(String, u16)> {
+    if !session.port_knock.is_empty() {
+        return None;
+    }
+    let session = crate::session::expand::resolve(session, domains).ok()?;
+    match session.jump_host {
+        Some(jump) => Some((jump.host, jump.port)),
+        None => Some((session.host, session.port)),
+    }
+}
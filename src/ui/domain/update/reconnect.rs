@@ -0,0 +1,109 @@
+use iced::Task;
+use std::time::{Duration, Instant};
+
+use crate::ui::App;
+use crate::ui::message::Message;
+use crate::ui::state::{AutoReconnect, SessionState};
+
+// Roughly three minutes of trying before the tab is left to the user.
+const MAX_ATTEMPTS: u32 = 8;
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::ReconnectNow(tab_index) => {
+            if app
+                .tabs
+                .get(tab_index)
+                .is_some_and(|tab| tab.auto_reconnect.is_some())
+            {
+                return Task::done(Message::RetryConnection(tab_index));
+            }
+            Task::none()
+        }
+        Message::CancelReconnect(tab_index) => {
+            if let Some(tab) = app.tabs.get_mut(tab_index)
+                && tab.auto_reconnect.take().is_some()
+            {
+                tab.write_notice("— reconnect cancelled —");
+            }
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Starts retrying a saved session whose connection dropped.
+pub(in crate::ui) fn schedule(app: &mut App, tab_index: usize) {
+    if !app.app_settings.auto_reconnect {
+        return;
+    }
+    if let Some(tab) = app.tabs.get_mut(tab_index)
+        && tab.sftp_key.is_some()
+    {
+        tab.auto_reconnect = Some(AutoReconnect {
+            attempt: 0,
+            next: Some(Instant::now() + delay(0)),
+            last_error: None,
+        });
+    }
+}
+
+/// Retries tabs whose wait is over, and schedules the next attempt for
+/// ones whose last attempt failed.
+pub(in crate::ui) fn tick(app: &mut App) -> Option<Task<Message>> {
+    let now = Instant::now();
+    let mut tasks = Vec::new();
+    for (index, tab) in app.tabs.iter_mut().enumerate() {
+        let Some(reconnect) = tab.auto_reconnect.as_mut() else {
+            continue;
+        };
+        match (&tab.state, reconnect.next) {
+            (SessionState::Connecting(_) | SessionState::Connected, _) => {}
+            (_, Some(at)) if at <= now => {
+                reconnect.next = None;
+                tasks.push(Task::done(Message::RetryConnection(index)));
+            }
+            (_, Some(_)) => {}
+            (state, None) => {
+                if let SessionState::Failed(error) = state {
+                    reconnect.last_error = Some(error.clone());
+                }
+                reconnect.attempt += 1;
+                if reconnect.attempt < MAX_ATTEMPTS {
+                    reconnect.next = Some(now + delay(reconnect.attempt));
+                    tab.state = SessionState::Disconnected;
+                } else {
+                    let error = reconnect
+                        .last_error
+                        .take()
+                        .unwrap_or_else(|| "Connection lost".to_string());
+                    tab.auto_reconnect = None;
+                    tab.write_notice("— gave up reconnecting —");
+                    tab.state = SessionState::Failed(error);
+                }
+            }
+        }
+    }
+    if tasks.is_empty() {
+        None
+    } else {
+        Some(Task::batch(tasks))
+    }
+}
+
+/// Wait before attempt `attempt`: 1s, doubling up to a minute.
+fn delay(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.min(6)).min(MAX_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_exponentially_up_to_a_minute() {
+        let delays: Vec<u64> = (0..MAX_ATTEMPTS).map(|n| delay(n).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+    }
+}
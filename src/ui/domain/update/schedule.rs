@@ -0,0 +1,296 @@
+use chrono::Local;
+use iced::Task;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::sessions::connect_saved_session;
+use super::{join_local_path, join_remote_path};
+use crate::session::SessionConfig;
+use crate::session::config::AuthMethod;
+use crate::session::expand::DomainRule;
+use crate::session::schedule::{JobDirection, TransferJob};
+use crate::ui::App;
+use crate::ui::message::{Message, SftpMessage};
+use crate::ui::state::{
+    ConnectDefaults, JobFailureToast, JobOwner, JobRun, SftpContextAction, SftpPane,
+};
+
+// Runs kept per job for the history line.
+const MAX_HISTORY: usize = 20;
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::Sftp(SftpMessage::ContextAction(pane, name, SftpContextAction::Schedule)) => {
+            let Some(session_id) = app
+                .tabs
+                .get(app.active_tab)
+                .and_then(|tab| tab.sftp_key.clone())
+            else {
+                return Task::none();
+            };
+            let Some(state) = app.sftp_state_for_tab(app.active_tab) else {
+                return Task::none();
+            };
+            // Scheduled files land in the directory shown in the other pane.
+            let job = match pane {
+                SftpPane::Local => TransferJob::new(
+                    session_id,
+                    JobDirection::Upload,
+                    join_remote_path(&state.remote_path, &name),
+                    join_local_path(&state.local_path, &name),
+                ),
+                SftpPane::Remote => TransferJob::new(
+                    session_id,
+                    JobDirection::Download,
+                    join_remote_path(&state.remote_path, &name),
+                    join_local_path(&state.local_path, &name),
+                ),
+            };
+            app.transfer_jobs.push(job);
+            app.sftp_show_jobs = true;
+            save(app);
+            Task::none()
+        }
+        Message::Sftp(SftpMessage::ShowJobs(show)) => {
+            app.sftp_show_jobs = show;
+            Task::none()
+        }
+        Message::TransferJobScheduleChanged(id, schedule) => {
+            if let Some(job) = app.transfer_jobs.iter_mut().find(|job| job.id == id) {
+                job.schedule = schedule;
+                save(app);
+            }
+            Task::none()
+        }
+        Message::DeleteTransferJob(id) => {
+            app.transfer_jobs.retain(|job| job.id != id);
+            app.job_runs.remove(&id);
+            save(app);
+            Task::none()
+        }
+        Message::RunTransferJob(id) => start(app, &id),
+        Message::TransferJobFinished(id, result) => {
+            if let Some(job) = app
+                .background_jobs
+                .running(&JobOwner::Scheduled(id.clone()))
+            {
+                app.background_jobs
+                    .finish(job, result.as_ref().map(|_| ()).map_err(Clone::clone));
+            }
+            let Some(job) = app.transfer_jobs.iter_mut().find(|job| job.id == id) else {
+                return Task::none();
+            };
+            job.last_run = Some(chrono::Utc::now());
+            if let Err(error) = &result {
+                app.job_toast = Some(JobFailureToast {
+                    name: job.name().to_string(),
+                    error: error.clone(),
+                });
+            }
+            let runs = app.job_runs.entry(id).or_default();
+            runs.push(JobRun {
+                finished: Local::now(),
+                result,
+            });
+            if runs.len() > MAX_HISTORY {
+                runs.remove(0);
+            }
+            save(app);
+            Task::none()
+        }
+        Message::JobToastDismiss => {
+            app.job_toast = None;
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Starts every job whose time has come. Called on each tick.
+pub(in crate::ui) fn run_due(app: &mut App) -> Option<Task<Message>> {
+    if app.transfer_jobs.is_empty() {
+        return None;
+    }
+    let now = Local::now();
+    let due: Vec<String> = app
+        .transfer_jobs
+        .iter()
+        .filter(|job| {
+            app.background_jobs
+                .running(&JobOwner::Scheduled(job.id.clone()))
+                .is_none()
+        })
+        .filter(|job| job.next_run(app.jobs_since).is_some_and(|next| next <= now))
+        .map(|job| job.id.clone())
+        .collect();
+    if due.is_empty() {
+        return None;
+    }
+    Some(Task::batch(due.iter().map(|id| start(app, id))))
+}
+
+fn start(app: &mut App, id: &str) -> Task<Message> {
+    let Some(job) = app.transfer_jobs.iter().find(|job| job.id == id).cloned() else {
+        return Task::none();
+    };
+    let owner = JobOwner::Scheduled(job.id.clone());
+    if app.background_jobs.running(&owner).is_some() {
+        return Task::none();
+    }
+    let session = app
+        .saved_sessions
+        .iter()
+        .find(|session| session.id == job.session_id)
+        .cloned();
+    let window = app.app_settings.sftp_pipeline_depth;
+    let domains = app.app_settings.host_domains.clone();
+    let defaults = app.connect_defaults();
+    let id = job.id.clone();
+    let (job_id, _) = app
+        .background_jobs
+        .start(owner, format!("Scheduled {}", job.name()));
+    let (task, handle) = Task::perform(
+        async move {
+            let session = session.ok_or_else(|| "The session no longer exists".to_string())?;
+            run(session, &domains, &defaults, job, window).await
+        },
+        move |result| Message::TransferJobFinished(id.clone(), result),
+    )
+    .abortable();
+    // Dropping the run closes its connection; a download leaves only its
+    // `.part` file behind.
+    app.background_jobs
+        .on_cancel(job_id, move || handle.abort());
+    task
+}
+
+fn save(app: &App) {
+    if let Err(e) = app.session_storage.save_jobs(&app.transfer_jobs) {
+        eprintln!("Failed to save transfer jobs: {}", e);
+    }
+}
+
+/// Copies the job's file over a connection of its own, so it doesn't matter
+/// whether a tab for the session is open. Returns the bytes copied.
+async fn run(
+    session: SessionConfig,
+    domains: &[DomainRule],
+    defaults: &ConnectDefaults,
+    job: TransferJob,
+    window: usize,
+) -> Result<u64, String> {
+    // Nobody is there to type a password when the job fires.
+    if matches!(session.auth_method, AuthMethod::Password)
+        && session.password.as_deref().is_none_or(str::is_empty)
+    {
+        return Err("The session has no saved password".to_string());
+    }
+    let (ssh, _rx, _) = connect_saved_session(&session, domains, defaults, None, None).await?;
+    let result = async {
+        let channel = ssh
+            .lock()
+            .await
+            .open_transfer_channel()
+            .await
+            .map_err(|e| format!("SFTP init failed: {}", e))?;
+        match job.direction {
+            JobDirection::Download => download(&channel, &job, window).await,
+            JobDirection::Upload => upload(&channel, &job, window).await,
+        }
+    }
+    .await;
+    if let Err(e) = ssh
+        .lock()
+        .await
+        .disconnect("scheduled transfer finished")
+        .await
+    {
+        tracing::warn!("scheduled transfer disconnect failed: {}", e);
+    }
+    result
+}
+
+// The previous copy stays in place until the new one is complete.
+async fn download(
+    channel: &crate::ssh::TransferChannel,
+    job: &TransferJob,
+    window: usize,
+) -> Result<u64, String> {
+    let file = channel
+        .open(&job.remote_path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", job.remote_path, e))?;
+    let partial = format!("{}.part", job.local_path);
+    let mut output = tokio::fs::File::create(&partial)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", partial, e))?;
+    let mut reader = file.reader(window, 0);
+    let mut total = 0;
+    let copied = async {
+        while let Some(chunk) = reader
+            .next_chunk()
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", job.remote_path, e))?
+        {
+            output
+                .write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", partial, e))?;
+            total += chunk.len() as u64;
+        }
+        output
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", partial, e))
+    }
+    .await;
+    let _ = file.close().await;
+    if let Err(e) = copied {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(e);
+    }
+    tokio::fs::rename(&partial, &job.local_path)
+        .await
+        .map_err(|e| format!("Failed to replace {}: {}", job.local_path, e))?;
+    Ok(total)
+}
+
+async fn upload(
+    channel: &crate::ssh::TransferChannel,
+    job: &TransferJob,
+    window: usize,
+) -> Result<u64, String> {
+    let mut input = tokio::fs::File::open(&job.local_path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", job.local_path, e))?;
+    let file = channel
+        .create(&job.remote_path)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", job.remote_path, e))?;
+    let mut writer = file.writer(window, 0);
+    let mut total = 0;
+    let copied = async {
+        loop {
+            let mut buffer = vec![0; file.chunk_size()];
+            let read = input
+                .read(&mut buffer)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", job.local_path, e))?;
+            if read == 0 {
+                break;
+            }
+            buffer.truncate(read);
+            writer
+                .write(buffer)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", job.remote_path, e))?;
+            total += read as u64;
+        }
+        writer
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", job.remote_path, e))
+    }
+    .await;
+    let _ = file.close().await;
+    copied.map(|_| total)
+}
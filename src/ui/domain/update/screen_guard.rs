@@ -0,0 +1,55 @@
+use iced::Task;
+use iced::event::Event;
+use iced::{keyboard, mouse};
+use std::time::Instant;
+
+use crate::ui::App;
+use crate::ui::message::{ActiveView, Message};
+
+/// Hides the terminal once it has gone without input for the configured
+/// time.
+pub(super) fn tick(app: &mut App) {
+    let Some(delay) = app.app_settings.screen_guard.delay() else {
+        app.idle_screen.hidden = false;
+        return;
+    };
+    if !app.idle_screen.hidden
+        && app.active_view == ActiveView::Terminal
+        && !app.tabs.is_empty()
+        && app.idle_screen.last_input.elapsed() >= delay
+    {
+        app.idle_screen.hidden = true;
+    }
+}
+
+/// Counts keys, clicks and scrolling as input. While the terminal is
+/// hidden a key only brings it back, and what it types doesn't reach the
+/// shell until it is released.
+pub(super) fn handle_runtime_event(app: &mut App, event: &Event) -> Option<Task<Message>> {
+    if matches!(
+        event,
+        Event::Keyboard(keyboard::Event::KeyPressed { .. })
+            | Event::Mouse(mouse::Event::ButtonPressed(_) | mouse::Event::WheelScrolled { .. })
+    ) {
+        app.idle_screen.last_input = Instant::now();
+    }
+    let screen = &mut app.idle_screen;
+    if !screen.hidden && !screen.revealing {
+        return None;
+    }
+    match event {
+        Event::Keyboard(keyboard::Event::KeyPressed { .. }) if screen.hidden => {
+            screen.hidden = false;
+            screen.revealing = true;
+        }
+        Event::Keyboard(keyboard::Event::KeyReleased { .. }) => screen.revealing = false,
+        _ => {}
+    }
+    matches!(event, Event::Keyboard(_) | Event::InputMethod(_)).then(Task::none)
+}
+
+pub(super) fn reveal(app: &mut App) -> Task<Message> {
+    app.idle_screen.hidden = false;
+    app.idle_screen.last_input = Instant::now();
+    app.focus_terminal_ime()
+}
@@ -0,0 +1,151 @@
+use iced::{Point, Task};
+
+use crate::session::config::Snippet;
+use crate::ui::App;
+use crate::ui::message::{Message, TerminalMessage};
+use crate::ui::state::{PlaceholderTarget, SelectionAction, SelectionActions};
+
+const SEARCH_URL: &str = "https://duckduckgo.com/?q=";
+// Long enough to tell snippets apart in the terminal menu.
+const SNIPPET_NAME_CHARS: usize = 40;
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::SelectionAction(action) => {
+            let Some(selection) = app.selection_actions.take() else {
+                return Task::none();
+            };
+            let task = match action {
+                SelectionAction::Copy => iced::clipboard::write(selection.text),
+                SelectionAction::SearchWeb => {
+                    Task::done(Message::OpenUrl(search_url(selection.text.trim())))
+                }
+                SelectionAction::OpenUrl => selection
+                    .url
+                    .map_or_else(Task::none, |url| Task::done(Message::OpenUrl(url))),
+                SelectionAction::RevealInSftp => selection
+                    .remote_path
+                    .map_or_else(Task::none, |path| Task::done(Message::OpenRemotePath(path))),
+                SelectionAction::SaveSnippet => {
+                    save_snippet(app, selection.text);
+                    Task::none()
+                }
+            };
+            Task::batch([task, app.focus_terminal_ime()])
+        }
+        Message::InsertSnippet(index) => {
+            app.terminal_menu = None;
+            let Some(snippet) = app.snippets.get(index).cloned() else {
+                return Task::none();
+            };
+            let target = PlaceholderTarget::Snippet;
+            if let Some(task) =
+                super::placeholders::prompt(app, &snippet.name, &snippet.text, target)
+            {
+                return task;
+            }
+            let data = app.bracketed_paste_bytes(&snippet.text);
+            Task::batch([
+                Task::done(Message::Terminal(TerminalMessage::Input(data))),
+                app.focus_terminal_ime(),
+            ])
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Offers the toolbar for whatever is now selected in the active tab.
+pub(in crate::ui) fn offer(app: &mut App, position: Point) {
+    app.selection_actions = None;
+    let Some(tab) = app.tabs.get(app.active_tab) else {
+        return;
+    };
+    let Some(text) = tab
+        .emulator
+        .copy_selection()
+        .filter(|text| !text.trim().is_empty())
+    else {
+        return;
+    };
+    let remote_path = Some(text.trim())
+        .filter(|path| path.starts_with('/') && !path.contains('\n'))
+        .filter(|_| app.active_tab != 0 && tab.ssh_handle.is_some())
+        .map(str::to_string);
+    app.selection_actions = Some(SelectionActions {
+        tab_index: app.active_tab,
+        position,
+        url: as_url(&text),
+        remote_path,
+        text,
+    });
+}
+
+fn save_snippet(app: &mut App, text: String) {
+    let first_line = text.trim().lines().next().unwrap_or_default();
+    let mut name: String = first_line.chars().take(SNIPPET_NAME_CHARS).collect();
+    if name.len() < first_line.len() {
+        name.push('…');
+    }
+    app.snippets.retain(|snippet| snippet.text != text);
+    app.snippets.insert(0, Snippet { name, text });
+    if let Err(e) = app.session_storage.save_snippets(&app.snippets) {
+        eprintln!("Failed to save snippets: {}", e);
+    }
+}
+
+/// The selection as a link to open, when it is a single web address.
+fn as_url(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let lower = text.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        Some(text.to_string())
+    } else if lower.starts_with("www.") {
+        Some(format!("https://{}", text))
+    } else {
+        None
+    }
+}
+
+fn search_url(query: &str) -> String {
+    let mut url = SEARCH_URL.to_string();
+    for byte in query.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            b' ' => url.push('+'),
+            byte => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_web_addresses() {
+        assert_eq!(
+            as_url(" https://example.com/a?b=1\n").as_deref(),
+            Some("https://example.com/a?b=1")
+        );
+        assert_eq!(
+            as_url("www.example.com").as_deref(),
+            Some("https://www.example.com")
+        );
+        assert_eq!(as_url("example.com"), None);
+        assert_eq!(as_url("see https://example.com"), None);
+    }
+
+    #[test]
+    fn encodes_search_queries() {
+        assert_eq!(
+            search_url("error: 50% done & café"),
+            "https://duckduckgo.com/?q=error%3A+50%25+done+%26+caf%C3%A9"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use iced::Task;
+
+use crate::ui::App;
+use crate::ui::message::{Message, SessionMessage};
+use crate::ui::state::{SessionColumn, SessionSort};
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::SetSessionTable(table) => {
+            app.window_state.session_table = table;
+            app.session_menu_open = None;
+            super::window::save_state(app);
+            Task::none()
+        }
+        Message::SortSessions(column) => {
+            app.session_sort = if app.session_sort.column == column {
+                SessionSort {
+                    column,
+                    descending: !app.session_sort.descending,
+                }
+            } else {
+                // Most recent first is the useful order for dates.
+                SessionSort {
+                    column,
+                    descending: column == SessionColumn::LastConnected,
+                }
+            };
+            Task::none()
+        }
+        Message::ToggleSessionSelected(id) => {
+            if !app.selected_sessions.remove(&id) {
+                app.selected_sessions.insert(id);
+            }
+            app.bulk_delete_armed = false;
+            Task::none()
+        }
+        Message::SelectSessions(ids, selected) => {
+            for id in ids {
+                if selected {
+                    app.selected_sessions.insert(id);
+                } else {
+                    app.selected_sessions.remove(&id);
+                }
+            }
+            app.bulk_delete_armed = false;
+            Task::none()
+        }
+        Message::ConnectSelectedSessions => {
+            let ids = selected_ids(app);
+            app.selected_sessions.clear();
+            app.bulk_delete_armed = false;
+            Task::batch(
+                ids.into_iter()
+                    .map(|id| app.update(Message::Sessions(SessionMessage::Connect(id))))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        // The first press asks for confirmation in the selection bar.
+        Message::DeleteSelectedSessions => {
+            if app.viewer_mode() {
+                return Task::none();
+            }
+            if !app.bulk_delete_armed {
+                app.bulk_delete_armed = true;
+                return Task::none();
+            }
+            for id in selected_ids(app) {
+                let _ = app.update(Message::Sessions(SessionMessage::Delete(id)));
+            }
+            app.selected_sessions.clear();
+            app.bulk_delete_armed = false;
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Selected sessions that still exist, in saved order.
+fn selected_ids(app: &App) -> Vec<String> {
+    app.saved_sessions
+        .iter()
+        .filter(|session| app.selected_sessions.contains(&session.id))
+        .map(|session| session.id.clone())
+        .collect()
+}
@@ -1,17 +1,31 @@
 use iced::Task;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
+use super::{placeholders, subshell, usage, warmup};
+use crate::core::ConnectionKey;
 use crate::session::SessionConfig;
-use crate::session::config::{PortForwardDirection, PortForwardRule};
+use crate::session::config::{AgentSignRule, PortForwardDirection, PortForwardRule, ProxyOverride};
+use crate::session::expand::DomainRule;
+use crate::session::template::SessionTemplate;
 use crate::ui::App;
-use crate::ui::message::{ActiveView, Message, SessionDialogTab};
-use crate::ui::state::{ConnectionTestStatus, PortForwardStatus, SessionTab, SftpState};
+use crate::ui::message::{ActiveView, Message, SessionDialogTab, SessionMessage};
+use crate::ui::state::{
+    AgentSignChoice, AuthChoice, ConnectDefaults, ConnectionTestStatus, DisconnectToast, JobOwner,
+    PlaceholderTarget, PortForwardStatus, SessionState, SessionTab, SftpState, SshConnection,
+};
 use uuid::Uuid;
 
-pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+/// Matches the largest value offered by the global setting.
+const MAX_TRANSFER_LIMIT: usize = 8;
+
+pub(in crate::ui) fn handle(app: &mut App, message: SessionMessage) -> Task<Message> {
+    if app.viewer_mode() && changes_sessions(&message) {
+        return Task::none();
+    }
     match message {
-        Message::CreateNewSession => {
+        SessionMessage::CreateNew => {
             app.editing_session = Some(SessionConfig::new(
                 String::new(),
                 String::new(),
@@ -32,8 +46,31 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
                 .or_else(|| app.app_settings.ssh_keys.first())
                 .map(|key| key.id.clone())
                 .unwrap_or_default();
+            app.form_fallback_keys.clear();
             app.form_key_passphrase.clear();
-            app.auth_method_password = false;
+            app.form_idle_timeout.clear();
+            app.form_connect_timeout.clear();
+            app.form_access_expires.clear();
+            app.form_disconnect_on_expiry = false;
+            app.form_debug_log = false;
+            app.form_preflight = false;
+            app.form_host_key.clear();
+            app.form_port_knock.clear();
+            app.form_jump_host.clear();
+            app.form_transfer_limits = None;
+            app.form_keepalive = None;
+            app.form_forward_agent = false;
+            app.form_compression = false;
+            app.form_bell = Default::default();
+            app.form_tags.clear();
+            app.form_badge.clear();
+            app.form_tmux_session.clear();
+            app.form_remote_command.clear();
+            app.form_environment.clear();
+            app.form_send_locale = false;
+            app.form_proxy.clear();
+            app.form_proxy_password.clear();
+            app.form_auth = AuthChoice::PrivateKey;
             app.show_password = false;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
@@ -50,165 +87,196 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
             app.port_forward_error = None;
             Task::none()
         }
-        Message::EditSession(id) => {
+        SessionMessage::ConnectClipboardTarget => {
+            let Some(target) = app.quick_connect_clipboard.take() else {
+                return Task::none();
+            };
+            app.show_quick_connect = false;
+            let saved = app.saved_sessions.iter().find(|session| {
+                session.host.eq_ignore_ascii_case(&target.host)
+                    && target
+                        .username
+                        .as_ref()
+                        .is_none_or(|username| *username == session.username)
+                    && target.port.is_none_or(|port| port == session.port)
+            });
+            if let Some(session) = saved {
+                return Task::done(Message::Sessions(SessionMessage::Connect(
+                    session.id.clone(),
+                )));
+            }
+
+            // Unknown host: open a prefilled new session so the user picks
+            // how to sign in.
+            let task = handle(app, SessionMessage::CreateNew);
+            app.form_name = target.host.clone();
+            app.form_host = target.host;
+            app.form_port = target.port.unwrap_or(22).to_string();
+            app.form_username = target.username.unwrap_or_default();
+            app.active_tab = 0;
+            app.active_view = ActiveView::SessionManager;
+            app.sftp_panel_open = false;
+            app.port_forward_panel_open = false;
+            task
+        }
+        SessionMessage::Edit(id) => {
             app.session_menu_open = None;
             if let Some(session) = app.saved_sessions.iter().find(|s| s.id == id).cloned() {
                 start_edit_session(app, session, SessionDialogTab::General);
             }
             Task::none()
         }
-        Message::DeleteSession(id) => {
+        SessionMessage::Delete(id) => {
             app.session_menu_open = None;
+            app.selected_sessions.remove(&id);
             if let Err(e) = app
                 .session_storage
                 .delete_session(&id, &mut app.saved_sessions)
             {
                 eprintln!("Failed to delete session: {}", e);
             }
+            usage::forget(app, &id);
+            let jobs = app.transfer_jobs.len();
+            app.transfer_jobs.retain(|job| job.session_id != id);
+            if app.transfer_jobs.len() != jobs
+                && let Err(e) = app.session_storage.save_jobs(&app.transfer_jobs)
+            {
+                eprintln!("Failed to save transfer jobs: {}", e);
+            }
             Task::none()
         }
-        Message::ConnectToSession(id) => {
+        SessionMessage::Connect(id) => {
             app.session_menu_open = None;
-            if let Some(session) = app.saved_sessions.iter().find(|s| s.id == id) {
-                let name = session.name.clone();
-                let host = session.host.clone();
-                let port = session.port;
-                let username = session.username.clone();
-                let password = session.password.clone();
-                let auth_method = session.auth_method.clone();
-                let key_passphrase = session.key_passphrase.clone();
-                println!("Connecting to {}:{} with user '{}'", host, port, username);
-
-                app.tabs.push(SessionTab::new(&name));
-                let new_tab_index = app.tabs.len() - 1;
-                if let Some(tab) = app.tabs.get_mut(new_tab_index) {
-                    tab.sftp_key = Some(id.clone());
+            let Some(session) = app.saved_sessions.iter().find(|s| s.id == id) else {
+                return Task::none();
+            };
+            if let Some(command) = session.remote_command.clone() {
+                let title = session.name.clone();
+                let target = PlaceholderTarget::RemoteCommand(id.clone());
+                if let Some(task) = placeholders::prompt(app, &title, &command, target) {
+                    return task;
                 }
-                app.sftp_states
-                    .entry(id.clone())
-                    .or_insert_with(SftpState::new);
-                app.active_tab = new_tab_index;
-                app.active_view = ActiveView::Terminal;
-                app.last_terminal_tab = app.active_tab;
-                let tab_index = app.active_tab;
-
-                let connect_task = Task::perform(
-                    async move {
-                        match crate::ssh::SshSession::connect(
-                            &host,
-                            port,
-                            &username,
-                            auth_method,
-                            password,
-                            key_passphrase,
-                        )
-                        .await
-                        {
-                            Ok((session, rx)) => {
-                                Ok((Arc::new(Mutex::new(session)), Arc::new(Mutex::new(rx))))
-                            }
-                            Err(e) => Err(e.to_string()),
-                        }
-                    },
-                    move |result| Message::SessionConnected(result, tab_index),
-                );
-                return Task::batch(vec![connect_task, app.focus_terminal_ime()]);
             }
-            Task::none()
+            open_saved_session(app, &id, None)
         }
-        Message::SaveSession => {
-            if let Some(ref mut session) = app.editing_session {
-                if app.form_name.trim().is_empty() {
-                    app.validation_error = Some("Session name is required".to_string());
-                    return Task::none();
-                }
-
-                if app.form_host.trim().is_empty() {
-                    app.validation_error = Some("Host is required".to_string());
-                    return Task::none();
-                }
-
-                if app.form_username.trim().is_empty() {
-                    app.validation_error = Some("Username is required".to_string());
-                    return Task::none();
-                }
-
-                let port = match app.form_port.parse::<u16>() {
-                    Ok(p) if p > 0 => p,
-                    _ => {
-                        app.validation_error =
-                            Some("Port must be a number between 1 and 65535".to_string());
-                        return Task::none();
-                    }
-                };
-
-                if app.auth_method_password && app.form_password.trim().is_empty() {
-                    app.validation_error =
-                        Some("Password is required for password authentication".to_string());
-                    return Task::none();
-                }
+        SessionMessage::Save => {
+            let Some(mut session) = app.editing_session.clone() else {
+                return Task::none();
+            };
+            if app.form_name.trim().is_empty() {
+                app.validation_error = Some("Session name is required".to_string());
+                return Task::none();
+            }
 
-                if !app.auth_method_password && app.form_key_id.trim().is_empty() {
-                    app.validation_error = Some("Private key is required".to_string());
-                    return Task::none();
-                }
+            if app.form_host.trim().is_empty() {
+                app.validation_error = Some("Host is required".to_string());
+                return Task::none();
+            }
 
-                session.name = app.form_name.clone();
-                session.host = app.form_host.clone();
-                session.port = port;
-                session.username = app.form_username.clone();
+            let (old_host, old_port) = (session.host.clone(), session.port);
+            if let Err(e) = apply_form(app, &mut session) {
+                app.validation_error = Some(e);
+                return Task::none();
+            }
 
-                if app.auth_method_password {
-                    session.auth_method = crate::session::config::AuthMethod::Password;
-                    session.password = Some(app.form_password.clone());
-                    session.key_passphrase = None;
+            // A pinned key belongs to the old address unless a new one
+            // was entered along with it.
+            let pinned_host_key =
+                Some(app.form_host_key.trim().to_string()).filter(|key| !key.is_empty());
+            let moved = old_host != session.host || old_port != session.port;
+            session.host_key_fingerprint =
+                if moved && pinned_host_key == session.host_key_fingerprint {
+                    None
                 } else {
-                    let key_id = app.form_key_id.trim().to_string();
-                    let key_path = app
-                        .app_settings
-                        .ssh_keys
-                        .iter()
-                        .find(|key| key.id == key_id)
-                        .map(|key| key.path.clone())
-                        .unwrap_or_default();
-                    session.auth_method = crate::session::config::AuthMethod::PrivateKey {
-                        path: key_path,
-                        key_id: if key_id.is_empty() {
-                            None
-                        } else {
-                            Some(key_id)
-                        },
-                    };
-                    session.password = None;
-                    session.key_passphrase = if app.form_key_passphrase.trim().is_empty() {
-                        None
-                    } else {
-                        Some(app.form_key_passphrase.clone())
-                    };
-                }
+                    pinned_host_key
+                };
 
-                if let Err(e) = app
-                    .session_storage
-                    .save_session(session.clone(), &mut app.saved_sessions)
-                {
-                    app.validation_error = Some(format!("Failed to save: {}", e));
-                    return Task::none();
-                }
+            if let Err(e) = app
+                .session_storage
+                .save_session(session.clone(), &mut app.saved_sessions)
+            {
+                app.validation_error = Some(format!("Failed to save: {}", e));
+                return Task::none();
+            }
+            let preflight = session.preflight_on_save.then(|| {
+                preflight_task(
+                    &session,
+                    &app.app_settings.host_domains,
+                    &app.connect_defaults(),
+                )
+            });
 
-                app.editing_session = None;
-                app.validation_error = None;
-                app.saved_key_menu_open = false;
-                app.port_forward_session_id = None;
-                app.port_forward_local_host = "127.0.0.1".to_string();
-                app.port_forward_local_port.clear();
-                app.port_forward_remote_host.clear();
-                app.port_forward_remote_port.clear();
-                app.port_forward_direction = PortForwardDirection::Local;
-                app.port_forward_error = None;
+            app.editing_session = None;
+            app.validation_error = None;
+            app.saved_key_menu_open = false;
+            app.port_forward_session_id = None;
+            app.port_forward_local_host = "127.0.0.1".to_string();
+            app.port_forward_local_port.clear();
+            app.port_forward_remote_host.clear();
+            app.port_forward_remote_port.clear();
+            app.port_forward_direction = PortForwardDirection::Local;
+            app.port_forward_error = None;
+            preflight.unwrap_or_else(Task::none)
+        }
+        SessionMessage::SaveTemplate => {
+            let Some(mut session) = app.editing_session.clone() else {
+                return Task::none();
+            };
+            let name = app.form_name.trim().to_string();
+            if name.is_empty() {
+                app.validation_error = Some("Name the template in the name field".to_string());
+                return Task::none();
             }
+            if let Err(e) = apply_form(app, &mut session) {
+                app.validation_error = Some(e);
+                return Task::none();
+            }
+            let mut templates = app.session_templates.clone();
+            templates.push(SessionTemplate::from_session(name, &session));
+            if let Err(e) = app.session_storage.save_templates(&templates) {
+                app.validation_error = Some(e);
+                return Task::none();
+            }
+            app.session_templates = templates;
+            Task::done(Message::Sessions(SessionMessage::CancelEdit))
+        }
+        SessionMessage::TemplateHostChanged(value) => {
+            app.template_host = value;
             Task::none()
         }
-        Message::CancelSessionEdit => {
+        SessionMessage::CreateFromTemplate(template_id) => {
+            let host = app.template_host.trim();
+            let Some(template) = app
+                .session_templates
+                .iter()
+                .find(|template| template.id == template_id)
+            else {
+                return Task::none();
+            };
+            if host.is_empty() {
+                return Task::none();
+            }
+            let session = template.instantiate(host);
+            let session_id = session.id.clone();
+            if let Err(e) = app
+                .session_storage
+                .save_session(session, &mut app.saved_sessions)
+            {
+                eprintln!("Failed to save session: {}", e);
+                return Task::none();
+            }
+            app.template_host.clear();
+            Task::done(Message::Sessions(SessionMessage::Connect(session_id)))
+        }
+        SessionMessage::DeleteTemplate(template_id) => {
+            app.session_templates
+                .retain(|template| template.id != template_id);
+            if let Err(e) = app.session_storage.save_templates(&app.session_templates) {
+                eprintln!("Failed to delete session template: {}", e);
+            }
+            Task::none()
+        }
+        SessionMessage::CancelEdit => {
             app.editing_session = None;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
@@ -222,7 +290,7 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
             app.port_forward_error = None;
             Task::none()
         }
-        Message::CloseSessionManager => {
+        SessionMessage::CloseManager => {
             if app.last_terminal_tab > 0 && app.last_terminal_tab < app.tabs.len() {
                 app.active_tab = app.last_terminal_tab;
                 app.active_view = ActiveView::Terminal;
@@ -233,37 +301,37 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
                 Task::none()
             }
         }
-        Message::ToggleAuthMethod => {
-            app.auth_method_password = !app.auth_method_password;
+        SessionMessage::SetAuthMethod(choice) => {
+            app.form_auth = choice;
             app.validation_error = None;
             app.show_password = false;
             app.connection_test_status = ConnectionTestStatus::Idle;
             app.saved_key_menu_open = false;
             Task::none()
         }
-        Message::SessionDialogTabSelected(tab) => {
+        SessionMessage::DialogTabSelected(tab) => {
             app.session_dialog_tab = tab;
             app.saved_key_menu_open = false;
             Task::none()
         }
-        Message::ClearValidationError => {
+        SessionMessage::ClearValidationError => {
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
             Task::none()
         }
-        Message::SessionNameChanged(value) => {
+        SessionMessage::NameChanged(value) => {
             app.form_name = value;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
             Task::none()
         }
-        Message::SessionHostChanged(value) => {
+        SessionMessage::HostChanged(value) => {
             app.form_host = value;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
             Task::none()
         }
-        Message::SessionPortChanged(value) => {
+        SessionMessage::PortChanged(value) => {
             if value.chars().all(|c| c.is_numeric()) {
                 app.form_port = value;
                 app.validation_error = None;
@@ -271,36 +339,187 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
             }
             Task::none()
         }
-        Message::SessionUsernameChanged(value) => {
+        SessionMessage::UsernameChanged(value) => {
             app.form_username = value;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
             Task::none()
         }
-        Message::SessionPasswordChanged(value) => {
+        SessionMessage::PasswordChanged(value) => {
             app.form_password = value;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
             Task::none()
         }
-        Message::TogglePasswordVisibility => {
+        SessionMessage::TogglePasswordVisibility => {
             app.show_password = !app.show_password;
             Task::none()
         }
-        Message::SessionKeyIdChanged(value) => {
+        SessionMessage::KeyIdChanged(value) => {
+            app.form_fallback_keys.retain(|id| *id != value);
             app.form_key_id = value;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
             app.saved_key_menu_open = false;
             Task::none()
         }
-        Message::SessionKeyPassphraseChanged(value) => {
+        SessionMessage::ToggleFallbackKey(id) => {
+            if app.form_fallback_keys.contains(&id) {
+                app.form_fallback_keys.retain(|fallback| *fallback != id);
+            } else {
+                app.form_fallback_keys.push(id);
+            }
+            app.connection_test_status = ConnectionTestStatus::Idle;
+            Task::none()
+        }
+        SessionMessage::IdleTimeoutChanged(value) => {
+            if value.chars().all(|c| c.is_ascii_digit()) {
+                app.form_idle_timeout = value;
+                app.validation_error = None;
+            }
+            Task::none()
+        }
+        SessionMessage::ConnectTimeoutChanged(value) => {
+            if value.chars().all(|c| c.is_ascii_digit()) {
+                app.form_connect_timeout = value;
+                app.validation_error = None;
+            }
+            Task::none()
+        }
+        SessionMessage::AccessExpiresChanged(value) => {
+            app.form_access_expires = value;
+            app.validation_error = None;
+            Task::none()
+        }
+        SessionMessage::DisconnectOnExpiryToggled(enabled) => {
+            app.form_disconnect_on_expiry = enabled;
+            Task::none()
+        }
+        SessionMessage::BellChanged(mode) => {
+            app.form_bell = mode;
+            Task::none()
+        }
+        SessionMessage::DebugLogToggled(enabled) => {
+            app.form_debug_log = enabled;
+            Task::none()
+        }
+        SessionMessage::PreflightToggled(enabled) => {
+            app.form_preflight = enabled;
+            Task::none()
+        }
+        SessionMessage::TransferLimitsToggled(enabled) => {
+            app.form_transfer_limits = enabled.then_some(app.app_settings.transfer_limits);
+            Task::none()
+        }
+        SessionMessage::KeepaliveToggled(enabled) => {
+            app.form_keepalive = enabled.then_some(app.app_settings.keepalive);
+            Task::none()
+        }
+        SessionMessage::KeepaliveChanged(keepalive) => {
+            if app.form_keepalive.is_some() {
+                app.form_keepalive = Some(keepalive);
+            }
+            Task::none()
+        }
+        SessionMessage::ForwardAgentToggled(enabled) => {
+            app.form_forward_agent = enabled;
+            Task::none()
+        }
+        SessionMessage::CompressionToggled(enabled) => {
+            app.form_compression = enabled;
+            Task::none()
+        }
+        SessionMessage::TransferLimitChanged(limit, value) => {
+            if let Some(limits) = app.form_transfer_limits.as_mut() {
+                limits.set(limit, value.min(MAX_TRANSFER_LIMIT));
+            }
+            Task::none()
+        }
+        SessionMessage::HostKeyChanged(value) => {
+            app.form_host_key = value;
+            app.connection_test_status = ConnectionTestStatus::Idle;
+            Task::none()
+        }
+        SessionMessage::PreflightFinished(id, result) => {
+            let Some(session) = app.saved_sessions.iter_mut().find(|s| s.id == id) else {
+                return Task::none();
+            };
+            match result {
+                Ok(report) => {
+                    tracing::info!(
+                        "pre-flight ok for {}: {} address(es), host key {}",
+                        session.host,
+                        report.addresses.len(),
+                        report.host_key_fingerprint
+                    );
+                    // An existing pin was already enforced by the handshake.
+                    if session.host_key_fingerprint.is_none() {
+                        session.host_key_fingerprint = Some(report.host_key_fingerprint);
+                        if let Err(e) = app.session_storage.save_sessions(&app.saved_sessions) {
+                            eprintln!("Failed to save pinned host key: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    let message = format!("Pre-flight check for {} failed: {}", session.name, e);
+                    app.last_error = Some((message, std::time::Instant::now()));
+                }
+            }
+            Task::none()
+        }
+        SessionMessage::PortKnockChanged(value) => {
+            app.form_port_knock = value;
+            Task::none()
+        }
+        SessionMessage::JumpHostChanged(value) => {
+            app.form_jump_host = value;
+            app.validation_error = None;
+            app.connection_test_status = ConnectionTestStatus::Idle;
+            Task::none()
+        }
+        SessionMessage::BadgeChanged(value) => {
+            app.form_badge = value;
+            Task::none()
+        }
+        SessionMessage::TmuxChanged(value) => {
+            app.form_tmux_session = value;
+            app.validation_error = None;
+            Task::none()
+        }
+        SessionMessage::ProxyChanged(value) => {
+            app.form_proxy = value;
+            app.validation_error = None;
+            Task::none()
+        }
+        SessionMessage::ProxyPasswordChanged(value) => {
+            app.form_proxy_password = value;
+            Task::none()
+        }
+        SessionMessage::RemoteCommandChanged(value) => {
+            app.form_remote_command = value;
+            app.validation_error = None;
+            Task::none()
+        }
+        SessionMessage::EnvironmentChanged(value) => {
+            app.form_environment = value;
+            app.validation_error = None;
+            Task::none()
+        }
+        SessionMessage::SendLocaleToggled(enabled) => {
+            app.form_send_locale = enabled;
+            Task::none()
+        }
+        SessionMessage::TagsChanged(value) => {
+            app.form_tags = value;
+            Task::none()
+        }
+        SessionMessage::KeyPassphraseChanged(value) => {
             app.form_key_passphrase = value;
             app.validation_error = None;
             app.connection_test_status = ConnectionTestStatus::Idle;
             Task::none()
         }
-        Message::TestConnection => {
+        SessionMessage::TestConnection => {
             let host = app.form_host.trim().to_string();
             if host.is_empty() {
                 app.connection_test_status =
@@ -322,29 +541,32 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
                 }
             };
 
-            let auth_method = if app.auth_method_password {
-                crate::session::config::AuthMethod::Password
-            } else {
-                let key_id = app.form_key_id.trim().to_string();
-                if key_id.is_empty() {
-                    app.connection_test_status =
-                        ConnectionTestStatus::Failed("Private key is required".to_string());
-                    return Task::none();
-                }
-                let key_path = app
-                    .app_settings
-                    .ssh_keys
-                    .iter()
-                    .find(|key| key.id == key_id)
-                    .map(|key| key.path.clone())
-                    .unwrap_or_default();
-                crate::session::config::AuthMethod::PrivateKey {
-                    path: key_path,
-                    key_id: Some(key_id),
+            let auth_method = match app.form_auth {
+                AuthChoice::Password => crate::session::config::AuthMethod::Password,
+                AuthChoice::Agent => crate::session::config::AuthMethod::Agent,
+                AuthChoice::PrivateKey => {
+                    let key_id = app.form_key_id.trim().to_string();
+                    if key_id.is_empty() {
+                        app.connection_test_status =
+                            ConnectionTestStatus::Failed("Private key is required".to_string());
+                        return Task::none();
+                    }
+                    let key_path = app
+                        .app_settings
+                        .ssh_keys
+                        .iter()
+                        .find(|key| key.id == key_id)
+                        .map(|key| key.path.clone())
+                        .unwrap_or_default();
+                    crate::session::config::AuthMethod::PrivateKey {
+                        path: key_path,
+                        key_id: Some(key_id),
+                        fallback_key_ids: app.form_fallback_keys.clone(),
+                    }
                 }
             };
 
-            let password = if app.auth_method_password {
+            let password = if app.form_auth == AuthChoice::Password {
                 let pass = app.form_password.clone();
                 if pass.trim().is_empty() {
                     app.connection_test_status =
@@ -356,7 +578,7 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
                 None
             };
 
-            let key_passphrase = if app.auth_method_password {
+            let key_passphrase = if app.form_auth != AuthChoice::PrivateKey {
                 None
             } else if app.form_key_passphrase.trim().is_empty() {
                 None
@@ -364,9 +586,80 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
                 Some(app.form_key_passphrase.clone())
             };
 
+            let port_knock =
+                match crate::session::config::KnockStep::parse_sequence(&app.form_port_knock) {
+                    Ok(steps) => steps,
+                    Err(e) => {
+                        app.connection_test_status = ConnectionTestStatus::Failed(e);
+                        return Task::none();
+                    }
+                };
+            let proxy = match form_proxy(app) {
+                Ok(proxy) => proxy,
+                Err(e) => {
+                    app.connection_test_status = ConnectionTestStatus::Failed(e);
+                    return Task::none();
+                }
+            };
+            let jump_host = match app.form_jump_host.trim() {
+                "" => None,
+                value => match crate::session::config::JumpHost::parse(value) {
+                    Ok(jump_host) => Some(jump_host),
+                    Err(e) => {
+                        app.connection_test_status = ConnectionTestStatus::Failed(e);
+                        return Task::none();
+                    }
+                },
+            };
+            // Test what connecting would reach, with variables and the
+            // group domain applied.
+            let mut draft = SessionConfig::new(String::new(), host, port, username);
+            draft.tags = parse_tags(&app.form_tags);
+            draft.jump_host = jump_host;
+            draft.proxy = proxy;
+            let draft =
+                match crate::session::expand::resolve(&draft, &app.app_settings.host_domains) {
+                    Ok(draft) => draft,
+                    Err(e) => {
+                        app.connection_test_status = ConnectionTestStatus::Failed(e);
+                        return Task::none();
+                    }
+                };
+            let proxy = draft.proxy_or(app.app_settings.proxy.as_ref());
+            let (host, username) = (draft.host, draft.username);
+            let options = crate::ssh::ConnectOptions {
+                pinned_host_key: Some(app.form_host_key.trim().to_string())
+                    .filter(|key| !key.is_empty()),
+                port_knock,
+                proxy,
+                jump_host: draft.jump_host,
+                prompter: app.credential_prompter(),
+                keepalive: app.form_keepalive.unwrap_or(app.app_settings.keepalive),
+                connect_timeout: Some(
+                    app.form_connect_timeout
+                        .trim()
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|&secs| secs > 0)
+                        .map(Duration::from_secs)
+                        .unwrap_or_else(|| app.default_connect_timeout()),
+                ),
+                compression: app.form_compression,
+                offer_keys: app.offered_keys(),
+                ..Default::default()
+            };
+
             app.connection_test_status = ConnectionTestStatus::Testing;
+            // Only the latest test's answer is shown.
+            if let Some(previous) = app.background_jobs.running(&JobOwner::ConnectionTest) {
+                app.background_jobs.cancel(previous);
+            }
+            let (job, _) = app.background_jobs.start(
+                JobOwner::ConnectionTest,
+                format!("Test {}@{}", username, host),
+            );
 
-            Task::perform(
+            let (task, handle) = Task::perform(
                 async move {
                     match crate::ssh::SshSession::connect(
                         &host,
@@ -375,6 +668,7 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
                         auth_method,
                         password,
                         key_passphrase,
+                        options,
                     )
                     .await
                     {
@@ -382,29 +676,35 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
                         Err(err) => Err(err.to_string()),
                     }
                 },
-                Message::TestConnectionResult,
+                |value| Message::Sessions(SessionMessage::TestConnectionResult(value)),
             )
+            .abortable();
+            app.background_jobs.on_cancel(job, move || handle.abort());
+            task
         }
-        Message::TestConnectionResult(result) => {
+        SessionMessage::TestConnectionResult(result) => {
+            if let Some(job) = app.background_jobs.running(&JobOwner::ConnectionTest) {
+                app.background_jobs.finish(job, result.clone());
+            }
             match result {
                 Ok(_) => app.connection_test_status = ConnectionTestStatus::Success,
                 Err(err) => app.connection_test_status = ConnectionTestStatus::Failed(err),
             }
             Task::none()
         }
-        Message::SessionSearchChanged(value) => {
+        SessionMessage::SearchChanged(value) => {
             app.session_search_query = value;
             Task::none()
         }
-        Message::ToggleSavedKeyMenu => {
+        SessionMessage::ToggleSavedKeyMenu => {
             app.saved_key_menu_open = !app.saved_key_menu_open;
             Task::none()
         }
-        Message::CloseSavedKeyMenu => {
+        SessionMessage::CloseSavedKeyMenu => {
             app.saved_key_menu_open = false;
             Task::none()
         }
-        Message::ToggleSessionMenu(id) => {
+        SessionMessage::ToggleMenu(id) => {
             app.session_menu_open = if app.session_menu_open.as_deref() == Some(&id) {
                 None
             } else {
@@ -412,48 +712,181 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
             };
             Task::none()
         }
-        Message::CloseSessionMenu => {
+        SessionMessage::CloseMenu => {
+            app.session_menu_open = None;
+            Task::none()
+        }
+        SessionMessage::ToggleUsage(id) => {
             app.session_menu_open = None;
+            app.usage_open = if app.usage_open.as_deref() == Some(&id) {
+                None
+            } else {
+                Some(id)
+            };
+            Task::none()
+        }
+        SessionMessage::EditConfig(tab_index) => {
+            edit_session_for_tab(app, tab_index, SessionDialogTab::General);
+            Task::none()
+        }
+        SessionMessage::CredentialInputChanged(value) => {
+            app.credential_input = value;
+            Task::none()
+        }
+        SessionMessage::CredentialRememberToggled(remember) => {
+            app.credential_remember = remember;
+            Task::none()
+        }
+        SessionMessage::SubmitCredential | SessionMessage::CancelCredential => {
+            let remember = std::mem::take(&mut app.credential_remember)
+                && app
+                    .credential_shown
+                    .as_ref()
+                    .is_some_and(|request| request.can_remember);
+            let answer = matches!(message, SessionMessage::SubmitCredential).then(|| {
+                crate::ssh::CredentialAnswer {
+                    value: std::mem::take(&mut app.credential_input),
+                    remember,
+                }
+            });
+            app.credential_queue.answer(answer);
+            app.credential_input.clear();
+            // The next tick shows whatever is asked next.
+            app.credential_shown = None;
+            app.focus_terminal_ime()
+        }
+        SessionMessage::AgentSignAnswer(choice) => {
+            // Viewer mode makes no lasting trust decisions: Always allows
+            // this one signature only.
+            if choice == AgentSignChoice::Always
+                && !app.viewer_mode()
+                && let Some(request) = app.agent_sign_shown.take()
+            {
+                app.agent_sign_rules.push(AgentSignRule {
+                    host: request.host,
+                    fingerprint: request.fingerprint,
+                });
+                if let Err(e) = app.session_storage.save_agent_rules(&app.agent_sign_rules) {
+                    eprintln!("Failed to save agent signing rules: {}", e);
+                }
+            }
+            app.credential_queue
+                .answer_sign(choice != AgentSignChoice::Deny);
+            app.agent_sign_shown = None;
+            Task::none()
+        }
+        SessionMessage::TryPasswordAuth(tab_index) => {
+            if edit_session_for_tab(app, tab_index, SessionDialogTab::General) {
+                app.form_auth = AuthChoice::Password;
+                app.form_password.clear();
+                app.show_password = false;
+            }
+            Task::none()
+        }
+        SessionMessage::OpenPortForwardCheck(tab_index) => {
+            edit_session_for_tab(app, tab_index, SessionDialogTab::PortForwarding);
+            Task::none()
+        }
+        SessionMessage::ShowDebugLog(tab_index) => {
+            if app
+                .tabs
+                .get(tab_index)
+                .is_some_and(|tab| tab.debug_log.is_some())
+            {
+                app.debug_log_tab = Some(tab_index);
+                app.debug_log_status = None;
+            }
+            Task::none()
+        }
+        SessionMessage::CloseDebugLog => {
+            app.debug_log_tab = None;
             Task::none()
         }
-        Message::OpenPortForwarding(id) => {
+        SessionMessage::ExportDebugLog => {
+            let Some(tab) = app.debug_log_tab.and_then(|index| app.tabs.get(index)) else {
+                return Task::none();
+            };
+            let Some(log) = tab.debug_log.as_ref() else {
+                return Task::none();
+            };
+            let mut contents = log.lines().join("\n");
+            contents.push('\n');
+            let file_name = format!("{}-debug.log", tab.title.replace(['/', '\\', ' '], "_"));
+            Task::perform(
+                async move {
+                    let Some(handle) = rfd::AsyncFileDialog::new()
+                        .set_file_name(file_name)
+                        .save_file()
+                        .await
+                    else {
+                        return Ok(None);
+                    };
+                    let path = handle.path().to_path_buf();
+                    tokio::fs::write(&path, contents)
+                        .await
+                        .map_err(|e| format!("Failed to export debug log: {}", e))?;
+                    Ok(Some(path.display().to_string()))
+                },
+                |value| Message::Sessions(SessionMessage::DebugLogExported(value)),
+            )
+        }
+        SessionMessage::DebugLogExported(result) => {
+            match result {
+                Ok(Some(path)) => app.debug_log_status = Some(format!("Saved to {}", path)),
+                Ok(None) => {}
+                Err(e) => app.debug_log_status = Some(e),
+            }
+            Task::none()
+        }
+        SessionMessage::DisconnectToastReconnect => {
+            let Some(toast) = app.disconnect_toast.take() else {
+                return Task::none();
+            };
+            Task::done(Message::SelectTab(toast.tab_index))
+                .chain(Task::done(Message::RetryConnection(toast.tab_index)))
+        }
+        SessionMessage::DisconnectToastDismiss => {
+            app.disconnect_toast = None;
+            Task::none()
+        }
+        SessionMessage::OpenPortForwarding(id) => {
             app.session_menu_open = None;
             if let Some(session) = app.saved_sessions.iter().find(|s| s.id == id).cloned() {
                 start_edit_session(app, session, SessionDialogTab::PortForwarding);
             }
             Task::none()
         }
-        Message::ClosePortForwarding => {
+        SessionMessage::ClosePortForwarding => {
             app.port_forward_session_id = None;
             app.port_forward_error = None;
             Task::none()
         }
-        Message::PortForwardLocalPortChanged(value) => {
+        SessionMessage::PortForwardLocalPortChanged(value) => {
             app.port_forward_local_port = value;
             app.port_forward_error = None;
             Task::none()
         }
-        Message::PortForwardLocalHostChanged(value) => {
+        SessionMessage::PortForwardLocalHostChanged(value) => {
             app.port_forward_local_host = value;
             app.port_forward_error = None;
             Task::none()
         }
-        Message::PortForwardRemoteHostChanged(value) => {
+        SessionMessage::PortForwardRemoteHostChanged(value) => {
             app.port_forward_remote_host = value;
             app.port_forward_error = None;
             Task::none()
         }
-        Message::PortForwardRemotePortChanged(value) => {
+        SessionMessage::PortForwardRemotePortChanged(value) => {
             app.port_forward_remote_port = value;
             app.port_forward_error = None;
             Task::none()
         }
-        Message::PortForwardDirectionChanged(direction) => {
+        SessionMessage::PortForwardDirectionChanged(direction) => {
             app.port_forward_direction = direction;
             app.port_forward_error = None;
             Task::none()
         }
-        Message::AddPortForward => {
+        SessionMessage::AddPortForward => {
             let session_id = match app.port_forward_session_id.clone() {
                 Some(id) => id,
                 None => return Task::none(),
@@ -565,7 +998,7 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
             app.port_forward_error = None;
             Task::none()
         }
-        Message::TogglePortForward(rule_id) => {
+        SessionMessage::TogglePortForward(rule_id) => {
             let session_id = match app.port_forward_session_id.clone() {
                 Some(id) => id,
                 None => return Task::none(),
@@ -596,7 +1029,7 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
             }
             Task::none()
         }
-        Message::DeletePortForward(rule_id) => {
+        SessionMessage::DeletePortForward(rule_id) => {
             let session_id = match app.port_forward_session_id.clone() {
                 Some(id) => id,
                 None => return Task::none(),
@@ -623,10 +1056,259 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
             }
             Task::none()
         }
-        _ => Task::none(),
     }
 }
 
+/// Opens a saved session in a new tab. `remote_command` replaces the
+/// session's own once its placeholders have been filled in.
+pub(super) fn open_saved_session(
+    app: &mut App,
+    id: &str,
+    remote_command: Option<String>,
+) -> Task<Message> {
+    let Some(session) = app.saved_sessions.iter().find(|s| s.id == id) else {
+        return Task::none();
+    };
+    let name = session.name.clone();
+    let debug_log = session.debug_log.then(crate::ssh::DebugLog::new);
+    let defaults = app.connect_defaults();
+    let key = connection_key(session, &app.app_settings.host_domains, &defaults);
+    // A debug log is there to show the handshake, tmux and
+    // remote commands run in the first shell, and the
+    // environment is the connection's, so those always log in.
+    if app.app_settings.share_connections
+        && debug_log.is_none()
+        && session.tmux_session.is_none()
+        && session.remote_command.is_none()
+        && session.remote_environment().is_empty()
+        && let Some(key) = &key
+        && let Some(shared) = app.sessions.shared(key)
+    {
+        println!(
+            "Opening {} on the existing connection to {}:{}",
+            name, session.host, session.port
+        );
+        app.sftp_states
+            .entry(id.to_string())
+            .or_insert_with(SftpState::new);
+        usage::record_connect(app, id);
+        return subshell::open_on_shared(app, &name, id, shared);
+    }
+    println!(
+        "Connecting to {}:{} with user '{}'",
+        session.host, session.port, session.username
+    );
+    let mut tab = SessionTab::new(&name);
+    let connect = connect_saved_session(
+        session,
+        &app.app_settings.host_domains,
+        &defaults,
+        debug_log.clone(),
+        app.tab_credential_prompter(tab.id),
+    );
+
+    tab.sftp_key = Some(id.to_string());
+    tab.debug_log = debug_log.clone();
+    tab.remote_command = remote_command;
+    tab.connect_timeout = defaults.connect_timeout_for(session);
+    app.tabs.push(tab);
+    let new_tab_index = app.tabs.len() - 1;
+    app.sftp_states
+        .entry(id.to_string())
+        .or_insert_with(SftpState::new);
+    app.active_tab = new_tab_index;
+    app.active_view = ActiveView::Terminal;
+    app.last_terminal_tab = app.active_tab;
+    let tab_index = app.active_tab;
+
+    let connect_task = match warmup::claim(app, id, tab_index) {
+        Some(task) => task,
+        None => Task::perform(connect, move |result| {
+            Message::SessionConnected(result, tab_index)
+        }),
+    };
+    Task::batch(vec![connect_task, app.focus_terminal_ime()])
+}
+
+/// What viewer mode refuses: changing saved sessions and their forwards,
+/// and revealing a typed password.
+fn changes_sessions(message: &SessionMessage) -> bool {
+    matches!(
+        message,
+        SessionMessage::CreateNew
+            | SessionMessage::Edit(_)
+            | SessionMessage::Delete(_)
+            | SessionMessage::Save
+            | SessionMessage::SaveTemplate
+            | SessionMessage::CreateFromTemplate(_)
+            | SessionMessage::DeleteTemplate(_)
+            | SessionMessage::EditConfig(_)
+            | SessionMessage::TogglePasswordVisibility
+            | SessionMessage::AddPortForward
+            | SessionMessage::TogglePortForward(_)
+            | SessionMessage::DeletePortForward(_)
+    )
+}
+
+/// Copies the dialog's fields into `session`, or says which one is invalid.
+/// The name and host are checked by the caller, since a template has no host.
+fn apply_form(app: &App, session: &mut SessionConfig) -> Result<(), String> {
+    if app.form_username.trim().is_empty() {
+        return Err("Username is required".to_string());
+    }
+
+    let port = match app.form_port.parse::<u16>() {
+        Ok(p) if p > 0 => p,
+        _ => return Err("Port must be a number between 1 and 65535".to_string()),
+    };
+
+    if app.form_auth == AuthChoice::Password && app.form_password.trim().is_empty() {
+        return Err("Password is required for password authentication".to_string());
+    }
+
+    if app.form_auth == AuthChoice::PrivateKey && app.form_key_id.trim().is_empty() {
+        return Err("Private key is required".to_string());
+    }
+
+    let idle_timeout = app.form_idle_timeout.trim();
+    let idle_timeout_minutes = if idle_timeout.is_empty() {
+        None
+    } else {
+        match idle_timeout.parse::<u32>() {
+            Ok(minutes) if minutes > 0 => Some(minutes),
+            _ => return Err("Idle timeout must be a positive number of minutes".to_string()),
+        }
+    };
+
+    let connect_timeout = app.form_connect_timeout.trim();
+    let connect_timeout_secs = if connect_timeout.is_empty() {
+        None
+    } else {
+        match connect_timeout.parse::<u32>() {
+            Ok(secs) if secs > 0 => Some(secs),
+            _ => return Err("Connect timeout must be a positive number of seconds".to_string()),
+        }
+    };
+
+    let access_expires_at = crate::session::expiry::parse(&app.form_access_expires)?;
+
+    let tmux_session = app.form_tmux_session.trim();
+    // tmux uses these to address windows and panes.
+    if tmux_session.contains([':', '.']) {
+        return Err("tmux session names can't contain ':' or '.'".to_string());
+    }
+    let remote_command = app.form_remote_command.trim();
+    if !remote_command.is_empty() && !tmux_session.is_empty() {
+        return Err("Use either a tmux session or a remote command, not both".to_string());
+    }
+
+    let environment = crate::session::config::EnvVar::parse_list(&app.form_environment)?;
+
+    let port_knock = crate::session::config::KnockStep::parse_sequence(&app.form_port_knock)?;
+    let jump_host = match app.form_jump_host.trim() {
+        "" => None,
+        value => Some(crate::session::config::JumpHost::parse(value)?),
+    };
+    let proxy = form_proxy(app)?;
+
+    session.name = app.form_name.clone();
+    session.host = app.form_host.clone();
+    session.port = port;
+    session.username = app.form_username.clone();
+    session.idle_timeout_minutes = idle_timeout_minutes;
+    session.connect_timeout_secs = connect_timeout_secs;
+    session.access_expires_at = access_expires_at;
+    session.disconnect_on_expiry = app.form_disconnect_on_expiry;
+    session.debug_log = app.form_debug_log;
+    session.preflight_on_save = app.form_preflight;
+    session.transfer_limits = app.form_transfer_limits;
+    session.keepalive = app.form_keepalive;
+    session.forward_agent = app.form_forward_agent;
+    session.compression = app.form_compression;
+    session.port_knock = port_knock;
+    session.jump_host = jump_host;
+    session.proxy = proxy;
+    session.bell = app.form_bell;
+    session.tags = parse_tags(&app.form_tags);
+    session.badge = Some(app.form_badge.trim().to_string()).filter(|b| !b.is_empty());
+    session.tmux_session = Some(tmux_session.to_string()).filter(|name| !name.is_empty());
+    session.remote_command = Some(remote_command.to_string()).filter(|cmd| !cmd.is_empty());
+    session.environment = environment;
+    session.send_locale = app.form_send_locale;
+
+    if app.form_auth == AuthChoice::Agent {
+        session.auth_method = crate::session::config::AuthMethod::Agent;
+        session.password = None;
+        session.key_passphrase = None;
+    } else if app.form_auth == AuthChoice::Password {
+        session.auth_method = crate::session::config::AuthMethod::Password;
+        session.password = Some(app.form_password.clone());
+        session.key_passphrase = None;
+    } else {
+        let key_id = app.form_key_id.trim().to_string();
+        let key_path = app
+            .app_settings
+            .ssh_keys
+            .iter()
+            .find(|key| key.id == key_id)
+            .map(|key| key.path.clone())
+            .unwrap_or_default();
+        session.auth_method = crate::session::config::AuthMethod::PrivateKey {
+            path: key_path,
+            key_id: if key_id.is_empty() {
+                None
+            } else {
+                Some(key_id)
+            },
+            fallback_key_ids: app.form_fallback_keys.clone(),
+        };
+        session.password = None;
+        session.key_passphrase = if app.form_key_passphrase.trim().is_empty() {
+            None
+        } else {
+            Some(app.form_key_passphrase.clone())
+        };
+    }
+    Ok(())
+}
+
+fn parse_tags(value: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in value
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(tag))
+        {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+// Opens the editor for the session behind a terminal tab, switching to the
+// session manager where the dialog lives. Returns false if the tab has no
+// saved session.
+fn edit_session_for_tab(app: &mut App, tab_index: usize, tab: SessionDialogTab) -> bool {
+    let Some(session) = app
+        .sftp_key_for_tab(tab_index)
+        .and_then(|id| app.saved_sessions.iter().find(|s| s.id == id))
+        .cloned()
+    else {
+        return false;
+    };
+    app.show_quick_connect = false;
+    app.active_tab = 0;
+    app.active_view = ActiveView::SessionManager;
+    app.sftp_panel_open = false;
+    app.port_forward_panel_open = false;
+    start_edit_session(app, session, tab);
+    true
+}
+
 fn start_edit_session(app: &mut App, session: SessionConfig, tab: SessionDialogTab) {
     app.form_name = session.name.clone();
     app.form_host = session.host.clone();
@@ -634,19 +1316,29 @@ fn start_edit_session(app: &mut App, session: SessionConfig, tab: SessionDialogT
     app.form_username = session.username.clone();
     if let Some(pass) = &session.password {
         app.form_password = pass.clone();
-        app.auth_method_password = true;
+        app.form_auth = AuthChoice::Password;
     } else {
         app.form_password.clear();
-        app.auth_method_password = false;
+        app.form_auth = AuthChoice::PrivateKey;
     }
-    if let crate::session::config::AuthMethod::Password = session.auth_method {
-        app.auth_method_password = true;
+    match session.auth_method {
+        crate::session::config::AuthMethod::Password => app.form_auth = AuthChoice::Password,
+        crate::session::config::AuthMethod::Agent => app.form_auth = AuthChoice::Agent,
+        crate::session::config::AuthMethod::PrivateKey { .. } => {}
     }
+    app.form_fallback_keys.clear();
     if let crate::session::config::AuthMethod::PrivateKey {
         ref path,
         ref key_id,
+        ref fallback_key_ids,
     } = session.auth_method
     {
+        // Keys deleted since the session was saved drop out.
+        app.form_fallback_keys = fallback_key_ids
+            .iter()
+            .filter(|id| app.app_settings.ssh_keys.iter().any(|key| key.id == **id))
+            .cloned()
+            .collect();
         if let Some(id) = key_id.as_ref() {
             app.form_key_id = id.clone();
         } else {
@@ -658,9 +1350,51 @@ fn start_edit_session(app: &mut App, session: SessionConfig, tab: SessionDialogT
                 .map(|key| key.id.clone())
                 .unwrap_or_default();
         }
-        app.auth_method_password = false;
+        app.form_auth = AuthChoice::PrivateKey;
     }
     app.form_key_passphrase = session.key_passphrase.clone().unwrap_or_default();
+    app.form_idle_timeout = session
+        .idle_timeout_minutes
+        .map(|minutes| minutes.to_string())
+        .unwrap_or_default();
+    app.form_connect_timeout = session
+        .connect_timeout_secs
+        .map(|secs| secs.to_string())
+        .unwrap_or_default();
+    app.form_access_expires = session
+        .access_expires_at
+        .map(crate::session::expiry::format)
+        .unwrap_or_default();
+    app.form_disconnect_on_expiry = session.disconnect_on_expiry;
+    app.form_debug_log = session.debug_log;
+    app.form_preflight = session.preflight_on_save;
+    app.form_host_key = session.host_key_fingerprint.clone().unwrap_or_default();
+    app.form_port_knock = crate::session::config::KnockStep::format_sequence(&session.port_knock);
+    app.form_jump_host = session
+        .jump_host
+        .as_ref()
+        .map(|jump_host| jump_host.to_string())
+        .unwrap_or_default();
+    app.form_transfer_limits = session.transfer_limits;
+    app.form_keepalive = session.keepalive;
+    app.form_forward_agent = session.forward_agent;
+    app.form_compression = session.compression;
+    app.form_bell = session.bell;
+    app.form_tags = session.tags.join(", ");
+    app.form_badge = session.badge.clone().unwrap_or_default();
+    app.form_tmux_session = session.tmux_session.clone().unwrap_or_default();
+    app.form_remote_command = session.remote_command.clone().unwrap_or_default();
+    app.form_environment = crate::session::config::EnvVar::format_list(&session.environment);
+    app.form_send_locale = session.send_locale;
+    app.form_proxy = session
+        .proxy
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    app.form_proxy_password = match &session.proxy {
+        Some(ProxyOverride::Via(proxy)) => proxy.password.clone().unwrap_or_default(),
+        _ => String::new(),
+    };
     app.show_password = false;
     app.editing_session = Some(session);
     app.validation_error = None;
@@ -679,6 +1413,292 @@ fn start_edit_session(app: &mut App, session: SessionConfig, tab: SessionDialogT
     app.port_forward_error = None;
 }
 
+/// The proxy override typed into the session form. The password field wins
+/// over one written into the URL.
+fn form_proxy(app: &App) -> Result<Option<ProxyOverride>, String> {
+    let mut proxy = ProxyOverride::parse(&app.form_proxy)?;
+    if let Some(ProxyOverride::Via(proxy)) = &mut proxy
+        && proxy.username.is_some()
+        && !app.form_proxy_password.is_empty()
+    {
+        proxy.password = Some(app.form_proxy_password.clone());
+    }
+    Ok(proxy)
+}
+
+/// Disconnects SSH tabs whose session has an idle timeout and has not seen
+/// keyboard input for that long.
+fn preflight_task(
+    session: &SessionConfig,
+    domains: &[DomainRule],
+    defaults: &ConnectDefaults,
+) -> Task<Message> {
+    let id = session.id.clone();
+    let connect_timeout = defaults.connect_timeout_for(session);
+    let session = match crate::session::expand::resolve(session, domains) {
+        Ok(session) => session,
+        Err(e) => {
+            return Task::done(Message::Sessions(SessionMessage::PreflightFinished(
+                id,
+                Err(e),
+            )));
+        }
+    };
+    let host = session.host.clone();
+    let port = session.port;
+    let username = session.username.clone();
+    let auth_method = session.auth_method.clone();
+    let password = session.password.clone();
+    let key_passphrase = session.key_passphrase.clone();
+    let options = crate::ssh::ConnectOptions {
+        pinned_host_key: session.host_key_fingerprint.clone(),
+        port_knock: session.port_knock.clone(),
+        jump_host: session.jump_host.clone(),
+        proxy: session.proxy_or(defaults.proxy.as_ref()),
+        connect_timeout: Some(connect_timeout),
+        offer_keys: defaults.offer_keys.clone(),
+        ..Default::default()
+    };
+    Task::perform(
+        async move {
+            crate::ssh::preflight(
+                &host,
+                port,
+                &username,
+                auth_method,
+                password,
+                key_passphrase,
+                options,
+            )
+            .await
+            .map_err(|e| format!("{:#}", e))
+        },
+        move |result| Message::Sessions(SessionMessage::PreflightFinished(id.clone(), result)),
+    )
+}
+
+/// Saves a key passphrase typed while connecting with the tab's session, if
+/// the user asked for it to be remembered and it unlocked the session's own
+/// key. Passphrases for other keys have nowhere to go, and nothing is saved
+/// in viewer mode.
+pub(in crate::ui) fn remember_typed_passphrases(
+    app: &mut App,
+    tab_index: usize,
+    typed: &[crate::ssh::TypedPassphrase],
+) {
+    if app.viewer_mode() {
+        return;
+    }
+    let Some(saved) = app.tabs.get(tab_index).and_then(|tab| {
+        app.saved_sessions
+            .iter_mut()
+            .find(|saved| tab.sftp_key.as_ref() == Some(&saved.id))
+    }) else {
+        return;
+    };
+    let crate::session::config::AuthMethod::PrivateKey { path, key_id, .. } = &saved.auth_method
+    else {
+        return;
+    };
+    let Some(typed) = typed
+        .iter()
+        .find(|typed| typed.remember && &typed.key_id == key_id && &typed.path == path)
+    else {
+        return;
+    };
+    saved.key_passphrase = Some(typed.passphrase.clone());
+    if let Err(e) = app.session_storage.save_sessions(&app.saved_sessions) {
+        eprintln!("Failed to save sessions: {}", e);
+    }
+}
+
+/// What a connection to `session` would be shared under: the target it
+/// resolves to, plus every setting that changes where it lands or how it
+/// logs in. None when the session doesn't resolve.
+pub(super) fn connection_key(
+    session: &SessionConfig,
+    domains: &[DomainRule],
+    defaults: &ConnectDefaults,
+) -> Option<ConnectionKey> {
+    let resolved = crate::session::expand::resolve(session, domains).ok()?;
+    let options = (
+        &resolved.auth_method,
+        &resolved.jump_host,
+        session.proxy_or(defaults.proxy.as_ref()),
+        &resolved.host_key_fingerprint,
+        &resolved.port_knock,
+        resolved.forward_agent,
+        resolved.compression,
+        session.keepalive.unwrap_or(defaults.keepalive),
+        &defaults.offer_keys,
+    );
+    Some(ConnectionKey::new(
+        &resolved.host,
+        &resolved.username,
+        resolved.port,
+        &options,
+    ))
+}
+
+/// Opens an SSH connection to a saved session; the shell is opened later,
+/// once the connection is attached to a tab.
+pub(in crate::ui) fn connect_saved_session(
+    session: &SessionConfig,
+    domains: &[DomainRule],
+    defaults: &ConnectDefaults,
+    debug_log: Option<crate::ssh::DebugLog>,
+    prompter: Option<Arc<dyn crate::ssh::CredentialPrompter>>,
+) -> impl std::future::Future<Output = Result<SshConnection, String>> + use<> {
+    let proxy = session.proxy_or(defaults.proxy.as_ref());
+    let keepalive = session.keepalive.unwrap_or(defaults.keepalive);
+    let connect_timeout = defaults.connect_timeout_for(session);
+    let offer_keys = defaults.offer_keys.clone();
+    let session = crate::session::expand::resolve(session, domains);
+    async move {
+        let session = session?;
+        let environment = session.remote_environment();
+        let options = crate::ssh::ConnectOptions {
+            debug_log,
+            pinned_host_key: session.host_key_fingerprint,
+            port_knock: session.port_knock,
+            jump_host: session.jump_host,
+            prompter,
+            keepalive,
+            connect_timeout: Some(connect_timeout),
+            forward_agent: session.forward_agent,
+            environment,
+            compression: session.compression,
+            proxy,
+            offer_keys,
+        };
+        match crate::ssh::SshSession::connect(
+            &session.host,
+            session.port,
+            &session.username,
+            session.auth_method,
+            session.password,
+            session.key_passphrase,
+            options,
+        )
+        .await
+        {
+            Ok((mut session, rx)) => {
+                let typed = session.take_typed_passphrases();
+                Ok((
+                    Arc::new(Mutex::new(session)),
+                    Arc::new(Mutex::new(rx)),
+                    typed,
+                ))
+            }
+            Err(e) => Err(format!("{:#}", e)),
+        }
+    }
+}
+
+pub(in crate::ui) fn disconnect_idle_tabs(app: &mut App) -> Option<Task<Message>> {
+    let now = std::time::Instant::now();
+    let mut tasks = Vec::new();
+    for tab in app.tabs.iter_mut().skip(1) {
+        if tab.state != SessionState::Connected || tab.session.is_none() {
+            continue;
+        }
+        let Some(minutes) = tab
+            .sftp_key
+            .as_ref()
+            .and_then(|id| app.saved_sessions.iter().find(|s| &s.id == id))
+            .and_then(|session| session.idle_timeout_minutes)
+        else {
+            continue;
+        };
+        if now.duration_since(tab.last_input) < Duration::from_secs(u64::from(minutes) * 60) {
+            continue;
+        }
+
+        tab.write_notice(&format!(
+            "— disconnected after {} min without input —",
+            minutes
+        ));
+        tasks.extend(disconnect_tab(tab, "idle timeout"));
+    }
+    if tasks.is_empty() {
+        None
+    } else {
+        Some(Task::batch(tasks))
+    }
+}
+
+/// Warns connected tabs shortly before their session's access expiry and,
+/// where the session asks for it, disconnects them once it has passed.
+pub(in crate::ui) fn check_access_expiry(app: &mut App) -> Option<Task<Message>> {
+    let now = chrono::Utc::now();
+    let mut tasks = Vec::new();
+    for tab in app.tabs.iter_mut().skip(1) {
+        if tab.state != SessionState::Connected || tab.session.is_none() {
+            continue;
+        }
+        let Some(session) = tab
+            .sftp_key
+            .as_ref()
+            .and_then(|id| app.saved_sessions.iter().find(|s| &s.id == id))
+        else {
+            continue;
+        };
+        let Some(expires_at) = session.access_expires_at else {
+            continue;
+        };
+        match crate::session::expiry::countdown(expires_at, now) {
+            Some(left)
+                if crate::session::expiry::is_ending(expires_at, now) && !tab.expiry_warned =>
+            {
+                tab.expiry_warned = true;
+                tab.write_notice(&format!("— access to {} ends in {} —", session.name, left));
+            }
+            None if session.disconnect_on_expiry => {
+                tab.write_notice("— disconnected: access expired —");
+                tasks.extend(disconnect_tab(tab, "access expired"));
+            }
+            _ => {}
+        }
+    }
+    if tasks.is_empty() {
+        None
+    } else {
+        Some(Task::batch(tasks))
+    }
+}
+
+fn disconnect_tab(tab: &mut SessionTab, reason: &'static str) -> Option<Task<Message>> {
+    tab.state = SessionState::Disconnected;
+    let ssh_handle = tab.ssh_handle.clone()?;
+    Some(Task::perform(
+        async move {
+            let guard = ssh_handle.lock().await;
+            if let Err(e) = guard.disconnect(reason).await {
+                tracing::warn!("{} disconnect failed: {}", reason, e);
+            }
+        },
+        |_| Message::Ignore,
+    ))
+}
+
+pub(in crate::ui) fn report_unexpected_disconnect(app: &mut App, tab_index: usize) {
+    let in_background = tab_index != app.active_tab || !app.window_focused;
+    if !in_background {
+        return;
+    }
+    let Some(tab) = app.tabs.get(tab_index) else {
+        return;
+    };
+    let title = tab.title.clone();
+    if let Err(e) = crate::platform::notify(
+        "Connection lost",
+        &format!("{} disconnected unexpectedly", title),
+    ) {
+        tracing::warn!("{}", e);
+    }
+    app.disconnect_toast = Some(DisconnectToast { tab_index, title });
+}
+
 pub(in crate::ui) fn apply_port_forwards(app: &App, session_id: &str) -> Task<Message> {
     let mut rules = match app
         .saved_sessions
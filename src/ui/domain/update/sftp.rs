@@ -0,0 +1,786 @@
+use iced::Task;
+use std::sync::atomic::Ordering;
+
+use super::{
+    cancel_transfer, compare_entries, details, dir_size, file_ops, handle_local_click,
+    handle_remote_click, join_local_path, join_remote_path, load_local_entries, log_tail, schedule,
+    schedule_transfer_tasks, start_delete, start_download, start_remote_list, start_rename,
+    start_upload, start_upload_files, trash, window,
+};
+use crate::ui::App;
+use crate::ui::message::{Message, SftpMessage};
+use crate::ui::state::{
+    SftpContextAction, SftpContextMenu, SftpListEvent, SftpPane, SftpTransferDirection,
+    SftpTransferStatus,
+};
+use crate::ui::virtual_list::ListViewport;
+
+/// The list under the window position `x` while a file is dragged.
+fn pane_at(app: &App, x: f32) -> SftpPane {
+    // SFTP Panel Right = window_width
+    // SFTP Panel Left = window_width - panel_width
+    // Content Left = SFTP Panel Left + 10 (handle) + 12 (padding)
+    // Content Width = panel_width - 10 - 24
+    // Split X = Content Left + Content Width / 2.0
+    let content_left = app.window_width as f32 - app.sftp_panel_width + 22.0;
+    let content_width = app.sftp_panel_width - 34.0;
+    let split_x = content_left + content_width / 2.0;
+    if x < split_x {
+        SftpPane::Local
+    } else {
+        SftpPane::Remote
+    }
+}
+
+/// Scrolls the list under a dragged file while it is held past the list's
+/// top or bottom edge, so a folder out of view can still be dropped on.
+pub(super) fn scroll_dragged_list(app: &mut App) -> Option<Task<Message>> {
+    app.sftp_file_dragging.as_ref()?;
+    let position = app.sftp_drag_position?;
+    let pane = pane_at(app, position.x);
+    let state = app.sftp_state_for_tab(app.active_tab)?;
+    let list = match pane {
+        SftpPane::Local => state.local_viewport,
+        SftpPane::Remote => state.remote_viewport,
+    };
+    if !app
+        .sftp_edge_scroll
+        .track(position.y, list.top, list.height)
+    {
+        return None;
+    }
+    let pixels = app.sftp_edge_scroll.step(std::time::Instant::now())?;
+    Some(iced::widget::operation::scroll_by(
+        crate::ui::views::sftp::list_id(pane),
+        iced::widget::scrollable::AbsoluteOffset { x: 0.0, y: pixels },
+    ))
+}
+
+pub(in crate::ui) fn handle(app: &mut App, message: SftpMessage) -> Task<Message> {
+    let deletes = matches!(
+        message,
+        SftpMessage::DeleteStart(..)
+            | SftpMessage::DeleteConfirm
+            | SftpMessage::ContextAction(_, _, SftpContextAction::Delete)
+    );
+    if deletes && app.viewer_mode() {
+        return Task::none();
+    }
+    match message {
+        SftpMessage::DragStart => {
+            app.sftp_dragging = true;
+        }
+        SftpMessage::DragEnd => {
+            app.sftp_dragging = false;
+            window::remember_sftp_panel_width(app);
+        }
+        SftpMessage::DragMove(point) => {
+            if app.sftp_dragging && app.window_width > 0 {
+                let max_width = (app.window_width as f32 - 240.0).max(320.0);
+                let width = (app.window_width as f32 - point.x).clamp(280.0, max_width);
+                app.sftp_panel_width = width;
+            }
+        }
+        SftpMessage::LocalPathChanged(path) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.local_path = path;
+                state.local_selected = None;
+                state.local_last_click = None;
+                state.context_menu = None;
+                let result = load_local_entries(&state.local_path);
+                match result {
+                    Ok(entries) => {
+                        state.local_entries = entries;
+                        state.local_error = None;
+                    }
+                    Err(err) => {
+                        state.local_entries.clear();
+                        state.local_error = Some(err);
+                    }
+                }
+            }
+        }
+        SftpMessage::RemotePathChanged(path) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.remote_path = path;
+                state.remote_selected = None;
+                state.remote_last_click = None;
+                state.context_menu = None;
+            }
+            if let Some(task) = start_remote_list(app, app.active_tab) {
+                return task;
+            }
+        }
+        SftpMessage::RemoteListing(tab_index, id, event) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(tab_index)
+                && state
+                    .remote_listing
+                    .as_ref()
+                    .is_some_and(|listing| listing.id == id)
+            {
+                match event {
+                    SftpListEvent::Opened(resolved_path) => {
+                        if let Some(path) = resolved_path {
+                            state.remote_path = path;
+                        }
+                    }
+                    SftpListEvent::Entries(mut entries) => {
+                        // Each batch is sorted on its own; the stable
+                        // sort then only has to merge two sorted runs.
+                        entries.sort_by(compare_entries);
+                        state.remote_entries.append(&mut entries);
+                        state.remote_entries.sort_by(compare_entries);
+                    }
+                    SftpListEvent::Finished => {
+                        if let Some(listing) = state.remote_listing.take() {
+                            state.cache_listing(listing.path);
+                            // Nothing worth keeping in the jobs panel.
+                            app.background_jobs.dismiss(listing.job);
+                        }
+                        return details::start(app, tab_index);
+                    }
+                    SftpListEvent::Failed(err) => {
+                        let listing = state.remote_listing.take();
+                        state.remote_entries.clear();
+                        state.remote_error = Some(err.clone());
+                        if let Some(listing) = listing {
+                            app.background_jobs.finish(listing.job, Err(err));
+                        }
+                    }
+                }
+            }
+        }
+        SftpMessage::EntryDetails(..) => {
+            return details::handle(app, message);
+        }
+        SftpMessage::ListScrolled(pane, viewport) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                let list = ListViewport {
+                    offset: viewport.absolute_offset().y,
+                    height: viewport.bounds().height,
+                    top: viewport.bounds().y,
+                };
+                match pane {
+                    SftpPane::Local => state.local_viewport = list,
+                    SftpPane::Remote => state.remote_viewport = list,
+                }
+            }
+        }
+        SftpMessage::SetFilter(pane, preset) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                match pane {
+                    SftpPane::Local => state.local_filter = preset,
+                    SftpPane::Remote => state.remote_filter = preset,
+                }
+            }
+        }
+        SftpMessage::PanelCursorMoved(point) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.panel_cursor = Some(point);
+            }
+        }
+        SftpMessage::LocalEntryPressed(name, is_dir) => {
+            return handle_local_click(app, name, is_dir);
+        }
+        SftpMessage::RemoteEntryPressed(name, is_dir) => {
+            return handle_remote_click(app, name, is_dir);
+        }
+        SftpMessage::FileDragStart(pane, name) => {
+            // Also select the item when dragging starts
+            let mut tasks = Vec::new();
+            match pane {
+                SftpPane::Local => {
+                    let is_dir = app
+                        .sftp_state_for_tab(app.active_tab)
+                        .and_then(|s| s.local_entries.iter().find(|e| e.name == name))
+                        .map(|e| e.is_dir)
+                        .unwrap_or(false);
+                    tasks.push(handle_local_click(app, name.clone(), is_dir));
+                }
+                SftpPane::Remote => {
+                    let is_dir = app
+                        .sftp_state_for_tab(app.active_tab)
+                        .and_then(|s| s.remote_entries.iter().find(|e| e.name == name))
+                        .map(|e| e.is_dir)
+                        .unwrap_or(false);
+                    tasks.push(handle_remote_click(app, name.clone(), is_dir));
+                }
+            }
+            app.sftp_file_dragging = Some((pane, name));
+            if !tasks.is_empty() {
+                return Task::batch(tasks);
+            }
+        }
+        SftpMessage::FileDragUpdate(position) => {
+            app.sftp_drag_position = Some(position);
+        }
+        SftpMessage::FileDragEnd => {
+            app.sftp_edge_scroll.stop();
+            if let Some((source_pane, name)) = app.sftp_file_dragging.take() {
+                let cursor_x = app.sftp_drag_position.map(|p| p.x).unwrap_or(0.0);
+                let target_pane = pane_at(app, cursor_x);
+
+                // Dropped on a folder in the same pane: move it there.
+                let hovered = app.sftp_hovered_file.clone();
+                if source_pane == target_pane
+                    && let Some((hovered_pane, folder)) = hovered
+                    && hovered_pane == source_pane
+                {
+                    app.sftp_drag_position = None;
+                    return file_ops::move_into(app, source_pane, name, folder);
+                }
+                if source_pane != target_pane {
+                    match (source_pane, target_pane) {
+                        (SftpPane::Local, SftpPane::Remote) => {
+                            if let Some(task) = start_upload(app, name) {
+                                return task;
+                            }
+                        }
+                        (SftpPane::Remote, SftpPane::Local) => {
+                            if let Some(task) = start_download(app, app.active_tab, name, None) {
+                                return task;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                app.sftp_drag_position = None;
+            }
+        }
+        SftpMessage::FileHover(hovered) => {
+            app.sftp_hovered_file = hovered;
+        }
+        SftpMessage::OpenContextMenu(pane, name) => {
+            let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) else {
+                return Task::none();
+            };
+            let position = state.panel_cursor.unwrap_or(iced::Point::new(16.0, 16.0));
+            if !name.is_empty() {
+                match pane {
+                    SftpPane::Local => {
+                        state.local_selected = Some(name.clone());
+                    }
+                    SftpPane::Remote => {
+                        state.remote_selected = Some(name.clone());
+                    }
+                }
+            }
+            state.context_menu = Some(SftpContextMenu {
+                pane,
+                name,
+                position,
+            });
+        }
+        SftpMessage::CloseContextMenu => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.context_menu = None;
+                if state.rename_target.is_some() {
+                    state.rename_target = None;
+                    state.rename_value.clear();
+                }
+            }
+        }
+        SftpMessage::ContextAction(pane, name, action) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.context_menu = None;
+            }
+
+            if matches!(
+                action,
+                SftpContextAction::Cut | SftpContextAction::Copy | SftpContextAction::Paste
+            ) {
+                return file_ops::handle(app, SftpMessage::ContextAction(pane, name, action));
+            }
+            if action == SftpContextAction::Schedule {
+                return schedule::handle(
+                    app,
+                    Message::Sftp(SftpMessage::ContextAction(pane, name, action)),
+                );
+            }
+            if action == SftpContextAction::CalculateSize {
+                return dir_size::handle(app, SftpMessage::ContextAction(pane, name, action));
+            }
+            if pane == SftpPane::Local && action == SftpContextAction::RevealLocal {
+                let Some(state) = app.sftp_state_for_tab(app.active_tab) else {
+                    return Task::none();
+                };
+                let path = std::path::PathBuf::from(join_local_path(&state.local_path, &name));
+                return Task::perform(
+                    async move { crate::platform::reveal_in_file_manager(&path) },
+                    Message::LocalFileRevealed,
+                );
+            }
+            if action == SftpContextAction::Refresh {
+                if pane == SftpPane::Remote
+                    && let Some(state) = app.sftp_state_for_tab_mut(app.active_tab)
+                {
+                    state.invalidate_remote_cache();
+                }
+                if let Some(state) = app.sftp_state_for_tab(app.active_tab) {
+                    let path = match pane {
+                        SftpPane::Local => state.local_path.clone(),
+                        SftpPane::Remote => state.remote_path.clone(),
+                    };
+                    let message = match pane {
+                        SftpPane::Local => Message::Sftp(SftpMessage::LocalPathChanged(path)),
+                        SftpPane::Remote => Message::Sftp(SftpMessage::RemotePathChanged(path)),
+                    };
+                    return Task::done(message);
+                }
+                return Task::none();
+            }
+
+            if pane == SftpPane::Local && action == SftpContextAction::Upload {
+                if let Some(task) = start_upload(app, name.clone()) {
+                    return task;
+                }
+            }
+            if pane == SftpPane::Remote && action == SftpContextAction::Tail {
+                let remote_path = app
+                    .sftp_state_for_tab(app.active_tab)
+                    .map(|state| join_remote_path(&state.remote_path, &name));
+                if let Some(remote_path) = remote_path {
+                    return log_tail::start(app, remote_path);
+                }
+            }
+            if pane == SftpPane::Remote && action == SftpContextAction::Download {
+                if let Some(task) = start_download(app, app.active_tab, name.clone(), None) {
+                    return task;
+                }
+            }
+            if pane == SftpPane::Remote && action == SftpContextAction::DownloadTo {
+                let tab_index = app.active_tab;
+                let start_dir = app
+                    .sftp_state_for_tab(tab_index)
+                    .map(|state| state.local_path.clone())
+                    .unwrap_or_default();
+                return Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Download to")
+                            .set_directory(start_dir)
+                            .pick_folder()
+                            .await
+                            .map(|handle| handle.path().to_path_buf())
+                    },
+                    move |folder| {
+                        Message::Sftp(SftpMessage::DownloadTo(tab_index, name.clone(), folder))
+                    },
+                );
+            }
+            if action == SftpContextAction::UploadFrom {
+                let tab_index = app.active_tab;
+                let start_dir = app
+                    .sftp_state_for_tab(tab_index)
+                    .map(|state| state.local_path.clone())
+                    .unwrap_or_default();
+                return Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Upload to current remote folder")
+                            .set_directory(start_dir)
+                            .pick_files()
+                            .await
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|handle| handle.path().to_path_buf())
+                            .collect()
+                    },
+                    move |files| Message::Sftp(SftpMessage::UploadFrom(tab_index, files)),
+                );
+            }
+            if action == SftpContextAction::Rename {
+                let is_dir = match pane {
+                    SftpPane::Local => app
+                        .sftp_state_for_tab(app.active_tab)
+                        .and_then(|state| {
+                            state
+                                .local_entries
+                                .iter()
+                                .find(|entry| entry.name == name)
+                                .map(|entry| entry.is_dir)
+                        })
+                        .unwrap_or(false),
+                    SftpPane::Remote => app
+                        .sftp_state_for_tab(app.active_tab)
+                        .and_then(|state| {
+                            state
+                                .remote_entries
+                                .iter()
+                                .find(|entry| entry.name == name)
+                                .map(|entry| entry.is_dir)
+                        })
+                        .unwrap_or(false),
+                };
+                if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                    state.rename_target = Some(crate::ui::state::SftpPendingAction {
+                        pane,
+                        name: name.clone(),
+                        is_dir,
+                    });
+                    state.rename_value = name.clone();
+                }
+                return iced::widget::operation::focus(app.sftp_rename_input_id.clone());
+            }
+            if action == SftpContextAction::Delete {
+                let is_dir = match pane {
+                    SftpPane::Local => app
+                        .sftp_state_for_tab(app.active_tab)
+                        .and_then(|state| {
+                            state
+                                .local_entries
+                                .iter()
+                                .find(|entry| entry.name == name)
+                                .map(|entry| entry.is_dir)
+                        })
+                        .unwrap_or(false),
+                    SftpPane::Remote => app
+                        .sftp_state_for_tab(app.active_tab)
+                        .and_then(|state| {
+                            state
+                                .remote_entries
+                                .iter()
+                                .find(|entry| entry.name == name)
+                                .map(|entry| entry.is_dir)
+                        })
+                        .unwrap_or(false),
+                };
+                if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                    state.delete_target =
+                        Some(crate::ui::state::SftpPendingAction { pane, name, is_dir });
+                }
+            }
+        }
+        SftpMessage::DownloadTo(tab_index, name, folder) => {
+            let Some(folder) = folder else {
+                return Task::none();
+            };
+            let folder = folder.to_string_lossy().into_owned();
+            if let Some(task) = start_download(app, tab_index, name, Some(folder)) {
+                return task;
+            }
+        }
+        SftpMessage::UploadFrom(tab_index, files) => {
+            if let Some(task) = start_upload_files(app, tab_index, files, None) {
+                return task;
+            }
+        }
+        SftpMessage::TransferCancel(id) => {
+            if let Some(task) = cancel_transfer(app, app.active_tab, id) {
+                return task;
+            }
+        }
+        SftpMessage::TransferPause(id) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                if let Some(transfer) = state
+                    .transfers
+                    .iter_mut()
+                    .find(|transfer| transfer.id == id)
+                {
+                    if transfer.status == SftpTransferStatus::Uploading {
+                        transfer.pause_flag.store(true, Ordering::SeqCst);
+                        transfer.status = SftpTransferStatus::Paused;
+                    }
+                }
+                if let Some(task) = schedule_transfer_tasks(app, app.active_tab) {
+                    return task;
+                }
+            }
+        }
+        SftpMessage::TransferResume(id) => {
+            let limits = app.transfer_limits_for_tab(app.active_tab);
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                let (uploads, downloads) = state.running_transfers();
+                if let Some(transfer) = state
+                    .transfers
+                    .iter_mut()
+                    .find(|transfer| transfer.id == id)
+                {
+                    let upload = transfer.direction == SftpTransferDirection::Upload;
+                    if transfer.status == SftpTransferStatus::Paused
+                        && limits.allows(upload, uploads, downloads)
+                    {
+                        transfer.pause_flag.store(false, Ordering::SeqCst);
+                        transfer.pause_notify.notify_waiters();
+                        transfer.status = SftpTransferStatus::Uploading;
+                    }
+                }
+            }
+        }
+        SftpMessage::TransferRetry(id) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                if let Some(transfer) = state
+                    .transfers
+                    .iter_mut()
+                    .find(|transfer| transfer.id == id)
+                {
+                    transfer.status = SftpTransferStatus::Queued;
+                    transfer.bytes_sent = 0;
+                    transfer.bytes_total = 0;
+                    transfer.started_at = None;
+                    transfer.last_update = None;
+                    transfer.last_bytes_sent = 0;
+                    transfer.last_rate_bps = None;
+                    transfer.pause_flag.store(false, Ordering::SeqCst);
+                }
+                if let Some(task) = schedule_transfer_tasks(app, app.active_tab) {
+                    return task;
+                }
+            }
+        }
+        SftpMessage::TransferDefaultsChanged(defaults) => {
+            let Some(mut session) = app.session_for_tab(app.active_tab).cloned() else {
+                return Task::none();
+            };
+            session.transfer_defaults = defaults;
+            if let Err(e) = app
+                .session_storage
+                .save_session(session, &mut app.saved_sessions)
+                && let Some(state) = app.sftp_state_for_tab_mut(app.active_tab)
+            {
+                state.remote_error = Some(format!("Failed to save: {}", e));
+            }
+        }
+        SftpMessage::TransferClearDone => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.transfers.retain(|transfer| {
+                    !matches!(
+                        transfer.status,
+                        SftpTransferStatus::Completed
+                            | SftpTransferStatus::Skipped
+                            | SftpTransferStatus::Failed(_)
+                            | SftpTransferStatus::Canceled
+                    )
+                });
+            }
+        }
+        SftpMessage::RenameStart(pane, name, is_dir) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.rename_target = Some(crate::ui::state::SftpPendingAction {
+                    pane,
+                    name: name.clone(),
+                    is_dir,
+                });
+                state.rename_value = name;
+            }
+            return iced::widget::operation::focus(app.sftp_rename_input_id.clone());
+        }
+        SftpMessage::RenameInput(value) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.rename_value = value;
+            }
+        }
+        SftpMessage::RenameCancel => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.rename_target = None;
+                state.rename_value.clear();
+            }
+        }
+        SftpMessage::RenameConfirm => {
+            if let Some(task) = start_rename(app) {
+                return task;
+            }
+        }
+        SftpMessage::FileOpFinished(_, _, _) => {
+            return file_ops::handle(app, message);
+        }
+        SftpMessage::DirSize(..) => {
+            return dir_size::handle(app, message);
+        }
+        SftpMessage::RenameFinished(tab_index, result) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+                let target = state.rename_target.clone();
+                state.rename_target = None;
+                state.rename_value.clear();
+                match result {
+                    Ok(()) => {
+                        if let Some(target) = target {
+                            return match target.pane {
+                                SftpPane::Local => Task::done(Message::Sftp(
+                                    SftpMessage::LocalPathChanged(state.local_path.clone()),
+                                )),
+                                SftpPane::Remote => {
+                                    state.invalidate_remote_cache();
+                                    if let Some(task) = start_remote_list(app, tab_index) {
+                                        task
+                                    } else {
+                                        Task::none()
+                                    }
+                                }
+                            };
+                        }
+                    }
+                    Err(err) => {
+                        state.remote_error = Some(err);
+                    }
+                }
+            }
+        }
+        SftpMessage::DeleteStart(pane, name, is_dir) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.delete_target =
+                    Some(crate::ui::state::SftpPendingAction { pane, name, is_dir });
+            }
+        }
+        SftpMessage::DeleteCancel => {
+            if let Some(state) = app.sftp_state_for_tab_mut(app.active_tab) {
+                state.delete_target = None;
+            }
+        }
+        SftpMessage::DeleteConfirm => {
+            if let Some(task) = start_delete(app) {
+                return task;
+            }
+        }
+        SftpMessage::DeleteFinished(tab_index, result) => {
+            if let Some(state) = app.sftp_state_for_tab_mut(tab_index) {
+                let target = state.delete_target.clone();
+                state.delete_target = None;
+                match result {
+                    Ok(()) => {
+                        if let Some(target) = target {
+                            return match target.pane {
+                                SftpPane::Local => Task::done(Message::Sftp(
+                                    SftpMessage::LocalPathChanged(state.local_path.clone()),
+                                )),
+                                SftpPane::Remote => {
+                                    state.invalidate_remote_cache();
+                                    if let Some(task) = start_remote_list(app, tab_index) {
+                                        task
+                                    } else {
+                                        Task::none()
+                                    }
+                                }
+                            };
+                        }
+                    }
+                    Err(err) => {
+                        state.remote_error = Some(err);
+                    }
+                }
+            }
+        }
+        SftpMessage::TransferUpdate(update) => {
+            let status = update.status.clone();
+            let mut should_refresh = false;
+            let mut error_message: Option<String> = None;
+            let mut job = None;
+            if let Some(state) = app.sftp_state_for_tab_mut(update.tab_index) {
+                if let Some(transfer) = state
+                    .transfers
+                    .iter_mut()
+                    .find(|transfer| transfer.id == update.id)
+                {
+                    job = transfer.job;
+                    transfer.bytes_sent = update.bytes_sent;
+                    transfer.bytes_total = update.bytes_total;
+                    let now = std::time::Instant::now();
+                    if transfer.started_at.is_none() {
+                        transfer.started_at = Some(now);
+                    }
+                    if let Some(last_update) = transfer.last_update {
+                        let elapsed = now.duration_since(last_update);
+                        if elapsed.as_millis() >= 200 {
+                            let delta_bytes =
+                                update.bytes_sent.saturating_sub(transfer.last_bytes_sent);
+                            let rate = (delta_bytes as f64 / elapsed.as_secs_f64()) as u64;
+                            transfer.last_rate_bps = Some(rate);
+                            transfer.last_update = Some(now);
+                            transfer.last_bytes_sent = update.bytes_sent;
+                        }
+                    } else {
+                        transfer.last_update = Some(now);
+                        transfer.last_bytes_sent = update.bytes_sent;
+                    }
+                    if let Some(status_value) = status.clone() {
+                        if status_value == SftpTransferStatus::Completed {
+                            // Marks the end for the average rate shown
+                            // once the transfer is done.
+                            transfer.last_update = Some(now);
+                        }
+                        transfer.status = status_value;
+                    }
+                    let upload_stopped = transfer.direction == SftpTransferDirection::Upload
+                        && matches!(
+                            status,
+                            Some(
+                                SftpTransferStatus::Completed
+                                    | SftpTransferStatus::Canceled
+                                    | SftpTransferStatus::Paused
+                            )
+                        );
+                    if upload_stopped {
+                        // Even a canceled upload may have left a partial
+                        // file behind.
+                        state.invalidate_remote_cache();
+                        should_refresh = update.tab_index == app.active_tab && app.sftp_panel_open;
+                    }
+                    if let Some(SftpTransferStatus::Failed(error)) = status.clone() {
+                        error_message = Some(error);
+                    }
+                }
+            }
+            if let Some(job) = job {
+                if update.bytes_total > 0 {
+                    app.background_jobs
+                        .set_progress(job, update.bytes_sent as f32 / update.bytes_total as f32);
+                }
+                match &status {
+                    Some(SftpTransferStatus::Completed | SftpTransferStatus::Skipped) => {
+                        app.background_jobs.finish(job, Ok(()))
+                    }
+                    Some(SftpTransferStatus::Failed(error)) => {
+                        app.background_jobs.finish(job, Err(error.clone()))
+                    }
+                    Some(SftpTransferStatus::Canceled) => {
+                        app.background_jobs.cancel(job);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(message) = error_message {
+                if let Some(state) = app.sftp_state_for_tab_mut(update.tab_index) {
+                    state.remote_error = Some(message);
+                }
+            }
+
+            let mut tasks = Vec::new();
+            if should_refresh {
+                if let Some(task) = start_remote_list(app, app.active_tab) {
+                    tasks.push(task);
+                }
+            }
+            if matches!(
+                status,
+                Some(
+                    SftpTransferStatus::Completed
+                        | SftpTransferStatus::Skipped
+                        | SftpTransferStatus::Failed(_)
+                        | SftpTransferStatus::Canceled
+                        | SftpTransferStatus::Paused
+                )
+            ) {
+                if let Some(task) = schedule_transfer_tasks(app, update.tab_index) {
+                    tasks.push(task);
+                }
+                // Details wait while transfers run; pick them up again.
+                tasks.push(details::pump(app, update.tab_index));
+            }
+            if !tasks.is_empty() {
+                return Task::batch(tasks);
+            }
+        }
+        SftpMessage::ShowJobs(_) => {
+            return schedule::handle(app, Message::Sftp(message));
+        }
+        SftpMessage::MovedToTrash(..)
+        | SftpMessage::UndoTrash
+        | SftpMessage::UndoTrashFinished(..)
+        | SftpMessage::TrashToastDismiss => {
+            return trash::handle(app, message);
+        }
+    }
+
+    Task::none()
+}
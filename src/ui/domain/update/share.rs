@@ -0,0 +1,98 @@
+use iced::Task;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::terminal::share::{self, ShareHost};
+use crate::ui::App;
+use crate::ui::message::{ActiveView, Message, TerminalMessage};
+use crate::ui::state::{SessionState, SessionTab};
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::ShareTab(tab_index) => {
+            app.terminal_menu = None;
+            if !app.app_settings.allow_terminal_sharing {
+                return Task::none();
+            }
+            let Some(tab) = app.tabs.get(tab_index).filter(|tab| tab.share.is_none()) else {
+                return Task::none();
+            };
+            // Viewers start from the screen as it is now, redrawn from the
+            // top left.
+            let screen = format!("\x1b[H\x1b[2J{}", tab.emulator.screen_text().join("\r\n"));
+            let title = tab.title.clone();
+            Task::perform(
+                async move { ShareHost::start(title, screen.into_bytes()).await },
+                move |result| Message::ShareStarted(tab_index, result.map(Arc::new)),
+            )
+        }
+        Message::ShareStarted(tab_index, result) => match result {
+            Ok(host) => {
+                let Some(tab) = app.tabs.get_mut(tab_index) else {
+                    return Task::none();
+                };
+                let link = host.link().to_string();
+                tab.share = Some(host);
+                Task::batch([iced::clipboard::write(link), app.focus_terminal_ime()])
+            }
+            Err(e) => {
+                app.last_error = Some((e, std::time::Instant::now()));
+                Task::none()
+            }
+        },
+        Message::StopSharing(tab_index) => {
+            app.terminal_menu = None;
+            if let Some(tab) = app.tabs.get_mut(tab_index) {
+                tab.share = None;
+            }
+            Task::none()
+        }
+        Message::CopyShareLink(tab_index) => app
+            .tabs
+            .get(tab_index)
+            .and_then(|tab| tab.share.as_ref())
+            .map_or_else(Task::none, |host| {
+                iced::clipboard::write(host.link().to_string())
+            }),
+        Message::WatchShare(link) => {
+            app.show_quick_connect = false;
+            Task::perform(async move { share::watch(&link).await }, |result| {
+                Message::WatchStarted(result.map(|(title, rx)| (title, Arc::new(Mutex::new(rx)))))
+            })
+        }
+        Message::WatchStarted(Err(e)) => {
+            app.last_error = Some((e, std::time::Instant::now()));
+            Task::none()
+        }
+        Message::WatchStarted(Ok((title, rx))) => {
+            // No session, so keystrokes in this tab go nowhere.
+            let mut tab = SessionTab::new(&format!("Watching {}", title));
+            tab.state = SessionState::Connected;
+            tab.rx = Some(rx.clone());
+            // Queries in the stream were already answered by the host.
+            drop(tab.emulator.take_output_receiver());
+            app.tabs.push(tab);
+            let tab_index = app.tabs.len() - 1;
+            app.active_tab = tab_index;
+            app.active_view = ActiveView::Terminal;
+            app.last_terminal_tab = tab_index;
+
+            let read_task = Task::perform(
+                async move {
+                    let mut guard = rx.lock().await;
+                    (tab_index, guard.recv().await.unwrap_or_default())
+                },
+                |(idx, data)| Message::Terminal(TerminalMessage::DataReceived(idx, data)),
+            );
+            let mut commands = vec![read_task, app.focus_terminal_ime()];
+            if app.window_width > 0 && app.window_height > 0 {
+                let (cols, rows) = app.terminal_grid_size(app.window_width, app.window_height);
+                commands.push(Task::done(Message::Terminal(TerminalMessage::Resize(
+                    cols, rows,
+                ))));
+            }
+            Task::batch(commands)
+        }
+        _ => Task::none(),
+    }
+}
@@ -0,0 +1,176 @@
+use iced::Task;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::forward_terminal_responses;
+use crate::core::backend::SessionBackend;
+use crate::core::session::Session;
+use crate::ssh::SshSession;
+use crate::ui::App;
+use crate::ui::message::{ActiveView, Message, TerminalMessage};
+use crate::ui::state::{SessionState, SessionTab};
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::DuplicateTab(tab_index) => {
+            // Without OSC 7 from the shell the duplicate starts at home.
+            let directory = app
+                .tabs
+                .get(tab_index)
+                .and_then(|tab| tab.emulator.working_directory());
+            open_sibling(app, tab_index, directory)
+        }
+        Message::NewShellOnHost(tab_index) => open_sibling(app, tab_index, None),
+        Message::SiblingShellOpened(tab_index, result) => {
+            let Some(tab) = app.tabs.get_mut(tab_index) else {
+                return Task::none();
+            };
+            let (channel_id, rx) = match result {
+                Ok(opened) => opened,
+                Err(e) => {
+                    tab.state = SessionState::Failed(e);
+                    return Task::none();
+                }
+            };
+            let Some(ssh_handle) = tab.ssh_handle.clone() else {
+                return Task::none();
+            };
+            tab.session = Some(Session::new(SessionBackend::Ssh {
+                session: ssh_handle,
+                channel_id,
+            }));
+            tab.rx = Some(rx.clone());
+            tab.state = SessionState::Connected;
+            tab.last_input = std::time::Instant::now();
+            forward_terminal_responses(tab);
+
+            let read_task = Task::perform(
+                async move {
+                    let mut guard = rx.lock().await;
+                    (tab_index, guard.recv().await.unwrap_or_default())
+                },
+                |(idx, data)| Message::Terminal(TerminalMessage::DataReceived(idx, data)),
+            );
+            let mut commands = vec![read_task];
+            if app.active_tab == tab_index && app.window_width > 0 && app.window_height > 0 {
+                let (cols, rows) = app.terminal_grid_size(app.window_width, app.window_height);
+                commands.push(Task::done(Message::Terminal(TerminalMessage::Resize(
+                    cols, rows,
+                ))));
+            }
+            Task::batch(commands)
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Opens a tab with another shell on the connection behind `tab_index`,
+/// so it needs no new login.
+fn open_sibling(app: &mut App, tab_index: usize, directory: Option<String>) -> Task<Message> {
+    app.terminal_menu = None;
+    let Some(source) = app
+        .tabs
+        .get(tab_index)
+        .filter(|tab| tab.state == SessionState::Connected)
+    else {
+        return Task::none();
+    };
+    let Some(ssh_handle) = source.ssh_handle.clone() else {
+        return Task::none();
+    };
+    let mut tab = SessionTab::new(&source.title);
+    tab.sftp_key = source.sftp_key.clone();
+    share_connection(&mut tab, source, ssh_handle.clone());
+    open_shell_tab(app, tab, ssh_handle, directory)
+}
+
+/// Opens a tab for saved session `session_id` with a shell on an already
+/// open connection to the same host, as OpenSSH's ControlMaster would.
+pub(super) fn open_on_shared(
+    app: &mut App,
+    title: &str,
+    session_id: &str,
+    ssh_handle: Arc<Mutex<SshSession>>,
+) -> Task<Message> {
+    let mut tab = SessionTab::new(title);
+    tab.sftp_key = Some(session_id.to_string());
+    match app.tabs.iter().find(|tab| {
+        tab.ssh_handle
+            .as_ref()
+            .is_some_and(|handle| Arc::ptr_eq(handle, &ssh_handle))
+    }) {
+        Some(source) => share_connection(&mut tab, source, ssh_handle.clone()),
+        None => {
+            if let Ok(ssh) = ssh_handle.try_lock() {
+                tab.bandwidth = Some(ssh.bandwidth());
+                tab.connection_status = Some(ssh.status());
+            }
+            tab.ssh_handle = Some(ssh_handle.clone());
+        }
+    }
+    open_shell_tab(app, tab, ssh_handle, None)
+}
+
+// The SFTP channels, traffic counters and status belong to the connection, so a
+// tab on it uses the same ones.
+fn share_connection(tab: &mut SessionTab, source: &SessionTab, ssh_handle: Arc<Mutex<SshSession>>) {
+    tab.ssh_handle = Some(ssh_handle);
+    tab.sftp_session = source.sftp_session.clone();
+    tab.sftp_list_channel = source.sftp_list_channel.clone();
+    tab.bandwidth = source.bandwidth.clone();
+    tab.connection_status = source.connection_status.clone();
+}
+
+fn open_shell_tab(
+    app: &mut App,
+    mut tab: SessionTab,
+    ssh_handle: Arc<Mutex<SshSession>>,
+    directory: Option<String>,
+) -> Task<Message> {
+    tab.state = SessionState::Connecting(std::time::Instant::now());
+    app.tabs.push(tab);
+    let new_index = app.tabs.len() - 1;
+    app.active_tab = new_index;
+    app.active_view = ActiveView::Terminal;
+    app.last_terminal_tab = new_index;
+
+    let (cols, rows) = if app.window_width > 0 && app.window_height > 0 {
+        app.terminal_grid_size(app.window_width, app.window_height)
+    } else {
+        (80, 24)
+    };
+    Task::batch([
+        Task::perform(
+            async move {
+                let mut session = ssh_handle.lock().await;
+                session
+                    .open_extra_shell(directory.as_deref(), cols as u32, rows as u32)
+                    .await
+                    .map(|(id, rx)| (id, Arc::new(Mutex::new(rx))))
+                    .map_err(|e| format!("Failed to open shell: {}", e))
+            },
+            move |result| Message::SiblingShellOpened(new_index, result),
+        ),
+        app.focus_terminal_ime(),
+    ])
+}
+
+/// Closes the tab's shell. The connection stays up while other tabs opened
+/// on it are still using it.
+pub(super) fn close_channel(tab: &SessionTab) -> Task<Message> {
+    let Some(session) = &tab.session else {
+        return Task::none();
+    };
+    let SessionBackend::Ssh {
+        session,
+        channel_id,
+    } = &*session.backend
+    else {
+        return Task::none();
+    };
+    let (session, channel_id) = (session.clone(), *channel_id);
+    Task::perform(
+        async move { session.lock().await.close_shell(channel_id).await },
+        |_| Message::Ignore,
+    )
+}
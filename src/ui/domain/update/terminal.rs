@@ -1,20 +1,77 @@
 use iced::Task;
 
+use crate::session::config::BellMode;
+use crate::settings::ShellExitAction;
 use crate::terminal::input::map_key_to_input;
+use crate::terminal::keymap::{self, KeyAction};
+use crate::terminal::scroll::{self, AltScreenScroll};
 use crate::ui::App;
-use crate::ui::message::{ActiveView, Message};
-use crate::ui::state::SessionState;
+use crate::ui::message::{ActiveView, Message, TerminalMessage};
+use crate::ui::state::{LocalProcess, SessionState, SessionTab, ShellExit, TerminalMenu};
 
-pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Message>> {
+pub(in crate::ui) fn handle(app: &mut App, message: TerminalMessage) -> Option<Task<Message>> {
     match message {
-        Message::TerminalDataReceived(tab_index, data) => {
+        TerminalMessage::DataReceived(tab_index, data) => {
             let next_rx = app.tabs.get(tab_index).and_then(|tab| tab.rx.clone());
+            let in_background = tab_index != app.active_tab || !app.window_focused;
+            let pause_scroll = app.app_settings.pause_scroll_in_history;
+            let mut first_activity = false;
             if let Some(tab) = app.tabs.get_mut(tab_index) {
                 if data.is_empty() {
+                    // Idle timeouts and retries change the state before the stream
+                    // ends, so only a drop from Connected is unexpected. A shell
+                    // exiting on a connection that is still up is not a drop.
+                    // The status is read without the session lock, which may
+                    // be held by a transfer or a write.
+                    let connection_up = tab
+                        .connection_status
+                        .as_ref()
+                        .is_none_or(|status| status.is_up());
+                    let dropped = tab.state == SessionState::Connected
+                        && tab.ssh_handle.is_some()
+                        && !connection_up;
+                    // A shell opened next to the main one ends its stream
+                    // when it exits, without a status.
+                    if tab.state == SessionState::Connected
+                        && tab.ssh_handle.is_some()
+                        && connection_up
+                    {
+                        tab.shell_exit = Some(ShellExit { status: None });
+                    }
                     tab.state = SessionState::Disconnected;
+                    if let Some(process) = tab.local_process.clone() {
+                        return Some(local_shell_exited(
+                            tab,
+                            tab_index,
+                            &process,
+                            app.app_settings.local_shell_exit,
+                        ));
+                    }
+                    if dropped {
+                        super::sessions::report_unexpected_disconnect(app, tab_index);
+                        super::reconnect::schedule(app, tab_index);
+                    }
                     return Some(Task::none());
                 }
 
+                tab.input_latency.output_received(std::time::Instant::now());
+                if let Some(share) = &tab.share {
+                    share.send(&data);
+                }
+
+                if data.starts_with(crate::terminal::output::THROTTLE_NOTICE) {
+                    tab.output_throttled_at = Some(std::time::Instant::now());
+                }
+
+                if tab.emulator.get_scroll_state().1 > 0 {
+                    if pause_scroll {
+                        tab.unseen_lines += data.iter().filter(|&&b| b == b'\n').count();
+                    } else {
+                        tab.emulator.scroll_to_bottom();
+                        tab.unseen_lines = 0;
+                    }
+                }
+
                 if let Some(tx) = &tab.parser_tx {
                     if tx.send(data.clone()).is_err() {
                         tracing::warn!("parser thread unavailable, falling back to direct parse");
@@ -25,9 +82,25 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
                     tab.emulator.process_input(&data);
                     tab.mark_full_damage();
                 }
+
+                if in_background && !tab.has_activity {
+                    tab.has_activity = true;
+                    first_activity = true;
+                }
             }
+            // Flash the taskbar entry / bounce the dock when a tab wakes up
+            // while the window is in the background.
+            let attention = match app.main_window {
+                Some(id) if first_activity && !app.window_focused => {
+                    iced::window::request_user_attention(
+                        id,
+                        Some(iced::window::UserAttention::Informational),
+                    )
+                }
+                _ => Task::none(),
+            };
             if let Some(rx) = next_rx {
-                return Some(Task::perform(
+                let read_task = Task::perform(
                     async move {
                         let mut guard = rx.lock().await;
                         match guard.recv().await {
@@ -56,12 +129,13 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
                             }
                         }
                     },
-                    |(idx, data)| Message::TerminalDataReceived(idx, data),
-                ));
+                    |(idx, data)| Message::Terminal(TerminalMessage::DataReceived(idx, data)),
+                );
+                return Some(Task::batch([attention, read_task]));
             }
-            Some(Task::none())
+            Some(attention)
         }
-        Message::TerminalDamaged(tab_index, damage) => {
+        TerminalMessage::Damaged(tab_index, damage) => {
             if let Some(tab) = app.tabs.get_mut(tab_index) {
                 match damage {
                     crate::terminal::TerminalDamage::Full => {
@@ -77,35 +151,45 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
             }
             Some(Task::none())
         }
-        Message::TerminalMousePress(col, line) => {
+        TerminalMessage::MousePress(col, line) => {
+            app.selection_actions = None;
             if let Some(tab) = app.tabs.get_mut(app.active_tab) {
                 tab.emulator.on_mouse_press(col, line);
                 tab.mark_full_damage();
             }
             Some(Task::none())
         }
-        Message::TerminalMouseDrag(col, line) => {
+        TerminalMessage::MouseDrag(col, line) => {
             if let Some(tab) = app.tabs.get_mut(app.active_tab) {
                 tab.emulator.on_mouse_drag(col, line);
                 tab.mark_full_damage();
             }
             Some(Task::none())
         }
-        Message::TerminalMouseRelease => {
+        TerminalMessage::DragScroll(lines, col, line) => {
+            if let Some(tab) = app.tabs.get_mut(app.active_tab) {
+                tab.emulator.scroll(lines);
+                tab.emulator.on_mouse_drag(col, line);
+                tab.mark_full_damage();
+            }
+            Some(Task::none())
+        }
+        TerminalMessage::MouseRelease(position) => {
             if let Some(tab) = app.tabs.get_mut(app.active_tab) {
                 tab.emulator.on_mouse_release();
                 tab.mark_full_damage();
             }
+            super::selection::offer(app, position);
             Some(Task::none())
         }
-        Message::TerminalMouseDoubleClick(col, line) => {
+        TerminalMessage::MouseDoubleClick(col, line) => {
             if let Some(tab) = app.tabs.get_mut(app.active_tab) {
                 tab.emulator.on_mouse_double_click(col, line);
                 tab.mark_full_damage();
             }
             Some(Task::none())
         }
-        Message::TerminalResize(cols, rows) => {
+        TerminalMessage::Resize(cols, rows) => {
             if let Some(tab) = app.tabs.get_mut(app.active_tab) {
                 tab.emulator.resize(cols, rows);
                 tab.ensure_line_caches(rows);
@@ -117,57 +201,335 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
                         async move {
                             let _ = session.resize(cols as u16, rows as u16).await;
                         },
-                        |_| Message::TerminalInput(vec![]),
+                        |_| Message::Terminal(TerminalMessage::Input(vec![])),
                     ));
                 }
             }
             Some(Task::none())
         }
-        Message::ScrollWheel(delta) => {
+        TerminalMessage::ScrollWheel(delta) => {
+            let options = app.app_settings.scroll;
+            let shift = app.modifiers.shift();
             if let Some(tab) = app.tabs.get_mut(app.active_tab) {
-                if delta.abs() > 0.001 {
-                    let clamped_delta = delta.clamp(-100.0, 100.0);
+                if !tab.emulator.wraps_lines() {
+                    let columns = options.columns(delta, shift);
+                    if columns != 0.0 {
+                        if tab.emulator.scroll_columns(columns) {
+                            tab.mark_full_damage();
+                        }
+                        return Some(Task::none());
+                    }
+                }
+                if options.alternate_screen == AltScreenScroll::Arrows
+                    && tab.emulator.wheel_sends_arrows()
+                {
+                    let arrows = options.arrows(delta).clamp(-100.0, 100.0);
+                    let steps = tab.emulator.scroll_steps(arrows);
+                    if steps == 0 {
+                        return Some(Task::none());
+                    }
+                    let keys = scroll::arrow_keys(steps, tab.emulator.app_cursor_keys());
+                    return Some(write_to_active_tab(app, keys));
+                }
+                let lines = options.lines(delta);
+                if lines.abs() > 0.001 {
+                    let clamped_delta = lines.clamp(-100.0, 100.0);
                     tab.emulator.scroll(clamped_delta);
+                    if tab.emulator.get_scroll_state().1 == 0 {
+                        tab.unseen_lines = 0;
+                    }
                     tab.mark_full_damage();
                 }
             }
             Some(Task::none())
         }
-        Message::TerminalInput(data) => {
+        TerminalMessage::ScrollTo(offset) => {
+            if let Some(tab) = app.tabs.get_mut(app.active_tab) {
+                tab.emulator.scroll_to_offset(offset);
+                if offset == 0 {
+                    tab.unseen_lines = 0;
+                }
+                tab.mark_full_damage();
+            }
+            Some(Task::none())
+        }
+        TerminalMessage::ToggleLineWrap(tab_index) => {
+            app.terminal_menu = None;
+            if let Some(tab) = app.tabs.get_mut(tab_index) {
+                let wrap = !tab.emulator.wraps_lines();
+                tab.emulator.set_wrap_lines(wrap);
+                tab.mark_full_damage();
+            }
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::ScrollColumnsHome => {
+            if let Some(tab) = app.tabs.get_mut(app.active_tab) {
+                let offset = tab.emulator.column_offset();
+                if tab.emulator.scroll_columns(-(offset as f32)) {
+                    tab.mark_full_damage();
+                }
+            }
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::ScrollToBottom => {
+            if let Some(tab) = app.tabs.get_mut(app.active_tab) {
+                tab.emulator.scroll_to_bottom();
+                tab.unseen_lines = 0;
+                tab.mark_full_damage();
+            }
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::ShellExited(tab_index, channel_id, exit_status) => {
+            let ran_command = app
+                .tabs
+                .get(tab_index)
+                .is_some_and(|tab| tab.remote_command.is_some())
+                || app
+                    .session_for_tab(tab_index)
+                    .is_some_and(|session| session.remote_command.is_some());
+            let close_on_clean_exit = app.app_settings.close_on_clean_exit;
+            // The channel tells a closed tab's shell apart from the one now
+            // at its index.
+            let Some(tab) = app.tabs.get_mut(tab_index).filter(|tab| {
+                tab.state == SessionState::Connected && tab.ssh_channel() == Some(channel_id)
+            }) else {
+                return Some(Task::none());
+            };
+            tab.state = SessionState::Disconnected;
+            if !ran_command {
+                if close_on_clean_exit && exit_status == Some(0) {
+                    return Some(Task::done(Message::CloseTab(tab_index)));
+                }
+                tab.shell_exit = Some(ShellExit {
+                    status: exit_status,
+                });
+                return Some(Task::none());
+            }
+            tab.command_exited = true;
+            Some(match exit_status {
+                Some(0) => Task::done(Message::CloseTab(tab_index)),
+                Some(code) => {
+                    tab.write_notice(&format!(
+                        "[command exited with code {}] — press Enter to close",
+                        code
+                    ));
+                    Task::none()
+                }
+                None => {
+                    tab.write_notice("[command ended] — press Enter to close");
+                    Task::none()
+                }
+            })
+        }
+        TerminalMessage::Input(data) => {
             if data.is_empty() {
                 return Some(Task::none());
             }
+            app.selection_actions = None;
+            if let Some(tab) = app.tabs.get_mut(app.active_tab)
+                && tab.emulator.reveal_cursor()
+            {
+                tab.mark_full_damage();
+            }
+            // An exited local shell only takes Enter or R, an ended remote
+            // command only Enter, and an exited SSH shell Enter or R to
+            // reconnect.
+            if let Some(tab) = app.tabs.get(app.active_tab)
+                && tab.state == SessionState::Disconnected
+                && (tab.local_process.is_some() || tab.command_exited || tab.shell_exit.is_some())
+            {
+                return Some(match data.as_slice() {
+                    b"\r" => Task::done(Message::CloseTab(app.active_tab)),
+                    b"r" | b"R" if tab.local_process.is_some() => {
+                        Task::done(Message::RestartLocalShell(app.active_tab))
+                    }
+                    b"r" | b"R" if tab.shell_exit.is_some() => {
+                        Task::done(Message::RetryConnection(app.active_tab))
+                    }
+                    _ => Task::none(),
+                });
+            }
 
+            let data_to_send = app.maybe_wrap_bracketed_paste(&data);
+            Some(write_to_active_tab(app, data_to_send))
+        }
+        TerminalMessage::ToggleSendKeyMenu => {
+            app.show_send_key_menu = !app.show_send_key_menu;
+            if app.show_send_key_menu {
+                return Some(Task::none());
+            }
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::ToggleBandwidthPopover => {
+            app.show_bandwidth_popover = !app.show_bandwidth_popover;
+            if app.show_bandwidth_popover {
+                return Some(Task::none());
+            }
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::SendKey(data) => {
+            app.show_send_key_menu = false;
+            Some(Task::batch([
+                write_to_active_tab(app, data),
+                app.focus_terminal_ime(),
+            ]))
+        }
+        TerminalMessage::ToggleRedaction => {
+            // Viewer mode keeps output redacted.
+            if !app.viewer_mode() {
+                app.set_redaction(!crate::terminal::redact::is_enabled());
+            }
+            None
+        }
+        TerminalMessage::ToggleCommandHistory => {
+            app.command_history_open = !app.command_history_open;
+            app.pending_rerun = None;
+            Some(Task::batch([
+                app.recalc_terminal_size(),
+                app.focus_terminal_ime(),
+            ]))
+        }
+        TerminalMessage::CommandHistoryQueryChanged(query) => {
+            app.command_history_query = query;
+            Some(Task::none())
+        }
+        TerminalMessage::InsertCommand(command) => Some(Task::batch([
+            write_to_active_tab(app, command.into_bytes()),
+            app.focus_terminal_ime(),
+        ])),
+        TerminalMessage::RunCommand(command) => {
+            app.pending_rerun = Some(command);
+            Some(Task::none())
+        }
+        TerminalMessage::ConfirmRunCommand => {
+            let Some(mut command) = app.pending_rerun.take() else {
+                return Some(Task::none());
+            };
+            command.push('\r');
+            Some(Task::batch([
+                write_to_active_tab(app, command.into_bytes()),
+                app.focus_terminal_ime(),
+            ]))
+        }
+        TerminalMessage::CancelRunCommand => {
+            app.pending_rerun = None;
+            Some(Task::none())
+        }
+        TerminalMessage::OpenMenu(position, line) => {
+            app.selection_actions = None;
+            let tab = app.tabs.get(app.active_tab);
+            let block = tab.and_then(|tab| tab.emulator.command_block_at(line));
+            let clipboard_helpers = app.app_settings.remote_clipboard
+                && tab.is_some_and(|tab| tab.ssh_handle.is_some());
+            let sharing = tab
+                .filter(|tab| app.app_settings.allow_terminal_sharing && tab.session.is_some())
+                .map(|tab| (app.active_tab, tab.share.is_some()));
+            let shell_tab = tab
+                .filter(|tab| tab.ssh_handle.is_some() && tab.state == SessionState::Connected)
+                .map(|_| app.active_tab);
+            let wrap_tab = tab.map(|tab| (app.active_tab, tab.emulator.wraps_lines()));
+            app.terminal_menu = Some(TerminalMenu {
+                position,
+                wrap_tab,
+                block,
+                clipboard_helpers,
+                sharing,
+                shell_tab,
+            });
+            Some(Task::none())
+        }
+        TerminalMessage::CopyBlockCommand(index) => {
+            app.terminal_menu = None;
+            let command = app
+                .tabs
+                .get(app.active_tab)
+                .and_then(|tab| tab.emulator.command_at(index));
+            Some(Task::batch([
+                command.map_or_else(Task::none, iced::clipboard::write),
+                app.focus_terminal_ime(),
+            ]))
+        }
+        TerminalMessage::CopyBlockOutput(index) => {
+            app.terminal_menu = None;
+            let output = app
+                .tabs
+                .get(app.active_tab)
+                .and_then(|tab| tab.emulator.command_output(index));
+            Some(Task::batch([
+                output.map_or_else(Task::none, iced::clipboard::write),
+                app.focus_terminal_ime(),
+            ]))
+        }
+        TerminalMessage::SaveBlockOutput(index) => {
+            app.terminal_menu = None;
+            let Some(tab) = app.tabs.get(app.active_tab) else {
+                return Some(Task::none());
+            };
+            let Some(mut contents) = tab.emulator.command_output(index) else {
+                return Some(Task::none());
+            };
+            contents.push('\n');
+            let file_name = format!("{}-output.txt", tab.title.replace(['/', '\\', ' '], "_"));
+            Some(Task::perform(
+                async move {
+                    let Some(handle) = rfd::AsyncFileDialog::new()
+                        .set_file_name(file_name)
+                        .save_file()
+                        .await
+                    else {
+                        return Ok(None);
+                    };
+                    let path = handle.path().to_path_buf();
+                    tokio::fs::write(&path, contents)
+                        .await
+                        .map_err(|e| format!("Failed to save command output: {}", e))?;
+                    Ok(Some(path.display().to_string()))
+                },
+                |value| Message::Terminal(TerminalMessage::BlockOutputSaved(value)),
+            ))
+        }
+        TerminalMessage::BlockOutputSaved(result) => {
+            if let Err(e) = result {
+                app.last_error = Some((e, std::time::Instant::now()));
+            }
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::CloseMenu => {
+            app.terminal_menu = None;
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::SelectAll => {
+            app.terminal_menu = None;
             if let Some(tab) = app.tabs.get_mut(app.active_tab) {
-                if let Some(session) = &tab.session {
-                    let session = session.clone();
-                    let data_to_send = app.maybe_wrap_bracketed_paste(&data);
-
-                    return Some(Task::perform(
-                        async move {
-                            let write_future = session.write(&data_to_send);
-                            match tokio::time::timeout(
-                                std::time::Duration::from_millis(2000),
-                                write_future,
-                            )
-                            .await
-                            {
-                                Ok(Ok(_)) => {}
-                                Ok(Err(e)) => tracing::warn!("ui write error: {}", e),
-                                Err(_) => tracing::warn!("ui write timeout - session unresponsive"),
-                            }
-                        },
-                        |_| Message::TerminalInput(vec![]),
-                    ));
-                } else {
-                    println!("UI: Tab {} ignoring input (no session)", app.active_tab);
-                }
-            } else {
-                println!("UI: Tab {} ignoring input (invalid index)", app.active_tab);
+                tab.emulator.select_all();
+                tab.mark_full_damage();
+            }
+            Some(app.focus_terminal_ime())
+        }
+        TerminalMessage::CopyScreen => {
+            app.terminal_menu = None;
+            if let Some(tab) = app.tabs.get(app.active_tab) {
+                let lines = tab.emulator.screen_text();
+                let content = lines.join("\n").trim_end().to_string();
+                return Some(Task::batch([
+                    iced::clipboard::write(content),
+                    app.focus_terminal_ime(),
+                ]));
             }
             Some(Task::none())
         }
-        Message::Copy => {
+        TerminalMessage::CopyBuffer => {
+            app.terminal_menu = None;
+            if let Some(tab) = app.tabs.get(app.active_tab) {
+                return Some(Task::batch([
+                    iced::clipboard::write(tab.emulator.buffer_text()),
+                    app.focus_terminal_ime(),
+                ]));
+            }
+            Some(Task::none())
+        }
+        TerminalMessage::Copy => {
+            app.terminal_menu = None;
             if let Some(tab) = app.tabs.get(app.active_tab) {
                 if let Some(content) = tab.emulator.copy_selection() {
                     return Some(iced::clipboard::write(content));
@@ -175,18 +537,30 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
             }
             Some(Task::none())
         }
-        Message::Paste => Some(iced::clipboard::read().map(Message::ClipboardReceived)),
-        Message::ClipboardReceived(content) => {
+        TerminalMessage::Paste => {
+            app.terminal_menu = None;
+            Some(
+                iced::clipboard::read()
+                    .map(|value| Message::Terminal(TerminalMessage::ClipboardReceived(value))),
+            )
+        }
+        TerminalMessage::ClipboardReceived(content) => {
             if let Some(text) = content {
                 app.ime_ignore_next_input = true;
                 app.ime_buffer.clear();
-                return Some(Task::done(Message::TerminalInput(
-                    app.bracketed_paste_bytes(&text),
-                )));
+                let text = app.app_settings.paste.apply(&text);
+                if text.is_empty() {
+                    return Some(Task::none());
+                }
+                let data = app.bracketed_paste_bytes(&text);
+                if app.app_settings.paste.slow {
+                    return Some(app.slow_paste(data));
+                }
+                return Some(Task::done(Message::Terminal(TerminalMessage::Input(data))));
             }
             Some(Task::none())
         }
-        Message::ImeBufferChanged(value) => {
+        TerminalMessage::ImeBufferChanged(value) => {
             if app.ime_ignore_next_input {
                 app.ime_ignore_next_input = false;
                 app.ime_buffer.clear();
@@ -195,7 +569,12 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
 
             let prev = app.ime_buffer.clone();
             app.ime_buffer = value.clone();
-            if app.active_view != ActiveView::Terminal || app.show_quick_connect {
+            if app.active_view != ActiveView::Terminal
+                || app.show_quick_connect
+                || app.placeholder_prompt.is_some()
+                || app.idle_screen.hidden
+                || app.idle_screen.revealing
+            {
                 return Some(Task::none());
             }
 
@@ -217,9 +596,9 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
                 if suffix.is_empty() {
                     return Some(Task::none());
                 }
-                return Some(Task::done(Message::TerminalInput(
+                return Some(Task::done(Message::Terminal(TerminalMessage::Input(
                     suffix.as_bytes().to_vec(),
-                )));
+                ))));
             }
 
             if prev.starts_with(&value) {
@@ -229,7 +608,7 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
                 }
                 let mut data = Vec::with_capacity(removed);
                 data.extend(std::iter::repeat(0x08u8).take(removed));
-                return Some(Task::done(Message::TerminalInput(data)));
+                return Some(Task::done(Message::Terminal(TerminalMessage::Input(data))));
             }
 
             let mut data = Vec::new();
@@ -239,21 +618,27 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
             if data.is_empty() {
                 return Some(Task::none());
             }
-            Some(Task::done(Message::TerminalInput(data)))
+            Some(Task::done(Message::Terminal(TerminalMessage::Input(data))))
         }
-        Message::ImePaste => {
+        TerminalMessage::ImePaste => {
             app.ime_ignore_next_input = true;
             app.ime_buffer.clear();
-            Some(iced::clipboard::read().map(Message::ClipboardReceived))
+            Some(
+                iced::clipboard::read()
+                    .map(|value| Message::Terminal(TerminalMessage::ClipboardReceived(value))),
+            )
         }
-        Message::ImeFocusChanged(focused) => {
+        TerminalMessage::ImeFocusChanged(focused) => {
             app.ime_focused = focused;
-            if app.active_view == ActiveView::Terminal && !app.show_quick_connect && !focused {
+            if app.active_view == ActiveView::Terminal
+                && !app.show_quick_connect
+                && app.placeholder_prompt.is_none()
+                && !focused
+            {
                 return Some(app.focus_terminal_ime());
             }
             Some(Task::none())
         }
-        _ => None,
     }
 }
 
@@ -265,6 +650,8 @@ pub(in crate::ui) fn handle_runtime_event(
     if Some(window) != app.main_window
         || app.active_view != ActiveView::Terminal
         || app.show_quick_connect
+        || app.show_tab_overview
+        || app.placeholder_prompt.is_some()
     {
         return Some(Task::none());
     }
@@ -285,7 +672,9 @@ pub(in crate::ui) fn handle_runtime_event(
                     app.ime_ignore_next_input = true;
                     app.ime_buffer.clear();
                     if !text.is_empty() {
-                        return Some(Task::done(Message::TerminalInput(text.as_bytes().to_vec())));
+                        return Some(Task::done(Message::Terminal(TerminalMessage::Input(
+                            text.as_bytes().to_vec(),
+                        ))));
                     }
                 }
                 iced_core::input_method::Event::Preedit(content, _) => {
@@ -313,24 +702,30 @@ pub(in crate::ui) fn handle_runtime_event(
                     key,
                     iced::keyboard::Key::Named(iced::keyboard::key::Named::Backspace)
                 ) {
-                    Message::TerminalInput(vec![0x7f])
+                    Message::Terminal(TerminalMessage::Input(vec![0x7f]))
                 } else if matches!(
                     key,
                     iced::keyboard::Key::Named(iced::keyboard::key::Named::Delete)
                 ) {
-                    Message::TerminalInput(vec![0x1b, b'[', b'3', b'~'])
-                } else if modifiers.command() {
-                    match key {
-                        iced::keyboard::Key::Character(c) if c.as_str() == "c" => Message::Copy,
-                        iced::keyboard::Key::Character(c) if c.as_str() == "v" => {
-                            if app.ime_focused {
-                                Message::Ignore
-                            } else {
-                                Message::Paste
-                            }
-                        }
-                        _ => Message::Ignore,
+                    Message::Terminal(TerminalMessage::Input(vec![0x1b, b'[', b'3', b'~']))
+                } else if let Some(action) =
+                    keymap::lookup(&app.app_settings.key_bindings, key, *modifiers)
+                {
+                    match action {
+                        KeyAction::Copy => Message::Terminal(TerminalMessage::Copy),
+                        KeyAction::Paste if app.ime_focused => Message::Ignore,
+                        KeyAction::Paste => Message::Terminal(TerminalMessage::Paste),
+                        KeyAction::SelectAll => Message::Terminal(TerminalMessage::SelectAll),
                     }
+                } else if let Some(custom) = app.app_settings.custom_keys.iter().find(|custom| {
+                    custom
+                        .shortcut
+                        .as_deref()
+                        .is_some_and(|chord| keymap::matches(chord, key, *modifiers))
+                }) {
+                    Message::Terminal(TerminalMessage::SendKey(custom.bytes()))
+                } else if modifiers.logo() {
+                    Message::Ignore
                 } else if modifiers.command()
                     && matches!(key, iced::keyboard::Key::Character(c) if c.as_str() == "t")
                 {
@@ -341,7 +736,7 @@ pub(in crate::ui) fn handle_runtime_event(
                         if app.ime_focused || !app.ime_preedit.is_empty() {
                             Message::Ignore
                         } else {
-                            Message::TerminalInput(s.as_bytes().to_vec())
+                            Message::Terminal(TerminalMessage::Input(s.as_bytes().to_vec()))
                         }
                     } else if matches!(key, iced::keyboard::Key::Character(_))
                         && !modifiers.control()
@@ -349,10 +744,10 @@ pub(in crate::ui) fn handle_runtime_event(
                         if s.is_empty() || app.ime_focused || !app.ime_preedit.is_empty() {
                             Message::Ignore
                         } else {
-                            Message::TerminalInput(s.as_bytes().to_vec())
+                            Message::Terminal(TerminalMessage::Input(s.as_bytes().to_vec()))
                         }
                     } else if let Some(data) = map_key_to_input(key.clone(), *modifiers) {
-                        Message::TerminalInput(data)
+                        Message::Terminal(TerminalMessage::Input(data))
                     } else {
                         Message::Ignore
                     }
@@ -364,13 +759,136 @@ pub(in crate::ui) fn handle_runtime_event(
             }
             Some(Task::done(message))
         }
-        iced::event::Event::Mouse(iced::mouse::Event::WheelScrolled { delta }) => {
-            let delta_y = match delta {
-                iced::mouse::ScrollDelta::Lines { y, .. } => *y,
-                iced::mouse::ScrollDelta::Pixels { y, .. } => *y / 20.0,
-            };
-            Some(Task::done(Message::ScrollWheel(delta_y)))
+        iced::event::Event::Mouse(iced::mouse::Event::WheelScrolled { delta }) => Some(Task::done(
+            Message::Terminal(TerminalMessage::ScrollWheel(*delta)),
+        )),
+        iced::event::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+            app.modifiers = *modifiers;
+            Some(Task::none())
         }
         _ => Some(Task::none()),
     }
 }
+
+// Restarting a shell that dies straight away would only loop.
+const MIN_RUN_BEFORE_RESTART: std::time::Duration = std::time::Duration::from_secs(2);
+
+fn local_shell_exited(
+    tab: &SessionTab,
+    tab_index: usize,
+    process: &LocalProcess,
+    action: ShellExitAction,
+) -> Task<Message> {
+    let status = match process.exit_code.get() {
+        Some(code) => format!("[process exited with code {}]", code),
+        None => "[process exited]".to_string(),
+    };
+    let action = match action {
+        ShellExitAction::Restart if process.started.elapsed() < MIN_RUN_BEFORE_RESTART => {
+            ShellExitAction::Hold
+        }
+        action => action,
+    };
+    match action {
+        ShellExitAction::Close => Task::done(Message::CloseTab(tab_index)),
+        ShellExitAction::Hold => {
+            tab.write_notice(&format!("{} — press Enter to close, R to restart", status));
+            Task::none()
+        }
+        ShellExitAction::Restart => {
+            tab.write_notice(&status);
+            Task::done(Message::RestartLocalShell(tab_index))
+        }
+    }
+}
+
+fn write_to_active_tab(app: &mut App, data: Vec<u8>) -> Task<Message> {
+    write_to_tab(app, app.active_tab, data)
+}
+
+pub(super) fn write_to_tab(app: &mut App, tab_index: usize, data: Vec<u8>) -> Task<Message> {
+    let Some(tab) = app.tabs.get_mut(tab_index) else {
+        println!("UI: Tab {} ignoring input (invalid index)", tab_index);
+        return Task::none();
+    };
+    tab.last_input = std::time::Instant::now();
+    tab.input_latency.input_sent(tab.last_input);
+    let Some(session) = tab.session.clone() else {
+        println!("UI: Tab {} ignoring input (no session)", tab_index);
+        return Task::none();
+    };
+
+    Task::perform(
+        async move {
+            let write_future = session.write(&data);
+            match tokio::time::timeout(std::time::Duration::from_millis(2000), write_future).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => tracing::warn!("ui write error: {}", e),
+                Err(_) => tracing::warn!("ui write timeout - session unresponsive"),
+            }
+        },
+        |_| Message::Terminal(TerminalMessage::Input(vec![])),
+    )
+}
+
+/// Reacts to BEL from any tab according to its session's bell setting.
+/// Local shells have no session and use the default flash.
+pub(in crate::ui) fn ring_bells(app: &mut App) -> Option<Task<Message>> {
+    let mut tasks = Vec::new();
+    let now = std::time::Instant::now();
+    for tab_index in 0..app.tabs.len() {
+        if !app.tabs[tab_index].emulator.take_bell() {
+            continue;
+        }
+        let mode = app
+            .session_for_tab(tab_index)
+            .map(|session| session.bell)
+            .unwrap_or_default();
+        if mode == BellMode::None {
+            continue;
+        }
+        let in_background = tab_index != app.active_tab || !app.window_focused;
+        let tab = &mut app.tabs[tab_index];
+        // Programs that ring in a loop should not spam sounds or notifications.
+        if tab
+            .last_bell
+            .is_some_and(|at| now.duration_since(at) < std::time::Duration::from_millis(500))
+        {
+            continue;
+        }
+        tab.last_bell = Some(now);
+
+        match mode {
+            BellMode::None => {}
+            BellMode::Visual => tab.visual_bell = Some(now),
+            BellMode::Sound => {
+                if let Err(e) = crate::platform::beep() {
+                    tracing::warn!("{}", e);
+                }
+            }
+            BellMode::Bounce => {
+                if let Some(id) = app.main_window
+                    && !app.window_focused
+                {
+                    tasks.push(iced::window::request_user_attention(
+                        id,
+                        Some(iced::window::UserAttention::Critical),
+                    ));
+                }
+            }
+            BellMode::Notification => {
+                if in_background
+                    && let Err(e) =
+                        crate::platform::notify("Bell", &format!("{} rang the bell", tab.title))
+                {
+                    tracing::warn!("{}", e);
+                }
+            }
+        }
+    }
+    if tasks.is_empty() {
+        None
+    } else {
+        Some(Task::batch(tasks))
+    }
+}
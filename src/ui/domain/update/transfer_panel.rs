@@ -0,0 +1,42 @@
+use iced::Task;
+
+use crate::ui::App;
+use crate::ui::message::Message;
+use crate::ui::state::TransferPanelMode;
+
+const WINDOW_SIZE: iced::Size = iced::Size::new(520.0, 340.0);
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::SetTransferPanelMode(mode) => {
+            app.transfer_panel_mode = mode;
+            match (mode, app.transfer_window) {
+                (TransferPanelMode::Detached, None) => {
+                    let (id, open) = iced::window::open(iced::window::Settings {
+                        size: WINDOW_SIZE,
+                        min_size: Some(iced::Size::new(360.0, 200.0)),
+                        level: iced::window::Level::AlwaysOnTop,
+                        ..iced::window::Settings::default()
+                    });
+                    app.transfer_window = Some(id);
+                    open.map(Message::WindowOpened)
+                }
+                (TransferPanelMode::Detached, Some(id)) => iced::window::gain_focus(id),
+                (_, Some(id)) => {
+                    app.transfer_window = None;
+                    iced::window::close(id)
+                }
+                (_, None) => Task::none(),
+            }
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Closing the transfer window puts the queue back in the SFTP panel.
+pub(super) fn window_closed(app: &mut App, id: iced::window::Id) {
+    if app.transfer_window == Some(id) {
+        app.transfer_window = None;
+        app.transfer_panel_mode = TransferPanelMode::Docked;
+    }
+}
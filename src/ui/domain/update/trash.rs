@@ -0,0 +1,225 @@
+use iced::Task;
+use russh_sftp::client::SftpSession;
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::core::session::Session;
+use crate::ui::App;
+use crate::ui::message::{Message, SftpMessage};
+use crate::ui::state::{SessionTab, TrashToast};
+
+pub(in crate::ui) fn handle(app: &mut App, message: SftpMessage) -> Task<Message> {
+    match message {
+        SftpMessage::MovedToTrash(tab_index, result) => {
+            app.trash_toast = result.as_ref().ok().cloned();
+            app.update(Message::Sftp(SftpMessage::DeleteFinished(
+                tab_index,
+                result.map(|_| ()),
+            )))
+        }
+        SftpMessage::UndoTrash => {
+            let Some(toast) = app.trash_toast.take() else {
+                return Task::none();
+            };
+            // Looked up by id, since tabs may have closed or moved since the
+            // delete.
+            let Some(tab) =
+                toast_tab(&app.tabs, toast.tab_id).and_then(|index| app.tabs.get(index))
+            else {
+                return Task::none();
+            };
+            let Some(session) = tab.session.clone() else {
+                return Task::none();
+            };
+            let sftp_session = tab.sftp_session.clone();
+            let tab_id = toast.tab_id;
+            Task::perform(
+                async move {
+                    let guard = sftp_guard(&session, &sftp_session).await?;
+                    let sftp = guard
+                        .as_ref()
+                        .ok_or_else(|| "SFTP not available".to_string())?;
+                    if sftp
+                        .try_exists(toast.original.clone())
+                        .await
+                        .unwrap_or(false)
+                    {
+                        return Err(format!(
+                            "Undo failed: {} was created again in the meantime",
+                            toast.original
+                        ));
+                    }
+                    sftp.rename(toast.trashed, toast.original)
+                        .await
+                        .map_err(|e| format!("Undo failed: {}", e))
+                },
+                move |result| Message::Sftp(SftpMessage::UndoTrashFinished(tab_id, result)),
+            )
+        }
+        SftpMessage::UndoTrashFinished(tab_id, result) => {
+            let Some(tab_index) = toast_tab(&app.tabs, tab_id) else {
+                return Task::none();
+            };
+            let Some(state) = app.sftp_state_for_tab_mut(tab_index) else {
+                return Task::none();
+            };
+            if let Err(err) = result {
+                state.remote_error = Some(err);
+                return Task::none();
+            }
+            state.invalidate_remote_cache();
+            super::start_remote_list(app, tab_index).unwrap_or_else(Task::none)
+        }
+        SftpMessage::TrashToastDismiss => {
+            app.trash_toast = None;
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Moves `path` into the trash folder instead of deleting it. The folder is
+/// created on first use; a leading `~/` is the remote home directory.
+pub(super) fn move_to_trash(
+    app: &App,
+    tab_index: usize,
+    path: String,
+    name: String,
+) -> Option<Task<Message>> {
+    let tab = app.tabs.get(tab_index)?;
+    let tab_id = tab.id;
+    let session = tab.session.clone()?;
+    let sftp_session = tab.sftp_session.clone();
+    let trash_dir = app.app_settings.sftp_trash_dir.clone();
+    Some(Task::perform(
+        async move {
+            if trash_dir.trim().is_empty() {
+                return Err("Move to trash failed: no trash folder is set".to_string());
+            }
+            let guard = sftp_guard(&session, &sftp_session).await?;
+            let sftp = guard
+                .as_ref()
+                .ok_or_else(|| "SFTP not available".to_string())?;
+            let home = sftp
+                .canonicalize(".")
+                .await
+                .map_err(|e| format!("Move to trash failed: {}", e))?;
+            let dir = resolve_dir(&trash_dir, &home);
+            let original = absolute(&path, &home);
+            if original == dir || original.starts_with(&format!("{}/", dir)) {
+                return Err(format!(
+                    "{} is already in the trash; turn off the remote trash in settings to delete it for good",
+                    name
+                ));
+            }
+            create_dirs(sftp, &dir).await?;
+            let trashed = format!("{}/{}", dir, trashed_name(&name, chrono::Local::now()));
+            sftp.rename(original.clone(), trashed.clone())
+                .await
+                .map_err(|e| format!("Move to trash failed: {}", e))?;
+            Ok(TrashToast {
+                tab_id,
+                name,
+                original,
+                trashed,
+            })
+        },
+        move |result| Message::Sftp(SftpMessage::MovedToTrash(tab_index, result)),
+    ))
+}
+
+/// Where the tab that trashed a file is now, if it is still open.
+fn toast_tab(tabs: &[SessionTab], tab_id: uuid::Uuid) -> Option<usize> {
+    tabs.iter().position(|tab| tab.id == tab_id)
+}
+
+async fn sftp_guard<'a>(
+    session: &Session,
+    sftp_session: &'a Arc<Mutex<Option<SftpSession>>>,
+) -> Result<MutexGuard<'a, Option<SftpSession>>, String> {
+    let mut guard = sftp_session.lock().await;
+    if guard.is_none() {
+        let ssh = match session.backend.as_ref() {
+            crate::core::backend::SessionBackend::Ssh { session, .. } => session.clone(),
+            _ => return Err("No SSH session".to_string()),
+        };
+        let mut ssh_guard = ssh.lock().await;
+        let created = ssh_guard
+            .open_sftp()
+            .await
+            .map_err(|e| format!("SFTP init failed: {}", e))?;
+        *guard = Some(created);
+    }
+    Ok(guard)
+}
+
+async fn create_dirs(sftp: &SftpSession, dir: &str) -> Result<(), String> {
+    let mut current = String::new();
+    for part in dir.split('/').filter(|part| !part.is_empty()) {
+        current = format!("{}/{}", current, part);
+        if !sftp.try_exists(current.clone()).await.unwrap_or(false) {
+            sftp.create_dir(current.clone())
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", current, e))?;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_dir(trash_dir: &str, home: &str) -> String {
+    let trash_dir = trash_dir.trim();
+    let dir = match trash_dir.strip_prefix('~') {
+        Some(rest) => format!("{}/{}", home, rest.trim_start_matches('/')),
+        None => absolute(trash_dir, home),
+    };
+    dir.trim_end_matches('/').to_string()
+}
+
+fn absolute(path: &str, home: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{}/{}", home.trim_end_matches('/'), path)
+    }
+}
+
+/// Prefixed with the time so repeated deletes of the same name never
+/// collide, and the trash lists in deletion order.
+fn trashed_name(name: &str, now: chrono::DateTime<chrono::Local>) -> String {
+    format!("{}-{}", now.format("%Y%m%d-%H%M%S%.3f"), name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn resolves_trash_paths() {
+        assert_eq!(
+            resolve_dir("~/.rivett-trash", "/home/alice"),
+            "/home/alice/.rivett-trash"
+        );
+        assert_eq!(resolve_dir("/var/trash/", "/home/alice"), "/var/trash");
+        assert_eq!(resolve_dir("trash", "/home/alice"), "/home/alice/trash");
+        assert_eq!(absolute("/etc/hosts", "/root"), "/etc/hosts");
+
+        let now = chrono::Local
+            .with_ymd_and_hms(2024, 3, 9, 14, 5, 7)
+            .unwrap();
+        assert_eq!(trashed_name("app.log", now), "20240309-140507.000-app.log");
+    }
+
+    #[test]
+    fn undo_follows_the_tab_after_earlier_tabs_close() {
+        let mut tabs: Vec<SessionTab> = ["Sessions", "web", "db"]
+            .into_iter()
+            .map(SessionTab::new)
+            .collect();
+        let db = tabs[2].id;
+        tabs.remove(1);
+        assert_eq!(toast_tab(&tabs, db), Some(1));
+        tabs.remove(1);
+        assert_eq!(toast_tab(&tabs, db), None);
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::ui::App;
+use crate::ui::state::SessionState;
+
+// Counters are brought up to date this often, not on every tick.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+// Longest gap counted as connected time, so a machine waking from sleep
+// doesn't credit the time it was asleep.
+const MAX_GAP: Duration = Duration::from_secs(10);
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Counts a new connection to a saved session; reconnects aren't counted.
+pub(super) fn record_connect(app: &mut App, session_id: &str) {
+    let Some(saved) = app
+        .saved_sessions
+        .iter_mut()
+        .find(|saved| saved.id == session_id)
+    else {
+        return;
+    };
+    saved.last_connected = Some(chrono::Utc::now());
+    if let Err(e) = app.session_storage.save_sessions(&app.saved_sessions) {
+        eprintln!("Failed to save sessions: {}", e);
+    }
+    app.session_usage
+        .entry(session_id.to_string())
+        .or_default()
+        .record_connect(chrono::Local::now().date_naive());
+    save(app);
+}
+
+pub(super) fn forget(app: &mut App, session_id: &str) {
+    if app.usage_open.as_deref() == Some(session_id) {
+        app.usage_open = None;
+    }
+    if app.session_usage.remove(session_id).is_some() {
+        save(app);
+    }
+}
+
+/// Adds connected time and traffic for open tabs, once per session for
+/// time and once per connection for traffic however many tabs share it.
+pub(super) fn tick(app: &mut App) {
+    let now = Instant::now();
+    let elapsed = now.duration_since(app.usage_tracker.ticked);
+    if elapsed < UPDATE_INTERVAL {
+        return;
+    }
+    app.usage_tracker.ticked = now;
+
+    let mut counted = HashSet::new();
+    let mut connections = Vec::new();
+    for tab in &app.tabs {
+        let Some(id) = tab.sftp_key.as_ref() else {
+            continue;
+        };
+        if !matches!(tab.state, SessionState::Connected)
+            || !app.saved_sessions.iter().any(|saved| &saved.id == id)
+        {
+            continue;
+        }
+        let usage = app.session_usage.entry(id.clone()).or_default();
+        if elapsed <= MAX_GAP && counted.insert(id.clone()) {
+            usage.add_connected(elapsed);
+            app.usage_tracker.dirty = true;
+        }
+        let Some(bandwidth) = &tab.bandwidth else {
+            continue;
+        };
+        if connections
+            .iter()
+            .any(|(seen, _): &(crate::ssh::Bandwidth, _)| seen.same_as(bandwidth))
+        {
+            continue;
+        }
+        let (last_in, last_out) = app
+            .usage_tracker
+            .connections
+            .iter()
+            .find(|(seen, _)| seen.same_as(bandwidth))
+            .map(|&(_, totals)| totals)
+            .unwrap_or_default();
+        let (bytes_in, bytes_out) = bandwidth.totals();
+        if (bytes_in, bytes_out) != (last_in, last_out) {
+            usage.add_traffic(
+                bytes_in.saturating_sub(last_in),
+                bytes_out.saturating_sub(last_out),
+            );
+            app.usage_tracker.dirty = true;
+        }
+        connections.push((bandwidth.clone(), (bytes_in, bytes_out)));
+    }
+    app.usage_tracker.connections = connections;
+
+    if app.usage_tracker.dirty && now.duration_since(app.usage_tracker.saved) >= SAVE_INTERVAL {
+        save(app);
+    }
+}
+
+/// Saves what hasn't been saved yet, before the app exits.
+pub(super) fn flush(app: &mut App) {
+    if app.usage_tracker.dirty {
+        save(app);
+    }
+}
+
+fn save(app: &mut App) {
+    let saved = &app.saved_sessions;
+    app.session_usage
+        .retain(|id, _| saved.iter().any(|session| &session.id == id));
+    if let Err(e) = app.session_storage.save_usage(&app.session_usage) {
+        eprintln!("Failed to save session usage: {}", e);
+    }
+    app.usage_tracker.saved = Instant::now();
+    app.usage_tracker.dirty = false;
+}
@@ -0,0 +1,154 @@
+use iced::Task;
+use std::time::{Duration, Instant};
+
+use super::sessions::connect_saved_session;
+use crate::session::config::AuthMethod;
+use crate::ui::App;
+use crate::ui::message::Message;
+use crate::ui::state::{SshConnection, WarmConnection};
+
+/// Unclaimed warm connections are closed after this long so hovering over
+/// the session list doesn't leave logins open on remote hosts.
+const WARM_TTL: Duration = Duration::from_secs(60);
+
+pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Task<Message> {
+    match message {
+        Message::WarmUpSession(id) => {
+            if !app.app_settings.preconnect_sessions
+                || app
+                    .warm_connection
+                    .as_ref()
+                    .is_some_and(|warm| warm.session_id == id)
+            {
+                return Task::none();
+            }
+            let Some(session) = app.saved_sessions.iter().find(|s| s.id == id) else {
+                return Task::none();
+            };
+            // Without a stored password the handshake would only fail.
+            if matches!(session.auth_method, AuthMethod::Password)
+                && session.password.as_deref().is_none_or(str::is_empty)
+            {
+                return Task::none();
+            }
+            // A tab already waiting on the previous warm-up keeps it.
+            if app
+                .warm_connection
+                .as_ref()
+                .is_some_and(|warm| warm.claimed_by.is_some())
+            {
+                return Task::none();
+            }
+
+            let debug_log = session.debug_log.then(crate::ssh::DebugLog::new);
+            let connect = connect_saved_session(
+                session,
+                &app.app_settings.host_domains,
+                &app.connect_defaults(),
+                debug_log.clone(),
+                None,
+            );
+            let previous = app.warm_connection.replace(WarmConnection {
+                session_id: id.clone(),
+                started: Instant::now(),
+                debug_log,
+                result: None,
+                claimed_by: None,
+            });
+            Task::batch(vec![
+                discard(previous),
+                Task::perform(connect, move |result| {
+                    Message::WarmUpFinished(id.clone(), result)
+                }),
+            ])
+        }
+        Message::WarmUpFinished(id, result) => {
+            let Some(warm) = app
+                .warm_connection
+                .as_mut()
+                .filter(|warm| warm.session_id == id && warm.result.is_none())
+            else {
+                // Superseded by another warm-up while the handshake ran.
+                return close(result);
+            };
+            match warm.claimed_by {
+                Some(tab_index) => {
+                    app.warm_connection = None;
+                    Task::done(Message::SessionConnected(result, tab_index))
+                }
+                None => {
+                    warm.result = Some(result);
+                    Task::none()
+                }
+            }
+        }
+        _ => Task::none(),
+    }
+}
+
+/// Hands a warm connection for `session_id` to a newly opened tab. Returns
+/// `None` when there is nothing usable and the caller should connect itself.
+pub(in crate::ui) fn claim(
+    app: &mut App,
+    session_id: &str,
+    tab_index: usize,
+) -> Option<Task<Message>> {
+    let warm = app
+        .warm_connection
+        .as_mut()
+        .filter(|warm| warm.session_id == session_id && warm.claimed_by.is_none())?;
+    if let Some(tab) = app.tabs.get_mut(tab_index) {
+        tab.debug_log = warm.debug_log.clone();
+    }
+    match warm.result.take() {
+        None => {
+            warm.claimed_by = Some(tab_index);
+            Some(Task::none())
+        }
+        Some(Ok(connection)) => {
+            app.warm_connection = None;
+            Some(Task::done(Message::SessionConnected(
+                Ok(connection),
+                tab_index,
+            )))
+        }
+        // A failed warm-up may have been transient; try again for real.
+        Some(Err(_)) => {
+            app.warm_connection = None;
+            None
+        }
+    }
+}
+
+pub(in crate::ui) fn expire(app: &mut App) -> Option<Task<Message>> {
+    let expired = app
+        .warm_connection
+        .as_ref()
+        .is_some_and(|warm| warm.claimed_by.is_none() && warm.started.elapsed() > WARM_TTL);
+    if !expired {
+        return None;
+    }
+    Some(discard(app.warm_connection.take()))
+}
+
+fn discard(warm: Option<WarmConnection>) -> Task<Message> {
+    match warm.and_then(|warm| warm.result) {
+        Some(result) => close(result),
+        None => Task::none(),
+    }
+}
+
+fn close(result: Result<SshConnection, String>) -> Task<Message> {
+    let Ok((session, _rx, _)) = result else {
+        return Task::none();
+    };
+    Task::perform(
+        async move {
+            let guard = session.lock().await;
+            if let Err(e) = guard.disconnect("unused warm connection").await {
+                tracing::warn!("warm connection disconnect failed: {}", e);
+            }
+        },
+        |_| Message::Ignore,
+    )
+}
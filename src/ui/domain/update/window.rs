@@ -1,7 +1,13 @@
 use iced::Task;
+use std::time::{Duration, Instant};
 
+use crate::settings::OpenTab;
 use crate::ui::App;
-use crate::ui::message::{ActiveView, Message};
+use crate::ui::message::{ActiveView, Message, SftpMessage, TerminalMessage};
+
+// Resizing and dragging send a stream of events; the state is written once
+// they stop.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
 
 pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Message>> {
     match message {
@@ -10,29 +16,52 @@ pub(in crate::ui) fn handle(app: &mut App, message: Message) -> Option<Task<Mess
             app.window_height = height;
             app.sftp_dragging = false;
 
-            let reserved_width = 0.0;
-            let h_padding = 24.0;
-            let v_padding = 80.0;
-
-            let term_w = (width as f32 - reserved_width - h_padding).max(0.0);
-            let term_h = (height as f32 - v_padding).max(0.0);
-
-            let cols = (term_w / app.cell_width()) as usize;
-            let rows = (term_h / app.cell_height()) as usize;
+            let (cols, rows) = app.terminal_grid_size(width, height);
 
             if width > 0 {
                 let max_width = (width as f32 - 240.0).max(320.0);
                 app.sftp_panel_width = app.sftp_panel_width.clamp(280.0, max_width);
             }
             app.pending_resize = Some((cols, rows, std::time::Instant::now()));
-            Some(Task::done(Message::TerminalResize(cols, rows)))
+            Some(Task::batch([
+                Task::done(Message::Terminal(TerminalMessage::Resize(cols, rows))),
+                check_maximized(app),
+            ]))
+        }
+        Message::WindowMoved(position) => {
+            app.window_position = Some(position);
+            Some(check_maximized(app))
+        }
+        // Size and position are only kept while not maximized, so leaving
+        // maximized goes back to where the window was before.
+        Message::WindowMaximized(maximized) => {
+            let mut state = app.window_state.clone();
+            state.maximized = maximized;
+            if !maximized {
+                if app.window_width > 0 && app.window_height > 0 {
+                    state.size = Some((app.window_width as f32, app.window_height as f32));
+                }
+                if let Some(position) = app.window_position {
+                    state.position = Some((position.x, position.y));
+                }
+            }
+            if state != app.window_state {
+                app.window_state = state;
+                app.window_state_changed = Some(Instant::now());
+            }
+            Some(Task::none())
         }
         Message::WindowOpened(_id) => Some(Task::none()),
         Message::WindowClosed(id) => {
             if Some(id) == app.main_window {
                 app.main_window = None;
+                if app.window_state_changed.is_some() {
+                    save_state(app);
+                }
+                super::usage::flush(app);
                 Some(iced::exit())
             } else {
+                super::transfer_panel::window_closed(app, id);
                 Some(Task::none())
             }
         }
@@ -46,6 +75,42 @@ pub(in crate::ui) fn handle_runtime_event(
     window: iced::window::Id,
 ) -> Option<Task<Message>> {
     if Some(window) == app.main_window {
+        if let iced::event::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key,
+            physical_key,
+            modifiers,
+            ..
+        }) = event
+        {
+            // Cmd/Ctrl+Shift+O toggles the tab overview from any view.
+            let overview_shortcut = modifiers.command()
+                && modifiers.shift()
+                && key
+                    .to_latin(*physical_key)
+                    .is_some_and(|c| c.eq_ignore_ascii_case(&'o'));
+            let close_overview = app.show_tab_overview
+                && matches!(
+                    key,
+                    iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape)
+                );
+            if overview_shortcut || close_overview {
+                return Some(Task::done(Message::ToggleTabOverview));
+            }
+        }
+        if app.show_quick_connect
+            && let iced::event::Event::Keyboard(iced::keyboard::Event::KeyPressed { key, .. }) =
+                event
+        {
+            match key {
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::ArrowDown) => {
+                    return Some(Task::done(Message::QuickConnectMoveSelection(1)));
+                }
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::ArrowUp) => {
+                    return Some(Task::done(Message::QuickConnectMoveSelection(-1)));
+                }
+                _ => {}
+            }
+        }
         if app.sftp_panel_open
             && app
                 .sftp_state_for_tab(app.active_tab)
@@ -59,7 +124,7 @@ pub(in crate::ui) fn handle_runtime_event(
                     key,
                     iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape)
                 ) {
-                    return Some(Task::done(Message::SftpRenameCancel));
+                    return Some(Task::done(Message::Sftp(SftpMessage::RenameCancel)));
                 }
             }
         }
@@ -67,16 +132,22 @@ pub(in crate::ui) fn handle_runtime_event(
         match event {
             iced::event::Event::Mouse(iced::mouse::Event::ButtonReleased(_)) => {
                 if app.sftp_file_dragging.is_some() {
-                    return Some(Task::done(Message::SftpFileDragEnd));
+                    return Some(Task::done(Message::Sftp(SftpMessage::FileDragEnd)));
                 }
             }
             iced::event::Event::Mouse(iced::mouse::Event::CursorMoved { position }) => {
                 if app.sftp_file_dragging.is_some() {
-                    return Some(Task::done(Message::SftpFileDragUpdate(*position)));
+                    return Some(Task::done(Message::Sftp(SftpMessage::FileDragUpdate(
+                        *position,
+                    ))));
                 }
             }
             iced::event::Event::Window(iced::window::Event::Focused) => {
                 app.ime_focused = false;
+                app.window_focused = true;
+                if let Some(tab) = app.tabs.get_mut(app.active_tab) {
+                    tab.has_activity = false;
+                }
                 app.reload_settings();
                 if app.active_view == ActiveView::Terminal && !app.show_quick_connect {
                     return Some(Task::batch(vec![
@@ -88,6 +159,7 @@ pub(in crate::ui) fn handle_runtime_event(
             }
             iced::event::Event::Window(iced::window::Event::Unfocused) => {
                 app.ime_focused = false;
+                app.window_focused = false;
                 return Some(Task::none());
             }
             iced::event::Event::Window(iced::window::Event::Resized(size)) => {
@@ -96,9 +168,85 @@ pub(in crate::ui) fn handle_runtime_event(
                     size.height as u32,
                 )));
             }
+            iced::event::Event::Window(iced::window::Event::Moved(position)) => {
+                return Some(Task::done(Message::WindowMoved(*position)));
+            }
             _ => {}
         }
     }
 
     None
 }
+
+fn check_maximized(app: &App) -> Task<Message> {
+    app.main_window.map_or_else(Task::none, |id| {
+        iced::window::is_maximized(id).map(Message::WindowMaximized)
+    })
+}
+
+pub(super) fn save_state(app: &mut App) {
+    app.window_state_changed = None;
+    if let Err(e) = app.settings_storage.save_window_state(&app.window_state) {
+        eprintln!("Failed to save window state: {}", e);
+    }
+}
+
+pub(super) fn save_settled_state(app: &mut App) {
+    let tabs = open_tabs(app);
+    if tabs != app.window_state.open_tabs {
+        app.window_state.open_tabs = tabs;
+        app.window_state_changed = Some(Instant::now());
+    }
+    if app
+        .window_state_changed
+        .is_some_and(|changed| changed.elapsed() >= SAVE_DELAY)
+    {
+        save_state(app);
+    }
+}
+
+/// Remembers the SFTP panel width for the saved session in the active tab.
+pub(super) fn remember_sftp_panel_width(app: &mut App) {
+    let Some(id) = app.session_for_tab(app.active_tab).map(|s| s.id.clone()) else {
+        return;
+    };
+    if app.window_state.sftp_panel_widths.get(&id) != Some(&app.sftp_panel_width) {
+        app.window_state
+            .sftp_panel_widths
+            .insert(id, app.sftp_panel_width);
+        app.window_state_changed = Some(Instant::now());
+    }
+}
+
+/// Uses the width last chosen for the active tab's session, if any.
+pub(super) fn restore_sftp_panel_width(app: &mut App) {
+    let Some(width) = app
+        .session_for_tab(app.active_tab)
+        .and_then(|session| app.window_state.sftp_panel_widths.get(&session.id))
+        .copied()
+    else {
+        return;
+    };
+    let max_width = if app.window_width > 0 {
+        (app.window_width as f32 - 240.0).max(320.0)
+    } else {
+        f32::MAX
+    };
+    app.sftp_panel_width = width.clamp(280.0, max_width);
+    app.sftp_panel_initialized = true;
+}
+
+// Local shells and saved sessions; anything else can't be reopened.
+fn open_tabs(app: &App) -> Vec<OpenTab> {
+    (1..app.tabs.len())
+        .filter_map(|index| {
+            if app.tabs[index].local_process.is_some() {
+                Some(OpenTab::Local)
+            } else {
+                app.session_for_tab(index).map(|session| OpenTab::Session {
+                    id: session.id.clone(),
+                })
+            }
+        })
+        .collect()
+}
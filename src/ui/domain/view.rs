@@ -1,12 +1,18 @@
 use iced::{Alignment, Element, Length};
 
 use crate::ui::App;
-use crate::ui::message::{ActiveView, Message};
+use crate::ui::components::session_dialog::SessionDialog;
+use crate::ui::message::{ActiveView, Message, SessionMessage, SftpMessage, TerminalMessage};
+use crate::ui::state::{AuthChoice, JobOwner, SftpPane, SftpState, TransferPanelMode};
 use crate::ui::style as ui_style;
+use crate::ui::views::status_bar::StatusBar;
 use crate::ui::{components, views};
 
 impl App {
-    pub fn view(&self, _window: iced::window::Id) -> Element<'_, Message> {
+    pub fn view(&self, window: iced::window::Id) -> Element<'_, Message> {
+        if Some(window) == self.transfer_window {
+            return self.transfer_window_view();
+        }
         use iced::widget::container::transparent;
         use iced::widget::{Space, button, column, container, row, stack, text, text_input};
 
@@ -16,10 +22,16 @@ impl App {
                 self.active_tab,
                 &self.ime_preedit,
                 self.terminal_font_size,
+                self.app_settings.terminal_padding.max(0.0),
+                self.tabs
+                    .get(self.active_tab)
+                    .and_then(|tab| self.credential_queue.touch_target(tab.id)),
                 self.use_gpu_renderer,
             ),
             ActiveView::SessionManager => views::session_manager::render(
                 &self.saved_sessions,
+                &self.session_templates,
+                &self.template_host,
                 &self.session_search_query,
                 self.editing_session.as_ref(),
                 &self.form_name,
@@ -27,9 +39,16 @@ impl App {
                 &self.form_port,
                 &self.form_username,
                 &self.form_password,
-                self.auth_method_password,
+                self.form_auth == AuthChoice::Password,
                 self.validation_error.as_ref(),
                 self.session_menu_open.as_deref(),
+                self.window_state
+                    .session_table
+                    .then(|| self.session_table()),
+                &self.host_probes,
+                &self.session_usage,
+                self.usage_open.as_deref(),
+                self.viewer_mode(),
             ),
         };
         if self.active_view == ActiveView::Terminal && !self.show_quick_connect {
@@ -38,12 +57,13 @@ impl App {
                 .get(self.active_tab)
                 .map(|tab| tab.emulator.cursor_position())
                 .unwrap_or((0, 0));
-            let cursor_x = cursor_col as f32 * self.cell_width();
-            let cursor_y = cursor_row as f32 * self.cell_height() + self.cell_height();
+            let padding = self.app_settings.terminal_padding.max(0.0);
+            let cursor_x = padding + cursor_col as f32 * self.cell_width();
+            let cursor_y = padding + cursor_row as f32 * self.cell_height() + self.cell_height();
 
             let ime_input = text_input("", &self.ime_buffer)
-                .on_input(Message::ImeBufferChanged)
-                .on_paste(|_| Message::ImePaste)
+                .on_input(|value| Message::Terminal(TerminalMessage::ImeBufferChanged(value)))
+                .on_paste(|_| Message::Terminal(TerminalMessage::ImePaste))
                 .id(self.ime_input_id.clone())
                 .size(1)
                 .padding(0)
@@ -63,30 +83,143 @@ impl App {
             .width(Length::Fill)
             .height(Length::Fill);
             content = stack![content, ime_layer].into();
+
+            if let Some(overlay) = self
+                .host_profile_for_tab(self.active_tab)
+                .and_then(views::terminal::host_profile_overlay)
+            {
+                content = stack![content, overlay].into();
+            }
+
+            if let Some(badge) = self
+                .session_for_tab(self.active_tab)
+                .and_then(|session| session.badge_text())
+            {
+                let color = self
+                    .host_profile_for_tab(self.active_tab)
+                    .and_then(|profile| profile.rgb())
+                    .map(|(r, g, b)| iced::Color::from_rgb8(r, g, b));
+                content = stack![content, views::terminal::corner_badge(badge, color)].into();
+            }
+
+            if self
+                .tabs
+                .get(self.active_tab)
+                .is_some_and(|tab| tab.bell_flashing())
+            {
+                let flash = container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(ui_style::bell_flash);
+                content = stack![content, flash].into();
+            }
+
+            if let Some(pill) = self
+                .tabs
+                .get(self.active_tab)
+                .and_then(views::terminal::scroll_pill)
+            {
+                content = stack![content, pill].into();
+            }
+
+            if let Some(pill) = self
+                .tabs
+                .get(self.active_tab)
+                .and_then(views::terminal::column_pill)
+            {
+                content = stack![content, pill].into();
+            }
+
+            if let Some(banner) = self
+                .tabs
+                .get(self.active_tab)
+                .and_then(|tab| views::terminal::reconnect_banner(self.active_tab, tab))
+            {
+                content = stack![content, banner].into();
+            }
+
+            if let Some(overlay) = self
+                .tabs
+                .get(self.active_tab)
+                .and_then(|tab| views::terminal::connection_closed(self.active_tab, tab))
+            {
+                content = stack![content, overlay].into();
+            }
+
+            if self.idle_screen.hidden {
+                let guard = views::terminal::screen_guard(self.app_settings.screen_guard.mode);
+                content = stack![content, guard].into();
+            }
+        }
+
+        if self.active_view == ActiveView::Terminal
+            && self.command_history_open
+            && let Some(tab) = self.tabs.get(self.active_tab)
+        {
+            let sidebar = views::command_history::render(
+                tab.emulator.command_history(),
+                &self.command_history_query,
+                self.pending_rerun.as_deref(),
+            );
+            content = row![content, sidebar].into();
         }
 
         // Build layout from top to bottom: tab_bar (if terminal) -> content -> status_bar
         let mut main_layout = column![];
 
         // Tab bar at the top (only in terminal view)
-        main_layout = main_layout.push(views::tab_bar::render(&self.tabs, self.active_tab));
+        let tab_profiles: Vec<_> = (0..self.tabs.len())
+            .map(|index| self.host_profile_for_tab(index))
+            .collect();
+        let tab_expiry: Vec<_> = (0..self.tabs.len())
+            .map(|index| {
+                self.session_for_tab(index)
+                    .and_then(|session| session.access_expires_at)
+            })
+            .collect();
+        main_layout = main_layout.push(views::tab_bar::render(
+            &self.tabs,
+            self.active_tab,
+            tab_profiles,
+            tab_expiry,
+        ));
 
         // Main content
         main_layout = main_layout.push(content);
 
         // Status bar at the bottom
-        main_layout = main_layout.push(views::status_bar::render(
-            &self.tabs,
-            self.active_tab,
-            self.active_view,
-            self.sftp_panel_open,
-            self.port_forward_panel_open,
-        ));
+        let status_bar = views::status_bar::render(StatusBar {
+            tabs: &self.tabs,
+            active_tab: self.active_tab,
+            active_view: self.active_view,
+            sftp_panel_open: self.sftp_panel_open,
+            port_forward_panel_open: self.port_forward_panel_open,
+            send_key_menu_open: self.show_send_key_menu,
+            command_history_open: self.command_history_open,
+            running_jobs: (!self.background_jobs.is_empty())
+                .then(|| self.background_jobs.running_count()),
+            jobs_panel_open: self.show_jobs_panel,
+        });
+        let transfer_pill = self
+            .sftp_state_for_tab(self.active_tab)
+            .filter(|_| {
+                self.transfer_panel_mode == TransferPanelMode::Compact && self.active_tab != 0
+            })
+            .map(|state| {
+                container(views::sftp::transfer_pill(&state.transfers))
+                    .padding([3, 12])
+                    .style(ui_style::status_bar)
+            });
+        main_layout = main_layout.push(row![status_bar, transfer_pill]);
 
         let base_container = container(main_layout.spacing(0).height(Length::Fill))
             .width(Length::Fill)
             .height(Length::Fill)
-            .style(ui_style::app_background);
+            .style(if self.active_view == ActiveView::Terminal {
+                ui_style::terminal_window_background
+            } else {
+                ui_style::app_background
+            });
 
         let content_view: Element<'_, Message> = base_container.into();
 
@@ -102,8 +235,12 @@ impl App {
                     .height(Length::Fill),
             )
             .interaction(iced::mouse::Interaction::ResizingHorizontally)
-            .on_press(Message::SftpDragStart);
+            .on_press(Message::Sftp(SftpMessage::DragStart));
 
+            let presets = &self.app_settings.sftp_filter_presets;
+            let find_preset = |name: Option<&str>| {
+                name.and_then(|name| presets.iter().find(|preset| preset.name == name))
+            };
             let sftp_content = container(views::sftp::render(
                 &sftp_state.local_path,
                 &sftp_state.remote_path,
@@ -111,7 +248,9 @@ impl App {
                 sftp_state.local_error.as_deref(),
                 &sftp_state.remote_entries,
                 sftp_state.remote_error.as_deref(),
-                sftp_state.remote_loading,
+                sftp_state.remote_listing.is_some(),
+                sftp_state.local_viewport,
+                sftp_state.remote_viewport,
                 &self.tabs[self.active_tab].state,
                 sftp_state.local_selected.as_deref(),
                 sftp_state.remote_selected.as_deref(),
@@ -119,11 +258,20 @@ impl App {
                 sftp_state.context_menu.as_ref(),
                 self.sftp_panel_width,
                 self.window_height as f32,
-                &sftp_state.transfers,
                 &self.sftp_rename_input_id,
                 sftp_state.rename_target.as_ref(),
                 &sftp_state.rename_value,
                 self.sftp_hovered_file.as_ref(),
+                sftp_state
+                    .clipboard
+                    .as_ref()
+                    .map(|clipboard| clipboard.pane),
+                self.viewer_mode(),
+                presets,
+                find_preset(sftp_state.local_filter.as_deref()),
+                find_preset(sftp_state.remote_filter.as_deref()),
+                (self.transfer_panel_mode == TransferPanelMode::Docked)
+                    .then(|| self.transfer_queue(sftp_state)),
             ))
             .padding(12)
             .width(Length::Fill)
@@ -150,7 +298,7 @@ impl App {
 
             let overlay = container(
                 iced::widget::mouse_area(sftp_panel)
-                    .on_move(Message::SftpPanelCursorMoved)
+                    .on_move(|value| Message::Sftp(SftpMessage::PanelCursorMoved(value)))
                     .on_press(Message::Ignore),
             )
             .width(Length::Fill)
@@ -160,8 +308,8 @@ impl App {
             let layered = stack![content_view, backdrop, overlay];
 
             iced::widget::mouse_area(layered)
-                .on_move(Message::SftpDragMove)
-                .on_release(Message::SftpDragEnd)
+                .on_move(|value| Message::Sftp(SftpMessage::DragMove(value)))
+                .on_release(Message::Sftp(SftpMessage::DragEnd))
                 .into()
         } else {
             content_view
@@ -202,7 +350,11 @@ impl App {
                             },
                         );
                         (
-                            components::port_forward_dialog::render_list(session, statuses),
+                            components::port_forward_dialog::render_list(
+                                session,
+                                statuses,
+                                &self.port_forward_checks,
+                            ),
                             banner,
                         )
                     } else {
@@ -310,7 +462,11 @@ impl App {
             // Center the popover
             let popover = container(views::quick_connect::render(
                 &self.quick_connect_query,
-                &self.saved_sessions,
+                self.quick_connect_matches(),
+                self.quick_connect_commands(),
+                self.quick_connect_selected,
+                self.quick_connect_clipboard.as_ref(),
+                self.quick_connect_share_link(),
             ))
             .width(Length::Fill)
             .height(Length::Fill)
@@ -342,7 +498,10 @@ impl App {
 
         let view_with_sftp_dialog = if sftp_state.delete_target.is_some() {
             let dialog_content = if let Some(target) = &sftp_state.delete_target {
-                views::sftp::delete_dialog(&target.name, target.is_dir)
+                let trash_dir = (target.pane == SftpPane::Remote
+                    && self.app_settings.sftp_remote_trash)
+                    .then_some(self.app_settings.sftp_trash_dir.as_str());
+                views::sftp::delete_dialog(&target.name, target.is_dir, trash_dir)
             } else {
                 container(Space::new()).into()
             };
@@ -355,7 +514,7 @@ impl App {
             .width(Length::Fill)
             .height(Length::Fill)
             .style(ui_style::modal_backdrop)
-            .on_press(Message::SftpDeleteCancel);
+            .on_press(Message::Sftp(SftpMessage::DeleteCancel));
 
             let dialog =
                 container(iced::widget::mouse_area(dialog_content).on_press(Message::Ignore))
@@ -381,33 +540,56 @@ impl App {
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .style(ui_style::modal_backdrop)
-                .on_press(Message::CancelSessionEdit);
+                .on_press(Message::Sessions(SessionMessage::CancelEdit));
 
                 // Centered dialog wrapped in mouse_area to capture clicks
-                let dialog_content = components::session_dialog::render(
-                    self.editing_session.as_ref(),
-                    &self.saved_sessions,
-                    &self.app_settings.ssh_keys,
-                    &self.form_name,
-                    &self.form_host,
-                    &self.form_port,
-                    &self.form_username,
-                    &self.form_password,
-                    &self.form_key_id,
-                    &self.form_key_passphrase,
-                    self.auth_method_password,
-                    self.show_password,
-                    &self.connection_test_status,
-                    self.saved_key_menu_open,
-                    self.validation_error.as_ref(),
-                    self.session_dialog_tab,
-                    &self.port_forward_local_host,
-                    &self.port_forward_local_port,
-                    &self.port_forward_remote_host,
-                    &self.port_forward_remote_port,
-                    self.port_forward_direction.clone(),
-                    self.port_forward_error.as_ref(),
-                );
+                let dialog_content = components::session_dialog::render(SessionDialog {
+                    editing_session: self.editing_session.as_ref(),
+                    saved_sessions: &self.saved_sessions,
+                    saved_keys: &self.app_settings.ssh_keys,
+                    form_name: &self.form_name,
+                    form_host: &self.form_host,
+                    form_port: &self.form_port,
+                    form_username: &self.form_username,
+                    form_password: &self.form_password,
+                    form_key_id: &self.form_key_id,
+                    form_fallback_keys: &self.form_fallback_keys,
+                    form_idle_timeout: &self.form_idle_timeout,
+                    form_connect_timeout: &self.form_connect_timeout,
+                    default_connect_timeout: self.app_settings.connect_timeout_secs,
+                    form_access_expires: &self.form_access_expires,
+                    form_disconnect_on_expiry: self.form_disconnect_on_expiry,
+                    form_debug_log: self.form_debug_log,
+                    form_preflight: self.form_preflight,
+                    form_host_key: &self.form_host_key,
+                    form_port_knock: &self.form_port_knock,
+                    form_jump_host: &self.form_jump_host,
+                    form_proxy: &self.form_proxy,
+                    form_proxy_password: &self.form_proxy_password,
+                    form_transfer_limits: self.form_transfer_limits,
+                    form_keepalive: self.form_keepalive,
+                    form_forward_agent: self.form_forward_agent,
+                    form_compression: self.form_compression,
+                    form_bell: self.form_bell,
+                    form_tags: &self.form_tags,
+                    form_badge: &self.form_badge,
+                    form_tmux_session: &self.form_tmux_session,
+                    form_remote_command: &self.form_remote_command,
+                    form_environment: &self.form_environment,
+                    form_send_locale: self.form_send_locale,
+                    form_auth: self.form_auth,
+                    show_password: self.show_password,
+                    connection_test_status: &self.connection_test_status,
+                    saved_key_menu_open: self.saved_key_menu_open,
+                    validation_error: self.validation_error.as_ref(),
+                    session_dialog_tab: self.session_dialog_tab,
+                    port_forward_local_host: &self.port_forward_local_host,
+                    port_forward_local_port: &self.port_forward_local_port,
+                    port_forward_remote_host: &self.port_forward_remote_host,
+                    port_forward_remote_port: &self.port_forward_remote_port,
+                    port_forward_direction: self.port_forward_direction.clone(),
+                    port_forward_error: self.port_forward_error.as_ref(),
+                });
 
                 // Wrap in mouse_area to prevent click-through
                 let dialog = container(
@@ -423,7 +605,326 @@ impl App {
                 view_with_sftp_dialog
             };
 
-        let root: Element<'_, Message> = with_session_dialog;
+        let with_log_tail: Element<'_, Message> = if let Some(log_tail) = &self.log_tail {
+            let backdrop = button(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::modal_backdrop)
+            .on_press(Message::LogTailClose);
+
+            let width = (self.window_width as f32 - 120.0).clamp(360.0, 1100.0);
+            let height = (self.window_height as f32 - 140.0).max(240.0);
+            let dialog = container(
+                iced::widget::mouse_area(views::log_viewer::render(log_tail, width, height))
+                    .on_press(Message::Ignore),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+            stack![with_session_dialog, backdrop, dialog].into()
+        } else {
+            with_session_dialog
+        };
+
+        let with_debug_log: Element<'_, Message> = if let Some(tab) =
+            self.debug_log_tab.and_then(|index| self.tabs.get(index))
+            && let Some(log) = &tab.debug_log
+        {
+            let backdrop = button(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::modal_backdrop)
+            .on_press(Message::Sessions(SessionMessage::CloseDebugLog));
+
+            let width = (self.window_width as f32 - 120.0).clamp(360.0, 1100.0);
+            let height = (self.window_height as f32 - 140.0).max(240.0);
+            let dialog = container(
+                iced::widget::mouse_area(views::debug_log::render(
+                    &tab.title,
+                    log.lines(),
+                    self.debug_log_status.as_deref(),
+                    width,
+                    height,
+                ))
+                .on_press(Message::Ignore),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+
+            stack![with_log_tail, backdrop, dialog].into()
+        } else {
+            with_log_tail
+        };
+
+        let with_overview: Element<'_, Message> = if self.show_tab_overview {
+            let backdrop = button(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::modal_backdrop)
+            .on_press(Message::ToggleTabOverview);
+            let overview = container(views::tab_overview::render(
+                &self.tabs,
+                self.active_tab,
+                self.window_width,
+            ))
+            .style(ui_style::quick_connect_container)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+            stack![with_debug_log, backdrop, container(overview).padding(24)].into()
+        } else {
+            with_debug_log
+        };
+
+        let with_send_keys: Element<'_, Message> = if self.show_send_key_menu {
+            let backdrop = iced::widget::mouse_area(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .on_press(Message::Terminal(TerminalMessage::ToggleSendKeyMenu));
+            let layer = container(views::send_key::render(&self.app_settings.custom_keys))
+                .padding(iced::Padding {
+                    top: 0.0,
+                    right: 12.0,
+                    bottom: 32.0,
+                    left: 0.0,
+                })
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_right(Length::Fill)
+                .align_bottom(Length::Fill);
+            stack![with_overview, backdrop, layer].into()
+        } else if self.show_jobs_panel {
+            let backdrop = iced::widget::mouse_area(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .on_press(Message::ToggleJobsPanel);
+            let layer = container(views::jobs::panel(&self.background_jobs))
+                .padding(iced::Padding {
+                    top: 0.0,
+                    right: 12.0,
+                    bottom: 32.0,
+                    left: 0.0,
+                })
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_right(Length::Fill)
+                .align_bottom(Length::Fill);
+            stack![with_overview, backdrop, layer].into()
+        } else if let Some(tab) = self
+            .tabs
+            .get(self.active_tab)
+            .filter(|tab| self.show_bandwidth_popover && tab.bandwidth.is_some())
+        {
+            let backdrop = iced::widget::mouse_area(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .on_press(Message::Terminal(TerminalMessage::ToggleBandwidthPopover));
+            let layer = container(views::bandwidth::popover(tab))
+                .padding(iced::Padding {
+                    top: 0.0,
+                    right: 12.0,
+                    bottom: 32.0,
+                    left: 0.0,
+                })
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_right(Length::Fill)
+                .align_bottom(Length::Fill);
+            stack![with_overview, backdrop, layer].into()
+        } else if let Some(terminal_menu) = self
+            .terminal_menu
+            .filter(|_| self.active_view == ActiveView::Terminal)
+        {
+            let backdrop = iced::widget::mouse_area(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .on_press(Message::Terminal(TerminalMessage::CloseMenu))
+            .on_right_press(Message::Terminal(TerminalMessage::CloseMenu));
+            let menu = views::terminal_menu::render(
+                terminal_menu,
+                &self.snippets,
+                self.window_width,
+                self.window_height,
+            );
+            stack![with_overview, backdrop, menu].into()
+        } else if let Some(selection) = self.selection_actions.as_ref().filter(|selection| {
+            self.active_view == ActiveView::Terminal && selection.tab_index == self.active_tab
+        }) {
+            let toolbar = views::terminal_menu::selection_toolbar(
+                selection,
+                self.window_width,
+                self.window_height,
+            );
+            stack![with_overview, toolbar].into()
+        } else {
+            with_overview
+        };
+
+        let sharing: Vec<Element<'_, Message>> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tab)| {
+                let host = tab.share.as_ref()?;
+                Some(views::toast::sharing(index, &tab.title, host))
+            })
+            .collect();
+        let has_toasts = !sharing.is_empty()
+            || self.disconnect_toast.is_some()
+            || self.job_toast.is_some()
+            || self.trash_toast.is_some()
+            || self.agent_sign_shown.is_some();
+        let toasts = column(sharing)
+            .push(self.trash_toast.as_ref().map(views::toast::trash))
+            .push(self.job_toast.as_ref().map(views::toast::job_failure))
+            .push(self.disconnect_toast.as_ref().map(views::toast::render))
+            .push(
+                self.agent_sign_shown
+                    .as_ref()
+                    .map(|request| views::toast::agent_sign(request, self.viewer_mode())),
+            )
+            .spacing(8);
+        let root: Element<'_, Message> = if has_toasts {
+            let layer = container(toasts)
+                .padding(iced::Padding {
+                    top: 0.0,
+                    right: 16.0,
+                    bottom: 36.0,
+                    left: 0.0,
+                })
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_right(Length::Fill)
+                .align_bottom(Length::Fill);
+            stack![with_send_keys, layer].into()
+        } else {
+            with_send_keys
+        };
+
+        let root: Element<'_, Message> = if let Some((_, program)) = &self.pending_close {
+            let backdrop = button(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::modal_backdrop)
+            .on_press(Message::CancelCloseTab);
+            let dialog = container(
+                iced::widget::mouse_area(views::close_warning::render(program))
+                    .on_press(Message::Ignore),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+            stack![root, backdrop, dialog].into()
+        } else {
+            root
+        };
+
+        let root: Element<'_, Message> = if let Some(prompt) = &self.placeholder_prompt {
+            let backdrop = button(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::modal_backdrop)
+            .on_press(Message::CancelPlaceholders);
+            let dialog = container(
+                iced::widget::mouse_area(views::placeholder_prompt::render(prompt))
+                    .on_press(Message::Ignore),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+            stack![root, backdrop, dialog].into()
+        } else {
+            root
+        };
+
+        let root: Element<'_, Message> = if let Some(deploy) = &self.deploy_key {
+            let backdrop = button(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::modal_backdrop)
+            .on_press(Message::CloseDeployKey);
+            let dialog = container(
+                iced::widget::mouse_area(views::deploy_key::render(
+                    deploy,
+                    &self.app_settings.ssh_keys,
+                ))
+                .on_press(Message::Ignore),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+            stack![root, backdrop, dialog].into()
+        } else {
+            root
+        };
+
+        // Above everything else: a connection is waiting on the answer.
+        let root: Element<'_, Message> = if let Some(request) = &self.credential_shown {
+            let backdrop = button(
+                container(Space::new())
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::modal_backdrop)
+            .on_press(Message::Ignore);
+            let dialog = container(
+                iced::widget::mouse_area(views::credential_prompt::render(
+                    request,
+                    &self.credential_input,
+                    self.credential_remember,
+                    &self.credential_input_id,
+                ))
+                .on_press(Message::Ignore),
+            )
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill);
+            stack![root, backdrop, dialog].into()
+        } else {
+            root
+        };
 
         let drag_layer: Element<'_, Message> = if let Some((_pane, name)) = &self.sftp_file_dragging
         {
@@ -457,6 +958,79 @@ impl App {
 
         stack![root, drag_layer].into()
     }
+
+    /// Queue of the active tab's session.
+    fn transfer_queue<'a>(&'a self, sftp_state: &'a SftpState) -> views::sftp::TransferQueue<'a> {
+        let session_id = self
+            .tabs
+            .get(self.active_tab)
+            .and_then(|tab| tab.sftp_key.as_deref());
+        views::sftp::TransferQueue {
+            transfers: &sftp_state.transfers,
+            running: sftp_state.running_transfers(),
+            limits: self.transfer_limits_for_tab(self.active_tab),
+            defaults: self
+                .session_for_tab(self.active_tab)
+                .map(|session| session.transfer_defaults),
+            show_jobs: self.sftp_show_jobs,
+            jobs: self
+                .transfer_jobs
+                .iter()
+                .filter(|job| session_id == Some(job.session_id.as_str()))
+                .map(|job| views::sftp::JobRow {
+                    job,
+                    runs: self.job_runs.get(&job.id).map_or(&[], Vec::as_slice),
+                    running: self
+                        .background_jobs
+                        .running(&JobOwner::Scheduled(job.id.clone()))
+                        .is_some(),
+                    next_run: job.next_run(self.jobs_since),
+                })
+                .collect(),
+        }
+    }
+
+    /// Latency is only known for sessions open in a tab; with several tabs
+    /// on one session the fastest is shown.
+    fn session_table(&self) -> views::session_manager::SessionTable<'_> {
+        let now = std::time::Instant::now();
+        let mut latency = std::collections::HashMap::new();
+        for tab in &self.tabs {
+            let (Some(id), Some(current)) = (&tab.sftp_key, tab.input_latency.current(now)) else {
+                continue;
+            };
+            latency
+                .entry(id.clone())
+                .and_modify(|fastest: &mut std::time::Duration| *fastest = (*fastest).min(current))
+                .or_insert(current);
+        }
+        views::session_manager::SessionTable {
+            sort: self.session_sort,
+            selected: &self.selected_sessions,
+            latency,
+            delete_armed: self.bulk_delete_armed,
+        }
+    }
+
+    /// The popped-out queue follows whichever tab is active in the main
+    /// window.
+    fn transfer_window_view(&self) -> Element<'_, Message> {
+        use iced::widget::container;
+
+        let sftp_state = self.sftp_state_for_tab(self.active_tab).unwrap_or_else(|| {
+            self.sftp_states
+                .get("session-manager")
+                .expect("missing sftp state")
+        });
+        container(iced::widget::responsive(move |size| {
+            views::sftp::transfer_queue(self.transfer_queue(sftp_state), size.width, true)
+        }))
+        .padding(12)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(ui_style::app_background)
+        .into()
+    }
 }
 
 fn sftp_name_column_width(panel_width: f32) -> f32 {
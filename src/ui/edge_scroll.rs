@@ -0,0 +1,85 @@
+use std::time::Instant;
+
+// Pixels per second for each pixel the pointer is past the edge, and how
+// far past the edge still speeds it up.
+const SPEED_PER_PIXEL: f32 = 10.0;
+const MAX_OVERSHOOT: f32 = 150.0;
+
+/// Scrolls a view while a drag is held past its top or bottom edge, faster
+/// the further past it the pointer is. Shared by terminal selections and
+/// files dragged in the SFTP lists.
+#[derive(Debug, Default)]
+pub struct EdgeScroll {
+    // Negative above the top, positive below the bottom.
+    overshoot: f32,
+    last_frame: Option<Instant>,
+}
+
+impl EdgeScroll {
+    /// Notes the pointer at `y` against a view spanning `top..top + height`
+    /// and returns whether it is past an edge.
+    pub fn track(&mut self, y: f32, top: f32, height: f32) -> bool {
+        self.overshoot = if height <= 0.0 {
+            0.0
+        } else if y < top {
+            y - top
+        } else if y > top + height {
+            y - top - height
+        } else {
+            0.0
+        };
+        if self.overshoot == 0.0 {
+            self.last_frame = None;
+        }
+        self.overshoot != 0.0
+    }
+
+    pub fn stop(&mut self) {
+        self.overshoot = 0.0;
+        self.last_frame = None;
+    }
+
+    /// Pixels to scroll since the previous frame, negative towards the top,
+    /// or `None` while the pointer is inside the view. The first frame past
+    /// the edge only starts the clock.
+    pub fn step(&mut self, now: Instant) -> Option<f32> {
+        if self.overshoot == 0.0 {
+            return None;
+        }
+        let elapsed = self
+            .last_frame
+            .map(|last| now.saturating_duration_since(last).as_secs_f32())
+            .unwrap_or(0.0);
+        self.last_frame = Some(now);
+        Some(self.overshoot.clamp(-MAX_OVERSHOOT, MAX_OVERSHOOT) * SPEED_PER_PIXEL * elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn scrolls_faster_further_past_the_edge() {
+        let start = Instant::now();
+        let frame = start + Duration::from_millis(100);
+        let mut scroll = EdgeScroll::default();
+        assert!(!scroll.track(50.0, 10.0, 100.0));
+        assert_eq!(scroll.step(start), None);
+
+        assert!(scroll.track(120.0, 10.0, 100.0));
+        assert_eq!(scroll.step(start), Some(0.0));
+        assert_eq!(scroll.step(frame), Some(10.0));
+
+        assert!(scroll.track(-30.0, 10.0, 100.0));
+        assert_eq!(scroll.step(frame + Duration::from_millis(100)), Some(-40.0));
+
+        // Far past the edge the speed stops growing.
+        scroll.track(10_000.0, 10.0, 100.0);
+        assert_eq!(
+            scroll.step(frame + Duration::from_millis(200)),
+            Some(MAX_OVERSHOOT * SPEED_PER_PIXEL * 0.1)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::{Duration, Instant};
+
+// Input that gets no output back within this long (a password prompt, a
+// silent command) is not counted as a sample.
+const MAX_ECHO_WAIT: Duration = Duration::from_secs(5);
+// Thresholds for the status bar indicator.
+const SLOW: Duration = Duration::from_millis(150);
+const LAGGY: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyLevel {
+    Good,
+    Slow,
+    Laggy,
+}
+
+/// Time from a keystroke being sent to the first output that follows it,
+/// which for a shell is the echo. Smoothed so one slow redraw doesn't
+/// flip the indicator.
+#[derive(Debug, Clone, Default)]
+pub struct InputLatency {
+    sent: Option<Instant>,
+    smoothed: Option<Duration>,
+}
+
+impl InputLatency {
+    /// Starts a measurement unless one is already waiting; fast typing
+    /// measures from the first key that hasn't been echoed yet.
+    pub fn input_sent(&mut self, now: Instant) {
+        if self
+            .sent
+            .is_none_or(|sent| now.duration_since(sent) > MAX_ECHO_WAIT)
+        {
+            self.sent = Some(now);
+        }
+    }
+
+    pub fn output_received(&mut self, now: Instant) {
+        let Some(sent) = self.sent.take() else {
+            return;
+        };
+        let sample = now.duration_since(sent);
+        if sample > MAX_ECHO_WAIT {
+            return;
+        }
+        self.smoothed = Some(match self.smoothed {
+            Some(previous) => (previous * 3 + sample) / 4,
+            None => sample,
+        });
+    }
+
+    /// The smoothed round trip, or how long the current keystroke has been
+    /// waiting if that is already longer, so a stalled link shows at once.
+    pub fn current(&self, now: Instant) -> Option<Duration> {
+        let waiting = self
+            .sent
+            .map(|sent| now.duration_since(sent))
+            .filter(|waiting| *waiting <= MAX_ECHO_WAIT);
+        match (self.smoothed, waiting) {
+            (Some(smoothed), Some(waiting)) => Some(smoothed.max(waiting)),
+            (Some(smoothed), None) => Some(smoothed),
+            // Nothing measured yet; only report a wait once it is noticeable.
+            (None, waiting) => waiting.filter(|waiting| *waiting >= SLOW),
+        }
+    }
+
+    pub fn level(latency: Duration) -> LatencyLevel {
+        if latency >= LAGGY {
+            LatencyLevel::Laggy
+        } else if latency >= SLOW {
+            LatencyLevel::Slow
+        } else {
+            LatencyLevel::Good
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_from_the_first_unechoed_key() {
+        let start = Instant::now();
+        let mut latency = InputLatency::default();
+        latency.input_sent(start);
+        latency.input_sent(start + Duration::from_millis(30));
+        latency.output_received(start + Duration::from_millis(80));
+        assert_eq!(latency.current(start), Some(Duration::from_millis(80)));
+
+        latency.input_sent(start + Duration::from_secs(1));
+        latency.output_received(start + Duration::from_millis(1160));
+        assert_eq!(
+            latency.current(start + Duration::from_secs(2)),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    #[test]
+    fn a_stalled_echo_shows_right_away() {
+        let start = Instant::now();
+        let mut latency = InputLatency::default();
+        latency.input_sent(start);
+        assert_eq!(latency.current(start + Duration::from_millis(50)), None);
+        let waiting = latency.current(start + Duration::from_millis(600)).unwrap();
+        assert_eq!(InputLatency::level(waiting), LatencyLevel::Laggy);
+        // Never echoed: dropped instead of recorded as a huge sample.
+        latency.output_received(start + Duration::from_secs(10));
+        assert_eq!(latency.current(start + Duration::from_secs(10)), None);
+    }
+}
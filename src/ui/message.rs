@@ -1,6 +1,9 @@
-use crate::session::config::PortForwardDirection;
+use crate::session::config::{Keepalive, PortForwardDirection, TransferDefaults};
 use crate::terminal::TerminalDamage;
-use crate::ui::state::{PortForwardStatus, SftpContextAction, SftpPane, SftpTransferUpdate};
+use crate::ui::state::{
+    DirSize, PortCheckResult, PortForwardStatus, SessionColumn, SftpContextAction, SftpPane,
+    SftpTransferUpdate,
+};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -15,15 +18,22 @@ pub enum ActiveView {
 pub enum SessionDialogTab {
     General,
     PortForwarding,
+    Advanced,
 }
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum Message {
+    Sftp(SftpMessage),
+    Terminal(TerminalMessage),
+    Sessions(SessionMessage),
     // CreateSession, // Removed unused
     CreateLocalTab,
+    RestartLocalShell(usize),
     SelectTab(usize),
     CloseTab(usize),
+    ConfirmCloseTab,
+    CancelCloseTab,
     // Menu actions
     ShowSessionManager,
     ToggleSftpPanel,
@@ -33,118 +43,316 @@ pub enum Message {
     PortForwardDragStart,
     PortForwardDragEnd,
     PortForwardDragMove(iced::Point),
-    SftpDragStart,                             // Window resize drag
-    SftpDragEnd,                               // Window resize drag end
-    SftpDragMove(iced::Point),                 // Window resize drag move
-    SftpFileDragStart(SftpPane, String),       // Internal file drag start
-    SftpFileDragUpdate(iced::Point),           // Internal file drag update
-    SftpFileDragEnd,                           // Internal file drag end
-    SftpFileHover(Option<(SftpPane, String)>), // Hover state
-    SftpLocalPathChanged(String),
-    SftpRemotePathChanged(String),
-    SftpRemoteLoaded(
-        usize,
-        Result<(Vec<crate::ui::state::SftpEntry>, Option<String>), String>,
+    ControlServerStarted(u16, Result<Arc<crate::control::ControlServer>, String>),
+    ControlCall(crate::control::ControlCall),
+    // Scheduled transfers
+    SetTransferPanelMode(crate::ui::state::TransferPanelMode),
+    RunTransferJob(String),
+    TransferJobFinished(String, Result<u64, String>),
+    TransferJobScheduleChanged(String, String),
+    DeleteTransferJob(String),
+    JobToastDismiss,
+    // Log tail viewer
+    LogTailStarted(
+        Uuid,
+        Result<Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>>>, String>,
     ),
-    SftpPanelCursorMoved(iced::Point),
-    SftpOpenContextMenu(SftpPane, String),
-    SftpCloseContextMenu,
-    SftpContextAction(SftpPane, String, SftpContextAction),
-    SftpTransferUpdate(SftpTransferUpdate),
-    SftpTransferCancel(Uuid),
-    SftpTransferRetry(Uuid),
-    SftpTransferClearDone,
-    SftpTransferPause(Uuid),
-    SftpTransferResume(Uuid),
-    SftpRenameStart(SftpPane, String, bool),
-    SftpRenameInput(String),
-    SftpRenameCancel,
-    SftpRenameConfirm,
-    SftpRenameFinished(usize, Result<(), String>),
-    SftpDeleteStart(SftpPane, String, bool),
-    SftpDeleteCancel,
-    SftpDeleteConfirm,
-    SftpDeleteFinished(usize, Result<(), String>),
-    SftpLocalEntryPressed(String, bool),
-    SftpRemoteEntryPressed(String, bool),
-    OpenPortForwarding(String),
-    ClosePortForwarding,
-    PortForwardLocalPortChanged(String),
-    PortForwardLocalHostChanged(String),
-    PortForwardRemoteHostChanged(String),
-    PortForwardRemotePortChanged(String),
-    PortForwardDirectionChanged(PortForwardDirection),
-    AddPortForward,
-    TogglePortForward(String),
-    DeletePortForward(String),
+    LogTailData(Uuid, Vec<u8>),
+    LogTailTogglePause,
+    LogTailFilterChanged(String),
+    LogTailHighlightChanged(String),
+    LogTailClear,
+    LogTailClose,
+    CheckPortForward(String),
+    PortForwardChecked(String, Vec<PortCheckResult>),
     ShowSettings,
     // Quick Connect
     ToggleQuickConnect,
+    ToggleTabOverview,
     QuickConnectQueryChanged(String),
+    QuickConnectMoveSelection(i32),
+    QuickConnectSubmit,
     SelectQuickConnectSession(String), // Session Name
-    ToggleSessionMenu(String),
-    CloseSessionMenu,
-    // Session management
-    CreateNewSession,
-    EditSession(String),
-    DeleteSession(String),
-    ConnectToSession(String),
-    SaveSession,
-    CancelSessionEdit,
-    CloseSessionManager,
-    ToggleAuthMethod,
-    #[allow(dead_code)]
-    ClearValidationError,
-    // Session form fields
-    SessionNameChanged(String),
-    SessionHostChanged(String),
-    SessionPortChanged(String),
-    SessionUsernameChanged(String),
-    SessionPasswordChanged(String),
-    TogglePasswordVisibility,
-    SessionKeyIdChanged(String),
-    SessionKeyPassphraseChanged(String),
-    SessionSearchChanged(String),
-    ToggleSavedKeyMenu,
-    CloseSavedKeyMenu,
-    SessionDialogTabSelected(SessionDialogTab),
-    TestConnection,
-    TestConnectionResult(Result<(), String>),
+    QuickConnectInsertCommand(usize, String),
+    QuickConnectClipboardRead(Option<String>),
+    // Session list
+    SetSessionTable(bool),
+    SortSessions(SessionColumn),
+    ToggleSessionSelected(String),
+    SelectSessions(Vec<String>, bool),
+    ConnectSelectedSessions,
+    DeleteSelectedSessions,
+    WarmUpSession(String),
+    WarmUpFinished(String, Result<crate::ui::state::SshConnection, String>),
+    ProbeSessions,
+    SessionProbed(String, Result<std::time::Duration, String>),
     // SSH Connection
-    SessionConnected(
+    SessionConnected(Result<crate::ui::state::SshConnection, String>, usize),
+    ShellOpened(Result<russh::ChannelId, String>, usize),
+    WindowResized(u32, u32),
+    WindowMoved(iced::Point),
+    WindowMaximized(bool),
+    WindowOpened(iced::window::Id),
+    WindowClosed(iced::window::Id),
+    OpenUrl(String),
+    OpenRemotePath(String),
+    /// A `path:line:col` Cmd+clicked in the terminal.
+    OpenTerminalLocation(crate::terminal::editor::FileLocation),
+    EditorOpened(Result<(), String>),
+    LocalFileRevealed(Result<(), String>),
+    RetryConnection(usize), // tab index to retry
+    ReconnectNow(usize),
+    CancelReconnect(usize),
+    ToggleFailureLog(usize),
+    SelectionAction(crate::ui::state::SelectionAction),
+    InsertSnippet(usize),
+    PlaceholderChanged(usize, String),
+    SubmitPlaceholders,
+    CancelPlaceholders,
+    /// Opens the deploy public key dialog for the saved session with this id.
+    OpenDeployKey(String),
+    DeployKeySelected(String),
+    DeployKey,
+    DeployKeyFinished(Result<crate::ssh::KeyDeployment, String>),
+    CloseDeployKey,
+    /// Clicked the idle terminal the screen guard is hiding.
+    RevealTerminal,
+    ToggleJobsPanel,
+    CancelBackgroundJob(crate::core::jobs::JobId),
+    ClearFinishedJobs,
+    /// An OSC 52 clipboard query's answer, for the tab with this id.
+    RemoteClipboardReply(Uuid, String),
+    InstallClipboardHelpers,
+    ClipboardHelpersInstalled(uuid::Uuid, Result<String, String>),
+    ShareTab(usize),
+    ShareStarted(
+        usize,
+        Result<Arc<crate::terminal::share::ShareHost>, String>,
+    ),
+    StopSharing(usize),
+    CopyShareLink(usize),
+    WatchShare(crate::terminal::share::ShareLink),
+    WatchStarted(Result<(String, Arc<Mutex<crate::terminal::OutputReceiver>>), String>),
+    DuplicateTab(usize),
+    NewShellOnHost(usize),
+    SiblingShellOpened(
+        usize,
         Result<
             (
-                Arc<Mutex<crate::ssh::SshSession>>,
-                Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>>>,
+                russh::ChannelId,
+                Arc<Mutex<crate::terminal::OutputReceiver>>,
             ),
             String,
         >,
+    ),
+    RuntimeEvent(iced::event::Event, iced::window::Id),
+    Ignore,
+    Tick(std::time::Instant),
+}
+
+/// Messages for the SFTP panel, handled in `update::sftp`.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum SftpMessage {
+    DragStart,                             // Panel resize drag
+    DragEnd,                               // Panel resize drag end
+    DragMove(iced::Point),                 // Panel resize drag move
+    FileDragStart(SftpPane, String),       // Internal file drag start
+    FileDragUpdate(iced::Point),           // Internal file drag update
+    FileDragEnd,                           // Internal file drag end
+    FileHover(Option<(SftpPane, String)>), // Hover state
+    LocalPathChanged(String),
+    RemotePathChanged(String),
+    RemoteListing(usize, Uuid, crate::ui::state::SftpListEvent),
+    ListScrolled(SftpPane, iced::widget::scrollable::Viewport),
+    SetFilter(SftpPane, Option<String>),
+    EntryDetails(
         usize,
+        String,
+        String,
+        Result<crate::ui::state::SftpEntryDetails, String>,
     ),
-    ShellOpened(Result<russh::ChannelId, String>, usize),
-    TerminalDataReceived(usize, Vec<u8>),
-    TerminalDamaged(usize, TerminalDamage),
-    TerminalInput(Vec<u8>),
-    // Terminal Mouse Events
-    TerminalMousePress(usize, usize),
-    TerminalMouseDrag(usize, usize),
-    TerminalMouseRelease,
-    TerminalMouseDoubleClick(usize, usize),
-    TerminalResize(usize, usize),
-    WindowResized(u32, u32),
-    WindowOpened(iced::window::Id),
-    WindowClosed(iced::window::Id),
-    OpenUrl(String),
-    ScrollWheel(f32),         // delta in lines
-    RetryConnection(usize),   // tab index to retry
-    EditSessionConfig(usize), // tab index to edit
+    LocalEntryPressed(String, bool),
+    RemoteEntryPressed(String, bool),
+    PanelCursorMoved(iced::Point),
+    OpenContextMenu(SftpPane, String),
+    CloseContextMenu,
+    ContextAction(SftpPane, String, SftpContextAction),
+    // Transfers
+    TransferUpdate(SftpTransferUpdate),
+    DownloadTo(usize, String, Option<std::path::PathBuf>),
+    UploadFrom(usize, Vec<std::path::PathBuf>),
+    TransferCancel(Uuid),
+    TransferRetry(Uuid),
+    TransferClearDone,
+    TransferDefaultsChanged(TransferDefaults),
+    TransferPause(Uuid),
+    TransferResume(Uuid),
+    ShowJobs(bool),
+    // File operations
+    RenameStart(SftpPane, String, bool),
+    RenameInput(String),
+    RenameCancel,
+    RenameConfirm,
+    RenameFinished(usize, Result<(), String>),
+    FileOpFinished(usize, SftpPane, Result<(), String>),
+    DirSize(usize, SftpPane, String, String, DirSize),
+    DeleteStart(SftpPane, String, bool),
+    DeleteCancel,
+    DeleteConfirm,
+    DeleteFinished(usize, Result<(), String>),
+    MovedToTrash(usize, Result<crate::ui::state::TrashToast, String>),
+    UndoTrash,
+    UndoTrashFinished(uuid::Uuid, Result<(), String>),
+    TrashToastDismiss,
+}
+
+/// Messages for the terminal of the active tab, handled in `update::terminal`.
+#[derive(Debug, Clone)]
+pub enum TerminalMessage {
+    DataReceived(usize, Vec<u8>),
+    Damaged(usize, TerminalDamage),
+    Input(Vec<u8>),
+    /// The shell or remote command on a tab's channel ended, with its exit
+    /// status if known.
+    ShellExited(usize, russh::ChannelId, Option<u32>),
+    // Mouse events
+    MousePress(usize, usize),
+    MouseDrag(usize, usize),
+    /// Lines to scroll, positive into history, while a selection is dragged
+    /// past the top or bottom edge, and the edge cell the drag is at.
+    DragScroll(f32, usize, usize),
+    /// Window position where the button was released.
+    MouseRelease(iced::Point),
+    MouseDoubleClick(usize, usize),
+    Resize(usize, usize),
+    ScrollToBottom,
+    /// Switches a tab between wrapping long lines and scrolling sideways.
+    ToggleLineWrap(usize),
+    ScrollColumnsHome,
+    ScrollTo(usize), // display offset from the bottom
+    ScrollWheel(iced::mouse::ScrollDelta),
+    // Clipboard and context menu
     Copy,
     Paste,
+    SelectAll,
+    CopyScreen,
+    CopyBuffer,
     ClipboardReceived(Option<String>),
+    /// Window position and the viewport line that was right-clicked.
+    OpenMenu(iced::Point, usize),
+    CloseMenu,
+    CopyBlockCommand(usize),
+    CopyBlockOutput(usize),
+    SaveBlockOutput(usize),
+    BlockOutputSaved(Result<Option<String>, String>),
+    ToggleSendKeyMenu,
+    ToggleBandwidthPopover,
+    SendKey(Vec<u8>),
+    ToggleRedaction,
+    // Command history
+    ToggleCommandHistory,
+    CommandHistoryQueryChanged(String),
+    InsertCommand(String),
+    RunCommand(String),
+    ConfirmRunCommand,
+    CancelRunCommand,
+    // Input method
     ImeBufferChanged(String),
     ImeFocusChanged(bool),
     ImePaste,
-    RuntimeEvent(iced::event::Event, iced::window::Id),
-    Ignore,
-    Tick(std::time::Instant),
+}
+
+/// Messages for the session manager, the session form and the prompts a
+/// connecting tab shows, handled in `update::sessions`.
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum SessionMessage {
+    // Session management
+    CreateNew,
+    Edit(String),
+    Delete(String),
+    Connect(String),
+    ConnectClipboardTarget,
+    Save,
+    CancelEdit,
+    CloseManager,
+    SearchChanged(String),
+    ToggleMenu(String),
+    CloseMenu,
+    ToggleUsage(String),
+    SaveTemplate,
+    TemplateHostChanged(String),
+    CreateFromTemplate(String),
+    DeleteTemplate(String),
+    // Session form fields
+    DialogTabSelected(SessionDialogTab),
+    SetAuthMethod(crate::ui::state::AuthChoice),
+    ClearValidationError,
+    NameChanged(String),
+    HostChanged(String),
+    PortChanged(String),
+    UsernameChanged(String),
+    PasswordChanged(String),
+    TogglePasswordVisibility,
+    KeyIdChanged(String),
+    /// Adds a saved key to the end of the ones tried after the first, or
+    /// takes it out.
+    ToggleFallbackKey(String),
+    KeyPassphraseChanged(String),
+    ToggleSavedKeyMenu,
+    CloseSavedKeyMenu,
+    IdleTimeoutChanged(String),
+    ConnectTimeoutChanged(String),
+    AccessExpiresChanged(String),
+    DisconnectOnExpiryToggled(bool),
+    DebugLogToggled(bool),
+    PreflightToggled(bool),
+    HostKeyChanged(String),
+    PortKnockChanged(String),
+    JumpHostChanged(String),
+    TransferLimitsToggled(bool),
+    TransferLimitChanged(crate::session::config::TransferLimit, usize),
+    KeepaliveToggled(bool),
+    KeepaliveChanged(Keepalive),
+    ForwardAgentToggled(bool),
+    CompressionToggled(bool),
+    BellChanged(crate::session::config::BellMode),
+    TagsChanged(String),
+    BadgeChanged(String),
+    TmuxChanged(String),
+    RemoteCommandChanged(String),
+    EnvironmentChanged(String),
+    SendLocaleToggled(bool),
+    ProxyChanged(String),
+    ProxyPasswordChanged(String),
+    TestConnection,
+    TestConnectionResult(Result<(), String>),
+    PreflightFinished(String, Result<crate::ssh::PreflightReport, String>),
+    // Port forwarding dialog
+    OpenPortForwarding(String),
+    ClosePortForwarding,
+    PortForwardLocalPortChanged(String),
+    PortForwardLocalHostChanged(String),
+    PortForwardRemoteHostChanged(String),
+    PortForwardRemotePortChanged(String),
+    PortForwardDirectionChanged(PortForwardDirection),
+    AddPortForward,
+    TogglePortForward(String),
+    DeletePortForward(String),
+    // Connecting tabs
+    EditConfig(usize), // tab index to edit
+    TryPasswordAuth(usize),
+    CredentialInputChanged(String),
+    CredentialRememberToggled(bool),
+    SubmitCredential,
+    CancelCredential,
+    AgentSignAnswer(crate::ui::state::AgentSignChoice),
+    OpenPortForwardCheck(usize),
+    ShowDebugLog(usize),
+    CloseDebugLog,
+    ExportDebugLog,
+    DebugLogExported(Result<Option<String>, String>),
+    DisconnectToastReconnect,
+    DisconnectToastDismiss,
 }
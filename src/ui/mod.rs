@@ -1,14 +1,20 @@
 mod app;
 mod components;
+mod credentials;
 mod domain;
+mod edge_scroll;
+mod latency;
 mod message;
 mod state;
 pub mod style;
 mod terminal_colors;
 mod terminal_gpu_widget;
+mod terminal_scrollbar;
+mod terminal_text_style;
 mod terminal_widget;
 mod views;
+mod virtual_list;
 
 pub use app::App;
-pub use message::{ActiveView, Message};
+pub use message::{ActiveView, Message, SessionMessage, SftpMessage, TerminalMessage};
 pub use state::SessionTab;
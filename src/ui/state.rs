@@ -1,12 +1,17 @@
+use crate::core::jobs::{CancelToken, JobId};
 use crate::core::session::Session;
+use crate::session::SessionConfig;
+use crate::session::config::{Keepalive, Proxy};
 use crate::terminal::{TerminalDamage, TerminalEmulator};
+use crate::ui::virtual_list::ListViewport;
 use iced::Point;
 use iced::widget::canvas::Cache;
 use russh_sftp::client::SftpSession;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::mpsc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::sync::Notify;
 
@@ -18,7 +23,28 @@ pub enum SessionState {
     Failed(String),
 }
 
+/// Global settings used where a saved session doesn't set its own.
+#[derive(Debug, Clone)]
+pub struct ConnectDefaults {
+    pub keepalive: Keepalive,
+    pub proxy: Option<Proxy>,
+    pub connect_timeout: Duration,
+    /// Saved keys tried after the session's own.
+    pub offer_keys: Vec<String>,
+}
+
+impl ConnectDefaults {
+    pub fn connect_timeout_for(&self, session: &SessionConfig) -> Duration {
+        session
+            .connect_timeout_secs
+            .map(|secs| Duration::from_secs(u64::from(secs.max(1))))
+            .unwrap_or(self.connect_timeout)
+    }
+}
+
 pub struct SessionTab {
+    /// Stays the same while tabs before it are closed or moved.
+    pub id: uuid::Uuid,
     pub title: String,
     pub chrome_cache: Cache,
     pub line_caches: Vec<Cache>,
@@ -28,7 +54,7 @@ pub struct SessionTab {
     pub session: Option<Session>,
     // Temporary storage for SSH handle before shell is opened
     pub ssh_handle: Option<Arc<Mutex<crate::ssh::SshSession>>>,
-    pub rx: Option<Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>>>>,
+    pub rx: Option<Arc<Mutex<crate::terminal::OutputReceiver>>>,
     pub emulator: TerminalEmulator,
     pub parser_tx: Option<mpsc::Sender<Vec<u8>>>,
     pub damage_rx: Option<Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<TerminalDamage>>>>,
@@ -38,7 +64,72 @@ pub struct SessionTab {
     pub pending_damage_full: bool,
     pub pending_damage_lines: Vec<usize>,
     pub sftp_session: Arc<Mutex<Option<SftpSession>>>,
+    // Raw channel that streams directory listings in batches.
+    pub sftp_list_channel: Arc<Mutex<Option<Arc<crate::ssh::TransferChannel>>>>,
     pub sftp_key: Option<String>,
+    // Set while retrying a tab that was connected before, so the shell
+    // reopens below the existing scrollback.
+    pub reconnecting: bool,
+    /// The session's remote command with its placeholders filled in, when
+    /// they were asked for on connecting.
+    pub remote_command: Option<String>,
+    pub last_input: std::time::Instant,
+    pub show_failure_log: bool,
+    pub debug_log: Option<crate::ssh::DebugLog>,
+    // Output arrived while the tab was not in front.
+    pub has_activity: bool,
+    // Lines of output received while scrolled up in history.
+    pub unseen_lines: usize,
+    // When the bell last rang, used to rate limit repeated bells.
+    pub last_bell: Option<std::time::Instant>,
+    // The access expiry warning has been shown for this connection.
+    pub expiry_warned: bool,
+    // Set while a dropped connection is being retried on its own.
+    pub auto_reconnect: Option<AutoReconnect>,
+    // When output was last dropped because the UI fell behind.
+    pub output_throttled_at: Option<std::time::Instant>,
+    // Socket byte counters of the SSH connection and the rates sampled from them.
+    pub bandwidth: Option<crate::ssh::Bandwidth>,
+    pub bandwidth_history: crate::ssh::BandwidthHistory,
+    // Whether that connection is still up, without locking `ssh_handle`.
+    pub connection_status: Option<crate::ssh::ConnectionStatus>,
+    pub input_latency: crate::ui::latency::InputLatency,
+    pub visual_bell: Option<std::time::Instant>,
+    // Set while the tab is mirrored read-only to viewers on the network.
+    pub share: Option<Arc<crate::terminal::share::ShareHost>>,
+    // The shell process of a local tab.
+    pub local_process: Option<Arc<LocalProcess>>,
+    // The remote command run in place of a shell has ended.
+    pub command_exited: bool,
+    // The shell has exited on a connection that stayed up.
+    pub shell_exit: Option<ShellExit>,
+    // Bounds each step of connecting; counted down under the spinner.
+    pub connect_timeout: Duration,
+}
+
+/// How the shell of an SSH tab ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShellExit {
+    /// Exit status, when the server sent one.
+    pub status: Option<u32>,
+}
+
+/// Retrying a connection that dropped, with growing waits between attempts.
+#[derive(Debug, Clone)]
+pub struct AutoReconnect {
+    /// Attempts that have failed so far.
+    pub attempt: u32,
+    /// When the next attempt starts; None while one is under way.
+    pub next: Option<Instant>,
+    pub last_error: Option<String>,
+}
+
+/// A local shell, with its exit code once it has ended.
+#[derive(Debug)]
+pub struct LocalProcess {
+    pub pid: Option<u32>,
+    pub started: Instant,
+    pub exit_code: std::sync::OnceLock<u32>,
 }
 
 impl std::fmt::Debug for SessionTab {
@@ -51,6 +142,78 @@ impl std::fmt::Debug for SessionTab {
     }
 }
 
+/// A column of the session manager's table view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionColumn {
+    Name,
+    Host,
+    User,
+    Tags,
+    LastConnected,
+    Latency,
+}
+
+impl SessionColumn {
+    pub const ALL: [SessionColumn; 6] = [
+        SessionColumn::Name,
+        SessionColumn::Host,
+        SessionColumn::User,
+        SessionColumn::Tags,
+        SessionColumn::LastConnected,
+        SessionColumn::Latency,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SessionColumn::Name => "Name",
+            SessionColumn::Host => "Host",
+            SessionColumn::User => "User",
+            SessionColumn::Tags => "Tags",
+            SessionColumn::LastConnected => "Last connected",
+            SessionColumn::Latency => "Latency",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionSort {
+    pub column: SessionColumn,
+    pub descending: bool,
+}
+
+impl Default for SessionSort {
+    fn default() -> Self {
+        Self {
+            column: SessionColumn::Name,
+            descending: false,
+        }
+    }
+}
+
+/// How the session form signs in; saved as `AuthMethod`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthChoice {
+    Password,
+    PrivateKey,
+    Agent,
+}
+
+impl AuthChoice {
+    pub const ALL: [AuthChoice; 3] = [
+        AuthChoice::Password,
+        AuthChoice::PrivateKey,
+        AuthChoice::Agent,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AuthChoice::Password => "Password",
+            AuthChoice::PrivateKey => "Private key",
+            AuthChoice::Agent => "SSH agent",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionTestStatus {
     Idle,
@@ -59,6 +222,209 @@ pub enum ConnectionTestStatus {
     Failed(String),
 }
 
+// Right-click menu over the terminal. `block` is the shell-integration
+// command under the cursor, when there is one. `clipboard_helpers` offers
+// installing pbcopy/pbpaste on an SSH host. `sharing` is the tab and
+// whether it is shared, or None when it can't be. `shell_tab` is an SSH tab
+// whose connection can take another shell.
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalMenu {
+    pub position: iced::Point,
+    /// The tab and whether it wraps long lines.
+    pub wrap_tab: Option<(usize, bool)>,
+    pub block: Option<usize>,
+    pub clipboard_helpers: bool,
+    pub sharing: Option<(usize, bool)>,
+    pub shell_tab: Option<usize>,
+}
+
+/// The toolbar offered over text just selected in a terminal.
+#[derive(Debug, Clone)]
+pub struct SelectionActions {
+    pub tab_index: usize,
+    /// Window position where the selection ended.
+    pub position: iced::Point,
+    pub text: String,
+    /// The selection as a link, when it reads as one.
+    pub url: Option<String>,
+    /// An absolute path the SFTP panel can open, on SSH tabs.
+    pub remote_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionAction {
+    Copy,
+    SearchWeb,
+    OpenUrl,
+    RevealInSftp,
+    SaveSnippet,
+}
+
+/// A new connection, its output, and the key passphrases typed to make it.
+pub type SshConnection = (
+    Arc<Mutex<crate::ssh::SshSession>>,
+    Arc<Mutex<crate::terminal::OutputReceiver>>,
+    Vec<crate::ssh::TypedPassphrase>,
+);
+
+// A saved session connected ahead of time because the user highlighted it.
+// `claimed_by` is the tab waiting for a handshake that is still running.
+#[derive(Debug)]
+pub struct WarmConnection {
+    pub session_id: String,
+    pub started: Instant,
+    pub debug_log: Option<crate::ssh::DebugLog>,
+    pub result: Option<Result<SshConnection, String>>,
+    pub claimed_by: Option<usize>,
+}
+
+/// The feature a background job belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobOwner {
+    Transfer(uuid::Uuid),
+    RemoteListing(uuid::Uuid),
+    ConnectionTest,
+    /// A scheduled transfer, by its id.
+    Scheduled(String),
+}
+
+/// The last background check of a saved session's host.
+#[derive(Debug, Clone)]
+pub struct HostProbe {
+    /// `host:port` that was checked, so an edited session is checked again.
+    pub target: String,
+    pub status: HostStatus,
+    pub checked: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostStatus {
+    Checking,
+    /// An SSH server answered; the time the TCP connection took.
+    Reachable(Duration),
+    Unreachable(String),
+}
+
+/// Installing a saved key's public half on a saved session's host.
+#[derive(Debug, Clone)]
+pub struct DeployKey {
+    pub session_id: String,
+    /// Shown as `user@host`.
+    pub target: String,
+    pub key_id: String,
+    pub status: DeployKeyStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeployKeyStatus {
+    Idle,
+    Deploying,
+    Done(crate::ssh::KeyDeployment),
+    Failed(String),
+}
+
+/// Values asked for before a snippet or a session's remote command with
+/// placeholders in it runs.
+#[derive(Debug, Clone)]
+pub struct PlaceholderPrompt {
+    pub title: String,
+    pub text: String,
+    pub placeholders: Vec<crate::session::placeholder::Placeholder>,
+    pub values: Vec<String>,
+    pub target: PlaceholderTarget,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaceholderTarget {
+    /// Pasted into the active tab.
+    Snippet,
+    /// Run when the saved session with this id connects.
+    RemoteCommand(String),
+}
+
+/// When there was last keyboard or mouse input, and whether the terminal
+/// is hidden for want of it.
+#[derive(Debug)]
+pub struct IdleScreen {
+    pub last_input: Instant,
+    pub hidden: bool,
+    /// The key that brought the terminal back is still down.
+    pub revealing: bool,
+}
+
+impl Default for IdleScreen {
+    fn default() -> Self {
+        Self {
+            last_input: Instant::now(),
+            hidden: false,
+            revealing: false,
+        }
+    }
+}
+
+/// What the usage counters were last brought up to date with.
+#[derive(Debug)]
+pub struct UsageTracker {
+    pub ticked: Instant,
+    pub saved: Instant,
+    /// Traffic counters of each open connection and the totals already
+    /// added to its session's usage.
+    pub connections: Vec<(crate::ssh::Bandwidth, (u64, u64))>,
+    pub dirty: bool,
+}
+
+impl Default for UsageTracker {
+    fn default() -> Self {
+        Self {
+            ticked: Instant::now(),
+            saved: Instant::now(),
+            connections: Vec::new(),
+            dirty: false,
+        }
+    }
+}
+
+// Shown when a scheduled transfer fails, since nobody may be watching the
+// SFTP panel when it runs.
+#[derive(Debug, Clone)]
+pub struct JobFailureToast {
+    pub name: String,
+    pub error: String,
+}
+
+/// One run of a scheduled transfer; `Ok` holds the bytes copied.
+#[derive(Debug, Clone)]
+pub struct JobRun {
+    pub finished: chrono::DateTime<chrono::Local>,
+    pub result: Result<u64, String>,
+}
+
+// Shown after a remote delete went to the trash folder, so a mistaken
+// delete can be taken back.
+#[derive(Debug, Clone)]
+pub struct TrashToast {
+    pub tab_id: uuid::Uuid,
+    pub name: String,
+    pub original: String,
+    pub trashed: String,
+}
+
+// Shown when a background SSH tab drops without the user asking for it.
+#[derive(Debug, Clone)]
+pub struct DisconnectToast {
+    pub tab_index: usize,
+    pub title: String,
+}
+
+/// The answer to a forwarded agent asking to sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentSignChoice {
+    AllowOnce,
+    /// Allow this key on this host from now on.
+    Always,
+    Deny,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PortForwardStatus {
     Pending,
@@ -66,12 +432,47 @@ pub enum PortForwardStatus {
     Error(String),
 }
 
+// Result of "Check" on a forward: whether its listener is bound and its
+// target can be reached from the side that connects to it.
+#[derive(Debug, Clone)]
+pub enum PortForwardCheck {
+    Running,
+    Finished(Vec<PortCheckResult>),
+}
+
+#[derive(Debug, Clone)]
+pub struct PortCheckResult {
+    pub label: String,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SftpEntry {
     pub name: String,
     pub size: Option<u64>,
     pub modified: Option<chrono::DateTime<chrono::Local>>,
     pub is_dir: bool,
+    pub is_symlink: bool,
+    /// Filled in by the background details queue for symlinks.
+    pub link_target: Option<String>,
+    /// Total size of a folder's contents, once asked for.
+    pub dir_size: Option<DirSize>,
+}
+
+/// A folder size from "Calculate Size", counted up while it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirSize {
+    Counting(u64),
+    Total(u64),
+    Failed,
+}
+
+/// What a symlink in a remote listing points to.
+#[derive(Debug, Clone)]
+pub struct SftpEntryDetails {
+    pub link_target: String,
+    pub is_dir: bool,
+    pub size: Option<u64>,
 }
 
 #[allow(dead_code)]
@@ -89,6 +490,8 @@ pub enum SftpTransferStatus {
     Failed(String),
     Canceled,
     Paused,
+    /// The destination existed and the session skips existing files.
+    Skipped,
 }
 
 #[derive(Debug, Clone)]
@@ -106,7 +509,9 @@ pub struct SftpTransfer {
     pub last_update: Option<std::time::Instant>,
     pub last_bytes_sent: u64,
     pub last_rate_bps: Option<u64>,
-    pub cancel_flag: Arc<AtomicBool>,
+    /// The job for the current run; a retried transfer gets a new one.
+    pub job: Option<JobId>,
+    pub cancel: CancelToken,
     pub pause_flag: Arc<AtomicBool>,
     pub pause_notify: Arc<Notify>,
 }
@@ -140,6 +545,38 @@ pub struct SftpContextMenu {
     pub position: Point,
 }
 
+/// A remote directory listing that is still streaming in.
+#[derive(Debug, Clone)]
+pub struct RemoteListing {
+    pub id: uuid::Uuid,
+    /// Path as requested, which is also the cache key.
+    pub path: String,
+    pub job: JobId,
+}
+
+/// Going back to a directory within this long reuses the previous listing
+/// instead of asking the server again.
+const REMOTE_CACHE_TTL: Duration = Duration::from_secs(30);
+const REMOTE_CACHE_MAX_DIRS: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct CachedListing {
+    pub entries: Vec<SftpEntry>,
+    /// Resolved path the listing was shown under.
+    pub remote_path: String,
+    fetched: Instant,
+}
+
+/// Progress of a streamed remote directory listing.
+#[derive(Debug, Clone)]
+pub enum SftpListEvent {
+    /// The directory is open; carries the resolved path for relative ones.
+    Opened(Option<String>),
+    Entries(Vec<SftpEntry>),
+    Finished,
+    Failed(String),
+}
+
 #[derive(Debug, Clone)]
 pub struct SftpState {
     pub local_path: String,
@@ -148,7 +585,13 @@ pub struct SftpState {
     pub local_error: Option<String>,
     pub remote_entries: Vec<SftpEntry>,
     pub remote_error: Option<String>,
-    pub remote_loading: bool,
+    pub remote_listing: Option<RemoteListing>,
+    pub remote_cache: HashMap<String, CachedListing>,
+    /// Names in the shown remote directory still waiting for details.
+    pub details_queue: VecDeque<String>,
+    pub details_in_flight: usize,
+    pub local_viewport: ListViewport,
+    pub remote_viewport: ListViewport,
     pub local_selected: Option<String>,
     pub remote_selected: Option<String>,
     pub local_last_click: Option<(String, Instant)>,
@@ -159,6 +602,19 @@ pub struct SftpState {
     pub rename_target: Option<SftpPendingAction>,
     pub rename_value: String,
     pub delete_target: Option<SftpPendingAction>,
+    pub clipboard: Option<SftpClipboard>,
+    /// Names of the filter presets applied to each pane.
+    pub local_filter: Option<String>,
+    pub remote_filter: Option<String>,
+}
+
+/// An entry cut or copied for pasting elsewhere in the same pane.
+#[derive(Debug, Clone)]
+pub struct SftpClipboard {
+    pub pane: SftpPane,
+    pub dir: String,
+    pub name: String,
+    pub cut: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -166,13 +622,111 @@ pub enum SftpContextAction {
     Refresh,
     Upload,
     Download,
+    DownloadTo,
+    UploadFrom,
     Rename,
     Delete,
+    Tail,
+    Cut,
+    Copy,
+    Paste,
+    Schedule,
+    CalculateSize,
+    RevealLocal,
+}
+
+/// A command from a tab's shell-integration history found by quick connect.
+#[derive(Debug, Clone)]
+pub struct CommandMatch {
+    pub record: crate::terminal::shell_integration::CommandRecord,
+    pub tab_index: usize,
+    pub tab_title: String,
+    pub hits: Vec<usize>,
+}
+
+/// Where the transfer queue is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransferPanelMode {
+    /// Below the file panes in the SFTP panel.
+    #[default]
+    Docked,
+    /// Collapsed to a progress pill in the status bar.
+    Compact,
+    /// In its own always-on-top window.
+    Detached,
+}
+
+pub const LOG_TAIL_MAX_LINES: usize = 5000;
+
+#[derive(Debug, Clone)]
+pub struct LogTailState {
+    pub id: uuid::Uuid,
+    pub path: String,
+    pub lines: std::collections::VecDeque<String>,
+    pub held: Vec<String>, // lines received while paused
+    pub partial: String,
+    pub paused: bool,
+    pub filter: String,
+    pub highlight: String,
+    pub streaming: bool,
+    pub error: Option<String>,
+    pub rx: Option<Arc<Mutex<tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>>>>,
+}
+
+impl LogTailState {
+    pub fn new(path: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4(),
+            path,
+            lines: std::collections::VecDeque::new(),
+            held: Vec::new(),
+            partial: String::new(),
+            paused: false,
+            filter: String::new(),
+            highlight: String::new(),
+            streaming: false,
+            error: None,
+            rx: None,
+        }
+    }
+
+    pub fn push_data(&mut self, data: &[u8]) {
+        self.partial.push_str(&String::from_utf8_lossy(data));
+        while let Some(pos) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=pos).collect();
+            let line = line.trim_end_matches(['\r', '\n']).to_string();
+            if self.paused {
+                self.held.push(line);
+            } else {
+                self.lines.push_back(line);
+            }
+        }
+        self.trim();
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        if !paused {
+            self.lines.extend(self.held.drain(..));
+            self.trim();
+        }
+    }
+
+    fn trim(&mut self) {
+        while self.lines.len() > LOG_TAIL_MAX_LINES {
+            self.lines.pop_front();
+        }
+        if self.held.len() > LOG_TAIL_MAX_LINES {
+            let excess = self.held.len() - LOG_TAIL_MAX_LINES;
+            self.held.drain(..excess);
+        }
+    }
 }
 
 impl Clone for SessionTab {
     fn clone(&self) -> Self {
         Self {
+            id: uuid::Uuid::new_v4(),
             title: self.title.clone(),
             chrome_cache: iced::widget::canvas::Cache::new(),
             line_caches: Vec::new(),
@@ -190,7 +744,29 @@ impl Clone for SessionTab {
             pending_damage_full: self.pending_damage_full,
             pending_damage_lines: self.pending_damage_lines.clone(),
             sftp_session: self.sftp_session.clone(),
+            sftp_list_channel: self.sftp_list_channel.clone(),
             sftp_key: self.sftp_key.clone(),
+            reconnecting: self.reconnecting,
+            remote_command: self.remote_command.clone(),
+            last_input: self.last_input,
+            show_failure_log: self.show_failure_log,
+            debug_log: self.debug_log.clone(),
+            has_activity: self.has_activity,
+            unseen_lines: self.unseen_lines,
+            last_bell: self.last_bell,
+            expiry_warned: self.expiry_warned,
+            auto_reconnect: self.auto_reconnect.clone(),
+            output_throttled_at: self.output_throttled_at,
+            bandwidth: self.bandwidth.clone(),
+            bandwidth_history: self.bandwidth_history.clone(),
+            connection_status: self.connection_status.clone(),
+            input_latency: self.input_latency.clone(),
+            visual_bell: self.visual_bell,
+            share: None,
+            local_process: self.local_process.clone(),
+            command_exited: self.command_exited,
+            shell_exit: self.shell_exit,
+            connect_timeout: self.connect_timeout,
         }
     }
 }
@@ -229,6 +805,7 @@ impl SessionTab {
         });
 
         Self {
+            id: uuid::Uuid::new_v4(),
             title: title.to_string(),
             chrome_cache: Cache::default(),
             line_caches,
@@ -246,7 +823,87 @@ impl SessionTab {
             pending_damage_full: true,
             pending_damage_lines: Vec::new(),
             sftp_session: Arc::new(Mutex::new(None)),
+            sftp_list_channel: Arc::new(Mutex::new(None)),
             sftp_key: None,
+            reconnecting: false,
+            remote_command: None,
+            last_input: std::time::Instant::now(),
+            show_failure_log: false,
+            debug_log: None,
+            has_activity: false,
+            unseen_lines: 0,
+            last_bell: None,
+            expiry_warned: false,
+            auto_reconnect: None,
+            output_throttled_at: None,
+            bandwidth: None,
+            bandwidth_history: crate::ssh::BandwidthHistory::default(),
+            connection_status: None,
+            input_latency: crate::ui::latency::InputLatency::default(),
+            visual_bell: None,
+            share: None,
+            local_process: None,
+            command_exited: false,
+            shell_exit: None,
+            connect_timeout: crate::ssh::DEFAULT_CONNECT_TIMEOUT,
+        }
+    }
+
+    /// The SSH channel the tab's shell runs on.
+    pub fn ssh_channel(&self) -> Option<russh::ChannelId> {
+        match &*self.session.as_ref()?.backend {
+            crate::core::backend::SessionBackend::Ssh { channel_id, .. } => Some(*channel_id),
+            crate::core::backend::SessionBackend::Local { .. } => None,
+        }
+    }
+
+    /// Name of a program still running in the tab, such as `vim`. For a
+    /// local shell that is whatever holds the terminal; elsewhere it is the
+    /// command shell integration last saw start and not finish.
+    pub fn running_program(&self) -> Option<String> {
+        if self.state != SessionState::Connected {
+            return None;
+        }
+        if let Some(process) = &self.local_process {
+            let session = self.session.as_ref()?;
+            let group = session.backend.foreground_process_group()?;
+            if process.pid == Some(group as u32) {
+                return None;
+            }
+            return Some(
+                crate::platform::process_name(group).unwrap_or_else(|| "A program".to_string()),
+            );
+        }
+        let command = self
+            .emulator
+            .command_history()
+            .pop()
+            .filter(|record| record.is_running())?
+            .command;
+        // `sudo vim x` is still vim as far as the user is concerned.
+        let program = command
+            .split_whitespace()
+            .find(|word| !word.contains('=') && !matches!(*word, "sudo" | "env" | "exec"))?;
+        Some(program.rsplit('/').next().unwrap_or(program).to_string())
+    }
+
+    /// Whether the visual bell flash is still showing.
+    pub fn bell_flashing(&self) -> bool {
+        self.visual_bell
+            .is_some_and(|at| at.elapsed() < std::time::Duration::from_millis(150))
+    }
+
+    /// Print a dim divider into the terminal so output from the new shell is
+    /// visibly separated from what was there before the drop.
+    pub fn write_reconnect_divider(&self) {
+        self.write_notice("— reconnected —");
+    }
+
+    /// Print a dim notice line into the terminal (e.g. why the session ended).
+    pub fn write_notice(&self, notice: &str) {
+        if let Some(parser_tx) = &self.parser_tx {
+            let line = format!("\r\n\x1b[2m{}\x1b[0m\r\n", notice);
+            let _ = parser_tx.send(line.into_bytes());
         }
     }
 
@@ -279,6 +936,40 @@ impl SessionTab {
 }
 
 impl SftpState {
+    pub fn cached_listing(&self, path: &str) -> Option<&CachedListing> {
+        self.remote_cache
+            .get(path)
+            .filter(|cached| cached.fetched.elapsed() < REMOTE_CACHE_TTL)
+    }
+
+    pub fn cache_listing(&mut self, path: String) {
+        self.remote_cache
+            .retain(|_, cached| cached.fetched.elapsed() < REMOTE_CACHE_TTL);
+        if self.remote_cache.len() >= REMOTE_CACHE_MAX_DIRS
+            && let Some(oldest) = self
+                .remote_cache
+                .iter()
+                .min_by_key(|(_, cached)| cached.fetched)
+                .map(|(path, _)| path.clone())
+        {
+            self.remote_cache.remove(&oldest);
+        }
+        self.remote_cache.insert(
+            path,
+            CachedListing {
+                entries: self.remote_entries.clone(),
+                remote_path: self.remote_path.clone(),
+                fetched: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops every cached listing. Called after changes on the server, since
+    /// a rename or delete can affect more directories than the one shown.
+    pub fn invalidate_remote_cache(&mut self) {
+        self.remote_cache.clear();
+    }
+
     pub fn new() -> Self {
         let local_path = dirs::home_dir()
             .map(|path| path.to_string_lossy().to_string())
@@ -291,7 +982,12 @@ impl SftpState {
             local_error: None,
             remote_entries: Vec::new(),
             remote_error: None,
-            remote_loading: false,
+            remote_listing: None,
+            remote_cache: HashMap::new(),
+            details_queue: VecDeque::new(),
+            details_in_flight: 0,
+            local_viewport: ListViewport::default(),
+            remote_viewport: ListViewport::default(),
             local_selected: None,
             remote_selected: None,
             local_last_click: None,
@@ -302,8 +998,22 @@ impl SftpState {
             rename_target: None,
             rename_value: String::new(),
             delete_target: None,
+            clipboard: None,
+            local_filter: None,
+            remote_filter: None,
         }
     }
+
+    /// Running uploads and downloads; paused ones hold no slot.
+    pub fn running_transfers(&self) -> (usize, usize) {
+        self.transfers
+            .iter()
+            .filter(|transfer| transfer.status == SftpTransferStatus::Uploading)
+            .fold((0, 0), |(up, down), transfer| match transfer.direction {
+                SftpTransferDirection::Upload => (up + 1, down),
+                SftpTransferDirection::Download => (up, down + 1),
+            })
+    }
 }
 
 // Simple Spinner definition
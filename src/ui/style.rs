@@ -1,9 +1,15 @@
 use iced::widget::scrollable;
 use iced::widget::{button, container, text};
 use iced::{Background, Border, Color, Shadow, Theme, Vector};
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+
+use crate::settings::TerminalTheme;
 
 static THEME_MODE: AtomicU8 = AtomicU8::new(0);
+// 0 follows THEME_MODE, 1 is light and 2 is dark.
+static TERMINAL_THEME: AtomicU8 = AtomicU8::new(0);
+// f32 bits of the terminal background opacity; 1.0 by default.
+static TERMINAL_OPACITY: AtomicU32 = AtomicU32::new(0x3f80_0000);
 
 pub fn set_dark_mode(enabled: bool) {
     THEME_MODE.store(if enabled { 1 } else { 0 }, Ordering::Relaxed);
@@ -13,8 +19,36 @@ fn is_dark() -> bool {
     THEME_MODE.load(Ordering::Relaxed) == 1
 }
 
-pub fn is_dark_mode() -> bool {
-    is_dark()
+pub fn set_terminal_theme(theme: TerminalTheme) {
+    let mode = match theme {
+        TerminalTheme::Match => 0,
+        TerminalTheme::Light => 1,
+        TerminalTheme::Dark => 2,
+    };
+    TERMINAL_THEME.store(mode, Ordering::Relaxed);
+}
+
+/// Whether the terminal is dark, which decides its own colors and those
+/// drawn over it, whatever the app theme.
+pub fn terminal_is_dark() -> bool {
+    match TERMINAL_THEME.load(Ordering::Relaxed) {
+        1 => false,
+        2 => true,
+        _ => is_dark(),
+    }
+}
+
+// The terminal and the app around it have opposite themes.
+fn terminal_contrasts() -> bool {
+    terminal_is_dark() != is_dark()
+}
+
+pub fn set_terminal_opacity(opacity: f32) {
+    TERMINAL_OPACITY.store(opacity.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+}
+
+pub fn terminal_opacity() -> f32 {
+    f32::from_bits(TERMINAL_OPACITY.load(Ordering::Relaxed))
 }
 
 #[allow(dead_code)]
@@ -79,15 +113,20 @@ fn color_text_muted() -> Color {
 }
 
 pub fn terminal_background() -> Color {
-    if is_dark() {
-        color_panel()
+    if terminal_is_dark() {
+        Color::from_rgb8(28, 28, 30)
     } else {
         Color::from_rgb8(255, 255, 255)
     }
 }
 
+/// Terminal background as painted, with the configured opacity applied.
+pub fn terminal_background_fill() -> Color {
+    terminal_background().scale_alpha(terminal_opacity())
+}
+
 pub fn terminal_foreground() -> Color {
-    if is_dark() {
+    if terminal_is_dark() {
         Color::from_rgb8(235, 235, 240)
     } else {
         Color::from_rgb8(20, 20, 22)
@@ -95,7 +134,7 @@ pub fn terminal_foreground() -> Color {
 }
 
 pub fn terminal_selection_bg() -> Color {
-    if is_dark() {
+    if terminal_is_dark() {
         Color::from_rgba8(70, 110, 180, 0.45)
     } else {
         Color::from_rgba8(100, 100, 200, 0.5)
@@ -103,7 +142,7 @@ pub fn terminal_selection_bg() -> Color {
 }
 
 pub fn terminal_scrollbar_track() -> Color {
-    if is_dark() {
+    if terminal_is_dark() {
         Color::from_rgba8(255, 255, 255, 0.06)
     } else {
         Color::from_rgba8(200, 200, 200, 0.2)
@@ -111,7 +150,7 @@ pub fn terminal_scrollbar_track() -> Color {
 }
 
 pub fn terminal_scrollbar_thumb() -> Color {
-    if is_dark() {
+    if terminal_is_dark() {
         Color::from_rgba8(255, 255, 255, 0.28)
     } else {
         Color::from_rgba8(100, 100, 100, 0.5)
@@ -119,7 +158,7 @@ pub fn terminal_scrollbar_thumb() -> Color {
 }
 
 pub fn terminal_cursor_color() -> Color {
-    if is_dark() {
+    if terminal_is_dark() {
         Color::from_rgba8(235, 235, 240, 0.4)
     } else {
         Color::from_rgba8(0, 0, 0, 0.35)
@@ -127,7 +166,7 @@ pub fn terminal_cursor_color() -> Color {
 }
 
 pub fn terminal_link_color() -> Color {
-    if is_dark() {
+    if terminal_is_dark() {
         Color::from_rgb8(120, 190, 255)
     } else {
         Color::from_rgb8(30, 64, 175)
@@ -260,6 +299,21 @@ pub fn app_background(_theme: &Theme) -> container::Style {
     }
 }
 
+// Used behind the terminal view so a translucent terminal shows the desktop.
+pub fn terminal_window_background(_theme: &Theme) -> container::Style {
+    // Against the app's own background a translucent dark terminal on a
+    // light app would wash out, and the other way round.
+    let behind = if terminal_contrasts() {
+        terminal_background()
+    } else {
+        color_bg()
+    };
+    container::Style {
+        background: Some(Background::Color(behind.scale_alpha(terminal_opacity()))),
+        ..container::Style::default()
+    }
+}
+
 pub fn panel(_theme: &Theme) -> container::Style {
     container::Style {
         background: Some(Background::Color(color_panel())),
@@ -532,9 +586,15 @@ pub fn tab_bar(_theme: &Theme) -> container::Style {
 // Terminal content area
 pub fn terminal_content(_theme: &Theme) -> container::Style {
     container::Style {
-        background: Some(Background::Color(terminal_background())),
+        background: Some(Background::Color(terminal_background_fill())),
         border: Border {
-            color: color_border(),
+            // The app's border would be a bright line around a dark
+            // terminal, or vanish around a light one.
+            color: if terminal_contrasts() {
+                terminal_background()
+            } else {
+                color_border()
+            },
             width: 1.0,
             radius: 6.0.into(),
         },
@@ -848,6 +908,80 @@ pub fn quick_connect_item(_theme: &Theme, status: button::Status) -> button::Sty
     style
 }
 
+pub fn quick_connect_item_selected(_theme: &Theme, _status: button::Status) -> button::Style {
+    button::Style {
+        background: Some(Background::Color(color_panel_elevated())),
+        text_color: color_text(),
+        border: Border {
+            color: color_accent(),
+            width: 1.0,
+            radius: 6.0.into(),
+        },
+        ..button::Style::default()
+    }
+}
+
+pub fn accent_color() -> Color {
+    color_accent()
+}
+
+pub fn bell_flash(_theme: &Theme) -> container::Style {
+    let mut color = terminal_foreground();
+    color.a = 0.12;
+    container::Style {
+        background: Some(Background::Color(color)),
+        ..container::Style::default()
+    }
+}
+
+/// Faint wash of a host profile color over the terminal.
+pub fn host_tint(color: Color) -> impl Fn(&Theme) -> container::Style {
+    move |_theme| {
+        let alpha = if is_dark() { 0.10 } else { 0.07 };
+        container::Style {
+            background: Some(Background::Color(color.scale_alpha(alpha))),
+            ..container::Style::default()
+        }
+    }
+}
+
+pub fn terminal_badge(color: Color) -> impl Fn(&Theme) -> container::Style {
+    move |_theme| container::Style {
+        background: Some(Background::Color(color.scale_alpha(0.12))),
+        border: Border {
+            color: color.scale_alpha(0.35),
+            width: 1.0,
+            radius: 4.0.into(),
+        },
+        ..container::Style::default()
+    }
+}
+
+/// Dims the last screen of a terminal whose shell has exited, leaving it
+/// readable behind the overlay.
+pub fn closed_terminal(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(modal_backdrop_color().scale_alpha(0.5))),
+        ..container::Style::default()
+    }
+}
+
+/// Covers an idle terminal with a dark wash, or with its own background
+/// when blanked.
+pub fn screen_guard(blank: bool) -> impl Fn(&Theme, button::Status) -> button::Style {
+    move |_theme, _status| {
+        let color = if blank {
+            terminal_background()
+        } else {
+            modal_backdrop_color()
+        };
+        button::Style {
+            background: Some(Background::Color(color)),
+            ..button::Style::default()
+        }
+    }
+}
+
 pub fn modal_backdrop(_theme: &Theme, _status: button::Status) -> button::Style {
     button::Style {
         background: Some(Background::Color(modal_backdrop_color())),
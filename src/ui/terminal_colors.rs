@@ -43,7 +43,7 @@ pub fn convert_indexed_color(idx: u8) -> Color {
 }
 
 fn ansi_16_palette() -> [Color; 16] {
-    if ui_style::is_dark_mode() {
+    if ui_style::terminal_is_dark() {
         [
             Color::from_rgb8(0, 0, 0),
             Color::from_rgb8(255, 85, 85),
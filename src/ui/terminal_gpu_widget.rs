@@ -14,9 +14,13 @@ use crate::ui::style as ui_style;
 use unicode_width::UnicodeWidthChar;
 
 use crate::terminal::TerminalEmulator;
-use crate::ui::Message;
+use crate::terminal::editor::FileLocation;
+use crate::ui::edge_scroll::EdgeScroll;
 use crate::ui::terminal_colors::convert_color;
-use crate::ui::terminal_widget::{cell_height, cell_width};
+use crate::ui::terminal_scrollbar::{self, Scrollbar, ScrollbarState};
+use crate::ui::terminal_text_style;
+use crate::ui::terminal_widget::{cell_height, cell_width, drag_cell, prompt_note_label};
+use crate::ui::{Message, TerminalMessage};
 
 pub struct TerminalGpuView<'a> {
     emulator: TerminalEmulator,
@@ -43,6 +47,11 @@ struct TerminalGpuState {
     is_dragging: bool,
     last_click_time: Option<std::time::Instant>,
     hover_link: Option<String>,
+    hover_location: Option<FileLocation>,
+    modifiers: iced::keyboard::Modifiers,
+    scrollbar: ScrollbarState,
+    edge_scroll: EdgeScroll,
+    drag_cell: (usize, usize),
 }
 
 impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
@@ -84,7 +93,46 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
     ) {
         let state = tree.state.downcast_mut::<TerminalGpuState>();
         let bounds = layout.bounds();
+        if let iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) = event {
+            state.modifiers = *modifiers;
+            if !modifiers.command() {
+                state.hover_location = None;
+            }
+        }
+        if let iced::Event::Window(iced::window::Event::RedrawRequested(now)) = event
+            && state.is_dragging
+            && let Some(pixels) = state.edge_scroll.step(*now)
+        {
+            let (col, line) = state.drag_cell;
+            shell.publish(Message::Terminal(TerminalMessage::DragScroll(
+                -pixels / cell_height(self.font_size),
+                col,
+                line,
+            )));
+            shell.request_redraw();
+            return;
+        }
         if let iced::Event::Mouse(mouse_event) = event {
+            let response = terminal_scrollbar::handle_mouse(
+                &mut state.scrollbar,
+                mouse_event,
+                bounds,
+                cursor,
+                self.emulator.get_scroll_state(),
+            );
+            if response.redraw {
+                shell.request_redraw();
+            }
+            if response.consumed {
+                state.hover_link = None;
+                state.hover_location = None;
+                if let Some(offset) = response.scroll_to {
+                    shell.publish(Message::Terminal(TerminalMessage::ScrollTo(offset)));
+                }
+                shell.capture_event();
+                return;
+            }
+
             let is_over = cursor.is_over(bounds);
             match mouse_event {
                 mouse::Event::ButtonPressed(mouse::Button::Left) => {
@@ -93,6 +141,12 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
                             shell.publish(Message::OpenUrl(link));
                             return;
                         }
+                        if state.modifiers.command()
+                            && let Some(location) = state.hover_location.clone()
+                        {
+                            shell.publish(Message::OpenTerminalLocation(location));
+                            return;
+                        }
                         if let Some(position) = cursor.position_in(bounds) {
                             let col = (position.x / cell_width(self.font_size)) as usize;
                             let line = (position.y / cell_height(self.font_size)) as usize;
@@ -101,37 +155,62 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
                                 if now.duration_since(last_click).as_millis() < 500 {
                                     state.is_dragging = true;
                                     state.last_click_time = None;
-                                    shell.publish(Message::TerminalMouseDoubleClick(col, line));
+                                    shell.publish(Message::Terminal(
+                                        TerminalMessage::MouseDoubleClick(col, line),
+                                    ));
                                     return;
                                 }
                             }
                             state.is_dragging = true;
                             state.last_click_time = Some(now);
-                            shell.publish(Message::TerminalMousePress(col, line));
+                            shell
+                                .publish(Message::Terminal(TerminalMessage::MousePress(col, line)));
                         }
                     }
                 }
                 mouse::Event::CursorMoved { .. } => {
-                    if state.is_dragging && is_over {
-                        if let Some(position) = cursor.position_in(bounds) {
-                            let col = (position.x / cell_width(self.font_size)) as usize;
-                            let line = (position.y / cell_height(self.font_size)) as usize;
-                            shell.publish(Message::TerminalMouseDrag(col, line));
+                    if state.is_dragging {
+                        if let Some(position) = cursor.position() {
+                            let (_, _, screen_lines) = self.emulator.get_scroll_state();
+                            let (col, line) =
+                                drag_cell(position, bounds, self.font_size, screen_lines);
+                            state.drag_cell = (col, line);
+                            if state.edge_scroll.track(position.y, bounds.y, bounds.height) {
+                                shell.request_redraw();
+                            }
+                            shell.publish(Message::Terminal(TerminalMessage::MouseDrag(col, line)));
                         }
                     } else if is_over {
                         if let Some(position) = cursor.position_in(bounds) {
                             let col = (position.x / cell_width(self.font_size)) as usize;
                             let line = (position.y / cell_height(self.font_size)) as usize;
                             state.hover_link = self.emulator.hyperlink_at(col, line);
+                            state.hover_location = if state.modifiers.command() {
+                                self.emulator.location_at(col, line)
+                            } else {
+                                None
+                            };
                         }
                     } else {
                         state.hover_link = None;
+                        state.hover_location = None;
+                    }
+                }
+                mouse::Event::ButtonPressed(mouse::Button::Right) => {
+                    if let Some(local) = cursor.position_in(bounds)
+                        && let Some(position) = cursor.position()
+                    {
+                        let line = (local.y / cell_height(self.font_size)) as usize;
+                        shell.publish(Message::Terminal(TerminalMessage::OpenMenu(position, line)));
                     }
                 }
                 mouse::Event::ButtonReleased(mouse::Button::Left) => {
                     if state.is_dragging {
                         state.is_dragging = false;
-                        shell.publish(Message::TerminalMouseRelease);
+                        state.edge_scroll.stop();
+                        shell.publish(Message::Terminal(TerminalMessage::MouseRelease(
+                            cursor.position().unwrap_or_default(),
+                        )));
                     }
                 }
                 _ => {}
@@ -150,7 +229,10 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
         let bounds = layout.bounds();
         if cursor.is_over(bounds) {
             let state = tree.state.downcast_ref::<TerminalGpuState>();
-            if state.hover_link.is_some() {
+            if state.scrollbar.hovered || state.scrollbar.drag_anchor.is_some() {
+                return mouse::Interaction::default();
+            }
+            if state.hover_link.is_some() || state.hover_location.is_some() {
                 return mouse::Interaction::Pointer;
             }
             return mouse::Interaction::Text;
@@ -160,7 +242,7 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
 
     fn draw(
         &self,
-        _tree: &tree::Tree,
+        tree: &tree::Tree,
         renderer: &mut iced::Renderer,
         _theme: &iced::Theme,
         _style: &renderer::Style,
@@ -173,44 +255,14 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
         let cell_h = cell_height(self.font_size);
         let terminal_font_family = crate::platform::default_terminal_font_family();
         let fallback_font_family = crate::platform::terminal_fallback_family();
+        let text_style = terminal_text_style::text_style();
 
         let clip_bounds = bounds.intersection(viewport).unwrap_or(bounds);
 
         let default_bg = ui_style::terminal_background();
-        fill_rect(renderer, bounds, default_bg);
-
-        let (total_lines, display_offset, screen_lines) = self.emulator.get_scroll_state();
-        if total_lines > screen_lines {
-            let scrollbar_width = 10.0;
-            let track_x = bounds.x + bounds.width - scrollbar_width;
-            let track_height = bounds.height;
-            let max_offset = total_lines.saturating_sub(screen_lines);
-            let scroll_fraction = if max_offset > 0 {
-                1.0 - (display_offset as f32 / max_offset as f32)
-            } else {
-                1.0
-            };
-            let thumb_fraction = (screen_lines as f32 / total_lines as f32).max(0.05);
-            let thumb_height = track_height * thumb_fraction;
-            let thumb_y = available_track(track_height, thumb_height) * scroll_fraction;
-
-            fill_rect(
-                renderer,
-                Rectangle::new(
-                    Point::new(track_x, bounds.y),
-                    Size::new(scrollbar_width, track_height),
-                ),
-                ui_style::terminal_scrollbar_track(),
-            );
-            fill_rect(
-                renderer,
-                Rectangle::new(
-                    Point::new(track_x, bounds.y + thumb_y),
-                    Size::new(scrollbar_width, thumb_height),
-                ),
-                ui_style::terminal_scrollbar_thumb(),
-            );
-        }
+        fill_rect(renderer, bounds, ui_style::terminal_background_fill());
+
+        let (_, _, screen_lines) = self.emulator.get_scroll_state();
 
         let (cursor_col, cursor_row, cursor_shape, cursor_rgb) = self.emulator.cursor_render_info();
         let preedit_len = self.preedit.map(display_width).unwrap_or(0);
@@ -230,7 +282,9 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
             self.emulator
                 .render_line(line, |col, _line, cell, is_selected| {
                     let c = cell.c;
-                    let mut fg = convert_color(cell.fg);
+                    let bold = cell.flags.contains(Flags::BOLD);
+                    let italic = cell.flags.contains(Flags::ITALIC);
+                    let mut fg = convert_color(text_style.foreground(cell.fg, bold));
                     let mut bg = convert_color(cell.bg);
                     if cell.flags.contains(Flags::INVERSE) {
                         std::mem::swap(&mut fg, &mut bg);
@@ -242,18 +296,10 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
                         };
                     }
 
-                    let weight = if cell.flags.contains(Flags::BOLD) {
-                        FontWeight::Bold
-                    } else {
-                        FontWeight::Normal
-                    };
-                    let style = if cell.flags.contains(Flags::ITALIC) {
-                        FontStyle::Italic
-                    } else {
-                        FontStyle::Normal
-                    };
+                    let weight = text_style.weight(bold);
+                    let style = text_style.style(italic);
                     let family = if c.is_ascii() {
-                        terminal_font_family
+                        text_style.family(terminal_font_family, bold, italic)
                     } else {
                         fallback_font_family
                     };
@@ -394,6 +440,34 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
             }
         }
 
+        let columns = (bounds.width / cell_w) as usize;
+        for note in self.emulator.prompt_notes() {
+            if let Some((col, label, color)) = prompt_note_label(&note, columns) {
+                renderer.fill_text(
+                    text::Text {
+                        content: label,
+                        bounds: Size::new(bounds.width, cell_h),
+                        size: self.font_size.into(),
+                        line_height: text::LineHeight::Absolute(Pixels(cell_h)),
+                        font: iced::Font {
+                            family: iced::font::Family::Name(terminal_font_family),
+                            ..iced::Font::DEFAULT
+                        },
+                        align_x: text::Alignment::Left,
+                        align_y: iced::alignment::Vertical::Top,
+                        shaping: text::Shaping::Basic,
+                        wrapping: text::Wrapping::None,
+                    },
+                    Point::new(
+                        bounds.x + col as f32 * cell_w,
+                        bounds.y + note.line as f32 * cell_h,
+                    ),
+                    color,
+                    clip_bounds,
+                );
+            }
+        }
+
         let cursor_x = bounds.x + (cursor_col + preedit_len) as f32 * cell_w;
         let cursor_y = bounds.y + cursor_row as f32 * cell_h;
         let cursor_color = cursor_rgb
@@ -494,6 +568,16 @@ impl Widget<Message, iced::Theme, iced::Renderer> for TerminalGpuView<'_> {
                 );
             }
         }
+
+        let state = tree.state.downcast_ref::<TerminalGpuState>();
+        if let Some(bar) = Scrollbar::new(bounds, self.emulator.get_scroll_state())
+            .filter(|bar| bar.is_visible(&state.scrollbar))
+        {
+            if state.scrollbar.hovered || state.scrollbar.drag_anchor.is_some() {
+                fill_rect(renderer, bar.track, ui_style::terminal_scrollbar_track());
+            }
+            fill_rect(renderer, bar.thumb, ui_style::terminal_scrollbar_thumb());
+        }
     }
 }
 
@@ -509,10 +593,6 @@ fn fill_rect(renderer: &mut iced::Renderer, bounds: Rectangle, color: Color) {
     );
 }
 
-fn available_track(track_h: f32, thumb_h: f32) -> f32 {
-    track_h - thumb_h
-}
-
 fn display_width(text: &str) -> usize {
     text.chars()
         .map(|ch| UnicodeWidthChar::width(ch).unwrap_or(1))
@@ -0,0 +1,200 @@
+use iced::{Point, Rectangle, Size, mouse};
+
+// Geometry shared by the CPU and GPU terminal widgets. The bar is thin and
+// only drawn while it is useful: in history, hovered, or being dragged.
+const BAR_WIDTH: f32 = 6.0;
+const HIT_WIDTH: f32 = 14.0;
+const MIN_THUMB_FRACTION: f32 = 0.05;
+
+#[derive(Debug, Default)]
+pub struct ScrollbarState {
+    pub hovered: bool,
+    // Distance from the top of the thumb to where it was grabbed.
+    pub drag_anchor: Option<f32>,
+}
+
+/// What the terminal widget should do after the scrollbar saw a mouse event.
+#[derive(Debug, Default)]
+pub struct Response {
+    /// The event was used by the scrollbar and must not reach the terminal.
+    pub consumed: bool,
+    pub redraw: bool,
+    pub scroll_to: Option<usize>,
+}
+
+pub fn handle_mouse(
+    state: &mut ScrollbarState,
+    event: &mouse::Event,
+    bounds: Rectangle,
+    cursor: mouse::Cursor,
+    scroll_state: (usize, usize, usize),
+) -> Response {
+    let Some(bar) = Scrollbar::new(Rectangle::with_size(bounds.size()), scroll_state) else {
+        let redraw = state.hovered || state.drag_anchor.is_some();
+        *state = ScrollbarState::default();
+        return Response {
+            redraw,
+            ..Response::default()
+        };
+    };
+    let position = cursor.position_in(bounds);
+
+    match event {
+        mouse::Event::CursorMoved { .. } => {
+            if let Some(anchor) = state.drag_anchor {
+                // Dragging keeps tracking the cursor even outside the widget.
+                let Some(absolute) = cursor.position() else {
+                    return Response::default();
+                };
+                let thumb_top = absolute.y - bounds.y - anchor;
+                return Response {
+                    consumed: true,
+                    redraw: true,
+                    scroll_to: Some(bar.offset_for_thumb_top(thumb_top)),
+                };
+            }
+            let hovered = position.is_some_and(|point| bar.contains(point));
+            let changed = hovered != state.hovered;
+            state.hovered = hovered;
+            Response {
+                consumed: hovered,
+                redraw: changed,
+                scroll_to: None,
+            }
+        }
+        mouse::Event::ButtonPressed(mouse::Button::Left) => match position {
+            Some(point) if bar.contains(point) => {
+                if bar.thumb_contains_y(point.y) {
+                    state.drag_anchor = Some(point.y - bar.thumb.y);
+                    Response {
+                        consumed: true,
+                        redraw: true,
+                        scroll_to: None,
+                    }
+                } else {
+                    Response {
+                        consumed: true,
+                        redraw: true,
+                        scroll_to: Some(bar.page_offset(point.y)),
+                    }
+                }
+            }
+            _ => Response::default(),
+        },
+        mouse::Event::ButtonReleased(mouse::Button::Left) if state.drag_anchor.is_some() => {
+            state.drag_anchor = None;
+            Response {
+                consumed: true,
+                redraw: true,
+                scroll_to: None,
+            }
+        }
+        _ => Response::default(),
+    }
+}
+
+pub struct Scrollbar {
+    pub track: Rectangle,
+    pub thumb: Rectangle,
+    display_offset: usize,
+    screen_lines: usize,
+    max_offset: usize,
+}
+
+impl Scrollbar {
+    /// Returns None when everything fits on screen and there is nothing to
+    /// scroll.
+    pub fn new(bounds: Rectangle, scroll_state: (usize, usize, usize)) -> Option<Self> {
+        let (total_lines, display_offset, screen_lines) = scroll_state;
+        if total_lines <= screen_lines || bounds.height <= 0.0 {
+            return None;
+        }
+        let max_offset = total_lines - screen_lines;
+        let display_offset = display_offset.min(max_offset);
+
+        let track = Rectangle::new(
+            Point::new(bounds.x + bounds.width - BAR_WIDTH, bounds.y),
+            Size::new(BAR_WIDTH, bounds.height),
+        );
+        let thumb_fraction =
+            (screen_lines as f32 / total_lines as f32).clamp(MIN_THUMB_FRACTION, 1.0);
+        let thumb_height = bounds.height * thumb_fraction;
+        let scroll_fraction = 1.0 - display_offset as f32 / max_offset as f32;
+        let thumb_y = bounds.y + (bounds.height - thumb_height) * scroll_fraction;
+        let thumb = Rectangle::new(
+            Point::new(track.x, thumb_y),
+            Size::new(BAR_WIDTH, thumb_height),
+        );
+
+        Some(Self {
+            track,
+            thumb,
+            display_offset,
+            screen_lines,
+            max_offset,
+        })
+    }
+
+    pub fn is_visible(&self, state: &ScrollbarState) -> bool {
+        self.display_offset > 0 || state.hovered || state.drag_anchor.is_some()
+    }
+
+    /// The hit area is wider than the drawn bar so it is easy to grab.
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.track.x + self.track.width - HIT_WIDTH
+            && point.y >= self.track.y
+            && point.y <= self.track.y + self.track.height
+    }
+
+    pub fn thumb_contains_y(&self, y: f32) -> bool {
+        y >= self.thumb.y && y <= self.thumb.y + self.thumb.height
+    }
+
+    /// Scroll offset that puts the top of the thumb at `thumb_top`.
+    pub fn offset_for_thumb_top(&self, thumb_top: f32) -> usize {
+        let range = self.track.height - self.thumb.height;
+        if range <= 0.0 {
+            return 0;
+        }
+        let fraction = ((thumb_top - self.track.y) / range).clamp(0.0, 1.0);
+        ((1.0 - fraction) * self.max_offset as f32).round() as usize
+    }
+
+    /// Offset after a click on the track above or below the thumb.
+    pub fn page_offset(&self, y: f32) -> usize {
+        if y < self.thumb.y {
+            (self.display_offset + self.screen_lines).min(self.max_offset)
+        } else {
+            self.display_offset.saturating_sub(self.screen_lines)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds() -> Rectangle {
+        Rectangle::new(Point::ORIGIN, Size::new(800.0, 400.0))
+    }
+
+    #[test]
+    fn hidden_when_nothing_to_scroll() {
+        assert!(Scrollbar::new(bounds(), (24, 0, 24)).is_none());
+    }
+
+    #[test]
+    fn thumb_position_round_trips_to_offset() {
+        let bar = Scrollbar::new(bounds(), (124, 50, 24)).unwrap();
+        assert_eq!(bar.offset_for_thumb_top(bar.thumb.y), 50);
+        assert_eq!(bar.offset_for_thumb_top(bar.track.y), 100);
+        assert_eq!(bar.offset_for_thumb_top(bar.track.height), 0);
+    }
+
+    #[test]
+    fn track_clicks_page_by_screen() {
+        let bar = Scrollbar::new(bounds(), (124, 50, 24)).unwrap();
+        assert_eq!(bar.page_offset(bar.track.y), 74);
+        assert_eq!(bar.page_offset(bar.track.y + bar.track.height), 26);
+    }
+}
@@ -0,0 +1,87 @@
+use alacritty_terminal::vte::ansi::Color as AnsiColor;
+use iced::font::{Style as FontStyle, Weight as FontWeight};
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashSet;
+
+use crate::settings::AppSettings;
+
+/// How bold and italic cells are drawn, shared by both terminal renderers.
+#[derive(Debug, Clone, Copy)]
+pub struct TextStyle {
+    pub bold_is_bright: bool,
+    pub render_italics: bool,
+    pub bold_family: Option<&'static str>,
+    pub italic_family: Option<&'static str>,
+}
+
+static TEXT_STYLE: RwLock<TextStyle> = RwLock::new(TextStyle {
+    bold_is_bright: false,
+    render_italics: true,
+    bold_family: None,
+    italic_family: None,
+});
+
+pub fn set_text_style(settings: &AppSettings) {
+    *TEXT_STYLE.write() = TextStyle {
+        bold_is_bright: settings.bold_is_bright,
+        render_italics: settings.render_italics,
+        bold_family: font_family(settings.bold_font.as_deref()),
+        italic_family: font_family(settings.italic_font.as_deref()),
+    };
+}
+
+pub fn text_style() -> TextStyle {
+    *TEXT_STYLE.read()
+}
+
+impl TextStyle {
+    /// Maps the eight base colors to their bright variants for bold cells.
+    pub fn foreground(&self, color: AnsiColor, bold: bool) -> AnsiColor {
+        if !(bold && self.bold_is_bright) {
+            return color;
+        }
+        match color {
+            AnsiColor::Named(named) if (named as usize) < 8 => AnsiColor::Indexed(named as u8 + 8),
+            AnsiColor::Indexed(index) if index < 8 => AnsiColor::Indexed(index + 8),
+            other => other,
+        }
+    }
+
+    pub fn weight(&self, bold: bool) -> FontWeight {
+        if bold {
+            FontWeight::Bold
+        } else {
+            FontWeight::Normal
+        }
+    }
+
+    pub fn style(&self, italic: bool) -> FontStyle {
+        if italic && self.render_italics {
+            FontStyle::Italic
+        } else {
+            FontStyle::Normal
+        }
+    }
+
+    /// Picks the dedicated bold or italic face when one is configured.
+    pub fn family(&self, regular: &'static str, bold: bool, italic: bool) -> &'static str {
+        let italic_family = self.italic_family.filter(|_| italic && self.render_italics);
+        let bold_family = self.bold_family.filter(|_| bold);
+        italic_family.or(bold_family).unwrap_or(regular)
+    }
+}
+
+// iced wants `&'static str` family names; each distinct name is leaked once.
+fn font_family(name: Option<&str>) -> Option<&'static str> {
+    static NAMES: Mutex<Option<HashSet<&'static str>>> = Mutex::new(None);
+
+    let name = name.map(str::trim).filter(|name| !name.is_empty())?;
+    let mut names = NAMES.lock();
+    let names = names.get_or_insert_with(HashSet::new);
+    if let Some(existing) = names.get(name) {
+        return Some(existing);
+    }
+    let leaked: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.insert(leaked);
+    Some(leaked)
+}
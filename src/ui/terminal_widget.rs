@@ -5,11 +5,18 @@ use iced::widget::canvas::{self, Cache, Canvas, Frame, Geometry, Text};
 use iced::widget::text::LineHeight;
 use iced::{Color, Element, Length, Point, Rectangle, Size, Theme};
 use crate::ui::style as ui_style;
+use std::sync::atomic::{AtomicU32, Ordering};
 use unicode_width::UnicodeWidthChar;
 
 use crate::terminal::TerminalEmulator;
-use crate::ui::Message;
+use crate::terminal::editor::FileLocation;
+use crate::terminal::shell_integration::PromptNote;
+use crate::ui::edge_scroll::EdgeScroll;
 use crate::ui::terminal_colors::convert_color;
+use crate::ui::terminal_scrollbar::{self, Scrollbar, ScrollbarState};
+use crate::ui::terminal_text_style;
+use crate::ui::views::command_history::format_duration;
+use crate::ui::{Message, TerminalMessage};
 
 pub const BASE_CELL_WIDTH: f32 = 7.2;
 pub const BASE_CELL_HEIGHT: f32 = 16.0;
@@ -18,8 +25,57 @@ pub fn cell_width(font_size: f32) -> f32 {
     BASE_CELL_WIDTH * (font_size / 12.0)
 }
 
+// Line height multiplier stored as f32 bits; 1.0 by default.
+static LINE_HEIGHT: AtomicU32 = AtomicU32::new(0x3f80_0000);
+
+pub fn set_line_height(multiplier: f32) {
+    LINE_HEIGHT.store(multiplier.clamp(1.0, 2.0).to_bits(), Ordering::Relaxed);
+}
+
+pub fn line_height() -> f32 {
+    f32::from_bits(LINE_HEIGHT.load(Ordering::Relaxed))
+}
+
+/// What to write at the end of a finished command's prompt line, in a
+/// viewport `columns` wide: the column it starts at, its text and colour.
+/// Failures show their exit code. `None` when the prompt and command leave
+/// no room for it.
+pub fn prompt_note_label(note: &PromptNote, columns: usize) -> Option<(usize, String, Color)> {
+    let duration = format_duration(note.duration);
+    let (label, color) = match note.exit_code {
+        Some(code) if code != 0 => (
+            format!("exit {}  {}", code, duration),
+            Color::from_rgb(0.9, 0.3, 0.3),
+        ),
+        _ => (
+            duration,
+            Color {
+                a: 0.45,
+                ..ui_style::terminal_foreground()
+            },
+        ),
+    };
+    // A column of margin on the right for the scrollbar, and at least two
+    // between the command and the note.
+    let start = columns.checked_sub(label.len() + 1)?;
+    (note.used_columns + 2 <= start).then_some((start, label, color))
+}
+
+/// The cell under the window `position` during a selection drag, kept on
+/// the nearest row and column when the pointer is outside `bounds`.
+pub fn drag_cell(
+    position: Point,
+    bounds: Rectangle,
+    font_size: f32,
+    screen_lines: usize,
+) -> (usize, usize) {
+    let col = ((position.x - bounds.x).max(0.0) / cell_width(font_size)) as usize;
+    let line = ((position.y - bounds.y).max(0.0) / cell_height(font_size)) as usize;
+    (col, line.min(screen_lines.saturating_sub(1)))
+}
+
 pub fn cell_height(font_size: f32) -> f32 {
-    BASE_CELL_HEIGHT * (font_size / 12.0)
+    BASE_CELL_HEIGHT * (font_size / 12.0) * line_height()
 }
 
 pub struct TerminalView<'a> {
@@ -59,6 +115,11 @@ pub struct TerminalWidgetState {
     is_dragging: bool,
     last_click_time: Option<std::time::Instant>,
     hover_link: Option<String>,
+    hover_location: Option<FileLocation>,
+    modifiers: iced::keyboard::Modifiers,
+    scrollbar: ScrollbarState,
+    edge_scroll: EdgeScroll,
+    drag_cell: (usize, usize),
 }
 
 impl Default for TerminalWidgetState {
@@ -67,6 +128,11 @@ impl Default for TerminalWidgetState {
             is_dragging: false,
             last_click_time: None,
             hover_link: None,
+            hover_location: None,
+            modifiers: iced::keyboard::Modifiers::default(),
+            scrollbar: ScrollbarState::default(),
+            edge_scroll: EdgeScroll::default(),
+            drag_cell: (0, 0),
         }
     }
 }
@@ -81,7 +147,52 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
         bounds: Rectangle,
         cursor: mouse::Cursor,
     ) -> Option<iced::widget::canvas::Action<Message>> {
+        if let iced::event::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) =
+            event
+        {
+            state.modifiers = *modifiers;
+            if !modifiers.command() {
+                state.hover_location = None;
+            }
+        }
+        // Each scroll step publishes a message and so asks for the next frame,
+        // which keeps scrolling while the pointer is held still.
+        if let iced::event::Event::Window(iced::window::Event::RedrawRequested(now)) = event
+            && state.is_dragging
+            && let Some(pixels) = state.edge_scroll.step(*now)
+        {
+            let (col, line) = state.drag_cell;
+            return Some(iced::widget::canvas::Action::publish(Message::Terminal(
+                TerminalMessage::DragScroll(-pixels / cell_height(self.font_size), col, line),
+            )));
+        }
         if let iced::event::Event::Mouse(mouse_event) = event {
+            let response = terminal_scrollbar::handle_mouse(
+                &mut state.scrollbar,
+                mouse_event,
+                bounds,
+                cursor,
+                self.emulator.get_scroll_state(),
+            );
+            if response.consumed {
+                state.hover_link = None;
+                state.hover_location = None;
+            }
+            if let Some(offset) = response.scroll_to {
+                return Some(
+                    iced::widget::canvas::Action::publish(Message::Terminal(
+                        TerminalMessage::ScrollTo(offset),
+                    ))
+                    .and_capture(),
+                );
+            }
+            if response.consumed {
+                return Some(iced::widget::canvas::Action::request_redraw().and_capture());
+            }
+            if response.redraw {
+                return Some(iced::widget::canvas::Action::request_redraw());
+            }
+
             // Need cell position
             // But if we release OUTSIDE bounds, we still need to stop drag.
             // So ButtonReleased should be handled regardless of bounds?
@@ -101,6 +212,13 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
                                 link,
                             )));
                         }
+                        if state.modifiers.command()
+                            && let Some(location) = state.hover_location.clone()
+                        {
+                            return Some(iced::widget::canvas::Action::publish(
+                                Message::OpenTerminalLocation(location),
+                            ));
+                        }
                         if let Some(position) = cursor.position_in(bounds) {
                             let col = (position.x / cell_width(self.font_size)) as usize;
                             let line = (position.y / cell_height(self.font_size)) as usize;
@@ -117,7 +235,9 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
                                     state.last_click_time = None; // Reset
                                     // self.cache.clear();
                                     return Some(iced::widget::canvas::Action::publish(
-                                        Message::TerminalMouseDoubleClick(col, line),
+                                        Message::Terminal(TerminalMessage::MouseDoubleClick(
+                                            col, line,
+                                        )),
                                     ));
                                 }
                             }
@@ -128,33 +248,48 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
                             state.last_click_time = Some(now);
 
                             // self.cache.clear();
-                            return Some(iced::widget::canvas::Action::publish(
-                                Message::TerminalMousePress(col, line),
-                            ));
+                            return Some(iced::widget::canvas::Action::publish(Message::Terminal(
+                                TerminalMessage::MousePress(col, line),
+                            )));
                         }
                     }
                 }
                 mouse::Event::CursorMoved { .. } => {
-                    if state.is_dragging && is_over {
-                        if let Some(position) = cursor.position_in(bounds) {
-                            let col = (position.x / cell_width(self.font_size)) as usize;
-                            let line = (position.y / cell_height(self.font_size)) as usize;
-
-                            // let mut emulator = self.emulator.clone();
-                            // emulator.on_mouse_drag(col, line);
-                            // self.cache.clear();
-                            return Some(iced::widget::canvas::Action::publish(
-                                Message::TerminalMouseDrag(col, line),
-                            ));
+                    if state.is_dragging {
+                        if let Some(position) = cursor.position() {
+                            let (_, _, screen_lines) = self.emulator.get_scroll_state();
+                            let (col, line) =
+                                drag_cell(position, bounds, self.font_size, screen_lines);
+                            state.drag_cell = (col, line);
+                            state.edge_scroll.track(position.y, bounds.y, bounds.height);
+                            return Some(iced::widget::canvas::Action::publish(Message::Terminal(
+                                TerminalMessage::MouseDrag(col, line),
+                            )));
                         }
                     } else if is_over {
                         if let Some(position) = cursor.position_in(bounds) {
                             let col = (position.x / cell_width(self.font_size)) as usize;
                             let line = (position.y / cell_height(self.font_size)) as usize;
                             state.hover_link = self.emulator.hyperlink_at(col, line);
+                            state.hover_location = if state.modifiers.command() {
+                                self.emulator.location_at(col, line)
+                            } else {
+                                None
+                            };
                         }
                     } else {
                         state.hover_link = None;
+                        state.hover_location = None;
+                    }
+                }
+                mouse::Event::ButtonPressed(mouse::Button::Right) => {
+                    if let Some(local) = cursor.position_in(bounds)
+                        && let Some(position) = cursor.position()
+                    {
+                        let line = (local.y / cell_height(self.font_size)) as usize;
+                        return Some(iced::widget::canvas::Action::publish(Message::Terminal(
+                            TerminalMessage::OpenMenu(position, line),
+                        )));
                     }
                 }
                 mouse::Event::ButtonReleased(mouse::Button::Left) => {
@@ -162,10 +297,11 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
                         // let mut emulator = self.emulator.clone();
                         // emulator.on_mouse_release();
                         state.is_dragging = false;
+                        state.edge_scroll.stop();
                         // self.cache.clear();
-                        return Some(iced::widget::canvas::Action::publish(
-                            Message::TerminalMouseRelease,
-                        ));
+                        return Some(iced::widget::canvas::Action::publish(Message::Terminal(
+                            TerminalMessage::MouseRelease(cursor.position().unwrap_or_default()),
+                        )));
                     }
                 }
                 _ => {}
@@ -181,7 +317,10 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
         cursor: mouse::Cursor,
     ) -> mouse::Interaction {
         if cursor.is_over(bounds) {
-            if state.hover_link.is_some() {
+            if state.scrollbar.hovered || state.scrollbar.drag_anchor.is_some() {
+                return mouse::Interaction::default();
+            }
+            if state.hover_link.is_some() || state.hover_location.is_some() {
                 return mouse::Interaction::Pointer;
             }
             mouse::Interaction::Text
@@ -192,7 +331,7 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
 
     fn draw(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         renderer: &iced::Renderer,
         _theme: &Theme,
         bounds: Rectangle,
@@ -206,35 +345,11 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
 
         let chrome = self.chrome_cache.draw(renderer, bounds.size(), |frame| {
             // Fill background
-            frame.fill_rectangle(Point::ORIGIN, bounds.size(), default_bg);
-
-            // Draw Scrollbar
-            let (total_lines, display_offset, screen_lines) = self.emulator.get_scroll_state();
-            if total_lines > screen_lines {
-                let scrollbar_width = 10.0;
-                let track_x = bounds.width - scrollbar_width;
-                let track_height = bounds.height;
-                let max_offset = total_lines.saturating_sub(screen_lines);
-                let scroll_fraction = if max_offset > 0 {
-                    1.0 - (display_offset as f32 / max_offset as f32)
-                } else {
-                    1.0
-                };
-                let thumb_fraction = (screen_lines as f32 / total_lines as f32).max(0.05);
-                let thumb_height = track_height * thumb_fraction;
-                let thumb_y = available_track(track_height, thumb_height) * scroll_fraction;
-
-                frame.fill_rectangle(
-                    Point::new(track_x, 0.0),
-                    Size::new(scrollbar_width, track_height),
-                    ui_style::terminal_scrollbar_track(),
-                );
-                frame.fill_rectangle(
-                    Point::new(track_x, thumb_y),
-                    Size::new(scrollbar_width, thumb_height),
-                    ui_style::terminal_scrollbar_thumb(),
-                );
-            }
+            frame.fill_rectangle(
+                Point::ORIGIN,
+                bounds.size(),
+                ui_style::terminal_background_fill(),
+            );
 
             // FPS Counter
             use std::sync::Mutex;
@@ -261,6 +376,7 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
         let cell_height = cell_height(self.font_size);
         let terminal_font_family = crate::platform::default_terminal_font_family();
         let fallback_font_family = crate::platform::terminal_fallback_family();
+        let text_style = terminal_text_style::text_style();
         let (cursor_col, cursor_row, cursor_shape, cursor_rgb) = self.emulator.cursor_render_info();
         let preedit_len = self.preedit.map(display_width).unwrap_or(0);
         let (_, _, screen_lines) = self.emulator.get_scroll_state();
@@ -288,7 +404,9 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
 
                         let x = col as f32 * cell_width;
                         let y = line as f32 * cell_height;
-                        let mut fg_color = convert_color(fg);
+                        let bold = cell.flags.contains(Flags::BOLD);
+                        let italic = cell.flags.contains(Flags::ITALIC);
+                        let mut fg_color = convert_color(text_style.foreground(fg, bold));
                         let mut bg_color = convert_color(bg);
                         if cell.flags.contains(Flags::INVERSE) {
                             std::mem::swap(&mut fg_color, &mut bg_color);
@@ -300,18 +418,10 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
                             };
                         }
 
-                        let weight = if cell.flags.contains(Flags::BOLD) {
-                            FontWeight::Bold
-                        } else {
-                            FontWeight::Normal
-                        };
-                        let style = if cell.flags.contains(Flags::ITALIC) {
-                            FontStyle::Italic
-                        } else {
-                            FontStyle::Normal
-                        };
+                        let weight = text_style.weight(bold);
+                        let style = text_style.style(italic);
                         let family = if c.is_ascii() {
-                            terminal_font_family
+                            text_style.family(terminal_font_family, bold, italic)
                         } else {
                             fallback_font_family
                         };
@@ -447,6 +557,22 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
         }
 
         let mut overlay = Frame::new(renderer, bounds.size());
+        let columns = (bounds.width / cell_width) as usize;
+        for note in self.emulator.prompt_notes() {
+            if let Some((col, label, color)) = prompt_note_label(&note, columns) {
+                overlay.fill_text(Text {
+                    content: label,
+                    position: Point::new(col as f32 * cell_width, note.line as f32 * cell_height),
+                    color,
+                    size: self.font_size.into(),
+                    font: iced::Font {
+                        family: iced::font::Family::Name(terminal_font_family),
+                        ..iced::Font::DEFAULT
+                    },
+                    ..Text::default()
+                });
+            }
+        }
         let cursor_x = (cursor_col + preedit_len) as f32 * cell_width;
         let cursor_y = cursor_row as f32 * cell_height;
         let cursor_color = cursor_rgb
@@ -536,15 +662,34 @@ impl<'a> canvas::Program<Message> for TerminalView<'a> {
 
         geometries.push(overlay.into_geometry());
 
+        // The scrollbar follows hover and drag state, so it stays out of the
+        // cached chrome.
+        if let Some(bar) = Scrollbar::new(
+            Rectangle::with_size(bounds.size()),
+            self.emulator.get_scroll_state(),
+        )
+        .filter(|bar| bar.is_visible(&state.scrollbar))
+        {
+            let mut frame = Frame::new(renderer, bounds.size());
+            if state.scrollbar.hovered || state.scrollbar.drag_anchor.is_some() {
+                frame.fill_rectangle(
+                    bar.track.position(),
+                    bar.track.size(),
+                    ui_style::terminal_scrollbar_track(),
+                );
+            }
+            frame.fill_rectangle(
+                bar.thumb.position(),
+                bar.thumb.size(),
+                ui_style::terminal_scrollbar_thumb(),
+            );
+            geometries.push(frame.into_geometry());
+        }
+
         geometries
     }
 }
 
-// Helper to avoid lifetime issues in closure
-fn available_track(track_h: f32, thumb_h: f32) -> f32 {
-    track_h - thumb_h
-}
-
 fn display_width(text: &str) -> usize {
     text.chars()
         .map(|ch| UnicodeWidthChar::width(ch).unwrap_or(1))
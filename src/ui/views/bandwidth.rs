@@ -0,0 +1,124 @@
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use iced::widget::{column, container, row, text};
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Theme, mouse};
+
+use crate::ssh::BandwidthHistory;
+use crate::ui::Message;
+use crate::ui::state::SessionTab;
+use crate::ui::style as ui_style;
+use crate::ui::views::sftp::format_size;
+
+const IN_COLOR: Color = Color::from_rgb(0.3, 0.6, 0.95);
+const OUT_COLOR: Color = Color::from_rgb(0.95, 0.6, 0.25);
+
+/// Current rates for the status bar, e.g. `↓ 1.2 MB/s ↑ 4.0 KB/s`.
+pub fn rate_label(history: &BandwidthHistory) -> String {
+    let (bytes_in, bytes_out) = history.current();
+    format!(
+        "↓ {}/s ↑ {}/s",
+        format_size(bytes_in),
+        format_size(bytes_out)
+    )
+}
+
+/// Popover opened from the status bar with the active session's traffic.
+pub fn popover(tab: &SessionTab) -> Element<'_, Message> {
+    let (total_in, total_out) = tab
+        .bandwidth
+        .as_ref()
+        .map(|bandwidth| bandwidth.totals())
+        .unwrap_or_default();
+    let (rate_in, rate_out) = tab.bandwidth_history.current();
+
+    container(
+        column![
+            text("Connection traffic")
+                .size(13)
+                .style(ui_style::header_text),
+            graph(&tab.bandwidth_history, 320.0, 90.0),
+            row![
+                text(format!("↓ {}/s", format_size(rate_in)))
+                    .size(12)
+                    .color(IN_COLOR),
+                text(format!("↑ {}/s", format_size(rate_out)))
+                    .size(12)
+                    .color(OUT_COLOR),
+            ]
+            .spacing(12),
+            text(format!(
+                "Received {} · Sent {}",
+                format_size(total_in),
+                format_size(total_out)
+            ))
+            .size(11)
+            .style(ui_style::muted_text),
+        ]
+        .spacing(8),
+    )
+    .padding([10, 12])
+    .style(ui_style::popover_menu)
+    .into()
+}
+
+/// Received and sent bytes per second over the last minute.
+pub fn graph<'a>(history: &BandwidthHistory, width: f32, height: f32) -> Element<'a, Message> {
+    Canvas::new(Graph {
+        samples: history.samples().collect(),
+    })
+    .width(Length::Fixed(width))
+    .height(Length::Fixed(height))
+    .into()
+}
+
+struct Graph {
+    samples: Vec<(u64, u64)>,
+}
+
+impl canvas::Program<Message> for Graph {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(
+            Point::ORIGIN,
+            bounds.size(),
+            Color::from_rgba(0.5, 0.5, 0.5, 0.08),
+        );
+        // Both lines share a scale so their heights can be compared.
+        let peak = self
+            .samples
+            .iter()
+            .map(|&(bytes_in, bytes_out)| bytes_in.max(bytes_out))
+            .max()
+            .unwrap_or(0)
+            .max(1) as f32;
+        let step = bounds.width / (crate::ssh::HISTORY_SECONDS - 1) as f32;
+        // Newest sample on the right edge.
+        let offset = crate::ssh::HISTORY_SECONDS.saturating_sub(self.samples.len()) as f32;
+        for (pick, color) in [(0usize, IN_COLOR), (1usize, OUT_COLOR)] {
+            let line = Path::new(|path| {
+                for (index, &(bytes_in, bytes_out)) in self.samples.iter().enumerate() {
+                    let value = if pick == 0 { bytes_in } else { bytes_out };
+                    let point = Point::new(
+                        (offset + index as f32) * step,
+                        bounds.height - value as f32 / peak * (bounds.height - 2.0) - 1.0,
+                    );
+                    if index == 0 {
+                        path.move_to(point);
+                    } else {
+                        path.line_to(point);
+                    }
+                }
+            });
+            frame.stroke(&line, Stroke::default().with_width(1.5).with_color(color));
+        }
+        vec![frame.into_geometry()]
+    }
+}
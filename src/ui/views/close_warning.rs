@@ -0,0 +1,39 @@
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+use crate::ui::Message;
+use crate::ui::style as ui_style;
+
+/// Asks before closing a tab that still has `program` running in it.
+pub fn render<'a>(program: &str) -> Element<'a, Message> {
+    let actions = row![
+        container("").width(Length::Fill),
+        button(text("Cancel").size(12))
+            .padding([6, 12])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::CancelCloseTab),
+        button(text("Close Tab").size(12))
+            .padding([6, 12])
+            .style(ui_style::primary_button_style)
+            .on_press(Message::ConfirmCloseTab),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(
+        column![
+            text(format!("{} is still running", program))
+                .size(16)
+                .style(ui_style::header_text),
+            text("Closing the tab will end it.")
+                .size(13)
+                .style(ui_style::muted_text),
+            actions
+        ]
+        .spacing(12)
+        .width(Length::Fixed(360.0)),
+    )
+    .padding(16)
+    .style(ui_style::dialog_container)
+    .into()
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use iced::widget::{Column, button, column, container, row, scrollable, text, text_input};
+use iced::{Alignment, Color, Element, Font, Length};
+
+use crate::terminal::shell_integration::CommandRecord;
+use crate::ui::style as ui_style;
+use crate::ui::{Message, TerminalMessage};
+
+pub const SIDEBAR_WIDTH: f32 = 300.0;
+// Rendering thousands of rows each frame is wasteful; older matches are
+// still reachable through the search box.
+const MAX_SHOWN: usize = 200;
+
+pub fn render<'a>(
+    records: Vec<CommandRecord>,
+    query: &'a str,
+    pending_rerun: Option<&'a str>,
+) -> Element<'a, Message> {
+    let header = row![
+        text("Command History")
+            .size(14)
+            .style(ui_style::header_text),
+        container("").width(Length::Fill),
+        button(text("✕").size(12))
+            .padding(4)
+            .style(ui_style::tab_close_button)
+            .on_press(Message::Terminal(TerminalMessage::ToggleCommandHistory)),
+    ]
+    .align_y(Alignment::Center);
+
+    let search = text_input("Search commands", query)
+        .on_input(|value| Message::Terminal(TerminalMessage::CommandHistoryQueryChanged(value)))
+        .padding([6, 8])
+        .size(13)
+        .style(ui_style::dialog_input);
+
+    let confirm = pending_rerun.map(|command| {
+        container(
+            column![
+                text("Run this command again?").size(12),
+                text(command).size(12).font(Font::MONOSPACE),
+                row![
+                    button(text("Run").size(12))
+                        .padding([4, 12])
+                        .style(ui_style::primary_button_style)
+                        .on_press(Message::Terminal(TerminalMessage::ConfirmRunCommand)),
+                    button(text("Cancel").size(12))
+                        .padding([4, 12])
+                        .style(ui_style::secondary_button_style)
+                        .on_press(Message::Terminal(TerminalMessage::CancelRunCommand)),
+                ]
+                .spacing(6),
+            ]
+            .spacing(6),
+        )
+        .padding(8)
+        .width(Length::Fill)
+        .style(ui_style::panel)
+    });
+
+    let needle = query.trim().to_lowercase();
+    let matches: Vec<CommandRecord> = records
+        .into_iter()
+        .rev()
+        .filter(|record| needle.is_empty() || record.command.to_lowercase().contains(&needle))
+        .take(MAX_SHOWN)
+        .collect();
+
+    let list: Element<'a, Message> = if matches.is_empty() {
+        let hint = if needle.is_empty() {
+            "No commands yet. Commands appear here when the shell emits OSC 133 prompt marks."
+        } else {
+            "No matching commands."
+        };
+        text(hint).size(12).style(ui_style::muted_text).into()
+    } else {
+        scrollable(
+            matches
+                .into_iter()
+                .fold(Column::new().spacing(4), |list, record| {
+                    list.push(entry(record))
+                }),
+        )
+        .style(ui_style::scrollable_style)
+        .height(Length::Fill)
+        .into()
+    };
+
+    container(column![header, search, confirm, list].spacing(10))
+        .padding(12)
+        .width(Length::Fixed(SIDEBAR_WIDTH))
+        .height(Length::Fill)
+        .style(ui_style::drawer_panel)
+        .into()
+}
+
+fn entry<'a>(record: CommandRecord) -> Element<'a, Message> {
+    let status = match (record.is_running(), record.exit_code) {
+        (true, _) => text("running").size(11).style(ui_style::muted_text),
+        (false, Some(0)) => text("✓").size(11).color(Color::from_rgb(0.3, 0.7, 0.4)),
+        (false, Some(code)) => text(format!("exit {}", code))
+            .size(11)
+            .color(Color::from_rgb(0.9, 0.3, 0.3)),
+        (false, None) => text("").size(11),
+    };
+    let details = row![
+        text(record.started_at.format("%H:%M:%S").to_string())
+            .size(11)
+            .style(ui_style::muted_text),
+        text(format_duration(record.elapsed()))
+            .size(11)
+            .style(ui_style::muted_text),
+        container("").width(Length::Fill),
+        status,
+    ]
+    .spacing(8);
+
+    let insert = button(
+        column![
+            text(record.command.clone()).size(12).font(Font::MONOSPACE),
+            details,
+        ]
+        .spacing(2),
+    )
+    .padding([6, 8])
+    .width(Length::Fill)
+    .style(ui_style::menu_item_button)
+    .on_press(Message::Terminal(TerminalMessage::InsertCommand(
+        record.command.clone(),
+    )));
+
+    row![
+        insert,
+        button(text("▶").size(11))
+            .padding([4, 6])
+            .style(ui_style::icon_button)
+            .on_press(Message::Terminal(TerminalMessage::RunCommand(
+                record.command
+            ))),
+    ]
+    .spacing(2)
+    .align_y(Alignment::Center)
+    .into()
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1000 {
+        format!("{}ms", millis)
+    } else if millis < 60_000 {
+        format!("{:.1}s", duration.as_secs_f32())
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
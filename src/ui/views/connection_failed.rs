@@ -0,0 +1,148 @@
+use iced::widget::text::Wrapping;
+use iced::widget::{Column, button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Font, Length};
+
+use crate::ssh::FailureKind;
+use crate::ui::style as ui_style;
+use crate::ui::{Message, SessionMessage};
+
+pub fn render(
+    err: &str,
+    tab_index: usize,
+    show_log: bool,
+    has_debug_log: bool,
+) -> Element<'_, Message> {
+    let kind = FailureKind::classify(err);
+
+    let action = |label: &'static str, message: Message| {
+        button(text(label).size(14))
+            .padding([8, 16])
+            .style(ui_style::secondary_button_style)
+            .on_press(message)
+    };
+
+    let mut actions = row![
+        button(text("🔄 Retry").size(14))
+            .padding([8, 16])
+            .style(ui_style::primary_button_style)
+            .on_press(Message::RetryConnection(tab_index)),
+    ]
+    .spacing(12);
+    match kind {
+        FailureKind::Auth => {
+            actions = actions.push(action(
+                "Try password instead",
+                Message::Sessions(SessionMessage::TryPasswordAuth(tab_index)),
+            ));
+        }
+        FailureKind::Dns => {
+            actions = actions.push(action(
+                "Edit host",
+                Message::Sessions(SessionMessage::EditConfig(tab_index)),
+            ));
+        }
+        FailureKind::Refused | FailureKind::Timeout => {
+            actions = actions.push(action(
+                "Edit host",
+                Message::Sessions(SessionMessage::EditConfig(tab_index)),
+            ));
+            actions = actions.push(action(
+                "Open port-forward check",
+                Message::Sessions(SessionMessage::OpenPortForwardCheck(tab_index)),
+            ));
+        }
+        FailureKind::HostKey | FailureKind::Other => {
+            actions = actions.push(action(
+                "✏️ Edit",
+                Message::Sessions(SessionMessage::EditConfig(tab_index)),
+            ));
+        }
+    }
+    actions = actions.push(action(
+        if show_log {
+            "Hide verbose log"
+        } else {
+            "View verbose log"
+        },
+        Message::ToggleFailureLog(tab_index),
+    ));
+    if has_debug_log {
+        actions = actions.push(action(
+            "Debug log",
+            Message::Sessions(SessionMessage::ShowDebugLog(tab_index)),
+        ));
+    }
+
+    // A changed key is the one failure that may mean an attack, so spell out
+    // both fingerprints instead of hiding them behind the verbose log.
+    let host_key_alert = (kind == FailureKind::HostKey).then(|| {
+        container(
+            column![
+                text("⚠️ The connection was refused to protect you")
+                    .size(14)
+                    .color(iced::Color::from_rgb(0.9, 0.3, 0.3)),
+                log_line(err.to_string()),
+                text(
+                    "Someone may be intercepting the connection. If the server was \
+                     reinstalled, update the pinned key under Edit → Advanced."
+                )
+                .size(12),
+            ]
+            .spacing(8),
+        )
+        .padding(12)
+        .width(Length::Fill)
+        .style(ui_style::error_banner)
+    });
+
+    let mut content = column![
+        text(format!("❌ {}", kind.title()))
+            .size(24)
+            .color(iced::Color::from_rgb(0.8, 0.2, 0.2)),
+        text(kind.hint()).size(14).style(ui_style::muted_text),
+        host_key_alert,
+        actions,
+    ]
+    .spacing(20)
+    .align_x(Alignment::Center)
+    .max_width(720);
+
+    if show_log {
+        let mut lines = Column::new().spacing(2);
+        lines = lines.push(log_line(format!("category: {:?}", kind)));
+        // The error is formatted with its full cause chain, outermost first.
+        for (depth, cause) in err.split(": ").enumerate() {
+            let line = if depth == 0 {
+                format!("error: {}", cause)
+            } else {
+                format!("{}caused by: {}", "  ".repeat(depth), cause)
+            };
+            lines = lines.push(log_line(line));
+        }
+        content = content.push(
+            container(
+                scrollable(container(lines).padding([8, 10]))
+                    .style(ui_style::scrollable_style)
+                    .width(Length::Fill),
+            )
+            .style(ui_style::panel)
+            .width(Length::Fill)
+            .max_height(220),
+        );
+    }
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
+
+fn log_line<'a>(line: String) -> Element<'a, Message> {
+    text(line)
+        .size(12)
+        .font(Font::MONOSPACE)
+        .wrapping(Wrapping::WordOrGlyph)
+        .into()
+}
@@ -0,0 +1,73 @@
+use iced::widget::{button, checkbox, column, container, row, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use crate::ssh::{CredentialKind, CredentialRequest};
+use crate::ui::style as ui_style;
+use crate::ui::{Message, SessionMessage};
+
+pub fn render<'a>(
+    request: &'a CredentialRequest,
+    value: &'a str,
+    remember: bool,
+    input_id: &'a iced::widget::Id,
+) -> Element<'a, Message> {
+    let title = match request.kind {
+        CredentialKind::Password => "Password required",
+        CredentialKind::KeyPassphrase => "Key passphrase required",
+        CredentialKind::KeyboardInteractive => "The server is asking",
+    };
+    let hint = if request.retry {
+        format!("{} — that didn't work, try again", request.target)
+    } else {
+        request.target.clone()
+    };
+
+    let input = text_input(&request.prompt, value)
+        .id(input_id.clone())
+        .on_input(|value| Message::Sessions(SessionMessage::CredentialInputChanged(value)))
+        .on_submit(Message::Sessions(SessionMessage::SubmitCredential))
+        .secure(!request.echo)
+        .padding([8, 10])
+        .size(13)
+        .style(ui_style::dialog_input);
+
+    // Saved with the session once the connection is in, like a passphrase
+    // typed into the session form.
+    let remember = request.can_remember.then(|| {
+        checkbox(remember)
+            .label("Remember in keyring")
+            .on_toggle(|value| Message::Sessions(SessionMessage::CredentialRememberToggled(value)))
+            .size(16)
+            .text_size(13)
+    });
+
+    let actions = row![
+        container("").width(Length::Fill),
+        button(text("Cancel").size(12))
+            .padding([6, 12])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::Sessions(SessionMessage::CancelCredential)),
+        button(text("Continue").size(12))
+            .padding([6, 12])
+            .style(ui_style::primary_button_style)
+            .on_press(Message::Sessions(SessionMessage::SubmitCredential)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(
+        column![
+            text(title).size(16).style(ui_style::header_text),
+            text(hint).size(13).style(ui_style::muted_text),
+            text(&request.prompt).size(12),
+            input,
+            remember,
+            actions
+        ]
+        .spacing(12)
+        .width(Length::Fixed(360.0)),
+    )
+    .padding(16)
+    .style(ui_style::dialog_container)
+    .into()
+}
@@ -0,0 +1,86 @@
+use iced::widget::text::Wrapping;
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Font, Length};
+
+use crate::ui::style as ui_style;
+use crate::ui::{Message, SessionMessage};
+
+pub fn render<'a>(
+    title: &'a str,
+    lines: Vec<String>,
+    status: Option<&'a str>,
+    width: f32,
+    height: f32,
+) -> Element<'a, Message> {
+    let header = row![
+        column![
+            text("Connection debug log")
+                .size(16)
+                .style(ui_style::header_text),
+            text(title).size(12).style(ui_style::muted_text),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        text(status.unwrap_or_default())
+            .size(12)
+            .style(ui_style::muted_text),
+        button(text("Export…").size(12))
+            .padding([4, 10])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::Sessions(SessionMessage::ExportDebugLog)),
+        button(text("✕").size(13))
+            .padding(6)
+            .style(ui_style::tab_close_button)
+            .on_press(Message::Sessions(SessionMessage::CloseDebugLog)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    let mut body = column![].spacing(1);
+    if lines.is_empty() {
+        body = body.push(
+            text("Nothing recorded yet.")
+                .size(12)
+                .style(ui_style::muted_text),
+        );
+    }
+    for line in lines {
+        body = body.push(
+            text(line)
+                .size(12)
+                .font(Font::MONOSPACE)
+                .wrapping(Wrapping::None),
+        );
+    }
+
+    let body = container(
+        scrollable(container(body).padding([6, 10]))
+            .anchor_bottom()
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::new()
+                    .width(6)
+                    .scroller_width(6)
+                    .margin(2),
+                horizontal: scrollable::Scrollbar::new()
+                    .width(6)
+                    .scroller_width(6)
+                    .margin(2),
+            })
+            .style(ui_style::scrollable_style)
+            .width(Length::Fill)
+            .height(Length::Fill),
+    )
+    .style(ui_style::panel)
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    container(
+        column![header, body]
+            .spacing(12)
+            .width(Length::Fixed(width))
+            .height(Length::Fixed(height)),
+    )
+    .padding(16)
+    .style(ui_style::dialog_container)
+    .into()
+}
@@ -0,0 +1,97 @@
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Color, Element, Length};
+
+use crate::settings::SshKeyEntry;
+use crate::ssh::KeyDeployment;
+use crate::ui::Message;
+use crate::ui::state::{DeployKey, DeployKeyStatus};
+use crate::ui::style as ui_style;
+
+/// Picks a saved key and installs its public half on the session's host,
+/// signing in with the password.
+pub fn render<'a>(deploy: &'a DeployKey, keys: &'a [SshKeyEntry]) -> Element<'a, Message> {
+    let deploying = deploy.status == DeployKeyStatus::Deploying;
+    let choices: Element<'a, Message> = if keys.is_empty() {
+        text("No saved keys yet. Add one under Settings → Keys.")
+            .size(12)
+            .style(ui_style::muted_text)
+            .into()
+    } else {
+        row(keys.iter().map(|key| {
+            button(text(format!("{} · {}", key.name, key.key_type)).size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(key.id == deploy.key_id))
+                .on_press_maybe((!deploying).then(|| Message::DeployKeySelected(key.id.clone())))
+                .into()
+        }))
+        .spacing(4)
+        .wrap()
+        .into()
+    };
+
+    let status = match &deploy.status {
+        DeployKeyStatus::Idle => None,
+        DeployKeyStatus::Deploying => Some(
+            text("Signing in with the password…")
+                .size(12)
+                .style(ui_style::muted_text),
+        ),
+        DeployKeyStatus::Done(KeyDeployment::Added) => Some(
+            text("Added to ~/.ssh/authorized_keys. The session can now sign in with this key.")
+                .size(12)
+                .color(Color::from_rgb(0.3, 0.7, 0.4)),
+        ),
+        DeployKeyStatus::Done(KeyDeployment::AlreadyPresent) => Some(
+            text("The key was already in ~/.ssh/authorized_keys.")
+                .size(12)
+                .color(Color::from_rgb(0.3, 0.7, 0.4)),
+        ),
+        DeployKeyStatus::Failed(e) => Some(
+            text(e.as_str())
+                .size(12)
+                .color(Color::from_rgb(0.9, 0.3, 0.3)),
+        ),
+    };
+
+    let finished = matches!(deploy.status, DeployKeyStatus::Done(_));
+    let actions = row![
+        container("").width(Length::Fill),
+        button(text(if finished { "Done" } else { "Cancel" }).size(12))
+            .padding([6, 12])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::CloseDeployKey),
+        (!finished).then(|| {
+            button(text("Deploy").size(12))
+                .padding([6, 12])
+                .style(ui_style::primary_button_style)
+                .on_press_maybe(
+                    (!deploying && !deploy.key_id.is_empty()).then_some(Message::DeployKey),
+                )
+        }),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(
+        column![
+            text("Deploy public key")
+                .size(16)
+                .style(ui_style::header_text),
+            text(format!(
+                "Appends the key to ~/.ssh/authorized_keys on {}, signing in with the \
+                 session's password.",
+                deploy.target
+            ))
+            .size(12)
+            .style(ui_style::muted_text),
+            choices,
+            status,
+            actions
+        ]
+        .spacing(12)
+        .width(Length::Fixed(420.0)),
+    )
+    .padding(16)
+    .style(ui_style::dialog_container)
+    .into()
+}
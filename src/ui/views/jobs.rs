@@ -0,0 +1,97 @@
+use iced::widget::{button, column, container, progress_bar, row, scrollable, text};
+use iced::{Alignment, Color, Element, Length};
+
+use crate::core::jobs::{Job, JobQueue, JobState};
+use crate::ui::Message;
+use crate::ui::state::JobOwner;
+use crate::ui::style as ui_style;
+
+const PANEL_WIDTH: f32 = 340.0;
+const PANEL_MAX_HEIGHT: f32 = 360.0;
+
+/// Popover opened from the status bar with everything running in the
+/// background, newest first.
+pub fn panel(jobs: &JobQueue<JobOwner>) -> Element<'_, Message> {
+    let has_finished = jobs.jobs().any(|job| !job.is_running());
+    let header = row![
+        text("Background jobs")
+            .size(13)
+            .style(ui_style::header_text)
+            .width(Length::Fill),
+        has_finished.then(|| {
+            button(text("Clear").size(11))
+                .padding([2, 8])
+                .style(ui_style::menu_button(false))
+                .on_press(Message::ClearFinishedJobs)
+        }),
+    ]
+    .align_y(Alignment::Center);
+
+    let rows = jobs
+        .jobs()
+        .rev()
+        .fold(column![].spacing(8), |rows, job| rows.push(job_row(job)));
+    let body: Element<'_, Message> = if jobs.is_empty() {
+        text("Nothing running")
+            .size(12)
+            .style(ui_style::muted_text)
+            .into()
+    } else {
+        scrollable(rows)
+            .direction(ui_style::thin_scrollbar())
+            .style(ui_style::scrollable_style)
+            .into()
+    };
+
+    container(column![header, body].spacing(10))
+        .padding([10, 12])
+        .width(Length::Fixed(PANEL_WIDTH))
+        .max_height(PANEL_MAX_HEIGHT)
+        .style(ui_style::popover_menu)
+        .into()
+}
+
+fn job_row(job: &Job<JobOwner>) -> Element<'_, Message> {
+    let status: Element<'_, Message> = match &job.state {
+        JobState::Running => match job.progress {
+            Some(progress) => row![
+                container(progress_bar(0.0..=1.0, progress))
+                    .height(Length::Fixed(4.0))
+                    .width(Length::Fill),
+                text(format!("{:.0}%", progress * 100.0))
+                    .size(11)
+                    .style(ui_style::muted_text),
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center)
+            .into(),
+            None => text(format!("Running for {}s", job.started.elapsed().as_secs()))
+                .size(11)
+                .style(ui_style::muted_text)
+                .into(),
+        },
+        JobState::Done => text("Done").size(11).style(ui_style::muted_text).into(),
+        JobState::Cancelled => text("Cancelled")
+            .size(11)
+            .style(ui_style::muted_text)
+            .into(),
+        JobState::Failed(error) => text(error)
+            .size(11)
+            .color(Color::from_rgb(0.9, 0.3, 0.3))
+            .into(),
+    };
+    row![
+        column![text(&job.label).size(12), status]
+            .spacing(3)
+            .width(Length::Fill),
+        job.is_running().then(|| {
+            button(text("✕").size(11))
+                .padding([2, 6])
+                .style(ui_style::icon_button)
+                .on_press(Message::CancelBackgroundJob(job.id))
+        }),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center)
+    .into()
+}
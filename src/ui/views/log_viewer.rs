@@ -0,0 +1,119 @@
+use iced::widget::text::Wrapping;
+use iced::widget::{button, column, container, row, scrollable, text, text_input};
+use iced::{Alignment, Element, Font, Length};
+
+use crate::ui::Message;
+use crate::ui::state::LogTailState;
+use crate::ui::style as ui_style;
+
+// Rendering thousands of text widgets is slow; only the tail end is shown.
+const MAX_VISIBLE_LINES: usize = 1000;
+
+pub fn render(state: &LogTailState, width: f32, height: f32) -> Element<'_, Message> {
+    let status = if let Some(err) = &state.error {
+        err.clone()
+    } else if state.paused {
+        format!("Paused ({} new lines)", state.held.len())
+    } else if state.streaming {
+        "Streaming".to_string()
+    } else if state.rx.is_none() && state.lines.is_empty() {
+        "Starting...".to_string()
+    } else {
+        "Stream ended".to_string()
+    };
+
+    let header = row![
+        column![
+            text("Tail").size(16).style(ui_style::header_text),
+            text(&state.path)
+                .size(12)
+                .style(ui_style::muted_text)
+                .wrapping(Wrapping::None),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        text(status).size(12).style(ui_style::muted_text),
+        button(text(if state.paused { "Resume" } else { "Pause" }).size(12))
+            .padding([4, 10])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::LogTailTogglePause),
+        button(text("Clear").size(12))
+            .padding([4, 10])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::LogTailClear),
+        button(text("✕").size(13))
+            .padding(6)
+            .style(ui_style::tab_close_button)
+            .on_press(Message::LogTailClose),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    let controls = row![
+        text_input("Filter", &state.filter)
+            .on_input(Message::LogTailFilterChanged)
+            .padding([4, 8])
+            .size(13)
+            .style(ui_style::dialog_input)
+            .width(Length::Fill),
+        text_input("Highlight", &state.highlight)
+            .on_input(Message::LogTailHighlightChanged)
+            .padding([4, 8])
+            .size(13)
+            .style(ui_style::dialog_input)
+            .width(Length::Fill),
+    ]
+    .spacing(8);
+
+    let filter = state.filter.to_lowercase();
+    let highlight = state.highlight.to_lowercase();
+    let matching: Vec<&String> = state
+        .lines
+        .iter()
+        .filter(|line| filter.is_empty() || line.to_lowercase().contains(&filter))
+        .collect();
+    let skip = matching.len().saturating_sub(MAX_VISIBLE_LINES);
+
+    let mut lines = column![];
+    for line in matching.into_iter().skip(skip) {
+        let mut line_text = text(line.as_str())
+            .size(12)
+            .font(Font::MONOSPACE)
+            .wrapping(Wrapping::None);
+        if !highlight.is_empty() && line.to_lowercase().contains(&highlight) {
+            line_text = line_text.color(ui_style::accent_color());
+        }
+        lines = lines.push(line_text);
+    }
+
+    let body = container(
+        scrollable(container(lines.spacing(1)).padding([6, 10]))
+            .anchor_bottom()
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::new()
+                    .width(6)
+                    .scroller_width(6)
+                    .margin(2),
+                horizontal: scrollable::Scrollbar::new()
+                    .width(6)
+                    .scroller_width(6)
+                    .margin(2),
+            })
+            .style(ui_style::scrollable_style)
+            .width(Length::Fill)
+            .height(Length::Fill),
+    )
+    .style(ui_style::panel)
+    .width(Length::Fill)
+    .height(Length::Fill);
+
+    container(
+        column![header, controls, body]
+            .spacing(12)
+            .width(Length::Fixed(width))
+            .height(Length::Fixed(height)),
+    )
+    .padding(16)
+    .style(ui_style::dialog_container)
+    .into()
+}
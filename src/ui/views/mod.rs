@@ -1,7 +1,21 @@
+pub mod bandwidth;
+pub mod close_warning;
+pub mod command_history;
+pub mod connection_failed;
+pub mod credential_prompt;
+pub mod debug_log;
+pub mod deploy_key;
+pub mod jobs;
+pub mod log_viewer;
+pub mod placeholder_prompt;
 pub mod quick_connect;
+pub mod send_key;
 pub mod session_manager;
 pub mod sftp;
 pub mod status_bar;
 pub mod tab_bar;
+pub mod tab_overview;
 pub mod terminal;
 pub mod terminal_gpu;
+pub mod terminal_menu;
+pub mod toast;
@@ -0,0 +1,90 @@
+use iced::widget::{Id, button, column, container, row, text, text_input};
+use iced::{Alignment, Element, Font, Length};
+
+use crate::ui::Message;
+use crate::ui::state::{PlaceholderPrompt, PlaceholderTarget};
+use crate::ui::style as ui_style;
+
+/// Focused when the prompt opens.
+pub fn first_input() -> Id {
+    Id::new("placeholder-input")
+}
+
+/// Asks for the values of a snippet's or remote command's placeholders.
+/// Placeholders with choices are picked from buttons instead of typed.
+pub fn render(prompt: &PlaceholderPrompt) -> Element<'_, Message> {
+    let mut fields = column![].spacing(8);
+    for (index, (placeholder, value)) in prompt.placeholders.iter().zip(&prompt.values).enumerate()
+    {
+        let field: Element<'_, Message> = if placeholder.choices.is_empty() {
+            let mut input = text_input(&placeholder.default, value)
+                .on_input(move |value| Message::PlaceholderChanged(index, value))
+                .on_submit(Message::SubmitPlaceholders)
+                .padding([6, 10])
+                .size(13)
+                .style(ui_style::dialog_input);
+            if index == 0 {
+                input = input.id(first_input());
+            }
+            input.into()
+        } else {
+            row(placeholder.choices.iter().map(|choice| {
+                button(text(choice.as_str()).size(12))
+                    .padding([4, 10])
+                    .style(ui_style::menu_button(choice == value))
+                    .on_press(Message::PlaceholderChanged(index, choice.clone()))
+                    .into()
+            }))
+            .spacing(4)
+            .wrap()
+            .into()
+        };
+        fields = fields.push(
+            row![
+                text(placeholder.name.as_str())
+                    .size(13)
+                    .width(Length::Fixed(110.0)),
+                field,
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let run_label = match prompt.target {
+        PlaceholderTarget::Snippet => "Paste",
+        PlaceholderTarget::RemoteCommand(_) => "Connect",
+    };
+    let actions = row![
+        container("").width(Length::Fill),
+        button(text("Cancel").size(12))
+            .padding([6, 12])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::CancelPlaceholders),
+        button(text(run_label).size(12))
+            .padding([6, 12])
+            .style(ui_style::primary_button_style)
+            .on_press(Message::SubmitPlaceholders),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(
+        column![
+            text(prompt.title.as_str())
+                .size(16)
+                .style(ui_style::header_text),
+            text(prompt.text.as_str())
+                .size(12)
+                .font(Font::MONOSPACE)
+                .style(ui_style::muted_text),
+            fields,
+            actions
+        ]
+        .spacing(12)
+        .width(Length::Fixed(420.0)),
+    )
+    .padding(16)
+    .style(ui_style::dialog_container)
+    .into()
+}
@@ -1,35 +1,97 @@
-use crate::session::SessionConfig;
-use crate::ui::Message;
+use crate::session::search::SessionMatch;
+use crate::session::target::ConnectTarget;
+use crate::terminal::share::ShareLink;
+use crate::ui::state::CommandMatch;
 use crate::ui::style as ui_style;
-use iced::widget::{Space, button, column, container, row, scrollable, text, text_input};
-use iced::{Alignment, Element, Length};
+use crate::ui::views::command_history::format_duration;
+use crate::ui::{Message, SessionMessage};
+use iced::widget::text::Span;
+use iced::widget::{
+    Space, button, column, container, rich_text, row, scrollable, span, text, text_input,
+};
+use iced::{Alignment, Element, Font, Length, font};
 
 pub fn render<'a>(
     quick_connect_query: &'a str,
-    saved_sessions: &'a [SessionConfig],
+    matches: Vec<SessionMatch<'a>>,
+    commands: Vec<CommandMatch>,
+    selected: usize,
+    clipboard_target: Option<&'a ConnectTarget>,
+    share_link: Option<ShareLink>,
 ) -> Element<'a, Message> {
     // 1. Search Bar
-    let search_bar = text_input("Search sessions...", quick_connect_query)
-        .on_input(Message::QuickConnectQueryChanged)
-        .padding(10)
-        .size(14)
-        .style(ui_style::search_input);
+    let search_bar = text_input(
+        "Search sessions, tags or command history...",
+        quick_connect_query,
+    )
+    .on_input(Message::QuickConnectQueryChanged)
+    .on_submit(Message::QuickConnectSubmit)
+    .padding(10)
+    .size(14)
+    .style(ui_style::search_input);
 
-    // 2. Remote Sessions List
-    let filtered_sessions: Vec<_> = saved_sessions
-        .iter()
-        .filter(|s| {
-            quick_connect_query.is_empty()
-                || s.name
-                    .to_lowercase()
-                    .contains(&quick_connect_query.to_lowercase())
-                || s.host
-                    .to_lowercase()
-                    .contains(&quick_connect_query.to_lowercase())
-        })
-        .collect();
+    // Offered when a host was copied from a wiki page or an alert.
+    let clipboard_section = clipboard_target.map(|target| {
+        column![
+            text("FROM CLIPBOARD")
+                .size(11)
+                .style(ui_style::quick_connect_section_header),
+            button(
+                row![
+                    text("📋").size(16).width(Length::Fixed(24.0)),
+                    text(format!("Connect to {}", target)).size(14),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .width(Length::Fill)
+            .padding(10)
+            .style(ui_style::quick_connect_item)
+            .on_press(Message::Sessions(SessionMessage::ConnectClipboardTarget)),
+            Space::new().height(16.0),
+        ]
+        .spacing(8)
+    });
+
+    // Someone sent a link to a terminal they are sharing.
+    let share_section = share_link.map(|link| {
+        column![
+            text("SHARED TERMINAL")
+                .size(11)
+                .style(ui_style::quick_connect_section_header),
+            button(
+                row![
+                    text("👁").size(16).width(Length::Fixed(24.0)),
+                    column![
+                        text("Watch shared terminal (read-only)").size(14),
+                        text(link.addr.to_string())
+                            .size(12)
+                            .style(ui_style::muted_text),
+                    ]
+                    .spacing(2),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .width(Length::Fill)
+            .padding(10)
+            .style(ui_style::quick_connect_item)
+            .on_press(Message::WatchShare(link)),
+            Space::new().height(16.0),
+        ]
+        .spacing(8)
+    });
 
-    let sessions_list: Element<'_, Message> = if filtered_sessions.is_empty() {
+    // Details of the highlighted result, kept up to date while typing.
+    let preview = match matches.get(selected) {
+        Some(m) => Some(session_preview(m)),
+        None => commands
+            .get(selected.wrapping_sub(matches.len()))
+            .map(command_preview),
+    };
+
+    // 2. Remote Sessions List
+    let local_selected = selected >= matches.len() + commands.len();
+    let command_offset = matches.len();
+    let sessions_list: Element<'_, Message> = if matches.is_empty() {
         container(
             text("No matching sessions")
                 .size(14)
@@ -40,28 +102,48 @@ pub fn render<'a>(
         .into()
     } else {
         column(
-            filtered_sessions
-                .iter()
-                .map(|session| {
+            matches
+                .into_iter()
+                .enumerate()
+                .map(|(index, m)| {
+                    let session = m.session;
+                    let mut detail = row![
+                        highlighted(&session.username, &m.username, 12.0),
+                        text("@").size(12).style(ui_style::muted_text),
+                        highlighted(&session.host, &m.host, 12.0),
+                        text(format!(":{}", session.port))
+                            .size(12)
+                            .style(ui_style::muted_text),
+                    ];
+                    for (tag_index, tag) in session.tags.iter().enumerate() {
+                        let hits = m
+                            .tags
+                            .iter()
+                            .find(|(matched, _)| *matched == tag_index)
+                            .map(|(_, hits)| hits.as_slice())
+                            .unwrap_or_default();
+                        detail = detail.push(Space::new().width(8.0));
+                        detail = detail.push(text("#").size(12).style(ui_style::muted_text));
+                        detail = detail.push(highlighted(tag, hits, 12.0));
+                    }
+
                     button(
                         row![
                             text(">_")
                                 .size(14)
                                 .style(ui_style::muted_text)
                                 .width(Length::Fixed(24.0)),
-                            column![
-                                text(&session.name).size(14),
-                                text(format!("{}:{}", session.host, session.port))
-                                    .size(12)
-                                    .style(ui_style::muted_text),
-                            ]
-                            .spacing(2),
+                            column![highlighted(&session.name, &m.name, 14.0), detail].spacing(2),
                         ]
                         .align_y(Alignment::Center),
                     )
                     .width(Length::Fill)
                     .padding(10)
-                    .style(ui_style::quick_connect_item)
+                    .style(if index == selected {
+                        ui_style::quick_connect_item_selected
+                    } else {
+                        ui_style::quick_connect_item
+                    })
                     .on_press(Message::SelectQuickConnectSession(session.id.clone()))
                     .into()
                 })
@@ -79,6 +161,56 @@ pub fn render<'a>(
     ]
     .spacing(8);
 
+    let command_section = (!commands.is_empty()).then(|| {
+        let rows = commands.into_iter().enumerate().map(|(index, m)| {
+            let status = match m.record.exit_code {
+                Some(0) | None => String::new(),
+                Some(code) => format!(" · exit {}", code),
+            };
+            button(
+                row![
+                    text("❯")
+                        .size(14)
+                        .style(ui_style::muted_text)
+                        .width(Length::Fixed(24.0)),
+                    column![
+                        highlighted(&m.record.command, &m.hits, 13.0),
+                        text(format!(
+                            "{} · {}{}",
+                            m.tab_title,
+                            m.record.started_at.format("%H:%M"),
+                            status
+                        ))
+                        .size(12)
+                        .style(ui_style::muted_text),
+                    ]
+                    .spacing(2),
+                ]
+                .align_y(Alignment::Center),
+            )
+            .width(Length::Fill)
+            .padding(10)
+            .style(if command_offset + index == selected {
+                ui_style::quick_connect_item_selected
+            } else {
+                ui_style::quick_connect_item
+            })
+            .on_press(Message::QuickConnectInsertCommand(
+                m.tab_index,
+                m.record.command,
+            ))
+            .into()
+        });
+        column![
+            Space::new().height(24.0),
+            text("COMMAND HISTORY")
+                .size(11)
+                .style(ui_style::quick_connect_section_header),
+            column(rows.collect::<Vec<Element<'a, Message>>>()).spacing(2),
+        ]
+        .spacing(8)
+    });
+
     // 3. Local System Section
     let local_section = column![
         text("LOCAL SYSTEM")
@@ -93,7 +225,11 @@ pub fn render<'a>(
         )
         .width(Length::Fill)
         .padding(10)
-        .style(ui_style::quick_connect_item)
+        .style(if local_selected {
+            ui_style::quick_connect_item_selected
+        } else {
+            ui_style::quick_connect_item
+        })
         .on_press(Message::CreateLocalTab),
     ]
     .spacing(8);
@@ -119,22 +255,126 @@ pub fn render<'a>(
         search_bar,
         Space::new().height(16.0),
         scrollable(column![
+            share_section,
+            clipboard_section,
             remote_section,
+            command_section,
             Space::new().height(24.0),
             local_section
         ])
         .direction(ui_style::thin_scrollbar())
         .style(ui_style::scrollable_style)
         .height(Length::Fill),
+        preview.map(|preview| container(preview)
+            .padding(10)
+            .width(Length::Fill)
+            .style(ui_style::panel)),
         Space::new().height(16.0),
         footer
     ]
     .spacing(0)
     .padding(24)
     .width(Length::Fixed(600.0))
-    .height(Length::Fixed(450.0));
+    .height(Length::Fixed(520.0));
 
     container(content)
         .style(ui_style::quick_connect_container)
         .into()
 }
+
+fn session_preview<'a>(m: &SessionMatch<'a>) -> Element<'a, Message> {
+    let session = m.session;
+    let last_connected = session.last_connected.map_or_else(
+        || "Never connected".to_string(),
+        |dt| {
+            format!(
+                "Last connected {}",
+                dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            )
+        },
+    );
+    let mut details = column![
+        text(format!(
+            "{}@{}:{}",
+            session.username, session.host, session.port
+        ))
+        .size(12)
+        .font(Font::MONOSPACE),
+        text(last_connected).size(12).style(ui_style::muted_text),
+    ]
+    .spacing(4);
+    if !session.tags.is_empty() {
+        details = details.push(
+            text(
+                session
+                    .tags
+                    .iter()
+                    .map(|tag| format!("#{}", tag))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+            .size(12)
+            .style(ui_style::muted_text),
+        );
+    }
+    details.into()
+}
+
+fn command_preview<'a>(m: &CommandMatch) -> Element<'a, Message> {
+    let record = &m.record;
+    let mut facts = vec![
+        format!("In {}", m.tab_title),
+        record.started_at.format("%Y-%m-%d %H:%M").to_string(),
+    ];
+    if let Some(duration) = record.duration {
+        facts.push(format!("took {}", format_duration(duration)));
+    }
+    match record.exit_code {
+        Some(code) => facts.push(format!("exit {}", code)),
+        None if record.duration.is_none() => facts.push("running".to_string()),
+        None => {}
+    }
+    column![
+        text(record.command.clone()).size(12).font(Font::MONOSPACE),
+        text(facts.join(" · ")).size(12).style(ui_style::muted_text),
+        text("↩ types it into the terminal without running it")
+            .size(11)
+            .style(ui_style::muted_text),
+    ]
+    .spacing(4)
+    .into()
+}
+
+// Renders `value` with the fuzzy-matched characters at `hits` emphasized.
+fn highlighted<'a>(value: &str, hits: &[usize], size: f32) -> Element<'a, Message> {
+    if hits.is_empty() {
+        return text(value.to_string()).size(size).into();
+    }
+
+    let mut spans: Vec<Span<'a, (), Font>> = Vec::new();
+    let mut run = String::new();
+    let mut run_is_hit = false;
+    for (index, c) in value.chars().enumerate() {
+        let is_hit = hits.contains(&index);
+        if is_hit != run_is_hit && !run.is_empty() {
+            spans.push(match_span(std::mem::take(&mut run), run_is_hit));
+        }
+        run_is_hit = is_hit;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(match_span(run, run_is_hit));
+    }
+    rich_text(spans).size(size).into()
+}
+
+fn match_span<'a>(content: String, is_hit: bool) -> Span<'a, (), Font> {
+    if is_hit {
+        span(content).color(ui_style::accent_color()).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::DEFAULT
+        })
+    } else {
+        span(content)
+    }
+}
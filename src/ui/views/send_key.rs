@@ -0,0 +1,54 @@
+use iced::widget::{Row, button, column, container, text};
+use iced::{Element, Length};
+
+use crate::terminal::special_keys::{self, CustomKey};
+use crate::ui::style as ui_style;
+use crate::ui::{Message, TerminalMessage};
+
+const KEYS_PER_ROW: usize = 6;
+
+pub fn render(custom_keys: &[CustomKey]) -> Element<'_, Message> {
+    let mut content = column![text("Send key").size(13).style(ui_style::header_text)].spacing(6);
+
+    let builtin: Vec<(String, Vec<u8>)> = special_keys::special_keys();
+    for chunk in builtin.chunks(KEYS_PER_ROW) {
+        content = content.push(key_row(chunk.to_vec()));
+    }
+
+    if !custom_keys.is_empty() {
+        content = content.push(text("Custom").size(12).style(ui_style::muted_text));
+        let custom: Vec<(String, Vec<u8>)> = custom_keys
+            .iter()
+            .map(|key| {
+                let label = match &key.shortcut {
+                    Some(shortcut) => format!("{} ({})", key.label, shortcut),
+                    None => key.label.clone(),
+                };
+                (label, key.bytes())
+            })
+            .collect();
+        for chunk in custom.chunks(KEYS_PER_ROW / 2) {
+            content = content.push(key_row(chunk.to_vec()));
+        }
+    }
+
+    container(content)
+        .padding([10, 12])
+        .width(Length::Fixed(380.0))
+        .style(ui_style::popover_menu)
+        .into()
+}
+
+fn key_row<'a>(keys: Vec<(String, Vec<u8>)>) -> Element<'a, Message> {
+    keys.into_iter()
+        .fold(Row::new().spacing(4), |row, (label, bytes)| {
+            row.push(
+                button(text(label).size(12).center())
+                    .padding([4, 6])
+                    .width(Length::Fill)
+                    .style(ui_style::secondary_button_style)
+                    .on_press(Message::Terminal(TerminalMessage::SendKey(bytes))),
+            )
+        })
+        .into()
+}
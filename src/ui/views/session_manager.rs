@@ -1,12 +1,29 @@
 use crate::session::SessionConfig;
-use crate::ui::Message;
+use crate::session::template::SessionTemplate;
+use crate::session::usage::SessionUsage;
 use crate::ui::components;
+use crate::ui::state::{HostProbe, SessionColumn, SessionSort};
 use crate::ui::style as ui_style;
-use iced::widget::{button, column, container, row, scrollable, text, text_input};
+use crate::ui::{Message, SessionMessage};
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, text_input};
 use iced::{Alignment, Element, Length};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// The dense list shown instead of cards; None renders the card grid.
+pub struct SessionTable<'a> {
+    pub sort: SessionSort,
+    pub selected: &'a HashSet<String>,
+    /// Current input latency by session id, for sessions open in a tab.
+    pub latency: HashMap<String, Duration>,
+    pub delete_armed: bool,
+}
 
 pub fn render<'a>(
     saved_sessions: &'a [SessionConfig],
+    templates: &'a [SessionTemplate],
+    template_host: &'a str,
     search_query: &'a str,
     editing_session: Option<&'a SessionConfig>,
     form_name: &'a str,
@@ -17,6 +34,11 @@ pub fn render<'a>(
     auth_method_password: bool,
     validation_error: Option<&'a String>,
     open_menu_id: Option<&'a str>,
+    table: Option<SessionTable<'a>>,
+    probes: &'a HashMap<String, HostProbe>,
+    usage: &'a HashMap<String, SessionUsage>,
+    usage_open: Option<&'a str>,
+    read_only: bool,
 ) -> Element<'a, Message> {
     // Suppress unused parameter warnings - these are used by the dialog at app level
     let _ = (
@@ -32,27 +54,82 @@ pub fn render<'a>(
     );
 
     let search_input = text_input("Search sessions...", search_query)
-        .on_input(Message::SessionSearchChanged)
+        .on_input(|value| Message::Sessions(SessionMessage::SearchChanged(value)))
         .padding([8, 12])
         .size(14)
         .style(ui_style::search_input)
         .width(Length::Fill);
 
+    let layout_button = |label, is_table: bool| {
+        let active = table.is_some() == is_table;
+        button(text(label).size(12))
+            .padding([6, 12])
+            .style(if active {
+                ui_style::primary_button_style
+            } else {
+                ui_style::secondary_button_style
+            })
+            .on_press_maybe((!active).then_some(Message::SetSessionTable(is_table)))
+    };
+
     let title_bar = row![
         search_input,
         container("").width(Length::Fill),
+        row![layout_button("Cards", false), layout_button("Table", true)].spacing(2),
+    ]
+    .push(if read_only {
+        Element::from(text("Viewer mode").size(12).style(ui_style::muted_text))
+    } else {
         button(text("+ New").size(12))
             .padding([6, 14])
             .style(ui_style::new_tab_button)
-            .on_press(Message::CreateNewSession),
-    ]
+            .on_press(Message::Sessions(SessionMessage::CreateNew))
+            .into()
+    })
     .spacing(10)
     .align_y(Alignment::Center)
     .padding([10, 16]);
 
+    // One-click sessions: type a hostname, pick the template it belongs to.
+    let template_bar = (!templates.is_empty() && !read_only).then(|| {
+        let mut bar = row![
+            text("From template").size(12).style(ui_style::muted_text),
+            text_input("hostname", template_host)
+                .on_input(|value| Message::Sessions(SessionMessage::TemplateHostChanged(value)))
+                .padding([6, 10])
+                .size(13)
+                .style(ui_style::dialog_input)
+                .width(Length::Fixed(220.0)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+        for template in templates {
+            bar = bar.push(
+                row![
+                    button(text(template.name.as_str()).size(12))
+                        .padding([6, 12])
+                        .style(ui_style::secondary_button_style)
+                        .on_press_maybe((!template_host.trim().is_empty()).then(|| {
+                            Message::Sessions(SessionMessage::CreateFromTemplate(
+                                template.id.clone(),
+                            ))
+                        }),),
+                    button(text("✕").size(11))
+                        .padding([6, 6])
+                        .style(ui_style::secondary_button_style)
+                        .on_press(Message::Sessions(SessionMessage::DeleteTemplate(
+                            template.id.clone()
+                        ))),
+                ]
+                .spacing(2),
+            );
+        }
+        container(bar).padding([8, 16]).width(Length::Fill)
+    });
+
     // Session list (full width now, no side panel)
     let query = search_query.trim().to_lowercase();
-    let filtered: Vec<&SessionConfig> = if query.is_empty() {
+    let mut filtered: Vec<&SessionConfig> = if query.is_empty() {
         saved_sessions.iter().collect()
     } else {
         saved_sessions
@@ -86,6 +163,9 @@ pub fn render<'a>(
         ]
         .align_x(Alignment::Center)
         .into()
+    } else if let Some(table) = table {
+        sort_sessions(&mut filtered, table.sort, &table.latency);
+        table_view(filtered, table, read_only)
     } else {
         iced::widget::responsive(move |size| {
             let card_width = 320.0;
@@ -103,7 +183,12 @@ pub fn render<'a>(
                 let mut row = row![].spacing(spacing);
                 for session in chunk {
                     let menu_open = open_menu_id == Some(session.id.as_str());
-                    row = row.push(components::session_card::render(session, menu_open));
+                    let status = probes.get(&session.id).map(|probe| &probe.status);
+                    let usage = (usage_open == Some(session.id.as_str()))
+                        .then(|| usage.get(&session.id).cloned().unwrap_or_default());
+                    row = row.push(components::session_card::render(
+                        session, menu_open, status, usage, read_only,
+                    ));
                 }
                 content = content.push(row);
             }
@@ -121,6 +206,7 @@ pub fn render<'a>(
         container(title_bar)
             .width(Length::Fill)
             .style(ui_style::tab_bar),
+        template_bar,
         container(session_list)
             .width(Length::Fill)
             .height(Length::Fill),
@@ -128,6 +214,231 @@ pub fn render<'a>(
     .spacing(0);
 
     iced::widget::mouse_area(content)
-        .on_press(Message::CloseSessionMenu)
+        .on_press(Message::Sessions(SessionMessage::CloseMenu))
         .into()
 }
+
+const CHECK_WIDTH: f32 = 24.0;
+const ACTIONS_WIDTH: f32 = 130.0;
+
+fn column_width(column: SessionColumn) -> Length {
+    match column {
+        SessionColumn::Name | SessionColumn::Host => Length::FillPortion(3),
+        SessionColumn::User | SessionColumn::Tags => Length::FillPortion(2),
+        SessionColumn::LastConnected => Length::Fixed(130.0),
+        SessionColumn::Latency => Length::Fixed(70.0),
+    }
+}
+
+fn table_view<'a>(
+    sessions: Vec<&'a SessionConfig>,
+    table: SessionTable<'a>,
+    read_only: bool,
+) -> Element<'a, Message> {
+    let ids: Vec<String> = sessions.iter().map(|session| session.id.clone()).collect();
+    let all_selected = ids.iter().all(|id| table.selected.contains(id));
+
+    let mut header_row = row![
+        container(
+            checkbox(all_selected)
+                .on_toggle(move |selected| Message::SelectSessions(ids.clone(), selected))
+                .size(14)
+        )
+        .width(Length::Fixed(CHECK_WIDTH)),
+    ]
+    .spacing(12)
+    .align_y(Alignment::Center);
+    for column in SessionColumn::ALL {
+        let arrow = match table.sort {
+            SessionSort {
+                column: sorted,
+                descending,
+            } if sorted == column => {
+                if descending {
+                    " ▼"
+                } else {
+                    " ▲"
+                }
+            }
+            _ => "",
+        };
+        header_row = header_row.push(
+            button(
+                text(format!("{}{}", column.label(), arrow))
+                    .size(13)
+                    .style(ui_style::muted_text),
+            )
+            .padding([2, 0])
+            .style(ui_style::icon_button)
+            .width(column_width(column))
+            .on_press(Message::SortSessions(column)),
+        );
+    }
+    header_row = header_row.push(container("").width(Length::Fixed(ACTIONS_WIDTH)));
+
+    let header = container(header_row)
+        .padding([6, 10])
+        .style(ui_style::table_header);
+
+    let mut rows = column![].spacing(0);
+    for (index, session) in sessions.iter().enumerate() {
+        let selected = table.selected.contains(&session.id);
+        let latency = table.latency.get(&session.id).map_or_else(
+            || "-".to_string(),
+            |latency| format!("{} ms", latency.as_millis()),
+        );
+        let last_connected = session.last_connected.map_or_else(
+            || "Never".to_string(),
+            |dt| {
+                dt.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            },
+        );
+        let id = session.id.clone();
+        let edit =
+            (!read_only).then(|| Message::Sessions(SessionMessage::Edit(session.id.clone())));
+        let cells = row![
+            container(
+                checkbox(selected)
+                    .on_toggle(move |_| Message::ToggleSessionSelected(id.clone()))
+                    .size(14)
+            )
+            .width(Length::Fixed(CHECK_WIDTH)),
+            text(session.name.as_str())
+                .size(13)
+                .width(column_width(SessionColumn::Name)),
+            text(format!("{}:{}", session.host, session.port))
+                .size(13)
+                .width(column_width(SessionColumn::Host)),
+            text(session.username.as_str())
+                .size(13)
+                .width(column_width(SessionColumn::User)),
+            text(session.tags.join(", "))
+                .size(12)
+                .style(ui_style::muted_text)
+                .width(column_width(SessionColumn::Tags)),
+            text(last_connected)
+                .size(12)
+                .style(ui_style::muted_text)
+                .width(column_width(SessionColumn::LastConnected)),
+            text(latency)
+                .size(12)
+                .style(ui_style::muted_text)
+                .width(column_width(SessionColumn::Latency)),
+            row![
+                button(text("Connect").size(12))
+                    .padding([4, 10])
+                    .style(ui_style::primary_button_style)
+                    .on_press(Message::Sessions(SessionMessage::Connect(
+                        session.id.clone()
+                    ))),
+                button(text("Edit").size(12))
+                    .padding([4, 10])
+                    .style(ui_style::secondary_button_style)
+                    .on_press_maybe(edit),
+            ]
+            .spacing(6)
+            .width(Length::Fixed(ACTIONS_WIDTH)),
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center);
+        rows = rows.push(
+            container(cells)
+                .padding([6, 10])
+                .style(ui_style::sftp_row_container(selected, false)),
+        );
+        if index + 1 < sessions.len() {
+            rows = rows.push(
+                container("")
+                    .height(1.0)
+                    .width(Length::Fill)
+                    .style(ui_style::divider),
+            );
+        }
+    }
+
+    let selection_bar = (!table.selected.is_empty()).then(|| {
+        let delete_label = if table.delete_armed {
+            format!("Delete {} sessions?", table.selected.len())
+        } else {
+            "Delete".to_string()
+        };
+        container(
+            row![
+                text(format!("{} selected", table.selected.len()))
+                    .size(12)
+                    .style(ui_style::muted_text),
+                button(text("Connect").size(12))
+                    .padding([6, 12])
+                    .style(ui_style::primary_button_style)
+                    .on_press(Message::ConnectSelectedSessions),
+                button(text(delete_label).size(12))
+                    .padding([6, 12])
+                    .style(ui_style::destructive_button_style)
+                    .on_press_maybe((!read_only).then_some(Message::DeleteSelectedSessions)),
+                button(text("Clear").size(12))
+                    .padding([6, 12])
+                    .style(ui_style::secondary_button_style)
+                    .on_press(Message::SelectSessions(
+                        table.selected.iter().cloned().collect(),
+                        false,
+                    )),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        )
+        .padding([0, 12])
+    });
+
+    column![
+        selection_bar,
+        container(header).padding([0, 12]),
+        scrollable(container(rows).padding([0, 12]))
+            .direction(ui_style::thin_scrollbar())
+            .style(ui_style::scrollable_style)
+            .height(Length::Fill),
+    ]
+    .spacing(8)
+    .padding([12, 0])
+    .into()
+}
+
+/// Sessions without a date or latency sort last in either direction.
+fn sort_sessions(
+    sessions: &mut [&SessionConfig],
+    sort: SessionSort,
+    latency: &HashMap<String, Duration>,
+) {
+    fn text_key(a: &str, b: &str) -> Ordering {
+        a.to_lowercase().cmp(&b.to_lowercase())
+    }
+    fn missing_last<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+    sessions.sort_by(|a, b| {
+        let order = match sort.column {
+            SessionColumn::Name => text_key(&a.name, &b.name),
+            SessionColumn::Host => text_key(&a.host, &b.host).then(a.port.cmp(&b.port)),
+            SessionColumn::User => text_key(&a.username, &b.username),
+            SessionColumn::Tags => text_key(&a.tags.join(","), &b.tags.join(",")),
+            SessionColumn::LastConnected => {
+                return missing_last(a.last_connected, b.last_connected, sort.descending);
+            }
+            SessionColumn::Latency => {
+                return missing_last(latency.get(&a.id), latency.get(&b.id), sort.descending);
+            }
+        };
+        if sort.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+}
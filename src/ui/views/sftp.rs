@@ -1,16 +1,44 @@
 use iced::widget::text::Wrapping;
 use iced::widget::{
-    Id, button, column, container, progress_bar, row, scrollable, svg, text, text_input, tooltip,
+    Id, Row, Space, button, checkbox, column, container, progress_bar, row, scrollable, svg, text,
+    text_input, tooltip,
 };
 use iced::{Alignment, Element, Length, Padding};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::ui::Message;
+use crate::session::config::{ConflictAction, TransferDefaults, TransferLimits};
+use crate::session::filter::FilterPreset;
+use crate::session::schedule::{JobDirection, TransferJob};
 use crate::ui::state::{
-    SftpContextAction, SftpContextMenu, SftpEntry, SftpPane, SftpTransfer, SftpTransferDirection,
-    SftpTransferStatus,
+    DirSize, JobRun, SftpContextAction, SftpContextMenu, SftpEntry, SftpPane, SftpTransfer,
+    SftpTransferDirection, SftpTransferStatus, TransferPanelMode,
 };
 use crate::ui::style as ui_style;
+use crate::ui::virtual_list::ListViewport;
+use crate::ui::{Message, SftpMessage};
+
+const ROW_HEIGHT: f32 = 24.0;
+// Rows have a fixed height so the visible ones can be found from the scroll
+// offset alone; the stride includes the gap below each row.
+const ROW_STRIDE: f32 = 26.0;
+// Past runs listed under a scheduled job.
+const JOB_HISTORY_SHOWN: usize = 5;
+
+/// The scrollable holding a pane's files.
+pub fn list_id(pane: SftpPane) -> Id {
+    match pane {
+        SftpPane::Local => Id::new("sftp-local-list"),
+        SftpPane::Remote => Id::new("sftp-remote-list"),
+    }
+}
+
+/// A scheduled transfer of the current session as shown in the queue.
+pub struct JobRow<'a> {
+    pub job: &'a TransferJob,
+    pub runs: &'a [JobRun],
+    pub running: bool,
+    pub next_run: Option<chrono::DateTime<chrono::Local>>,
+}
 
 pub fn render<'a>(
     local_path: &'a str,
@@ -20,6 +48,8 @@ pub fn render<'a>(
     remote_entries: &'a [SftpEntry],
     remote_error: Option<&'a str>,
     remote_loading: bool,
+    local_viewport: ListViewport,
+    remote_viewport: ListViewport,
     session_state: &'a crate::ui::state::SessionState,
     local_selected: Option<&'a str>,
     remote_selected: Option<&'a str>,
@@ -27,20 +57,30 @@ pub fn render<'a>(
     context_menu: Option<&'a SftpContextMenu>,
     panel_width: f32,
     panel_height: f32,
-    transfers: &'a [SftpTransfer],
     rename_input_id: &'a Id,
     rename_target: Option<&'a crate::ui::state::SftpPendingAction>,
     rename_value: &'a str,
     hovered_file: Option<&'a (SftpPane, String)>,
+    clipboard_pane: Option<SftpPane>,
+    read_only: bool,
+    filter_presets: &'a [FilterPreset],
+    local_filter: Option<&'a FilterPreset>,
+    remote_filter: Option<&'a FilterPreset>,
+    queue: Option<TransferQueue<'a>>,
 ) -> Element<'a, Message> {
+    let local_entries = filtered(local_entries, local_filter);
+    let remote_entries = filtered(remote_entries, remote_filter);
     let list_padding_left = 14;
     let list_padding_right = 6;
-    let local_scroll_id = Id::new("sftp-local-list");
-    let remote_scroll_id = Id::new("sftp-remote-list");
+    let local_scroll_id = list_id(SftpPane::Local);
+    let remote_scroll_id = list_id(SftpPane::Remote);
 
-    let local_breadcrumbs = breadcrumb_row(local_path, panel_width, Message::SftpLocalPathChanged);
-    let remote_breadcrumbs =
-        breadcrumb_row(remote_path, panel_width, Message::SftpRemotePathChanged);
+    let local_breadcrumbs = breadcrumb_row(local_path, panel_width, |value| {
+        Message::Sftp(SftpMessage::LocalPathChanged(value))
+    });
+    let remote_breadcrumbs = breadcrumb_row(remote_path, panel_width, |value| {
+        Message::Sftp(SftpMessage::RemotePathChanged(value))
+    });
 
     let local_list = if let Some(error) = local_error {
         scrollable(
@@ -69,12 +109,10 @@ pub fn render<'a>(
         .style(ui_style::scrollable_style)
         .height(Length::Fill)
     } else {
-        let mut rows = column![];
-        for entry in local_entries {
-            let size = entry
-                .size
-                .map(format_size)
-                .unwrap_or_else(|| "-".to_string());
+        let visible = local_viewport.visible_rows(local_entries.len(), ROW_STRIDE, panel_height);
+        let mut rows = column![Space::new().height(visible.start as f32 * ROW_STRIDE)];
+        for entry in &local_entries[visible.clone()] {
+            let size = entry_size(entry);
             let modified = entry
                 .modified
                 .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
@@ -85,12 +123,16 @@ pub fn render<'a>(
                 .unwrap_or(false);
             rows = rows.push(file_row(
                 entry.name.clone(),
+                entry.link_target.as_deref(),
                 size,
                 modified,
                 entry.is_dir,
                 selected,
                 hovered,
-                Message::SftpFileDragStart(SftpPane::Local, entry.name.clone()),
+                Message::Sftp(SftpMessage::FileDragStart(
+                    SftpPane::Local,
+                    entry.name.clone(),
+                )),
                 name_column_width,
                 SftpPane::Local,
                 context_menu,
@@ -99,18 +141,26 @@ pub fn render<'a>(
                 rename_value,
             ));
         }
+        rows =
+            rows.push(Space::new().height((local_entries.len() - visible.end) as f32 * ROW_STRIDE));
 
-        scrollable(rows.spacing(2))
+        scrollable(rows)
             .id(local_scroll_id.clone())
+            .on_scroll(|viewport| {
+                Message::Sftp(SftpMessage::ListScrolled(SftpPane::Local, viewport))
+            })
             .direction(ui_style::thin_scrollbar())
             .style(ui_style::scrollable_style)
             .height(Length::Fill)
     };
     let local_list: Element<'_, Message> = iced::widget::mouse_area(local_list)
-        .on_right_press(Message::SftpOpenContextMenu(SftpPane::Local, String::new()))
+        .on_right_press(Message::Sftp(SftpMessage::OpenContextMenu(
+            SftpPane::Local,
+            String::new(),
+        )))
         .into();
 
-    let remote_list = if remote_loading {
+    let remote_list = if remote_loading && remote_entries.is_empty() {
         scrollable(
             container(column![text("Loading...").size(12).style(ui_style::muted_text),].spacing(6))
                 .padding(pad_trbl(0, list_padding_right, 0, list_padding_left)),
@@ -146,12 +196,10 @@ pub fn render<'a>(
         .style(ui_style::scrollable_style)
         .height(Length::Fill)
     } else {
-        let mut rows = column![];
-        for entry in remote_entries {
-            let size = entry
-                .size
-                .map(format_size)
-                .unwrap_or_else(|| "-".to_string());
+        let visible = remote_viewport.visible_rows(remote_entries.len(), ROW_STRIDE, panel_height);
+        let mut rows = column![Space::new().height(visible.start as f32 * ROW_STRIDE)];
+        for entry in &remote_entries[visible.clone()] {
+            let size = entry_size(entry);
             let modified = entry
                 .modified
                 .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
@@ -162,12 +210,16 @@ pub fn render<'a>(
                 .unwrap_or(false);
             rows = rows.push(file_row(
                 entry.name.clone(),
+                entry.link_target.as_deref(),
                 size,
                 modified,
                 entry.is_dir,
                 selected,
                 hovered,
-                Message::SftpFileDragStart(SftpPane::Remote, entry.name.clone()),
+                Message::Sftp(SftpMessage::FileDragStart(
+                    SftpPane::Remote,
+                    entry.name.clone(),
+                )),
                 name_column_width,
                 SftpPane::Remote,
                 context_menu,
@@ -176,17 +228,22 @@ pub fn render<'a>(
                 rename_value,
             ));
         }
-        scrollable(rows.spacing(2))
+        rows = rows
+            .push(Space::new().height((remote_entries.len() - visible.end) as f32 * ROW_STRIDE));
+        scrollable(rows)
             .id(remote_scroll_id.clone())
+            .on_scroll(|viewport| {
+                Message::Sftp(SftpMessage::ListScrolled(SftpPane::Remote, viewport))
+            })
             .direction(ui_style::thin_scrollbar())
             .style(ui_style::scrollable_style)
             .height(Length::Fill)
     };
     let remote_list: Element<'_, Message> = iced::widget::mouse_area(remote_list)
-        .on_right_press(Message::SftpOpenContextMenu(
+        .on_right_press(Message::Sftp(SftpMessage::OpenContextMenu(
             SftpPane::Remote,
             String::new(),
-        ))
+        )))
         .into();
 
     let make_list_header = || {
@@ -234,6 +291,7 @@ pub fn render<'a>(
         row![
             text("Local").size(14).style(ui_style::header_text),
             container("").width(Length::Fill),
+            filter_tabs(SftpPane::Local, filter_presets, local_filter),
         ]
         .align_y(Alignment::Center),
         local_breadcrumbs,
@@ -271,6 +329,7 @@ pub fn render<'a>(
         row![
             text("Remote").size(14).style(ui_style::header_text),
             container("").width(Length::Fill),
+            filter_tabs(SftpPane::Remote, filter_presets, remote_filter),
         ]
         .align_y(Alignment::Center),
         remote_breadcrumbs,
@@ -288,47 +347,7 @@ pub fn render<'a>(
         .spacing(12)
         .height(Length::Fill);
 
-    let queue_content_width = (panel_width - 24.0).max(200.0);
-    let transfer_name_width = (queue_content_width * (3.6 / 11.0)).max(140.0);
-
-    let mut queue_rows = column![];
-    for transfer in transfers.iter().rev().take(6) {
-        let (status, progress) = transfer_status(transfer);
-        queue_rows = queue_rows.push(transfer_row(
-            transfer,
-            status,
-            progress,
-            transfer_name_width,
-        ));
-    }
-    if transfers.is_empty() {
-        queue_rows = queue_rows.push(text("No transfers").size(12).style(ui_style::muted_text));
-    }
-    let queue_rows = queue_rows.spacing(8);
-
-    let queue = column![
-        row![
-            text("Transfers").size(12).style(ui_style::muted_text),
-            container("").width(Length::Fill),
-            button(text("Clear").size(12))
-                .padding([2, 6])
-                .style(ui_style::icon_button)
-                .on_press(Message::SftpTransferClearDone),
-        ]
-        .align_y(Alignment::Center),
-        container(
-            scrollable(queue_rows)
-                .direction(ui_style::thin_scrollbar())
-                .style(ui_style::scrollable_style)
-                .height(Length::Fill),
-        )
-        .padding([8, 0])
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .style(ui_style::panel),
-    ]
-    .spacing(8)
-    .height(Length::Fixed(180.0));
+    let queue = queue.map(|queue| transfer_queue(queue, panel_width, false));
 
     let base = column![
         row![
@@ -355,12 +374,12 @@ pub fn render<'a>(
     .height(Length::Fill);
 
     let base: Element<'_, Message> = iced::widget::mouse_area(base)
-        .on_press(Message::SftpCloseContextMenu)
+        .on_press(Message::Sftp(SftpMessage::CloseContextMenu))
         .into();
 
     let overlay: Element<'_, Message> = if let Some(menu) = context_menu {
         let menu_width = 160.0;
-        let menu_height = 150.0;
+        let menu_height = 430.0;
         let padding = 8.0;
         let max_x = (panel_width - menu_width - padding).max(padding);
         let max_y = (panel_height - menu_height - padding).max(padding);
@@ -368,18 +387,74 @@ pub fn render<'a>(
         let y = menu.position.y.clamp(padding, max_y);
 
         let has_target = !menu.name.is_empty();
+        let can_delete = has_target && !read_only;
+        let can_paste = clipboard_pane == Some(menu.pane);
+        // Tail and scheduled transfers work on single files only.
+        let can_tail = has_target
+            && !remote_entries
+                .iter()
+                .any(|entry| entry.is_dir && entry.name == menu.name);
+        let can_schedule_local = has_target
+            && !local_entries
+                .iter()
+                .any(|entry| entry.is_dir && entry.name == menu.name);
+        let can_size = match menu.pane {
+            SftpPane::Local => &local_entries,
+            SftpPane::Remote => &remote_entries,
+        }
+        .iter()
+        .any(|entry| entry.is_dir && entry.name == menu.name);
         let actions = match menu.pane {
             SftpPane::Local => vec![
                 ("Refresh", SftpContextAction::Refresh, false, true),
                 ("Upload", SftpContextAction::Upload, false, has_target),
+                (
+                    "Schedule",
+                    SftpContextAction::Schedule,
+                    false,
+                    can_schedule_local,
+                ),
+                ("Cut", SftpContextAction::Cut, false, has_target),
+                ("Copy", SftpContextAction::Copy, false, has_target),
+                ("Paste", SftpContextAction::Paste, false, can_paste),
                 ("Rename", SftpContextAction::Rename, false, has_target),
-                ("Delete", SftpContextAction::Delete, true, has_target),
+                (
+                    "Calculate Size",
+                    SftpContextAction::CalculateSize,
+                    false,
+                    can_size,
+                ),
+                (
+                    "Open Containing Folder",
+                    SftpContextAction::RevealLocal,
+                    false,
+                    has_target,
+                ),
+                ("Delete", SftpContextAction::Delete, true, can_delete),
             ],
             SftpPane::Remote => vec![
                 ("Refresh", SftpContextAction::Refresh, false, true),
                 ("Download", SftpContextAction::Download, false, has_target),
+                (
+                    "Download to…",
+                    SftpContextAction::DownloadTo,
+                    false,
+                    can_tail,
+                ),
+                ("Upload from…", SftpContextAction::UploadFrom, false, true),
+                ("Schedule", SftpContextAction::Schedule, false, can_tail),
+                ("Tail", SftpContextAction::Tail, false, can_tail),
+                ("Cut", SftpContextAction::Cut, false, has_target),
+                ("Copy", SftpContextAction::Copy, false, has_target),
+                ("Paste", SftpContextAction::Paste, false, can_paste),
                 ("Rename", SftpContextAction::Rename, false, has_target),
-                ("Delete", SftpContextAction::Delete, true, has_target),
+                (
+                    "Calculate Size",
+                    SftpContextAction::CalculateSize,
+                    false,
+                    can_size,
+                ),
+                ("Delete", SftpContextAction::Delete, true, can_delete),
             ],
         };
 
@@ -397,11 +472,11 @@ pub fn render<'a>(
                 .style(button_style)
                 .width(Length::Fill);
             if enabled {
-                menu_button = menu_button.on_press(Message::SftpContextAction(
+                menu_button = menu_button.on_press(Message::Sftp(SftpMessage::ContextAction(
                     menu.pane,
                     menu.name.clone(),
                     action,
-                ));
+                )));
             }
             menu_column = menu_column.push(menu_button);
         }
@@ -438,12 +513,199 @@ pub fn render<'a>(
     iced::widget::stack![base, overlay].into()
 }
 
-pub fn delete_dialog<'a>(name: &'a str, is_dir: bool) -> Element<'a, Message> {
-    let title = text("Delete").size(16).style(ui_style::header_text);
-    let message = if is_dir {
-        format!("Delete folder \"{}\"?", name)
+/// The transfer queue of the current session, with its scheduled jobs.
+pub struct TransferQueue<'a> {
+    pub transfers: &'a [SftpTransfer],
+    pub running: (usize, usize),
+    pub limits: TransferLimits,
+    pub defaults: Option<TransferDefaults>,
+    pub show_jobs: bool,
+    pub jobs: Vec<JobRow<'a>>,
+}
+
+/// The queue below the file panes, or filling its own window when
+/// `detached`.
+pub fn transfer_queue<'a>(
+    queue: TransferQueue<'a>,
+    width: f32,
+    detached: bool,
+) -> Element<'a, Message> {
+    let TransferQueue {
+        transfers,
+        running: running_transfers,
+        limits: transfer_limits,
+        defaults: transfer_defaults,
+        show_jobs,
+        jobs,
+    } = queue;
+    let queue_content_width = (width - 24.0).max(200.0);
+    let transfer_name_width = (queue_content_width * (3.6 / 11.0)).max(140.0);
+
+    let mut queue_rows = column![];
+    // Docked, only the latest transfers fit; the window shows the whole queue.
+    let shown = if detached { transfers.len() } else { 6 };
+    for transfer in transfers.iter().rev().take(shown) {
+        let (status, progress) = transfer_status(transfer);
+        queue_rows = queue_rows.push(transfer_row(
+            transfer,
+            status,
+            progress,
+            transfer_name_width,
+        ));
+    }
+    if transfers.is_empty() {
+        queue_rows = queue_rows.push(text("No transfers").size(12).style(ui_style::muted_text));
+    }
+    let queue_rows = queue_rows.spacing(8);
+
+    let (running_uploads, running_downloads) = running_transfers;
+    let slots = text(format!(
+        "↑ {}/{}  ↓ {}/{}  · {}/{} slots",
+        running_uploads,
+        transfer_limits.max_uploads.max(1),
+        running_downloads,
+        transfer_limits.max_downloads.max(1),
+        running_uploads + running_downloads,
+        transfer_limits.max_concurrent.max(1),
+    ))
+    .size(11)
+    .style(ui_style::muted_text);
+
+    let queue_tabs = row![
+        button(text("Transfers").size(11))
+            .padding([3, 8])
+            .style(ui_style::compact_tab(!show_jobs))
+            .on_press(Message::Sftp(SftpMessage::ShowJobs(false))),
+        button(text(format!("Scheduled ({})", jobs.len())).size(11))
+            .padding([3, 8])
+            .style(ui_style::compact_tab(show_jobs))
+            .on_press(Message::Sftp(SftpMessage::ShowJobs(true))),
+    ]
+    .spacing(2);
+    let queue_header = if show_jobs {
+        row![
+            queue_tabs,
+            container("").width(Length::Fill),
+            text("Runs while Rivett is open")
+                .size(11)
+                .style(ui_style::muted_text),
+        ]
+    } else {
+        row![
+            queue_tabs,
+            container("").width(Length::Fill),
+            slots,
+            button(text("Clear").size(12))
+                .padding([2, 6])
+                .style(ui_style::icon_button)
+                .on_press(Message::Sftp(SftpMessage::TransferClearDone)),
+        ]
+    };
+    let mode_buttons = if detached {
+        row![panel_mode_button("Dock", TransferPanelMode::Docked)]
+    } else {
+        row![
+            panel_mode_button("Collapse", TransferPanelMode::Compact),
+            panel_mode_button("Pop out", TransferPanelMode::Detached),
+        ]
+    };
+    let queue_header = queue_header.push(mode_buttons.spacing(2));
+    let show_options = transfer_defaults.is_some() && !show_jobs;
+    let queue_rows = if show_jobs {
+        job_rows(jobs, transfer_name_width)
+    } else {
+        queue_rows
+    };
+
+    let queue = column![
+        queue_header.spacing(8).align_y(Alignment::Center),
+        transfer_defaults
+            .filter(|_| show_options)
+            .map(transfer_options),
+        container(
+            scrollable(queue_rows)
+                .direction(ui_style::thin_scrollbar())
+                .style(ui_style::scrollable_style)
+                .height(Length::Fill),
+        )
+        .padding([8, 0])
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(ui_style::panel),
+    ]
+    .spacing(8)
+    .height(if detached {
+        Length::Fill
+    } else {
+        Length::Fixed(if show_options { 212.0 } else { 180.0 })
+    });
+
+    queue.into()
+}
+
+fn panel_mode_button<'a>(label: &'a str, mode: TransferPanelMode) -> Element<'a, Message> {
+    button(text(label).size(12))
+        .padding([2, 6])
+        .style(ui_style::icon_button)
+        .on_press(Message::SetTransferPanelMode(mode))
+        .into()
+}
+
+/// Stands in for the queue in the status bar while it is collapsed: overall
+/// progress of the running transfers, and a click brings the queue back.
+pub fn transfer_pill<'a>(transfers: &[SftpTransfer]) -> Element<'a, Message> {
+    let active: Vec<_> = transfers
+        .iter()
+        .filter(|transfer| {
+            matches!(
+                transfer.status,
+                SftpTransferStatus::Queued
+                    | SftpTransferStatus::Uploading
+                    | SftpTransferStatus::Paused
+            )
+        })
+        .collect();
+    let sent: u64 = active.iter().map(|transfer| transfer.bytes_sent).sum();
+    let total: u64 = active.iter().map(|transfer| transfer.bytes_total).sum();
+    let content: Element<'a, Message> = if active.is_empty() {
+        text("Transfers").size(12).into()
     } else {
-        format!("Delete file \"{}\"?", name)
+        let progress = if total > 0 {
+            (sent as f32 / total as f32).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        row![
+            text(format!("⇅ {}", active.len())).size(12),
+            container(progress_bar(0.0..=1.0, progress))
+                .height(Length::Fixed(4.0))
+                .width(Length::Fixed(60.0)),
+            text(format!("{:.0}%", progress * 100.0))
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center)
+        .into()
+    };
+    button(content)
+        .padding([4, 10])
+        .style(ui_style::menu_button(false))
+        .on_press(Message::SetTransferPanelMode(TransferPanelMode::Docked))
+        .into()
+}
+
+/// `trash_dir` is set when the remote item will be moved there instead.
+pub fn delete_dialog<'a>(
+    name: &'a str,
+    is_dir: bool,
+    trash_dir: Option<&str>,
+) -> Element<'a, Message> {
+    let title = text("Delete").size(16).style(ui_style::header_text);
+    let kind = if is_dir { "folder" } else { "file" };
+    let message = match trash_dir {
+        Some(dir) => format!("Move {} \"{}\" to {}?", kind, name, dir),
+        None => format!("Delete {} \"{}\"?", kind, name),
     };
 
     let hint = text(message).size(13).style(ui_style::muted_text);
@@ -453,11 +715,18 @@ pub fn delete_dialog<'a>(name: &'a str, is_dir: bool) -> Element<'a, Message> {
         button(text("Cancel").size(12))
             .padding([6, 12])
             .style(ui_style::secondary_button_style)
-            .on_press(Message::SftpDeleteCancel),
-        button(text("Delete").size(12))
-            .padding([6, 12])
-            .style(ui_style::destructive_button_style)
-            .on_press(Message::SftpDeleteConfirm),
+            .on_press(Message::Sftp(SftpMessage::DeleteCancel)),
+        button(
+            text(if trash_dir.is_some() {
+                "Move to Trash"
+            } else {
+                "Delete"
+            })
+            .size(12)
+        )
+        .padding([6, 12])
+        .style(ui_style::destructive_button_style)
+        .on_press(Message::Sftp(SftpMessage::DeleteConfirm)),
     ]
     .spacing(8)
     .align_y(Alignment::Center);
@@ -488,12 +757,12 @@ fn transfer_row(
             action_button(
                 "Pause",
                 icon_svg(PAUSE_SVG),
-                Message::SftpTransferPause(transfer.id),
+                Message::Sftp(SftpMessage::TransferPause(transfer.id)),
             ),
             action_button(
                 "Cancel",
                 icon_svg(CANCEL_SVG),
-                Message::SftpTransferCancel(transfer.id),
+                Message::Sftp(SftpMessage::TransferCancel(transfer.id)),
             ),
         ]
         .spacing(4)
@@ -502,12 +771,12 @@ fn transfer_row(
             action_button(
                 "Resume",
                 icon_svg(RESUME_SVG),
-                Message::SftpTransferResume(transfer.id),
+                Message::Sftp(SftpMessage::TransferResume(transfer.id)),
             ),
             action_button(
                 "Cancel",
                 icon_svg(CANCEL_SVG),
-                Message::SftpTransferCancel(transfer.id),
+                Message::Sftp(SftpMessage::TransferCancel(transfer.id)),
             ),
         ]
         .spacing(4)
@@ -515,12 +784,12 @@ fn transfer_row(
         SftpTransferStatus::Queued => action_button(
             "Cancel",
             icon_svg(CANCEL_SVG),
-            Message::SftpTransferCancel(transfer.id),
+            Message::Sftp(SftpMessage::TransferCancel(transfer.id)),
         ),
         SftpTransferStatus::Failed(_) | SftpTransferStatus::Canceled => action_button(
             "Retry",
             icon_svg(RETRY_SVG),
-            Message::SftpTransferRetry(transfer.id),
+            Message::Sftp(SftpMessage::TransferRetry(transfer.id)),
         ),
         _ => container("").into(),
     };
@@ -534,7 +803,7 @@ fn transfer_row(
         SftpTransferStatus::Paused => icon_svg(PAUSED_SVG),
         SftpTransferStatus::Completed => icon_svg(CHECK_SVG),
         SftpTransferStatus::Failed(_) => icon_svg(ERROR_SVG),
-        SftpTransferStatus::Canceled => icon_svg(CANCEL_STATUS_SVG),
+        SftpTransferStatus::Canceled | SftpTransferStatus::Skipped => icon_svg(CANCEL_STATUS_SVG),
     };
 
     container(
@@ -565,6 +834,179 @@ fn transfer_row(
     .into()
 }
 
+fn job_rows<'a>(jobs: Vec<JobRow<'a>>, name_width: f32) -> iced::widget::Column<'a, Message> {
+    if jobs.is_empty() {
+        return column![
+            text("Right-click a file and choose Schedule to copy it regularly")
+                .size(12)
+                .style(ui_style::muted_text)
+        ]
+        .padding(pad_trbl(0, 8, 0, 8));
+    }
+    let rows = jobs.into_iter().map(|row| {
+        let job = row.job;
+        let arrow = match job.direction {
+            JobDirection::Download => "↓",
+            JobDirection::Upload => "↑",
+        };
+        let status = if row.running {
+            "Running…".to_string()
+        } else {
+            match row.next_run {
+                Some(next) => format!("Next {}", next.format("%b %-d %H:%M")),
+                None => "Use e.g. every 30m or daily 02:30".to_string(),
+            }
+        };
+        let history = row
+            .runs
+            .iter()
+            .rev()
+            .take(JOB_HISTORY_SHOWN)
+            .map(|run| match &run.result {
+                Ok(bytes) => format!("✓ {} {}", run.finished.format("%H:%M"), format_size(*bytes)),
+                Err(error) => format!("✕ {} {}", run.finished.format("%H:%M"), error),
+            })
+            .collect::<Vec<_>>()
+            .join("  ·  ");
+        let direction = match job.direction {
+            JobDirection::Download => format!("{} → {}", job.remote_path, job.local_path),
+            JobDirection::Upload => format!("{} → {}", job.local_path, job.remote_path),
+        };
+        let details = if history.is_empty() {
+            direction
+        } else {
+            history
+        };
+        container(
+            column![
+                row![
+                    text(format!(
+                        "{} {}",
+                        arrow,
+                        truncate_name(job.name(), name_width, 13.0)
+                    ))
+                    .size(13)
+                    .wrapping(Wrapping::None)
+                    .width(Length::FillPortion(3)),
+                    text_input("every 1h", &job.schedule)
+                        .on_input(|value| Message::TransferJobScheduleChanged(
+                            job.id.clone(),
+                            value
+                        ))
+                        .padding([3, 6])
+                        .size(12)
+                        .style(ui_style::dialog_input)
+                        .width(Length::FillPortion(2)),
+                    text(status)
+                        .size(12)
+                        .style(ui_style::muted_text)
+                        .wrapping(Wrapping::None)
+                        .width(Length::FillPortion(3)),
+                    button(text("Run now").size(12))
+                        .padding([2, 6])
+                        .style(ui_style::icon_button)
+                        .on_press_maybe(
+                            (!row.running).then(|| Message::RunTransferJob(job.id.clone()))
+                        ),
+                    button(text("✕").size(12))
+                        .padding([2, 6])
+                        .style(ui_style::icon_button)
+                        .on_press(Message::DeleteTransferJob(job.id.clone())),
+                ]
+                .spacing(6)
+                .align_y(Alignment::Center),
+                text(details)
+                    .size(11)
+                    .style(ui_style::muted_text)
+                    .wrapping(Wrapping::None),
+            ]
+            .spacing(2),
+        )
+        .padding(pad_trbl(0, 8, 0, 8))
+        .into()
+    });
+    iced::widget::Column::with_children(rows).spacing(8)
+}
+
+// Hidden entries are left out before the virtual list sees them, so the
+// viewport math only counts rows that are shown.
+fn filtered<'a>(entries: &'a [SftpEntry], filter: Option<&FilterPreset>) -> Vec<&'a SftpEntry> {
+    entries
+        .iter()
+        .filter(|entry| filter.is_none_or(|filter| !filter.ignores(&entry.name, entry.is_dir)))
+        .collect()
+}
+
+fn filter_tabs<'a>(
+    pane: SftpPane,
+    presets: &'a [FilterPreset],
+    selected: Option<&'a FilterPreset>,
+) -> Row<'a, Message> {
+    if presets.is_empty() {
+        return Row::new();
+    }
+    let tab = |label: &'a str, preset: Option<&'a FilterPreset>| {
+        button(text(label).size(11))
+            .padding([3, 8])
+            .style(ui_style::compact_tab(selected == preset))
+            .on_press(Message::Sftp(SftpMessage::SetFilter(
+                pane,
+                preset.map(|preset| preset.name.clone()),
+            )))
+    };
+    presets.iter().fold(
+        Row::new().spacing(2).push(tab("All", None)),
+        |row, preset| row.push(tab(&preset.name, Some(preset))),
+    )
+}
+
+// Remembered for the session, so they apply to every later transfer.
+fn transfer_options<'a>(defaults: TransferDefaults) -> Element<'a, Message> {
+    let conflict = ConflictAction::ALL
+        .into_iter()
+        .fold(Row::new().spacing(2), |row, action| {
+            row.push(
+                button(text(action.label()).size(11))
+                    .padding([3, 8])
+                    .style(ui_style::compact_tab(action == defaults.conflict))
+                    .on_press(Message::Sftp(SftpMessage::TransferDefaultsChanged(
+                        TransferDefaults {
+                            conflict: action,
+                            ..defaults
+                        },
+                    ))),
+            )
+        });
+    row![
+        checkbox(defaults.preserve_permissions)
+            .label("Keep permissions")
+            .on_toggle(move |preserve_permissions| {
+                Message::Sftp(SftpMessage::TransferDefaultsChanged(TransferDefaults {
+                    preserve_permissions,
+                    ..defaults
+                }))
+            })
+            .size(14)
+            .text_size(12),
+        checkbox(defaults.resume)
+            .label("Resume partial files")
+            .on_toggle(move |resume| {
+                Message::Sftp(SftpMessage::TransferDefaultsChanged(TransferDefaults {
+                    resume,
+                    ..defaults
+                }))
+            })
+            .size(14)
+            .text_size(12),
+        container("").width(Length::Fill),
+        text("If it exists").size(11).style(ui_style::muted_text),
+        conflict,
+    ]
+    .spacing(12)
+    .align_y(Alignment::Center)
+    .into()
+}
+
 fn transfer_status(transfer: &SftpTransfer) -> (String, f32) {
     let total = transfer.bytes_total.max(1);
     let progress = (transfer.bytes_sent as f32 / total as f32).clamp(0.0, 1.0);
@@ -584,9 +1026,13 @@ fn transfer_status(transfer: &SftpTransfer) -> (String, f32) {
             }
         }
         SftpTransferStatus::Paused => format!("Paused · {}", rate),
-        SftpTransferStatus::Completed => format!("{} completed", direction),
+        SftpTransferStatus::Completed => match average_rate(transfer) {
+            Some(rate) => format!("{} completed · {}/s", direction, format_size(rate)),
+            None => format!("{} completed", direction),
+        },
         SftpTransferStatus::Failed(_) => format!("{} failed", direction),
         SftpTransferStatus::Canceled => format!("{} canceled", direction),
+        SftpTransferStatus::Skipped => "Skipped, file exists".to_string(),
     };
     (status, progress)
 }
@@ -618,6 +1064,14 @@ fn transfer_rate(transfer: &SftpTransfer) -> String {
     "--".to_string()
 }
 
+fn average_rate(transfer: &SftpTransfer) -> Option<u64> {
+    let elapsed = transfer
+        .last_update?
+        .duration_since(transfer.started_at?)
+        .as_secs_f64();
+    (elapsed > 0.0).then(|| (transfer.bytes_sent as f64 / elapsed) as u64)
+}
+
 fn pad_trbl(top: u16, right: u16, bottom: u16, left: u16) -> Padding {
     Padding {
         top: top.into(),
@@ -720,6 +1174,7 @@ const RESUME_SVG: &str = r###"<svg width="14" height="14" viewBox="0 0 24 24" fi
 
 fn file_row(
     name: String,
+    link_target: Option<&str>,
     size: String,
     modified: String,
     is_dir: bool,
@@ -738,11 +1193,14 @@ fn file_row(
         .map(|target| target.pane == pane && target.name == name)
         .unwrap_or(false);
 
-    let display_name = truncate_name(&name, name_column_width, 14.0);
+    let display_name = match link_target {
+        Some(target) => truncate_name(&format!("{} → {}", name, target), name_column_width, 14.0),
+        None => truncate_name(&name, name_column_width, 14.0),
+    };
     let name_cell: Element<'static, Message> = if is_renaming {
         text_input("New name", rename_value)
-            .on_input(Message::SftpRenameInput)
-            .on_submit(Message::SftpRenameConfirm)
+            .on_input(|value| Message::Sftp(SftpMessage::RenameInput(value)))
+            .on_submit(Message::Sftp(SftpMessage::RenameConfirm))
             .id(rename_input_id.clone())
             .padding([4, 8])
             .size(13)
@@ -776,12 +1234,19 @@ fn file_row(
     )
     .padding(pad_trbl(2, 6, 2, 10))
     .width(Length::Fill)
+    .center_y(Length::Fixed(ROW_HEIGHT))
     .style(ui_style::sftp_row_container(selected, hovered));
 
     let row_area = iced::widget::mouse_area(row_container)
-        .on_right_press(Message::SftpOpenContextMenu(pane, name.clone()))
-        .on_enter(Message::SftpFileHover(Some((pane, name.clone()))))
-        .on_exit(Message::SftpFileHover(None))
+        .on_right_press(Message::Sftp(SftpMessage::OpenContextMenu(
+            pane,
+            name.clone(),
+        )))
+        .on_enter(Message::Sftp(SftpMessage::FileHover(Some((
+            pane,
+            name.clone(),
+        )))))
+        .on_exit(Message::Sftp(SftpMessage::FileHover(None)))
         .on_press(if is_renaming {
             Message::Ignore
         } else {
@@ -789,7 +1254,7 @@ fn file_row(
         });
     // Start drag/select on MouseDown
 
-    row_area.into()
+    container(row_area).height(Length::Fixed(ROW_STRIDE)).into()
 }
 
 fn breadcrumb_row<'a>(
@@ -989,7 +1454,17 @@ fn breadcrumb_segments(path: &str) -> Vec<(String, String)> {
     }
 }
 
-fn format_size(bytes: u64) -> String {
+fn entry_size(entry: &SftpEntry) -> String {
+    match (entry.size, entry.dir_size) {
+        (Some(bytes), _) => format_size(bytes),
+        (None, Some(DirSize::Counting(bytes))) => format!("{}…", format_size(bytes)),
+        (None, Some(DirSize::Total(bytes))) => format_size(bytes),
+        (None, Some(DirSize::Failed)) => "?".to_string(),
+        (None, None) => "-".to_string(),
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = 1024 * 1024;
     const GB: u64 = 1024 * 1024 * 1024;
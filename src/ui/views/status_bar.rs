@@ -1,16 +1,43 @@
 use crate::ui::SessionTab;
+use crate::ui::latency::{InputLatency, LatencyLevel};
+use crate::ui::state::SessionState;
 use crate::ui::style as ui_style;
-use crate::ui::{ActiveView, Message};
+use crate::ui::views;
+use crate::ui::{ActiveView, Message, SessionMessage, TerminalMessage};
 use iced::widget::{button, container, row, text};
-use iced::{Alignment, Element, Length};
+use iced::{Alignment, Color, Element, Length};
+use std::time::{Duration, Instant};
 
-pub fn render<'a>(
-    tabs: &'a [SessionTab],
-    active_tab: usize,
-    active_view: ActiveView,
-    sftp_panel_open: bool,
-    port_forward_panel_open: bool,
-) -> Element<'a, Message> {
+// How long the throttled label stays up after output was last dropped.
+const THROTTLED_LABEL_DURATION: Duration = Duration::from_secs(5);
+
+/// What the status bar shows: the current tab and which of the panels it
+/// toggles are open.
+pub struct StatusBar<'a> {
+    pub tabs: &'a [SessionTab],
+    pub active_tab: usize,
+    pub active_view: ActiveView,
+    pub sftp_panel_open: bool,
+    pub port_forward_panel_open: bool,
+    pub send_key_menu_open: bool,
+    pub command_history_open: bool,
+    /// Jobs still running, or None when there are none to show.
+    pub running_jobs: Option<usize>,
+    pub jobs_panel_open: bool,
+}
+
+pub fn render(bar: StatusBar<'_>) -> Element<'_, Message> {
+    let StatusBar {
+        tabs,
+        active_tab,
+        active_view,
+        sftp_panel_open,
+        port_forward_panel_open,
+        send_key_menu_open,
+        command_history_open,
+        running_jobs,
+        jobs_panel_open,
+    } = bar;
     let current_tab = tabs.get(active_tab);
     let (status_left, connection_label, sftp_enabled, port_forward_id) =
         if let Some(tab) = current_tab {
@@ -69,18 +96,114 @@ pub fn render<'a>(
             .on_press(Message::Ignore)
     };
 
+    let debug_button = current_tab
+        .filter(|tab| active_view == ActiveView::Terminal && tab.debug_log.is_some())
+        .map(|_| {
+            button(text("Debug").size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(false))
+                .on_press(Message::Sessions(SessionMessage::ShowDebugLog(active_tab)))
+        });
+
+    let send_key_button = current_tab
+        .filter(|tab| active_view == ActiveView::Terminal && tab.session.is_some())
+        .map(|_| {
+            button(text("Keys").size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(send_key_menu_open))
+                .on_press(Message::Terminal(TerminalMessage::ToggleSendKeyMenu))
+        });
+
+    let history_button = current_tab
+        .filter(|tab| active_view == ActiveView::Terminal && tab.session.is_some())
+        .map(|_| {
+            button(text("History").size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(command_history_open))
+                .on_press(Message::Terminal(TerminalMessage::ToggleCommandHistory))
+        });
+
+    let redact_button = current_tab
+        .filter(|tab| active_view == ActiveView::Terminal && tab.session.is_some())
+        .map(|_| {
+            button(text("Redact").size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(crate::terminal::redact::is_enabled()))
+                .on_press(Message::Terminal(TerminalMessage::ToggleRedaction))
+        });
+
+    let bandwidth_button = current_tab
+        .filter(|tab| active_view == ActiveView::Terminal && tab.bandwidth.is_some())
+        .map(|tab| {
+            button(text(views::bandwidth::rate_label(&tab.bandwidth_history)).size(12))
+                .padding([4, 10])
+                .style(ui_style::menu_button(false))
+                .on_press(Message::Terminal(TerminalMessage::ToggleBandwidthPopover))
+        });
+
+    // Shown while there is anything running, or anything left to look at.
+    let jobs_button = running_jobs.map(|running| {
+        let label = match running {
+            0 => "Jobs".to_string(),
+            running => format!("Jobs ({})", running),
+        };
+        button(text(label).size(12))
+            .padding([4, 10])
+            .style(ui_style::menu_button(jobs_panel_open))
+            .on_press(Message::ToggleJobsPanel)
+    });
+
+    let latency = current_tab
+        .filter(|tab| {
+            active_view == ActiveView::Terminal
+                && tab.ssh_handle.is_some()
+                && tab.state == SessionState::Connected
+        })
+        .and_then(|tab| tab.input_latency.current(Instant::now()))
+        .map(|latency| {
+            let color = match InputLatency::level(latency) {
+                LatencyLevel::Good => Color::from_rgb8(52, 199, 89),
+                LatencyLevel::Slow => Color::from_rgb(0.9, 0.7, 0.1),
+                LatencyLevel::Laggy => Color::from_rgb(0.9, 0.3, 0.3),
+            };
+            row![
+                text("●").size(10).color(color),
+                text(format!("{} ms", latency.as_millis()))
+                    .size(12)
+                    .style(ui_style::muted_text),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center)
+        });
+
+    let throttled = current_tab
+        .filter(|_| active_view == ActiveView::Terminal)
+        .and_then(|tab| tab.output_throttled_at)
+        .filter(|at| at.elapsed() < THROTTLED_LABEL_DURATION)
+        .map(|_| {
+            text("Output throttled")
+                .size(12)
+                .color(Color::from_rgb(0.9, 0.6, 0.2))
+        });
+
     let status_bar = row![
         menu_button,
         text(status_left).size(12),
+        throttled,
         container("").width(Length::Fill),
         sftp_button,
         port_forward_button,
+        debug_button,
+        send_key_button,
+        history_button,
+        redact_button,
+        jobs_button,
+        latency,
         text(connection_label).size(12).style(ui_style::muted_text),
         text("UTF-8").size(12).style(ui_style::muted_text),
         text("│").size(12).style(ui_style::muted_text),
         text("24x120").size(12).style(ui_style::muted_text),
-        text("│").size(12).style(ui_style::muted_text),
-        text("↑ 3.2MB/s").size(12).style(ui_style::muted_text),
+        bandwidth_button,
     ]
     .align_y(Alignment::Center)
     .spacing(8);
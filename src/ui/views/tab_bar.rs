@@ -1,8 +1,10 @@
+use crate::session::expiry;
+use crate::session::profile::HostProfile;
 use crate::ui::Message;
 use crate::ui::SessionTab;
 use crate::ui::style as ui_style;
 use iced::widget::{Space, button, container, responsive, row, text};
-use iced::{Alignment, Element, Length};
+use iced::{Alignment, Color, Element, Length};
 
 fn truncate_title(title: &str, max_chars: usize) -> String {
     if max_chars <= 3 {
@@ -15,11 +17,18 @@ fn truncate_title(title: &str, max_chars: usize) -> String {
     format!("{}...", truncated)
 }
 
-pub fn render<'a>(tabs: &'a [SessionTab], active_tab: usize) -> Element<'a, Message> {
+pub fn render<'a>(
+    tabs: &'a [SessionTab],
+    active_tab: usize,
+    profiles: Vec<Option<&'a HostProfile>>,
+    access_expiry: Vec<Option<chrono::DateTime<chrono::Utc>>>,
+) -> Element<'a, Message> {
+    let now = chrono::Utc::now();
     let inner = responsive(move |size| {
         let spacing = 4.0;
         let padding = 24.0;
-        let plus_width = 44.0;
+        let show_overview = tabs.len() > 2;
+        let plus_width = if show_overview { 88.0 } else { 44.0 };
 
         let count = tabs.len().max(1) as f32;
         let available = (size.width - padding - plus_width).max(80.0);
@@ -45,8 +54,41 @@ pub fn render<'a>(tabs: &'a [SessionTab], active_tab: usize) -> Element<'a, Mess
                             .into()
                     };
 
+                    let activity_dot = (tab.has_activity && !is_active)
+                        .then(|| text("●").size(8).color(ui_style::accent_color()));
+
+                    let profile = profiles.get(index).copied().flatten();
+                    let profile_color = profile
+                        .and_then(HostProfile::rgb)
+                        .map(|(r, g, b)| Color::from_rgb8(r, g, b));
+                    let profile_badge = profile.and_then(HostProfile::badge).map(|badge| {
+                        text(badge)
+                            .size(10)
+                            .color(profile_color.unwrap_or_else(ui_style::accent_color))
+                    });
+                    let profile_dot = profile_color
+                        .filter(|_| profile_badge.is_none())
+                        .map(|color| text("■").size(9).color(color));
+
+                    let countdown =
+                        access_expiry.get(index).copied().flatten().map(
+                            |at| match expiry::countdown(at, now) {
+                                Some(left) if expiry::is_ending(at, now) => {
+                                    text(left).size(10).color(Color::from_rgb(0.9, 0.6, 0.2))
+                                }
+                                Some(left) => text(left).size(10).style(ui_style::muted_text),
+                                None => text("expired")
+                                    .size(10)
+                                    .color(Color::from_rgb(0.9, 0.3, 0.3)),
+                            },
+                        );
+
                     let tab_content = row![
+                        activity_dot,
+                        profile_dot,
                         text(title).size(13),
+                        profile_badge,
+                        countdown,
                         container("").width(Length::Fill),
                         close_button
                     ]
@@ -76,6 +118,14 @@ pub fn render<'a>(tabs: &'a [SessionTab], active_tab: usize) -> Element<'a, Mess
                 .style(ui_style::new_tab_button)
                 .on_press(Message::ToggleQuickConnect),
         );
+        if show_overview {
+            tab_bar = tab_bar.push(
+                button(text("▦").size(14))
+                    .padding([6, 10])
+                    .style(ui_style::new_tab_button)
+                    .on_press(Message::ToggleTabOverview),
+            );
+        }
 
         tab_bar.into()
     });
@@ -0,0 +1,156 @@
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Text};
+use iced::widget::text::Wrapping;
+use iced::widget::{Column, Row, button, column, container, scrollable, text};
+use iced::{Element, Font, Length, Point, Rectangle, Renderer, Theme, mouse};
+
+use crate::ui::Message;
+use crate::ui::state::{SessionState, SessionTab};
+use crate::ui::style as ui_style;
+use crate::ui::views;
+
+const THUMBNAIL_WIDTH: f32 = 280.0;
+const THUMBNAIL_HEIGHT: f32 = 160.0;
+const THUMBNAIL_FONT_SIZE: f32 = 6.0;
+const GRID_SPACING: f32 = 16.0;
+
+pub fn render(tabs: &[SessionTab], active_tab: usize, window_width: u32) -> Element<'_, Message> {
+    let columns = ((window_width as f32 - 64.0 + GRID_SPACING)
+        / (THUMBNAIL_WIDTH + 16.0 + GRID_SPACING))
+        .floor()
+        .max(1.0) as usize;
+
+    // Tab 0 is the session manager, which has no terminal to preview.
+    let terminals: Vec<(usize, &SessionTab)> = tabs.iter().enumerate().skip(1).collect();
+
+    let body: Element<'_, Message> = if terminals.is_empty() {
+        container(
+            text("No open terminals")
+                .size(14)
+                .style(ui_style::muted_text),
+        )
+        .padding(40)
+        .center_x(Length::Fill)
+        .into()
+    } else {
+        let mut grid = Column::new().spacing(GRID_SPACING);
+        for chunk in terminals.chunks(columns) {
+            let mut grid_row = Row::new().spacing(GRID_SPACING);
+            for &(index, tab) in chunk {
+                grid_row = grid_row.push(tile(index, tab, index == active_tab));
+            }
+            grid = grid.push(grid_row);
+        }
+        scrollable(grid)
+            .direction(ui_style::thin_scrollbar())
+            .style(ui_style::scrollable_style)
+            .height(Length::Fill)
+            .into()
+    };
+
+    container(
+        column![
+            text("All tabs").size(18).style(ui_style::header_text),
+            text("Click a tab to jump to it. Esc to close.")
+                .size(12)
+                .style(ui_style::muted_text),
+            body,
+        ]
+        .spacing(12),
+    )
+    .padding(32)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
+
+fn tile(index: usize, tab: &SessionTab, is_active: bool) -> Element<'_, Message> {
+    let status = match &tab.state {
+        SessionState::Connected => "Connected",
+        SessionState::Connecting(_) => "Connecting",
+        SessionState::Disconnected => "Disconnected",
+        SessionState::Failed(_) => "Failed",
+    };
+
+    let preview = container(
+        Canvas::new(Thumbnail {
+            lines: tab.emulator.screen_text(),
+        })
+        .width(Length::Fixed(THUMBNAIL_WIDTH))
+        .height(Length::Fixed(THUMBNAIL_HEIGHT)),
+    )
+    .style(ui_style::terminal_content);
+
+    let traffic = tab.bandwidth.as_ref().map(|_| {
+        column![
+            views::bandwidth::graph(&tab.bandwidth_history, THUMBNAIL_WIDTH, 28.0),
+            text(views::bandwidth::rate_label(&tab.bandwidth_history))
+                .size(11)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(4)
+    });
+
+    button(
+        column![
+            preview,
+            text(&tab.title)
+                .size(13)
+                .wrapping(Wrapping::None)
+                .width(Length::Fixed(THUMBNAIL_WIDTH)),
+            text(status).size(11).style(ui_style::muted_text),
+            traffic,
+        ]
+        .spacing(6),
+    )
+    .padding(8)
+    .style(if is_active {
+        ui_style::quick_connect_item_selected
+    } else {
+        ui_style::quick_connect_item
+    })
+    .on_press(Message::SelectTab(index))
+    .into()
+}
+
+// Scaled-down rendering of a terminal's visible screen.
+struct Thumbnail {
+    lines: Vec<String>,
+}
+
+impl canvas::Program<Message> for Thumbnail {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let line_height = THUMBNAIL_FONT_SIZE + 1.0;
+        let padding = 4.0;
+        let visible = ((bounds.height - padding * 2.0) / line_height).floor() as usize;
+        // Keep the bottom of the screen, where the prompt usually is.
+        let last_used = self
+            .lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |index| index + 1);
+        let start = last_used.saturating_sub(visible);
+
+        for (row, line) in self.lines[start..last_used].iter().enumerate() {
+            frame.fill_text(Text {
+                content: line.clone(),
+                position: Point::new(padding, padding + row as f32 * line_height),
+                color: ui_style::terminal_foreground(),
+                size: THUMBNAIL_FONT_SIZE.into(),
+                font: Font::MONOSPACE,
+                max_width: bounds.width - padding * 2.0,
+                ..Text::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -1,19 +1,31 @@
-use crate::ui::Message;
+use crate::session::profile::HostProfile;
+use crate::settings::ScreenGuardMode;
 use crate::ui::state::{SessionState, SessionTab, Spinner};
 use crate::ui::style as ui_style;
 use crate::ui::terminal_widget;
-use iced::widget::{column, container, row, text};
-use iced::{Alignment, Element, Length};
+use crate::ui::{Message, SessionMessage, TerminalMessage};
+use iced::widget::{Space, button, column, container, row, stack, text};
+use iced::{Alignment, Color, Element, Length};
+use std::time::Duration;
 
 pub fn render<'a>(
     tabs: &'a [SessionTab],
     active_tab: usize,
     ime_preedit: &'a str,
     font_size: f32,
+    padding: f32,
+    touch_target: Option<String>,
     use_gpu_renderer: bool,
 ) -> Element<'a, Message> {
     if use_gpu_renderer {
-        return super::terminal_gpu::render(tabs, active_tab, ime_preedit, font_size);
+        return super::terminal_gpu::render(
+            tabs,
+            active_tab,
+            ime_preedit,
+            font_size,
+            padding,
+            touch_target,
+        );
     }
     if tabs.is_empty() {
         return column![
@@ -22,7 +34,7 @@ pub fn render<'a>(
                     text("No open tabs").size(24).style(ui_style::header_text),
                     text("Create a new session to get started").style(ui_style::muted_text),
                     iced::widget::button(text("Create Session"))
-                        .on_press(Message::CreateNewSession)
+                        .on_press(Message::Sessions(SessionMessage::CreateNew))
                         .padding([10, 20])
                         .style(ui_style::save_button)
                 ]
@@ -64,7 +76,9 @@ pub fn render<'a>(
 
     match current_tab_state {
         SessionState::Connecting(start_time) => {
-            let _elapsed = start_time.elapsed().as_secs_f32();
+            let timeout = tabs
+                .get(active_tab)
+                .map_or(tabs[0].connect_timeout, |tab| tab.connect_timeout);
 
             let spinner = iced::widget::canvas(Spinner::new(*start_time))
                 .width(Length::Fixed(50.0))
@@ -73,7 +87,12 @@ pub fn render<'a>(
             container(
                 column![
                     spinner,
-                    text("Connecting...").size(16).style(ui_style::muted_text)
+                    text(connecting_label(timeout))
+                        .size(16)
+                        .style(ui_style::muted_text),
+                    touch_target.map(|target| {
+                        text(format!("Touch your security key to sign in as {}", target)).size(13)
+                    }),
                 ]
                 .spacing(20)
                 .align_x(Alignment::Center),
@@ -85,32 +104,13 @@ pub fn render<'a>(
             .into()
         }
         SessionState::Failed(err) => {
-            let current_tab_index = active_tab;
-
-            container(
-                column![
-                    text("❌ Connection Failed")
-                        .size(24)
-                        .color(iced::Color::from_rgb(0.8, 0.2, 0.2)),
-                    text(err).size(14).style(ui_style::muted_text),
-                    row![
-                        iced::widget::button(text("🔄 Retry").size(14))
-                            .padding([8, 16])
-                            .on_press(Message::RetryConnection(current_tab_index)),
-                        iced::widget::button(text("✏️ Edit").size(14))
-                            .padding([8, 16])
-                            .on_press(Message::EditSessionConfig(current_tab_index)),
-                    ]
-                    .spacing(12)
-                ]
-                .spacing(20)
-                .align_x(Alignment::Center),
+            let tab = tabs.get(active_tab);
+            super::connection_failed::render(
+                err,
+                active_tab,
+                tab.is_some_and(|tab| tab.show_failure_log),
+                tab.is_some_and(|tab| tab.debug_log.is_some()),
             )
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x(Length::Fill)
-            .center_y(Length::Fill)
-            .into()
         }
         _ => iced::widget::responsive(move |size| {
             let _cols = (size.width / terminal_widget::cell_width(font_size)) as usize;
@@ -132,10 +132,230 @@ pub fn render<'a>(
             )
             .width(Length::Fill)
             .height(Length::Fill)
-            .padding(0)
+            .padding(padding)
             .style(ui_style::terminal_content)
             .into()
         })
         .into(),
     }
 }
+
+/// The line under the spinner. The timeout applies to each step of
+/// connecting (proxy, jump host, handshake, sign in) on its own, so it is
+/// shown as such rather than counted down as one deadline.
+pub(super) fn connecting_label(timeout: Duration) -> String {
+    format!("Connecting... (step timeout {}s)", timeout.as_secs())
+}
+
+/// Floating "jump to bottom" pill shown while the viewport is in history.
+pub fn scroll_pill(tab: &SessionTab) -> Option<Element<'_, Message>> {
+    let (_, display_offset, _) = tab.emulator.get_scroll_state();
+    if display_offset == 0 || !matches!(tab.state, SessionState::Connected) {
+        return None;
+    }
+    let label = match tab.unseen_lines {
+        0 => format!("{} lines up ↓", display_offset),
+        1 => "1 new line ↓".to_string(),
+        count => format!("{} new lines ↓", count),
+    };
+    let pill = button(text(label).size(12))
+        .padding([4, 12])
+        .style(ui_style::primary_button_style)
+        .on_press(Message::Terminal(TerminalMessage::ScrollToBottom));
+    Some(
+        container(pill)
+            .padding(16)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_right(Length::Fill)
+            .align_bottom(Length::Fill)
+            .into(),
+    )
+}
+
+/// Shown while unwrapped lines are scrolled sideways; takes the view back
+/// to the first column.
+pub fn column_pill(tab: &SessionTab) -> Option<Element<'_, Message>> {
+    let offset = tab.emulator.column_offset();
+    if tab.emulator.wraps_lines() || offset == 0 {
+        return None;
+    }
+    let pill = button(text(format!("← Column {}", offset + 1)).size(12))
+        .padding([4, 12])
+        .style(ui_style::primary_button_style)
+        .on_press(Message::Terminal(TerminalMessage::ScrollColumnsHome));
+    Some(
+        container(pill)
+            .padding(16)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_left(Length::Fill)
+            .align_bottom(Length::Fill)
+            .into(),
+    )
+}
+
+/// Countdown shown over the scrollback while a dropped connection waits
+/// for its next attempt.
+pub fn reconnect_banner(tab_index: usize, tab: &SessionTab) -> Option<Element<'_, Message>> {
+    let reconnect = tab.auto_reconnect.as_ref()?;
+    let status = match reconnect.next {
+        Some(at) => {
+            let seconds = at
+                .saturating_duration_since(std::time::Instant::now())
+                .as_secs_f32()
+                .ceil();
+            format!("Connection lost. Reconnecting in {}s…", seconds)
+        }
+        None => "Reconnecting…".to_string(),
+    };
+    let detail = reconnect
+        .last_error
+        .as_ref()
+        .filter(|_| reconnect.attempt > 0)
+        .map(|error| {
+            text(format!("Attempt {} failed: {}", reconnect.attempt, error))
+                .size(12)
+                .style(ui_style::muted_text)
+        });
+    let banner = row![
+        column![text(status).size(13).style(ui_style::header_text), detail]
+            .spacing(2)
+            .width(Length::Fill),
+        button(text("Reconnect Now").size(12))
+            .padding([4, 10])
+            .style(ui_style::primary_button_style)
+            .on_press_maybe(
+                reconnect
+                    .next
+                    .is_some()
+                    .then_some(Message::ReconnectNow(tab_index))
+            ),
+        button(text("Cancel").size(12))
+            .padding([4, 10])
+            .style(ui_style::secondary_button_style)
+            .on_press(Message::CancelReconnect(tab_index)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+    Some(
+        container(
+            container(banner)
+                .padding([10, 12])
+                .width(Length::Fixed(420.0))
+                .style(ui_style::popover_menu),
+        )
+        .padding(16)
+        .width(Length::Fill)
+        .center_x(Length::Fill)
+        .into(),
+    )
+}
+
+/// Shown over the last screen once the remote shell has exited, with what
+/// it exited with.
+pub fn connection_closed(tab_index: usize, tab: &SessionTab) -> Option<Element<'_, Message>> {
+    let exit = tab
+        .shell_exit
+        .filter(|_| tab.state == SessionState::Disconnected)?;
+    let status = match exit.status {
+        Some(code) => format!("The remote shell exited with status {}.", code),
+        None => "The remote shell exited.".to_string(),
+    };
+    let dialog = column![
+        text("Connection closed")
+            .size(16)
+            .style(ui_style::header_text),
+        text(status).size(13),
+        row![
+            text("Enter closes the tab, R reconnects.")
+                .size(12)
+                .style(ui_style::muted_text)
+                .width(Length::Fill),
+            button(text("Close").size(12))
+                .padding([6, 12])
+                .style(ui_style::secondary_button_style)
+                .on_press(Message::CloseTab(tab_index)),
+            button(text("Reconnect").size(12))
+                .padding([6, 12])
+                .style(ui_style::primary_button_style)
+                .on_press(Message::RetryConnection(tab_index)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+    ]
+    .spacing(10)
+    .width(Length::Fixed(380.0));
+    Some(
+        container(
+            container(dialog)
+                .padding(16)
+                .style(ui_style::dialog_container),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .style(ui_style::closed_terminal)
+        .into(),
+    )
+}
+
+/// Covers the terminal while nobody is using it; a click or key shows it
+/// again.
+pub fn screen_guard<'a>(mode: ScreenGuardMode) -> Element<'a, Message> {
+    let hint = text("Press a key or click to show the terminal")
+        .size(13)
+        .style(ui_style::muted_text);
+    button(
+        container(hint)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .padding(0)
+    .style(ui_style::screen_guard(mode == ScreenGuardMode::Blank))
+    .on_press(Message::RevealTerminal)
+    .into()
+}
+
+/// Tint and watermark for tabs whose host matches a profile rule.
+pub fn host_profile_overlay(profile: &HostProfile) -> Option<Element<'_, Message>> {
+    let color = profile.rgb().map(|(r, g, b)| Color::from_rgb8(r, g, b));
+    if color.is_none() && profile.badge().is_none() {
+        return None;
+    }
+    let tint = color.map(|color| {
+        container(Space::new())
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ui_style::host_tint(color))
+    });
+    let watermark = profile.badge().map(|badge| {
+        let badge_color = color.unwrap_or_else(ui_style::terminal_foreground);
+        container(text(badge).size(56).color(badge_color.scale_alpha(0.14)))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+    });
+    Some(stack![tint, watermark].into())
+}
+
+/// Translucent per-session badge in the top-right corner of the terminal.
+pub fn corner_badge<'a>(badge: String, color: Option<Color>) -> Element<'a, Message> {
+    let color = color.unwrap_or_else(ui_style::terminal_foreground);
+    container(
+        container(text(badge).size(12).color(color.scale_alpha(0.7)))
+            .padding([3, 8])
+            .style(ui_style::terminal_badge(color)),
+    )
+    // Leave room for the scrollbar along the right edge.
+    .padding(iced::Padding::new(8.0).right(20.0))
+    .width(Length::Fill)
+    .align_right(Length::Fill)
+    .into()
+}
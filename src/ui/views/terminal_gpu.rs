@@ -1,8 +1,8 @@
-use crate::ui::Message;
 use crate::ui::state::{SessionState, SessionTab, Spinner};
 use crate::ui::style as ui_style;
 use crate::ui::terminal_gpu_widget::TerminalGpuView;
-use iced::widget::{column, container, row, text};
+use crate::ui::{Message, SessionMessage};
+use iced::widget::{column, container, text};
 use iced::{Alignment, Element, Length};
 
 // TODO: Replace with real GPU renderer (wgpu atlas + instance pipeline).
@@ -12,6 +12,8 @@ pub fn render<'a>(
     active_tab: usize,
     ime_preedit: &'a str,
     font_size: f32,
+    padding: f32,
+    touch_target: Option<String>,
 ) -> Element<'a, Message> {
     if tabs.is_empty() {
         return column![
@@ -20,7 +22,7 @@ pub fn render<'a>(
                     text("No open tabs").size(24).style(ui_style::header_text),
                     text("Create a new session to get started").style(ui_style::muted_text),
                     iced::widget::button(text("Create Session"))
-                        .on_press(Message::CreateNewSession)
+                        .on_press(Message::Sessions(SessionMessage::CreateNew))
                         .padding([10, 20])
                         .style(ui_style::save_button)
                 ]
@@ -48,7 +50,9 @@ pub fn render<'a>(
 
     match current_tab_state {
         SessionState::Connecting(start_time) => {
-            let _elapsed = start_time.elapsed().as_secs_f32();
+            let timeout = tabs
+                .get(active_tab)
+                .map_or(tabs[0].connect_timeout, |tab| tab.connect_timeout);
 
             let spinner = iced::widget::canvas(Spinner::new(*start_time))
                 .width(Length::Fixed(50.0))
@@ -57,7 +61,12 @@ pub fn render<'a>(
             container(
                 column![
                     spinner,
-                    text("Connecting...").size(16).style(ui_style::muted_text)
+                    text(super::terminal::connecting_label(timeout))
+                        .size(16)
+                        .style(ui_style::muted_text),
+                    touch_target.map(|target| {
+                        text(format!("Touch your security key to sign in as {}", target)).size(13)
+                    }),
                 ]
                 .spacing(20)
                 .align_x(Alignment::Center),
@@ -69,32 +78,13 @@ pub fn render<'a>(
             .into()
         }
         SessionState::Failed(err) => {
-            let current_tab_index = active_tab;
-
-            container(
-                column![
-                    text("❌ Connection Failed")
-                        .size(24)
-                        .color(iced::Color::from_rgb(0.8, 0.2, 0.2)),
-                    text(err).size(14).style(ui_style::muted_text),
-                    row![
-                        iced::widget::button(text("🔄 Retry").size(14))
-                            .padding([8, 16])
-                            .on_press(Message::RetryConnection(current_tab_index)),
-                        iced::widget::button(text("✏️ Edit").size(14))
-                            .padding([8, 16])
-                            .on_press(Message::EditSessionConfig(current_tab_index)),
-                    ]
-                    .spacing(12)
-                ]
-                .spacing(20)
-                .align_x(Alignment::Center),
+            let tab = tabs.get(active_tab);
+            super::connection_failed::render(
+                err,
+                active_tab,
+                tab.is_some_and(|tab| tab.show_failure_log),
+                tab.is_some_and(|tab| tab.debug_log.is_some()),
             )
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x(Length::Fill)
-            .center_y(Length::Fill)
-            .into()
         }
         _ => container(
             TerminalGpuView::new(
@@ -110,7 +100,7 @@ pub fn render<'a>(
         )
         .width(Length::Fill)
         .height(Length::Fill)
-        .padding(0)
+        .padding(padding)
         .style(ui_style::terminal_content)
         .into(),
     }
@@ -0,0 +1,215 @@
+use iced::widget::{Space, button, column, container, row, text};
+use iced::{Element, Length};
+
+use crate::session::config::Snippet;
+use crate::ui::state::{SelectionAction, SelectionActions, TerminalMenu};
+use crate::ui::style as ui_style;
+use crate::ui::{Message, TerminalMessage};
+
+const MENU_WIDTH: f32 = 200.0;
+const MENU_HEIGHT: f32 = 190.0;
+const BLOCK_ITEMS_HEIGHT: f32 = 110.0;
+const HELPER_ITEM_HEIGHT: f32 = 40.0;
+const SHELL_ITEMS_HEIGHT: f32 = 75.0;
+const SNIPPET_ITEM_HEIGHT: f32 = 32.0;
+const WRAP_ITEM_HEIGHT: f32 = 32.0;
+// The menu lists only the newest snippets so it stays on screen.
+const MENU_SNIPPETS: usize = 6;
+const TOOLBAR_HEIGHT: f32 = 34.0;
+
+pub fn render<'a>(
+    menu: TerminalMenu,
+    snippets: &'a [Snippet],
+    window_width: u32,
+    window_height: u32,
+) -> Element<'a, Message> {
+    let item = |label: &'a str, message: Message| {
+        button(text(label).size(13))
+            .padding([6, 10])
+            .width(Length::Fill)
+            .style(ui_style::menu_item_button)
+            .on_press(message)
+    };
+
+    let mut items = column![
+        item("Copy", Message::Terminal(TerminalMessage::Copy)),
+        item("Paste", Message::Terminal(TerminalMessage::Paste)),
+        item("Select All", Message::Terminal(TerminalMessage::SelectAll)),
+        item(
+            "Copy Screen",
+            Message::Terminal(TerminalMessage::CopyScreen)
+        ),
+        item(
+            "Copy Entire Buffer",
+            Message::Terminal(TerminalMessage::CopyBuffer)
+        ),
+    ]
+    .spacing(2);
+    let mut height = MENU_HEIGHT;
+    if let Some((tab, wraps)) = menu.wrap_tab {
+        items = items.push(if wraps {
+            item(
+                "Don't Wrap Long Lines",
+                Message::Terminal(TerminalMessage::ToggleLineWrap(tab)),
+            )
+        } else {
+            item(
+                "Wrap Long Lines",
+                Message::Terminal(TerminalMessage::ToggleLineWrap(tab)),
+            )
+        });
+        height += WRAP_ITEM_HEIGHT;
+    }
+    if let Some(block) = menu.block {
+        items = items
+            .push(
+                container("")
+                    .height(1.0)
+                    .width(Length::Fill)
+                    .style(ui_style::divider),
+            )
+            .push(item(
+                "Copy Command",
+                Message::Terminal(TerminalMessage::CopyBlockCommand(block)),
+            ))
+            .push(item(
+                "Copy Output",
+                Message::Terminal(TerminalMessage::CopyBlockOutput(block)),
+            ))
+            .push(item(
+                "Save Output to File…",
+                Message::Terminal(TerminalMessage::SaveBlockOutput(block)),
+            ));
+        height += BLOCK_ITEMS_HEIGHT;
+    }
+    if menu.clipboard_helpers {
+        items = items
+            .push(
+                container("")
+                    .height(1.0)
+                    .width(Length::Fill)
+                    .style(ui_style::divider),
+            )
+            .push(item(
+                "Install pbcopy/pbpaste",
+                Message::InstallClipboardHelpers,
+            ));
+        height += HELPER_ITEM_HEIGHT;
+    }
+    if let Some(tab) = menu.shell_tab {
+        items = items
+            .push(
+                container("")
+                    .height(1.0)
+                    .width(Length::Fill)
+                    .style(ui_style::divider),
+            )
+            .push(item("Duplicate Tab", Message::DuplicateTab(tab)))
+            .push(item("New Shell on This Host", Message::NewShellOnHost(tab)));
+        height += SHELL_ITEMS_HEIGHT;
+    }
+    if !snippets.is_empty() {
+        items = items.push(
+            container("")
+                .height(1.0)
+                .width(Length::Fill)
+                .style(ui_style::divider),
+        );
+        for (index, snippet) in snippets.iter().take(MENU_SNIPPETS).enumerate() {
+            items = items.push(
+                button(text(format!("Paste “{}”", snippet.name)).size(13))
+                    .padding([6, 10])
+                    .width(Length::Fill)
+                    .style(ui_style::menu_item_button)
+                    .on_press(Message::InsertSnippet(index)),
+            );
+            height += SNIPPET_ITEM_HEIGHT;
+        }
+        height += 5.0;
+    }
+    if let Some((tab, shared)) = menu.sharing {
+        items = items
+            .push(
+                container("")
+                    .height(1.0)
+                    .width(Length::Fill)
+                    .style(ui_style::divider),
+            )
+            .push(if shared {
+                item("Stop Sharing", Message::StopSharing(tab))
+            } else {
+                item("Share Read-Only", Message::ShareTab(tab))
+            });
+        height += HELPER_ITEM_HEIGHT;
+    }
+
+    let menu_box = container(items)
+        .padding(6)
+        .width(Length::Fixed(MENU_WIDTH))
+        .style(ui_style::popover_menu);
+
+    // Keep the menu on screen when opened near the right or bottom edge.
+    let x = menu
+        .position
+        .x
+        .min(window_width as f32 - MENU_WIDTH)
+        .max(0.0);
+    let y = menu.position.y.min(window_height as f32 - height).max(0.0);
+
+    column![
+        Space::new().height(Length::Fixed(y)),
+        row![Space::new().width(Length::Fixed(x)), menu_box],
+    ]
+    .into()
+}
+
+/// The row of actions floating over a finished selection, above where the
+/// mouse was released.
+pub fn selection_toolbar<'a>(
+    selection: &SelectionActions,
+    window_width: u32,
+    window_height: u32,
+) -> Element<'a, Message> {
+    let mut actions = vec![("Copy", SelectionAction::Copy)];
+    actions.push(("Search Web", SelectionAction::SearchWeb));
+    if selection.url.is_some() {
+        actions.push(("Open URL", SelectionAction::OpenUrl));
+    }
+    if selection.remote_path.is_some() {
+        actions.push(("Show in SFTP", SelectionAction::RevealInSftp));
+    }
+    actions.push(("Save as Snippet", SelectionAction::SaveSnippet));
+
+    // Close enough to place the bar; the labels are short.
+    let width: f32 = actions
+        .iter()
+        .map(|(label, _)| label.len() as f32 * 7.0 + 22.0)
+        .sum::<f32>()
+        + 8.0;
+    let buttons = actions.into_iter().map(|(label, action)| {
+        button(text(label).size(12))
+            .padding([5, 8])
+            .style(ui_style::menu_item_button)
+            .on_press(Message::SelectionAction(action))
+            .into()
+    });
+    let toolbar = container(row(buttons).spacing(2))
+        .padding(4)
+        .style(ui_style::popover_menu);
+
+    let x = (selection.position.x - width / 2.0)
+        .min(window_width as f32 - width)
+        .max(0.0);
+    let above = selection.position.y - TOOLBAR_HEIGHT - 10.0;
+    let y = if above >= 0.0 {
+        above
+    } else {
+        (selection.position.y + 16.0).min(window_height as f32 - TOOLBAR_HEIGHT)
+    };
+
+    column![
+        Space::new().height(Length::Fixed(y)),
+        row![Space::new().width(Length::Fixed(x)), toolbar],
+    ]
+    .into()
+}
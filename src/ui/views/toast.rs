@@ -0,0 +1,188 @@
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Color, Element, Length};
+
+use crate::ssh::AgentSignRequest;
+use crate::terminal::share::ShareHost;
+use crate::ui::state::{AgentSignChoice, DisconnectToast, JobFailureToast, TrashToast};
+use crate::ui::style as ui_style;
+use crate::ui::{Message, SessionMessage, SftpMessage};
+
+pub fn render(toast: &DisconnectToast) -> Element<'_, Message> {
+    let content = row![
+        column![
+            text("Connection lost")
+                .size(13)
+                .style(ui_style::header_text),
+            text(format!("{} disconnected unexpectedly", toast.title))
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        button(text("Reconnect").size(12))
+            .padding([4, 10])
+            .style(ui_style::primary_button_style)
+            .on_press(Message::Sessions(SessionMessage::DisconnectToastReconnect)),
+        button(text("✕").size(13))
+            .padding(6)
+            .style(ui_style::tab_close_button)
+            .on_press(Message::Sessions(SessionMessage::DisconnectToastDismiss)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(content)
+        .padding([10, 12])
+        .width(Length::Fixed(340.0))
+        .style(ui_style::popover_menu)
+        .into()
+}
+
+pub fn job_failure(toast: &JobFailureToast) -> Element<'_, Message> {
+    let content = row![
+        column![
+            text("Scheduled transfer failed")
+                .size(13)
+                .style(ui_style::header_text),
+            text(format!("{}: {}", toast.name, toast.error))
+                .size(12)
+                .style(ui_style::muted_text),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        button(text("✕").size(13))
+            .padding(6)
+            .style(ui_style::tab_close_button)
+            .on_press(Message::JobToastDismiss),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(content)
+        .padding([10, 12])
+        .width(Length::Fixed(340.0))
+        .style(ui_style::popover_menu)
+        .into()
+}
+
+pub fn trash(toast: &TrashToast) -> Element<'_, Message> {
+    let content = row![
+        column![
+            text(format!("Moved {} to the trash", toast.name))
+                .size(13)
+                .style(ui_style::header_text),
+            text(&toast.trashed).size(12).style(ui_style::muted_text),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        button(text("Undo").size(12))
+            .padding([4, 10])
+            .style(ui_style::primary_button_style)
+            .on_press(Message::Sftp(SftpMessage::UndoTrash)),
+        button(text("✕").size(13))
+            .padding(6)
+            .style(ui_style::tab_close_button)
+            .on_press(Message::Sftp(SftpMessage::TrashToastDismiss)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(content)
+        .padding([10, 12])
+        .width(Length::Fixed(340.0))
+        .style(ui_style::popover_menu)
+        .into()
+}
+
+// Stays until answered: the server is waiting on the signature. Viewer
+// mode only answers once, so "Always" isn't offered there.
+pub fn agent_sign(request: &AgentSignRequest, viewer_mode: bool) -> Element<'_, Message> {
+    let key = if request.comment.is_empty() {
+        request.fingerprint.clone()
+    } else {
+        format!("{} ({})", request.comment, request.fingerprint)
+    };
+    let content = column![
+        text(format!("{} wants to sign with your key", request.target))
+            .size(13)
+            .style(ui_style::header_text),
+        text(key).size(12).style(ui_style::muted_text),
+        row![
+            button(text("Allow Once").size(12))
+                .padding([4, 10])
+                .style(ui_style::primary_button_style)
+                .on_press(Message::Sessions(SessionMessage::AgentSignAnswer(
+                    AgentSignChoice::AllowOnce
+                ))),
+        ]
+        .push((!viewer_mode).then(|| {
+            button(text("Always for This Host").size(12))
+                .padding([4, 10])
+                .style(ui_style::secondary_button_style)
+                .on_press(Message::Sessions(SessionMessage::AgentSignAnswer(
+                    AgentSignChoice::Always,
+                )))
+        }))
+        .push(
+            button(text("Deny").size(12))
+                .padding([4, 10])
+                .style(ui_style::secondary_button_style)
+                .on_press(Message::Sessions(SessionMessage::AgentSignAnswer(
+                    AgentSignChoice::Deny,
+                ))),
+        )
+        .spacing(8),
+    ]
+    .spacing(6);
+
+    container(content)
+        .padding([10, 12])
+        .width(Length::Fixed(340.0))
+        .style(ui_style::popover_menu)
+        .into()
+}
+
+// Shown for as long as a tab is shared, so it is never shared unnoticed.
+pub fn sharing<'a>(tab_index: usize, title: &str, host: &ShareHost) -> Element<'a, Message> {
+    let viewers = match host.viewers() {
+        0 => "no viewers yet".to_string(),
+        1 => "1 viewer".to_string(),
+        count => format!("{} viewers", count),
+    };
+    let link = if host.locked_out() {
+        "Too many wrong codes; no one else can join".to_string()
+    } else {
+        host.link().to_string()
+    };
+    let content = row![
+        column![
+            text(format!("Sharing {} read-only", title))
+                .size(13)
+                .style(ui_style::header_text),
+            text(format!("{} · {}", link, viewers))
+                .size(12)
+                .style(ui_style::muted_text),
+            text("Not encrypted: anyone on the network can read the output")
+                .size(12)
+                .color(Color::from_rgb(0.9, 0.6, 0.2)),
+        ]
+        .spacing(2)
+        .width(Length::Fill),
+        button(text("Copy Link").size(12))
+            .padding([4, 10])
+            .style(ui_style::secondary_button_style)
+            .on_press_maybe((!host.locked_out()).then_some(Message::CopyShareLink(tab_index))),
+        button(text("Stop").size(12))
+            .padding([4, 10])
+            .style(ui_style::primary_button_style)
+            .on_press(Message::StopSharing(tab_index)),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center);
+
+    container(content)
+        .padding([10, 12])
+        .width(Length::Fixed(340.0))
+        .style(ui_style::popover_menu)
+        .into()
+}
@@ -0,0 +1,66 @@
+use std::ops::Range;
+
+// Rows outside the viewport that are still built, so a fast scroll doesn't
+// show a blank strip before the next view pass catches up.
+const OVERSCAN: usize = 8;
+
+/// Scroll position of a list as last reported by its scrollable. Only rows
+/// inside it are built; the rest of the content height is empty space.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ListViewport {
+    pub offset: f32,
+    pub height: f32,
+    /// Window position of the list's top edge.
+    pub top: f32,
+}
+
+impl ListViewport {
+    /// Rows of `row_height` that intersect the viewport. Before the first
+    /// scroll event the height is unknown, so `fallback_height` is used.
+    pub fn visible_rows(&self, len: usize, row_height: f32, fallback_height: f32) -> Range<usize> {
+        let height = if self.height > 0.0 {
+            self.height
+        } else {
+            fallback_height
+        };
+        // The scrollable clamps its offset when the content shrinks; do the
+        // same so a shorter list isn't rendered as blank space.
+        let max_offset = (len as f32 * row_height - height).max(0.0);
+        let offset = self.offset.clamp(0.0, max_offset);
+        let first = (offset / row_height).floor() as usize;
+        let last = ((offset + height) / row_height).ceil() as usize;
+        first.saturating_sub(OVERSCAN).min(len)..(last + OVERSCAN).min(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_only_rows_near_the_viewport() {
+        let viewport = ListViewport {
+            offset: 26_000.0,
+            height: 520.0,
+            top: 0.0,
+        };
+        assert_eq!(viewport.visible_rows(50_000, 26.0, 800.0), 992..1028);
+        assert_eq!(viewport.visible_rows(10, 26.0, 800.0), 0..10);
+    }
+
+    #[test]
+    fn falls_back_before_the_first_scroll() {
+        let viewport = ListViewport::default();
+        assert_eq!(viewport.visible_rows(1000, 25.0, 500.0), 0..28);
+    }
+
+    #[test]
+    fn clamps_a_stale_offset_to_the_end() {
+        let viewport = ListViewport {
+            offset: 10_000.0,
+            height: 100.0,
+            top: 0.0,
+        };
+        assert_eq!(viewport.visible_rows(20, 10.0, 0.0), 2..20);
+    }
+}